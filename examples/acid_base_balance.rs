@@ -27,7 +27,7 @@ enum Primary { MetabolicAcidosis, MetabolicAlkalosis, RespiratoryAcidosis, Respi
 enum RespiratoryChronicity { Acute, Chronic }
 
 #[derive(Debug, Clone, Copy)]
-struct ABG {
+struct Abg {
     ph:    f64,
     paco2: f64,   // mmHg
     hco3:  f64,   // mEq/L
@@ -51,7 +51,7 @@ fn corrected_anion_gap(ag: f64, albumin_g_dl: f64) -> f64 {
 
 /// Classify a primary disorder from pH, PaCO₂, and HCO₃⁻.
 /// Boundaries follow Boron & Boulpaep, Ch 28, Table 28-7.
-fn classify(abg: ABG) -> Primary {
+fn classify(abg: Abg) -> Primary {
     let acidemic = abg.ph < 7.35;
     let alkalemic = abg.ph > 7.45;
     let low_co2 = abg.paco2 < 35.0;
@@ -96,7 +96,7 @@ fn respiratory_alkalosis_compensation(paco2: f64, c: RespiratoryChronicity) -> f
     }
 }
 
-fn print_abg(label: &str, abg: ABG) {
+fn print_abg(label: &str, abg: Abg) {
     println!("{:>34}  pH {:>5.2}  PaCO₂ {:>4.0}  HCO₃⁻ {:>4.0}   →  {:?}",
              label, abg.ph, abg.paco2, abg.hco3, classify(abg));
 }
@@ -113,11 +113,11 @@ fn main() {
     println!("halved  HCO₃⁻ buffer (12/40) → pH {:.3}", acd);
 
     println!("\n━━━ 2. The four primary disorders ━━━");
-    print_abg("normal",                      ABG { ph: 7.40, paco2: 40.0, hco3: 24.0 });
-    print_abg("DKA (metabolic acidosis)",    ABG { ph: 7.20, paco2: 25.0, hco3:  9.0 });
-    print_abg("vomiting (metab alkalosis)",  ABG { ph: 7.55, paco2: 48.0, hco3: 38.0 });
-    print_abg("COPD (resp acidosis, chr.)",  ABG { ph: 7.34, paco2: 60.0, hco3: 31.0 });
-    print_abg("anxiety (resp alkalosis)",    ABG { ph: 7.55, paco2: 25.0, hco3: 22.0 });
+    print_abg("normal",                      Abg { ph: 7.40, paco2: 40.0, hco3: 24.0 });
+    print_abg("DKA (metabolic acidosis)",    Abg { ph: 7.20, paco2: 25.0, hco3:  9.0 });
+    print_abg("vomiting (metab alkalosis)",  Abg { ph: 7.55, paco2: 48.0, hco3: 38.0 });
+    print_abg("COPD (resp acidosis, chr.)",  Abg { ph: 7.34, paco2: 60.0, hco3: 31.0 });
+    print_abg("anxiety (resp alkalosis)",    Abg { ph: 7.55, paco2: 25.0, hco3: 22.0 });
 
     println!("\n━━━ 3. Winters compensation in metabolic acidosis ━━━");
     println!("Patient with HCO₃⁻ 9 (DKA): expected PaCO₂ to compensate?");
@@ -189,25 +189,25 @@ mod tests {
 
     #[test]
     fn classifies_dka_as_metabolic_acidosis() {
-        let abg = ABG { ph: 7.20, paco2: 25.0, hco3: 9.0 };
+        let abg = Abg { ph: 7.20, paco2: 25.0, hco3: 9.0 };
         assert_eq!(classify(abg), Primary::MetabolicAcidosis);
     }
 
     #[test]
     fn classifies_vomiting_as_metabolic_alkalosis() {
-        let abg = ABG { ph: 7.55, paco2: 48.0, hco3: 38.0 };
+        let abg = Abg { ph: 7.55, paco2: 48.0, hco3: 38.0 };
         assert_eq!(classify(abg), Primary::MetabolicAlkalosis);
     }
 
     #[test]
     fn classifies_copd_as_respiratory_acidosis() {
-        let abg = ABG { ph: 7.34, paco2: 60.0, hco3: 31.0 };
+        let abg = Abg { ph: 7.34, paco2: 60.0, hco3: 31.0 };
         assert_eq!(classify(abg), Primary::RespiratoryAcidosis);
     }
 
     #[test]
     fn classifies_anxiety_as_respiratory_alkalosis() {
-        let abg = ABG { ph: 7.55, paco2: 25.0, hco3: 22.0 };
+        let abg = Abg { ph: 7.55, paco2: 25.0, hco3: 22.0 };
         assert_eq!(classify(abg), Primary::RespiratoryAlkalosis);
     }
 
@@ -0,0 +1,64 @@
+//! Million-agent storage in the entity-component world.
+//!
+//! Spawns N agents with a Position, an AgentKind and (for a third of
+//! them) a Velocity, then times a drift step over the contiguous
+//! component storage and a query for one agent type.
+//!
+//! Usage: cargo run --release --example agent_world_scale [agents]
+
+use human_biology::geometry::Vec3;
+use human_biology::simulation::{AgentKind, Position, Velocity, World};
+use std::time::Instant;
+
+fn main() {
+    let n: usize = std::env::args()
+        .nth(1)
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(1_000_000);
+    let kinds = [
+        AgentKind::Macrophage,
+        AgentKind::Lymphocyte,
+        AgentKind::Neuron,
+    ];
+
+    let start = Instant::now();
+    let mut world = World::new();
+    for i in 0..n {
+        let agent = world.spawn();
+        let t = i as f64;
+        world
+            .insert(agent, Position(Vec3::new(t.sin(), t.cos(), 0.0)))
+            .expect("alive");
+        world.insert(agent, kinds[i % 3]).expect("alive");
+        if i % 3 == 0 {
+            world
+                .insert(agent, Velocity(Vec3::new(0.0, 0.0, 1.0)))
+                .expect("alive");
+        }
+    }
+    let spawn_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let start = Instant::now();
+    world.advance_positions(0.1);
+    let drift_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let start = Instant::now();
+    let neurons = world.entities_of_kind(AgentKind::Neuron).count();
+    let query_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+    let lifted = world
+        .components::<Position>()
+        .iter()
+        .filter(|p| p.0.z > 0.0)
+        .count();
+    println!("Agent world: {} entities\n", world.len());
+    println!("{:<28} {:>10.2} ms", "spawn + 2-3 components", spawn_ms);
+    println!(
+        "{:<28} {:>10.2} ms ({} moved)",
+        "drift Position += v dt", drift_ms, lifted
+    );
+    println!(
+        "{:<28} {:>10.2} ms ({} found)",
+        "query AgentKind::Neuron", query_ms, neurons
+    );
+}
@@ -151,11 +151,11 @@ fn main() {
             self.ca_serum += d_ca * dt;
             self.bone_mass += d_bone * dt;
 
-            self.pth = self.pth.max(5.0).min(500.0);
-            self.calcitriol = self.calcitriol.max(8.0).min(150.0);
-            self.calcitonin = self.calcitonin.max(2.0).min(100.0);
-            self.ca_serum = self.ca_serum.max(6.0).min(14.0);
-            self.bone_mass = self.bone_mass.max(400.0).min(1200.0);
+            self.pth = self.pth.clamp(5.0, 500.0);
+            self.calcitriol = self.calcitriol.clamp(8.0, 150.0);
+            self.calcitonin = self.calcitonin.clamp(2.0, 100.0);
+            self.ca_serum = self.ca_serum.clamp(6.0, 14.0);
+            self.bone_mass = self.bone_mass.clamp(400.0, 1200.0);
 
             self.time += dt;
         }
@@ -172,7 +172,7 @@ fn main() {
                 (ca, _pth) if ca > 10.5 => "Hypercalcemia (other)",
                 (ca, pth) if ca < 8.5 && pth > 65.0 => "Secondary hyperparathyroidism (Vit D def/CKD)",
                 (ca, pth) if ca < 8.5 && pth < 15.0 => "Hypoparathyroidism",
-                (ca, pth) if ca >= 8.5 && ca <= 10.5 && pth >= 15.0 && pth <= 65.0 => "Normal calcium homeostasis",
+                (ca, pth) if (8.5..=10.5).contains(&ca) && (15.0..=65.0).contains(&pth) => "Normal calcium homeostasis",
                 _ => "Indeterminate",
             };
 
@@ -339,7 +339,7 @@ fn main() {
     println!("{}", "─".repeat(75));
 
     for week in 0..=12 {
-        let vit_d_dose = if week >= 1 && week <= 8 {
+        let vit_d_dose = if (1..=8).contains(&week) {
             50000.0
         } else if week > 8 {
             2000.0 * 7.0
@@ -347,7 +347,7 @@ fn main() {
             0.0
         };
 
-        let vit_d_supplement = if week >= 1 && week <= 8 {
+        let vit_d_supplement = if (1..=8).contains(&week) {
             25.0
         } else if week > 8 {
             7.0
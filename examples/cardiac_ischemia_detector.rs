@@ -94,8 +94,8 @@ fn main() {
     println!("Coronary flow model: Gould 1974 simplified CFR (residual = 1 - severity²)");
     println!();
     println!(
-        "{:>4} {:>4} {:>4} {:>6} {:>5} {:>6} {:>5} {:<24} {}",
-        "min", "HR", "SBP", "PRP", "MVO2", "flow", "S/D", "stage", "status",
+        "{:>4} {:>4} {:>4} {:>6} {:>5} {:>6} {:>5} {:<24} status",
+        "min", "HR", "SBP", "PRP", "MVO2", "flow", "S/D", "stage",
     );
     println!("{}", "-".repeat(90));
 
@@ -48,8 +48,8 @@ fn main() {
 
         let sv_rest = 70.0;
         let sv_max = 120.0;
-        let hr_max = 195.0;
-        let sv = sv_rest + (sv_max - sv_rest) * (((hr - 70.0) / (hr_max - 70.0)) as f64).min(1.0);
+        let hr_max: f64 = 195.0;
+        let sv = sv_rest + (sv_max - sv_rest) * ((hr - 70.0) / (hr_max - 70.0)).min(1.0);
 
         let cardiac_output_l_min = (hr * sv) / 1000.0;
 
@@ -65,7 +65,7 @@ fn main() {
 
         let resting_avo2_diff = 5.0;
         let max_avo2_diff = 15.0;
-        let exercise_intensity = (((hr - 70.0) / (hr_max - 70.0)) as f64).min(1.0).max(0.0);
+        let exercise_intensity = ((hr - 70.0) / (hr_max - 70.0)).clamp(0.0, 1.0);
         let avo2_diff_ml_dl = resting_avo2_diff + (max_avo2_diff - resting_avo2_diff) * exercise_intensity;
 
         let vo2_l_min = cardiac_output_l_min * (avo2_diff_ml_dl / 100.0);
@@ -107,13 +107,13 @@ fn main() {
             self.camp += d_camp * dt;
             self.pka_active += d_pka * dt;
 
-            self.receptor_active = self.receptor_active.max(0.0).min(100.0);
-            self.receptor_desensitized = self.receptor_desensitized.max(0.0).min(100.0);
-            self.receptor_free = self.receptor_free.max(0.0).min(100.0);
-            self.gs_active = self.gs_active.max(0.0).min(50.0);
-            self.gs_inactive = self.gs_inactive.max(0.0).min(50.0);
-            self.camp = self.camp.max(0.0).min(100.0);
-            self.pka_active = self.pka_active.max(0.0).min(50.0);
+            self.receptor_active = self.receptor_active.clamp(0.0, 100.0);
+            self.receptor_desensitized = self.receptor_desensitized.clamp(0.0, 100.0);
+            self.receptor_free = self.receptor_free.clamp(0.0, 100.0);
+            self.gs_active = self.gs_active.clamp(0.0, 50.0);
+            self.gs_inactive = self.gs_inactive.clamp(0.0, 50.0);
+            self.camp = self.camp.clamp(0.0, 100.0);
+            self.pka_active = self.pka_active.clamp(0.0, 50.0);
 
             self.time += dt;
         }
@@ -67,7 +67,7 @@ fn main() {
             let temp_effect = (self.temp_celsius - 37.0) * 0.5;
 
             self.p50 = base_p50 + ph_effect + bpg_effect + temp_effect;
-            self.p50 = self.p50.max(15.0).min(40.0);
+            self.p50 = self.p50.clamp(15.0, 40.0);
         }
 
         fn a_v_difference(&self, arterial_po2: f64, venous_po2: f64) -> f64 {
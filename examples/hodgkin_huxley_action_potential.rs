@@ -156,7 +156,7 @@ fn main() {
 
     for i in 0..200 {
         let t = i as f64 * 0.01;
-        let i_ext = if t >= 0.5 && t < 1.0 { 10.0 } else { 0.0 };
+        let i_ext = if (0.5..1.0).contains(&t) { 10.0 } else { 0.0 };
 
         if i % 5 == 0 {
             let g_na = neuron.sodium_conductance();
@@ -192,7 +192,7 @@ fn main() {
 
         for i in 0..2000 {
             let t = i as f64 * 0.01;
-            let i_ext = if (t >= 0.5 && t < 1.0) || (t >= interval && t < interval + 0.5) {
+            let i_ext = if (0.5..1.0).contains(&t) || (t >= interval && t < interval + 0.5) {
                 10.0
             } else {
                 0.0
@@ -144,12 +144,12 @@ fn main() {
             self.aldosterone += d_aldo * dt;
             self.sodium_retention += d_na_retention * dt;
 
-            self.hr = self.hr.max(40.0).min(160.0);
-            self.svr = self.svr.max(0.5).min(2.0);
-            self.renin = self.renin.max(0.1).min(20.0);
-            self.angiotensin2 = self.angiotensin2.max(5.0).min(150.0);
-            self.aldosterone = self.aldosterone.max(2.0).min(80.0);
-            self.sodium_retention = self.sodium_retention.max(-2.0).min(5.0);
+            self.hr = self.hr.clamp(40.0, 160.0);
+            self.svr = self.svr.clamp(0.5, 2.0);
+            self.renin = self.renin.clamp(0.1, 20.0);
+            self.angiotensin2 = self.angiotensin2.clamp(5.0, 150.0);
+            self.aldosterone = self.aldosterone.clamp(2.0, 80.0);
+            self.sodium_retention = self.sodium_retention.clamp(-2.0, 5.0);
 
             let svr_from_ang2 = 1.0 + (self.angiotensin2 - 15.0) * 0.008;
             self.svr = self.svr * 0.7 + svr_from_ang2 * 0.3;
@@ -124,10 +124,10 @@ fn main() {
         fn interpret_status(&self) -> (&str, &str) {
             let diagnosis = match (self.tsh, self.t4, self.t3) {
                 (tsh, t4, _) if tsh > 4.5 && t4 < 5.0 => "Primary hypothyroidism",
-                (tsh, t4, _) if tsh > 4.5 && t4 >= 5.0 && t4 < 7.5 => "Subclinical hypothyroidism",
+                (tsh, t4, _) if tsh > 4.5 && (5.0..7.5).contains(&t4) => "Subclinical hypothyroidism",
                 (tsh, _, _) if tsh < 0.4 => "Secondary hypothyroidism / TSH deficiency",
                 (tsh, t4, _) if tsh < 0.1 && t4 > 12.0 => "Hyperthyroidism (Graves' or toxic nodule)",
-                (tsh, t4, t3) if tsh >= 0.4 && tsh <= 4.5 && t4 >= 5.0 && t4 <= 12.0 && t3 >= 80.0 && t3 <= 200.0 => "Euthyroid (normal)",
+                (tsh, t4, t3) if (0.4..=4.5).contains(&tsh) && (5.0..=12.0).contains(&t4) && (80.0..=200.0).contains(&t3) => "Euthyroid (normal)",
                 _ => "Borderline / Indeterminate",
             };
 
@@ -308,7 +308,7 @@ fn main() {
         };
 
         let lt4_effect = if day >= 7 {
-            (100.0 / 100.0) * 0.015
+            (lt4_dose / 100.0) * 0.015
         } else {
             0.0
         };
@@ -0,0 +1,60 @@
+/* C interface to the human_biology coupled simulation engine.
+ *
+ * Link against the cdylib or staticlib built by `cargo build --release`.
+ * Engines are opaque handles owned by the caller and released with
+ * hb_engine_free. Fallible calls return HB_OK or a negative HB_ERROR_*
+ * code; hb_last_error() then describes the failure on the calling thread.
+ * Strings are NUL-terminated UTF-8 and only borrowed during the call.
+ * Observables and inputs are addressed as "entity.port", e.g.
+ * "body.core_temperature_c". Time is in minutes.
+ *
+ * Keep in sync with src/capi.rs.
+ */
+#ifndef HUMAN_BIOLOGY_H
+#define HUMAN_BIOLOGY_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define HB_OK 0
+#define HB_ERROR_NULL_POINTER (-1)
+#define HB_ERROR_INVALID_STRING (-2)
+#define HB_ERROR_MODEL (-3)
+#define HB_ERROR_PANIC (-4)
+
+typedef struct HbEngine HbEngine;
+
+const char *hb_version(void);
+const char *hb_last_error(void);
+
+/* Returns NULL on failure. */
+HbEngine *hb_engine_new(double dt_minutes);
+HbEngine *hb_engine_from_scenario(const char *toml);
+void hb_engine_free(HbEngine *engine);
+
+/* model: "activity_schedule", "bioreactor", "circadian_clock",
+ * "glucose_insulin", "iron_erythropoiesis", "respiratory_control",
+ * "sleep_wake" or "thermoregulation"; preset may be NULL for the model
+ * default. Presets: activity_schedule "sedentary" (default) or
+ * "morning_run", bioreactor "perfusion" (default) or "static",
+ * circadian_clock "entrained", glucose_insulin "healthy" (default) or
+ * "type2_diabetes", iron_erythropoiesis "healthy" (default) or
+ * "menstruating", respiratory_control "healthy", sleep_wake "adult",
+ * thermoregulation "adult". */
+int hb_engine_add_entity(HbEngine *engine, const char *name, const char *model_name,
+                         const char *preset);
+int hb_engine_couple(HbEngine *engine, const char *from, const char *to, double gain,
+                     double offset);
+int hb_engine_set_input(HbEngine *engine, const char *path, double value);
+int hb_engine_get_output(HbEngine *engine, const char *path, double *value);
+int hb_engine_step(HbEngine *engine, uint64_t steps);
+double hb_engine_time_minutes(const HbEngine *engine);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* HUMAN_BIOLOGY_H */
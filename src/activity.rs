@@ -0,0 +1,601 @@
+//! Exercise prescriptions converted into the loads and demands the body
+//! models consume: bone strain cycles, muscle activation, metabolic heat
+//! and heart rate.
+
+use crate::biology::signaling::LoadCycles;
+use crate::biology::{BiologyError, BiologyResult};
+use crate::organism::Human;
+use crate::simulation::engine::{load, save, unknown_input, Component};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Resting oxygen uptake, 1 MET.
+const RESTING_VO2_ML_KG_MIN: f64 = 3.5;
+// ACSM metabolic equations: VO2 per m/min of horizontal speed, and leg
+// cycling at 1.8 mL per kg·m of work plus 3.5 for unloaded pedalling.
+// ACSM's Guidelines for Exercise Testing and Prescription, 10th ed., 2018
+const WALKING_VO2_PER_M_MIN: f64 = 0.1;
+const RUNNING_VO2_PER_M_MIN: f64 = 0.2;
+const CYCLING_VO2_PER_KGM: f64 = 1.8;
+const UNLOADED_CYCLING_VO2_ML_KG_MIN: f64 = 3.5;
+const KGM_MIN_PER_W: f64 = 6.12;
+// Resistance training costs 3.5 MET at light loads and 6 MET at heavy.
+// Ainsworth BE et al. (2011) Med Sci Sports Exerc 43:1575-1581
+const RESISTANCE_LIGHT_MET: f64 = 3.5;
+const RESISTANCE_HEAVY_MET: f64 = 6.0;
+// 20.1 J per mL O2 at a mixed-fuel respiratory quotient.
+// Brockway JM (1987) Hum Nutr Clin Nutr 41:463-471, PMID 3429265
+const JOULES_PER_ML_O2: f64 = 20.1;
+// Peak tibial strain rises with gait speed, ~500 µε walking at 1.3 m/s
+// and ~1000 µε running at 3 m/s; heavy lifts approach running strains,
+// cycling barely loads the skeleton.
+// Burr DB et al. (1996) Bone 18:405-410
+// Milgrom C et al. (2000) J Bone Joint Surg Br 82:591-594
+const WALKING_STRAIN_PER_M_S: f64 = 380.0;
+const RUNNING_STRAIN_PER_M_S: f64 = 340.0;
+const RESISTANCE_STRAIN_AT_1RM: f64 = 1200.0;
+const CYCLING_STRAIN_MICROSTRAIN: f64 = 150.0;
+// Load cycles per leg per minute: half the step cadence.
+const WALKING_CYCLES_PER_MIN: f64 = 55.0;
+const RUNNING_CYCLES_PER_MIN: f64 = 80.0;
+const CYCLING_CYCLES_PER_MIN: f64 = 80.0;
+// Healthy adults take 4000-18000 steps a day outside structured exercise.
+// Tudor-Locke C et al. (2011) Int J Behav Nutr Phys Act 8:79
+const DEFAULT_BACKGROUND_STEPS: f64 = 7000.0;
+const BACKGROUND_WALKING_SPEED_M_S: f64 = 1.3;
+// Quadriceps EMG reaches about half of maximal voluntary activation at
+// VO2max in running and cycling.
+const AEROBIC_ACTIVATION_AT_VO2MAX: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Exercise {
+    Walking {
+        speed_m_s: f64,
+    },
+    Running {
+        speed_m_s: f64,
+    },
+    Cycling {
+        power_w: f64,
+    },
+    // Load as a fraction of one-repetition maximum.
+    ResistanceTraining {
+        relative_load: f64,
+        repetitions: u32,
+    },
+}
+
+impl Exercise {
+    fn validate(&self) -> BiologyResult<()> {
+        let valid = match *self {
+            Exercise::Walking { speed_m_s } | Exercise::Running { speed_m_s } => speed_m_s > 0.0,
+            Exercise::Cycling { power_w } => power_w >= 0.0,
+            Exercise::ResistanceTraining { relative_load, .. } => {
+                relative_load > 0.0 && relative_load <= 1.0
+            }
+        };
+        if !valid {
+            return Err(BiologyError::InvalidParameter(format!(
+                "invalid exercise {:?}",
+                self
+            )));
+        }
+        Ok(())
+    }
+
+    // Steady-state demand, before capping at VO2max.
+    pub fn oxygen_uptake_ml_kg_min(&self, body_mass_kg: f64) -> f64 {
+        match *self {
+            Exercise::Walking { speed_m_s } => {
+                RESTING_VO2_ML_KG_MIN + WALKING_VO2_PER_M_MIN * speed_m_s * 60.0
+            }
+            Exercise::Running { speed_m_s } => {
+                RESTING_VO2_ML_KG_MIN + RUNNING_VO2_PER_M_MIN * speed_m_s * 60.0
+            }
+            Exercise::Cycling { power_w } => {
+                RESTING_VO2_ML_KG_MIN
+                    + UNLOADED_CYCLING_VO2_ML_KG_MIN
+                    + CYCLING_VO2_PER_KGM * power_w * KGM_MIN_PER_W / body_mass_kg
+            }
+            Exercise::ResistanceTraining { relative_load, .. } => {
+                let heaviness = ((relative_load - 0.4) / 0.4).clamp(0.0, 1.0);
+                RESTING_VO2_ML_KG_MIN
+                    * (RESISTANCE_LIGHT_MET
+                        + (RESISTANCE_HEAVY_MET - RESISTANCE_LIGHT_MET) * heaviness)
+            }
+        }
+    }
+
+    pub fn peak_bone_strain_microstrain(&self) -> f64 {
+        match *self {
+            Exercise::Walking { speed_m_s } => WALKING_STRAIN_PER_M_S * speed_m_s,
+            Exercise::Running { speed_m_s } => RUNNING_STRAIN_PER_M_S * speed_m_s,
+            Exercise::Cycling { .. } => CYCLING_STRAIN_MICROSTRAIN,
+            Exercise::ResistanceTraining { relative_load, .. } => {
+                RESISTANCE_STRAIN_AT_1RM * relative_load
+            }
+        }
+    }
+
+    fn cycles_per_session(&self, duration_minutes: f64) -> f64 {
+        match *self {
+            Exercise::Walking { .. } => WALKING_CYCLES_PER_MIN * duration_minutes,
+            Exercise::Running { .. } => RUNNING_CYCLES_PER_MIN * duration_minutes,
+            Exercise::Cycling { .. } => CYCLING_CYCLES_PER_MIN * duration_minutes,
+            Exercise::ResistanceTraining { repetitions, .. } => repetitions as f64,
+        }
+    }
+}
+
+// The individual the prescription is applied to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseCapacity {
+    pub body_mass_kg: f64,
+    pub vo2max_ml_kg_min: f64,
+    pub resting_heart_rate_bpm: f64,
+    pub max_heart_rate_bpm: f64,
+}
+
+impl ExerciseCapacity {
+    // Maximal heart rate 208 - 0.7 × age.
+    // Tanaka H, Monahan KD, Seals DR (2001) J Am Coll Cardiol 37:153-156
+    pub fn new(body_mass_kg: f64, age_years: f64, vo2max_ml_kg_min: f64) -> BiologyResult<Self> {
+        if body_mass_kg.is_nan()
+            || body_mass_kg <= 0.0
+            || vo2max_ml_kg_min.is_nan()
+            || vo2max_ml_kg_min <= RESTING_VO2_ML_KG_MIN
+        {
+            return Err(BiologyError::InvalidParameter(
+                "body mass must be positive and VO2max above resting uptake".to_string(),
+            ));
+        }
+        Ok(Self {
+            body_mass_kg,
+            vo2max_ml_kg_min,
+            resting_heart_rate_bpm: 65.0,
+            max_heart_rate_bpm: 208.0 - 0.7 * age_years,
+        })
+    }
+
+    pub fn for_human(human: &Human, vo2max_ml_kg_min: f64) -> BiologyResult<Self> {
+        Self::new(human.weight_kg, human.age_years, vo2max_ml_kg_min)
+    }
+
+    // Fraction of VO2 reserve used.
+    fn reserve_fraction(&self, vo2_ml_kg_min: f64) -> f64 {
+        ((vo2_ml_kg_min - RESTING_VO2_ML_KG_MIN) / (self.vo2max_ml_kg_min - RESTING_VO2_ML_KG_MIN))
+            .clamp(0.0, 1.0)
+    }
+
+    // Percent heart-rate reserve tracks percent VO2 reserve.
+    // Swain DP, Leutholtz BC (1997) Med Sci Sports Exerc 29:410-414
+    pub fn heart_rate_bpm(&self, vo2_ml_kg_min: f64) -> f64 {
+        self.resting_heart_rate_bpm
+            + (self.max_heart_rate_bpm - self.resting_heart_rate_bpm)
+                * self.reserve_fraction(vo2_ml_kg_min)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseSession {
+    pub exercise: Exercise,
+    pub start_minute: f64,
+    pub duration_minutes: f64,
+}
+
+// What the body experiences during one minute of the plan.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ActivitySample {
+    pub minute: f64,
+    // Fraction of maximal voluntary activation of the working muscles.
+    pub muscle_activation: f64,
+    // Metabolic power above rest, the `activity_w` input of
+    // `Thermoregulation`.
+    pub activity_w: f64,
+    pub heart_rate_bpm: f64,
+    pub peak_bone_strain_microstrain: f64,
+}
+
+impl ActivitySample {
+    fn resting(minute: f64, capacity: &ExerciseCapacity) -> Self {
+        Self {
+            minute,
+            muscle_activation: 0.0,
+            activity_w: 0.0,
+            heart_rate_bpm: capacity.resting_heart_rate_bpm,
+            peak_bone_strain_microstrain: 0.0,
+        }
+    }
+}
+
+// Timed exercise sessions on a clock in minutes, plus the everyday walking
+// that loads the skeleton between them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExercisePrescription {
+    pub sessions: Vec<ExerciseSession>,
+    pub background_steps_per_day: f64,
+}
+
+impl Default for ExercisePrescription {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExercisePrescription {
+    pub fn new() -> Self {
+        Self {
+            sessions: Vec::new(),
+            background_steps_per_day: DEFAULT_BACKGROUND_STEPS,
+        }
+    }
+
+    pub fn session(
+        mut self,
+        exercise: Exercise,
+        start_minute: f64,
+        duration_minutes: f64,
+    ) -> BiologyResult<Self> {
+        exercise.validate()?;
+        if !(start_minute >= 0.0 && duration_minutes > 0.0) {
+            return Err(BiologyError::InvalidParameter(
+                "sessions need a non-negative start and positive duration".to_string(),
+            ));
+        }
+        if self.sessions.iter().any(|s| {
+            start_minute < s.start_minute + s.duration_minutes
+                && s.start_minute < start_minute + duration_minutes
+        }) {
+            return Err(BiologyError::InvalidParameter(
+                "sessions must not overlap".to_string(),
+            ));
+        }
+        self.sessions.push(ExerciseSession {
+            exercise,
+            start_minute,
+            duration_minutes,
+        });
+        Ok(self)
+    }
+
+    pub fn with_background_steps(mut self, steps_per_day: f64) -> BiologyResult<Self> {
+        if steps_per_day.is_nan() || steps_per_day < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "step count must be non-negative".to_string(),
+            ));
+        }
+        self.background_steps_per_day = steps_per_day;
+        Ok(self)
+    }
+
+    pub fn session_at(&self, minute: f64) -> Option<&ExerciseSession> {
+        self.sessions
+            .iter()
+            .find(|s| minute >= s.start_minute && minute < s.start_minute + s.duration_minutes)
+    }
+
+    pub fn sample(&self, minute: f64, capacity: &ExerciseCapacity) -> ActivitySample {
+        let Some(session) = self.session_at(minute) else {
+            return ActivitySample::resting(minute, capacity);
+        };
+        let exercise = session.exercise;
+        let demand = exercise.oxygen_uptake_ml_kg_min(capacity.body_mass_kg);
+        let vo2 = demand.min(capacity.vo2max_ml_kg_min);
+        let muscle_activation = match exercise {
+            Exercise::ResistanceTraining { relative_load, .. } => relative_load,
+            _ => AEROBIC_ACTIVATION_AT_VO2MAX * capacity.reserve_fraction(vo2),
+        };
+        ActivitySample {
+            minute,
+            muscle_activation,
+            activity_w: (vo2 - RESTING_VO2_ML_KG_MIN) * capacity.body_mass_kg * JOULES_PER_ML_O2
+                / 60.0,
+            heart_rate_bpm: capacity.heart_rate_bpm(vo2),
+            peak_bone_strain_microstrain: exercise.peak_bone_strain_microstrain(),
+        }
+    }
+
+    // Samples every `dt_minutes` from `start_minute` for `duration_minutes`.
+    pub fn time_series(
+        &self,
+        capacity: &ExerciseCapacity,
+        start_minute: f64,
+        duration_minutes: f64,
+        dt_minutes: f64,
+    ) -> BiologyResult<Vec<ActivitySample>> {
+        if dt_minutes.is_nan() || dt_minutes <= 0.0 || duration_minutes < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "sampling step must be positive".to_string(),
+            ));
+        }
+        let n = (duration_minutes / dt_minutes).round() as usize;
+        Ok((0..n)
+            .map(|i| self.sample(start_minute + i as f64 * dt_minutes, capacity))
+            .collect())
+    }
+
+    // Skeletal loading on day `day` (counted from minute 0), for
+    // `WntSignaling::apply_load`.
+    pub fn daily_bone_loads(&self, day: u32) -> Vec<LoadCycles> {
+        let start = day as f64 * 1440.0;
+        let walking = Exercise::Walking {
+            speed_m_s: BACKGROUND_WALKING_SPEED_M_S,
+        };
+        let mut loads = vec![LoadCycles {
+            peak_microstrain: walking.peak_bone_strain_microstrain(),
+            cycles: self.background_steps_per_day / 2.0,
+        }];
+        for session in &self.sessions {
+            let overlap = (session.start_minute + session.duration_minutes).min(start + 1440.0)
+                - session.start_minute.max(start);
+            if overlap > 0.0 {
+                let fraction = overlap / session.duration_minutes;
+                loads.push(LoadCycles {
+                    peak_microstrain: session.exercise.peak_bone_strain_microstrain(),
+                    cycles: session
+                        .exercise
+                        .cycles_per_session(session.duration_minutes)
+                        * fraction,
+                });
+            }
+        }
+        loads
+    }
+}
+
+// A prescription played out for one individual on the engine clock.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivitySchedule {
+    pub prescription: ExercisePrescription,
+    pub capacity: ExerciseCapacity,
+    pub minute: f64,
+}
+
+impl ActivitySchedule {
+    pub fn new(prescription: ExercisePrescription, capacity: ExerciseCapacity) -> Self {
+        Self {
+            prescription,
+            capacity,
+            minute: 0.0,
+        }
+    }
+
+    pub fn current(&self) -> ActivitySample {
+        self.prescription.sample(self.minute, &self.capacity)
+    }
+
+    pub fn advance(&mut self, dt_minutes: f64) {
+        self.minute += dt_minutes;
+    }
+}
+
+// Banister impulse-response model: each session raises fitness and a
+// larger but shorter-lived fatigue; performance is their difference.
+// Banister EW et al. (1975) Aust J Sports Med 7:57-61
+const FITNESS_TIME_CONSTANT_DAYS: f64 = 42.0;
+const FATIGUE_TIME_CONSTANT_DAYS: f64 = 7.0;
+const FATIGUE_WEIGHT: f64 = 2.0;
+// Training impulse weights each minute by heart-rate reserve.
+// Banister EW (1991) Physiological Testing of the High-Performance Athlete, 403-424
+const TRIMP_COEFFICIENT: f64 = 0.64;
+const TRIMP_EXPONENT: f64 = 1.92;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrainingLoad {
+    pub fitness: f64,
+    pub fatigue: f64,
+}
+
+impl TrainingLoad {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn training_impulse(samples: &[ActivitySample], capacity: &ExerciseCapacity) -> f64 {
+        let dt = match samples {
+            [a, b, ..] => b.minute - a.minute,
+            _ => 1.0,
+        };
+        samples
+            .iter()
+            .map(|s| {
+                let reserve = ((s.heart_rate_bpm - capacity.resting_heart_rate_bpm)
+                    / (capacity.max_heart_rate_bpm - capacity.resting_heart_rate_bpm))
+                    .clamp(0.0, 1.0);
+                dt * reserve * TRIMP_COEFFICIENT * (TRIMP_EXPONENT * reserve).exp()
+            })
+            .sum()
+    }
+
+    pub fn add_impulse(&mut self, trimp: f64) {
+        self.fitness += trimp.max(0.0);
+        self.fatigue += trimp.max(0.0);
+    }
+
+    // Fatigue clears in proportion to tissue recovery, so short sleep
+    // (`SleepWakeRegulation::recovery_factor` below 1) lets it build up.
+    pub fn step_day(&mut self, recovery_factor: f64) {
+        self.fitness *= (-1.0 / FITNESS_TIME_CONSTANT_DAYS).exp();
+        self.fatigue *= (-recovery_factor.max(0.0) / FATIGUE_TIME_CONSTANT_DAYS).exp();
+    }
+
+    pub fn performance(&self) -> f64 {
+        self.fitness - FATIGUE_WEIGHT * self.fatigue
+    }
+}
+
+// Drives other models by coupling, e.g. `exercise.activity_w` into
+// `body.activity_w`.
+impl Component for ActivitySchedule {
+    fn kind(&self) -> &'static str {
+        "activity_schedule"
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[]
+    }
+
+    fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("activity_w", "W"),
+            ("heart_rate_bpm", "1/min"),
+            ("muscle_activation", "1"),
+            ("bone_strain_microstrain", "µε"),
+        ]
+    }
+
+    fn set_input(&mut self, name: &str, _: f64) -> BiologyResult<()> {
+        Err(unknown_input(self.kind(), name))
+    }
+
+    fn output(&self, name: &str) -> Option<f64> {
+        let sample = self.current();
+        match name {
+            "activity_w" => Some(sample.activity_w),
+            "heart_rate_bpm" => Some(sample.heart_rate_bpm),
+            "muscle_activation" => Some(sample.muscle_activation),
+            "bone_strain_microstrain" => Some(sample.peak_bone_strain_microstrain),
+            _ => None,
+        }
+    }
+
+    fn step(&mut self, dt_minutes: f64) {
+        self.advance(dt_minutes);
+    }
+
+    fn save_state(&self) -> Value {
+        save(self)
+    }
+
+    fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+        load(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::signaling::{WntSignaling, HABITUAL_STRAIN_MICROSTRAIN};
+
+    fn runner() -> ExerciseCapacity {
+        ExerciseCapacity::new(70.0, 30.0, 50.0).unwrap()
+    }
+
+    #[test]
+    fn test_acsm_energy_and_heart_rate() {
+        let capacity = runner();
+        let plan = ExercisePrescription::new()
+            .session(Exercise::Running { speed_m_s: 3.0 }, 60.0, 30.0)
+            .unwrap();
+        let rest = plan.sample(0.0, &capacity);
+        assert_eq!(rest.activity_w, 0.0);
+        assert_eq!(rest.heart_rate_bpm, 65.0);
+
+        // 180 m/min: 39.5 mL/kg/min, ~11 MET.
+        let run = plan.sample(70.0, &capacity);
+        assert!((run.activity_w - 36.0 * 70.0 * 20.1 / 60.0).abs() < 1e-9);
+        assert!(run.heart_rate_bpm > 150.0 && run.heart_rate_bpm < 187.0);
+        assert!(run.muscle_activation > 0.3 && run.muscle_activation <= 0.5);
+
+        // Demand beyond VO2max is capped.
+        let sprint = ExercisePrescription::new()
+            .session(Exercise::Running { speed_m_s: 7.0 }, 0.0, 5.0)
+            .unwrap()
+            .sample(1.0, &capacity);
+        assert_eq!(sprint.heart_rate_bpm, capacity.max_heart_rate_bpm);
+
+        let series = plan.time_series(&capacity, 50.0, 60.0, 1.0).unwrap();
+        assert_eq!(series.len(), 60);
+        assert_eq!(series.iter().filter(|s| s.activity_w > 0.0).count(), 30);
+    }
+
+    #[test]
+    fn test_running_loads_bone_more_than_cycling() {
+        let loads = |exercise: Exercise| {
+            let plan = ExercisePrescription::new()
+                .session(exercise, 600.0, 45.0)
+                .unwrap();
+            let mut site = WntSignaling::new();
+            site.apply_load(&plan.daily_bone_loads(0)).unwrap();
+            site.strain_microstrain
+        };
+        let sedentary = {
+            let mut site = WntSignaling::new();
+            site.apply_load(&ExercisePrescription::new().daily_bone_loads(0))
+                .unwrap();
+            site.strain_microstrain
+        };
+        assert!((sedentary - HABITUAL_STRAIN_MICROSTRAIN).abs() < 20.0);
+        let running = loads(Exercise::Running { speed_m_s: 3.5 });
+        let cycling = loads(Exercise::Cycling { power_w: 150.0 });
+        assert!(running > 1.3 * sedentary);
+        assert!((cycling - sedentary).abs() < 5.0);
+        let squats = loads(Exercise::ResistanceTraining {
+            relative_load: 0.8,
+            repetitions: 40,
+        });
+        assert!(squats > sedentary);
+        // Sessions are only counted on the day they happen.
+        let plan = ExercisePrescription::new()
+            .session(Exercise::Running { speed_m_s: 3.5 }, 600.0, 45.0)
+            .unwrap();
+        assert_eq!(plan.daily_bone_loads(1).len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_prescriptions_rejected() {
+        let plan = ExercisePrescription::new()
+            .session(Exercise::Walking { speed_m_s: 1.3 }, 0.0, 30.0)
+            .unwrap();
+        assert!(plan
+            .clone()
+            .session(Exercise::Walking { speed_m_s: 1.3 }, 20.0, 30.0)
+            .is_err());
+        assert!(plan
+            .clone()
+            .session(Exercise::Running { speed_m_s: -1.0 }, 60.0, 30.0)
+            .is_err());
+        assert!(plan
+            .clone()
+            .session(
+                Exercise::ResistanceTraining {
+                    relative_load: 1.2,
+                    repetitions: 5,
+                },
+                60.0,
+                30.0,
+            )
+            .is_err());
+        assert!(plan.with_background_steps(-1.0).is_err());
+        assert!(ExerciseCapacity::new(70.0, 30.0, 3.0).is_err());
+    }
+
+    #[test]
+    fn test_training_load_and_sleep_limited_recovery() {
+        let capacity = runner();
+        let plan = ExercisePrescription::new()
+            .session(Exercise::Running { speed_m_s: 3.0 }, 0.0, 45.0)
+            .unwrap();
+        let samples = plan.time_series(&capacity, 0.0, 45.0, 1.0).unwrap();
+        let trimp = TrainingLoad::training_impulse(&samples, &capacity);
+        // 45 min near 80% HRR: ~100 TRIMP.
+        assert!(trimp > 70.0 && trimp < 130.0, "{}", trimp);
+
+        let train = |recovery: f64| {
+            let mut load = TrainingLoad::new();
+            for day in 0..28 {
+                if day % 2 == 0 {
+                    load.add_impulse(trimp);
+                }
+                load.step_day(recovery);
+            }
+            load
+        };
+        let rested = train(1.0);
+        let deprived = train(0.8);
+        assert!(rested.fitness > 0.0);
+        assert_eq!(rested.fitness, deprived.fitness);
+        assert!(deprived.fatigue > rested.fatigue);
+        assert!(deprived.performance() < rested.performance());
+    }
+}
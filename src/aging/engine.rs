@@ -0,0 +1,276 @@
+use crate::aging::glycation::{AgeCrosslinking, CollagenTissue};
+use crate::aging::senescence::{SaspSignal, SenescenceParameters, SenescentCellBurden};
+use crate::biology::genetics::gene_regulation::GeneRegulatoryNetwork;
+use crate::biology::BiologyResult;
+use crate::organism::Human;
+use crate::pathology::fibrosis::FibrosisModel;
+use crate::systems::cardiovascular::hematology::BiologicalSex;
+use crate::systems::immune::immunosenescence::Immunosenescence;
+use serde::{Deserialize, Serialize};
+
+// Leukocyte telomeres shorten ~25 bp/year across adulthood from ~7.5 kb at 20.
+// Müezzinler A, Zaineddin AK, Brenner H (2013) Ageing Res Rev 12:509-519, PMID 23333817
+const LEUKOCYTE_TELOMERE_AT_20_BP: f64 = 7500.0;
+const LEUKOCYTE_TELOMERE_LOSS_BP_PER_YEAR: f64 = 25.0;
+// Median natural menopause at 51, preceded by ~4 years of perimenopausal
+// follicle loss.
+// McKinlay SM, Brambilla DJ, Posner JG (1992) Maturitas 14:103-115, PMID 1565019
+pub const MEDIAN_MENOPAUSE_AGE_YEARS: f64 = 51.0;
+const PERIMENOPAUSE_YEARS: f64 = 4.0;
+const POSTMENOPAUSAL_OVARIAN_CAPACITY: f64 = 0.05;
+// Total testosterone falls ~1.6%/year after 40.
+// Feldman HA et al. (2002) J Clin Endocrinol Metab 87:589-598, PMID 11836290
+const ANDROPAUSE_ONSET_YEARS: f64 = 40.0;
+const TESTICULAR_DECLINE_PER_YEAR: f64 = 0.016;
+// GFR falls ~0.75 mL/min/year after 40 in the Baltimore Longitudinal Study.
+// Lindeman RD, Tobin J, Shock NW (1985) J Am Geriatr Soc 33:278-285, PMID 3989190
+const NEPHRON_LOSS_ONSET_YEARS: f64 = 40.0;
+const NEPHRON_LOSS_PER_YEAR: f64 = 0.006;
+// AGE folds are reported against a young adult's collagen.
+const YOUNG_ADULT_REFERENCE_YEARS: f64 = 25.0;
+
+// Fraction of young-adult gonadal steroid output at a given age.
+pub fn gonadal_capacity(sex: BiologicalSex, age_years: f64, menopause_age_years: f64) -> f64 {
+    match sex {
+        BiologicalSex::Female => {
+            let onset = menopause_age_years - PERIMENOPAUSE_YEARS;
+            let progress = ((age_years - onset) / PERIMENOPAUSE_YEARS).clamp(0.0, 1.0);
+            1.0 - progress * (1.0 - POSTMENOPAUSAL_OVARIAN_CAPACITY)
+        }
+        BiologicalSex::Male => {
+            (1.0 - TESTICULAR_DECLINE_PER_YEAR).powf((age_years - ANDROPAUSE_ONSET_YEARS).max(0.0))
+        }
+    }
+}
+
+// Snapshot handed to every age-dependent model after each simulated year.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgingState {
+    pub age_years: f64,
+    pub sasp: SaspSignal,
+    pub regeneration_factor: f64,
+    pub leukocyte_telomere_bp: f64,
+    pub gonadal_capacity: f64,
+    pub skin_age_fold: f64,
+    pub cartilage_age_fold: f64,
+    pub bone_age_fold: f64,
+}
+
+impl AgingState {
+    pub fn crosslink_fold(&self, tissue: CollagenTissue) -> f64 {
+        match tissue {
+            CollagenTissue::Skin => self.skin_age_fold,
+            CollagenTissue::ArticularCartilage => self.cartilage_age_fold,
+            CollagenTissue::CorticalBone => self.bone_age_fold,
+        }
+    }
+}
+
+// Tissue models implement this to pick up their age-dependent parameters.
+pub trait AgeDependent {
+    fn apply_aging(&mut self, state: &AgingState);
+}
+
+// Dermal fibroblasts stand in for all fibrotic tissues: the scar inherits
+// the AGE load of the surrounding collagen.
+impl AgeDependent for FibrosisModel {
+    fn apply_aging(&mut self, state: &AgingState) {
+        self.age_crosslink_fold = state.skin_age_fold;
+    }
+}
+
+// Methylation drift over the years since the network was last aged; the
+// first call counts as one engine step.
+impl AgeDependent for GeneRegulatoryNetwork {
+    fn apply_aging(&mut self, state: &AgingState) {
+        let previous = self.aged_to_years.unwrap_or(state.age_years - 1.0);
+        self.apply_drift(state.age_years - previous);
+        self.aged_to_years = Some(state.age_years);
+    }
+}
+
+// Thymic involution and repertoire loss follow age; inflammaging follows
+// the SASP the engine has accrued, so senolytics reach vaccine responses.
+impl AgeDependent for Immunosenescence {
+    fn apply_aging(&mut self, state: &AgingState) {
+        self.update(state.age_years, state.sasp.inflammatory_fold_over_young);
+    }
+}
+
+// Advances a virtual individual year by year, accruing the slow damage
+// processes and pushing their consequences into the organ models.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgingEngine {
+    pub senescence: SenescentCellBurden,
+    pub crosslinks: Vec<AgeCrosslinking>,
+    pub leukocyte_telomere_bp: f64,
+    pub menopause_age_years: f64,
+}
+
+impl AgingEngine {
+    pub fn new(human: &Human) -> BiologyResult<Self> {
+        let age = human.age_years;
+        let crosslinks = [
+            CollagenTissue::Skin,
+            CollagenTissue::ArticularCartilage,
+            CollagenTissue::CorticalBone,
+        ]
+        .into_iter()
+        .map(|tissue| AgeCrosslinking::new(tissue, age))
+        .collect::<BiologyResult<Vec<_>>>()?;
+        Ok(Self {
+            senescence: SenescentCellBurden::new(SenescenceParameters::human_time_rescaled(), age),
+            crosslinks,
+            leukocyte_telomere_bp: LEUKOCYTE_TELOMERE_AT_20_BP
+                - LEUKOCYTE_TELOMERE_LOSS_BP_PER_YEAR * (age - 20.0),
+            menopause_age_years: MEDIAN_MENOPAUSE_AGE_YEARS,
+        })
+    }
+
+    fn crosslink_fold(&self, tissue: CollagenTissue) -> f64 {
+        self.crosslinks
+            .iter()
+            .find(|c| c.tissue == tissue)
+            .map_or(1.0, |c| {
+                c.pentosidine_pmol_per_mg
+                    / AgeCrosslinking::normoglycemic_reference(tissue, YOUNG_ADULT_REFERENCE_YEARS)
+            })
+    }
+
+    pub fn state(&self, human: &Human) -> AgingState {
+        AgingState {
+            age_years: human.age_years,
+            sasp: self.senescence.sasp(),
+            regeneration_factor: self.senescence.regeneration_rate_factor(),
+            leukocyte_telomere_bp: self.leukocyte_telomere_bp,
+            gonadal_capacity: human.gonadal.gonadal_capacity,
+            skin_age_fold: self.crosslink_fold(CollagenTissue::Skin),
+            cartilage_age_fold: self.crosslink_fold(CollagenTissue::ArticularCartilage),
+            bone_age_fold: self.crosslink_fold(CollagenTissue::CorticalBone),
+        }
+    }
+
+    // One-year steps: daily senescent-cell turnover, yearly crosslink,
+    // telomere, gonadal and nephron updates, then the tissue hooks.
+    pub fn advance_years(
+        &mut self,
+        human: &mut Human,
+        years: usize,
+        tissues: &mut [&mut dyn AgeDependent],
+    ) -> BiologyResult<AgingState> {
+        for _ in 0..years {
+            human.set_age_years(human.age_years + 1.0)?;
+            for _ in 0..365 {
+                self.senescence.step(1.0);
+            }
+            let glucose = human.glucose.mean_glucose_mg_dl();
+            for crosslink in &mut self.crosslinks {
+                crosslink.step_years(glucose, 1.0);
+            }
+            self.leukocyte_telomere_bp -= LEUKOCYTE_TELOMERE_LOSS_BP_PER_YEAR;
+
+            human.gonadal.gonadal_capacity =
+                gonadal_capacity(human.sex, human.age_years, self.menopause_age_years);
+            if human.age_years > NEPHRON_LOSS_ONSET_YEARS {
+                human.renal.glomeruli.functioning_nephron_fraction *= 1.0 - NEPHRON_LOSS_PER_YEAR;
+            }
+
+            let state = self.state(human);
+            for tissue in tissues.iter_mut() {
+                tissue.apply_aging(&state);
+            }
+        }
+        Ok(self.state(human))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathology::fibrosis::FibroticTissue;
+
+    #[test]
+    fn test_gonadal_capacity_by_sex() {
+        let female = |age| gonadal_capacity(BiologicalSex::Female, age, 51.0);
+        assert_eq!(female(40.0), 1.0);
+        assert!(female(49.0) < 1.0 && female(49.0) > 0.05);
+        assert!((female(60.0) - POSTMENOPAUSAL_OVARIAN_CAPACITY).abs() < 1e-12);
+        let male = |age| gonadal_capacity(BiologicalSex::Male, age, 51.0);
+        assert_eq!(male(35.0), 1.0);
+        assert!((0.5..0.65).contains(&male(70.0)));
+    }
+
+    #[test]
+    fn test_decades_accumulate_damage_and_slow_regeneration() {
+        let mut human = Human::new(BiologicalSex::Male, 30.0, 175.0, 75.0).unwrap();
+        let mut engine = AgingEngine::new(&human).unwrap();
+        let young = engine.state(&human);
+        let gfr_young = human.renal.gfr_ml_min();
+        let old = engine.advance_years(&mut human, 45, &mut []).unwrap();
+        assert_eq!(human.age_years, 75.0);
+        assert!(
+            old.sasp.inflammatory_fold_over_young > 3.0 * young.sasp.inflammatory_fold_over_young
+        );
+        assert!(old.regeneration_factor < young.regeneration_factor);
+        assert!((young.leukocyte_telomere_bp - old.leukocyte_telomere_bp - 1125.0).abs() < 1e-9);
+        assert!(old.skin_age_fold > 1.3 && old.cartilage_age_fold > old.skin_age_fold);
+        // ~0.75 mL/min/year from 40 to 75.
+        let gfr_loss = gfr_young - human.renal.gfr_ml_min();
+        assert!((15.0..40.0).contains(&gfr_loss), "gfr loss {gfr_loss}");
+        assert!(old.gonadal_capacity < 0.65);
+    }
+
+    #[test]
+    fn test_menopause_reaches_axis_and_registered_tissues() {
+        let mut human = Human::new(BiologicalSex::Female, 45.0, 162.0, 62.0).unwrap();
+        let mut engine = AgingEngine::new(&human).unwrap();
+        let mut scar = FibrosisModel::new(FibroticTissue::Skin);
+        engine
+            .advance_years(&mut human, 10, &mut [&mut scar])
+            .unwrap();
+        assert!((human.gonadal.gonadal_capacity - POSTMENOPAUSAL_OVARIAN_CAPACITY).abs() < 1e-12);
+        assert!(scar.age_crosslink_fold > 1.0);
+        assert_eq!(
+            scar.age_crosslink_fold,
+            engine.state(&human).crosslink_fold(CollagenTissue::Skin)
+        );
+        assert!(engine.advance_years(&mut human, 60, &mut []).is_err());
+    }
+
+    #[test]
+    fn test_epigenetic_drift_silences_island_promoters_with_age() {
+        use crate::biology::genetics::epigenetics::{EpigeneticKinetics, PromoterState};
+        use crate::biology::genetics::gene_regulation::RegulatedGene;
+
+        let mut human = Human::new(BiologicalSex::Male, 30.0, 175.0, 75.0).unwrap();
+        let mut engine = AgingEngine::new(&human).unwrap();
+        let mut grn = GeneRegulatoryNetwork::new(EpigeneticKinetics::default());
+        let island = grn.add_gene(
+            RegulatedGene::new("island", 1.0, 0.5)
+                .with_promoter(PromoterState::open())
+                .with_cpg_island(true),
+        );
+        let poor = grn.add_gene(RegulatedGene::new("poor", 1.0, 0.5));
+        engine
+            .advance_years(&mut human, 40, &mut [&mut grn])
+            .unwrap();
+        assert_eq!(grn.aged_to_years, Some(70.0));
+        let expected = 1.0 - 0.95 * (-0.005_f64 * 40.0).exp();
+        assert!((grn.genes[island].promoter.methylation - expected).abs() < 1e-9);
+        assert!(grn.genes[poor].promoter.methylation < PromoterState::silenced().methylation);
+    }
+
+    #[test]
+    fn test_aging_weakens_germinal_centres() {
+        let mut human = Human::new(BiologicalSex::Female, 25.0, 165.0, 60.0).unwrap();
+        let mut engine = AgingEngine::new(&human).unwrap();
+        let mut immunity = Immunosenescence::for_age(25.0);
+        let young = immunity.germinal_centre_factor();
+        engine
+            .advance_years(&mut human, 50, &mut [&mut immunity])
+            .unwrap();
+        assert_eq!(immunity.age_years, 75.0);
+        assert!(immunity.baseline_il6_pg_ml > 2.0);
+        assert!(immunity.germinal_centre_factor() < 0.5 * young);
+    }
+}
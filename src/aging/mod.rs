@@ -0,0 +1,9 @@
+pub mod engine;
+pub mod glycation;
+pub mod senescence;
+pub mod telomere;
+
+pub use engine::{gonadal_capacity, AgeDependent, AgingEngine, AgingState};
+pub use glycation::{AgeCrosslinking, CollagenTissue};
+pub use senescence::{CellState, SaspSignal, SenescenceParameters, SenescentCellBurden};
+pub use telomere::{ProliferatingPopulation, ReplicativeCell, TelomereParameters};
@@ -0,0 +1,256 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellState {
+    Proliferating,
+    Quiescent,
+    Senescent,
+    Apoptotic,
+}
+
+impl CellState {
+    pub fn can_divide(&self) -> bool {
+        matches!(self, CellState::Proliferating)
+    }
+
+    pub fn secretes_sasp(&self) -> bool {
+        matches!(self, CellState::Senescent)
+    }
+}
+
+// Saturating-removal model of senescent cell turnover:
+// dX/dt = η·t − β·X/(κ + X)
+// Karin O et al. (2019) Nat Commun 10:5495, PMID 31792199. Parameters are
+// the mouse fit from that paper (X in units where Xc is the death threshold).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SenescenceParameters {
+    pub production_slope_per_day_per_year: f64,
+    pub removal_max_per_day: f64,
+    pub removal_half_saturation: f64,
+    pub death_threshold: f64,
+    // Age of the young cohort SASP is reported against.
+    pub young_reference_age_years: f64,
+}
+
+impl SenescenceParameters {
+    pub fn mouse_karin_2019() -> Self {
+        Self {
+            production_slope_per_day_per_year: 0.084,
+            removal_max_per_day: 0.15,
+            removal_half_saturation: 0.5,
+            death_threshold: 17.0,
+            // Young (3-month) mice are the baseline cohort in Karin 2019.
+            young_reference_age_years: 0.25,
+        }
+    }
+
+    // The mouse fit with time stretched 30-fold, mapping the ~2.7 year
+    // mouse lifespan onto ~80 years: rates per day scale by 1/30 and the
+    // production slope, being per day per year of age, by 1/30².
+    pub fn human_time_rescaled() -> Self {
+        let mouse = Self::mouse_karin_2019();
+        let scale = 30.0;
+        Self {
+            production_slope_per_day_per_year: mouse.production_slope_per_day_per_year
+                / (scale * scale),
+            removal_max_per_day: mouse.removal_max_per_day / scale,
+            young_reference_age_years: mouse.young_reference_age_years * scale,
+            ..mouse
+        }
+    }
+}
+
+impl Default for SenescenceParameters {
+    fn default() -> Self {
+        Self::mouse_karin_2019()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaspSignal {
+    pub burden_fraction_of_threshold: f64,
+    pub inflammatory_fold_over_young: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SenescentCellBurden {
+    pub params: SenescenceParameters,
+    pub burden: f64,
+    pub age_years: f64,
+    pub damage_production_per_day: f64,
+    pub removal_boost: f64,
+    young_reference_burden: f64,
+}
+
+impl SenescentCellBurden {
+    pub fn new(params: SenescenceParameters, age_years: f64) -> Self {
+        let young_reference_burden =
+            Self::quasi_steady_burden(&params, params.young_reference_age_years);
+        let burden = Self::quasi_steady_burden(&params, age_years);
+        Self {
+            params,
+            burden,
+            age_years,
+            damage_production_per_day: 0.0,
+            removal_boost: 0.0,
+            young_reference_burden,
+        }
+    }
+
+    // At a fixed age the burden relaxes toward η·t·κ / (β − η·t); once
+    // production exceeds the saturated removal capacity it runs away.
+    pub fn quasi_steady_burden(params: &SenescenceParameters, age_years: f64) -> f64 {
+        let production = params.production_slope_per_day_per_year * age_years;
+        if production >= params.removal_max_per_day {
+            return params.death_threshold;
+        }
+        (params.removal_half_saturation * production / (params.removal_max_per_day - production))
+            .min(params.death_threshold)
+    }
+
+    pub fn production_rate_per_day(&self) -> f64 {
+        self.params.production_slope_per_day_per_year * self.age_years
+            + self.damage_production_per_day
+    }
+
+    pub fn removal_rate_per_day(&self) -> f64 {
+        self.params.removal_max_per_day * (1.0 + self.removal_boost) * self.burden
+            / (self.params.removal_half_saturation + self.burden)
+    }
+
+    pub fn step(&mut self, dt_days: f64) {
+        let d_burden = self.production_rate_per_day() - self.removal_rate_per_day();
+        self.burden = (self.burden + d_burden * dt_days).max(0.0);
+        self.age_years += dt_days / 365.25;
+    }
+
+    pub fn add_damage(&mut self, extra_production_per_day: f64) {
+        self.damage_production_per_day =
+            (self.damage_production_per_day + extra_production_per_day).max(0.0);
+    }
+
+    // One-off influx of cells that became senescent elsewhere; production
+    // and removal rates are unchanged.
+    pub fn add_senescent_cells(&mut self, amount: f64) {
+        self.burden += amount.max(0.0);
+    }
+
+    // Hit-and-run senolytic dosing kills a fraction of senescent cells at once.
+    pub fn apply_senolytic(&mut self, kill_fraction: f64) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&kill_fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "senolytic kill fraction must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.burden *= 1.0 - kill_fraction;
+        Ok(())
+    }
+
+    // Immune clearance enhancement (e.g. senolytic CAR-T) raises β.
+    pub fn set_removal_boost(&mut self, boost: f64) {
+        self.removal_boost = boost.max(0.0);
+    }
+
+    pub fn fraction_of_threshold(&self) -> f64 {
+        (self.burden / self.params.death_threshold).clamp(0.0, 1.0)
+    }
+
+    // SASP output is taken as proportional to the number of senescent cells.
+    pub fn sasp(&self) -> SaspSignal {
+        SaspSignal {
+            burden_fraction_of_threshold: self.fraction_of_threshold(),
+            inflammatory_fold_over_young: self.burden / self.young_reference_burden.max(1e-9),
+        }
+    }
+
+    pub fn regeneration_rate_factor(&self) -> f64 {
+        1.0 - self.fraction_of_threshold()
+    }
+
+    pub fn bone_formation_factor(&self) -> f64 {
+        self.regeneration_rate_factor()
+    }
+
+    pub fn exceeds_threshold(&self) -> bool {
+        self.burden >= self.params.death_threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_state_division() {
+        assert!(CellState::Proliferating.can_divide());
+        assert!(!CellState::Senescent.can_divide());
+        assert!(CellState::Senescent.secretes_sasp());
+    }
+
+    #[test]
+    fn test_burden_accumulates_with_age() {
+        let params = SenescenceParameters::mouse_karin_2019();
+        let young = SenescentCellBurden::quasi_steady_burden(&params, 0.5);
+        let old = SenescentCellBurden::quasi_steady_burden(&params, 1.5);
+        assert!(old > 3.0 * young);
+    }
+
+    #[test]
+    fn test_human_rescaling_preserves_trajectory_shape() {
+        let mouse = SenescenceParameters::mouse_karin_2019();
+        let human = SenescenceParameters::human_time_rescaled();
+        let at_mouse = SenescentCellBurden::quasi_steady_burden(&mouse, 1.5);
+        let at_human = SenescentCellBurden::quasi_steady_burden(&human, 45.0);
+        assert!((at_mouse - at_human).abs() < 1e-9);
+        let young = SenescentCellBurden::new(human, 7.5);
+        assert!((young.sasp().inflammatory_fold_over_young - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dynamic_burden_tracks_quasi_steady_state() {
+        let mut cells = SenescentCellBurden::new(SenescenceParameters::default(), 0.5);
+        for _ in 0..182 {
+            cells.step(1.0);
+        }
+        let expected = SenescentCellBurden::quasi_steady_burden(&cells.params, cells.age_years);
+        assert!((cells.burden - expected).abs() / expected < 0.25);
+    }
+
+    #[test]
+    fn test_senolytic_effect_wanes() {
+        let mut cells = SenescentCellBurden::new(SenescenceParameters::default(), 1.5);
+        let before = cells.burden;
+        cells.apply_senolytic(0.5).unwrap();
+        let after_dose = cells.burden;
+        assert!(after_dose < 0.6 * before);
+        for _ in 0..60 {
+            cells.step(1.0);
+        }
+        assert!(cells.burden > after_dose && cells.burden < before);
+        assert!(cells.apply_senolytic(1.5).is_err());
+    }
+
+    #[test]
+    fn test_sasp_degrades_regeneration() {
+        let young = SenescentCellBurden::new(SenescenceParameters::default(), 0.3);
+        let old = SenescentCellBurden::new(SenescenceParameters::default(), 1.6);
+        assert!(
+            old.sasp().inflammatory_fold_over_young > young.sasp().inflammatory_fold_over_young
+        );
+        assert!(old.regeneration_rate_factor() < young.regeneration_rate_factor());
+        assert!(old.bone_formation_factor() > 0.0);
+    }
+
+    #[test]
+    fn test_damage_raises_burden() {
+        let mut control = SenescentCellBurden::new(SenescenceParameters::default(), 1.0);
+        let mut irradiated = control.clone();
+        irradiated.add_damage(0.02);
+        for _ in 0..30 {
+            control.step(1.0);
+            irradiated.step(1.0);
+        }
+        assert!(irradiated.burden > control.burden);
+    }
+}
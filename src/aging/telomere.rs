@@ -0,0 +1,312 @@
+use super::senescence::{CellState, SenescentCellBurden};
+use crate::biology::{BiologyError, BiologyResult};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// Fibroblast terminal restriction fragments shorten ~50-100 bp per population
+// doubling from ~9 kb and cells senesce near ~4-5 kb.
+// Harley CB, Futcher AB, Greider CW (1990) Nature 345:458-460, PMID 2342578
+// Hayflick limit of ~50 doublings.
+// Hayflick L, Moorhead PS (1961) Exp Cell Res 25:585-621, PMID 13905658
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TelomereParameters {
+    pub initial_length_bp: f64,
+    pub loss_per_division_bp: f64,
+    pub loss_sd_bp: f64,
+    pub critical_length_bp: f64,
+}
+
+impl TelomereParameters {
+    pub fn human_fibroblast() -> Self {
+        Self {
+            initial_length_bp: 9000.0,
+            loss_per_division_bp: 90.0,
+            loss_sd_bp: 30.0,
+            critical_length_bp: 4500.0,
+        }
+    }
+
+    pub fn expected_replicative_capacity(&self) -> f64 {
+        ((self.initial_length_bp - self.critical_length_bp) / self.loss_per_division_bp).max(0.0)
+    }
+}
+
+impl Default for TelomereParameters {
+    fn default() -> Self {
+        Self::human_fibroblast()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplicativeCell {
+    pub telomere_length_bp: f64,
+    pub divisions: u32,
+    pub state: CellState,
+}
+
+impl ReplicativeCell {
+    pub fn new(telomere_length_bp: f64) -> Self {
+        Self {
+            telomere_length_bp,
+            divisions: 0,
+            state: CellState::Proliferating,
+        }
+    }
+
+    pub fn remaining_divisions(&self, params: &TelomereParameters) -> f64 {
+        ((self.telomere_length_bp - params.critical_length_bp) / params.loss_per_division_bp)
+            .max(0.0)
+    }
+
+    fn shorten<R: Rng>(&mut self, params: &TelomereParameters, rng: &mut R) {
+        // Box-Muller for the per-division end-replication loss.
+        let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+        let u2: f64 = rng.gen();
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+        let loss = (params.loss_per_division_bp + params.loss_sd_bp * z).max(0.0);
+        self.telomere_length_bp = (self.telomere_length_bp - loss).max(0.0);
+        self.divisions += 1;
+        if self.telomere_length_bp <= params.critical_length_bp {
+            self.state = CellState::Senescent;
+        }
+    }
+
+    // Returns the daughter cell; both copies lose telomere independently.
+    pub fn divide<R: Rng>(
+        &mut self,
+        params: &TelomereParameters,
+        rng: &mut R,
+    ) -> Option<ReplicativeCell> {
+        if !self.state.can_divide() {
+            return None;
+        }
+        let mut daughter = self.clone();
+        self.shorten(params, rng);
+        daughter.shorten(params, rng);
+        Some(daughter)
+    }
+}
+
+// Agent-based proliferating compartment with logistic contact inhibition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProliferatingPopulation {
+    pub params: TelomereParameters,
+    pub cells: Vec<ReplicativeCell>,
+    pub carrying_capacity: usize,
+    pub division_rate_per_day: f64,
+    pub senescent_clearance_per_day: f64,
+}
+
+impl ProliferatingPopulation {
+    pub fn new(
+        params: TelomereParameters,
+        initial_cells: usize,
+        carrying_capacity: usize,
+        division_rate_per_day: f64,
+    ) -> BiologyResult<Self> {
+        if initial_cells == 0 || carrying_capacity < initial_cells {
+            return Err(BiologyError::InvalidParameter(
+                "population must start non-empty and within carrying capacity".to_string(),
+            ));
+        }
+        if division_rate_per_day <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "division rate must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            params,
+            cells: vec![ReplicativeCell::new(params.initial_length_bp); initial_cells],
+            carrying_capacity,
+            division_rate_per_day,
+            senescent_clearance_per_day: 0.0,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn count_in_state(&self, state: CellState) -> usize {
+        self.cells.iter().filter(|c| c.state == state).count()
+    }
+
+    pub fn senescent_fraction(&self) -> f64 {
+        if self.cells.is_empty() {
+            return 0.0;
+        }
+        self.count_in_state(CellState::Senescent) as f64 / self.cells.len() as f64
+    }
+
+    pub fn mean_telomere_length_bp(&self) -> f64 {
+        if self.cells.is_empty() {
+            return 0.0;
+        }
+        self.cells.iter().map(|c| c.telomere_length_bp).sum::<f64>() / self.cells.len() as f64
+    }
+
+    pub fn mean_divisions(&self) -> f64 {
+        if self.cells.is_empty() {
+            return 0.0;
+        }
+        self.cells.iter().map(|c| c.divisions as f64).sum::<f64>() / self.cells.len() as f64
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        !self.cells.iter().any(|c| c.state.can_divide())
+    }
+
+    // Returns the number of cells that became senescent during the step.
+    pub fn step<R: Rng>(&mut self, dt_days: f64, rng: &mut R) -> usize {
+        let crowding = 1.0 - self.cells.len() as f64 / self.carrying_capacity as f64;
+        let p_divide = (self.division_rate_per_day * dt_days * crowding.max(0.0)).clamp(0.0, 1.0);
+        let p_clear = (self.senescent_clearance_per_day * dt_days).clamp(0.0, 1.0);
+
+        let mut newly_senescent = 0;
+        let mut free_slots = self.carrying_capacity.saturating_sub(self.cells.len());
+        let mut daughters = Vec::new();
+        for cell in self.cells.iter_mut() {
+            if free_slots == 0 {
+                break;
+            }
+            if !cell.state.can_divide() || rng.gen::<f64>() >= p_divide {
+                continue;
+            }
+            if let Some(daughter) = cell.divide(&self.params, rng) {
+                newly_senescent += usize::from(!cell.state.can_divide());
+                newly_senescent += usize::from(!daughter.state.can_divide());
+                daughters.push(daughter);
+                free_slots -= 1;
+            }
+        }
+        self.cells.extend(daughters);
+        if p_clear > 0.0 {
+            self.cells
+                .retain(|c| c.state != CellState::Senescent || rng.gen::<f64>() >= p_clear);
+        }
+        newly_senescent
+    }
+
+    // Serial passaging: split back to the seeding density once confluent.
+    pub fn passage<R: Rng>(&mut self, keep: usize, rng: &mut R) {
+        while self.cells.len() > keep.max(1) {
+            let idx = rng.gen_range(0..self.cells.len());
+            self.cells.swap_remove(idx);
+        }
+    }
+
+    // Newly senescent cells join the Karin burden once, with a fully
+    // senescent compartment mapped onto the death threshold.
+    pub fn feed_senescence(&self, burden: &mut SenescentCellBurden, newly_senescent: usize) {
+        if self.cells.is_empty() {
+            return;
+        }
+        let fraction = newly_senescent as f64 / self.cells.len() as f64;
+        burden.add_senescent_cells(fraction * burden.params.death_threshold);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aging::senescence::SenescenceParameters;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_expected_capacity_near_hayflick_limit() {
+        let capacity = TelomereParameters::human_fibroblast().expected_replicative_capacity();
+        assert!((40.0..=60.0).contains(&capacity));
+    }
+
+    #[test]
+    fn test_single_lineage_senesces() {
+        let params = TelomereParameters::default();
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut cell = ReplicativeCell::new(params.initial_length_bp);
+        while cell.divide(&params, &mut rng).is_some() {}
+        assert_eq!(cell.state, CellState::Senescent);
+        assert!(cell.divisions > 30 && cell.divisions < 80);
+        assert!(cell.divide(&params, &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_serial_passage_exhausts_culture() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut culture =
+            ProliferatingPopulation::new(TelomereParameters::default(), 50, 400, 1.0).unwrap();
+        let start_length = culture.mean_telomere_length_bp();
+        for _ in 0..400 {
+            culture.step(1.0, &mut rng);
+            if culture.len() >= 350 {
+                culture.passage(50, &mut rng);
+            }
+            if culture.is_exhausted() {
+                break;
+            }
+        }
+        assert!(culture.is_exhausted());
+        assert!(culture.senescent_fraction() > 0.9);
+        assert!(culture.mean_telomere_length_bp() < start_length - 3000.0);
+    }
+
+    #[test]
+    fn test_contact_inhibition_limits_size() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut tissue =
+            ProliferatingPopulation::new(TelomereParameters::default(), 20, 100, 1.0).unwrap();
+        for _ in 0..60 {
+            tissue.step(1.0, &mut rng);
+        }
+        assert!(tissue.len() <= 100);
+        assert!(tissue.mean_divisions() < 10.0);
+    }
+
+    #[test]
+    fn test_exhaustion_feeds_senescence_burden() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut tissue = ProliferatingPopulation::new(
+            TelomereParameters {
+                initial_length_bp: 4700.0,
+                ..TelomereParameters::default()
+            },
+            50,
+            200,
+            1.0,
+        )
+        .unwrap();
+        let mut burden = SenescentCellBurden::new(SenescenceParameters::default(), 1.0);
+        let mut control = burden.clone();
+        for _ in 0..10 {
+            let newly = tissue.step(1.0, &mut rng);
+            tissue.feed_senescence(&mut burden, newly);
+            burden.step(1.0);
+            control.step(1.0);
+        }
+        assert!(burden.burden > control.burden);
+
+        // Feeding adds cells once; it does not ratchet up production.
+        let production = control.damage_production_per_day;
+        let mut once = control.clone();
+        tissue.feed_senescence(&mut once, 20);
+        let mut twice = control.clone();
+        tissue.feed_senescence(&mut twice, 10);
+        tissue.feed_senescence(&mut twice, 10);
+        assert!((once.burden - twice.burden).abs() < 1e-12);
+        assert_eq!(once.damage_production_per_day, production);
+        once.step(1.0);
+        twice.step(1.0);
+        assert!((once.burden - twice.burden).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_population_rejected() {
+        let params = TelomereParameters::default();
+        assert!(ProliferatingPopulation::new(params, 0, 10, 1.0).is_err());
+        assert!(ProliferatingPopulation::new(params, 20, 10, 1.0).is_err());
+    }
+}
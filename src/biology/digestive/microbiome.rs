@@ -0,0 +1,341 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Colonic community as generalised Lotka-Volterra guilds,
+// dx_i/dt = x_i (r_i + Σ_j a_ij x_j) − k_i x_i + immigration, the form
+// fitted to antibiotic and C. difficile time series in mice.
+// Stein RR et al. (2013) PLoS Comput Biol 9:e1003388
+//
+// Biomass is relative to the total healthy load, with the healthy state
+// below as the stable equilibrium at reference fibre intake.
+const HEALTHY_BIOMASS: [f64; 5] = [0.45, 0.40, 0.08, 0.02, 0.0];
+const MAX_GROWTH_PER_DAY: [f64; 5] = [1.0, 0.8, 1.2, 1.5, 1.0];
+// Share of each guild's growth fuelled by dietary fibre; Proteobacteria
+// and C. difficile live on simple sugars, mucus and amino acids.
+const FIBRE_DEPENDENCE: [f64; 5] = [0.7, 0.8, 0.8, 0.0, 0.0];
+// Rows are the affected guild. Butyrate producers cross-feed on acetate
+// and lactate, and by keeping the epithelium hypoxic they starve
+// facultative Proteobacteria.
+// Byndloss MX et al. (2017) Science 357:570-575
+// Secondary bile acids from commensal Clostridia block C. difficile.
+// Buffie CG et al. (2015) Nature 517:205-208
+const INTERACTIONS: [[f64; 5]; 5] = [
+    [-1.902, -0.3, -0.3, 0.0, 0.0],
+    [0.2, -2.3, 0.5, -0.5, 0.0],
+    [-0.5, -0.5, -9.688, 0.0, 0.0],
+    [-0.5, -2.8, -1.0, -3.75, 0.0],
+    [-0.5, -2.5, -1.0, -0.5, -2.0],
+];
+// Reseeding from food and the environment keeps every guild from true
+// extinction; C. difficile arrives as ingested spores.
+const IMMIGRATION_PER_DAY: [f64; 5] = [1e-4, 1e-4, 1e-4, 1e-4, 1e-5];
+
+// Fermentation of ~40 g/day of fibre and resistant starch yields
+// ~400 mmol/day of SCFA at an acetate:propionate:butyrate ratio of
+// about 60:20:20.
+// Cummings JH et al. (1987) Gut 28:1221-1227
+const REFERENCE_FERMENTABLE_G_PER_DAY: f64 = 40.0;
+// mmol/day per unit biomass at reference fibre; `[acetate, propionate,
+// butyrate]` for each guild.
+const SCFA_YIELDS: [[f64; 3]; 5] = [
+    [300.0, 172.2, 0.0],
+    [0.0, 0.0, 200.0],
+    [1250.0, 0.0, 0.0],
+    [250.0, 125.0, 0.0],
+    [0.0, 0.0, 0.0],
+];
+const REFERENCE_SCFA_MMOL_PER_DAY: f64 = 400.0;
+const REFERENCE_BUTYRATE_MMOL_PER_DAY: f64 = 80.0;
+// SCFA acidify the colon and raise soluble calcium; inulin-type fructans
+// raise fractional calcium absorption by ~8%.
+// Abrams SA et al. (2005) Am J Clin Nutr 82:471-476
+const CALCIUM_ABSORPTION_PER_RELATIVE_SCFA: f64 = 0.1;
+// Butyrate induces colonic regulatory T cells; LPS from Proteobacteria
+// drives TLR4 signalling and metabolic endotoxaemia.
+// Furusawa Y et al. (2013) Nature 504:446-450
+// Cani PD et al. (2007) Diabetes 56:1761-1772
+const TOXIGENIC_C_DIFFICILE_BIOMASS: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MicrobialGuild {
+    // Bacteroidetes: polysaccharide degraders, acetate and propionate.
+    Bacteroidetes,
+    // Faecalibacterium, Roseburia and other butyrate-producing Firmicutes.
+    ButyrateProducers,
+    Bifidobacteria,
+    // Enterobacteriaceae and other LPS-bearing facultative anaerobes.
+    Proteobacteria,
+    ClostridioidesDifficile,
+}
+
+impl MicrobialGuild {
+    pub const ALL: [MicrobialGuild; 5] = [
+        MicrobialGuild::Bacteroidetes,
+        MicrobialGuild::ButyrateProducers,
+        MicrobialGuild::Bifidobacteria,
+        MicrobialGuild::Proteobacteria,
+        MicrobialGuild::ClostridioidesDifficile,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// Per-day kill rates during a course; spores make C. difficile immune.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AntibioticCourse {
+    pub kill_rate_per_day: [f64; 5],
+    pub days_remaining: f64,
+}
+
+impl AntibioticCourse {
+    pub fn new(kill_rate_per_day: [f64; 5], days: f64) -> BiologyResult<Self> {
+        if days.is_nan() || days <= 0.0 || kill_rate_per_day.iter().any(|k| k.is_nan() || *k < 0.0)
+        {
+            return Err(BiologyError::InvalidParameter(
+                "antibiotic course needs a positive duration and non-negative kill rates"
+                    .to_string(),
+            ));
+        }
+        Ok(Self {
+            kill_rate_per_day,
+            days_remaining: days,
+        })
+    }
+
+    // Clindamycin-like anti-anaerobe cover; the classic C. difficile risk.
+    pub fn broad_spectrum(days: f64) -> BiologyResult<Self> {
+        Self::new([3.0, 4.0, 3.0, 0.5, 0.0], days)
+    }
+
+    // Fluoroquinolone-like: strong against Enterobacteriaceae, weak
+    // against strict anaerobes.
+    pub fn gram_negative(days: f64) -> BiologyResult<Self> {
+        Self::new([0.5, 0.2, 0.2, 5.0, 0.0], days)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ShortChainFattyAcids {
+    pub acetate_mmol_per_day: f64,
+    pub propionate_mmol_per_day: f64,
+    pub butyrate_mmol_per_day: f64,
+}
+
+impl ShortChainFattyAcids {
+    pub fn total_mmol_per_day(&self) -> f64 {
+        self.acetate_mmol_per_day + self.propionate_mmol_per_day + self.butyrate_mmol_per_day
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GutMicrobiome {
+    pub biomass: [f64; 5],
+    pub fermentable_fibre_g_per_day: f64,
+    pub antibiotic: Option<AntibioticCourse>,
+    pub days: f64,
+}
+
+impl Default for GutMicrobiome {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+impl GutMicrobiome {
+    pub fn new_healthy() -> Self {
+        Self {
+            biomass: HEALTHY_BIOMASS,
+            fermentable_fibre_g_per_day: REFERENCE_FERMENTABLE_G_PER_DAY,
+            antibiotic: None,
+            days: 0.0,
+        }
+    }
+
+    pub fn set_fermentable_fibre(&mut self, g_per_day: f64) -> BiologyResult<()> {
+        if g_per_day.is_nan() || g_per_day < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "fibre intake must be non-negative".to_string(),
+            ));
+        }
+        self.fermentable_fibre_g_per_day = g_per_day;
+        Ok(())
+    }
+
+    pub fn start_antibiotic(&mut self, course: AntibioticCourse) {
+        self.antibiotic = Some(course);
+    }
+
+    pub fn abundance(&self, guild: MicrobialGuild) -> f64 {
+        self.biomass[guild.index()]
+    }
+
+    pub fn total_biomass(&self) -> f64 {
+        self.biomass.iter().sum()
+    }
+
+    pub fn relative_abundance(&self, guild: MicrobialGuild) -> f64 {
+        self.abundance(guild) / self.total_biomass().max(1e-12)
+    }
+
+    // Shannon diversity of the guild composition.
+    pub fn shannon_diversity(&self) -> f64 {
+        MicrobialGuild::ALL
+            .iter()
+            .map(|&g| self.relative_abundance(g))
+            .filter(|&p| p > 0.0)
+            .map(|p| -p * p.ln())
+            .sum()
+    }
+
+    fn fibre_ratio(&self) -> f64 {
+        self.fermentable_fibre_g_per_day / REFERENCE_FERMENTABLE_G_PER_DAY
+    }
+
+    fn growth_rate(&self, i: usize) -> f64 {
+        MAX_GROWTH_PER_DAY[i]
+            * (FIBRE_DEPENDENCE[i] * self.fibre_ratio() + 1.0 - FIBRE_DEPENDENCE[i])
+    }
+
+    pub fn short_chain_fatty_acids(&self) -> ShortChainFattyAcids {
+        let substrate = self.fibre_ratio();
+        let mut produced = [0.0; 3];
+        for (x, yields) in self.biomass.iter().zip(SCFA_YIELDS.iter()) {
+            for (total, y) in produced.iter_mut().zip(yields.iter()) {
+                *total += x * y * substrate;
+            }
+        }
+        ShortChainFattyAcids {
+            acetate_mmol_per_day: produced[0],
+            propionate_mmol_per_day: produced[1],
+            butyrate_mmol_per_day: produced[2],
+        }
+    }
+
+    // Multiplier on fractional intestinal calcium absorption, for
+    // `MineralHomeostasis::set_gut_calcium_absorption_factor`.
+    pub fn calcium_absorption_factor(&self) -> f64 {
+        let relative =
+            self.short_chain_fatty_acids().total_mmol_per_day() / REFERENCE_SCFA_MMOL_PER_DAY;
+        (1.0 + CALCIUM_ABSORPTION_PER_RELATIVE_SCFA * (relative - 1.0)).clamp(0.85, 1.3)
+    }
+
+    // Mucosal inflammatory drive, 0 for a healthy community; on the scale of
+    // `IronHomeostasis::inflammation`.
+    pub fn inflammatory_tone(&self) -> f64 {
+        let healthy_proteobacteria = HEALTHY_BIOMASS[MicrobialGuild::Proteobacteria.index()];
+        let endotoxin = (self.abundance(MicrobialGuild::Proteobacteria) / healthy_proteobacteria)
+            .sqrt()
+            + self.abundance(MicrobialGuild::ClostridioidesDifficile)
+                / TOXIGENIC_C_DIFFICILE_BIOMASS;
+        let regulatory = 0.5
+            + 0.5 * self.short_chain_fatty_acids().butyrate_mmol_per_day
+                / REFERENCE_BUTYRATE_MMOL_PER_DAY;
+        (endotoxin / regulatory - 1.0).max(0.0)
+    }
+
+    pub fn step(&mut self, dt_days: f64) {
+        let kill = self
+            .antibiotic
+            .map(|a| a.kill_rate_per_day)
+            .unwrap_or([0.0; 5]);
+        let current = self.biomass;
+        for i in 0..current.len() {
+            let interaction: f64 = INTERACTIONS[i]
+                .iter()
+                .zip(current.iter())
+                .map(|(a, x)| a * x)
+                .sum();
+            let rate = self.growth_rate(i) + interaction - kill[i];
+            self.biomass[i] =
+                (current[i] + (current[i] * rate + IMMIGRATION_PER_DAY[i]) * dt_days).max(0.0);
+        }
+        if let Some(course) = self.antibiotic.as_mut() {
+            course.days_remaining -= dt_days;
+            if course.days_remaining <= 1e-9 {
+                self.antibiotic = None;
+            }
+        }
+        self.days += dt_days;
+    }
+
+    pub fn run_days(&mut self, days: f64) {
+        let steps = (days / 0.01).round() as usize;
+        for _ in 0..steps {
+            self.step(0.01);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metabolism::MineralHomeostasis;
+
+    #[test]
+    fn test_healthy_community_is_stable() {
+        let mut gut = GutMicrobiome::new_healthy();
+        let scfa = gut.short_chain_fatty_acids();
+        assert!((scfa.total_mmol_per_day() - 400.0).abs() < 1.0);
+        assert!((scfa.acetate_mmol_per_day / scfa.total_mmol_per_day() - 0.6).abs() < 0.01);
+        gut.run_days(60.0);
+        for guild in MicrobialGuild::ALL {
+            let healthy = HEALTHY_BIOMASS[guild.index()];
+            assert!((gut.abundance(guild) - healthy).abs() < 0.01, "{guild:?}");
+        }
+        assert!(gut.abundance(MicrobialGuild::ClostridioidesDifficile) < 1e-3);
+        assert!(gut.inflammatory_tone() < 0.05);
+        assert!((gut.calcium_absorption_factor() - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_broad_spectrum_antibiotic_opens_niche_for_c_difficile() {
+        let mut gut = GutMicrobiome::new_healthy();
+        let diversity = gut.shannon_diversity();
+        gut.start_antibiotic(AntibioticCourse::broad_spectrum(7.0).unwrap());
+        gut.run_days(7.0);
+        assert!(gut.antibiotic.is_none());
+        assert!(gut.short_chain_fatty_acids().butyrate_mmol_per_day < 1.0);
+        assert!(gut.calcium_absorption_factor() < 0.95);
+        gut.run_days(7.0);
+        assert!(gut.abundance(MicrobialGuild::ClostridioidesDifficile) > 0.05);
+        assert!(gut.inflammatory_tone() > 0.5);
+        // Commensals regrow from reseeding and push C. difficile back out.
+        gut.run_days(60.0);
+        assert!(gut.abundance(MicrobialGuild::ClostridioidesDifficile) < 0.01);
+        assert!((gut.shannon_diversity() - diversity).abs() < 0.1);
+        assert!(AntibioticCourse::broad_spectrum(0.0).is_err());
+    }
+
+    #[test]
+    fn test_gram_negative_course_spares_butyrate() {
+        let mut gut = GutMicrobiome::new_healthy();
+        gut.start_antibiotic(AntibioticCourse::gram_negative(5.0).unwrap());
+        gut.run_days(5.0);
+        assert!(gut.abundance(MicrobialGuild::Proteobacteria) < 1e-3);
+        assert!(gut.short_chain_fatty_acids().butyrate_mmol_per_day > 60.0);
+        assert_eq!(gut.inflammatory_tone(), 0.0);
+    }
+
+    #[test]
+    fn test_fibre_feeds_butyrate_and_calcium_absorption() {
+        let mut gut = GutMicrobiome::new_healthy();
+        gut.set_fermentable_fibre(60.0).unwrap();
+        gut.run_days(30.0);
+        assert!(gut.short_chain_fatty_acids().butyrate_mmol_per_day > 1.5 * 80.0);
+        let factor = gut.calcium_absorption_factor();
+        assert!(factor > 1.05);
+
+        let mut control = MineralHomeostasis::new_healthy();
+        let mut prebiotic = MineralHomeostasis::new_healthy();
+        prebiotic.set_gut_calcium_absorption_factor(factor).unwrap();
+        control.run_days(1.0);
+        prebiotic.run_days(1.0);
+        assert!(
+            prebiotic.fluxes.gut_calcium_absorbed_mg_per_day
+                > control.fluxes.gut_calcium_absorbed_mg_per_day
+        );
+        assert!(gut.set_fermentable_fibre(-1.0).is_err());
+    }
+}
@@ -0,0 +1,10 @@
+pub mod microbiome;
+pub mod oral_absorption;
+pub mod transit;
+
+pub use microbiome::{AntibioticCourse, GutMicrobiome, MicrobialGuild, ShortChainFattyAcids};
+pub use oral_absorption::{
+    simulate_oral_dose, Ionization, OralAbsorptionModel, OralAbsorptionSummary, OralFormulation,
+    ReleaseMechanism,
+};
+pub use transit::{FedState, GiCompartment, GiSegment, GiTract};
@@ -0,0 +1,424 @@
+use crate::biology::digestive::transit::{GiSegment, GiTract};
+use crate::biology::{BiologyError, BiologyResult};
+use crate::pharmacology::pharmacokinetics::Pharmacokinetics;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Ionization {
+    Neutral,
+    WeakAcid { pka: f64 },
+    WeakBase { pka: f64 },
+}
+
+impl Ionization {
+    // Henderson-Hasselbalch total solubility over intrinsic solubility,
+    // capped where salt solubility takes over.
+    pub fn solubility_factor(&self, ph: f64) -> f64 {
+        let factor = match self {
+            Ionization::Neutral => 1.0,
+            Ionization::WeakAcid { pka } => 1.0 + 10f64.powf(ph - pka),
+            Ionization::WeakBase { pka } => 1.0 + 10f64.powf(pka - ph),
+        };
+        factor.min(1e4)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReleaseMechanism {
+    Solution,
+    ImmediateRelease,
+    // Methacrylate coats (e.g. Eudragit L100-55) dissolve above pH 5.5.
+    EntericCoated,
+    ExtendedRelease { release_half_life_h: f64 },
+}
+
+const ENTERIC_COAT_DISSOLUTION_PH: f64 = 5.5;
+// Per-compartment ka per unit Peff (1e-4 cm/s), matching the CAT estimate
+// fa = 1 − (1 + 0.54·Peff)^−7 over a 3.3 h small-intestinal transit.
+const ABSORPTION_RATE_PER_PEFF_PER_H: f64 = 1.14;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OralFormulation {
+    pub name: String,
+    pub dose_mg: f64,
+    pub release: ReleaseMechanism,
+    pub ionization: Ionization,
+    pub intrinsic_solubility_mg_ml: f64,
+    pub dissolution_rate_per_h: f64,
+    pub effective_permeability_1e4_cm_s: f64,
+    // At pH 1.5; falls tenfold per pH unit above it.
+    pub acid_degradation_per_h: f64,
+    // Luminal proteases/peptidases beyond the stomach.
+    pub protease_degradation_per_h: f64,
+    // Transcytosis by M cells over Peyer's patches.
+    pub m_cell_uptake_per_h: f64,
+}
+
+impl OralFormulation {
+    pub fn validate(&self) -> BiologyResult<()> {
+        let rates = [
+            self.dose_mg,
+            self.intrinsic_solubility_mg_ml,
+            self.dissolution_rate_per_h,
+            self.effective_permeability_1e4_cm_s,
+            self.acid_degradation_per_h,
+            self.protease_degradation_per_h,
+            self.m_cell_uptake_per_h,
+        ];
+        if rates.iter().any(|r| *r < 0.0 || !r.is_finite()) {
+            return Err(BiologyError::InvalidParameter(
+                "formulation rates and amounts must be finite and non-negative".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // BCS class I: freely soluble, Peff 1.34e-4 cm/s, fa > 0.9.
+    // Lennernäs H (2007) Xenobiotica 37:1015-1051, PMID 17968735
+    pub fn metoprolol_immediate_release() -> Self {
+        Self {
+            name: "metoprolol IR".to_string(),
+            dose_mg: 100.0,
+            release: ReleaseMechanism::ImmediateRelease,
+            ionization: Ionization::Neutral,
+            intrinsic_solubility_mg_ml: 100.0,
+            dissolution_rate_per_h: 4.0,
+            effective_permeability_1e4_cm_s: 1.34,
+            acid_degradation_per_h: 0.0,
+            protease_degradation_per_h: 0.0,
+            m_cell_uptake_per_h: 0.0,
+        }
+    }
+
+    // Weak base that needs gastric acid to dissolve; acid suppression cuts
+    // its exposure by most of the dose.
+    // Chin TW et al. (1995) Antimicrob Agents Chemother 39:1671-1675, PMID 7486898
+    pub fn ketoconazole_tablet() -> Self {
+        Self {
+            name: "ketoconazole".to_string(),
+            dose_mg: 200.0,
+            release: ReleaseMechanism::ImmediateRelease,
+            ionization: Ionization::WeakBase { pka: 6.5 },
+            intrinsic_solubility_mg_ml: 0.017,
+            dissolution_rate_per_h: 3.0,
+            effective_permeability_1e4_cm_s: 2.0,
+            acid_degradation_per_h: 0.0,
+            protease_degradation_per_h: 0.0,
+            m_cell_uptake_per_h: 0.0,
+        }
+    }
+
+    // Acid-labile benzimidazole, degraded within minutes at gastric pH, so it
+    // is given as enteric-coated pellets.
+    // Pilbrant A, Cederberg C (1985) Scand J Gastroenterol Suppl 108:113-120, PMID 3858969
+    pub fn omeprazole(release: ReleaseMechanism) -> Self {
+        Self {
+            name: "omeprazole".to_string(),
+            dose_mg: 20.0,
+            release,
+            ionization: Ionization::Neutral,
+            intrinsic_solubility_mg_ml: 0.5,
+            dissolution_rate_per_h: 4.0,
+            effective_permeability_1e4_cm_s: 2.0,
+            acid_degradation_per_h: 20.0,
+            protease_degradation_per_h: 0.0,
+            m_cell_uptake_per_h: 0.0,
+        }
+    }
+
+    // Protein subunit antigen: denatured by acid, digested by pancreatic
+    // proteases, and sampled by ileal M cells.
+    // Woodley JF (1994) Crit Rev Ther Drug Carrier Syst 11:61-95, PMID 7704918
+    // Neutra MR, Kozlowski PA (2006) Nat Rev Immunol 6:148-158, PMID 16491139
+    pub fn protein_antigen(release: ReleaseMechanism) -> Self {
+        Self {
+            name: "protein antigen".to_string(),
+            dose_mg: 1.0,
+            release,
+            ionization: Ionization::Neutral,
+            intrinsic_solubility_mg_ml: 10.0,
+            dissolution_rate_per_h: 2.0,
+            effective_permeability_1e4_cm_s: 0.0,
+            acid_degradation_per_h: 6.0,
+            protease_degradation_per_h: 1.5,
+            m_cell_uptake_per_h: 0.05,
+        }
+    }
+
+    fn absorption_rate_per_h(&self) -> f64 {
+        ABSORPTION_RATE_PER_PEFF_PER_H * self.effective_permeability_1e4_cm_s
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OralAbsorptionSummary {
+    pub fraction_absorbed: f64,
+    pub fraction_degraded: f64,
+    pub mucosal_uptake_fraction: f64,
+    pub fraction_excreted: f64,
+    pub mean_absorption_time_h: f64,
+}
+
+// Tracks undissolved and dissolved dose in every GI compartment: release
+// and pH-dependent dissolution, luminal degradation, absorption from
+// dissolved drug, M-cell sampling in the ileum, and transit downstream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OralAbsorptionModel {
+    pub tract: GiTract,
+    pub formulation: OralFormulation,
+    pub solid_mg: Vec<f64>,
+    pub dissolved_mg: Vec<f64>,
+    pub absorbed_mg: f64,
+    pub degraded_mg: f64,
+    pub mucosal_uptake_mg: f64,
+    pub excreted_mg: f64,
+    pub time_h: f64,
+    absorption_first_moment: f64,
+}
+
+impl OralAbsorptionModel {
+    pub fn new(tract: GiTract, formulation: OralFormulation) -> BiologyResult<Self> {
+        formulation.validate()?;
+        let n = tract.compartments.len();
+        let mut solid_mg = vec![0.0; n];
+        let mut dissolved_mg = vec![0.0; n];
+        match formulation.release {
+            ReleaseMechanism::Solution => dissolved_mg[0] = formulation.dose_mg,
+            _ => solid_mg[0] = formulation.dose_mg,
+        }
+        Ok(Self {
+            tract,
+            formulation,
+            solid_mg,
+            dissolved_mg,
+            absorbed_mg: 0.0,
+            degraded_mg: 0.0,
+            mucosal_uptake_mg: 0.0,
+            excreted_mg: 0.0,
+            time_h: 0.0,
+            absorption_first_moment: 0.0,
+        })
+    }
+
+    // Noyes-Whitney: the rate constant applies at sink conditions and scales
+    // with the remaining solubility headroom relative to the dose.
+    fn dissolution_rate(&self, i: usize) -> f64 {
+        let f = &self.formulation;
+        let c = &self.tract.compartments[i];
+        let k = match f.release {
+            ReleaseMechanism::Solution | ReleaseMechanism::ImmediateRelease => {
+                f.dissolution_rate_per_h
+            }
+            ReleaseMechanism::EntericCoated if c.ph < ENTERIC_COAT_DISSOLUTION_PH => 0.0,
+            ReleaseMechanism::EntericCoated => f.dissolution_rate_per_h,
+            ReleaseMechanism::ExtendedRelease {
+                release_half_life_h,
+            } => std::f64::consts::LN_2 / release_half_life_h.max(1e-6),
+        };
+        let capacity =
+            f.intrinsic_solubility_mg_ml * f.ionization.solubility_factor(c.ph) * c.volume_ml;
+        let headroom = (capacity - self.dissolved_mg[i]) / f.dose_mg.max(1e-12);
+        k * self.solid_mg[i] * headroom.clamp(0.0, 1.0)
+    }
+
+    pub fn step(&mut self, dt_h: f64) {
+        let f = self.formulation.clone();
+        let ka = f.absorption_rate_per_h();
+        let n = self.tract.compartments.len();
+        let mut inflow_solid = 0.0;
+        let mut inflow_dissolved = 0.0;
+        for i in 0..n {
+            let c = self.tract.compartments[i];
+            let dissolution = self.dissolution_rate(i) * dt_h;
+            let acid = f.acid_degradation_per_h * 10f64.powf(-(c.ph - 1.5).max(0.0));
+            let protease = if c.segment == GiSegment::Stomach {
+                0.0
+            } else {
+                f.protease_degradation_per_h
+            };
+            let m_cell = if c.segment.has_peyers_patches() {
+                f.m_cell_uptake_per_h
+            } else {
+                0.0
+            };
+            let absorb = ka * c.absorptive_scale;
+            let transit = c.transit_rate_per_h;
+
+            // Coated solids are protected; exposed solids and solution are not.
+            let solid_exposed = !matches!(f.release, ReleaseMechanism::EntericCoated);
+            let solid = self.solid_mg[i];
+            let solid_loss = if solid_exposed {
+                acid * solid * dt_h
+            } else {
+                0.0
+            };
+            let solid_out = transit * solid * dt_h;
+
+            let dissolved = self.dissolved_mg[i];
+            let degraded = (acid + protease) * dissolved * dt_h;
+            let absorbed = absorb * dissolved * dt_h;
+            let sampled = m_cell * dissolved * dt_h;
+            let dissolved_out = transit * dissolved * dt_h;
+
+            self.solid_mg[i] =
+                (solid + inflow_solid - dissolution - solid_loss - solid_out).max(0.0);
+            self.dissolved_mg[i] = (dissolved + inflow_dissolved + dissolution
+                - degraded
+                - absorbed
+                - sampled
+                - dissolved_out)
+                .max(0.0);
+            self.degraded_mg += solid_loss + degraded;
+            self.absorbed_mg += absorbed;
+            self.mucosal_uptake_mg += sampled;
+            self.absorption_first_moment += (self.time_h + 0.5 * dt_h) * absorbed;
+            inflow_solid = solid_out;
+            inflow_dissolved = dissolved_out;
+        }
+        self.excreted_mg += inflow_solid + inflow_dissolved;
+        self.time_h += dt_h;
+    }
+
+    pub fn run_hours(&mut self, hours: f64) {
+        let dt = 0.01;
+        let steps = (hours / dt).round() as usize;
+        for _ in 0..steps {
+            self.step(dt);
+        }
+    }
+
+    pub fn summary(&self) -> OralAbsorptionSummary {
+        let dose = self.formulation.dose_mg.max(1e-12);
+        OralAbsorptionSummary {
+            fraction_absorbed: self.absorbed_mg / dose,
+            fraction_degraded: self.degraded_mg / dose,
+            mucosal_uptake_fraction: self.mucosal_uptake_mg / dose,
+            fraction_excreted: self.excreted_mg / dose,
+            mean_absorption_time_h: self.absorption_first_moment / self.absorbed_mg.max(1e-12),
+        }
+    }
+
+    // Oral route: Fa scales bioavailability after gut-wall and hepatic
+    // losses already in `pk`; tmax follows from ka = 1/MAT and k.
+    pub fn apply_to(&self, pk: &Pharmacokinetics) -> Pharmacokinetics {
+        let summary = self.summary();
+        let mut adjusted = pk.clone();
+        adjusted.absorption.bioavailability =
+            (pk.absorption.bioavailability * summary.fraction_absorbed).clamp(0.0, 1.0);
+        let ka = 1.0 / summary.mean_absorption_time_h.max(1e-6);
+        let k = pk.elimination_constant();
+        if (ka - k).abs() > 1e-9 {
+            adjusted.absorption.time_to_peak_hours = (ka / k).ln() / (ka - k);
+        }
+        adjusted
+    }
+}
+
+// Runs a formulation through a tract until it has cleared (48 h).
+pub fn simulate_oral_dose(
+    tract: GiTract,
+    formulation: OralFormulation,
+) -> BiologyResult<OralAbsorptionModel> {
+    let mut model = OralAbsorptionModel::new(tract, formulation)?;
+    model.run_hours(48.0);
+    Ok(model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::digestive::transit::FedState;
+
+    fn fasted() -> GiTract {
+        GiTract::new(FedState::Fasted)
+    }
+
+    #[test]
+    fn test_highly_permeable_drug_is_almost_fully_absorbed() {
+        let model =
+            simulate_oral_dose(fasted(), OralFormulation::metoprolol_immediate_release()).unwrap();
+        let s = model.summary();
+        assert!(s.fraction_absorbed > 0.9, "fa {}", s.fraction_absorbed);
+        assert!(s.mean_absorption_time_h > 0.5 && s.mean_absorption_time_h < 3.0);
+        let mass = s.fraction_absorbed + s.fraction_degraded + s.fraction_excreted;
+        let remaining: f64 = model
+            .solid_mg
+            .iter()
+            .chain(&model.dissolved_mg)
+            .sum::<f64>()
+            / 100.0;
+        assert!((mass + remaining - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_acid_suppression_impairs_weak_base_absorption() {
+        let normal = simulate_oral_dose(fasted(), OralFormulation::ketoconazole_tablet())
+            .unwrap()
+            .summary();
+        let achlorhydric = simulate_oral_dose(
+            fasted().with_gastric_ph(6.0),
+            OralFormulation::ketoconazole_tablet(),
+        )
+        .unwrap()
+        .summary();
+        assert!(achlorhydric.fraction_absorbed < 0.5 * normal.fraction_absorbed);
+    }
+
+    #[test]
+    fn test_enteric_coating_protects_acid_labile_drug() {
+        let plain = simulate_oral_dose(
+            fasted(),
+            OralFormulation::omeprazole(ReleaseMechanism::ImmediateRelease),
+        )
+        .unwrap()
+        .summary();
+        let coated = simulate_oral_dose(
+            fasted(),
+            OralFormulation::omeprazole(ReleaseMechanism::EntericCoated),
+        )
+        .unwrap()
+        .summary();
+        assert!(plain.fraction_degraded > 0.5);
+        assert!(coated.fraction_absorbed > 2.0 * plain.fraction_absorbed);
+    }
+
+    #[test]
+    fn test_oral_antigen_reaches_peyers_patches_only_when_protected() {
+        let solution = simulate_oral_dose(
+            fasted(),
+            OralFormulation::protein_antigen(ReleaseMechanism::Solution),
+        )
+        .unwrap()
+        .summary();
+        let coated = simulate_oral_dose(
+            fasted(),
+            OralFormulation::protein_antigen(ReleaseMechanism::EntericCoated),
+        )
+        .unwrap()
+        .summary();
+        assert!(solution.fraction_degraded > 0.9);
+        assert!(coated.mucosal_uptake_fraction > 5.0 * solution.mucosal_uptake_fraction);
+        assert_eq!(coated.fraction_absorbed, 0.0);
+    }
+
+    #[test]
+    fn test_absorption_feeds_pharmacokinetic_oral_route() {
+        let pk = Pharmacokinetics::new(0.5, 3.5, 290.0);
+        let ir = simulate_oral_dose(fasted(), OralFormulation::metoprolol_immediate_release())
+            .unwrap()
+            .apply_to(&pk);
+        let mut er_formulation = OralFormulation::metoprolol_immediate_release();
+        er_formulation.release = ReleaseMechanism::ExtendedRelease {
+            release_half_life_h: 6.0,
+        };
+        let er = simulate_oral_dose(fasted(), er_formulation)
+            .unwrap()
+            .apply_to(&pk);
+        assert!(ir.absorption.bioavailability > 0.45 && ir.absorption.bioavailability <= 0.5);
+        assert!(er.absorption.time_to_peak_hours > ir.absorption.time_to_peak_hours);
+        assert!(er.absorption.bioavailability < ir.absorption.bioavailability);
+        let mut bad = OralFormulation::metoprolol_immediate_release();
+        bad.dose_mg = -1.0;
+        assert!(OralAbsorptionModel::new(fasted(), bad).is_err());
+    }
+}
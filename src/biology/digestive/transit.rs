@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GiSegment {
+    Stomach,
+    Duodenum,
+    Jejunum,
+    Ileum,
+    Colon,
+}
+
+impl GiSegment {
+    // Peyer's patches and their M cells cluster in the distal ileum.
+    pub fn has_peyers_patches(&self) -> bool {
+        matches!(self, GiSegment::Ileum)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FedState {
+    Fasted,
+    Fed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GiCompartment {
+    pub segment: GiSegment,
+    pub ph: f64,
+    pub volume_ml: f64,
+    // First-order outflow to the next compartment.
+    pub transit_rate_per_h: f64,
+    // Absorptive surface relative to a small-intestinal compartment.
+    pub absorptive_scale: f64,
+}
+
+// Compartmental absorption and transit (CAT): stomach, seven equal
+// small-intestinal compartments and the colon.
+// Yu LX, Amidon GL (1999) Int J Pharm 186:119-125, PMID 10486429
+pub const SMALL_INTESTINE_COMPARTMENTS: usize = 7;
+// Mean small-intestinal transit 3.3 h, largely independent of meals.
+// Yu LX et al. (1996) Int J Pharm 140:111-118
+const SMALL_INTESTINE_TRANSIT_H: f64 = 3.32;
+// Liquids leave the fasted stomach with t½ ~15 min; a solid meal ~90 min.
+// Camilleri M et al. (2013) Neurogastroenterol Motil 25:733-739, PMID 23957723
+const FASTED_GASTRIC_HALF_TIME_MIN: f64 = 15.0;
+const FED_GASTRIC_HALF_TIME_MIN: f64 = 90.0;
+const COLON_TRANSIT_H: f64 = 35.0;
+// Luminal pH: stomach 1.5 fasted / ~5 after a meal, duodenum ~6, rising to
+// ~7.4 in the terminal ileum, colon ~6.5.
+// Evans DF et al. (1988) Gut 29:1035-1041, PMID 3410329
+const FASTED_GASTRIC_PH: f64 = 1.5;
+const FED_GASTRIC_PH: f64 = 5.0;
+const SMALL_INTESTINE_PH: [f64; SMALL_INTESTINE_COMPARTMENTS] = [6.0, 6.3, 6.6, 6.9, 7.1, 7.3, 7.4];
+const COLON_PH: f64 = 6.5;
+// Free luminal water is scarce: ~105 mL over the whole small bowel and ~13 mL
+// in the colon, in pockets.
+// Schiller C et al. (2005) Aliment Pharmacol Ther 22:971-979, PMID 16268972
+const SMALL_INTESTINE_COMPARTMENT_VOLUME_ML: f64 = 15.0;
+const COLON_VOLUME_ML: f64 = 13.0;
+// Colonic absorption is roughly an order of magnitude below the small bowel.
+const COLON_ABSORPTIVE_SCALE: f64 = 0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GiTract {
+    pub fed_state: FedState,
+    pub compartments: Vec<GiCompartment>,
+}
+
+impl GiTract {
+    pub fn new(fed_state: FedState) -> Self {
+        let (gastric_half_time, gastric_ph, gastric_volume) = match fed_state {
+            FedState::Fasted => (FASTED_GASTRIC_HALF_TIME_MIN, FASTED_GASTRIC_PH, 250.0),
+            FedState::Fed => (FED_GASTRIC_HALF_TIME_MIN, FED_GASTRIC_PH, 700.0),
+        };
+        let mut compartments = vec![GiCompartment {
+            segment: GiSegment::Stomach,
+            ph: gastric_ph,
+            volume_ml: gastric_volume,
+            transit_rate_per_h: std::f64::consts::LN_2 / (gastric_half_time / 60.0),
+            absorptive_scale: 0.0,
+        }];
+        let si_rate = SMALL_INTESTINE_COMPARTMENTS as f64 / SMALL_INTESTINE_TRANSIT_H;
+        for (i, ph) in SMALL_INTESTINE_PH.iter().enumerate() {
+            let segment = match i {
+                0 => GiSegment::Duodenum,
+                1..=3 => GiSegment::Jejunum,
+                _ => GiSegment::Ileum,
+            };
+            compartments.push(GiCompartment {
+                segment,
+                ph: *ph,
+                volume_ml: SMALL_INTESTINE_COMPARTMENT_VOLUME_ML,
+                transit_rate_per_h: si_rate,
+                absorptive_scale: 1.0,
+            });
+        }
+        compartments.push(GiCompartment {
+            segment: GiSegment::Colon,
+            ph: COLON_PH,
+            volume_ml: COLON_VOLUME_ML,
+            transit_rate_per_h: 1.0 / COLON_TRANSIT_H,
+            absorptive_scale: COLON_ABSORPTIVE_SCALE,
+        });
+        Self {
+            fed_state,
+            compartments,
+        }
+    }
+
+    // Proton-pump inhibition or atrophic gastritis raises gastric pH.
+    pub fn with_gastric_ph(mut self, ph: f64) -> Self {
+        self.compartments[0].ph = ph.clamp(0.5, 8.0);
+        self
+    }
+
+    pub fn gastric_emptying_half_time_min(&self) -> f64 {
+        std::f64::consts::LN_2 / self.compartments[0].transit_rate_per_h * 60.0
+    }
+
+    pub fn small_intestine_transit_h(&self) -> f64 {
+        self.compartments
+            .iter()
+            .filter(|c| !matches!(c.segment, GiSegment::Stomach | GiSegment::Colon))
+            .map(|c| 1.0 / c.transit_rate_per_h)
+            .sum()
+    }
+
+    pub fn colon_transit_h(&self) -> f64 {
+        self.compartments
+            .last()
+            .map_or(0.0, |c| 1.0 / c.transit_rate_per_h)
+    }
+}
+
+impl Default for GiTract {
+    fn default() -> Self {
+        Self::new(FedState::Fasted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::ground_truth::GroundTruthDatabase;
+
+    #[test]
+    fn test_transit_times_match_reference() {
+        let db = GroundTruthDatabase::new();
+        let gi = db.get_dataset("gastrointestinal").unwrap();
+        let fed = GiTract::new(FedState::Fed);
+        assert!(gi.is_within_expected_range(
+            "gastric_emptying_half_time_min",
+            fed.gastric_emptying_half_time_min()
+        ));
+        assert!(gi.is_within_expected_range(
+            "small_intestine_transit_time_hours",
+            fed.small_intestine_transit_h()
+        ));
+        assert!(gi.is_within_expected_range("colonic_transit_time_hours", fed.colon_transit_h()));
+        let fasted = GiTract::new(FedState::Fasted);
+        assert!(gi.is_within_expected_range("gastric_acid_ph", fasted.compartments[0].ph));
+    }
+
+    #[test]
+    fn test_ph_gradient_and_peyers_patches() {
+        let tract = GiTract::default();
+        let si: Vec<&GiCompartment> = tract.compartments[1..=SMALL_INTESTINE_COMPARTMENTS]
+            .iter()
+            .collect();
+        assert!(si.windows(2).all(|w| w[1].ph >= w[0].ph));
+        assert!(si.last().unwrap().segment.has_peyers_patches());
+        assert!(!GiSegment::Duodenum.has_peyers_patches());
+        assert_eq!(tract.with_gastric_ph(6.0).compartments[0].ph, 6.0);
+    }
+}
@@ -0,0 +1,224 @@
+use super::hormone::{normalized_stimulation, normalized_suppression, Hormone, HormonePool};
+use super::signals::{
+    EndocrineSignals, FeedbackAxis, REFERENCE_CALCITONIN_PG_ML, REFERENCE_CALCITRIOL_PG_ML,
+    REFERENCE_FGF23_PG_ML, REFERENCE_PTH_PG_ML,
+};
+use super::vitamin_d::REFERENCE_CALCIDIOL_NMOL_L;
+use serde::{Deserialize, Serialize};
+
+// Serum ionized calcium 1.15-1.30 mmol/L, phosphate 2.5-4.5 mg/dL.
+// Boron & Boulpaep, Medical Physiology 3e, Ch 52
+pub const REFERENCE_IONIZED_CALCIUM_MMOL_L: f64 = 1.25;
+pub const REFERENCE_PHOSPHATE_MG_DL: f64 = 3.5;
+
+// Inverse sigmoidal PTH release vs ionized calcium, as the four-parameter
+// Brown model: (A − D)/(1 + (Ca/S)^m) + D, normalised to 1 at the reference
+// calcium. Normocalcaemia sits just above the set point on the low-secretion
+// shoulder, leaving several-fold reserve for hypocalcaemia.
+// Brown EM (1983) J Clin Endocrinol Metab 56:572-581, PMID 6827467
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ParathyroidSetPoint {
+    pub max_secretion: f64,
+    pub min_secretion: f64,
+    pub set_point_mmol_l: f64,
+    pub slope: f64,
+    pub gland_mass: f64,
+}
+
+impl ParathyroidSetPoint {
+    pub fn normal() -> Self {
+        Self {
+            max_secretion: 8.0,
+            min_secretion: 0.2,
+            set_point_mmol_l: 1.2,
+            slope: 20.0,
+            gland_mass: 1.0,
+        }
+    }
+
+    pub fn relative_secretion(&self, ionized_calcium_mmol_l: f64) -> f64 {
+        let raw = |ca: f64| {
+            (self.max_secretion - self.min_secretion)
+                / (1.0 + (ca.max(0.0) / self.set_point_mmol_l).powf(self.slope))
+                + self.min_secretion
+        };
+        self.gland_mass * raw(ionized_calcium_mmol_l) / raw(REFERENCE_IONIZED_CALCIUM_MMOL_L)
+    }
+}
+
+impl Default for ParathyroidSetPoint {
+    fn default() -> Self {
+        Self::normal()
+    }
+}
+
+// Calcium-sensing parathyroid and C cells, renal 1α-hydroxylase under
+// PTH (stimulatory) and FGF23/phosphate (inhibitory) control and limited by
+// 25(OH)D supply, and osteocyte
+// FGF23 induced by calcitriol and phosphate.
+// Blaine J, Chonchol M, Levi M (2015) Clin J Am Soc Nephrol 10:1257, PMID 25287933
+// Quarles LD (2012) Nat Rev Endocrinol 8:276-286, PMID 22249518
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalciumRegulatoryAxis {
+    pub pth: HormonePool,
+    pub calcitriol: HormonePool,
+    pub calcitonin: HormonePool,
+    pub fgf23: HormonePool,
+    pub parathyroid: ParathyroidSetPoint,
+    pub ionized_calcium_mmol_l: f64,
+    pub phosphate_mg_dl: f64,
+    // 25(OH)D substrate for the 1α-hydroxylase.
+    pub calcidiol_nmol_l: f64,
+    pub renal_hydroxylase_capacity: f64,
+    pub fgf23_production_factor: f64,
+}
+
+impl CalciumRegulatoryAxis {
+    pub fn new_healthy() -> Self {
+        Self {
+            pth: HormonePool::new(Hormone::Pth, REFERENCE_PTH_PG_ML).expect("valid preset"),
+            calcitriol: HormonePool::new(Hormone::Calcitriol, REFERENCE_CALCITRIOL_PG_ML)
+                .expect("valid preset"),
+            calcitonin: HormonePool::new(Hormone::Calcitonin, REFERENCE_CALCITONIN_PG_ML)
+                .expect("valid preset"),
+            fgf23: HormonePool::new(Hormone::Fgf23, REFERENCE_FGF23_PG_ML).expect("valid preset"),
+            parathyroid: ParathyroidSetPoint::normal(),
+            ionized_calcium_mmol_l: REFERENCE_IONIZED_CALCIUM_MMOL_L,
+            phosphate_mg_dl: REFERENCE_PHOSPHATE_MG_DL,
+            calcidiol_nmol_l: REFERENCE_CALCIDIOL_NMOL_L,
+            renal_hydroxylase_capacity: 1.0,
+            fgf23_production_factor: 1.0,
+        }
+    }
+
+    pub fn set_serum_minerals(&mut self, ionized_calcium_mmol_l: f64, phosphate_mg_dl: f64) {
+        self.ionized_calcium_mmol_l = ionized_calcium_mmol_l.max(0.0);
+        self.phosphate_mg_dl = phosphate_mg_dl.max(0.0);
+    }
+
+    pub fn set_calcidiol(&mut self, nmol_l: f64) {
+        self.calcidiol_nmol_l = nmol_l.max(0.0);
+    }
+
+    // Osteocyte overproduction, e.g. X-linked hypophosphataemia or
+    // tumour-induced osteomalacia.
+    pub fn set_fgf23_production_factor(&mut self, factor: f64) {
+        self.fgf23_production_factor = factor.max(0.0);
+    }
+
+    pub fn fgf23_pg_ml(&self) -> f64 {
+        self.fgf23.concentration
+    }
+}
+
+impl Default for CalciumRegulatoryAxis {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+impl FeedbackAxis for CalciumRegulatoryAxis {
+    fn step(&mut self, dt_hours: f64) {
+        let ca = self.ionized_calcium_mmol_l;
+        // Calcitriol suppresses PTH gene transcription via the parathyroid VDR;
+        // hyperphosphataemia stimulates secretion directly.
+        // Slatopolsky E et al. (1996) J Clin Invest 97:2534-2540, PMID 8647946
+        let pth_secretion = self.pth.secretion_for(REFERENCE_PTH_PG_ML)
+            * self.parathyroid.relative_secretion(ca)
+            * normalized_suppression(
+                self.calcitriol.concentration,
+                REFERENCE_CALCITRIOL_PG_ML,
+                1.0,
+            )
+            * normalized_stimulation(self.phosphate_mg_dl, REFERENCE_PHOSPHATE_MG_DL, 2.0);
+        let calcitriol_secretion = self.calcitriol.secretion_for(REFERENCE_CALCITRIOL_PG_ML)
+            * self.renal_hydroxylase_capacity
+            * normalized_stimulation(self.calcidiol_nmol_l, REFERENCE_CALCIDIOL_NMOL_L, 1.0)
+            * normalized_stimulation(self.pth.concentration, REFERENCE_PTH_PG_ML, 1.0)
+            * normalized_suppression(self.fgf23.concentration, REFERENCE_FGF23_PG_ML, 1.0)
+            * normalized_suppression(self.phosphate_mg_dl, REFERENCE_PHOSPHATE_MG_DL, 1.0);
+        let calcitonin_secretion = self.calcitonin.secretion_for(REFERENCE_CALCITONIN_PG_ML)
+            * normalized_stimulation(ca, REFERENCE_IONIZED_CALCIUM_MMOL_L, 10.0);
+
+        // Phosphate drives FGF23 steeply and independently of calcitriol, so
+        // it keeps rising in CKD despite vitamin D deficiency.
+        let phosphate_drive = (self.phosphate_mg_dl / REFERENCE_PHOSPHATE_MG_DL).powi(3);
+        let calcitriol_drive = 0.5
+            + 0.5
+                * normalized_stimulation(
+                    self.calcitriol.concentration,
+                    REFERENCE_CALCITRIOL_PG_ML,
+                    1.0,
+                );
+        let fgf23_secretion = self.fgf23.secretion_for(REFERENCE_FGF23_PG_ML)
+            * self.fgf23_production_factor
+            * phosphate_drive
+            * calcitriol_drive;
+
+        self.pth.step(pth_secretion, dt_hours);
+        self.fgf23.step(fgf23_secretion, dt_hours);
+        self.calcitriol.step(calcitriol_secretion, dt_hours);
+        self.calcitonin.step(calcitonin_secretion, dt_hours);
+    }
+
+    fn publish(&self, signals: &mut EndocrineSignals) {
+        signals.pth_pg_ml = self.pth.concentration;
+        signals.calcitriol_pg_ml = self.calcitriol.concentration;
+        signals.calcitonin_pg_ml = self.calcitonin.concentration;
+        signals.fgf23_pg_ml = self.fgf23.concentration;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_point_normalised() {
+        let gland = ParathyroidSetPoint::normal();
+        assert!((gland.relative_secretion(REFERENCE_IONIZED_CALCIUM_MMOL_L) - 1.0).abs() < 1e-12);
+        assert!(gland.relative_secretion(1.0) > 1.5);
+        assert!(gland.relative_secretion(1.5) < 0.3);
+    }
+
+    #[test]
+    fn test_hypocalcemia_raises_pth_and_calcitriol() {
+        let mut axis = CalciumRegulatoryAxis::new_healthy();
+        axis.set_serum_minerals(1.05, REFERENCE_PHOSPHATE_MG_DL);
+        axis.run_hours(48.0, 0.01);
+        assert!(
+            axis.pth.concentration > 65.0,
+            "pth {}",
+            axis.pth.concentration
+        );
+        assert!(axis.calcitriol.concentration > REFERENCE_CALCITRIOL_PG_ML);
+    }
+
+    #[test]
+    fn test_hypercalcemia_suppresses_pth() {
+        let mut axis = CalciumRegulatoryAxis::new_healthy();
+        axis.set_serum_minerals(1.45, REFERENCE_PHOSPHATE_MG_DL);
+        axis.run_hours(24.0, 0.01);
+        assert!(axis.pth.concentration < 15.0);
+        assert!(axis.calcitonin.concentration > REFERENCE_CALCITONIN_PG_ML);
+    }
+
+    #[test]
+    fn test_phosphate_load_induces_fgf23() {
+        let mut axis = CalciumRegulatoryAxis::new_healthy();
+        axis.set_serum_minerals(REFERENCE_IONIZED_CALCIUM_MMOL_L, 6.0);
+        axis.run_hours(24.0, 0.01);
+        assert!(axis.fgf23_pg_ml() > REFERENCE_FGF23_PG_ML);
+    }
+
+    #[test]
+    fn test_fgf23_lowers_calcitriol() {
+        let mut axis = CalciumRegulatoryAxis::new_healthy();
+        axis.set_fgf23_production_factor(5.0);
+        axis.run_hours(48.0, 0.01);
+        assert!(axis.fgf23_pg_ml() > 2.0 * REFERENCE_FGF23_PG_ML);
+        let mut signals = EndocrineSignals::default();
+        axis.publish(&mut signals);
+        assert!(signals.calcitriol_ratio() < 0.8);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HormoneClass {
+    Peptide,
+    Glycoprotein,
+    Steroid,
+    Secosteroid,
+    Iodothyronine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Hormone {
+    Crh,
+    Acth,
+    Cortisol,
+    Trh,
+    Tsh,
+    FreeT4,
+    FreeT3,
+    Gnrh,
+    Lh,
+    Fsh,
+    Testosterone,
+    Estradiol,
+    Pth,
+    Calcitriol,
+    Calcitonin,
+    Fgf23,
+    Gh,
+    Igf1,
+}
+
+impl Hormone {
+    pub fn class(&self) -> HormoneClass {
+        match self {
+            Hormone::Crh
+            | Hormone::Acth
+            | Hormone::Trh
+            | Hormone::Gnrh
+            | Hormone::Pth
+            | Hormone::Calcitonin
+            | Hormone::Fgf23
+            | Hormone::Gh
+            | Hormone::Igf1 => HormoneClass::Peptide,
+            Hormone::Tsh | Hormone::Lh | Hormone::Fsh => HormoneClass::Glycoprotein,
+            Hormone::Cortisol | Hormone::Testosterone | Hormone::Estradiol => HormoneClass::Steroid,
+            Hormone::Calcitriol => HormoneClass::Secosteroid,
+            Hormone::FreeT4 | Hormone::FreeT3 => HormoneClass::Iodothyronine,
+        }
+    }
+
+    pub fn unit(&self) -> &'static str {
+        match self {
+            Hormone::Crh | Hormone::Trh | Hormone::Gnrh => "relative",
+            Hormone::Acth | Hormone::Pth | Hormone::Calcitonin | Hormone::Calcitriol => "pg/mL",
+            Hormone::Fgf23 => "pg/mL",
+            Hormone::FreeT3 | Hormone::Estradiol => "pg/mL",
+            Hormone::Cortisol => "µg/dL",
+            Hormone::Tsh => "mIU/L",
+            Hormone::FreeT4 | Hormone::Testosterone => "ng/dL",
+            Hormone::Lh | Hormone::Fsh => "IU/L",
+            Hormone::Gh | Hormone::Igf1 => "ng/mL",
+        }
+    }
+
+    // Plasma half-lives. Boron WF, Boulpaep EL, Medical Physiology 3e, Ch 47-52;
+    // TSH: Odell WD et al. (1967) J Clin Invest 46:953, PMID 6026101;
+    // PTH(1-84): Bieglmayer C et al. (2002) Clin Chem 48:1731, PMID 12324490;
+    // calcitriol: Jones G (2008) Am J Clin Nutr 88:582S, PMID 18689406;
+    // FGF23: Khosravi A et al. (2007) J Clin Endocrinol Metab 92:2374, PMID 17374707;
+    // IGF-1 in the ternary complex with IGFBP-3 and ALS: Guler HP et al. (1989)
+    // Acta Endocrinol 121:753-758
+    pub fn half_life_hours(&self) -> f64 {
+        match self {
+            Hormone::Crh => 0.15,
+            Hormone::Acth => 0.15,
+            Hormone::Cortisol => 1.1,
+            Hormone::Trh => 0.1,
+            Hormone::Tsh => 0.9,
+            Hormone::FreeT4 => 7.0 * 24.0,
+            Hormone::FreeT3 => 24.0,
+            Hormone::Gnrh => 0.06,
+            Hormone::Lh => 0.5,
+            Hormone::Fsh => 3.0,
+            Hormone::Testosterone => 1.0,
+            Hormone::Estradiol => 1.0,
+            Hormone::Pth => 0.06,
+            Hormone::Calcitriol => 5.0,
+            Hormone::Calcitonin => 0.17,
+            Hormone::Fgf23 => 1.0,
+            Hormone::Gh => 0.33,
+            Hormone::Igf1 => 15.0,
+        }
+    }
+
+    pub fn clearance_rate_per_hour(&self) -> f64 {
+        std::f64::consts::LN_2 / self.half_life_hours()
+    }
+}
+
+// First-order secretion/clearance compartment, dC/dt = S − k·C.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HormonePool {
+    pub hormone: Hormone,
+    pub concentration: f64,
+    pub clearance_rate_per_hour: f64,
+}
+
+impl HormonePool {
+    pub fn new(hormone: Hormone, concentration: f64) -> BiologyResult<Self> {
+        if concentration < 0.0 {
+            return Err(BiologyError::InvalidValue(format!(
+                "{:?} concentration must be non-negative",
+                hormone
+            )));
+        }
+        Ok(Self {
+            hormone,
+            concentration,
+            clearance_rate_per_hour: hormone.clearance_rate_per_hour(),
+        })
+    }
+
+    pub fn steady_state(&self, secretion_per_hour: f64) -> f64 {
+        secretion_per_hour.max(0.0) / self.clearance_rate_per_hour
+    }
+
+    // Secretion rate that holds the pool at `concentration`.
+    pub fn secretion_for(&self, concentration: f64) -> f64 {
+        concentration * self.clearance_rate_per_hour
+    }
+
+    // Exact solution for constant secretion over the step, so short-lived
+    // peptides stay stable alongside week-long iodothyronines.
+    pub fn step(&mut self, secretion_per_hour: f64, dt_hours: f64) {
+        let target = self.steady_state(secretion_per_hour);
+        let decay = (-self.clearance_rate_per_hour * dt_hours).exp();
+        self.concentration = target + (self.concentration - target) * decay;
+    }
+
+    // Zero clearance would make the steady state infinite.
+    pub fn scale_clearance(&mut self, factor: f64) -> BiologyResult<()> {
+        if !factor.is_finite() || factor <= 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{:?} clearance factor must be positive and finite, got {}",
+                self.hormone, factor
+            )));
+        }
+        self.clearance_rate_per_hour = self.hormone.clearance_rate_per_hour() * factor;
+        Ok(())
+    }
+}
+
+// Receptor occupancy with EC50 expressed in the hormone's own units.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Receptor {
+    pub ec50: f64,
+    pub hill_coefficient: f64,
+}
+
+impl Receptor {
+    pub fn new(ec50: f64, hill_coefficient: f64) -> BiologyResult<Self> {
+        if ec50 <= 0.0 || hill_coefficient <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "receptor EC50 and Hill coefficient must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            ec50,
+            hill_coefficient,
+        })
+    }
+
+    pub fn occupancy(&self, concentration: f64) -> f64 {
+        let x = (concentration.max(0.0) / self.ec50).powf(self.hill_coefficient);
+        x / (1.0 + x)
+    }
+
+    // Response relative to the response at a reference concentration.
+    pub fn relative_effect(&self, concentration: f64, reference: f64) -> f64 {
+        self.occupancy(concentration) / self.occupancy(reference).max(1e-12)
+    }
+}
+
+// Feedback inhibition normalised to 1 at the reference level, with
+// half-maximal suppression there.
+pub fn normalized_suppression(level: f64, reference: f64, hill: f64) -> f64 {
+    2.0 / (1.0 + (level.max(0.0) / reference).powf(hill))
+}
+
+pub fn normalized_stimulation(level: f64, reference: f64, hill: f64) -> f64 {
+    let x = (level.max(0.0) / reference).powf(hill);
+    2.0 * x / (1.0 + x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hormone_classes() {
+        assert_eq!(Hormone::Cortisol.class(), HormoneClass::Steroid);
+        assert_eq!(Hormone::Tsh.class(), HormoneClass::Glycoprotein);
+        assert_eq!(Hormone::Calcitriol.class(), HormoneClass::Secosteroid);
+        assert!(Hormone::FreeT4.half_life_hours() > 100.0 * Hormone::Pth.half_life_hours());
+    }
+
+    #[test]
+    fn test_pool_relaxes_to_steady_state() {
+        let mut pool = HormonePool::new(Hormone::Cortisol, 0.0).unwrap();
+        let secretion = pool.secretion_for(12.0);
+        for _ in 0..100 {
+            pool.step(secretion, 0.25);
+        }
+        assert!((pool.concentration - 12.0).abs() < 0.1);
+        assert!(HormonePool::new(Hormone::Pth, -1.0).is_err());
+
+        // Halving clearance doubles the steady state.
+        pool.scale_clearance(0.5).unwrap();
+        assert!((pool.steady_state(secretion) - 24.0).abs() < 1e-9);
+        for factor in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(pool.scale_clearance(factor).is_err());
+        }
+        assert!((pool.steady_state(secretion) - 24.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_exact_step_stable_for_fast_clearance() {
+        let mut pth = HormonePool::new(Hormone::Pth, 35.0).unwrap();
+        pth.step(0.0, 1.0);
+        assert!(pth.concentration >= 0.0 && pth.concentration < 1.0);
+    }
+
+    #[test]
+    fn test_receptor_occupancy() {
+        let receptor = Receptor::new(10.0, 1.0).unwrap();
+        assert!((receptor.occupancy(10.0) - 0.5).abs() < 1e-12);
+        assert!(receptor.relative_effect(20.0, 10.0) > 1.0);
+        assert!(Receptor::new(0.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_normalized_feedback() {
+        assert!((normalized_suppression(5.0, 5.0, 2.0) - 1.0).abs() < 1e-12);
+        assert!(normalized_suppression(10.0, 5.0, 2.0) < 1.0);
+        assert!(normalized_stimulation(10.0, 5.0, 2.0) > 1.0);
+    }
+}
@@ -0,0 +1,157 @@
+use super::hormone::{normalized_suppression, Hormone, HormonePool};
+use super::signals::{
+    EndocrineSignals, FeedbackAxis, REFERENCE_ACTH_PG_ML, REFERENCE_CORTISOL_UG_DL,
+};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::TAU;
+
+// CRH → ACTH → cortisol with cortisol feedback at both upper tiers.
+// Sriram K et al. (2012) PLoS ONE 7:e30016, PMID 22363416
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HpaAxis {
+    pub crh: HormonePool,
+    pub acth: HormonePool,
+    pub cortisol: HormonePool,
+    pub clock_hour: f64,
+    pub circadian_amplitude: f64,
+    pub circadian_peak_hour: f64,
+    pub feedback_hill: f64,
+    pub stress_drive: f64,
+    pub adrenal_capacity: f64,
+    pub exogenous_glucocorticoid_ug_dl: f64,
+}
+
+impl HpaAxis {
+    pub fn new_healthy() -> Self {
+        Self {
+            crh: HormonePool::new(Hormone::Crh, 1.0).expect("valid preset"),
+            acth: HormonePool::new(Hormone::Acth, REFERENCE_ACTH_PG_ML).expect("valid preset"),
+            cortisol: HormonePool::new(Hormone::Cortisol, REFERENCE_CORTISOL_UG_DL)
+                .expect("valid preset"),
+            clock_hour: 0.0,
+            circadian_amplitude: AMPLITUDE,
+            circadian_peak_hour: PEAK_HOUR,
+            feedback_hill: 1.0,
+            stress_drive: 1.0,
+            adrenal_capacity: 1.0,
+            exogenous_glucocorticoid_ug_dl: 0.0,
+        }
+    }
+
+    // Primary adrenal insufficiency.
+    pub fn addison() -> Self {
+        Self {
+            adrenal_capacity: 0.1,
+            ..Self::new_healthy()
+        }
+    }
+
+    fn circadian_drive(&self) -> f64 {
+        let phase = TAU * (self.clock_hour - self.circadian_peak_hour) / 24.0;
+        (1.0 + self.circadian_amplitude * phase.cos()).max(0.0)
+    }
+
+    // Glucocorticoid receptor feedback sees endogenous plus exogenous steroid.
+    fn feedback_level(&self) -> f64 {
+        self.cortisol.concentration + self.exogenous_glucocorticoid_ug_dl
+    }
+
+    pub fn total_glucocorticoid_ug_dl(&self) -> f64 {
+        self.feedback_level()
+    }
+}
+
+const AMPLITUDE: f64 = 1.0;
+const PEAK_HOUR: f64 = 5.0;
+
+impl Default for HpaAxis {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+impl FeedbackAxis for HpaAxis {
+    fn step(&mut self, dt_hours: f64) {
+        let feedback = normalized_suppression(
+            self.feedback_level(),
+            REFERENCE_CORTISOL_UG_DL,
+            self.feedback_hill,
+        );
+        let crh_secretion =
+            self.crh.secretion_for(1.0) * self.circadian_drive() * self.stress_drive * feedback;
+        let acth_secretion =
+            self.acth.secretion_for(REFERENCE_ACTH_PG_ML) * self.crh.concentration * feedback;
+        let cortisol_secretion = self.cortisol.secretion_for(REFERENCE_CORTISOL_UG_DL)
+            * (self.acth.concentration / REFERENCE_ACTH_PG_ML)
+            * self.adrenal_capacity;
+
+        self.crh.step(crh_secretion, dt_hours);
+        self.acth.step(acth_secretion, dt_hours);
+        self.cortisol.step(cortisol_secretion, dt_hours);
+        self.clock_hour = (self.clock_hour + dt_hours) % 24.0;
+    }
+
+    fn publish(&self, signals: &mut EndocrineSignals) {
+        signals.cortisol_ug_dl = self.total_glucocorticoid_ug_dl();
+        signals.acth_pg_ml = self.acth.concentration;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::ground_truth::GroundTruthDatabase;
+
+    fn settle(axis: &mut HpaAxis) {
+        axis.run_hours(96.0, 0.05);
+    }
+
+    fn at_hour(axis: &mut HpaAxis, hour: f64) -> f64 {
+        let wait = (hour - axis.clock_hour).rem_euclid(24.0);
+        axis.run_hours(wait, 0.05);
+        axis.cortisol.concentration
+    }
+
+    #[test]
+    fn test_diurnal_cortisol_matches_registry() {
+        let db = GroundTruthDatabase::new();
+        let endo = db.get_dataset("endocrine").unwrap();
+        let mut axis = HpaAxis::new_healthy();
+        settle(&mut axis);
+        let morning = at_hour(&mut axis, 8.0);
+        let evening = at_hour(&mut axis, 23.0);
+        assert!(
+            endo.is_within_expected_range("cortisol_morning_ug_dl", morning),
+            "morning {morning}"
+        );
+        assert!(
+            endo.is_within_expected_range("cortisol_evening_ug_dl", evening),
+            "evening {evening}"
+        );
+        // A sinusoidal drive underamplifies the clinical 3-5× ratio.
+        assert!(morning > 1.4 * evening);
+    }
+
+    #[test]
+    fn test_addison_raises_acth() {
+        let mut healthy = HpaAxis::new_healthy();
+        let mut addison = HpaAxis::addison();
+        settle(&mut healthy);
+        settle(&mut addison);
+        assert!(addison.acth.concentration > 2.0 * healthy.acth.concentration);
+        assert!(addison.cortisol.concentration < healthy.cortisol.concentration);
+    }
+
+    #[test]
+    fn test_exogenous_steroid_suppresses_axis() {
+        let mut axis = HpaAxis::new_healthy();
+        settle(&mut axis);
+        let baseline = axis.cortisol.concentration;
+        axis.exogenous_glucocorticoid_ug_dl = 30.0;
+        axis.run_hours(24.0, 0.05);
+        assert!(axis.cortisol.concentration < 0.5 * baseline);
+        let mut signals = EndocrineSignals::default();
+        axis.publish(&mut signals);
+        assert!(signals.glucocorticoid_ratio() > 2.0);
+    }
+}
@@ -0,0 +1,143 @@
+use super::hormone::{normalized_stimulation, normalized_suppression, Hormone, HormonePool};
+use super::signals::{
+    EndocrineSignals, FeedbackAxis, REFERENCE_ESTRADIOL_PG_ML, REFERENCE_FSH_IU_L,
+    REFERENCE_LH_IU_L, REFERENCE_TESTOSTERONE_NG_DL,
+};
+use crate::systems::cardiovascular::hematology::BiologicalSex;
+use serde::{Deserialize, Serialize};
+
+// GnRH → LH/FSH → gonadal steroid with steroid negative feedback. Pulsatility
+// and the mid-cycle positive-feedback surge are not represented.
+// Boron & Boulpaep, Medical Physiology 3e, Ch 54-55
+// Gonadal inhibin adds FSH-selective feedback that scales with gonadal mass.
+// Burger HG et al. (2002) Recent Prog Horm Res 57:257-275, PMID 12017547
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HpgAxis {
+    pub sex: BiologicalSex,
+    pub gnrh: HormonePool,
+    pub lh: HormonePool,
+    pub fsh: HormonePool,
+    pub sex_steroid: HormonePool,
+    pub gonadal_capacity: f64,
+    pub exogenous_steroid: f64,
+}
+
+impl HpgAxis {
+    pub fn new(sex: BiologicalSex) -> Self {
+        let steroid = match sex {
+            BiologicalSex::Male => {
+                HormonePool::new(Hormone::Testosterone, REFERENCE_TESTOSTERONE_NG_DL)
+            }
+            BiologicalSex::Female => {
+                HormonePool::new(Hormone::Estradiol, REFERENCE_ESTRADIOL_PG_ML)
+            }
+        }
+        .expect("valid preset");
+        Self {
+            sex,
+            gnrh: HormonePool::new(Hormone::Gnrh, 1.0).expect("valid preset"),
+            lh: HormonePool::new(Hormone::Lh, REFERENCE_LH_IU_L).expect("valid preset"),
+            fsh: HormonePool::new(Hormone::Fsh, REFERENCE_FSH_IU_L).expect("valid preset"),
+            sex_steroid: steroid,
+            gonadal_capacity: 1.0,
+            exogenous_steroid: 0.0,
+        }
+    }
+
+    // Follicle depletion leaves ovarian estradiol output near zero.
+    pub fn postmenopausal() -> Self {
+        Self {
+            gonadal_capacity: 0.05,
+            ..Self::new(BiologicalSex::Female)
+        }
+    }
+
+    pub fn reference_steroid(&self) -> f64 {
+        match self.sex {
+            BiologicalSex::Male => REFERENCE_TESTOSTERONE_NG_DL,
+            BiologicalSex::Female => REFERENCE_ESTRADIOL_PG_ML,
+        }
+    }
+
+    fn feedback_level(&self) -> f64 {
+        self.sex_steroid.concentration + self.exogenous_steroid
+    }
+}
+
+impl FeedbackAxis for HpgAxis {
+    fn step(&mut self, dt_hours: f64) {
+        let reference = self.reference_steroid();
+        let feedback = normalized_suppression(self.feedback_level(), reference, 1.0);
+        let gnrh_secretion = self.gnrh.secretion_for(1.0) * feedback;
+        let lh_secretion =
+            self.lh.secretion_for(REFERENCE_LH_IU_L) * self.gnrh.concentration * feedback;
+        let inhibin = normalized_suppression(self.gonadal_capacity, 1.0, 1.0);
+        let fsh_secretion = self.fsh.secretion_for(REFERENCE_FSH_IU_L)
+            * self.gnrh.concentration
+            * feedback
+            * inhibin;
+        let steroid_secretion = self.sex_steroid.secretion_for(reference)
+            * self.gonadal_capacity
+            * normalized_stimulation(self.lh.concentration, REFERENCE_LH_IU_L, 1.0);
+
+        self.gnrh.step(gnrh_secretion, dt_hours);
+        self.lh.step(lh_secretion, dt_hours);
+        self.fsh.step(fsh_secretion, dt_hours);
+        self.sex_steroid.step(steroid_secretion, dt_hours);
+    }
+
+    fn publish(&self, signals: &mut EndocrineSignals) {
+        signals.lh_iu_l = self.lh.concentration;
+        signals.fsh_iu_l = self.fsh.concentration;
+        let total = self.sex_steroid.concentration + self.exogenous_steroid;
+        match self.sex {
+            BiologicalSex::Male => signals.testosterone_ng_dl = total,
+            BiologicalSex::Female => {
+                signals.estradiol_pg_ml = total;
+                signals.testosterone_ng_dl = FEMALE_TESTOSTERONE_NG_DL;
+            }
+        }
+    }
+}
+
+// Adult female total testosterone 15-70 ng/dL.
+// Bhasin S et al. (2018) J Clin Endocrinol Metab 103:1715, PMID 29562364
+const FEMALE_TESTOSTERONE_NG_DL: f64 = 30.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_male_steady_state_at_reference() {
+        let mut axis = HpgAxis::new(BiologicalSex::Male);
+        axis.run_hours(72.0, 0.02);
+        let t = axis.sex_steroid.concentration;
+        assert!((264.0..=916.0).contains(&t));
+    }
+
+    #[test]
+    fn test_menopause_raises_gonadotropins() {
+        // Postmenopausal FSH is typically > 25 IU/L.
+        let mut axis = HpgAxis::postmenopausal();
+        axis.run_hours(240.0, 0.02);
+        assert!(
+            axis.fsh.concentration > 25.0,
+            "fsh {}",
+            axis.fsh.concentration
+        );
+        let mut signals = EndocrineSignals::default();
+        axis.publish(&mut signals);
+        assert!(signals.sex_steroid_bone_protection() < 0.5);
+        assert!(axis.fsh.concentration > axis.lh.concentration);
+    }
+
+    #[test]
+    fn test_exogenous_testosterone_suppresses_lh() {
+        let mut axis = HpgAxis::new(BiologicalSex::Male);
+        axis.exogenous_steroid = 900.0;
+        axis.run_hours(72.0, 0.02);
+        assert!(axis.lh.concentration < 0.5 * REFERENCE_LH_IU_L);
+        assert!(axis.sex_steroid.concentration < REFERENCE_TESTOSTERONE_NG_DL);
+    }
+}
@@ -0,0 +1,149 @@
+use super::hormone::{normalized_stimulation, normalized_suppression, Hormone, HormonePool};
+use super::signals::{
+    EndocrineSignals, FeedbackAxis, REFERENCE_FREE_T3_PG_ML, REFERENCE_FREE_T4_NG_DL,
+    REFERENCE_TSH_MIU_L,
+};
+use serde::{Deserialize, Serialize};
+
+// TRH → TSH → T4 → T3, with the steep inverse log-linear TSH-FT4 relation
+// produced mostly at the pituitary.
+// Spencer CA et al. (1990) J Clin Endocrinol Metab 70:453-460
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HptAxis {
+    pub trh: HormonePool,
+    pub tsh: HormonePool,
+    pub free_t4: HormonePool,
+    pub free_t3: HormonePool,
+    pub thyroid_capacity: f64,
+    pub autonomous_t4_fraction: f64,
+    pub deiodinase_activity: f64,
+    pub levothyroxine_ng_dl_per_hour: f64,
+}
+
+// Pituitary feedback is far steeper than hypothalamic feedback.
+const PITUITARY_FEEDBACK_HILL: f64 = 4.0;
+const HYPOTHALAMIC_FEEDBACK_HILL: f64 = 1.0;
+
+impl HptAxis {
+    pub fn new_healthy() -> Self {
+        Self {
+            trh: HormonePool::new(Hormone::Trh, 1.0).expect("valid preset"),
+            tsh: HormonePool::new(Hormone::Tsh, REFERENCE_TSH_MIU_L).expect("valid preset"),
+            free_t4: HormonePool::new(Hormone::FreeT4, REFERENCE_FREE_T4_NG_DL)
+                .expect("valid preset"),
+            free_t3: HormonePool::new(Hormone::FreeT3, REFERENCE_FREE_T3_PG_ML)
+                .expect("valid preset"),
+            thyroid_capacity: 1.0,
+            autonomous_t4_fraction: 0.0,
+            deiodinase_activity: 1.0,
+            levothyroxine_ng_dl_per_hour: 0.0,
+        }
+    }
+
+    // Loss of functional thyroid mass, e.g. Hashimoto thyroiditis.
+    pub fn primary_hypothyroid(remaining_capacity: f64) -> Self {
+        Self {
+            thyroid_capacity: remaining_capacity.clamp(0.0, 1.0),
+            ..Self::new_healthy()
+        }
+    }
+
+    // TSH-receptor antibodies drive secretion independently of TSH.
+    pub fn graves(autonomous_t4_fraction: f64) -> Self {
+        Self {
+            autonomous_t4_fraction: autonomous_t4_fraction.max(0.0),
+            ..Self::new_healthy()
+        }
+    }
+}
+
+impl Default for HptAxis {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+impl FeedbackAxis for HptAxis {
+    fn step(&mut self, dt_hours: f64) {
+        let t3 = self.free_t3.concentration;
+        let t4 = self.free_t4.concentration;
+        let trh_secretion = self.trh.secretion_for(1.0)
+            * normalized_suppression(t3, REFERENCE_FREE_T3_PG_ML, HYPOTHALAMIC_FEEDBACK_HILL);
+        let tsh_secretion = self.tsh.secretion_for(REFERENCE_TSH_MIU_L)
+            * self.trh.concentration
+            * normalized_suppression(t4, REFERENCE_FREE_T4_NG_DL, PITUITARY_FEEDBACK_HILL);
+        let t4_secretion = self.free_t4.secretion_for(REFERENCE_FREE_T4_NG_DL)
+            * (self.thyroid_capacity
+                * normalized_stimulation(self.tsh.concentration, REFERENCE_TSH_MIU_L, 1.0)
+                + self.autonomous_t4_fraction)
+            + self.levothyroxine_ng_dl_per_hour;
+        let t3_secretion = self.free_t3.secretion_for(REFERENCE_FREE_T3_PG_ML)
+            * self.deiodinase_activity
+            * (t4 / REFERENCE_FREE_T4_NG_DL);
+
+        self.trh.step(trh_secretion, dt_hours);
+        self.tsh.step(tsh_secretion, dt_hours);
+        self.free_t4.step(t4_secretion, dt_hours);
+        self.free_t3.step(t3_secretion, dt_hours);
+    }
+
+    fn publish(&self, signals: &mut EndocrineSignals) {
+        signals.tsh_miu_l = self.tsh.concentration;
+        signals.free_t4_ng_dl = self.free_t4.concentration;
+        signals.free_t3_pg_ml = self.free_t3.concentration;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::ground_truth::GroundTruthDatabase;
+
+    const SIX_WEEKS_HOURS: f64 = 42.0 * 24.0;
+
+    #[test]
+    fn test_euthyroid_within_registry() {
+        let db = GroundTruthDatabase::new();
+        let endo = db.get_dataset("endocrine").unwrap();
+        let mut axis = HptAxis::new_healthy();
+        axis.run_hours(SIX_WEEKS_HOURS, 0.5);
+        assert!(endo.is_within_expected_range("tsh_miu_l", axis.tsh.concentration));
+        assert!(endo.is_within_expected_range("free_t4_ng_dl", axis.free_t4.concentration));
+    }
+
+    #[test]
+    fn test_hypothyroidism_elevates_tsh() {
+        let mut axis = HptAxis::primary_hypothyroid(0.3);
+        axis.run_hours(SIX_WEEKS_HOURS, 0.5);
+        assert!(
+            axis.tsh.concentration > 4.5,
+            "tsh {}",
+            axis.tsh.concentration
+        );
+        assert!(axis.free_t4.concentration < REFERENCE_FREE_T4_NG_DL);
+    }
+
+    #[test]
+    fn test_graves_suppresses_tsh() {
+        let mut axis = HptAxis::graves(1.5);
+        axis.run_hours(SIX_WEEKS_HOURS, 0.5);
+        assert!(
+            axis.tsh.concentration < 0.4,
+            "tsh {}",
+            axis.tsh.concentration
+        );
+        let mut signals = EndocrineSignals::default();
+        axis.publish(&mut signals);
+        assert!(signals.thyroid_metabolic_ratio() > 1.2);
+    }
+
+    #[test]
+    fn test_levothyroxine_restores_hypothyroid() {
+        let mut untreated = HptAxis::primary_hypothyroid(0.3);
+        let mut treated = HptAxis::primary_hypothyroid(0.3);
+        treated.levothyroxine_ng_dl_per_hour = 0.6 * treated.free_t4.secretion_for(1.2);
+        untreated.run_hours(SIX_WEEKS_HOURS, 0.5);
+        treated.run_hours(SIX_WEEKS_HOURS, 0.5);
+        assert!(treated.tsh.concentration < untreated.tsh.concentration);
+    }
+}
@@ -0,0 +1,17 @@
+pub mod calcium;
+pub mod hormone;
+pub mod hpa;
+pub mod hpg;
+pub mod hpt;
+pub mod signals;
+pub mod somatotropic;
+pub mod vitamin_d;
+
+pub use calcium::{CalciumRegulatoryAxis, ParathyroidSetPoint};
+pub use hormone::{Hormone, HormoneClass, HormonePool, Receptor};
+pub use hpa::HpaAxis;
+pub use hpg::HpgAxis;
+pub use hpt::HptAxis;
+pub use signals::{EndocrineSignals, FeedbackAxis};
+pub use somatotropic::SomatotropicAxis;
+pub use vitamin_d::VitaminDMetabolism;
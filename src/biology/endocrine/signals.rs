@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+// Circulating hormone levels published by the feedback axes for bone,
+// metabolic and immune consumers. Defaults are healthy adult mid-range values.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EndocrineSignals {
+    pub cortisol_ug_dl: f64,
+    pub acth_pg_ml: f64,
+    pub tsh_miu_l: f64,
+    pub free_t4_ng_dl: f64,
+    pub free_t3_pg_ml: f64,
+    pub lh_iu_l: f64,
+    pub fsh_iu_l: f64,
+    pub testosterone_ng_dl: f64,
+    pub estradiol_pg_ml: f64,
+    pub pth_pg_ml: f64,
+    pub calcitriol_pg_ml: f64,
+    pub calcitonin_pg_ml: f64,
+    pub fgf23_pg_ml: f64,
+    pub gh_ng_ml: f64,
+    pub igf1_ng_ml: f64,
+}
+
+impl EndocrineSignals {
+    pub fn reference_adult() -> Self {
+        Self {
+            cortisol_ug_dl: REFERENCE_CORTISOL_UG_DL,
+            acth_pg_ml: REFERENCE_ACTH_PG_ML,
+            tsh_miu_l: REFERENCE_TSH_MIU_L,
+            free_t4_ng_dl: REFERENCE_FREE_T4_NG_DL,
+            free_t3_pg_ml: REFERENCE_FREE_T3_PG_ML,
+            lh_iu_l: REFERENCE_LH_IU_L,
+            fsh_iu_l: REFERENCE_FSH_IU_L,
+            testosterone_ng_dl: REFERENCE_TESTOSTERONE_NG_DL,
+            estradiol_pg_ml: REFERENCE_ESTRADIOL_PG_ML,
+            pth_pg_ml: REFERENCE_PTH_PG_ML,
+            calcitriol_pg_ml: REFERENCE_CALCITRIOL_PG_ML,
+            calcitonin_pg_ml: REFERENCE_CALCITONIN_PG_ML,
+            fgf23_pg_ml: REFERENCE_FGF23_PG_ML,
+            gh_ng_ml: REFERENCE_GH_NG_ML,
+            igf1_ng_ml: REFERENCE_IGF1_NG_ML,
+        }
+    }
+
+    // Glucocorticoid excess suppresses lymphocyte proliferation and osteoblasts.
+    pub fn glucocorticoid_ratio(&self) -> f64 {
+        self.cortisol_ug_dl / REFERENCE_CORTISOL_UG_DL
+    }
+
+    // T3 sets basal metabolic rate.
+    pub fn thyroid_metabolic_ratio(&self) -> f64 {
+        self.free_t3_pg_ml / REFERENCE_FREE_T3_PG_ML
+    }
+
+    // Estradiol restrains osteoclastogenesis in both sexes; testosterone
+    // contributes via aromatisation (≈0.3% conversion is ignored here).
+    pub fn sex_steroid_bone_protection(&self) -> f64 {
+        let estrogen = self.estradiol_pg_ml / REFERENCE_ESTRADIOL_PG_ML;
+        let androgen = self.testosterone_ng_dl / REFERENCE_TESTOSTERONE_NG_DL;
+        estrogen.max(androgen)
+    }
+
+    pub fn pth_ratio(&self) -> f64 {
+        self.pth_pg_ml / REFERENCE_PTH_PG_ML
+    }
+
+    pub fn calcitriol_ratio(&self) -> f64 {
+        self.calcitriol_pg_ml / REFERENCE_CALCITRIOL_PG_ML
+    }
+
+    pub fn igf1_ratio(&self) -> f64 {
+        self.igf1_ng_ml / REFERENCE_IGF1_NG_ML
+    }
+}
+
+impl Default for EndocrineSignals {
+    fn default() -> Self {
+        Self::reference_adult()
+    }
+}
+
+// Mean 24-h cortisol; the registry morning/evening values bracket it.
+pub const REFERENCE_CORTISOL_UG_DL: f64 = 10.0;
+// Morning ACTH 10-60 pg/mL. Boron & Boulpaep, Medical Physiology 3e, Ch 50
+pub const REFERENCE_ACTH_PG_ML: f64 = 25.0;
+// TSH and free T4 match the endocrine ground-truth registry
+// (Spencer 2016, Hoermann 2017).
+pub const REFERENCE_TSH_MIU_L: f64 = 2.0;
+pub const REFERENCE_FREE_T4_NG_DL: f64 = 1.2;
+// Free T3 2.3-4.2 pg/mL. Boron & Boulpaep, Medical Physiology 3e, Ch 49
+pub const REFERENCE_FREE_T3_PG_ML: f64 = 3.2;
+// Adult male LH/FSH 1.5-9 IU/L and total testosterone 264-916 ng/dL.
+// Bhasin S et al. (2018) J Clin Endocrinol Metab 103:1715, PMID 29562364
+pub const REFERENCE_LH_IU_L: f64 = 5.0;
+pub const REFERENCE_FSH_IU_L: f64 = 5.0;
+pub const REFERENCE_TESTOSTERONE_NG_DL: f64 = 600.0;
+// Follicular-phase estradiol. Stricker R et al. (2006) Clin Chem Lab Med
+// 44:883, PMID 16776638
+pub const REFERENCE_ESTRADIOL_PG_ML: f64 = 80.0;
+// Intact PTH 15-65 pg/mL; calcitriol 20-60 pg/mL; calcitonin < 10 pg/mL.
+// Boron & Boulpaep, Medical Physiology 3e, Ch 52
+pub const REFERENCE_PTH_PG_ML: f64 = 35.0;
+pub const REFERENCE_CALCITRIOL_PG_ML: f64 = 40.0;
+pub const REFERENCE_CALCITONIN_PG_ML: f64 = 5.0;
+// Intact FGF23 in healthy adults ~20-60 pg/mL.
+// Wolf M (2012) Kidney Int 82:737-747, PMID 22622492
+pub const REFERENCE_FGF23_PG_ML: f64 = 40.0;
+// Mean 24-h GH in adults is ~0.5-1 ng/mL between pulses; serum IGF-1 in
+// young adults ~100-300 ng/mL, peaking near twice that in mid-puberty.
+// Juul A et al. (1994) J Clin Endocrinol Metab 78:744-752, PMID 8126152
+pub const REFERENCE_GH_NG_ML: f64 = 1.0;
+pub const REFERENCE_IGF1_NG_ML: f64 = 200.0;
+
+pub trait FeedbackAxis {
+    fn step(&mut self, dt_hours: f64);
+
+    // Write this axis' outputs into the shared signal bundle.
+    fn publish(&self, signals: &mut EndocrineSignals);
+
+    fn run_hours(&mut self, hours: f64, dt_hours: f64) {
+        let steps = (hours / dt_hours).round().max(0.0) as usize;
+        for _ in 0..steps {
+            self.step(dt_hours);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reference_ratios_are_unity() {
+        let signals = EndocrineSignals::reference_adult();
+        assert!((signals.glucocorticoid_ratio() - 1.0).abs() < 1e-12);
+        assert!((signals.pth_ratio() - 1.0).abs() < 1e-12);
+        assert!((signals.sex_steroid_bone_protection() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sex_steroid_deficiency_lowers_protection() {
+        let signals = EndocrineSignals {
+            estradiol_pg_ml: 10.0,
+            testosterone_ng_dl: 20.0,
+            ..EndocrineSignals::reference_adult()
+        };
+        assert!(signals.sex_steroid_bone_protection() < 0.2);
+    }
+}
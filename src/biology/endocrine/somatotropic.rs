@@ -0,0 +1,119 @@
+use super::hormone::{normalized_stimulation, normalized_suppression, Hormone, HormonePool};
+use super::signals::{EndocrineSignals, FeedbackAxis, REFERENCE_GH_NG_ML, REFERENCE_IGF1_NG_ML};
+use serde::{Deserialize, Serialize};
+
+// Pituitary GH → hepatic IGF-1, with IGF-1 feeding back on GH release both
+// directly at the somatotroph and through hypothalamic somatostatin. GHRH
+// and the pulsatile pattern are folded into a mean secretion rate.
+// Berelowitz M et al. (1981) Science 212:1279-1281, PMID 6262917
+// Sex steroids raise GH pulse amplitude in puberty, which the pubertal
+// drive stands in for.
+// Mauras N et al. (1996) J Clin Endocrinol Metab 81:1201-1205, PMID 8772599
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SomatotropicAxis {
+    pub gh: HormonePool,
+    pub igf1: HormonePool,
+    pub pituitary_capacity: f64,
+    // GH receptor responsiveness of the liver; near zero in Laron syndrome.
+    pub hepatic_sensitivity: f64,
+    pub pubertal_drive: f64,
+    // Steady plasma level added by daily somatropin injections.
+    pub exogenous_gh_ng_ml: f64,
+}
+
+impl SomatotropicAxis {
+    pub fn new_adult() -> Self {
+        Self {
+            gh: HormonePool::new(Hormone::Gh, REFERENCE_GH_NG_ML).expect("valid preset"),
+            igf1: HormonePool::new(Hormone::Igf1, REFERENCE_IGF1_NG_ML).expect("valid preset"),
+            pituitary_capacity: 1.0,
+            hepatic_sensitivity: 1.0,
+            pubertal_drive: 1.0,
+            exogenous_gh_ng_ml: 0.0,
+        }
+    }
+
+    // Isolated GH deficiency with a fraction of somatotroph output left.
+    pub fn gh_deficient(remaining_capacity: f64) -> Self {
+        Self {
+            pituitary_capacity: remaining_capacity.clamp(0.0, 1.0),
+            ..Self::new_adult()
+        }
+    }
+
+    pub fn total_gh(&self) -> f64 {
+        self.gh.concentration + self.exogenous_gh_ng_ml
+    }
+}
+
+impl Default for SomatotropicAxis {
+    fn default() -> Self {
+        Self::new_adult()
+    }
+}
+
+impl FeedbackAxis for SomatotropicAxis {
+    fn step(&mut self, dt_hours: f64) {
+        let feedback = normalized_suppression(self.igf1.concentration, REFERENCE_IGF1_NG_ML, 1.0);
+        let gh_secretion = self.gh.secretion_for(REFERENCE_GH_NG_ML)
+            * self.pituitary_capacity
+            * self.pubertal_drive.max(0.0)
+            * feedback;
+        let igf1_secretion = self.igf1.secretion_for(REFERENCE_IGF1_NG_ML)
+            * self.hepatic_sensitivity
+            * normalized_stimulation(self.total_gh(), REFERENCE_GH_NG_ML, 1.0);
+        self.gh.step(gh_secretion, dt_hours);
+        self.igf1.step(igf1_secretion, dt_hours);
+    }
+
+    fn publish(&self, signals: &mut EndocrineSignals) {
+        signals.gh_ng_ml = self.total_gh();
+        signals.igf1_ng_ml = self.igf1.concentration;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adult_steady_state_at_reference() {
+        let mut axis = SomatotropicAxis::new_adult();
+        axis.run_hours(240.0, 0.1);
+        assert!((axis.gh.concentration - REFERENCE_GH_NG_ML).abs() < 1e-6);
+        assert!((axis.igf1.concentration - REFERENCE_IGF1_NG_ML).abs() < 1e-6);
+        let mut signals = EndocrineSignals::default();
+        axis.publish(&mut signals);
+        assert!((signals.igf1_ratio() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_gh_deficiency_lowers_igf1_until_replaced() {
+        let mut axis = SomatotropicAxis::gh_deficient(0.2);
+        axis.run_hours(480.0, 0.1);
+        let untreated = axis.igf1.concentration;
+        assert!(untreated < 0.5 * REFERENCE_IGF1_NG_ML, "igf1 {untreated}");
+        axis.exogenous_gh_ng_ml = 1.0;
+        axis.run_hours(480.0, 0.1);
+        assert!(axis.igf1.concentration > 0.9 * REFERENCE_IGF1_NG_ML);
+    }
+
+    #[test]
+    fn test_igf1_feedback_and_pubertal_drive() {
+        // GH resistance leaves IGF-1 low and GH high through lost feedback.
+        let mut laron = SomatotropicAxis {
+            hepatic_sensitivity: 0.1,
+            ..SomatotropicAxis::new_adult()
+        };
+        laron.run_hours(480.0, 0.1);
+        assert!(laron.gh.concentration > 1.5 * REFERENCE_GH_NG_ML);
+        assert!(laron.igf1.concentration < 0.3 * REFERENCE_IGF1_NG_ML);
+
+        let mut puberty = SomatotropicAxis {
+            pubertal_drive: 3.0,
+            ..SomatotropicAxis::new_adult()
+        };
+        puberty.run_hours(480.0, 0.1);
+        assert!(puberty.igf1.concentration > 1.3 * REFERENCE_IGF1_NG_ML);
+    }
+}
@@ -0,0 +1,318 @@
+use super::hormone::normalized_stimulation;
+use super::signals::{REFERENCE_CALCITRIOL_PG_ML, REFERENCE_FGF23_PG_ML};
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::LN_2;
+
+// Serum 25(OH)D of 75 nmol/L (30 ng/mL) is taken as replete; below
+// 50 nmol/L is insufficient and below 30 nmol/L deficient.
+// Holick MF et al. (2011) J Clin Endocrinol Metab 96:1911-1930
+pub const REFERENCE_CALCIDIOL_NMOL_L: f64 = 75.0;
+pub const DEFICIENT_CALCIDIOL_NMOL_L: f64 = 30.0;
+// UVB photolyses epidermal 7-dehydrocholesterol to previtamin D3, which
+// reaches photoequilibrium near one minimal erythemal dose (about 2.5
+// standard erythemal doses for skin type II). A whole-body MED yields
+// 250-500 µg of vitamin D3.
+// Holick MF (2004) Am J Clin Nutr 80:1678S-1688S
+const WHOLE_BODY_SYNTHESIS_UG: f64 = 400.0;
+const PHOTOEQUILIBRIUM_SED: f64 = 2.5;
+// Previtamin D3 isomerises thermally to vitamin D3 in the skin.
+// Tian XQ et al. (1993) J Biol Chem 268:14888-14892
+const THERMAL_ISOMERISATION_HALF_LIFE_H: f64 = 2.5;
+// Adipose tissue sequesters vitamin D3 within days and releases it over
+// months, buffering 25(OH)D through the winter.
+// Mawer EB et al. (1972) Clin Sci 43:413-431
+const ADIPOSE_UPTAKE_PER_DAY: f64 = 1.0;
+const ADIPOSE_RELEASE_HALF_LIFE_DAYS: f64 = 40.0;
+// Hepatic 25-hydroxylation saturates at high doses while vitamin D3 is
+// also lost through bile and side-chain catabolism, so the 25(OH)D
+// response flattens as intake rises. At usual intakes each µg/day of
+// vitamin D3 raises steady-state 25(OH)D by ~0.7 nmol/L, and a replete
+// adult uses ~100 µg/day.
+// Heaney RP et al. (2003) Am J Clin Nutr 77:204-210
+const HEPATIC_VMAX_UG_PER_DAY: f64 = 1100.0;
+const HEPATIC_KM_UG: f64 = 1000.0;
+const OTHER_CLEARANCE_PER_DAY: f64 = 0.08;
+const CALCIDIOL_PER_UG_HYDROXYLATED: f64 = 0.75;
+// 25(OH)D half-life ~15 days.
+// Jones G (2008) Am J Clin Nutr 88:582S-586S
+const CALCIDIOL_HALF_LIFE_DAYS: f64 = 15.0;
+// Calcitriol and FGF23 both induce the catabolic 24-hydroxylase CYP24A1.
+// Shimada T et al. (2004) J Bone Miner Res 19:429-435
+const CYP24_HILL: f64 = 1.0;
+
+// Habitual exposure: face, arms and legs (~35% of skin) receiving about
+// 3 SED a day, plus a 400 IU supplement.
+const HEALTHY_UV_SED_PER_DAY: f64 = 3.0;
+const HEALTHY_EXPOSED_SKIN_FRACTION: f64 = 0.35;
+const HEALTHY_ORAL_UG_PER_DAY: f64 = 10.0;
+
+// 7-dehydrocholesterol → previtamin D3 → cholecalciferol in skin, oral
+// intake, adipose storage and hepatic conversion to 25(OH)D. Renal
+// 1α-hydroxylation to calcitriol belongs to `CalciumRegulatoryAxis`, which
+// takes `calcidiol_nmol_l` as its substrate and returns calcitriol and
+// FGF23 to drive 25(OH)D catabolism.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VitaminDMetabolism {
+    // 1 for young fair skin; age, pigmentation and sunscreen lower it.
+    pub skin_synthesis_capacity: f64,
+    pub uv_sed_per_day: f64,
+    pub exposed_skin_fraction: f64,
+    pub oral_ug_per_day: f64,
+    pub previtamin_d3_ug: f64,
+    pub cholecalciferol_ug: f64,
+    pub adipose_store_ug: f64,
+    pub calcidiol_nmol_l: f64,
+    pub calcitriol_pg_ml: f64,
+    pub fgf23_pg_ml: f64,
+}
+
+impl VitaminDMetabolism {
+    // Replete steady state under habitual sun and a small supplement.
+    pub fn new_healthy() -> Self {
+        let mut pathway = Self {
+            skin_synthesis_capacity: 1.0,
+            uv_sed_per_day: HEALTHY_UV_SED_PER_DAY,
+            exposed_skin_fraction: HEALTHY_EXPOSED_SKIN_FRACTION,
+            oral_ug_per_day: HEALTHY_ORAL_UG_PER_DAY,
+            previtamin_d3_ug: 0.0,
+            cholecalciferol_ug: 0.0,
+            adipose_store_ug: 0.0,
+            calcidiol_nmol_l: 0.0,
+            calcitriol_pg_ml: REFERENCE_CALCITRIOL_PG_ML,
+            fgf23_pg_ml: REFERENCE_FGF23_PG_ML,
+        };
+        pathway.settle();
+        pathway
+    }
+
+    // Places every pool at the steady state of the current inputs.
+    fn settle(&mut self) {
+        let skin = self.skin_synthesis_ug_per_day();
+        let input = skin + self.oral_ug_per_day;
+        self.previtamin_d3_ug = skin / (24.0 * isomerisation_rate_per_hour());
+        // Input = Vmax·D/(Km + D) + k·D, solved for the circulating pool D.
+        let b = HEPATIC_VMAX_UG_PER_DAY + OTHER_CLEARANCE_PER_DAY * HEPATIC_KM_UG - input;
+        self.cholecalciferol_ug = (-b
+            + (b * b + 4.0 * OTHER_CLEARANCE_PER_DAY * input * HEPATIC_KM_UG).sqrt())
+            / (2.0 * OTHER_CLEARANCE_PER_DAY);
+        self.adipose_store_ug =
+            self.cholecalciferol_ug * ADIPOSE_UPTAKE_PER_DAY / adipose_release_per_day();
+        self.calcidiol_nmol_l = CALCIDIOL_PER_UG_HYDROXYLATED
+            * self.hepatic_25_hydroxylation_ug_per_day()
+            / self.catabolism_factor();
+    }
+
+    pub fn set_sun_exposure(
+        &mut self,
+        sed_per_day: f64,
+        exposed_fraction: f64,
+    ) -> BiologyResult<()> {
+        if sed_per_day.is_nan() || sed_per_day < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "UV dose must be non-negative".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&exposed_fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "exposed skin fraction must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.uv_sed_per_day = sed_per_day;
+        self.exposed_skin_fraction = exposed_fraction;
+        Ok(())
+    }
+
+    pub fn set_supplement(&mut self, ug_per_day: f64) -> BiologyResult<()> {
+        if ug_per_day.is_nan() || ug_per_day < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "supplement dose must be non-negative".to_string(),
+            ));
+        }
+        self.oral_ug_per_day = ug_per_day;
+        Ok(())
+    }
+
+    pub fn set_skin_synthesis_capacity(&mut self, capacity: f64) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&capacity) {
+            return Err(BiologyError::InvalidParameter(
+                "skin synthesis capacity must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.skin_synthesis_capacity = capacity;
+        Ok(())
+    }
+
+    // A single exposure, e.g. an afternoon in the sun, on top of the
+    // habitual daily dose.
+    pub fn expose_skin(&mut self, sed: f64, exposed_fraction: f64) -> BiologyResult<()> {
+        if sed.is_nan() || sed < 0.0 || !(0.0..=1.0).contains(&exposed_fraction) {
+            return Err(BiologyError::InvalidValue(
+                "exposure needs a non-negative dose and a skin fraction in [0, 1]".to_string(),
+            ));
+        }
+        self.previtamin_d3_ug += self.previtamin_yield_ug(sed, exposed_fraction);
+        Ok(())
+    }
+
+    // Oral bolus such as a weekly or monthly loading dose.
+    pub fn take_dose(&mut self, ug: f64) -> BiologyResult<()> {
+        if ug.is_nan() || ug < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "dose must be non-negative".to_string(),
+            ));
+        }
+        self.cholecalciferol_ug += ug;
+        Ok(())
+    }
+
+    pub fn set_renal_signals(&mut self, calcitriol_pg_ml: f64, fgf23_pg_ml: f64) {
+        self.calcitriol_pg_ml = calcitriol_pg_ml.max(0.0);
+        self.fgf23_pg_ml = fgf23_pg_ml.max(0.0);
+    }
+
+    fn previtamin_yield_ug(&self, sed: f64, exposed_fraction: f64) -> f64 {
+        WHOLE_BODY_SYNTHESIS_UG
+            * exposed_fraction
+            * self.skin_synthesis_capacity
+            * (1.0 - (-sed / PHOTOEQUILIBRIUM_SED).exp())
+    }
+
+    pub fn skin_synthesis_ug_per_day(&self) -> f64 {
+        self.previtamin_yield_ug(self.uv_sed_per_day, self.exposed_skin_fraction)
+    }
+
+    pub fn hepatic_25_hydroxylation_ug_per_day(&self) -> f64 {
+        HEPATIC_VMAX_UG_PER_DAY * self.cholecalciferol_ug
+            / (HEPATIC_KM_UG + self.cholecalciferol_ug)
+    }
+
+    // CYP24A1 activity relative to reference calcitriol and FGF23.
+    pub fn catabolism_factor(&self) -> f64 {
+        0.5 * (normalized_stimulation(
+            self.calcitriol_pg_ml,
+            REFERENCE_CALCITRIOL_PG_ML,
+            CYP24_HILL,
+        ) + normalized_stimulation(self.fgf23_pg_ml, REFERENCE_FGF23_PG_ML, CYP24_HILL))
+    }
+
+    pub fn calcidiol_ng_ml(&self) -> f64 {
+        // 25(OH)D: 1 ng/mL = 2.496 nmol/L.
+        self.calcidiol_nmol_l / 2.496
+    }
+
+    pub fn is_deficient(&self) -> bool {
+        self.calcidiol_nmol_l < DEFICIENT_CALCIDIOL_NMOL_L
+    }
+
+    pub fn step(&mut self, dt_hours: f64) {
+        let dt_days = dt_hours / 24.0;
+        let isomerised =
+            self.previtamin_d3_ug * (1.0 - (-isomerisation_rate_per_hour() * dt_hours).exp());
+        self.previtamin_d3_ug += self.skin_synthesis_ug_per_day() * dt_days - isomerised;
+
+        let hepatic = self.hepatic_25_hydroxylation_ug_per_day();
+        let other = OTHER_CLEARANCE_PER_DAY * self.cholecalciferol_ug;
+        let to_adipose = ADIPOSE_UPTAKE_PER_DAY * self.cholecalciferol_ug;
+        let from_adipose = adipose_release_per_day() * self.adipose_store_ug;
+        self.cholecalciferol_ug = (self.cholecalciferol_ug
+            + isomerised
+            + (self.oral_ug_per_day + from_adipose - to_adipose - hepatic - other) * dt_days)
+            .max(0.0);
+        self.adipose_store_ug =
+            (self.adipose_store_ug + (to_adipose - from_adipose) * dt_days).max(0.0);
+
+        let clearance = LN_2 / CALCIDIOL_HALF_LIFE_DAYS * self.catabolism_factor();
+        let production = CALCIDIOL_PER_UG_HYDROXYLATED * LN_2 / CALCIDIOL_HALF_LIFE_DAYS * hepatic;
+        self.calcidiol_nmol_l = (self.calcidiol_nmol_l
+            + (production - clearance * self.calcidiol_nmol_l) * dt_days)
+            .max(0.0);
+    }
+
+    pub fn run_days(&mut self, days: f64) {
+        let steps = (days * 24.0).round() as usize;
+        for _ in 0..steps {
+            self.step(1.0);
+        }
+    }
+}
+
+impl Default for VitaminDMetabolism {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+fn isomerisation_rate_per_hour() -> f64 {
+    LN_2 / THERMAL_ISOMERISATION_HALF_LIFE_H
+}
+
+fn adipose_release_per_day() -> f64 {
+    LN_2 / ADIPOSE_RELEASE_HALF_LIFE_DAYS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_healthy_steady_state() {
+        let mut pathway = VitaminDMetabolism::new_healthy();
+        let start = pathway.calcidiol_nmol_l;
+        assert!((start - REFERENCE_CALCIDIOL_NMOL_L).abs() < 2.0, "{start}");
+        pathway.run_days(60.0);
+        assert!((pathway.calcidiol_nmol_l - start).abs() < 0.5);
+        assert!((pathway.calcidiol_ng_ml() - 30.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_winter_depletes_stores_slowly() {
+        let mut pathway = VitaminDMetabolism::new_healthy();
+        pathway.set_sun_exposure(0.0, 0.0).unwrap();
+        pathway.set_supplement(0.0).unwrap();
+        pathway.run_days(30.0);
+        // Adipose release holds 25(OH)D well above what its own half-life
+        // alone would leave.
+        assert!(pathway.calcidiol_nmol_l > 0.5 * REFERENCE_CALCIDIOL_NMOL_L);
+        pathway.run_days(150.0);
+        assert!(pathway.is_deficient(), "{}", pathway.calcidiol_nmol_l);
+        assert!(pathway.set_sun_exposure(1.0, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_supplementation_dose_response_saturates() {
+        let steady = |ug: f64| {
+            let mut pathway = VitaminDMetabolism::new_healthy();
+            pathway.set_sun_exposure(0.0, 0.0).unwrap();
+            pathway.set_supplement(ug).unwrap();
+            pathway.settle();
+            pathway.calcidiol_nmol_l
+        };
+        // 1000 IU/day (25 µg) lifts a sun-deprived adult by ~17 nmol/L.
+        assert!((steady(25.0) - steady(0.0) - 17.5).abs() < 1.0);
+        let low = steady(100.0) - steady(0.0);
+        let high = steady(1000.0) - steady(900.0);
+        assert!(high < low);
+        let mut pathway = VitaminDMetabolism::new_healthy();
+        assert!(pathway.set_supplement(-1.0).is_err());
+        assert!(pathway.take_dose(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_sun_exposure_and_skin_capacity() {
+        let mut young = VitaminDMetabolism::new_healthy();
+        let mut old = young.clone();
+        old.set_skin_synthesis_capacity(0.25).unwrap();
+        young.expose_skin(5.0, 0.8).unwrap();
+        old.expose_skin(5.0, 0.8).unwrap();
+        let baseline = VitaminDMetabolism::new_healthy().calcidiol_nmol_l;
+        young.run_days(14.0);
+        old.run_days(14.0);
+        assert!(young.calcidiol_nmol_l > old.calcidiol_nmol_l);
+        assert!(old.calcidiol_nmol_l < baseline + 0.5 * (young.calcidiol_nmol_l - baseline));
+        // Doses past photoequilibrium add little.
+        let fresh = VitaminDMetabolism::new_healthy();
+        let ratio = fresh.previtamin_yield_ug(20.0, 1.0) / fresh.previtamin_yield_ug(5.0, 1.0);
+        assert!(ratio < 1.2);
+    }
+}
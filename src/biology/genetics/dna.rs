@@ -1,3 +1,5 @@
+use super::rna::{MRnaSequence, Ribonucleotide};
+use crate::biology::{BiologyError, BiologyResult};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -36,6 +38,51 @@ impl Nucleotide {
             _ => None,
         }
     }
+
+    pub fn transcribed(&self) -> Ribonucleotide {
+        match self {
+            Nucleotide::Adenine => Ribonucleotide::Adenine,
+            Nucleotide::Thymine => Ribonucleotide::Uracil,
+            Nucleotide::Guanine => Ribonucleotide::Guanine,
+            Nucleotide::Cytosine => Ribonucleotide::Cytosine,
+        }
+    }
+}
+
+// Point and small indel mutations, positions 0-based on the coding strand.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Mutation {
+    Substitution {
+        position: usize,
+        base: Nucleotide,
+    },
+    Insertion {
+        position: usize,
+        bases: Vec<Nucleotide>,
+    },
+    Deletion {
+        position: usize,
+        length: usize,
+    },
+}
+
+impl Mutation {
+    pub fn position(&self) -> usize {
+        match self {
+            Mutation::Substitution { position, .. }
+            | Mutation::Insertion { position, .. }
+            | Mutation::Deletion { position, .. } => *position,
+        }
+    }
+
+    // Net change in sequence length.
+    pub fn length_change(&self) -> isize {
+        match self {
+            Mutation::Substitution { .. } => 0,
+            Mutation::Insertion { bases, .. } => bases.len() as isize,
+            Mutation::Deletion { length, .. } => -(*length as isize),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -48,11 +95,13 @@ impl DNASequence {
         DNASequence { sequence }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         let sequence: Option<Vec<Nucleotide>> = s.chars().map(Nucleotide::from_char).collect();
         sequence.map(DNASequence::new)
     }
 
+    #[allow(clippy::inherent_to_string)]
     pub fn to_string(&self) -> String {
         self.sequence.iter().map(|n| n.to_char()).collect()
     }
@@ -91,6 +140,50 @@ impl DNASequence {
         }
     }
 
+    pub fn nucleotides(&self) -> &[Nucleotide] {
+        &self.sequence
+    }
+
+    // Reads this sequence as the coding strand: the mRNA carries the same
+    // bases with U for T.
+    pub fn transcribe(&self) -> MRnaSequence {
+        MRnaSequence::new(self.sequence.iter().map(|n| n.transcribed()).collect())
+    }
+
+    pub fn mutate(&self, mutation: &Mutation) -> BiologyResult<Self> {
+        let mut sequence = self.sequence.clone();
+        match mutation {
+            Mutation::Substitution { position, base } => {
+                let slot = sequence.get_mut(*position).ok_or_else(|| {
+                    BiologyError::InvalidValue(format!("position {} is out of range", position))
+                })?;
+                *slot = *base;
+            }
+            Mutation::Insertion { position, bases } => {
+                if *position > sequence.len() {
+                    return Err(BiologyError::InvalidValue(format!(
+                        "position {} is out of range",
+                        position
+                    )));
+                }
+                sequence.splice(*position..*position, bases.iter().copied());
+            }
+            Mutation::Deletion { position, length } => {
+                let end = position
+                    .checked_add(*length)
+                    .filter(|end| *end <= sequence.len())
+                    .ok_or_else(|| {
+                        BiologyError::InvalidValue(format!(
+                            "deletion of {} bases at {} is out of range",
+                            length, position
+                        ))
+                    })?;
+                sequence.drain(*position..end);
+            }
+        }
+        Ok(DNASequence { sequence })
+    }
+
     pub fn gc_content(&self) -> f64 {
         if self.sequence.is_empty() {
             return 0.0;
@@ -134,6 +227,38 @@ mod tests {
         assert_eq!(seq.gc_content(), 0.5);
     }
 
+    #[test]
+    fn test_mutations() {
+        let seq = DNASequence::from_str("ATGCATGC").unwrap();
+        let snp = Mutation::Substitution {
+            position: 1,
+            base: Nucleotide::Cytosine,
+        };
+        assert_eq!(seq.mutate(&snp).unwrap().to_string(), "ACGCATGC");
+        let insertion = Mutation::Insertion {
+            position: 8,
+            bases: vec![Nucleotide::Adenine; 2],
+        };
+        assert_eq!(seq.mutate(&insertion).unwrap().to_string(), "ATGCATGCAA");
+        let deletion = Mutation::Deletion {
+            position: 2,
+            length: 3,
+        };
+        assert_eq!(seq.mutate(&deletion).unwrap().to_string(), "ATTGC");
+        assert_eq!(deletion.length_change(), -3);
+        let too_long = Mutation::Deletion {
+            position: 6,
+            length: 3,
+        };
+        assert!(seq.mutate(&too_long).is_err());
+        let overflowing = Mutation::Deletion {
+            position: 2,
+            length: usize::MAX,
+        };
+        assert!(seq.mutate(&overflowing).is_err());
+        assert_eq!(seq.transcribe().to_string(), "AUGCAUGC");
+    }
+
     #[test]
     fn test_slice() {
         let seq = DNASequence::from_str("ATGCATGC").unwrap();
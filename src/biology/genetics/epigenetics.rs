@@ -0,0 +1,270 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Mean-field promoter chromatin with writer/eraser kinetics. Each mark
+// recruits its own writer and the eraser of the opposing mark, which gives
+// the bistable, heritable states of epigenetic memory.
+// Dodd IB et al. (2007) Cell 129:813-822, PMID 17512413
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EpigeneticKinetics {
+    pub de_novo_methylation_per_day: f64,
+    pub demethylation_per_day: f64,
+    pub acetylation_per_day: f64,
+    pub deacetylation_per_day: f64,
+    pub recruitment_strength: f64,
+    pub recruitment_half_saturation: f64,
+    pub maintenance_fidelity: f64,
+}
+
+impl EpigeneticKinetics {
+    // CpG maintenance methylation is ~95-99% faithful per replication.
+    // Riggs AD, Xiong Z (2004) Proc Natl Acad Sci USA 101:4-5, PMID 14695893
+    pub fn somatic_default() -> Self {
+        Self {
+            de_novo_methylation_per_day: 0.05,
+            demethylation_per_day: 0.05,
+            acetylation_per_day: 0.5,
+            deacetylation_per_day: 0.5,
+            recruitment_strength: 8.0,
+            recruitment_half_saturation: 0.5,
+            maintenance_fidelity: 0.97,
+        }
+    }
+
+    fn recruitment(&self, mark: f64) -> f64 {
+        let k2 = self.recruitment_half_saturation * self.recruitment_half_saturation;
+        1.0 + self.recruitment_strength * mark * mark / (k2 + mark * mark)
+    }
+}
+
+impl Default for EpigeneticKinetics {
+    fn default() -> Self {
+        Self::somatic_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChromatinState {
+    Open,
+    Poised,
+    Silenced,
+}
+
+// Fractions of promoter CpGs methylated and nucleosomes acetylated.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PromoterState {
+    pub methylation: f64,
+    pub acetylation: f64,
+}
+
+impl PromoterState {
+    pub fn new(methylation: f64, acetylation: f64) -> BiologyResult<Self> {
+        if !(0.0..=1.0).contains(&methylation) || !(0.0..=1.0).contains(&acetylation) {
+            return Err(BiologyError::InvalidValue(
+                "promoter mark fractions must lie in [0, 1]".to_string(),
+            ));
+        }
+        Ok(Self {
+            methylation,
+            acetylation,
+        })
+    }
+
+    pub fn open() -> Self {
+        Self {
+            methylation: 0.05,
+            acetylation: 0.9,
+        }
+    }
+
+    // Unmethylated but hypoacetylated, as lineage genes in progenitors.
+    pub fn poised() -> Self {
+        Self {
+            methylation: 0.1,
+            acetylation: 0.1,
+        }
+    }
+
+    pub fn silenced() -> Self {
+        Self {
+            methylation: 0.9,
+            acetylation: 0.05,
+        }
+    }
+
+    // Acetylation is permissive; CpG methylation blocks factor binding.
+    pub fn transcription_gate(&self) -> f64 {
+        (self.acetylation * (1.0 - self.methylation)).clamp(0.0, 1.0)
+    }
+
+    pub fn chromatin_state(&self) -> ChromatinState {
+        if self.methylation > 0.5 {
+            ChromatinState::Silenced
+        } else if self.acetylation > 0.5 {
+            ChromatinState::Open
+        } else {
+            ChromatinState::Poised
+        }
+    }
+
+    // `writer_signal` is transcription-factor or stimulus recruitment of HATs
+    // (0 = none); `hdac_inhibition` scales eraser-blocking drugs such as HDAC
+    // inhibitors (0 = none, 1 = complete).
+    pub fn step(
+        &mut self,
+        kinetics: &EpigeneticKinetics,
+        writer_signal: f64,
+        hdac_inhibition: f64,
+        dt_days: f64,
+    ) {
+        let m = self.methylation;
+        let a = self.acetylation;
+        let signal = writer_signal.max(0.0);
+        let hdac = 1.0 - hdac_inhibition.clamp(0.0, 1.0);
+
+        let methylate =
+            kinetics.de_novo_methylation_per_day * kinetics.recruitment(m) * (1.0 - a) * (1.0 - m);
+        let demethylate = kinetics.demethylation_per_day * kinetics.recruitment(a) * m;
+        let acetylate = kinetics.acetylation_per_day
+            * (signal + kinetics.recruitment(a) - 1.0)
+            * (1.0 - m)
+            * (1.0 - a);
+        let deacetylate = kinetics.deacetylation_per_day * hdac * kinetics.recruitment(m) * a;
+
+        self.methylation = (m + (methylate - demethylate) * dt_days).clamp(0.0, 1.0);
+        self.acetylation = (a + (acetylate - deacetylate) * dt_days).clamp(0.0, 1.0);
+    }
+
+    // Replication dilutes marks the maintenance machinery fails to copy.
+    pub fn divide(&mut self, kinetics: &EpigeneticKinetics) {
+        self.methylation *= kinetics.maintenance_fidelity.clamp(0.0, 1.0);
+        self.acetylation *= 0.5 + 0.5 * kinetics.maintenance_fidelity.clamp(0.0, 1.0);
+    }
+
+    // 5-azacytidine-like DNMT inhibition: sites lost on replication.
+    pub fn apply_demethylating_agent(&mut self, fraction_removed: f64) {
+        self.methylation *= 1.0 - fraction_removed.clamp(0.0, 1.0);
+    }
+}
+
+impl Default for PromoterState {
+    fn default() -> Self {
+        Self::silenced()
+    }
+}
+
+// Age-related methylation drift: CpG-island promoters (polycomb targets in
+// particular) slowly gain de novo methylation while CpG-poor promoters lose
+// it to imperfect maintenance; histone acetylation erodes alongside.
+// Teschendorff AE et al. (2010) Genome Res 20:440-446, PMID 20219944
+// Heyn H et al. (2012) Proc Natl Acad Sci USA 109:10522-10527, PMID 22689993
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EpigeneticDrift {
+    pub island_gain_per_year: f64,
+    pub non_island_loss_per_year: f64,
+    pub acetylation_loss_per_year: f64,
+}
+
+impl EpigeneticDrift {
+    pub fn human_default() -> Self {
+        Self {
+            island_gain_per_year: 0.005,
+            non_island_loss_per_year: 0.003,
+            acetylation_loss_per_year: 0.002,
+        }
+    }
+
+    pub fn apply(&self, promoter: &mut PromoterState, cpg_island: bool, years: f64) {
+        let years = years.max(0.0);
+        if cpg_island {
+            let unmethylated =
+                (1.0 - promoter.methylation) * (-self.island_gain_per_year * years).exp();
+            promoter.methylation = 1.0 - unmethylated;
+        } else {
+            promoter.methylation *= (-self.non_island_loss_per_year * years).exp();
+        }
+        promoter.acetylation *= (-self.acetylation_loss_per_year * years).exp();
+    }
+}
+
+impl Default for EpigeneticDrift {
+    fn default() -> Self {
+        Self::human_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(promoter: &mut PromoterState, kinetics: &EpigeneticKinetics, signal: f64, days: usize) {
+        for _ in 0..days * 10 {
+            promoter.step(kinetics, signal, 0.0, 0.1);
+        }
+    }
+
+    #[test]
+    fn test_gate_ordering() {
+        assert!(PromoterState::open().transcription_gate() > 0.8);
+        assert!(PromoterState::silenced().transcription_gate() < 0.01);
+        assert!(PromoterState::new(1.2, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_both_states_are_self_sustaining() {
+        let kinetics = EpigeneticKinetics::default();
+        let mut open = PromoterState::open();
+        let mut closed = PromoterState::silenced();
+        run(&mut open, &kinetics, 0.0, 200);
+        run(&mut closed, &kinetics, 0.0, 200);
+        assert_eq!(open.chromatin_state(), ChromatinState::Open);
+        assert_eq!(closed.chromatin_state(), ChromatinState::Silenced);
+    }
+
+    #[test]
+    fn test_transient_signal_is_remembered() {
+        let kinetics = EpigeneticKinetics::default();
+        let mut promoter = PromoterState::new(0.3, 0.1).unwrap();
+        run(&mut promoter, &kinetics, 4.0, 10);
+        run(&mut promoter, &kinetics, 0.0, 120);
+        assert_eq!(promoter.chromatin_state(), ChromatinState::Open);
+    }
+
+    #[test]
+    fn test_replication_with_low_fidelity_erodes_silencing() {
+        let leaky = EpigeneticKinetics {
+            maintenance_fidelity: 0.7,
+            ..EpigeneticKinetics::default()
+        };
+        let mut promoter = PromoterState::silenced();
+        for _ in 0..10 {
+            promoter.divide(&leaky);
+        }
+        assert!(promoter.methylation < 0.1);
+    }
+
+    #[test]
+    fn test_hdac_inhibition_opens_chromatin() {
+        let kinetics = EpigeneticKinetics::default();
+        let mut treated = PromoterState::new(0.2, 0.2).unwrap();
+        let mut control = treated;
+        for _ in 0..300 {
+            treated.step(&kinetics, 0.5, 0.9, 0.1);
+            control.step(&kinetics, 0.5, 0.0, 0.1);
+        }
+        assert!(treated.transcription_gate() > control.transcription_gate());
+    }
+
+    #[test]
+    fn test_drift_methylates_islands_and_demethylates_the_rest() {
+        let drift = EpigeneticDrift::default();
+        let mut island = PromoterState::open();
+        let mut non_island = PromoterState::silenced();
+        let young_gate = island.transcription_gate();
+        drift.apply(&mut island, true, 50.0);
+        drift.apply(&mut non_island, false, 50.0);
+        assert!(island.methylation > 0.2 && island.methylation < 0.5);
+        assert!(island.transcription_gate() < 0.8 * young_gate);
+        assert!(non_island.methylation < 0.9 && non_island.methylation > 0.7);
+    }
+}
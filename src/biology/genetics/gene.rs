@@ -0,0 +1,383 @@
+use super::dna::{DNASequence, Mutation, Nucleotide};
+use super::epigenetics::{EpigeneticDrift, PromoterState};
+use super::gene_regulation::RegulatedGene;
+use super::genetic_code::translate;
+use super::protein::ProteinSequence;
+use super::rna::MRnaSequence;
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Half-open [start, end) span on the gene's genomic sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Exon {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Exon {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end == self.start
+    }
+
+    pub fn contains(&self, position: usize) -> bool {
+        (self.start..self.end).contains(&position)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MutationEffect {
+    Intronic,
+    Silent,
+    Missense,
+    Nonsense,
+    StopLoss,
+    InFrameIndel,
+    Frameshift,
+}
+
+// CpG island criteria: GC > 50% and observed/expected CpG > 0.6.
+// Gardiner-Garden M, Frommer M (1987) J Mol Biol 196:261-282, PMID 3656447
+const CPG_ISLAND_MIN_GC: f64 = 0.5;
+const CPG_ISLAND_MIN_OBSERVED_EXPECTED: f64 = 0.6;
+
+// A protein-coding gene: upstream promoter and the genomic coding strand
+// with its exons. Introns are spliced out on transcription. `chromatin`
+// carries the promoter's CpG methylation and histone acetylation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gene {
+    pub name: String,
+    pub promoter: DNASequence,
+    pub sequence: DNASequence,
+    pub exons: Vec<Exon>,
+    pub chromatin: PromoterState,
+}
+
+impl Gene {
+    pub fn new(
+        name: &str,
+        promoter: DNASequence,
+        sequence: DNASequence,
+        exons: Vec<Exon>,
+    ) -> BiologyResult<Self> {
+        let mut gene = Self {
+            name: name.to_string(),
+            promoter,
+            sequence,
+            exons,
+            chromatin: PromoterState::silenced(),
+        };
+        gene.validate()?;
+        // Somatic CpG-island promoters are kept unmethylated; CpG-poor
+        // promoters are methylated unless tissue factors open them.
+        if gene.has_cpg_island() {
+            gene.chromatin = PromoterState::open();
+        }
+        Ok(gene)
+    }
+
+    pub fn with_chromatin(mut self, chromatin: PromoterState) -> Self {
+        self.chromatin = chromatin;
+        self
+    }
+
+    pub fn single_exon(
+        name: &str,
+        promoter: DNASequence,
+        sequence: DNASequence,
+    ) -> BiologyResult<Self> {
+        let exon = Exon::new(0, sequence.len());
+        Self::new(name, promoter, sequence, vec![exon])
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        if self.exons.is_empty() {
+            return Err(BiologyError::InvalidParameter(format!(
+                "gene {} has no exons",
+                self.name
+            )));
+        }
+        let mut previous_end = 0;
+        for exon in &self.exons {
+            if exon.is_empty() || exon.start < previous_end || exon.end > self.sequence.len() {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "gene {} exons must be non-empty, ordered, non-overlapping and in range",
+                    self.name
+                )));
+            }
+            previous_end = exon.end;
+        }
+        Ok(())
+    }
+
+    // TATA box consensus TATAWAW (W = A/T), ~25-30 bp upstream of the
+    // transcription start in a minority of human promoters.
+    // Bucher P (1990) J Mol Biol 212:563-578, PMID 2329577
+    pub fn has_tata_box(&self) -> bool {
+        use Nucleotide::{Adenine as A, Thymine as T};
+        let is_w = |n: Nucleotide| matches!(n, A | T);
+        self.promoter.nucleotides().windows(7).any(|w| {
+            w[0] == T
+                && w[1] == A
+                && w[2] == T
+                && w[3] == A
+                && is_w(w[4])
+                && w[5] == A
+                && is_w(w[6])
+        })
+    }
+
+    pub fn cpg_observed_expected(&self) -> f64 {
+        let bases = self.promoter.nucleotides();
+        let count = |base: Nucleotide| bases.iter().filter(|&&b| b == base).count() as f64;
+        let expected = count(Nucleotide::Cytosine) * count(Nucleotide::Guanine);
+        if expected == 0.0 {
+            return 0.0;
+        }
+        let cpg = bases
+            .windows(2)
+            .filter(|w| w[0] == Nucleotide::Cytosine && w[1] == Nucleotide::Guanine)
+            .count() as f64;
+        cpg * bases.len() as f64 / expected
+    }
+
+    pub fn has_cpg_island(&self) -> bool {
+        self.promoter.gc_content() > CPG_ISLAND_MIN_GC
+            && self.cpg_observed_expected() > CPG_ISLAND_MIN_OBSERVED_EXPECTED
+    }
+
+    pub fn transcription_gate(&self) -> f64 {
+        self.chromatin.transcription_gate()
+    }
+
+    pub fn apply_drift(&mut self, drift: &EpigeneticDrift, years: f64) {
+        let cpg_island = self.has_cpg_island();
+        drift.apply(&mut self.chromatin, cpg_island, years);
+    }
+
+    // GRN node carrying this gene's chromatin and island status.
+    pub fn regulated(&self, max_transcription_per_day: f64, decay_per_day: f64) -> RegulatedGene {
+        RegulatedGene::new(&self.name, max_transcription_per_day, decay_per_day)
+            .with_promoter(self.chromatin)
+            .with_cpg_island(self.has_cpg_island())
+    }
+
+    pub fn coding_sequence(&self) -> DNASequence {
+        let nucleotides = self.sequence.nucleotides();
+        DNASequence::new(
+            self.exons
+                .iter()
+                .flat_map(|exon| nucleotides[exon.start..exon.end].iter().copied())
+                .collect(),
+        )
+    }
+
+    // Spliced mature mRNA.
+    pub fn transcribe(&self) -> MRnaSequence {
+        self.coding_sequence().transcribe()
+    }
+
+    pub fn translate(&self) -> BiologyResult<ProteinSequence> {
+        translate(&self.transcribe())
+    }
+
+    fn is_exonic(&self, mutation: &Mutation) -> bool {
+        let start = mutation.position();
+        let end = match mutation {
+            Mutation::Deletion { length, .. } => start + length,
+            _ => start + 1,
+        };
+        self.exons
+            .iter()
+            .any(|exon| start < exon.end && end > exon.start)
+    }
+
+    // Applies the mutation to the genomic sequence, shifting exon
+    // boundaries downstream of an indel.
+    pub fn mutate(&self, mutation: &Mutation) -> BiologyResult<Self> {
+        let sequence = self.sequence.mutate(mutation)?;
+        let position = mutation.position();
+        let mut exons = self.exons.clone();
+        match mutation {
+            Mutation::Substitution { .. } => {}
+            Mutation::Insertion { bases, .. } => {
+                let n = bases.len();
+                for exon in &mut exons {
+                    if position <= exon.start {
+                        exon.start += n;
+                        exon.end += n;
+                    } else if position < exon.end {
+                        exon.end += n;
+                    }
+                }
+            }
+            Mutation::Deletion { length, .. } => {
+                let deleted_end = position + length;
+                for exon in &mut exons {
+                    if deleted_end <= exon.start {
+                        exon.start -= length;
+                        exon.end -= length;
+                    } else if position < exon.end {
+                        if position < exon.start || deleted_end > exon.end {
+                            return Err(BiologyError::InvalidValue(format!(
+                                "deletion {}..{} spans a splice junction",
+                                position, deleted_end
+                            )));
+                        }
+                        exon.end -= length;
+                    }
+                }
+            }
+        }
+        Ok(
+            Self::new(&self.name, self.promoter.clone(), sequence, exons)?
+                .with_chromatin(self.chromatin),
+        )
+    }
+
+    pub fn classify(&self, mutation: &Mutation) -> BiologyResult<MutationEffect> {
+        if !self.is_exonic(mutation) {
+            return Ok(MutationEffect::Intronic);
+        }
+        let reference = self.translate()?;
+        let shift = mutation.length_change();
+        if shift % 3 != 0 {
+            return Ok(MutationEffect::Frameshift);
+        }
+        let variant = match self.mutate(mutation)?.translate() {
+            Ok(protein) => protein,
+            Err(_) => return Ok(MutationEffect::StopLoss),
+        };
+        Ok(if shift != 0 {
+            MutationEffect::InFrameIndel
+        } else if variant == reference {
+            MutationEffect::Silent
+        } else if variant.len() < reference.len() {
+            MutationEffect::Nonsense
+        } else if variant.len() > reference.len() {
+            MutationEffect::StopLoss
+        } else {
+            MutationEffect::Missense
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_exon_gene() -> Gene {
+        // Exon 1: ATG AAA G | intron GTAAGT...AG | exon 2: GC TGG TAA
+        let promoter = DNASequence::from_str("GCGCTATAAAAGGCGC").unwrap();
+        let sequence = DNASequence::from_str("ATGAAAGGTAAGTCCCAGGCTGGTAA").unwrap();
+        Gene::new(
+            "TEST1",
+            promoter,
+            sequence,
+            vec![Exon::new(0, 7), Exon::new(18, 26)],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_splicing_and_translation() {
+        let gene = two_exon_gene();
+        assert!(gene.has_tata_box());
+        assert_eq!(gene.transcribe().to_string(), "AUGAAAGGCUGGUAA");
+        assert_eq!(gene.translate().unwrap().to_string(), "MKGW");
+        let overlapping = Gene::new(
+            "BAD",
+            gene.promoter.clone(),
+            gene.sequence.clone(),
+            vec![Exon::new(0, 10), Exon::new(8, 20)],
+        );
+        assert!(overlapping.is_err());
+    }
+
+    #[test]
+    fn test_mutation_effects() {
+        let gene = two_exon_gene();
+        let snp = |position, base| Mutation::Substitution { position, base };
+        // AAA→AAG stays lysine; AAA→GAA is glutamate; AAA→TAA stops.
+        assert_eq!(
+            gene.classify(&snp(5, Nucleotide::Guanine)).unwrap(),
+            MutationEffect::Silent
+        );
+        assert_eq!(
+            gene.classify(&snp(3, Nucleotide::Guanine)).unwrap(),
+            MutationEffect::Missense
+        );
+        assert_eq!(
+            gene.classify(&snp(3, Nucleotide::Thymine)).unwrap(),
+            MutationEffect::Nonsense
+        );
+        assert_eq!(
+            gene.classify(&snp(12, Nucleotide::Adenine)).unwrap(),
+            MutationEffect::Intronic
+        );
+        let one_base = Mutation::Deletion {
+            position: 4,
+            length: 1,
+        };
+        assert_eq!(
+            gene.classify(&one_base).unwrap(),
+            MutationEffect::Frameshift
+        );
+    }
+
+    #[test]
+    fn test_indel_shifts_downstream_exons() {
+        let gene = two_exon_gene();
+        let codon_insert = Mutation::Insertion {
+            position: 3,
+            bases: DNASequence::from_str("CCC").unwrap().nucleotides().to_vec(),
+        };
+        let mutant = gene.mutate(&codon_insert).unwrap();
+        assert_eq!(mutant.exons, vec![Exon::new(0, 10), Exon::new(21, 29)]);
+        assert_eq!(mutant.translate().unwrap().to_string(), "MPKGW");
+        assert_eq!(
+            gene.classify(&codon_insert).unwrap(),
+            MutationEffect::InFrameIndel
+        );
+        let across_junction = Mutation::Deletion {
+            position: 5,
+            length: 4,
+        };
+        assert!(gene.mutate(&across_junction).is_err());
+    }
+
+    #[test]
+    fn test_promoter_chromatin_follows_cpg_content() {
+        let gene = two_exon_gene();
+        assert!(gene.has_cpg_island());
+        assert!(gene.transcription_gate() > 0.8);
+        let cpg_poor = Gene::single_exon(
+            "POOR",
+            DNASequence::from_str("ATATAAAAGGCATTCA").unwrap(),
+            gene.sequence.clone(),
+        )
+        .unwrap();
+        assert!(!cpg_poor.has_cpg_island());
+        assert!(cpg_poor.transcription_gate() < 0.01);
+
+        let node = gene.regulated(2.0, 0.5);
+        assert!(node.cpg_island);
+        assert_eq!(node.promoter, gene.chromatin);
+
+        let mut aged = gene.clone();
+        aged.apply_drift(&EpigeneticDrift::default(), 60.0);
+        assert!(aged.chromatin.methylation > gene.chromatin.methylation);
+        let snp = Mutation::Substitution {
+            position: 5,
+            base: Nucleotide::Guanine,
+        };
+        assert_eq!(aged.mutate(&snp).unwrap().chromatin, aged.chromatin);
+    }
+}
@@ -0,0 +1,281 @@
+use super::epigenetics::{EpigeneticDrift, EpigeneticKinetics, PromoterState};
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RegulatoryInput {
+    pub source: usize,
+    pub activating: bool,
+    pub half_max: f64,
+    pub hill: f64,
+}
+
+impl RegulatoryInput {
+    fn response(&self, level: f64) -> f64 {
+        let x = (level.max(0.0) / self.half_max).powf(self.hill);
+        if self.activating {
+            x / (1.0 + x)
+        } else {
+            1.0 / (1.0 + x)
+        }
+    }
+}
+
+// Expression in arbitrary units with dx/dt = k·gate·f(inputs) − γ·x, where the
+// promoter chromatin gate scales the achievable transcription rate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegulatedGene {
+    pub name: String,
+    pub max_transcription_per_day: f64,
+    pub decay_per_day: f64,
+    pub basal_fraction: f64,
+    pub expression: f64,
+    pub promoter: PromoterState,
+    pub cpg_island: bool,
+    pub inputs: Vec<RegulatoryInput>,
+}
+
+impl RegulatedGene {
+    pub fn new(name: &str, max_transcription_per_day: f64, decay_per_day: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            max_transcription_per_day,
+            decay_per_day,
+            basal_fraction: 0.01,
+            expression: 0.0,
+            promoter: PromoterState::silenced(),
+            cpg_island: false,
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn with_promoter(mut self, promoter: PromoterState) -> Self {
+        self.promoter = promoter;
+        self
+    }
+
+    pub fn with_cpg_island(mut self, cpg_island: bool) -> Self {
+        self.cpg_island = cpg_island;
+        self
+    }
+
+    pub fn activated_by(mut self, source: usize, half_max: f64, hill: f64) -> Self {
+        self.inputs.push(RegulatoryInput {
+            source,
+            activating: true,
+            half_max,
+            hill,
+        });
+        self
+    }
+
+    pub fn repressed_by(mut self, source: usize, half_max: f64, hill: f64) -> Self {
+        self.inputs.push(RegulatoryInput {
+            source,
+            activating: false,
+            half_max,
+            hill,
+        });
+        self
+    }
+
+    pub fn steady_state_ceiling(&self) -> f64 {
+        self.max_transcription_per_day / self.decay_per_day
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GeneRegulatoryNetwork {
+    pub genes: Vec<RegulatedGene>,
+    pub kinetics: EpigeneticKinetics,
+    pub hdac_inhibition: f64,
+    pub drift: EpigeneticDrift,
+    // Chronological age the promoters were last drifted to.
+    pub aged_to_years: Option<f64>,
+}
+
+impl GeneRegulatoryNetwork {
+    pub fn new(kinetics: EpigeneticKinetics) -> Self {
+        Self {
+            genes: Vec::new(),
+            kinetics,
+            hdac_inhibition: 0.0,
+            drift: EpigeneticDrift::default(),
+            aged_to_years: None,
+        }
+    }
+
+    pub fn add_gene(&mut self, gene: RegulatedGene) -> usize {
+        self.genes.push(gene);
+        self.genes.len() - 1
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        for gene in &self.genes {
+            if gene.decay_per_day <= 0.0 || gene.max_transcription_per_day < 0.0 {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "{}: rates must be positive",
+                    gene.name
+                )));
+            }
+            if let Some(input) = gene.inputs.iter().find(|i| i.source >= self.genes.len()) {
+                return Err(BiologyError::InvalidState(format!(
+                    "{}: regulator index {} out of range",
+                    gene.name, input.source
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.genes.iter().position(|g| g.name == name)
+    }
+
+    pub fn expression(&self, index: usize) -> f64 {
+        self.genes.get(index).map(|g| g.expression).unwrap_or(0.0)
+    }
+
+    // `external` carries per-gene stimulus (e.g. a differentiation cue or
+    // PAMP) that adds to transcription-factor drive; missing entries are 0.
+    pub fn step(&mut self, external: &[f64], dt_days: f64) {
+        let levels: Vec<f64> = self.genes.iter().map(|g| g.expression).collect();
+        for (i, gene) in self.genes.iter_mut().enumerate() {
+            let stimulus = external.get(i).copied().unwrap_or(0.0).max(0.0);
+            let activators: Vec<f64> = gene
+                .inputs
+                .iter()
+                .filter(|input| input.activating)
+                .map(|input| input.response(levels[input.source]))
+                .collect();
+            let repression: f64 = gene
+                .inputs
+                .iter()
+                .filter(|input| !input.activating)
+                .map(|input| input.response(levels[input.source]))
+                .product();
+            let activation = if activators.is_empty() {
+                1.0
+            } else {
+                activators.iter().cloned().fold(0.0, f64::max)
+            };
+            let drive = (activation * repression + stimulus).min(1.0);
+
+            // Bound activators recruit histone acetyltransferases.
+            let writer_signal = if activators.is_empty() {
+                stimulus
+            } else {
+                drive
+            };
+            gene.promoter
+                .step(&self.kinetics, writer_signal, self.hdac_inhibition, dt_days);
+
+            let rate = gene.max_transcription_per_day
+                * gene.promoter.transcription_gate()
+                * (gene.basal_fraction + (1.0 - gene.basal_fraction) * drive);
+            gene.expression = (gene.expression
+                + (rate - gene.decay_per_day * gene.expression) * dt_days)
+                .max(0.0);
+        }
+    }
+
+    pub fn apply_drift(&mut self, years: f64) {
+        for gene in &mut self.genes {
+            self.drift.apply(&mut gene.promoter, gene.cpg_island, years);
+        }
+    }
+
+    pub fn divide(&mut self) {
+        for gene in &mut self.genes {
+            gene.promoter.divide(&self.kinetics);
+            gene.expression *= 0.5;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::genetics::epigenetics::ChromatinState;
+
+    // Master regulator with positive autoregulation, as in MyoD-driven
+    // myogenic commitment.
+    fn master_regulator_network() -> (GeneRegulatoryNetwork, usize) {
+        let mut grn = GeneRegulatoryNetwork::new(EpigeneticKinetics::default());
+        let master = grn.add_gene(
+            RegulatedGene::new("master", 2.0, 0.5)
+                .with_promoter(PromoterState::poised())
+                .activated_by(0, 1.0, 2.0),
+        );
+        (grn, master)
+    }
+
+    fn run(grn: &mut GeneRegulatoryNetwork, external: &[f64], days: usize) {
+        for _ in 0..days * 10 {
+            grn.step(external, 0.1);
+        }
+    }
+
+    #[test]
+    fn test_silenced_promoter_blocks_expression() {
+        let (mut grn, master) = master_regulator_network();
+        grn.genes[master].expression = 3.0;
+        grn.genes[master].promoter = PromoterState::new(0.95, 0.0).unwrap();
+        run(&mut grn, &[], 30);
+        assert!(grn.expression(master) < 0.5);
+    }
+
+    #[test]
+    fn test_transient_cue_commits_cell_identity() {
+        let (mut grn, master) = master_regulator_network();
+        run(&mut grn, &[], 20);
+        assert!(grn.expression(master) < 0.1);
+        run(&mut grn, &[1.0], 10);
+        run(&mut grn, &[], 100);
+        assert!(grn.expression(master) > 2.0, "{}", grn.expression(master));
+        assert_eq!(
+            grn.genes[master].promoter.chromatin_state(),
+            ChromatinState::Open
+        );
+        for _ in 0..5 {
+            grn.divide();
+            run(&mut grn, &[], 5);
+        }
+        assert!(grn.expression(master) > 2.0);
+    }
+
+    #[test]
+    fn test_silenced_lineage_gene_resists_cue() {
+        let (mut grn, master) = master_regulator_network();
+        grn.genes[master].promoter = PromoterState::silenced();
+        run(&mut grn, &[1.0], 10);
+        run(&mut grn, &[], 50);
+        assert!(grn.expression(master) < 0.5);
+    }
+
+    #[test]
+    fn test_repressor_limits_target() {
+        let mut grn = GeneRegulatoryNetwork::new(EpigeneticKinetics::default());
+        let repressor = grn.add_gene(
+            RegulatedGene::new("repressor", 1.0, 0.5).with_promoter(PromoterState::open()),
+        );
+        let free =
+            grn.add_gene(RegulatedGene::new("free", 1.0, 0.5).with_promoter(PromoterState::open()));
+        let target = grn.add_gene(
+            RegulatedGene::new("target", 1.0, 0.5)
+                .with_promoter(PromoterState::open())
+                .repressed_by(repressor, 0.5, 2.0),
+        );
+        grn.validate().unwrap();
+        run(&mut grn, &[], 30);
+        assert!(grn.expression(target) < 0.5 * grn.expression(free));
+        assert_eq!(grn.index_of("target"), Some(target));
+    }
+
+    #[test]
+    fn test_validate_catches_bad_edges() {
+        let mut grn = GeneRegulatoryNetwork::new(EpigeneticKinetics::default());
+        grn.add_gene(RegulatedGene::new("orphan", 1.0, 0.5).activated_by(3, 1.0, 1.0));
+        assert!(grn.validate().is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use super::protein::{AminoAcid, ProteinSequence};
+use super::rna::{Codon, MRnaSequence, Ribonucleotide};
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Codons are indexed 16·first + 4·second + third with bases ordered U, C,
+// A, G, the layout of the textbook codon wheel.
+const BASE_ORDER: [Ribonucleotide; 4] = [
+    Ribonucleotide::Uracil,
+    Ribonucleotide::Cytosine,
+    Ribonucleotide::Adenine,
+    Ribonucleotide::Guanine,
+];
+// Standard genetic code (NCBI translation table 1); '*' is stop.
+const STANDARD_CODE: &[u8; 64] =
+    b"FFLLSSSSYY**CC*WLLLLPPPPHHQQRRRRIIIMTTTTNNKKSSRRVVVVAAAADDEEGGGG";
+// Homo sapiens codon usage per thousand codons, Kazusa CUTG (93,487 CDS).
+// Nakamura Y, Gojobori T, Ikemura T (2000) Nucleic Acids Res 28:292, PMID 10592250
+const HUMAN_CODON_USAGE_PER_THOUSAND: [f64; 64] = [
+    17.6, 20.3, 7.7, 12.9, 15.2, 17.7, 12.2, 4.4, 12.2, 15.3, 1.0, 0.8, 10.6, 12.6, 1.6, 13.2,
+    13.2, 19.6, 7.2, 39.6, 17.5, 19.8, 16.9, 6.9, 10.9, 15.1, 12.3, 34.2, 4.5, 10.4, 6.2, 11.4,
+    16.0, 20.8, 7.5, 22.0, 13.1, 18.9, 15.1, 6.1, 17.0, 19.1, 24.4, 31.9, 12.1, 19.5, 12.2, 12.0,
+    11.0, 14.5, 7.1, 28.1, 18.4, 27.7, 15.8, 7.4, 21.8, 25.1, 29.0, 39.6, 10.8, 22.2, 16.5, 16.5,
+];
+// Codons used at under 10% of their family's most frequent synonym.
+const RARE_CODON_ADAPTIVENESS: f64 = 0.1;
+
+fn base_index(base: Ribonucleotide) -> usize {
+    match base {
+        Ribonucleotide::Uracil => 0,
+        Ribonucleotide::Cytosine => 1,
+        Ribonucleotide::Adenine => 2,
+        Ribonucleotide::Guanine => 3,
+    }
+}
+
+fn codon_index(codon: &Codon) -> usize {
+    16 * base_index(codon[0]) + 4 * base_index(codon[1]) + base_index(codon[2])
+}
+
+fn codon_at(index: usize) -> Codon {
+    [
+        BASE_ORDER[index / 16],
+        BASE_ORDER[(index / 4) % 4],
+        BASE_ORDER[index % 4],
+    ]
+}
+
+// None for the three stop codons.
+pub fn translate_codon(codon: &Codon) -> Option<AminoAcid> {
+    AminoAcid::from_one_letter(STANDARD_CODE[codon_index(codon)] as char)
+}
+
+pub fn synonymous_codons(amino_acid: AminoAcid) -> Vec<Codon> {
+    (0..64)
+        .filter(|&i| STANDARD_CODE[i] as char == amino_acid.one_letter())
+        .map(codon_at)
+        .collect()
+}
+
+// Ribosomal scanning: translation starts at the first AUG and runs to the
+// first in-frame stop codon.
+pub fn translate(mrna: &MRnaSequence) -> BiologyResult<ProteinSequence> {
+    let start = mrna
+        .find_start_codon()
+        .ok_or_else(|| BiologyError::InvalidValue("mRNA has no AUG start codon".to_string()))?;
+    let mut residues = Vec::new();
+    for codon in mrna.codons_from(start) {
+        match translate_codon(&codon) {
+            Some(amino_acid) => residues.push(amino_acid),
+            None => return Ok(ProteinSequence::new(residues)),
+        }
+    }
+    Err(BiologyError::InvalidValue(
+        "open reading frame has no in-frame stop codon".to_string(),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CodonOptimizationReport {
+    pub codon_adaptation_index: f64,
+    pub gc_content: f64,
+    // GC at synonymous third positions, the part optimization can change.
+    pub gc3_content: f64,
+    pub uridine_fraction: f64,
+    pub rare_codon_fraction: f64,
+}
+
+// Relative adaptiveness w of each codon: its usage over that of the most
+// used synonym. CAI is the geometric mean of w over the sense codons,
+// skipping Met and Trp which have no synonyms.
+// Sharp PM, Li WH (1987) Nucleic Acids Res 15:1281-1295, PMID 3547335
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodonUsageTable {
+    pub per_thousand: Vec<f64>,
+}
+
+impl CodonUsageTable {
+    pub fn human() -> Self {
+        Self {
+            per_thousand: HUMAN_CODON_USAGE_PER_THOUSAND.to_vec(),
+        }
+    }
+
+    pub fn relative_adaptiveness(&self, codon: &Codon) -> Option<f64> {
+        let amino_acid = translate_codon(codon)?;
+        let best = synonymous_codons(amino_acid)
+            .iter()
+            .map(|c| self.per_thousand[codon_index(c)])
+            .fold(0.0, f64::max);
+        Some(self.per_thousand[codon_index(codon)] / best)
+    }
+
+    pub fn preferred_codon(&self, amino_acid: AminoAcid) -> Codon {
+        synonymous_codons(amino_acid)
+            .into_iter()
+            .max_by(|a, b| {
+                self.per_thousand[codon_index(a)].total_cmp(&self.per_thousand[codon_index(b)])
+            })
+            .expect("every amino acid has a codon")
+    }
+
+    // Most-used codon per residue, AUG-initiated and UGA-terminated.
+    pub fn optimize(&self, protein: &ProteinSequence) -> MRnaSequence {
+        let mut bases = Vec::with_capacity(3 * protein.len() + 6);
+        if protein.get(0) != Some(AminoAcid::Methionine) {
+            bases.extend(self.preferred_codon(AminoAcid::Methionine));
+        }
+        for &amino_acid in protein.residues() {
+            bases.extend(self.preferred_codon(amino_acid));
+        }
+        bases.extend([
+            Ribonucleotide::Uracil,
+            Ribonucleotide::Guanine,
+            Ribonucleotide::Adenine,
+        ]);
+        MRnaSequence::new(bases)
+    }
+
+    pub fn analyze(&self, mrna: &MRnaSequence) -> BiologyResult<CodonOptimizationReport> {
+        let start = mrna
+            .find_start_codon()
+            .ok_or_else(|| BiologyError::InvalidValue("mRNA has no AUG start codon".to_string()))?;
+        let mut log_w_sum = 0.0;
+        let mut scored = 0usize;
+        let mut rare = 0usize;
+        let mut gc3 = 0usize;
+        let mut sense = 0usize;
+        for codon in mrna.codons_from(start) {
+            let Some(amino_acid) = translate_codon(&codon) else {
+                break;
+            };
+            sense += 1;
+            if codon[2].is_gc() {
+                gc3 += 1;
+            }
+            if matches!(amino_acid, AminoAcid::Methionine | AminoAcid::Tryptophan) {
+                continue;
+            }
+            let w = self.relative_adaptiveness(&codon).unwrap_or(0.0).max(1e-6);
+            if w < RARE_CODON_ADAPTIVENESS {
+                rare += 1;
+            }
+            log_w_sum += w.ln();
+            scored += 1;
+        }
+        if sense == 0 {
+            return Err(BiologyError::InvalidValue(
+                "open reading frame is empty".to_string(),
+            ));
+        }
+        Ok(CodonOptimizationReport {
+            codon_adaptation_index: if scored > 0 {
+                (log_w_sum / scored as f64).exp()
+            } else {
+                1.0
+            },
+            gc_content: mrna.gc_content(),
+            gc3_content: gc3 as f64 / sense as f64,
+            uridine_fraction: mrna.uridine_fraction(),
+            rare_codon_fraction: if scored > 0 {
+                rare as f64 / scored as f64
+            } else {
+                0.0
+            },
+        })
+    }
+}
+
+impl Default for CodonUsageTable {
+    fn default() -> Self {
+        Self::human()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_standard_code() {
+        let stops = (0..64)
+            .filter(|&i| translate_codon(&codon_at(i)).is_none())
+            .count();
+        assert_eq!(stops, 3);
+        assert_eq!(synonymous_codons(AminoAcid::Leucine).len(), 6);
+        assert_eq!(synonymous_codons(AminoAcid::Methionine).len(), 1);
+        let sense: usize = AminoAcid::ALL
+            .iter()
+            .map(|&aa| synonymous_codons(aa).len())
+            .sum();
+        assert_eq!(sense, 61);
+    }
+
+    #[test]
+    fn test_translation_runs_from_aug_to_stop() {
+        let mrna = MRnaSequence::from_str("GCCAUGAAAUGGGGCUAAGCC").unwrap();
+        assert_eq!(translate(&mrna).unwrap().to_string(), "MKWG");
+        assert!(translate(&MRnaSequence::from_str("GCCGCC").unwrap()).is_err());
+        assert!(translate(&MRnaSequence::from_str("AUGAAA").unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_optimization_raises_cai() {
+        let table = CodonUsageTable::human();
+        // Deliberately rare synonyms: CGU (Arg), UCG (Ser), ACG (Thr).
+        let native = MRnaSequence::from_str("AUGCGUUCGACGCGUUAA").unwrap();
+        let protein = translate(&native).unwrap();
+        let optimized = table.optimize(&protein);
+        assert_eq!(translate(&optimized).unwrap(), protein);
+
+        let before = table.analyze(&native).unwrap();
+        let after = table.analyze(&optimized).unwrap();
+        assert!(before.codon_adaptation_index < 0.4);
+        assert!((after.codon_adaptation_index - 1.0).abs() < 1e-12);
+        assert_eq!(after.rare_codon_fraction, 0.0);
+        assert!(after.uridine_fraction < before.uridine_fraction);
+        assert_eq!(table.preferred_codon(AminoAcid::Leucine), codon_at(19));
+    }
+}
@@ -2,21 +2,34 @@ pub mod allele;
 pub mod ancestry;
 pub mod dietary_genetics;
 pub mod dna;
+pub mod epigenetics;
+pub mod gene;
+pub mod gene_regulation;
+pub mod genetic_code;
 pub mod genotype;
 pub mod phenotype;
 pub mod population_traits;
+pub mod protein;
+pub mod rna;
 pub mod snp;
+pub mod variants;
 
 pub use allele::{Allele, AlleleFrequency, AllelePair, AlleleType, CarrierStatus, Zygosity};
 pub use ancestry::{Ancestry, AncestryProfile};
 pub use dietary_genetics::{
     ADH1BVariant, ADORA2AGenotype, AlcoholMetabolismGenetics, BitterTasteSensitivity,
-    CYP1A2Genotype, CaffeineSensitivity, DietaryGeneticProfile, FoodSensitivity, FolateMetabolism,
+    CYP1A2Genotype, CaffeineSensitivity, DietaryGeneticProfile, FolateMetabolism, FoodSensitivity,
     GlutenSensitivity, IronAbsorption, LCTGenotype, LactoseTolerance, MTHFRGenotype,
     MetabolismSpeed, NutrientMetabolism, NutritionPlan, Omega3Conversion, SensitivitySeverity,
     SensitivityType, TAS2R38Genotype, TasteGenetics, ToleranceLevel, VitaminDMetabolism,
 };
-pub use dna::{DNASequence, Nucleotide};
+pub use dna::{DNASequence, Mutation, Nucleotide};
+pub use epigenetics::{ChromatinState, EpigeneticDrift, EpigeneticKinetics, PromoterState};
+pub use gene::{Exon, Gene, MutationEffect};
+pub use gene_regulation::{GeneRegulatoryNetwork, RegulatedGene, RegulatoryInput};
+pub use genetic_code::{
+    synonymous_codons, translate, translate_codon, CodonOptimizationReport, CodonUsageTable,
+};
 pub use genotype::{
     AncestryComponent, DiseaseRisk, Genotype, GenotypeRiskProfile, MetabolizerStatus,
     PharmacogeneticMarker, PhenotypeAssociation, Severity,
@@ -28,4 +41,9 @@ pub use phenotype::{
     WarfarinSensitivity,
 };
 pub use population_traits::{AlcoholToleranceInfo, PopulationSpecificTraits};
+pub use protein::{AminoAcid, ProteinSequence};
+pub use rna::{Codon, MRnaSequence, Ribonucleotide};
 pub use snp::SNP;
+pub use variants::{
+    GeneticVariant, GenotypeDependent, VariantCall, VariantEffect, VariantEffects, VariantRegistry,
+};
@@ -0,0 +1,147 @@
+use crate::biology::BiologyError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AminoAcid {
+    Alanine,
+    Arginine,
+    Asparagine,
+    AsparticAcid,
+    Cysteine,
+    GlutamicAcid,
+    Glutamine,
+    Glycine,
+    Histidine,
+    Isoleucine,
+    Leucine,
+    Lysine,
+    Methionine,
+    Phenylalanine,
+    Proline,
+    Serine,
+    Threonine,
+    Tryptophan,
+    Tyrosine,
+    Valine,
+}
+
+impl AminoAcid {
+    pub const ALL: [AminoAcid; 20] = [
+        AminoAcid::Alanine,
+        AminoAcid::Arginine,
+        AminoAcid::Asparagine,
+        AminoAcid::AsparticAcid,
+        AminoAcid::Cysteine,
+        AminoAcid::GlutamicAcid,
+        AminoAcid::Glutamine,
+        AminoAcid::Glycine,
+        AminoAcid::Histidine,
+        AminoAcid::Isoleucine,
+        AminoAcid::Leucine,
+        AminoAcid::Lysine,
+        AminoAcid::Methionine,
+        AminoAcid::Phenylalanine,
+        AminoAcid::Proline,
+        AminoAcid::Serine,
+        AminoAcid::Threonine,
+        AminoAcid::Tryptophan,
+        AminoAcid::Tyrosine,
+        AminoAcid::Valine,
+    ];
+
+    pub fn one_letter(&self) -> char {
+        match self {
+            AminoAcid::Alanine => 'A',
+            AminoAcid::Arginine => 'R',
+            AminoAcid::Asparagine => 'N',
+            AminoAcid::AsparticAcid => 'D',
+            AminoAcid::Cysteine => 'C',
+            AminoAcid::GlutamicAcid => 'E',
+            AminoAcid::Glutamine => 'Q',
+            AminoAcid::Glycine => 'G',
+            AminoAcid::Histidine => 'H',
+            AminoAcid::Isoleucine => 'I',
+            AminoAcid::Leucine => 'L',
+            AminoAcid::Lysine => 'K',
+            AminoAcid::Methionine => 'M',
+            AminoAcid::Phenylalanine => 'F',
+            AminoAcid::Proline => 'P',
+            AminoAcid::Serine => 'S',
+            AminoAcid::Threonine => 'T',
+            AminoAcid::Tryptophan => 'W',
+            AminoAcid::Tyrosine => 'Y',
+            AminoAcid::Valine => 'V',
+        }
+    }
+
+    pub fn from_one_letter(c: char) -> Option<Self> {
+        let c = c.to_ascii_uppercase();
+        Self::ALL.into_iter().find(|aa| aa.one_letter() == c)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProteinSequence {
+    residues: Vec<AminoAcid>,
+}
+
+impl ProteinSequence {
+    pub fn new(residues: Vec<AminoAcid>) -> Self {
+        ProteinSequence { residues }
+    }
+
+    pub fn len(&self) -> usize {
+        self.residues.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.residues.is_empty()
+    }
+
+    pub fn residues(&self) -> &[AminoAcid] {
+        &self.residues
+    }
+
+    pub fn get(&self, index: usize) -> Option<AminoAcid> {
+        self.residues.get(index).copied()
+    }
+}
+
+impl FromStr for ProteinSequence {
+    type Err = BiologyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| {
+                AminoAcid::from_one_letter(c).ok_or_else(|| {
+                    BiologyError::InvalidValue(format!("'{}' is not an amino acid code", c))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(ProteinSequence::new)
+    }
+}
+
+impl fmt::Display for ProteinSequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: String = self.residues.iter().map(|aa| aa.one_letter()).collect();
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_letter_round_trip() {
+        for aa in AminoAcid::ALL {
+            assert_eq!(AminoAcid::from_one_letter(aa.one_letter()), Some(aa));
+        }
+        let protein = ProteinSequence::from_str("MKWv").unwrap();
+        assert_eq!(protein.to_string(), "MKWV");
+        assert!(ProteinSequence::from_str("MB").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use crate::biology::BiologyError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Ribonucleotide {
+    Adenine,
+    Uracil,
+    Guanine,
+    Cytosine,
+}
+
+impl Ribonucleotide {
+    pub fn to_char(&self) -> char {
+        match self {
+            Ribonucleotide::Adenine => 'A',
+            Ribonucleotide::Uracil => 'U',
+            Ribonucleotide::Guanine => 'G',
+            Ribonucleotide::Cytosine => 'C',
+        }
+    }
+
+    pub fn from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            'A' => Some(Ribonucleotide::Adenine),
+            'U' => Some(Ribonucleotide::Uracil),
+            'G' => Some(Ribonucleotide::Guanine),
+            'C' => Some(Ribonucleotide::Cytosine),
+            _ => None,
+        }
+    }
+
+    pub fn is_gc(&self) -> bool {
+        matches!(self, Ribonucleotide::Guanine | Ribonucleotide::Cytosine)
+    }
+}
+
+pub type Codon = [Ribonucleotide; 3];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MRnaSequence {
+    sequence: Vec<Ribonucleotide>,
+}
+
+impl MRnaSequence {
+    pub fn new(sequence: Vec<Ribonucleotide>) -> Self {
+        MRnaSequence { sequence }
+    }
+
+    pub fn len(&self) -> usize {
+        self.sequence.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sequence.is_empty()
+    }
+
+    pub fn bases(&self) -> &[Ribonucleotide] {
+        &self.sequence
+    }
+
+    // Codons in frame from `offset`; a trailing partial codon is dropped.
+    pub fn codons_from(&self, offset: usize) -> impl Iterator<Item = Codon> + '_ {
+        self.sequence
+            .get(offset..)
+            .unwrap_or(&[])
+            .chunks_exact(3)
+            .map(|c| [c[0], c[1], c[2]])
+    }
+
+    pub fn find_start_codon(&self) -> Option<usize> {
+        self.sequence.windows(3).position(|w| {
+            w == [
+                Ribonucleotide::Adenine,
+                Ribonucleotide::Uracil,
+                Ribonucleotide::Guanine,
+            ]
+        })
+    }
+
+    pub fn gc_content(&self) -> f64 {
+        if self.sequence.is_empty() {
+            return 0.0;
+        }
+        self.sequence.iter().filter(|b| b.is_gc()).count() as f64 / self.sequence.len() as f64
+    }
+
+    // Uridine-rich transcripts are more immunostimulatory via TLR7/8,
+    // which matters for unmodified therapeutic mRNA.
+    // Karikó K et al. (2005) Immunity 23:165-175, PMID 16111635
+    pub fn uridine_fraction(&self) -> f64 {
+        if self.sequence.is_empty() {
+            return 0.0;
+        }
+        let uridines = self
+            .sequence
+            .iter()
+            .filter(|b| matches!(b, Ribonucleotide::Uracil))
+            .count();
+        uridines as f64 / self.sequence.len() as f64
+    }
+}
+
+impl FromStr for MRnaSequence {
+    type Err = BiologyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .map(|c| {
+                Ribonucleotide::from_char(c).ok_or_else(|| {
+                    BiologyError::InvalidValue(format!("'{}' is not a ribonucleotide", c))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(MRnaSequence::new)
+    }
+}
+
+impl fmt::Display for MRnaSequence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: String = self.sequence.iter().map(|b| b.to_char()).collect();
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mrna_from_str_rejects_thymine() {
+        let mrna = MRnaSequence::from_str("augGCU").unwrap();
+        assert_eq!(mrna.to_string(), "AUGGCU");
+        assert!(MRnaSequence::from_str("ATG").is_err());
+    }
+
+    #[test]
+    fn test_codons_and_start() {
+        let mrna = MRnaSequence::from_str("GGAUGCCCUAAG").unwrap();
+        assert_eq!(mrna.find_start_codon(), Some(2));
+        assert_eq!(mrna.codons_from(2).count(), 3);
+        assert!((mrna.uridine_fraction() - 2.0 / 12.0).abs() < 1e-12);
+    }
+}
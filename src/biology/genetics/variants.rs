@@ -0,0 +1,445 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::pathology::fibrosis::FibrosisModel;
+use crate::pharmacology::drug_interactions::RegimenDrug;
+use crate::pharmacology::pharmacogenomics::{
+    MetabolizerPhenotype, PharmacogeneticGene, PharmacogeneticProfile,
+};
+use crate::pharmacology::pharmacokinetics::Pharmacokinetics;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Effects are per allele and relative to the reference allele; a diploid
+// genotype averages its two alleles so that reference/reference is 1.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VariantEffect {
+    // Activity of a drug-metabolizing enzyme or uptake transporter.
+    PathwayActivity {
+        gene: PharmacogeneticGene,
+        activity: f64,
+    },
+    // Expression of a drug target; dose requirement scales with it.
+    TargetExpression {
+        gene: PharmacogeneticGene,
+        factor: f64,
+    },
+    // proα1(I) chain output, and whether the chain folds into a normal
+    // triple helix.
+    CollagenAlpha1 {
+        chain_output: f64,
+        structurally_normal: bool,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneticVariant {
+    pub name: String,
+    pub gene: String,
+    pub rs_id: Option<String>,
+    pub effect: VariantEffect,
+}
+
+impl GeneticVariant {
+    pub fn new(name: &str, gene: &str, effect: VariantEffect) -> Self {
+        Self {
+            name: name.to_string(),
+            gene: gene.to_string(),
+            rs_id: None,
+            effect,
+        }
+    }
+
+    pub fn with_rs_id(mut self, rs_id: &str) -> Self {
+        self.rs_id = Some(rs_id.to_string());
+        self
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        let value = match self.effect {
+            VariantEffect::PathwayActivity { activity, .. } => activity,
+            VariantEffect::TargetExpression { factor, .. } => factor,
+            VariantEffect::CollagenAlpha1 { chain_output, .. } => chain_output,
+        };
+        if !value.is_finite() || value < 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "variant {} needs a non-negative effect size",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+// One line of a declarative genotype: a registered variant and how many
+// copies (1 heterozygous, 2 homozygous) the individual carries.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VariantCall {
+    pub variant: String,
+    pub copies: u8,
+}
+
+impl VariantCall {
+    pub fn heterozygous(variant: &str) -> Self {
+        Self {
+            variant: variant.to_string(),
+            copies: 1,
+        }
+    }
+
+    pub fn homozygous(variant: &str) -> Self {
+        Self {
+            variant: variant.to_string(),
+            copies: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantRegistry {
+    variants: HashMap<String, GeneticVariant>,
+}
+
+impl VariantRegistry {
+    pub fn new() -> Self {
+        Self {
+            variants: HashMap::new(),
+        }
+    }
+
+    pub fn standard() -> Self {
+        use PharmacogeneticGene::*;
+        let activity = |gene, activity| VariantEffect::PathwayActivity { gene, activity };
+        let mut registry = Self::new();
+        for variant in [
+            // CPIC allele function: *4 none, *10 decreased (0.25), *1xN
+            // duplication doubles activity.
+            // Caudle KE et al. (2020) Clin Transl Sci 13:116-124, PMID 31647186
+            GeneticVariant::new("CYP2D6*4", "CYP2D6", activity(CYP2D6, 0.0))
+                .with_rs_id("rs3892097"),
+            GeneticVariant::new("CYP2D6*10", "CYP2D6", activity(CYP2D6, 0.25))
+                .with_rs_id("rs1065852"),
+            GeneticVariant::new("CYP2D6*1xN", "CYP2D6", activity(CYP2D6, 2.0)),
+            // Johnson JA et al. (2017) Clin Pharmacol Ther 102:397-404, PMID 28198005
+            GeneticVariant::new("CYP2C9*2", "CYP2C9", activity(CYP2C9, 0.5))
+                .with_rs_id("rs1799853"),
+            GeneticVariant::new("CYP2C9*3", "CYP2C9", activity(CYP2C9, 0.0))
+                .with_rs_id("rs1057910"),
+            // Moriyama B et al. (2017) Clin Pharmacol Ther 102:45-51, PMID 27981572
+            GeneticVariant::new("CYP2C19*2", "CYP2C19", activity(CYP2C19, 0.0))
+                .with_rs_id("rs4244285"),
+            GeneticVariant::new("CYP2C19*17", "CYP2C19", activity(CYP2C19, 1.5))
+                .with_rs_id("rs12248560"),
+            // c.521T>C triples simvastatin acid AUC in CC homozygotes.
+            // Pasanen MK et al. (2006) Pharmacogenet Genomics 16:873-879, PMID 17108811
+            GeneticVariant::new("SLCO1B1*5", "SLCO1B1", activity(SLCO1B1, 0.3))
+                .with_rs_id("rs4149056"),
+            // Warfarin dose ~6.2 (GG), 4.8 (GA), 3.5 (AA) mg/day.
+            // Rieder MJ et al. (2005) N Engl J Med 352:2285-2293, PMID 15930419
+            GeneticVariant::new(
+                "VKORC1 -1639G>A",
+                "VKORC1",
+                VariantEffect::TargetExpression {
+                    gene: VKORC1,
+                    factor: 0.56,
+                },
+            )
+            .with_rs_id("rs9923231"),
+            // OI type I: premature stop codons abolish output from one allele.
+            // Willing MC et al. (1994) Am J Hum Genet 55:638-647, PMID 7942841
+            GeneticVariant::new(
+                "COL1A1 null",
+                "COL1A1",
+                VariantEffect::CollagenAlpha1 {
+                    chain_output: 0.0,
+                    structurally_normal: true,
+                },
+            ),
+            // OI types II-IV: helical glycine substitutions act dominant-
+            // negatively through mixed trimers.
+            // Marini JC et al. (2007) Hum Mutat 28:209-221, PMID 17078022
+            GeneticVariant::new(
+                "COL1A1 Gly substitution",
+                "COL1A1",
+                VariantEffect::CollagenAlpha1 {
+                    chain_output: 1.0,
+                    structurally_normal: false,
+                },
+            ),
+        ] {
+            registry.register(variant).expect("valid preset");
+        }
+        registry
+    }
+
+    pub fn register(&mut self, variant: GeneticVariant) -> BiologyResult<()> {
+        variant.validate()?;
+        self.variants.insert(variant.name.clone(), variant);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&GeneticVariant> {
+        self.variants.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.variants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+
+    // Folds a genotype into the parameter modifiers it implies. Alleles not
+    // listed are taken as reference.
+    pub fn resolve(&self, calls: &[VariantCall]) -> BiologyResult<VariantEffects> {
+        let mut alleles: HashMap<&str, Vec<&GeneticVariant>> = HashMap::new();
+        for call in calls {
+            let variant = self.get(&call.variant).ok_or_else(|| {
+                BiologyError::InvalidValue(format!("unknown variant {}", call.variant))
+            })?;
+            if !(1..=2).contains(&call.copies) {
+                return Err(BiologyError::InvalidValue(format!(
+                    "variant {} must be carried in 1 or 2 copies",
+                    call.variant
+                )));
+            }
+            let gene = alleles.entry(variant.gene.as_str()).or_default();
+            for _ in 0..call.copies {
+                gene.push(variant);
+            }
+            if gene.len() > 2 {
+                return Err(BiologyError::InvalidValue(format!(
+                    "more than two alleles called for {}",
+                    variant.gene
+                )));
+            }
+        }
+
+        let mut effects = VariantEffects::reference();
+        for variants in alleles.values() {
+            let reference_alleles = 2 - variants.len();
+            let mut activity: HashMap<PharmacogeneticGene, f64> = HashMap::new();
+            let mut expression: HashMap<PharmacogeneticGene, f64> = HashMap::new();
+            let mut normal_chains = reference_alleles as f64;
+            let mut total_chains = reference_alleles as f64;
+            let mut collagen = false;
+            for variant in variants {
+                match variant.effect {
+                    VariantEffect::PathwayActivity { gene, activity: a } => {
+                        *activity.entry(gene).or_insert(reference_alleles as f64) += a;
+                    }
+                    VariantEffect::TargetExpression { gene, factor } => {
+                        *expression.entry(gene).or_insert(reference_alleles as f64) += factor;
+                    }
+                    VariantEffect::CollagenAlpha1 {
+                        chain_output,
+                        structurally_normal,
+                    } => {
+                        collagen = true;
+                        total_chains += chain_output;
+                        if structurally_normal {
+                            normal_chains += chain_output;
+                        }
+                    }
+                }
+            }
+            for (gene, sum) in activity {
+                effects.pathway_activity.insert(gene, sum / 2.0);
+            }
+            for (gene, sum) in expression {
+                effects.target_expression.insert(gene, sum / 2.0);
+            }
+            if collagen {
+                effects.collagen_quantity = total_chains / 2.0;
+                // Each type I trimer carries two α1 chains; one abnormal
+                // chain is enough to disrupt the helix.
+                let normal_fraction = normal_chains / total_chains.max(1e-12);
+                effects.collagen_stability = normal_fraction * normal_fraction;
+            }
+        }
+        Ok(effects)
+    }
+}
+
+impl Default for VariantRegistry {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VariantEffects {
+    pub pathway_activity: HashMap<PharmacogeneticGene, f64>,
+    pub target_expression: HashMap<PharmacogeneticGene, f64>,
+    pub collagen_quantity: f64,
+    pub collagen_stability: f64,
+}
+
+impl VariantEffects {
+    pub fn reference() -> Self {
+        Self {
+            pathway_activity: HashMap::new(),
+            target_expression: HashMap::new(),
+            collagen_quantity: 1.0,
+            collagen_stability: 1.0,
+        }
+    }
+
+    pub fn activity(&self, gene: PharmacogeneticGene) -> f64 {
+        self.pathway_activity.get(&gene).copied().unwrap_or(1.0)
+    }
+
+    pub fn dose_requirement_factor(&self, gene: PharmacogeneticGene) -> f64 {
+        self.target_expression.get(&gene).copied().unwrap_or(1.0)
+    }
+
+    // CPIC activity-score bands with the score halved onto our 0-1 scale.
+    pub fn metabolizer_phenotype(&self, gene: PharmacogeneticGene) -> MetabolizerPhenotype {
+        match self.activity(gene) {
+            a if a <= 0.0 => MetabolizerPhenotype::Poor,
+            a if a <= 0.5 => MetabolizerPhenotype::Intermediate,
+            a if a <= 1.125 => MetabolizerPhenotype::Normal,
+            a if a <= 1.25 => MetabolizerPhenotype::Rapid,
+            _ => MetabolizerPhenotype::UltraRapid,
+        }
+    }
+
+    // Only the fraction metabolized by each pathway scales with its activity.
+    pub fn clearance_ratio(&self, drug: &RegimenDrug) -> f64 {
+        1.0 - drug
+            .metabolized_by
+            .iter()
+            .map(|s| s.fraction_metabolized * (1.0 - self.activity(s.enzyme)))
+            .sum::<f64>()
+    }
+
+    pub fn adjust_pharmacokinetics(
+        &self,
+        pk: &Pharmacokinetics,
+        drug: &RegimenDrug,
+    ) -> Pharmacokinetics {
+        pk.with_clearance_ratio(self.clearance_ratio(drug))
+    }
+
+    pub fn to_profile(&self) -> PharmacogeneticProfile {
+        let mut profile = PharmacogeneticProfile::new();
+        for &gene in self.pathway_activity.keys() {
+            profile.add_phenotype(gene, self.metabolizer_phenotype(gene));
+        }
+        profile
+    }
+}
+
+// Models implement this to take genotype-specific parameters. Apply once,
+// to a freshly built model.
+pub trait GenotypeDependent {
+    fn apply_genotype(&mut self, effects: &VariantEffects);
+}
+
+// Less procollagen lowers deposition; structurally abnormal fibrils are
+// turned over faster.
+impl GenotypeDependent for FibrosisModel {
+    fn apply_genotype(&mut self, effects: &VariantEffects) {
+        self.params.collagen_deposition_per_day *= effects.collagen_quantity;
+        self.params.collagen_remodeling_per_day /= effects.collagen_stability.max(0.05);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pathology::fibrosis::FibroticTissue;
+
+    #[test]
+    fn test_cyp2d6_diplotypes_map_to_phenotypes() {
+        let registry = VariantRegistry::standard();
+        let poor = registry
+            .resolve(&[VariantCall::homozygous("CYP2D6*4")])
+            .unwrap();
+        assert_eq!(
+            poor.metabolizer_phenotype(PharmacogeneticGene::CYP2D6),
+            MetabolizerPhenotype::Poor
+        );
+        let intermediate = registry
+            .resolve(&[
+                VariantCall::heterozygous("CYP2D6*4"),
+                VariantCall::heterozygous("CYP2D6*10"),
+            ])
+            .unwrap();
+        assert_eq!(
+            intermediate.metabolizer_phenotype(PharmacogeneticGene::CYP2D6),
+            MetabolizerPhenotype::Intermediate
+        );
+        let ultra = registry
+            .resolve(&[VariantCall::heterozygous("CYP2D6*1xN")])
+            .unwrap();
+        assert_eq!(
+            ultra.to_profile().phenotypes[&PharmacogeneticGene::CYP2D6],
+            MetabolizerPhenotype::UltraRapid
+        );
+        assert!(registry
+            .resolve(&[
+                VariantCall::homozygous("CYP2D6*4"),
+                VariantCall::heterozygous("CYP2D6*10"),
+            ])
+            .is_err());
+        assert!(registry
+            .resolve(&[VariantCall::heterozygous("CYP2D6*99")])
+            .is_err());
+    }
+
+    #[test]
+    fn test_slco1b1_raises_statin_exposure() {
+        let registry = VariantRegistry::standard();
+        let cc = registry
+            .resolve(&[VariantCall::homozygous("SLCO1B1*5")])
+            .unwrap();
+        let simvastatin_acid = RegimenDrug::new("simvastatin acid", 0.001)
+            .substrate_of(PharmacogeneticGene::SLCO1B1, 0.9);
+        let pk = Pharmacokinetics::new(0.05, 2.0, 50.0);
+        let adjusted = cc.adjust_pharmacokinetics(&pk, &simvastatin_acid);
+        let auc_ratio = pk.excretion.clearance_ml_per_min / adjusted.excretion.clearance_ml_per_min;
+        assert!((2.5..4.0).contains(&auc_ratio), "auc ratio {auc_ratio}");
+    }
+
+    #[test]
+    fn test_vkorc1_lowers_warfarin_dose_requirement() {
+        let registry = VariantRegistry::standard();
+        let ga = registry
+            .resolve(&[VariantCall::heterozygous("VKORC1 -1639G>A")])
+            .unwrap();
+        let aa = registry
+            .resolve(&[VariantCall::homozygous("VKORC1 -1639G>A")])
+            .unwrap();
+        let dose =
+            |e: &VariantEffects| 6.2 * e.dose_requirement_factor(PharmacogeneticGene::VKORC1);
+        assert!((dose(&ga) - 4.8).abs() < 0.3);
+        assert!((dose(&aa) - 3.5).abs() < 0.3);
+    }
+
+    #[test]
+    fn test_col1a1_alleles_differ_in_quantity_and_quality() {
+        let registry = VariantRegistry::standard();
+        let null = registry
+            .resolve(&[VariantCall::heterozygous("COL1A1 null")])
+            .unwrap();
+        assert_eq!(null.collagen_quantity, 0.5);
+        assert_eq!(null.collagen_stability, 1.0);
+        let glycine = registry
+            .resolve(&[VariantCall::heterozygous("COL1A1 Gly substitution")])
+            .unwrap();
+        assert_eq!(glycine.collagen_quantity, 1.0);
+        assert_eq!(glycine.collagen_stability, 0.25);
+
+        let healthy = FibrosisModel::new(FibroticTissue::Skin);
+        let mut oi = healthy.clone();
+        oi.apply_genotype(&glycine);
+        assert!(
+            oi.params.collagen_remodeling_per_day
+                > 3.0 * healthy.params.collagen_remodeling_per_day
+        );
+        let cyp = registry
+            .resolve(&[VariantCall::heterozygous("CYP2D6*4")])
+            .unwrap();
+        assert_eq!(cyp.collagen_quantity, 1.0);
+    }
+}
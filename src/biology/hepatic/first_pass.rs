@@ -0,0 +1,160 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Well-stirred liver: CL_H = Q·fu·CLint / (Q + fu·CLint), E_H = CL_H / Q.
+// Rowland M, Benet LZ, Graham GG (1973) J Pharmacokinet Biopharm 1:123-136, PMID 4764426
+pub fn well_stirred_clearance_ml_min(
+    blood_flow_ml_min: f64,
+    fraction_unbound_blood: f64,
+    intrinsic_clearance_ml_min: f64,
+) -> f64 {
+    let unbound = fraction_unbound_blood * intrinsic_clearance_ml_min;
+    blood_flow_ml_min * unbound / (blood_flow_ml_min + unbound).max(1e-12)
+}
+
+// Oral bioavailability F = Fa·Fg·Fh: absorbed fraction, escape from gut-wall
+// metabolism, and escape from hepatic extraction on the first portal pass.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HepaticDrug {
+    pub name: String,
+    pub fraction_unbound_blood: f64,
+    pub intrinsic_clearance_ml_min: f64,
+    pub fraction_absorbed: f64,
+    pub gut_availability: f64,
+}
+
+impl HepaticDrug {
+    pub fn new(
+        name: &str,
+        fraction_unbound_blood: f64,
+        intrinsic_clearance_ml_min: f64,
+        fraction_absorbed: f64,
+        gut_availability: f64,
+    ) -> BiologyResult<Self> {
+        for (value, what) in [
+            (fraction_unbound_blood, "fraction unbound"),
+            (fraction_absorbed, "fraction absorbed"),
+            (gut_availability, "gut availability"),
+        ] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "{what} must lie in [0, 1]"
+                )));
+            }
+        }
+        if intrinsic_clearance_ml_min < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "intrinsic clearance must be non-negative".to_string(),
+            ));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            fraction_unbound_blood,
+            intrinsic_clearance_ml_min,
+            fraction_absorbed,
+            gut_availability,
+        })
+    }
+
+    // High-extraction drug, E_H ~0.7 and oral F ~0.25.
+    // Walle T et al. (1985) Clin Pharmacol Ther 38:509-518, PMID 4053487
+    pub fn propranolol() -> Self {
+        Self::new("propranolol", 0.16, 21_000.0, 0.95, 1.0).expect("valid preset")
+    }
+
+    // CYP3A4 substrate with gut-wall loss: Fg ~0.57, Fh ~0.6.
+    // Thummel KE et al. (1996) Clin Pharmacol Ther 59:491-502, PMID 8646820
+    pub fn midazolam() -> Self {
+        Self::new("midazolam", 0.03, 22_000.0, 1.0, 0.57).expect("valid preset")
+    }
+
+    // Low-extraction drug: CL ~3 mL/min, F ~1.
+    // Holford NH (1986) Clin Pharmacokinet 11:483-504, PMID 3542339
+    pub fn warfarin() -> Self {
+        Self::new("warfarin", 0.01, 300.0, 1.0, 1.0).expect("valid preset")
+    }
+
+    pub fn hepatic_clearance_ml_min(&self, blood_flow_ml_min: f64, functional_mass: f64) -> f64 {
+        well_stirred_clearance_ml_min(
+            blood_flow_ml_min,
+            self.fraction_unbound_blood,
+            self.intrinsic_clearance_ml_min * functional_mass,
+        )
+    }
+
+    pub fn extraction_ratio(&self, blood_flow_ml_min: f64, functional_mass: f64) -> f64 {
+        self.hepatic_clearance_ml_min(blood_flow_ml_min, functional_mass)
+            / blood_flow_ml_min.max(1e-12)
+    }
+
+    // Portosystemic shunts carry part of the portal load straight to the
+    // systemic circulation, bypassing extraction.
+    pub fn hepatic_availability(
+        &self,
+        blood_flow_ml_min: f64,
+        functional_mass: f64,
+        portosystemic_shunt: f64,
+    ) -> f64 {
+        let through_liver = 1.0 - self.extraction_ratio(blood_flow_ml_min, functional_mass);
+        portosystemic_shunt + (1.0 - portosystemic_shunt) * through_liver
+    }
+
+    pub fn oral_bioavailability(
+        &self,
+        blood_flow_ml_min: f64,
+        functional_mass: f64,
+        portosystemic_shunt: f64,
+    ) -> f64 {
+        self.fraction_absorbed
+            * self.gut_availability
+            * self.hepatic_availability(blood_flow_ml_min, functional_mass, portosystemic_shunt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIVER_BLOOD_FLOW: f64 = 1450.0;
+
+    #[test]
+    fn test_extraction_classes() {
+        let high = HepaticDrug::propranolol().extraction_ratio(LIVER_BLOOD_FLOW, 1.0);
+        let low = HepaticDrug::warfarin().extraction_ratio(LIVER_BLOOD_FLOW, 1.0);
+        assert!(high > 0.6 && high < 0.8);
+        assert!(low < 0.01);
+        let f = HepaticDrug::propranolol().oral_bioavailability(LIVER_BLOOD_FLOW, 1.0, 0.0);
+        assert!(f > 0.2 && f < 0.35);
+    }
+
+    #[test]
+    fn test_flow_limits_high_but_not_low_extraction() {
+        let propranolol = HepaticDrug::propranolol();
+        let warfarin = HepaticDrug::warfarin();
+        let ratio = |d: &HepaticDrug| {
+            d.hepatic_clearance_ml_min(0.5 * LIVER_BLOOD_FLOW, 1.0)
+                / d.hepatic_clearance_ml_min(LIVER_BLOOD_FLOW, 1.0)
+        };
+        assert!(ratio(&propranolol) < 0.75);
+        assert!(ratio(&warfarin) > 0.99);
+    }
+
+    #[test]
+    fn test_midazolam_gut_and_liver_first_pass() {
+        let midazolam = HepaticDrug::midazolam();
+        let fh = midazolam.hepatic_availability(LIVER_BLOOD_FLOW, 1.0, 0.0);
+        assert!(fh > 0.5 && fh < 0.75);
+        let f = midazolam.oral_bioavailability(LIVER_BLOOD_FLOW, 1.0, 0.0);
+        // Oral midazolam F ~0.3-0.4.
+        assert!(f > 0.28 && f < 0.42);
+        assert!(HepaticDrug::new("x", 1.5, 1.0, 1.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_shunting_raises_oral_exposure() {
+        let propranolol = HepaticDrug::propranolol();
+        let healthy = propranolol.oral_bioavailability(LIVER_BLOOD_FLOW, 1.0, 0.0);
+        let cirrhotic = propranolol.oral_bioavailability(LIVER_BLOOD_FLOW, 0.5, 0.4);
+        assert!(cirrhotic > 2.0 * healthy);
+    }
+}
@@ -0,0 +1,257 @@
+use crate::biology::hepatic::first_pass::HepaticDrug;
+use crate::biology::{BiologyError, BiologyResult};
+use crate::pharmacology::pharmacokinetics::{DoseAdjustment, Pharmacokinetics};
+use serde::{Deserialize, Serialize};
+
+// Hepatic blood flow ~1450 mL/min in a 70 kg adult.
+// Davies B, Morris T (1993) Pharm Res 10:1093-1095, PMID 8378254
+pub const HEPATIC_BLOOD_FLOW_ML_MIN: f64 = 1450.0;
+const PLASMA_VOLUME_DL: f64 = 30.0;
+// Albumin: synthesis ~0.2 g/kg/day, t½ ~19 days, ~40% intravascular.
+// Rothschild MA, Oratz M, Schreiber SS (1972) N Engl J Med 286:748-757, PMID 4551704
+const ALBUMIN_SYNTHESIS_G_PER_KG_DAY: f64 = 0.2;
+const ALBUMIN_HALF_LIFE_DAYS: f64 = 19.0;
+const ALBUMIN_INTRAVASCULAR_FRACTION: f64 = 0.33;
+// Heme turnover yields ~4 mg/kg/day bilirubin, cleared by UGT1A1
+// conjugation.
+// Berk PD et al. (1969) J Clin Invest 48:2176-2190, PMID 5824077
+const BILIRUBIN_PRODUCTION_MG_PER_KG_DAY: f64 = 4.0;
+const BILIRUBIN_CLEARANCE_DL_PER_DAY: f64 = 450.0;
+// Prothrombin time prolongs as hepatic factor synthesis falls.
+const INR_MASS_EXPONENT: f64 = 0.8;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HepaticFunction {
+    pub blood_flow_ml_min: f64,
+    pub functional_mass: f64,
+    pub portosystemic_shunt: f64,
+    // Gilbert syndrome leaves ~30% UGT1A1 activity.
+    pub ugt1a1_activity: f64,
+    pub hemolysis_factor: f64,
+    pub weight_kg: f64,
+    pub albumin_pool_g: f64,
+    // Clinical grades 1 (none) to 3 used by Child-Pugh.
+    pub ascites_grade: u8,
+    pub encephalopathy_grade: u8,
+}
+
+impl HepaticFunction {
+    pub fn new_healthy() -> Self {
+        let weight_kg = 70.0;
+        let mut liver = Self {
+            blood_flow_ml_min: HEPATIC_BLOOD_FLOW_ML_MIN,
+            functional_mass: 1.0,
+            portosystemic_shunt: 0.0,
+            ugt1a1_activity: 1.0,
+            hemolysis_factor: 1.0,
+            weight_kg,
+            albumin_pool_g: 0.0,
+            ascites_grade: 1,
+            encephalopathy_grade: 1,
+        };
+        liver.albumin_pool_g = liver.albumin_synthesis_g_day() / albumin_decay_per_day();
+        liver
+    }
+
+    // Typical decompensated cirrhosis: lost parenchyma, portal hypertension
+    // with shunting, and mildly reduced effective hepatic flow.
+    pub fn cirrhosis(functional_mass: f64, portosystemic_shunt: f64) -> BiologyResult<Self> {
+        if !(0.0..=1.0).contains(&functional_mass) || !(0.0..=1.0).contains(&portosystemic_shunt) {
+            return Err(BiologyError::InvalidParameter(
+                "functional mass and shunt fraction must lie in [0, 1]".to_string(),
+            ));
+        }
+        let mut liver = Self::new_healthy();
+        liver.functional_mass = functional_mass;
+        liver.portosystemic_shunt = portosystemic_shunt;
+        liver.blood_flow_ml_min *= 0.8;
+        Ok(liver)
+    }
+
+    pub fn albumin_synthesis_g_day(&self) -> f64 {
+        ALBUMIN_SYNTHESIS_G_PER_KG_DAY * self.weight_kg * self.functional_mass
+    }
+
+    pub fn step_days(&mut self, dt_days: f64) {
+        let d_pool = self.albumin_synthesis_g_day() - albumin_decay_per_day() * self.albumin_pool_g;
+        self.albumin_pool_g = (self.albumin_pool_g + d_pool * dt_days).max(0.0);
+    }
+
+    pub fn run_days(&mut self, days: usize) {
+        for _ in 0..days {
+            self.step_days(1.0);
+        }
+    }
+
+    pub fn serum_albumin_g_dl(&self) -> f64 {
+        self.albumin_pool_g * ALBUMIN_INTRAVASCULAR_FRACTION / PLASMA_VOLUME_DL
+    }
+
+    // Unconjugated bilirubin turns over in hours, so it sits at steady state
+    // on the day scale.
+    pub fn serum_bilirubin_mg_dl(&self) -> f64 {
+        let production =
+            BILIRUBIN_PRODUCTION_MG_PER_KG_DAY * self.weight_kg * self.hemolysis_factor;
+        let clearance =
+            BILIRUBIN_CLEARANCE_DL_PER_DAY * self.functional_mass * self.ugt1a1_activity;
+        production / clearance.max(1e-9)
+    }
+
+    pub fn inr(&self) -> f64 {
+        self.functional_mass.max(0.05).powf(-INR_MASS_EXPONENT)
+    }
+
+    // Pugh RN et al. (1973) Br J Surg 60:646-649, PMID 4541913
+    pub fn child_pugh_score(&self) -> u8 {
+        let bilirubin = match self.serum_bilirubin_mg_dl() {
+            b if b < 2.0 => 1,
+            b if b <= 3.0 => 2,
+            _ => 3,
+        };
+        let albumin = match self.serum_albumin_g_dl() {
+            a if a > 3.5 => 1,
+            a if a >= 2.8 => 2,
+            _ => 3,
+        };
+        let inr = match self.inr() {
+            i if i < 1.7 => 1,
+            i if i <= 2.3 => 2,
+            _ => 3,
+        };
+        bilirubin
+            + albumin
+            + inr
+            + self.ascites_grade.clamp(1, 3)
+            + self.encephalopathy_grade.clamp(1, 3)
+    }
+
+    pub fn dose_adjustment(&self) -> DoseAdjustment {
+        DoseAdjustment::hepatic_impairment(self.child_pugh_score())
+    }
+
+    pub fn oral_bioavailability(&self, drug: &HepaticDrug) -> f64 {
+        drug.oral_bioavailability(
+            self.blood_flow_ml_min,
+            self.functional_mass,
+            self.portosystemic_shunt,
+        )
+    }
+
+    pub fn hepatic_clearance_ml_min(&self, drug: &HepaticDrug) -> f64 {
+        // Shunted blood is not cleared either.
+        (1.0 - self.portosystemic_shunt)
+            * drug.hepatic_clearance_ml_min(self.blood_flow_ml_min, self.functional_mass)
+    }
+
+    // Oral route: the hepatic availability of first pass scales the
+    // bioavailability already in `pk` (absorption and gut-wall losses), so
+    // it composes with `OralAbsorptionModel::apply_to` in either order;
+    // systemic clearance is scaled on the hepatic share (1 − fe) of
+    // elimination.
+    pub fn apply_to_oral(&self, pk: &Pharmacokinetics, drug: &HepaticDrug) -> Pharmacokinetics {
+        let fe = (pk.excretion.percent_unchanged / 100.0).clamp(0.0, 1.0);
+        let healthy = drug.hepatic_clearance_ml_min(HEPATIC_BLOOD_FLOW_ML_MIN, 1.0);
+        let hepatic_ratio = self.hepatic_clearance_ml_min(drug) / healthy.max(1e-9);
+        let mut adjusted = pk.with_clearance_ratio(fe + (1.0 - fe) * hepatic_ratio);
+        let hepatic_availability = drug.hepatic_availability(
+            self.blood_flow_ml_min,
+            self.functional_mass,
+            self.portosystemic_shunt,
+        );
+        adjusted.absorption.bioavailability =
+            (adjusted.absorption.bioavailability * hepatic_availability).clamp(0.0, 1.0);
+        adjusted
+    }
+}
+
+impl Default for HepaticFunction {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+fn albumin_decay_per_day() -> f64 {
+    std::f64::consts::LN_2 / ALBUMIN_HALF_LIFE_DAYS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::digestive::{simulate_oral_dose, FedState, GiTract, OralFormulation};
+
+    #[test]
+    fn test_healthy_markers_in_reference_range() {
+        let liver = HepaticFunction::new_healthy();
+        let albumin = liver.serum_albumin_g_dl();
+        let bilirubin = liver.serum_bilirubin_mg_dl();
+        assert!(albumin > 3.5 && albumin < 5.0, "albumin {albumin}");
+        assert!(bilirubin > 0.3 && bilirubin < 1.2, "bilirubin {bilirubin}");
+        assert_eq!(liver.child_pugh_score(), 5);
+    }
+
+    #[test]
+    fn test_albumin_falls_slowly_after_liver_injury() {
+        let mut liver = HepaticFunction::cirrhosis(0.4, 0.3).unwrap();
+        let start = liver.serum_albumin_g_dl();
+        liver.run_days(7);
+        assert!(liver.serum_albumin_g_dl() > 0.8 * start);
+        liver.run_days(83);
+        assert!(liver.serum_albumin_g_dl() < 2.8);
+        liver.ascites_grade = 2;
+        assert!(liver.child_pugh_score() >= 9);
+        assert!(liver.dose_adjustment().adjustment_factor < 1.0);
+        assert!(HepaticFunction::cirrhosis(1.5, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_gilbert_and_hemolysis_raise_bilirubin() {
+        let healthy = HepaticFunction::new_healthy().serum_bilirubin_mg_dl();
+        let mut gilbert = HepaticFunction::new_healthy();
+        gilbert.ugt1a1_activity = 0.3;
+        let mut hemolytic = HepaticFunction::new_healthy();
+        hemolytic.hemolysis_factor = 3.0;
+        assert!(gilbert.serum_bilirubin_mg_dl() > 2.0 * healthy);
+        assert!(hemolytic.serum_bilirubin_mg_dl() > 1.5);
+    }
+
+    #[test]
+    fn test_first_pass_wired_into_oral_pharmacokinetics() {
+        let mut pk = Pharmacokinetics::new(1.0, 4.0, 250.0);
+        pk.excretion.percent_unchanged = 1.0;
+        let drug = HepaticDrug::propranolol();
+        let healthy = HepaticFunction::new_healthy().apply_to_oral(&pk, &drug);
+        assert!(healthy.absorption.bioavailability < 0.35);
+        assert!((healthy.metabolism.half_life_hours - 4.0).abs() < 0.1);
+
+        let cirrhotic = HepaticFunction::cirrhosis(0.5, 0.4)
+            .unwrap()
+            .apply_to_oral(&pk, &drug);
+        assert!(cirrhotic.absorption.bioavailability > 2.0 * healthy.absorption.bioavailability);
+        assert!(cirrhotic.metabolism.half_life_hours > healthy.metabolism.half_life_hours);
+        assert!(
+            cirrhotic.calculate_concentration(80.0, 2.0)
+                > 2.0 * healthy.calculate_concentration(80.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn test_first_pass_composes_with_absorption_in_either_order() {
+        let mut pk = Pharmacokinetics::new(0.9, 4.0, 250.0);
+        pk.excretion.percent_unchanged = 1.0;
+        let drug = HepaticDrug::propranolol();
+        let liver = HepaticFunction::cirrhosis(0.5, 0.4).unwrap();
+        let absorption = simulate_oral_dose(
+            GiTract::new(FedState::Fasted),
+            OralFormulation::metoprolol_immediate_release(),
+        )
+        .unwrap();
+        let liver_first = absorption.apply_to(&liver.apply_to_oral(&pk, &drug));
+        let gut_first = liver.apply_to_oral(&absorption.apply_to(&pk), &drug);
+        let (a, b) = (
+            liver_first.absorption.bioavailability,
+            gut_first.absorption.bioavailability,
+        );
+        assert!((a - b).abs() < 1e-12);
+        assert!(a < 0.9 * absorption.summary().fraction_absorbed);
+    }
+}
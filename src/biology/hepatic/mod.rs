@@ -0,0 +1,5 @@
+pub mod first_pass;
+pub mod liver_function;
+
+pub use first_pass::{well_stirred_clearance_ml_min, HepaticDrug};
+pub use liver_function::{HepaticFunction, HEPATIC_BLOOD_FLOW_ML_MIN};
@@ -1,6 +1,12 @@
 use std::fmt;
 
+pub mod digestive;
+pub mod endocrine;
 pub mod genetics;
+pub mod hepatic;
+pub mod physiology;
+pub mod renal;
+pub mod signaling;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum BiologyError {
@@ -0,0 +1,5 @@
+pub mod properties;
+pub mod thermoregulation;
+
+pub use properties::{arrhenius_factor, q10_factor, ChemicalProperty, PropertyConsumer};
+pub use thermoregulation::{HeatBalance, ThermalEnvironment, ThermalMedium, Thermoregulation};
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+// Physical state announced by one model for others to react to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ChemicalProperty {
+    Temperature { celsius: f64 },
+    OxygenTension { mmhg: f64 },
+}
+
+pub trait PropertyConsumer {
+    fn consume(&mut self, property: ChemicalProperty);
+}
+
+pub const REFERENCE_TEMPERATURE_C: f64 = 37.0;
+// Most enzymes speed up about twofold per 10 °C below their optimum.
+// Elias M et al. (2014) Trends Biochem Sci 39:1-7, PMID 24315123
+pub const TYPICAL_ENZYME_Q10: f64 = 2.0;
+
+// Rate multiplier for a process with the given Q10 relative to 37 °C.
+pub fn q10_factor(q10: f64, celsius: f64) -> f64 {
+    q10.powf((celsius - REFERENCE_TEMPERATURE_C) / 10.0)
+}
+
+pub const GAS_CONSTANT_J_PER_MOL_K: f64 = 8.314_462_618;
+pub const ZERO_CELSIUS_K: f64 = 273.15;
+
+// Rate multiplier at `celsius` relative to `reference_celsius` for a
+// process with the given activation energy; preferred over Q10 across
+// wide ranges, where Q10 itself drifts with temperature.
+pub fn arrhenius_factor(activation_energy_j_mol: f64, celsius: f64, reference_celsius: f64) -> f64 {
+    let t = celsius + ZERO_CELSIUS_K;
+    let reference = reference_celsius + ZERO_CELSIUS_K;
+    (activation_energy_j_mol / GAS_CONSTANT_J_PER_MOL_K * (1.0 / reference - 1.0 / t)).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_q10_factor() {
+        assert!((q10_factor(2.0, 37.0) - 1.0).abs() < 1e-12);
+        assert!((q10_factor(2.0, 47.0) - 2.0).abs() < 1e-12);
+        assert!(q10_factor(3.0, 35.0) < 1.0);
+    }
+
+    #[test]
+    fn test_arrhenius_factor() {
+        assert_eq!(arrhenius_factor(50e3, 25.0, 25.0), 1.0);
+        // ~53 kJ/mol doubles a rate between 25 and 35 °C.
+        assert!((arrhenius_factor(52.9e3, 35.0, 25.0) - 2.0).abs() < 0.01);
+        assert!(arrhenius_factor(50e3, 5.0, 25.0) < 1.0);
+    }
+}
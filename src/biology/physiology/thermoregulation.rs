@@ -0,0 +1,543 @@
+use crate::biology::physiology::properties::{ChemicalProperty, PropertyConsumer};
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::engine::{load, save, unknown_input, Component};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Two-node (core/skin shell) model with proportional hypothalamic control.
+// Constants are from the Gagge model unless noted.
+// Gagge AP, Stolwijk JAJ, Nishi Y (1971) ASHRAE Trans 77(1):247-262
+// Gagge AP, Fobelets AP, Berglund LG (1986) ASHRAE Trans 92(2B):709-731
+pub const CORE_SET_POINT_C: f64 = 36.8;
+pub const SKIN_SET_POINT_C: f64 = 33.7;
+// Resting metabolic rate, 1 met.
+pub const MET_W_PER_M2: f64 = 58.2;
+// Whole-body specific heat 0.97 W·h/kg/K.
+const BODY_SPECIFIC_HEAT_J_PER_KG_K: f64 = 3492.0;
+// Tissue conductance between core and skin with blood flow shut down.
+const BASAL_CORE_SKIN_CONDUCTANCE_W_PER_M2_K: f64 = 5.28;
+// Heat carried by blood, 1.163 W·h per litre per kelvin.
+const BLOOD_HEAT_CAPACITY_W_H_PER_L_K: f64 = 1.163;
+const NEUTRAL_SKIN_BLOOD_FLOW_L_PER_M2_H: f64 = 6.3;
+const VASODILATION_GAIN_L_PER_M2_H_K: f64 = 50.0;
+const VASOCONSTRICTION_GAIN_PER_K: f64 = 0.5;
+const MAX_SKIN_BLOOD_FLOW_L_PER_M2_H: f64 = 90.0;
+const MIN_SKIN_BLOOD_FLOW_L_PER_M2_H: f64 = 0.5;
+const SWEAT_GAIN_G_PER_M2_H_K: f64 = 170.0;
+const SWEAT_SKIN_SCALE_K: f64 = 10.7;
+// Latent heat of sweat, 0.68 W·h/g.
+const SWEAT_LATENT_HEAT_W_H_PER_G: f64 = 0.68;
+const SHIVER_GAIN_W_PER_M2_K2: f64 = 19.4;
+// Insensible diffusion through dry skin is 6% of the evaporative maximum.
+const SKIN_DIFFUSION_FRACTION: f64 = 0.06;
+const LINEAR_RADIATIVE_COEFFICIENT_W_PER_M2_K: f64 = 4.7;
+// Lewis relation for evaporative vs convective transfer, K/kPa.
+const LEWIS_RATIO_K_PER_KPA: f64 = 16.5;
+const CLO_M2_K_PER_W: f64 = 0.155;
+const CLOTHING_PERMEABILITY_INDEX: f64 = 0.45;
+// Convective exchange in still water is ~25 times that of still air.
+// Nadel ER et al. (1974) J Appl Physiol 36:465-471
+const WATER_CONVECTIVE_COEFFICIENT_W_PER_M2_K: f64 = 230.0;
+const MAX_INTEGRATION_STEP_S: f64 = 10.0;
+
+// Tetens saturation vapour pressure over water.
+fn saturation_vapour_pressure_kpa(celsius: f64) -> f64 {
+    0.61078 * (17.27 * celsius / (celsius + 237.3)).exp()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ThermalMedium {
+    Air,
+    Water,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ThermalEnvironment {
+    pub medium: ThermalMedium,
+    // Air or water temperature.
+    pub ambient_temperature_c: f64,
+    pub mean_radiant_temperature_c: f64,
+    pub air_velocity_m_s: f64,
+    pub relative_humidity: f64,
+    pub clothing_clo: f64,
+}
+
+impl ThermalEnvironment {
+    pub fn air(temperature_c: f64, relative_humidity: f64) -> BiologyResult<Self> {
+        let env = Self {
+            medium: ThermalMedium::Air,
+            ambient_temperature_c: temperature_c,
+            mean_radiant_temperature_c: temperature_c,
+            air_velocity_m_s: 0.1,
+            relative_humidity,
+            clothing_clo: 0.0,
+        };
+        env.validate()?;
+        Ok(env)
+    }
+
+    // Nude resting subjects are thermoneutral near 28-30 °C.
+    pub fn thermoneutral() -> Self {
+        Self::air(29.0, 0.5).expect("valid preset")
+    }
+
+    pub fn water(temperature_c: f64) -> BiologyResult<Self> {
+        let env = Self {
+            medium: ThermalMedium::Water,
+            ..Self::air(temperature_c, 1.0)?
+        };
+        Ok(env)
+    }
+
+    pub fn with_clothing(mut self, clo: f64) -> BiologyResult<Self> {
+        self.clothing_clo = clo;
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn with_air_velocity(mut self, velocity_m_s: f64) -> BiologyResult<Self> {
+        self.air_velocity_m_s = velocity_m_s;
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&self.relative_humidity) {
+            return Err(BiologyError::InvalidParameter(
+                "relative humidity must lie in [0, 1]".to_string(),
+            ));
+        }
+        if self.air_velocity_m_s < 0.0 || self.clothing_clo < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "air velocity and clothing insulation must be non-negative".to_string(),
+            ));
+        }
+        if !(-60.0..=60.0).contains(&self.ambient_temperature_c) {
+            return Err(BiologyError::InvalidParameter(
+                "ambient temperature must lie in [-60, 60] °C".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn vapour_pressure_kpa(&self) -> f64 {
+        self.relative_humidity * saturation_vapour_pressure_kpa(self.ambient_temperature_c)
+    }
+
+    pub fn convective_coefficient_w_per_m2_k(&self) -> f64 {
+        match self.medium {
+            ThermalMedium::Air => (8.3 * self.air_velocity_m_s.powf(0.6)).max(3.1),
+            ThermalMedium::Water => WATER_CONVECTIVE_COEFFICIENT_W_PER_M2_K,
+        }
+    }
+}
+
+// Whole-body heat flows in watts; losses are positive.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HeatBalance {
+    pub metabolic_w: f64,
+    pub shivering_w: f64,
+    pub radiation_w: f64,
+    pub convection_w: f64,
+    pub conduction_w: f64,
+    pub evaporation_w: f64,
+    pub respiratory_w: f64,
+}
+
+impl HeatBalance {
+    pub fn production_w(&self) -> f64 {
+        self.metabolic_w + self.shivering_w
+    }
+
+    pub fn loss_w(&self) -> f64 {
+        self.radiation_w
+            + self.convection_w
+            + self.conduction_w
+            + self.evaporation_w
+            + self.respiratory_w
+    }
+
+    pub fn storage_w(&self) -> f64 {
+        self.production_w() - self.loss_w()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct ThermalFluxes {
+    balance: HeatBalance,
+    core_to_skin_w_per_m2: f64,
+    skin_blood_flow_l_per_m2_h: f64,
+    sweat_g_per_m2_h: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thermoregulation {
+    pub core_temperature_c: f64,
+    pub skin_temperature_c: f64,
+    pub core_set_point_c: f64,
+    pub weight_kg: f64,
+    pub surface_area_m2: f64,
+    pub activity_w: f64,
+    pub environment: ThermalEnvironment,
+    pub cumulative_sweat_g: f64,
+    pub elapsed_s: f64,
+}
+
+impl Thermoregulation {
+    pub fn new_adult() -> Self {
+        Self {
+            core_temperature_c: CORE_SET_POINT_C,
+            skin_temperature_c: SKIN_SET_POINT_C,
+            core_set_point_c: CORE_SET_POINT_C,
+            weight_kg: 70.0,
+            surface_area_m2: 1.8,
+            activity_w: 0.0,
+            environment: ThermalEnvironment::thermoneutral(),
+            cumulative_sweat_g: 0.0,
+            elapsed_s: 0.0,
+        }
+    }
+
+    pub fn set_environment(&mut self, environment: ThermalEnvironment) -> BiologyResult<()> {
+        environment.validate()?;
+        self.environment = environment;
+        Ok(())
+    }
+
+    // The environment with air and radiant temperature both set to `c`.
+    fn at_ambient(&self, c: f64) -> ThermalEnvironment {
+        let mut environment = self.environment;
+        environment.ambient_temperature_c = c;
+        environment.mean_radiant_temperature_c = c;
+        environment
+    }
+
+    // Heat liberated by muscular work above rest, in watts.
+    pub fn set_activity_w(&mut self, activity_w: f64) -> BiologyResult<()> {
+        check_activity_w(activity_w)?;
+        self.activity_w = activity_w;
+        Ok(())
+    }
+
+    // Pyrogens (PGE2 acting on the preoptic area) raise the set point; the
+    // body then defends the new value with vasoconstriction and shivering.
+    pub fn set_fever_shift(&mut self, shift_c: f64) -> BiologyResult<()> {
+        check_fever_shift(shift_c)?;
+        self.core_set_point_c = CORE_SET_POINT_C + shift_c;
+        Ok(())
+    }
+
+    fn skin_mass_fraction(skin_blood_flow: f64) -> f64 {
+        0.0418 + 0.745 / (skin_blood_flow + 0.585)
+    }
+
+    fn fluxes(&self) -> ThermalFluxes {
+        let env = &self.environment;
+        let warm_core = (self.core_temperature_c - self.core_set_point_c).max(0.0);
+        let cold_core = (self.core_set_point_c - self.core_temperature_c).max(0.0);
+        let warm_skin = (self.skin_temperature_c - SKIN_SET_POINT_C).max(0.0);
+        let cold_skin = (SKIN_SET_POINT_C - self.skin_temperature_c).max(0.0);
+
+        let skin_blood_flow = ((NEUTRAL_SKIN_BLOOD_FLOW_L_PER_M2_H
+            + VASODILATION_GAIN_L_PER_M2_H_K * warm_core)
+            / (1.0 + VASOCONSTRICTION_GAIN_PER_K * (cold_skin + cold_core)))
+            .clamp(
+                MIN_SKIN_BLOOD_FLOW_L_PER_M2_H,
+                MAX_SKIN_BLOOD_FLOW_L_PER_M2_H,
+            );
+        let sweat = SWEAT_GAIN_G_PER_M2_H_K * warm_core * (warm_skin / SWEAT_SKIN_SCALE_K).exp();
+        // Skin and core cold signals multiply; after a febrile set-point jump
+        // the central signal alone is enough to drive chills.
+        let shivering = SHIVER_GAIN_W_PER_M2_K2 * cold_skin.max(cold_core) * cold_core;
+        let metabolic = MET_W_PER_M2 + self.activity_w / self.surface_area_m2;
+        let total_metabolic = metabolic + shivering;
+
+        let core_to_skin = (BASAL_CORE_SKIN_CONDUCTANCE_W_PER_M2_K
+            + BLOOD_HEAT_CAPACITY_W_H_PER_L_K * skin_blood_flow)
+            * (self.core_temperature_c - self.skin_temperature_c);
+
+        let ambient_pa = env.vapour_pressure_kpa();
+        let respiratory = 0.0014 * total_metabolic * (34.0 - env.ambient_temperature_c)
+            + 0.0173 * total_metabolic * (5.87 - ambient_pa);
+
+        let h_c = env.convective_coefficient_w_per_m2_k();
+        let (radiation, convection, conduction, evaporation) = match env.medium {
+            ThermalMedium::Water => (
+                0.0,
+                0.0,
+                h_c * (self.skin_temperature_c - env.ambient_temperature_c),
+                0.0,
+            ),
+            ThermalMedium::Air => {
+                let h_r = LINEAR_RADIATIVE_COEFFICIENT_W_PER_M2_K;
+                let clothing_area_factor = 1.0 + 0.15 * env.clothing_clo;
+                let clothing_resistance = CLO_M2_K_PER_W * env.clothing_clo;
+                let surface_resistance = 1.0 / (clothing_area_factor * (h_c + h_r));
+                let scale = 1.0 / (clothing_resistance + surface_resistance);
+                let radiation =
+                    scale * h_r * (self.skin_temperature_c - env.mean_radiant_temperature_c)
+                        / (h_c + h_r);
+                let convection =
+                    scale * h_c * (self.skin_temperature_c - env.ambient_temperature_c)
+                        / (h_c + h_r);
+
+                let h_e = LEWIS_RATIO_K_PER_KPA * h_c;
+                let vapour_resistance = clothing_resistance
+                    / (LEWIS_RATIO_K_PER_KPA * CLOTHING_PERMEABILITY_INDEX)
+                    + 1.0 / (clothing_area_factor * h_e);
+                let e_max = ((saturation_vapour_pressure_kpa(self.skin_temperature_c)
+                    - ambient_pa)
+                    / vapour_resistance)
+                    .max(0.0);
+                // Sweat beyond the evaporative limit drips off uselessly.
+                let e_sweat = (sweat * SWEAT_LATENT_HEAT_W_H_PER_G).min(e_max);
+                let sweat_wettedness = if e_max > 0.0 { e_sweat / e_max } else { 1.0 };
+                let diffusion = SKIN_DIFFUSION_FRACTION * (1.0 - sweat_wettedness) * e_max;
+                (radiation, convection, 0.0, e_sweat + diffusion)
+            }
+        };
+
+        let area = self.surface_area_m2;
+        ThermalFluxes {
+            balance: HeatBalance {
+                metabolic_w: metabolic * area,
+                shivering_w: shivering * area,
+                radiation_w: radiation * area,
+                convection_w: convection * area,
+                conduction_w: conduction * area,
+                evaporation_w: evaporation * area,
+                respiratory_w: respiratory * area,
+            },
+            core_to_skin_w_per_m2: core_to_skin,
+            skin_blood_flow_l_per_m2_h: skin_blood_flow,
+            sweat_g_per_m2_h: sweat,
+        }
+    }
+
+    pub fn heat_balance(&self) -> HeatBalance {
+        self.fluxes().balance
+    }
+
+    pub fn skin_blood_flow_l_per_m2_h(&self) -> f64 {
+        self.fluxes().skin_blood_flow_l_per_m2_h
+    }
+
+    pub fn sweat_rate_g_per_h(&self) -> f64 {
+        self.fluxes().sweat_g_per_m2_h * self.surface_area_m2
+    }
+
+    pub fn temperature(&self) -> ChemicalProperty {
+        ChemicalProperty::Temperature {
+            celsius: self.core_temperature_c,
+        }
+    }
+
+    // Advances the two compartments and returns the new core temperature.
+    pub fn step(&mut self, dt_s: f64) -> ChemicalProperty {
+        let substeps = (dt_s / MAX_INTEGRATION_STEP_S).ceil().max(1.0) as usize;
+        let h = dt_s / substeps as f64;
+        let capacity_per_m2 = self.weight_kg * BODY_SPECIFIC_HEAT_J_PER_KG_K / self.surface_area_m2;
+        for _ in 0..substeps {
+            let flux = self.fluxes();
+            let area = self.surface_area_m2;
+            let b = flux.balance;
+            let skin_fraction = Self::skin_mass_fraction(flux.skin_blood_flow_l_per_m2_h);
+            let core_net = (b.production_w() - b.respiratory_w) / area - flux.core_to_skin_w_per_m2;
+            let skin_net = flux.core_to_skin_w_per_m2
+                - (b.radiation_w + b.convection_w + b.conduction_w + b.evaporation_w) / area;
+            self.core_temperature_c += core_net * h / ((1.0 - skin_fraction) * capacity_per_m2);
+            self.skin_temperature_c += skin_net * h / (skin_fraction * capacity_per_m2);
+            self.cumulative_sweat_g += flux.sweat_g_per_m2_h * area * h / 3600.0;
+            self.elapsed_s += h;
+        }
+        self.temperature()
+    }
+
+    // Steps for the given minutes, pushing each minute's core temperature
+    // to every temperature-sensitive consumer.
+    pub fn run_minutes(&mut self, minutes: usize, consumers: &mut [&mut dyn PropertyConsumer]) {
+        for _ in 0..minutes {
+            let property = self.step(60.0);
+            for consumer in consumers.iter_mut() {
+                consumer.consume(property);
+            }
+        }
+    }
+
+    pub fn is_hypothermic(&self) -> bool {
+        self.core_temperature_c < 35.0
+    }
+
+    pub fn is_hyperthermic(&self) -> bool {
+        self.core_temperature_c > 38.5
+    }
+}
+
+impl Default for Thermoregulation {
+    fn default() -> Self {
+        Self::new_adult()
+    }
+}
+
+fn check_activity_w(activity_w: f64) -> BiologyResult<()> {
+    if activity_w < 0.0 {
+        return Err(BiologyError::InvalidParameter(
+            "activity heat must be non-negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_fever_shift(shift_c: f64) -> BiologyResult<()> {
+    if !(0.0..=4.0).contains(&shift_c) {
+        return Err(BiologyError::InvalidParameter(
+            "fever set-point shift must lie in [0, 4] °C".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl Component for Thermoregulation {
+    fn kind(&self) -> &'static str {
+        "thermoregulation"
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("activity_w", "W"),
+            ("ambient_temperature_c", "°C"),
+            ("fever_shift_c", "°C"),
+        ]
+    }
+
+    fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("core_temperature_c", "°C"),
+            ("skin_temperature_c", "°C"),
+            ("sweat_rate_g_per_h", "g/h"),
+            ("metabolic_w", "W"),
+        ]
+    }
+
+    fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "activity_w" => self.set_activity_w(value),
+            "fever_shift_c" => self.set_fever_shift(value),
+            "ambient_temperature_c" => self.set_environment(self.at_ambient(value)),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn check_input(&self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "activity_w" => check_activity_w(value),
+            "fever_shift_c" => check_fever_shift(value),
+            "ambient_temperature_c" => self.at_ambient(value).validate(),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn output(&self, name: &str) -> Option<f64> {
+        match name {
+            "core_temperature_c" => Some(self.core_temperature_c),
+            "skin_temperature_c" => Some(self.skin_temperature_c),
+            "sweat_rate_g_per_h" => Some(self.sweat_rate_g_per_h()),
+            "metabolic_w" => Some(self.heat_balance().metabolic_w),
+            _ => None,
+        }
+    }
+
+    fn step(&mut self, dt_minutes: f64) {
+        Thermoregulation::step(self, dt_minutes * 60.0);
+    }
+
+    fn save_state(&self) -> Value {
+        save(self)
+    }
+
+    fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+        load(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::metabolism::enzyme_kinetics::MichaelisMentenEnzyme;
+    use crate::pathology::fibrosis::{FibrosisModel, FibroticTissue};
+
+    #[test]
+    fn test_thermoneutral_core_holds_near_37() {
+        let mut body = Thermoregulation::new_adult();
+        body.run_minutes(180, &mut []);
+        assert!((36.5..37.3).contains(&body.core_temperature_c));
+        assert!((32.5..34.5).contains(&body.skin_temperature_c));
+        assert!(body.heat_balance().storage_w().abs() < 10.0);
+        assert_eq!(body.heat_balance().shivering_w, 0.0);
+    }
+
+    #[test]
+    fn test_exercise_in_heat_raises_core_and_sweating() {
+        let mut body = Thermoregulation::new_adult();
+        body.set_environment(ThermalEnvironment::air(35.0, 0.6).unwrap())
+            .unwrap();
+        body.set_activity_w(400.0).unwrap();
+        body.run_minutes(60, &mut []);
+        assert!(body.is_hyperthermic());
+        assert!(body.cumulative_sweat_g > 500.0 && body.cumulative_sweat_g < 1500.0);
+        let balance = body.heat_balance();
+        assert!(balance.evaporation_w > balance.radiation_w + balance.convection_w);
+        assert!(body.skin_blood_flow_l_per_m2_h() > 30.0);
+        assert!(body.set_activity_w(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_cold_triggers_vasoconstriction_and_shivering() {
+        let mut air = Thermoregulation::new_adult();
+        let cold_air = ThermalEnvironment::air(5.0, 0.5)
+            .unwrap()
+            .with_clothing(1.0)
+            .unwrap();
+        air.set_environment(cold_air).unwrap();
+        air.run_minutes(60, &mut []);
+        assert!(air.skin_blood_flow_l_per_m2_h() < 0.5 * NEUTRAL_SKIN_BLOOD_FLOW_L_PER_M2_H);
+        assert!(air.heat_balance().shivering_w > 0.0);
+
+        let mut immersed = Thermoregulation::new_adult();
+        immersed
+            .set_environment(ThermalEnvironment::water(10.0).unwrap())
+            .unwrap();
+        immersed.run_minutes(60, &mut []);
+        let balance = immersed.heat_balance();
+        assert!(immersed.skin_temperature_c < 12.0);
+        assert!(balance.shivering_w > 1.5 * balance.metabolic_w);
+        assert!(balance.conduction_w > 1.3 * air.heat_balance().loss_w());
+        assert!(immersed.core_temperature_c < air.core_temperature_c);
+        assert!(ThermalEnvironment::air(20.0, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_fever_set_point_raises_core() {
+        let mut body = Thermoregulation::new_adult();
+        body.set_fever_shift(2.0).unwrap();
+        body.run_minutes(30, &mut []);
+        assert!(body.heat_balance().shivering_w > 0.0);
+        body.run_minutes(210, &mut []);
+        assert!((38.3..39.0).contains(&body.core_temperature_c));
+        assert!(body.set_fever_shift(5.0).is_err());
+    }
+
+    #[test]
+    fn test_consumers_follow_emitted_core_temperature() {
+        let mut enzyme = MichaelisMentenEnzyme::new("LDH".to_string(), 100.0, 1.0, 1000.0);
+        let mut scar = FibrosisModel::new(FibroticTissue::Skin);
+        let baseline_velocity = enzyme.reaction_velocity(1.0);
+        let mut body = Thermoregulation::new_adult();
+        body.set_fever_shift(2.0).unwrap();
+        body.run_minutes(240, &mut [&mut enzyme, &mut scar]);
+        assert_eq!(enzyme.temperature_celsius, body.core_temperature_c);
+        assert_eq!(scar.temperature_celsius, body.core_temperature_c);
+        assert!(enzyme.reaction_velocity(1.0) > 1.1 * baseline_velocity);
+    }
+}
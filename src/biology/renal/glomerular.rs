@@ -0,0 +1,174 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Starling ultrafiltration along the glomerular capillary: protein-free
+// filtrate leaves the plasma, so oncotic pressure climbs from afferent to
+// efferent end and net filtration pressure falls along the capillary.
+// Deen WM, Robertson CR, Brenner BM (1972) Am J Physiol 223:1178-1183, PMID 4654350
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GlomerularFiltration {
+    pub renal_plasma_flow_ml_min: f64,
+    pub capillary_pressure_mmhg: f64,
+    pub bowman_space_pressure_mmhg: f64,
+    pub afferent_oncotic_pressure_mmhg: f64,
+    // Whole-kidney Kf for both kidneys.
+    pub ultrafiltration_coefficient_ml_min_mmhg: f64,
+    pub functioning_nephron_fraction: f64,
+}
+
+impl GlomerularFiltration {
+    // RPF ~650 mL/min, GFR ~125 mL/min, FF ~0.19 in young adults.
+    // Smith HW (1951) The Kidney, Oxford University Press
+    pub fn new_healthy() -> Self {
+        Self {
+            renal_plasma_flow_ml_min: 650.0,
+            capillary_pressure_mmhg: 55.0,
+            bowman_space_pressure_mmhg: 15.0,
+            afferent_oncotic_pressure_mmhg: 25.0,
+            ultrafiltration_coefficient_ml_min_mmhg: 10.4,
+            functioning_nephron_fraction: 1.0,
+        }
+    }
+
+    // Solves GFR = Kf·(Pgc − Pbs − π̄) with π̄ the mean of afferent and
+    // efferent oncotic pressure, π_e = π_a / (1 − FF).
+    pub fn gfr_ml_min(&self) -> f64 {
+        let kf = self.ultrafiltration_coefficient_ml_min_mmhg * self.functioning_nephron_fraction;
+        let rpf = self.renal_plasma_flow_ml_min.max(1e-9);
+        let pi_a = self.afferent_oncotic_pressure_mmhg;
+        let hydraulic = self.capillary_pressure_mmhg - self.bowman_space_pressure_mmhg;
+        let mut gfr = 0.0;
+        for _ in 0..60 {
+            let ff = (gfr / rpf).min(0.9);
+            let pi_mean = 0.5 * (pi_a + pi_a / (1.0 - ff));
+            let next = (kf * (hydraulic - pi_mean)).max(0.0);
+            gfr = 0.5 * (gfr + next);
+        }
+        gfr
+    }
+
+    pub fn filtration_fraction(&self) -> f64 {
+        self.gfr_ml_min() / self.renal_plasma_flow_ml_min.max(1e-9)
+    }
+
+    pub fn lose_nephrons(&mut self, fraction: f64) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "nephron loss fraction must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.functioning_nephron_fraction *= 1.0 - fraction;
+        Ok(())
+    }
+}
+
+impl Default for GlomerularFiltration {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+// Creatinine generation ~20-25 mg/kg/day in men, ~15-20 in women.
+// Heymsfield SB et al. (1983) Am J Clin Nutr 37:478-494, PMID 6829490
+pub fn creatinine_generation_mg_per_day(weight_kg: f64, is_female: bool) -> f64 {
+    weight_kg * if is_female { 18.0 } else { 23.0 }
+}
+
+// Tubular secretion adds ~10-20% to filtered creatinine clearance, rising
+// as GFR falls.
+// Shemesh O et al. (1985) Kidney Int 28:830-838, PMID 2418254
+pub fn creatinine_secretion_fraction(gfr_ml_min: f64) -> f64 {
+    0.15 + 0.35 * (1.0 - gfr_ml_min / 120.0).clamp(0.0, 1.0)
+}
+
+// Steady state: generation = Scr · (GFR + secretion clearance).
+pub fn steady_state_creatinine_mg_dl(generation_mg_per_day: f64, gfr_ml_min: f64) -> f64 {
+    let clearance_dl_per_day =
+        gfr_ml_min * (1.0 + creatinine_secretion_fraction(gfr_ml_min)) * 1440.0 / 100.0;
+    generation_mg_per_day / clearance_dl_per_day.max(1e-9)
+}
+
+// Race-free CKD-EPI creatinine equation, mL/min/1.73 m².
+// Inker LA et al. (2021) N Engl J Med 385:1737-1749, PMID 34554658
+pub fn ckd_epi_2021_egfr(serum_creatinine_mg_dl: f64, age_years: f64, is_female: bool) -> f64 {
+    let (kappa, alpha, sex_factor) = if is_female {
+        (0.7, -0.241, 1.012)
+    } else {
+        (0.9, -0.302, 1.0)
+    };
+    let ratio = serum_creatinine_mg_dl.max(1e-6) / kappa;
+    142.0
+        * ratio.min(1.0).powf(alpha)
+        * ratio.max(1.0).powf(-1.2)
+        * 0.9938f64.powf(age_years)
+        * sex_factor
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CkdStage {
+    G1,
+    G2,
+    G3a,
+    G3b,
+    G4,
+    G5,
+}
+
+impl CkdStage {
+    // KDIGO (2013) Kidney Int Suppl 3:1-150
+    pub fn from_egfr(egfr: f64) -> Self {
+        match egfr {
+            e if e >= 90.0 => CkdStage::G1,
+            e if e >= 60.0 => CkdStage::G2,
+            e if e >= 45.0 => CkdStage::G3a,
+            e if e >= 30.0 => CkdStage::G3b,
+            e if e >= 15.0 => CkdStage::G4,
+            _ => CkdStage::G5,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_healthy_gfr_and_filtration_fraction() {
+        let glomeruli = GlomerularFiltration::new_healthy();
+        let gfr = glomeruli.gfr_ml_min();
+        assert!(gfr > 110.0 && gfr < 140.0, "gfr {gfr}");
+        let ff = glomeruli.filtration_fraction();
+        assert!(ff > 0.16 && ff < 0.22);
+    }
+
+    #[test]
+    fn test_afferent_constriction_lowers_gfr() {
+        let healthy = GlomerularFiltration::new_healthy();
+        let mut constricted = healthy;
+        constricted.capillary_pressure_mmhg = 48.0;
+        constricted.renal_plasma_flow_ml_min = 500.0;
+        assert!(constricted.gfr_ml_min() < 0.8 * healthy.gfr_ml_min());
+    }
+
+    #[test]
+    fn test_creatinine_rises_hyperbolically_as_gfr_falls() {
+        let generation = creatinine_generation_mg_per_day(70.0, false);
+        let normal = steady_state_creatinine_mg_dl(generation, 120.0);
+        let halved = steady_state_creatinine_mg_dl(generation, 60.0);
+        assert!(normal > 0.7 && normal < 1.2, "scr {normal}");
+        assert!(halved > 1.5 * normal);
+    }
+
+    #[test]
+    fn test_ckd_epi_round_trip_and_staging() {
+        assert!((ckd_epi_2021_egfr(1.0, 50.0, false) - 92.0).abs() < 2.0);
+        assert!(ckd_epi_2021_egfr(1.0, 50.0, true) < ckd_epi_2021_egfr(1.0, 50.0, false));
+        assert_eq!(
+            CkdStage::from_egfr(ckd_epi_2021_egfr(2.0, 70.0, false)),
+            CkdStage::G3b
+        );
+        let mut glomeruli = GlomerularFiltration::new_healthy();
+        glomeruli.lose_nephrons(0.5).unwrap();
+        assert!(glomeruli.lose_nephrons(1.5).is_err());
+    }
+}
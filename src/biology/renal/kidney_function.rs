@@ -0,0 +1,149 @@
+use crate::biology::renal::glomerular::{
+    ckd_epi_2021_egfr, creatinine_generation_mg_per_day, steady_state_creatinine_mg_dl, CkdStage,
+    GlomerularFiltration,
+};
+use crate::biology::renal::tubular::{
+    RenalDrugHandling, Solute, SoluteExcretion, TubularTransport,
+};
+use crate::pharmacology::pharmacokinetics::{DoseAdjustment, Pharmacokinetics};
+use serde::{Deserialize, Serialize};
+
+// Young-adult GFR that drug labels and secretion capacities refer to.
+pub const REFERENCE_GFR_ML_MIN: f64 = 125.0;
+
+// Whole-kidney function: Starling filtration feeding segmental tubular
+// transport, with creatinine as the clinical read-out and drug renal
+// clearance as the hand-off to pharmacokinetics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenalFunction {
+    pub glomeruli: GlomerularFiltration,
+    pub tubules: TubularTransport,
+    pub weight_kg: f64,
+    pub age_years: f64,
+    pub is_female: bool,
+}
+
+impl RenalFunction {
+    pub fn new_healthy_adult() -> Self {
+        Self {
+            glomeruli: GlomerularFiltration::new_healthy(),
+            tubules: TubularTransport::new_healthy(),
+            weight_kg: 70.0,
+            age_years: 40.0,
+            is_female: false,
+        }
+    }
+
+    pub fn gfr_ml_min(&self) -> f64 {
+        self.glomeruli.gfr_ml_min()
+    }
+
+    pub fn serum_creatinine_mg_dl(&self) -> f64 {
+        let generation = creatinine_generation_mg_per_day(self.weight_kg, self.is_female);
+        steady_state_creatinine_mg_dl(generation, self.gfr_ml_min())
+    }
+
+    pub fn estimated_gfr(&self) -> f64 {
+        ckd_epi_2021_egfr(
+            self.serum_creatinine_mg_dl(),
+            self.age_years,
+            self.is_female,
+        )
+    }
+
+    pub fn ckd_stage(&self) -> CkdStage {
+        CkdStage::from_egfr(self.estimated_gfr())
+    }
+
+    pub fn excretion(&self, solute: Solute, plasma: f64) -> SoluteExcretion {
+        self.tubules.handle(solute, plasma, self.gfr_ml_min())
+    }
+
+    pub fn urine_output_ml_per_day(&self) -> f64 {
+        self.tubules.urine_flow_ml_min(self.gfr_ml_min()) * 1440.0
+    }
+
+    pub fn drug_renal_clearance_ml_min(&self, drug: &RenalDrugHandling) -> f64 {
+        drug.renal_clearance_ml_min(self.gfr_ml_min(), REFERENCE_GFR_ML_MIN)
+    }
+
+    // Only the renally excreted fraction (fe, taken from percent unchanged)
+    // scales with kidney function; non-renal clearance is left as is.
+    pub fn adjust_pharmacokinetics(
+        &self,
+        pk: &Pharmacokinetics,
+        drug: &RenalDrugHandling,
+    ) -> Pharmacokinetics {
+        let fe = (pk.excretion.percent_unchanged / 100.0).clamp(0.0, 1.0);
+        let reference = drug.renal_clearance_ml_min(REFERENCE_GFR_ML_MIN, REFERENCE_GFR_ML_MIN);
+        let renal_ratio = self.drug_renal_clearance_ml_min(drug) / reference.max(1e-9);
+        pk.with_clearance_ratio(1.0 - fe + fe * renal_ratio)
+    }
+
+    pub fn dose_adjustment(&self) -> DoseAdjustment {
+        DoseAdjustment::renal_impairment(self.estimated_gfr())
+    }
+}
+
+impl Default for RenalFunction {
+    fn default() -> Self {
+        Self::new_healthy_adult()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validation::ground_truth::GroundTruthDatabase;
+
+    #[test]
+    fn test_healthy_adult_matches_reference_ranges() {
+        let kidney = RenalFunction::new_healthy_adult();
+        let db = GroundTruthDatabase::new();
+        let renal = db.get_dataset("renal").unwrap();
+        assert!(renal.is_within_expected_range(
+            "serum_creatinine_mg_dl_male",
+            kidney.serum_creatinine_mg_dl()
+        ));
+        assert!(renal.is_within_expected_range("gfr_ml_per_min_1_73m2", kidney.estimated_gfr()));
+        assert!(renal.is_within_expected_range(
+            "urine_output_ml_per_hr",
+            kidney.urine_output_ml_per_day() / 24.0
+        ));
+        assert_eq!(kidney.ckd_stage(), CkdStage::G1);
+    }
+
+    #[test]
+    fn test_nephron_loss_progresses_ckd_stage() {
+        let mut kidney = RenalFunction::new_healthy_adult();
+        kidney.age_years = 65.0;
+        kidney.glomeruli.lose_nephrons(0.7).unwrap();
+        assert!(kidney.serum_creatinine_mg_dl() > 1.6);
+        assert!(matches!(kidney.ckd_stage(), CkdStage::G3b | CkdStage::G4));
+        assert!(kidney.dose_adjustment().adjustment_factor < 1.0);
+    }
+
+    #[test]
+    fn test_renal_impairment_prolongs_renally_cleared_drug() {
+        let mut pk = Pharmacokinetics::new(1.0, 2.5, 18.0);
+        pk.excretion.percent_unchanged = 95.0;
+        let drug = RenalDrugHandling::gentamicin();
+        let healthy = RenalFunction::new_healthy_adult();
+        let unchanged = healthy.adjust_pharmacokinetics(&pk, &drug);
+        assert!(
+            (unchanged.metabolism.half_life_hours / pk.metabolism.half_life_hours - 1.0).abs()
+                < 0.1
+        );
+
+        let mut impaired = RenalFunction::new_healthy_adult();
+        impaired.glomeruli.lose_nephrons(0.75).unwrap();
+        let adjusted = impaired.adjust_pharmacokinetics(&pk, &drug);
+        assert!(adjusted.metabolism.half_life_hours > 3.0 * pk.metabolism.half_life_hours);
+
+        let hepatic = Pharmacokinetics::new(1.0, 2.5, 18.0);
+        let mut hepatic = hepatic;
+        hepatic.excretion.percent_unchanged = 5.0;
+        let barely = impaired.adjust_pharmacokinetics(&hepatic, &drug);
+        assert!(barely.metabolism.half_life_hours < 1.1 * hepatic.metabolism.half_life_hours);
+    }
+}
@@ -0,0 +1,7 @@
+pub mod glomerular;
+pub mod kidney_function;
+pub mod tubular;
+
+pub use glomerular::{ckd_epi_2021_egfr, CkdStage, GlomerularFiltration};
+pub use kidney_function::RenalFunction;
+pub use tubular::{NephronSegment, RenalDrugHandling, Solute, SoluteExcretion, TubularTransport};
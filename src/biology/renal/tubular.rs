@@ -0,0 +1,344 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NephronSegment {
+    ProximalTubule,
+    LoopOfHenle,
+    DistalConvolutedTubule,
+    CollectingDuct,
+}
+
+impl NephronSegment {
+    pub const ALL: [NephronSegment; 4] = [
+        NephronSegment::ProximalTubule,
+        NephronSegment::LoopOfHenle,
+        NephronSegment::DistalConvolutedTubule,
+        NephronSegment::CollectingDuct,
+    ];
+}
+
+// Plasma units: Na⁺/K⁺/phosphate in mmol/L, glucose in mg/dL; filtered and
+// excreted loads are per minute in mmol or mg accordingly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Solute {
+    Sodium,
+    Potassium,
+    Glucose,
+    Phosphate,
+}
+
+impl Solute {
+    fn load_per_min(&self, plasma: f64, flow_ml_min: f64) -> f64 {
+        match self {
+            Solute::Glucose => plasma * flow_ml_min / 100.0,
+            _ => plasma * flow_ml_min / 1000.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoluteExcretion {
+    pub solute: Solute,
+    pub filtered_per_min: f64,
+    // Negative entries are net secretion.
+    pub reabsorbed_per_min: Vec<(NephronSegment, f64)>,
+    pub excreted_per_min: f64,
+}
+
+impl SoluteExcretion {
+    pub fn fractional_excretion(&self) -> f64 {
+        self.excreted_per_min / self.filtered_per_min.max(1e-12)
+    }
+
+    pub fn excreted_per_day(&self) -> f64 {
+        self.excreted_per_min * 1440.0
+    }
+
+    pub fn reabsorbed_in(&self, segment: NephronSegment) -> f64 {
+        self.reabsorbed_per_min
+            .iter()
+            .filter(|(s, _)| *s == segment)
+            .map(|(_, r)| r)
+            .sum()
+    }
+}
+
+// Fractions of the load delivered to each segment that it reabsorbs, chosen
+// so that ~67% of filtered Na⁺ and water go in the proximal tubule, ~25% of
+// Na⁺ in the loop, ~5% in the DCT and ~3% in the collecting duct.
+// Palmer LG, Schnermann J (2015) Clin J Am Soc Nephrol 10:676-687, PMID 25098598
+const PT_FRACTION: f64 = 0.67;
+const LOOP_SODIUM_FRACTION: f64 = 0.76;
+const DCT_SODIUM_FRACTION: f64 = 0.62;
+const CD_SODIUM_FRACTION_AT_NORMAL_ALDOSTERONE: f64 = 0.83;
+// Distal Na⁺ transport saturates, so loop blockade is only partly
+// compensated downstream (mmol/min at a reference GFR of 125 mL/min).
+// Ellison DH et al. (1989) J Clin Invest 83:113-126, PMID 2910903
+const DCT_SODIUM_CAPACITY_MMOL_MIN: f64 = 1.3;
+const CD_SODIUM_CAPACITY_MMOL_MIN: f64 = 0.65;
+const REFERENCE_GFR_ML_MIN: f64 = 125.0;
+// Only the descending limb is water-permeable.
+const LOOP_WATER_FRACTION: f64 = 0.45;
+const DCT_WATER_FRACTION: f64 = 0.0;
+const LOOP_POTASSIUM_FRACTION: f64 = 0.6;
+const DCT_POTASSIUM_FRACTION: f64 = 0.5;
+// Principal-cell K⁺ secretion scales with aldosterone and plasma K⁺.
+// Palmer BF (2015) Clin J Am Soc Nephrol 10:1050-1060, PMID 24721891
+const CD_POTASSIUM_SECRETION_MMOL_MIN: f64 = 0.035;
+// SGLT2 carries ~90% of filtered glucose; under SGLT2 blockade downstream
+// SGLT1 more than doubles its uptake, still leaving 50-60% of the load to
+// the urine.
+// Wright EM et al. (2011) Physiol Rev 91:733-794, PMID 21527736
+// Rieg T et al. (2014) Am J Physiol Renal Physiol 306:F188-F193, PMID 24226519
+const SGLT2_SHARE_OF_TM: f64 = 0.9;
+const SGLT1_RESERVE_UNDER_BLOCKADE: f64 = 1.2;
+// Nephron heterogeneity makes transport maxima splay rather than switch.
+const TM_SPLAY: f64 = 0.998;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TubularTransport {
+    pub aldosterone_activity: f64,
+    pub adh_activity: f64,
+    // Maximal glucose reabsorption ~375 mg/min in healthy adults.
+    // DeFronzo RA et al. (2013) Diabetes Care 36:3169-3176, PMID 23735727
+    pub glucose_tm_mg_min: f64,
+    pub sglt2_inhibition: f64,
+    // TmP/GFR 0.8-1.35 mmol/L; PTH and FGF23 lower it.
+    // Walton RJ, Bijvoet OL (1975) Lancet 2:309-310, PMID 50513
+    pub phosphate_tm_per_gfr_mmol_l: f64,
+    pub loop_diuretic_inhibition: f64,
+}
+
+impl TubularTransport {
+    pub fn new_healthy() -> Self {
+        Self {
+            aldosterone_activity: 1.0,
+            adh_activity: 1.0,
+            glucose_tm_mg_min: 375.0,
+            sglt2_inhibition: 0.0,
+            phosphate_tm_per_gfr_mmol_l: 1.0,
+            loop_diuretic_inhibition: 0.0,
+        }
+    }
+
+    pub fn set_sglt2_inhibition(&mut self, inhibition: f64) -> BiologyResult<()> {
+        self.sglt2_inhibition = unit_fraction(inhibition, "SGLT2 inhibition")?;
+        Ok(())
+    }
+
+    pub fn set_loop_diuretic_inhibition(&mut self, inhibition: f64) -> BiologyResult<()> {
+        self.loop_diuretic_inhibition = unit_fraction(inhibition, "NKCC2 inhibition")?;
+        Ok(())
+    }
+
+    fn collecting_duct_sodium_fraction(&self) -> f64 {
+        let a = CD_SODIUM_FRACTION_AT_NORMAL_ALDOSTERONE * self.aldosterone_activity.max(0.0);
+        a / (1.0 - CD_SODIUM_FRACTION_AT_NORMAL_ALDOSTERONE + a)
+    }
+
+    // Without ADH the collecting duct still reabsorbs ~30% of delivered water;
+    // maximal antidiuresis takes it past 99%.
+    fn collecting_duct_water_fraction(&self) -> f64 {
+        (0.3 + 0.64 * self.adh_activity.max(0.0)).min(0.995)
+    }
+
+    pub fn effective_glucose_tm_mg_min(&self) -> f64 {
+        let blocked = SGLT2_SHARE_OF_TM * self.sglt2_inhibition;
+        let recruited =
+            (1.0 - SGLT2_SHARE_OF_TM) * SGLT1_RESERVE_UNDER_BLOCKADE * self.sglt2_inhibition;
+        self.glucose_tm_mg_min * (1.0 - blocked + recruited)
+    }
+
+    pub fn handle(&self, solute: Solute, plasma: f64, gfr_ml_min: f64) -> SoluteExcretion {
+        let filtered = solute.load_per_min(plasma, gfr_ml_min);
+        let loop_block = 1.0 - self.loop_diuretic_inhibition;
+        let nephron_mass = gfr_ml_min / REFERENCE_GFR_ML_MIN;
+        let mut delivered = filtered;
+        let mut reabsorbed_per_min = Vec::with_capacity(4);
+        for segment in NephronSegment::ALL {
+            let reabsorbed = match (solute, segment) {
+                (Solute::Glucose, NephronSegment::ProximalTubule) => {
+                    saturable(delivered, self.effective_glucose_tm_mg_min())
+                }
+                (Solute::Phosphate, NephronSegment::ProximalTubule) => saturable(
+                    delivered,
+                    self.phosphate_tm_per_gfr_mmol_l * gfr_ml_min / 1000.0,
+                ),
+                (Solute::Glucose | Solute::Phosphate, _) => 0.0,
+                (_, NephronSegment::ProximalTubule) => PT_FRACTION * delivered,
+                (Solute::Sodium, NephronSegment::LoopOfHenle) => {
+                    LOOP_SODIUM_FRACTION * loop_block * delivered
+                }
+                (Solute::Potassium, NephronSegment::LoopOfHenle) => {
+                    LOOP_POTASSIUM_FRACTION * loop_block * delivered
+                }
+                (Solute::Sodium, NephronSegment::DistalConvolutedTubule) => (DCT_SODIUM_FRACTION
+                    * delivered)
+                    .min(DCT_SODIUM_CAPACITY_MMOL_MIN * nephron_mass),
+                (Solute::Potassium, NephronSegment::DistalConvolutedTubule) => {
+                    DCT_POTASSIUM_FRACTION * delivered
+                }
+                (Solute::Sodium, NephronSegment::CollectingDuct) => {
+                    (self.collecting_duct_sodium_fraction() * delivered)
+                        .min(CD_SODIUM_CAPACITY_MMOL_MIN * nephron_mass)
+                }
+                // Secretion is flow-dependent, so loop blockade raises it.
+                (Solute::Potassium, NephronSegment::CollectingDuct) => {
+                    -CD_POTASSIUM_SECRETION_MMOL_MIN
+                        * self.aldosterone_activity.max(0.0)
+                        * (plasma / 4.0)
+                        * (1.0 + self.loop_diuretic_inhibition)
+                }
+            };
+            delivered -= reabsorbed;
+            reabsorbed_per_min.push((segment, reabsorbed));
+        }
+        SoluteExcretion {
+            solute,
+            filtered_per_min: filtered,
+            reabsorbed_per_min,
+            excreted_per_min: delivered.max(0.0),
+        }
+    }
+
+    pub fn urine_flow_ml_min(&self, gfr_ml_min: f64) -> f64 {
+        let after_pt = gfr_ml_min * (1.0 - PT_FRACTION);
+        // Loop blockade washes out the medullary gradient that drives both
+        // descending-limb and collecting-duct water uptake.
+        let gradient = 1.0 - 0.6 * self.loop_diuretic_inhibition;
+        let after_loop = after_pt * (1.0 - LOOP_WATER_FRACTION * gradient);
+        let after_dct = after_loop * (1.0 - DCT_WATER_FRACTION);
+        after_dct * (1.0 - self.collecting_duct_water_fraction() * gradient)
+    }
+}
+
+impl Default for TubularTransport {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+// Non-rectangular hyperbola between first-order uptake and the Tm plateau.
+fn saturable(load: f64, tm: f64) -> f64 {
+    let sum = load + tm;
+    let disc = (sum * sum - 4.0 * TM_SPLAY * load * tm).max(0.0);
+    ((sum - disc.sqrt()) / (2.0 * TM_SPLAY)).min(load)
+}
+
+fn unit_fraction(value: f64, what: &str) -> BiologyResult<f64> {
+    if !(0.0..=1.0).contains(&value) {
+        return Err(BiologyError::InvalidParameter(format!(
+            "{what} must lie in [0, 1]"
+        )));
+    }
+    Ok(value)
+}
+
+// Renal handling of a drug: unbound filtration plus active secretion, less
+// passive back-diffusion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenalDrugHandling {
+    pub name: String,
+    pub fraction_unbound: f64,
+    pub secretion_clearance_ml_min: f64,
+    pub reabsorbed_fraction: f64,
+}
+
+impl RenalDrugHandling {
+    // Aminoglycosides are filtered and not secreted; CLr tracks GFR.
+    // Zaske DE et al. (1982) JAMA 248:3122-3126, PMID 7143690
+    pub fn gentamicin() -> Self {
+        Self {
+            name: "gentamicin".to_string(),
+            fraction_unbound: 0.95,
+            secretion_clearance_ml_min: 0.0,
+            reabsorbed_fraction: 0.0,
+        }
+    }
+
+    // OCT2/MATE secretion gives metformin CLr ~500 mL/min.
+    // Graham GG et al. (2011) Clin Pharmacokinet 50:81-98, PMID 21241070
+    pub fn metformin() -> Self {
+        Self {
+            name: "metformin".to_string(),
+            fraction_unbound: 1.0,
+            secretion_clearance_ml_min: 380.0,
+            reabsorbed_fraction: 0.0,
+        }
+    }
+
+    // Secretory capacity shrinks with nephron mass (intact nephron hypothesis).
+    // Bricker NS et al. (1960) Am J Med 28:77-98, PMID 14430441
+    pub fn renal_clearance_ml_min(&self, gfr_ml_min: f64, reference_gfr_ml_min: f64) -> f64 {
+        let secretion =
+            self.secretion_clearance_ml_min * gfr_ml_min / reference_gfr_ml_min.max(1e-9);
+        (self.fraction_unbound * gfr_ml_min + secretion) * (1.0 - self.reabsorbed_fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sodium_segmental_profile() {
+        let tubules = TubularTransport::new_healthy();
+        let na = tubules.handle(Solute::Sodium, 140.0, 125.0);
+        let pt = na.reabsorbed_in(NephronSegment::ProximalTubule) / na.filtered_per_min;
+        let loop_share = na.reabsorbed_in(NephronSegment::LoopOfHenle) / na.filtered_per_min;
+        assert!((pt - 0.67).abs() < 0.01);
+        assert!(loop_share > 0.2 && loop_share < 0.3);
+        let fe = na.fractional_excretion();
+        assert!(fe > 0.003 && fe < 0.01, "FENa {fe}");
+        assert!(na.excreted_per_day() > 80.0 && na.excreted_per_day() < 250.0);
+    }
+
+    #[test]
+    fn test_glucosuria_above_renal_threshold() {
+        let mut tubules = TubularTransport::new_healthy();
+        let fasting = tubules.handle(Solute::Glucose, 90.0, 125.0);
+        let hyperglycemic = tubules.handle(Solute::Glucose, 300.0, 125.0);
+        assert!(fasting.excreted_per_day() < 500.0);
+        assert!(hyperglycemic.excreted_per_day() > 10_000.0);
+        tubules.set_sglt2_inhibition(1.0).unwrap();
+        let gliflozin = tubules.handle(Solute::Glucose, 90.0, 125.0);
+        // SGLT2 inhibitors cause ~50-80 g/day glucosuria.
+        assert!(gliflozin.excreted_per_day() > 40_000.0);
+        assert!(tubules.set_sglt2_inhibition(2.0).is_err());
+    }
+
+    #[test]
+    fn test_potassium_is_secreted_in_collecting_duct() {
+        let tubules = TubularTransport::new_healthy();
+        let k = tubules.handle(Solute::Potassium, 4.0, 125.0);
+        assert!(k.reabsorbed_in(NephronSegment::CollectingDuct) < 0.0);
+        assert!(k.excreted_per_day() > 50.0 && k.excreted_per_day() < 120.0);
+        let phosphate = tubules.handle(Solute::Phosphate, 1.1, 125.0);
+        assert!(phosphate.fractional_excretion() > 0.05 && phosphate.fractional_excretion() < 0.2);
+    }
+
+    #[test]
+    fn test_adh_and_loop_diuretic_set_urine_volume() {
+        let mut tubules = TubularTransport::new_healthy();
+        let normal = tubules.urine_flow_ml_min(125.0) * 1.44;
+        assert!(normal > 1.0 && normal < 2.5, "urine {normal} L/day");
+        tubules.adh_activity = 0.0;
+        assert!(tubules.urine_flow_ml_min(125.0) * 1.44 > 10.0);
+        let mut diuresed = TubularTransport::new_healthy();
+        diuresed.set_loop_diuretic_inhibition(0.8).unwrap();
+        assert!(diuresed.urine_flow_ml_min(125.0) * 1.44 > 3.0 * normal);
+        let na = diuresed.handle(Solute::Sodium, 140.0, 125.0);
+        assert!(na.fractional_excretion() > 0.05);
+    }
+
+    #[test]
+    fn test_secreted_drug_clearance_exceeds_gfr() {
+        let metformin = RenalDrugHandling::metformin();
+        let gentamicin = RenalDrugHandling::gentamicin();
+        let normal = metformin.renal_clearance_ml_min(125.0, 125.0);
+        assert!(normal > 400.0 && normal < 600.0);
+        assert!((gentamicin.renal_clearance_ml_min(60.0, 125.0) - 57.0).abs() < 1.0);
+        assert!(metformin.renal_clearance_ml_min(30.0, 125.0) < 0.3 * normal);
+    }
+}
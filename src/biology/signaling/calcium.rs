@@ -0,0 +1,327 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::agents::{Position, World};
+use serde::{Deserialize, Serialize};
+
+// Cytosolic/ER Ca²⁺ handling in µM and seconds. IP3 receptor gating and
+// SERCA uptake follow the two-variable reduction of the De Young-Keizer
+// model, which oscillates for intermediate IP3.
+// Li YX, Rinzel J (1994) J Theor Biol 166:461-473
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CalciumParameters {
+    // ER to cytosol volume ratio.
+    pub er_volume_ratio: f64,
+    pub ip3r_max_rate_per_s: f64,
+    pub er_leak_per_s: f64,
+    pub serca_max_um_s: f64,
+    pub serca_half_um: f64,
+    pub ip3_dissociation_um: f64,
+    pub inhibitory_ca_dissociation_um: f64,
+    pub ip3_inhibition_dissociation_um: f64,
+    pub activating_ca_dissociation_um: f64,
+    pub inactivation_binding_per_um_s: f64,
+    // Ryanodine receptors release Ca²⁺ in response to Ca²⁺ itself (CICR);
+    // sparse in non-excitable cells, dominant in muscle.
+    // Fabiato A (1983) Am J Physiol 245:C1-C14
+    pub ryr_max_rate_per_s: f64,
+    pub ryr_half_ca_um: f64,
+    // Store depletion opens STIM1/Orai1 channels in the plasma membrane,
+    // balanced at rest by the PMCA pump.
+    // Putney JW (1986) Cell Calcium 7:1-12
+    pub soce_max_um_s: f64,
+    pub soce_half_er_um: f64,
+    pub pmca_max_um_s: f64,
+    pub pmca_half_um: f64,
+    pub ip3_degradation_per_s: f64,
+}
+
+impl CalciumParameters {
+    pub fn li_rinzel_1994() -> Self {
+        Self {
+            er_volume_ratio: 0.185,
+            ip3r_max_rate_per_s: 6.0,
+            er_leak_per_s: 0.11,
+            serca_max_um_s: 0.9,
+            serca_half_um: 0.1,
+            ip3_dissociation_um: 0.13,
+            inhibitory_ca_dissociation_um: 1.049,
+            ip3_inhibition_dissociation_um: 0.9434,
+            activating_ca_dissociation_um: 0.08234,
+            inactivation_binding_per_um_s: 0.2,
+            ryr_max_rate_per_s: 0.0,
+            ryr_half_ca_um: 0.5,
+            soce_max_um_s: 0.02,
+            soce_half_er_um: 5.0,
+            pmca_max_um_s: 0.015,
+            pmca_half_um: 0.2,
+            ip3_degradation_per_s: 0.14,
+        }
+    }
+
+    pub fn with_ryanodine_receptors(mut self, max_rate_per_s: f64) -> BiologyResult<Self> {
+        if max_rate_per_s.is_nan() || max_rate_per_s < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "RyR rate must be non-negative".to_string(),
+            ));
+        }
+        self.ryr_max_rate_per_s = max_rate_per_s;
+        Ok(self)
+    }
+}
+
+impl Default for CalciumParameters {
+    fn default() -> Self {
+        Self::li_rinzel_1994()
+    }
+}
+
+// Ca²⁺ state of one cell; also the agent component the network steps.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CalciumCell {
+    pub cytosolic_um: f64,
+    pub er_um: f64,
+    pub ip3_um: f64,
+    // Fraction of IP3R subunits not Ca²⁺-inactivated.
+    pub ip3r_available: f64,
+    // Agonist-driven PLC activity.
+    pub ip3_production_um_s: f64,
+}
+
+impl Default for CalciumCell {
+    fn default() -> Self {
+        Self::resting()
+    }
+}
+
+impl CalciumCell {
+    pub fn resting() -> Self {
+        Self {
+            cytosolic_um: 0.055,
+            er_um: 10.34,
+            ip3_um: 0.0,
+            ip3r_available: 0.724,
+            ip3_production_um_s: 0.0,
+        }
+    }
+
+    // Open probability of the IP3R from fast IP3 and Ca²⁺ activation and
+    // slow Ca²⁺ inactivation.
+    pub fn ip3r_open_probability(&self, p: &CalciumParameters) -> f64 {
+        let m = self.ip3_um / (self.ip3_um + p.ip3_dissociation_um);
+        let n = self.cytosolic_um / (self.cytosolic_um + p.activating_ca_dissociation_um);
+        (m * n * self.ip3r_available).powi(3)
+    }
+
+    // Net ER release, positive into the cytosol.
+    pub fn er_flux_um_s(&self, p: &CalciumParameters) -> f64 {
+        let c = self.cytosolic_um;
+        let gradient = self.er_um - c;
+        let ryr = p.ryr_max_rate_per_s * c.powi(3) / (c.powi(3) + p.ryr_half_ca_um.powi(3));
+        let release = p.er_volume_ratio
+            * (p.ip3r_max_rate_per_s * self.ip3r_open_probability(p) + ryr + p.er_leak_per_s)
+            * gradient;
+        release - p.serca_max_um_s * c * c / (c * c + p.serca_half_um * p.serca_half_um)
+    }
+
+    pub fn store_operated_entry_um_s(&self, p: &CalciumParameters) -> f64 {
+        let e4 = self.er_um.max(0.0).powi(4);
+        p.soce_max_um_s * p.soce_half_er_um.powi(4) / (p.soce_half_er_um.powi(4) + e4)
+    }
+
+    pub fn pmca_extrusion_um_s(&self, p: &CalciumParameters) -> f64 {
+        let c = self.cytosolic_um;
+        p.pmca_max_um_s * c * c / (c * c + p.pmca_half_um * p.pmca_half_um)
+    }
+
+    pub fn step(&mut self, p: &CalciumParameters, dt_s: f64) {
+        let c = self.cytosolic_um;
+        let er = self.er_flux_um_s(p);
+        let membrane = self.store_operated_entry_um_s(p) - self.pmca_extrusion_um_s(p);
+        let q2 = p.inhibitory_ca_dissociation_um * (self.ip3_um + p.ip3_dissociation_um)
+            / (self.ip3_um + p.ip3_inhibition_dissociation_um);
+        let h_inf = q2 / (q2 + c);
+        let h_rate = p.inactivation_binding_per_um_s * (q2 + c);
+
+        self.cytosolic_um = (c + (er + membrane) * dt_s).max(0.0);
+        self.er_um = (self.er_um - er / p.er_volume_ratio * dt_s).max(0.0);
+        self.ip3r_available = h_inf + (self.ip3r_available - h_inf) * (-h_rate * dt_s).exp();
+        self.ip3_um = (self.ip3_um
+            + (self.ip3_production_um_s - p.ip3_degradation_per_s * self.ip3_um) * dt_s)
+            .max(0.0);
+    }
+}
+
+// Gap junctions pass IP3 between touching cells, so a stimulated cell
+// triggers release in its neighbours in turn: an intercellular wave.
+// Leybaert L, Sanderson MJ (2012) Physiol Rev 92:1359-1392
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GapJunctionCoupling {
+    pub contact_distance: f64,
+    pub ip3_permeability_per_s: f64,
+}
+
+impl GapJunctionCoupling {
+    pub fn new(contact_distance: f64, ip3_permeability_per_s: f64) -> BiologyResult<Self> {
+        if contact_distance.is_nan()
+            || contact_distance <= 0.0
+            || ip3_permeability_per_s.is_nan()
+            || ip3_permeability_per_s < 0.0
+        {
+            return Err(BiologyError::InvalidParameter(
+                "contact distance must be positive and permeability non-negative".to_string(),
+            ));
+        }
+        Ok(Self {
+            contact_distance,
+            ip3_permeability_per_s,
+        })
+    }
+
+    // Advances every agent carrying a Position and a CalciumCell. Contacts
+    // are found by all-pairs distance, which suits tissue patches of a few
+    // thousand cells.
+    pub fn step_world(&self, world: &mut World, params: &CalciumParameters, dt_s: f64) {
+        let cells: Vec<_> = world
+            .query2::<Position, CalciumCell>()
+            .map(|(entity, position, cell)| (entity, position.0, cell.ip3_um))
+            .collect();
+        let mut exchange = vec![0.0; cells.len()];
+        for (i, &(_, a, ip3_a)) in cells.iter().enumerate() {
+            for (j, &(_, b, ip3_b)) in cells.iter().enumerate().skip(i + 1) {
+                if a.distance(b) <= self.contact_distance {
+                    let flux = self.ip3_permeability_per_s * (ip3_b - ip3_a) * dt_s;
+                    exchange[i] += flux;
+                    exchange[j] -= flux;
+                }
+            }
+        }
+        for ((entity, _, _), delta) in cells.into_iter().zip(exchange) {
+            if let Some(cell) = world.get_mut::<CalciumCell>(entity) {
+                cell.ip3_um = (cell.ip3_um + delta).max(0.0);
+                cell.step(params, dt_s);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Vec3;
+
+    fn trace(ip3_production: f64, seconds: f64) -> Vec<f64> {
+        let p = CalciumParameters::default();
+        let mut cell = CalciumCell {
+            ip3_production_um_s: ip3_production,
+            ..CalciumCell::resting()
+        };
+        let dt = 0.001;
+        (0..(seconds / dt) as usize)
+            .map(|_| {
+                cell.step(&p, dt);
+                cell.cytosolic_um
+            })
+            .collect()
+    }
+
+    fn peaks(trace: &[f64]) -> usize {
+        let max = trace.iter().cloned().fold(0.0, f64::max);
+        let min = trace.iter().cloned().fold(f64::INFINITY, f64::min);
+        if max - min < 0.05 {
+            return 0;
+        }
+        let mid = 0.5 * (max + min);
+        trace
+            .windows(2)
+            .filter(|w| w[0] < mid && w[1] >= mid)
+            .count()
+    }
+
+    #[test]
+    fn test_oscillations_encode_agonist_strength() {
+        let rest = trace(0.0, 100.0);
+        assert!((rest.last().unwrap() - CalciumCell::resting().cytosolic_um).abs() < 1e-3);
+        // Discard the first 100 s transient.
+        let low = &trace(0.07, 200.0)[100_000..];
+        let high = &trace(0.09, 200.0)[100_000..];
+        let saturating = &trace(0.2, 200.0)[100_000..];
+        assert!(peaks(low) >= 3);
+        assert!(peaks(high) > peaks(low));
+        // Strong stimulation holds a raised plateau instead.
+        assert_eq!(peaks(saturating), 0);
+        assert!(saturating.iter().all(|&c| c > 0.2));
+    }
+
+    #[test]
+    fn test_ryanodine_receptors_amplify_a_calcium_trigger() {
+        let peak = |ryr: f64| {
+            let p = CalciumParameters::default()
+                .with_ryanodine_receptors(ryr)
+                .unwrap();
+            let mut cell = CalciumCell {
+                cytosolic_um: 0.5,
+                ..CalciumCell::resting()
+            };
+            let mut peak: f64 = 0.0;
+            for _ in 0..2000 {
+                cell.step(&p, 0.001);
+                peak = peak.max(cell.cytosolic_um);
+            }
+            peak
+        };
+        assert!(peak(2.0) > 2.0 * peak(0.0), "{} {}", peak(2.0), peak(0.0));
+        assert!(CalciumParameters::default()
+            .with_ryanodine_receptors(-1.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_depleted_store_draws_in_calcium() {
+        let p = CalciumParameters::default();
+        let full = CalciumCell::resting();
+        let depleted = CalciumCell { er_um: 2.0, ..full };
+        assert!(depleted.store_operated_entry_um_s(&p) > 10.0 * full.store_operated_entry_um_s(&p));
+        // At rest entry and extrusion balance.
+        let balance = full.store_operated_entry_um_s(&p) - full.pmca_extrusion_um_s(&p);
+        assert!(balance.abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ip3_wave_spreads_through_gap_junctions() {
+        let p = CalciumParameters::default();
+        let arrivals = |permeability: f64| {
+            let coupling = GapJunctionCoupling::new(12.0, permeability).unwrap();
+            let mut world = World::new();
+            let cells: Vec<_> = (0..8)
+                .map(|i| {
+                    let e = world.spawn();
+                    let x = 10.0 * i as f64;
+                    world.insert(e, Position(Vec3::new(x, 0.0, 0.0))).unwrap();
+                    let cell = CalciumCell {
+                        ip3_production_um_s: if i == 0 { 0.5 } else { 0.0 },
+                        ..CalciumCell::resting()
+                    };
+                    world.insert(e, cell).unwrap();
+                    e
+                })
+                .collect();
+            let mut arrival = vec![None; cells.len()];
+            for step in 0..30_000 {
+                coupling.step_world(&mut world, &p, 0.001);
+                for (k, e) in cells.iter().enumerate() {
+                    let c = world.get::<CalciumCell>(*e).unwrap().cytosolic_um;
+                    if arrival[k].is_none() && c > 0.2 {
+                        arrival[k] = Some(step);
+                    }
+                }
+            }
+            arrival
+        };
+        let coupled = arrivals(2.0);
+        let reached: Vec<usize> = coupled.iter().map_while(|a| *a).collect();
+        assert!(reached.len() >= 3 && reached.len() < 8);
+        assert!(reached.windows(2).all(|w| w[1] > w[0]));
+        let isolated = arrivals(0.0);
+        assert!(isolated[0].is_some() && isolated[1..].iter().all(Option::is_none));
+        assert!(GapJunctionCoupling::new(0.0, 1.0).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use crate::biology::physiology::properties::{ChemicalProperty, PropertyConsumer};
+use serde::{Deserialize, Serialize};
+
+// Typical interstitial PO2 of well-perfused tissue; target-gene folds are
+// reported relative to HIF-1α activity here.
+pub const NORMOXIC_TISSUE_PO2_MMHG: f64 = 40.0;
+// HIF-1α protein rises steeply as O2 falls below ~6%, half-maximal near
+// 2% (~15 mmHg) and maximal around 0.5%.
+// Jiang BH et al. (1996) Am J Physiol 271:C1172-C1180
+const HIF_HALF_MAX_PO2_MMHG: f64 = 15.0;
+const HIF_HILL: f64 = 3.0;
+// PHD-driven degradation gives HIF-1α a <5 min half-life on
+// reoxygenation; without oxygen it persists for about an hour.
+// Salceda S, Caro J (1997) J Biol Chem 272:22642-22647
+const ANOXIC_HALF_LIFE_MIN: f64 = 60.0;
+// Maximal induction of the main HIF targets at full stabilisation.
+// VEGF: Shweiki D et al. (1992) Nature 359:843-845
+const VEGF_MAX_FOLD: f64 = 10.0;
+// LOX: Erler JT et al. (2006) Nature 440:1222-1226
+const LOX_MAX_FOLD: f64 = 5.0;
+// Hexokinase 2, PFKFB3, PKM and PDK1 shift ATP supply to glycolysis.
+// Kim JW et al. (2006) Cell Metab 3:177-185
+const GLYCOLYTIC_MAX_FOLD: f64 = 3.0;
+// Renal EPO rises exponentially with falling oxygen delivery, up to
+// ~1000-fold in severe hypoxia or anaemia.
+// Jelkmann W (2011) J Physiol 589:1251-1258
+const EPO_MAX_FOLD: f64 = 1000.0;
+
+// HIF-1α stabilisation in one tissue, from 0 (fully hydroxylated and
+// degraded) to 1 (no PHD activity). The one place oxygen tension becomes
+// transcriptional output, so every model reading its targets agrees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hif1a {
+    pub po2_mmhg: f64,
+    pub stabilization: f64,
+}
+
+impl Default for Hif1a {
+    fn default() -> Self {
+        Self::at_po2(NORMOXIC_TISSUE_PO2_MMHG)
+    }
+}
+
+impl Hif1a {
+    // Steady state at a constant oxygen tension.
+    pub fn at_po2(po2_mmhg: f64) -> Self {
+        Self {
+            po2_mmhg,
+            stabilization: Self::steady_state(po2_mmhg),
+        }
+    }
+
+    pub fn steady_state(po2_mmhg: f64) -> f64 {
+        1.0 / (1.0 + (po2_mmhg.max(0.0) / HIF_HALF_MAX_PO2_MMHG).powf(HIF_HILL))
+    }
+
+    // Constant synthesis against basal plus PHD-dependent degradation, so
+    // protein clears in minutes on reoxygenation but builds up slowly.
+    pub fn step(&mut self, po2_mmhg: f64, dt_minutes: f64) {
+        let basal = std::f64::consts::LN_2 / ANOXIC_HALF_LIFE_MIN;
+        let hydroxylation = basal * (po2_mmhg.max(0.0) / HIF_HALF_MAX_PO2_MMHG).powf(HIF_HILL);
+        let degradation = basal + hydroxylation;
+        // Exact update for the linear ODE keeps large steps stable.
+        let target = basal / degradation;
+        self.stabilization =
+            target + (self.stabilization - target) * (-degradation * dt_minutes).exp();
+        self.po2_mmhg = po2_mmhg;
+    }
+
+    fn fold(&self, max_fold: f64) -> f64 {
+        let reference = Self::steady_state(NORMOXIC_TISSUE_PO2_MMHG);
+        (1.0 + (max_fold - 1.0) * self.stabilization) / (1.0 + (max_fold - 1.0) * reference)
+    }
+
+    pub fn vegf_fold(&self) -> f64 {
+        self.fold(VEGF_MAX_FOLD)
+    }
+
+    pub fn lox_fold(&self) -> f64 {
+        self.fold(LOX_MAX_FOLD)
+    }
+
+    pub fn glycolytic_enzyme_fold(&self) -> f64 {
+        self.fold(GLYCOLYTIC_MAX_FOLD)
+    }
+
+    // Exponential in stabilisation, so hyperoxia also suppresses EPO below
+    // its normoxic level.
+    pub fn epo_fold(&self) -> f64 {
+        let reference = Self::steady_state(NORMOXIC_TISSUE_PO2_MMHG);
+        EPO_MAX_FOLD.powf(self.stabilization - reference)
+    }
+}
+
+// Adopts the announced tension at steady state; callers wanting the
+// transient use `step`.
+impl PropertyConsumer for Hif1a {
+    fn consume(&mut self, property: ChemicalProperty) {
+        if let ChemicalProperty::OxygenTension { mmhg } = property {
+            *self = Self::at_po2(mmhg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stabilization_rises_steeply_below_six_percent() {
+        let normoxic = Hif1a::default();
+        assert!(normoxic.stabilization < 0.06);
+        assert!(Hif1a::at_po2(100.0).stabilization < 0.005);
+        assert!((Hif1a::at_po2(HIF_HALF_MAX_PO2_MMHG).stabilization - 0.5).abs() < 1e-12);
+        assert!(Hif1a::at_po2(4.0).stabilization > 0.95);
+        for fold in [
+            normoxic.vegf_fold(),
+            normoxic.lox_fold(),
+            normoxic.glycolytic_enzyme_fold(),
+            normoxic.epo_fold(),
+        ] {
+            assert!((fold - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_targets_follow_one_signal() {
+        let hypoxic = Hif1a::at_po2(10.0);
+        assert!(hypoxic.vegf_fold() > 4.0 && hypoxic.vegf_fold() < VEGF_MAX_FOLD);
+        assert!(hypoxic.lox_fold() > 2.0);
+        assert!(hypoxic.glycolytic_enzyme_fold() > 1.5);
+        assert!(hypoxic.epo_fold() > 50.0);
+        assert!(Hif1a::at_po2(100.0).epo_fold() < 1.0);
+    }
+
+    #[test]
+    fn test_slow_accumulation_fast_clearance() {
+        let mut hif = Hif1a::default();
+        hif.step(5.0, 10.0);
+        assert!(hif.stabilization < 0.5 * Hif1a::steady_state(5.0));
+        hif.step(5.0, 600.0);
+        assert!((hif.stabilization - Hif1a::steady_state(5.0)).abs() < 1e-2);
+        // Half-life of ~3 min at normoxic tension.
+        hif.step(NORMOXIC_TISSUE_PO2_MMHG, 3.0);
+        assert!((hif.stabilization - 0.5).abs() < 0.05);
+        hif.step(NORMOXIC_TISSUE_PO2_MMHG, 10.0);
+        assert!(hif.stabilization < 0.1);
+
+        let mut consumer = Hif1a::default();
+        consumer.consume(ChemicalProperty::OxygenTension { mmhg: 10.0 });
+        assert_eq!(consumer, Hif1a::at_po2(10.0));
+        consumer.consume(ChemicalProperty::Temperature { celsius: 39.0 });
+        assert_eq!(consumer, Hif1a::at_po2(10.0));
+    }
+}
@@ -0,0 +1,9 @@
+pub mod calcium;
+pub mod hypoxia;
+pub mod tgf_beta;
+pub mod wnt;
+
+pub use calcium::{CalciumCell, CalciumParameters, GapJunctionCoupling};
+pub use hypoxia::{Hif1a, NORMOXIC_TISSUE_PO2_MMHG};
+pub use tgf_beta::TgfBetaSignaling;
+pub use wnt::{LoadCycles, WntSignaling, HABITUAL_STRAIN_MICROSTRAIN};
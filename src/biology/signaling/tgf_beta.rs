@@ -0,0 +1,127 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Osteoclasts release and activate latent TGF-β1 stored in bone matrix,
+// and the released ligand recruits mesenchymal progenitors to the
+// resorption pit via SMAD2/3, coupling formation to resorption.
+// Tang Y et al. (2009) Nat Med 15:757-765
+const SMAD23_HALF_LIGAND: f64 = 1.0;
+// Matrix BMP-2 signals through SMAD1/5/8 to commit recruited progenitors
+// to the osteoblast lineage.
+// Katagiri T et al. (1994) J Cell Biol 127:1755-1766
+const SMAD158_HALF_LIGAND: f64 = 1.0;
+
+// TGF-β and BMP stored in bone matrix and the SMAD signalling they drive
+// at a reversal site. Ligand released into a pit is set by its matrix
+// content, not its depth, so signals are per unit of resorbed volume.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TgfBetaSignaling {
+    // Latent TGF-β per unit matrix, relative to normal bone.
+    pub tgf_beta_matrix_content: f64,
+    pub bmp_matrix_content: f64,
+    // Fraction of TGF-β type I receptor (ALK5) kinase inhibited.
+    pub receptor_blockade: f64,
+}
+
+impl Default for TgfBetaSignaling {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TgfBetaSignaling {
+    pub fn new() -> Self {
+        Self {
+            tgf_beta_matrix_content: 1.0,
+            bmp_matrix_content: 1.0,
+            receptor_blockade: 0.0,
+        }
+    }
+
+    pub fn with_matrix_content(mut self, tgf_beta: f64, bmp: f64) -> BiologyResult<Self> {
+        if tgf_beta.is_nan() || tgf_beta < 0.0 || bmp.is_nan() || bmp < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "matrix growth factor content must be non-negative".to_string(),
+            ));
+        }
+        self.tgf_beta_matrix_content = tgf_beta;
+        self.bmp_matrix_content = bmp;
+        Ok(self)
+    }
+
+    pub fn set_receptor_blockade(&mut self, fraction: f64) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "receptor blockade must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.receptor_blockade = fraction;
+        Ok(())
+    }
+
+    // Active TGF-β freed by resorbing a volume of matrix.
+    pub fn released_tgf_beta(&self, resorbed_volume: f64) -> f64 {
+        self.tgf_beta_matrix_content * resorbed_volume.max(0.0)
+    }
+
+    pub fn smad23_activity(&self) -> f64 {
+        let ligand = self.tgf_beta_matrix_content * (1.0 - self.receptor_blockade);
+        ligand / (ligand + SMAD23_HALF_LIGAND)
+    }
+
+    pub fn smad158_activity(&self) -> f64 {
+        self.bmp_matrix_content / (self.bmp_matrix_content + SMAD158_HALF_LIGAND)
+    }
+
+    // Activity over that of normal matrix, so at most 1 + half.
+    fn relative(activity: f64, half: f64) -> f64 {
+        activity * (1.0 + half)
+    }
+
+    // Osteoblasts reaching a reversal site per unit resorbed volume,
+    // relative to normal matrix.
+    pub fn osteoblast_recruitment(&self) -> f64 {
+        Self::relative(self.smad23_activity(), SMAD23_HALF_LIGAND)
+    }
+
+    // Both pathways raise type I collagen output per osteoblast.
+    // Ignotz RA, Massagué J (1986) J Biol Chem 261:4337-4345
+    pub fn collagen_synthesis_fold(&self) -> f64 {
+        let tgf = Self::relative(self.smad23_activity(), SMAD23_HALF_LIGAND);
+        let bmp = Self::relative(self.smad158_activity(), SMAD158_HALF_LIGAND);
+        0.5 * (tgf + bmp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normal_matrix_is_reference() {
+        let signaling = TgfBetaSignaling::new();
+        assert!((signaling.osteoblast_recruitment() - 1.0).abs() < 1e-12);
+        assert!((signaling.collagen_synthesis_fold() - 1.0).abs() < 1e-12);
+        assert_eq!(signaling.released_tgf_beta(0.01), 0.01);
+    }
+
+    #[test]
+    fn test_smad_signalling_saturates_and_can_be_blocked() {
+        let rich = TgfBetaSignaling::new()
+            .with_matrix_content(10.0, 10.0)
+            .unwrap();
+        assert!(rich.osteoblast_recruitment() > 1.5);
+        assert!(rich.osteoblast_recruitment() < 1.0 + SMAD23_HALF_LIGAND);
+        assert!(rich.collagen_synthesis_fold() > 1.5);
+
+        let mut blocked = TgfBetaSignaling::new();
+        blocked.set_receptor_blockade(0.8).unwrap();
+        assert!(blocked.osteoblast_recruitment() < 0.5);
+        // BMP signalling is untouched by ALK5 inhibition.
+        assert!(blocked.collagen_synthesis_fold() > 0.5);
+        assert!(blocked.set_receptor_blockade(1.2).is_err());
+        assert!(TgfBetaSignaling::new()
+            .with_matrix_content(-1.0, 1.0)
+            .is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Peak strains of habitual loading at the human tibia and spine; the
+// mechanostat holds bone mass steady around this set point.
+// Frost HM (1987) Bone Miner 2:73-85
+pub const HABITUAL_STRAIN_MICROSTRAIN: f64 = 1000.0;
+// Osteocyte Sost expression falls with strain and rises on unloading.
+// Robling AG et al. (2008) J Biol Chem 283:5866-5875
+const SCLEROSTIN_STRAIN_EXPONENT: f64 = 0.5;
+const UNLOADED_SCLEROSTIN_FOLD: f64 = 1.5;
+const MIN_SCLEROSTIN_FOLD: f64 = 0.3;
+// Sclerostin binds LRP5/6 and blocks canonical Wnt; the habitual level is
+// taken to inhibit half of the receptor pool, so full neutralisation at
+// most doubles β-catenin signalling.
+// Li X et al. (2005) J Biol Chem 280:19883-19887
+const SCLEROSTIN_HALF_INHIBITION: f64 = 1.0;
+// Wnt signalling induces DKK1 and other inhibitors, so formation under
+// sustained sclerostin blockade wanes over months while resorption stays
+// suppressed.
+// McClung MR et al. (2014) N Engl J Med 370:412-420
+const FEEDBACK_TIME_CONSTANT_DAYS: f64 = 90.0;
+const FEEDBACK_GAIN: f64 = 0.8;
+// Bone responds to the daily sum of cycles weighted by peak stress to the
+// fourth power. A habitual day of ~7000 steps at ~500 µε gives the same
+// stimulus as about 220 cycles at the habitual set point.
+// Beaupré GS, Orr TE, Carter DR (1990) J Orthop Res 8:651-661
+const DAILY_STIMULUS_EXPONENT: f64 = 4.0;
+const HABITUAL_DAILY_CYCLES: f64 = 220.0;
+
+// `cycles` loading events in one day, each reaching `peak_microstrain`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoadCycles {
+    pub peak_microstrain: f64,
+    pub cycles: f64,
+}
+
+// Canonical Wnt/β-catenin signalling in osteoblasts of one skeletal site,
+// gated by osteocyte sclerostin.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WntSignaling {
+    pub strain_microstrain: f64,
+    // Fraction of sclerostin bound by a neutralising antibody.
+    pub sclerostin_neutralization: f64,
+    // Induced DKK1-type inhibition, as extra sclerostin-equivalents.
+    pub feedback_inhibition: f64,
+    pub days: f64,
+}
+
+impl Default for WntSignaling {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WntSignaling {
+    pub fn new() -> Self {
+        Self {
+            strain_microstrain: HABITUAL_STRAIN_MICROSTRAIN,
+            sclerostin_neutralization: 0.0,
+            feedback_inhibition: 0.0,
+            days: 0.0,
+        }
+    }
+
+    pub fn set_mechanical_strain(&mut self, microstrain: f64) -> BiologyResult<()> {
+        if microstrain.is_nan() || microstrain < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "strain must be non-negative".to_string(),
+            ));
+        }
+        self.strain_microstrain = microstrain;
+        Ok(())
+    }
+
+    // Sets strain to the single habitual-day equivalent of a day's loads.
+    pub fn apply_load(&mut self, loads: &[LoadCycles]) -> BiologyResult<()> {
+        if loads
+            .iter()
+            .any(|l| !(l.peak_microstrain >= 0.0 && l.cycles >= 0.0))
+        {
+            return Err(BiologyError::InvalidValue(
+                "load cycles and strains must be non-negative".to_string(),
+            ));
+        }
+        let stimulus: f64 = loads
+            .iter()
+            .map(|l| l.cycles * l.peak_microstrain.powf(DAILY_STIMULUS_EXPONENT))
+            .sum();
+        self.set_mechanical_strain(
+            (stimulus / HABITUAL_DAILY_CYCLES).powf(1.0 / DAILY_STIMULUS_EXPONENT),
+        )
+    }
+
+    pub fn set_sclerostin_neutralization(&mut self, fraction: f64) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "sclerostin neutralisation must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.sclerostin_neutralization = fraction;
+        Ok(())
+    }
+
+    // Osteocyte sclerostin output relative to habitual loading.
+    pub fn sclerostin_expression(&self) -> f64 {
+        let relative = self.strain_microstrain / HABITUAL_STRAIN_MICROSTRAIN;
+        relative
+            .powf(-SCLEROSTIN_STRAIN_EXPONENT)
+            .clamp(MIN_SCLEROSTIN_FOLD, UNLOADED_SCLEROSTIN_FOLD)
+    }
+
+    pub fn free_sclerostin(&self) -> f64 {
+        self.sclerostin_expression() * (1.0 - self.sclerostin_neutralization)
+    }
+
+    // β-catenin signalling relative to a habitually loaded, untreated site.
+    pub fn beta_catenin_activity(&self) -> f64 {
+        let inhibitors = self.free_sclerostin() + self.feedback_inhibition;
+        (1.0 + 1.0 / SCLEROSTIN_HALF_INHIBITION) / (1.0 + inhibitors / SCLEROSTIN_HALF_INHIBITION)
+    }
+
+    // Only signalling above baseline induces the feedback inhibitors.
+    pub fn step(&mut self, dt_days: f64) {
+        let target = FEEDBACK_GAIN * (self.beta_catenin_activity() - 1.0).max(0.0);
+        self.feedback_inhibition +=
+            (target - self.feedback_inhibition) * dt_days / FEEDBACK_TIME_CONSTANT_DAYS;
+        self.feedback_inhibition = self.feedback_inhibition.max(0.0);
+        self.days += dt_days;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loading_suppresses_sclerostin() {
+        let mut site = WntSignaling::new();
+        assert!((site.beta_catenin_activity() - 1.0).abs() < 1e-12);
+        site.set_mechanical_strain(2000.0).unwrap();
+        assert!(site.sclerostin_expression() < 1.0);
+        assert!(site.beta_catenin_activity() > 1.0);
+        site.set_mechanical_strain(0.0).unwrap();
+        assert_eq!(site.sclerostin_expression(), UNLOADED_SCLEROSTIN_FOLD);
+        assert!(site.beta_catenin_activity() < 1.0);
+        assert!(site.set_mechanical_strain(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_daily_load_history_sets_equivalent_strain() {
+        let mut site = WntSignaling::new();
+        let walking = LoadCycles {
+            peak_microstrain: 500.0,
+            cycles: 3500.0,
+        };
+        site.apply_load(&[walking]).unwrap();
+        assert!((site.strain_microstrain - HABITUAL_STRAIN_MICROSTRAIN).abs() < 5.0);
+        // A few dozen high-strain jumps outweigh thousands of extra steps.
+        let jumps = LoadCycles {
+            peak_microstrain: 2000.0,
+            cycles: 50.0,
+        };
+        let mut jumping = site.clone();
+        jumping.apply_load(&[walking, jumps]).unwrap();
+        let double_walk = LoadCycles {
+            cycles: 7000.0,
+            ..walking
+        };
+        site.apply_load(&[double_walk]).unwrap();
+        assert!(jumping.strain_microstrain > site.strain_microstrain);
+        site.apply_load(&[]).unwrap();
+        assert_eq!(site.strain_microstrain, 0.0);
+        assert!(site
+            .apply_load(&[LoadCycles {
+                peak_microstrain: -1.0,
+                cycles: 1.0,
+            }])
+            .is_err());
+    }
+
+    #[test]
+    fn test_neutralisation_raises_wnt_until_feedback_catches_up() {
+        let mut site = WntSignaling::new();
+        site.set_sclerostin_neutralization(0.9).unwrap();
+        let early = site.beta_catenin_activity();
+        assert!(early > 1.6 && early < 2.0);
+        for _ in 0..365 {
+            site.step(1.0);
+        }
+        let late = site.beta_catenin_activity();
+        assert!(late > 1.0 && late < early - 0.3, "late {late}");
+        // Signalling below baseline induces no feedback.
+        let mut unloaded = WntSignaling::new();
+        unloaded.set_mechanical_strain(0.0).unwrap();
+        unloaded.step(30.0);
+        assert_eq!(unloaded.feedback_inhibition, 0.0);
+        assert!(site.set_sclerostin_neutralization(1.5).is_err());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::biomechanics::multibody::STANDARD_GRAVITY_M_S2;
+use crate::simulation::population::{
+    VirtualIndividual, YOUNG_FEMALE_FEMORAL_NECK_BMD_G_CM2, YOUNG_FEMALE_FEMORAL_NECK_SD_G_CM2,
+};
+use serde::{Deserialize, Serialize};
+
+// Hip impact in a fall as an effective mass dropping from greater-
+// trochanter height onto a linear spring: peak force v·sqrt(m·k), with
+// v² = 2gh plus any horizontal speed carried into the landing. The
+// trochanteric soft tissue absorbs a further ~71 N per mm of thickness.
+// The factor of risk Φ = impact force / femoral failure load predicts
+// fracture above one.
+// Robinovitch SN, Hayes WC, McMahon TA (1991) J Biomech Eng 113:366-374
+// Robinovitch SN, McMahon TA, Hayes WC (1995) J Orthop Res 13:956-962
+// Hayes WC, Myers ER, Robinovitch SN et al. (1996) Bone 18:77S-86S
+
+// Fraction of body mass effectively moving with the pelvis at impact.
+const EFFECTIVE_MASS_FRACTION: f64 = 7.0 / 20.0;
+const PELVIS_STIFFNESS_N_M: f64 = 71.0e3;
+const SOFT_TISSUE_ATTENUATION_N_PER_MM: f64 = 71.0;
+// Greater trochanter height relative to stature.
+// Winter DA (2009) Biomechanics and Motor Control of Human Movement, 4th ed., Fig. 4.1
+const TROCHANTER_HEIGHT_FRACTION: f64 = 0.530;
+// Share of walking speed still horizontal when the hip lands after a trip.
+const TRIP_CARRIED_SPEED_FRACTION: f64 = 0.5;
+
+// Femoral failure in a sideways-fall configuration: mean 7200 N for young
+// and 3440 N for elderly femora. Taken linear in femoral-neck aBMD through
+// those means at ~1.0 and ~0.65 g/cm².
+// Courtney AC et al. (1995) J Bone Joint Surg Am 77:387-395
+const FAILURE_LOAD_SLOPE_N_PER_G_CM2: f64 = (7200.0 - 3440.0) / (1.0 - 0.65);
+const FAILURE_LOAD_AT_ZERO_BMD_N: f64 = 3440.0 - FAILURE_LOAD_SLOPE_N_PER_G_CM2 * 0.65;
+const MIN_FAILURE_LOAD_N: f64 = 500.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FallKind {
+    // Toppling sideways from standing onto the greater trochanter.
+    Sideways,
+    // Tripping while walking and landing on the hip.
+    Trip { walking_speed_m_s: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FallScenario {
+    pub kind: FallKind,
+    pub body_mass_kg: f64,
+    pub height_m: f64,
+    pub soft_tissue_mm: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ImpactLoad {
+    pub impact_velocity_m_s: f64,
+    pub effective_mass_kg: f64,
+    // Before soft-tissue attenuation.
+    pub undamped_force_n: f64,
+    pub peak_force_n: f64,
+}
+
+impl FallScenario {
+    pub fn new(
+        kind: FallKind,
+        body_mass_kg: f64,
+        height_m: f64,
+        soft_tissue_mm: f64,
+    ) -> BiologyResult<Self> {
+        if body_mass_kg.is_nan() || body_mass_kg <= 0.0 || height_m.is_nan() || height_m <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "body mass and height must be positive".to_string(),
+            ));
+        }
+        if soft_tissue_mm.is_nan() || soft_tissue_mm < 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "soft-tissue thickness must be non-negative, got {}",
+                soft_tissue_mm
+            )));
+        }
+        if let FallKind::Trip { walking_speed_m_s } = kind {
+            if walking_speed_m_s.is_nan() || walking_speed_m_s < 0.0 {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "walking speed must be non-negative, got {}",
+                    walking_speed_m_s
+                )));
+            }
+        }
+        Ok(Self {
+            kind,
+            body_mass_kg,
+            height_m,
+            soft_tissue_mm,
+        })
+    }
+
+    pub fn for_individual(
+        individual: &VirtualIndividual,
+        kind: FallKind,
+        soft_tissue_mm: f64,
+    ) -> BiologyResult<Self> {
+        Self::new(
+            kind,
+            individual.human.weight_kg,
+            individual.human.height_cm / 100.0,
+            soft_tissue_mm,
+        )
+    }
+
+    pub fn fall_height_m(&self) -> f64 {
+        TROCHANTER_HEIGHT_FRACTION * self.height_m
+    }
+
+    pub fn impact(&self) -> ImpactLoad {
+        let vertical_squared = 2.0 * STANDARD_GRAVITY_M_S2 * self.fall_height_m();
+        let horizontal = match self.kind {
+            FallKind::Sideways => 0.0,
+            FallKind::Trip { walking_speed_m_s } => TRIP_CARRIED_SPEED_FRACTION * walking_speed_m_s,
+        };
+        let velocity = (vertical_squared + horizontal * horizontal).sqrt();
+        let mass = EFFECTIVE_MASS_FRACTION * self.body_mass_kg;
+        let undamped = velocity * (mass * PELVIS_STIFFNESS_N_M).sqrt();
+        ImpactLoad {
+            impact_velocity_m_s: velocity,
+            effective_mass_kg: mass,
+            undamped_force_n: undamped,
+            peak_force_n: (undamped - SOFT_TISSUE_ATTENUATION_N_PER_MM * self.soft_tissue_mm)
+                .max(0.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProximalFemurStrength {
+    pub femoral_neck_bmd_g_cm2: f64,
+}
+
+impl ProximalFemurStrength {
+    pub fn new(femoral_neck_bmd_g_cm2: f64) -> BiologyResult<Self> {
+        if femoral_neck_bmd_g_cm2.is_nan() || femoral_neck_bmd_g_cm2 <= 0.0 {
+            return Err(BiologyError::InvalidValue(format!(
+                "femoral-neck BMD must be positive, got {}",
+                femoral_neck_bmd_g_cm2
+            )));
+        }
+        Ok(Self {
+            femoral_neck_bmd_g_cm2,
+        })
+    }
+
+    pub fn from_t_score(t_score: f64) -> BiologyResult<Self> {
+        Self::new(
+            YOUNG_FEMALE_FEMORAL_NECK_BMD_G_CM2 + t_score * YOUNG_FEMALE_FEMORAL_NECK_SD_G_CM2,
+        )
+    }
+
+    pub fn for_individual(individual: &VirtualIndividual) -> BiologyResult<Self> {
+        Self::new(individual.femoral_neck_bmd_g_cm2)
+    }
+
+    // Sideways-fall failure load; floored where the linear fit runs out.
+    pub fn failure_load_n(&self) -> f64 {
+        (FAILURE_LOAD_AT_ZERO_BMD_N + FAILURE_LOAD_SLOPE_N_PER_G_CM2 * self.femoral_neck_bmd_g_cm2)
+            .max(MIN_FAILURE_LOAD_N)
+    }
+
+    // Factor of risk Φ; a fracture is expected above one.
+    pub fn load_to_strength(&self, scenario: &FallScenario) -> f64 {
+        scenario.impact().peak_force_n / self.failure_load_n()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sideways_fall_force_and_padding() {
+        let lean = FallScenario::new(FallKind::Sideways, 65.0, 1.65, 0.0).unwrap();
+        let impact = lean.impact();
+        // ~4 m/s and 5-6 kN before attenuation, as in the pendulum and
+        // volunteer studies.
+        assert!((3.5..4.5).contains(&impact.impact_velocity_m_s));
+        assert!((4500.0..6500.0).contains(&impact.undamped_force_n));
+        let padded = FallScenario {
+            soft_tissue_mm: 40.0,
+            ..lean
+        };
+        assert!((impact.peak_force_n - padded.impact().peak_force_n - 40.0 * 71.0).abs() < 1e-9);
+        let trip = FallScenario {
+            kind: FallKind::Trip {
+                walking_speed_m_s: 1.3,
+            },
+            ..lean
+        };
+        assert!(trip.impact().peak_force_n > impact.peak_force_n);
+        assert!(FallScenario::new(FallKind::Sideways, 65.0, 1.65, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_factor_of_risk_separates_young_and_osteoporotic_hips() {
+        let fall = FallScenario::new(FallKind::Sideways, 65.0, 1.65, 25.0).unwrap();
+        let young = ProximalFemurStrength::new(1.0).unwrap();
+        assert!((young.failure_load_n() - 7200.0).abs() < 1e-6);
+        assert!(young.load_to_strength(&fall) < 1.0);
+        let osteoporotic = ProximalFemurStrength::from_t_score(-3.0).unwrap();
+        assert!(osteoporotic.load_to_strength(&fall) > 1.0);
+        assert!(ProximalFemurStrength::new(0.0).is_err());
+    }
+}
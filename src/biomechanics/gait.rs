@@ -0,0 +1,261 @@
+use crate::biology::signaling::LoadCycles;
+use crate::biology::{BiologyError, BiologyResult};
+use crate::biomechanics::multibody::{BoneSection, STANDARD_GRAVITY_M_S2};
+use serde::{Deserialize, Serialize};
+
+// Parametric level walking: each channel is a periodic sum of Gaussian
+// bumps over the stride (0 = heel strike, toe-off near 0.6), scaled to
+// its published peak at a reference speed and rising linearly with
+// speed. Forces are in body weights before scaling to newtons, moments
+// in N·m per kg of body mass.
+// Bergmann G et al. (2001) J Biomech 34:859-871
+// Kutzner I et al. (2010) J Biomech 43:2164-2173
+// Winter DA (2009) Biomechanics and Motor Control of Human Movement, 4th ed., ch. 5
+
+const REFERENCE_SPEED_M_S: f64 = 1.1;
+// Fractional change of every peak per m/s away from the reference speed.
+const SPEED_GAIN_PER_M_S: f64 = 0.25;
+const MAX_WALKING_SPEED_M_S: f64 = 2.5;
+const SHAPE_SAMPLES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GaitChannel {
+    HipContactForce,
+    KneeContactForce,
+    // Axial force in the tibial shaft, carried as the knee contact force.
+    TibialAxialForce,
+    // Sagittal, extensor positive.
+    HipFlexionMoment,
+    KneeFlexionMoment,
+    // Frontal-plane external moment, the usual knee loading index.
+    KneeAdductionMoment,
+}
+
+impl GaitChannel {
+    pub const ALL: [GaitChannel; 6] = [
+        GaitChannel::HipContactForce,
+        GaitChannel::KneeContactForce,
+        GaitChannel::TibialAxialForce,
+        GaitChannel::HipFlexionMoment,
+        GaitChannel::KneeFlexionMoment,
+        GaitChannel::KneeAdductionMoment,
+    ];
+
+    pub fn is_force(self) -> bool {
+        matches!(
+            self,
+            GaitChannel::HipContactForce
+                | GaitChannel::KneeContactForce
+                | GaitChannel::TibialAxialForce
+        )
+    }
+
+    // (peak at the reference speed, swing-phase floor, bumps as
+    // (centre, width, relative height)).
+    fn profile(self) -> (f64, f64, &'static [(f64, f64, f64)]) {
+        match self {
+            // 2.38 BW for the average patient, peaks at 16% and 50%.
+            GaitChannel::HipContactForce => (2.38, 0.12, &[(0.16, 0.06, 1.0), (0.49, 0.06, 0.9)]),
+            // 2.61 BW, the first peak dominant.
+            GaitChannel::KneeContactForce | GaitChannel::TibialAxialForce => {
+                (2.61, 0.1, &[(0.14, 0.05, 1.0), (0.47, 0.06, 0.85)])
+            }
+            // Extensor early stance, flexor before toe-off.
+            GaitChannel::HipFlexionMoment => (0.8, 0.0, &[(0.08, 0.06, 1.0), (0.50, 0.08, -1.0)]),
+            // Extensor at loading response, flexor through mid-stance.
+            GaitChannel::KneeFlexionMoment => (
+                0.5,
+                0.0,
+                &[(0.14, 0.05, 1.0), (0.40, 0.07, -0.6), (0.57, 0.03, 0.3)],
+            ),
+            GaitChannel::KneeAdductionMoment => {
+                (0.35, 0.0, &[(0.17, 0.06, 1.0), (0.46, 0.06, 0.85)])
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GaitModel {
+    pub speed_m_s: f64,
+    // Steps (not strides) per minute.
+    pub cadence_steps_min: f64,
+    pub body_mass_kg: f64,
+}
+
+impl GaitModel {
+    pub fn new(speed_m_s: f64, cadence_steps_min: f64, body_mass_kg: f64) -> BiologyResult<Self> {
+        if speed_m_s.is_nan() || speed_m_s <= 0.0 || speed_m_s > MAX_WALKING_SPEED_M_S {
+            return Err(BiologyError::InvalidParameter(format!(
+                "walking speed must lie in (0, {}] m/s, got {}",
+                MAX_WALKING_SPEED_M_S, speed_m_s
+            )));
+        }
+        if cadence_steps_min.is_nan() || cadence_steps_min <= 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "cadence must be positive, got {}",
+                cadence_steps_min
+            )));
+        }
+        if body_mass_kg.is_nan() || body_mass_kg <= 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "body mass must be positive, got {}",
+                body_mass_kg
+            )));
+        }
+        Ok(Self {
+            speed_m_s,
+            cadence_steps_min,
+            body_mass_kg,
+        })
+    }
+
+    // Comfortable adult walking, ~1.3 m/s at ~110 steps/min.
+    pub fn comfortable(body_mass_kg: f64) -> BiologyResult<Self> {
+        Self::new(1.3, 110.0, body_mass_kg)
+    }
+
+    pub fn stride_period_s(&self) -> f64 {
+        120.0 / self.cadence_steps_min
+    }
+
+    pub fn body_weight_n(&self) -> f64 {
+        self.body_mass_kg * STANDARD_GRAVITY_M_S2
+    }
+
+    fn speed_scale(&self) -> f64 {
+        (1.0 + SPEED_GAIN_PER_M_S * (self.speed_m_s - REFERENCE_SPEED_M_S)).max(0.0)
+    }
+
+    // Multiplier from the unit-peak shape to N or N·m.
+    fn scale(&self, channel: GaitChannel) -> f64 {
+        let (peak, floor, bumps) = channel.profile();
+        let unit = if channel.is_force() {
+            self.body_weight_n()
+        } else {
+            self.body_mass_kg
+        };
+        unit * self.speed_scale() * peak / shape_max(floor, bumps)
+    }
+
+    // Value at `phase` ∈ [0, 1) of the stride, in N or N·m.
+    pub fn at_phase(&self, channel: GaitChannel, phase: f64) -> f64 {
+        let (_, floor, bumps) = channel.profile();
+        self.scale(channel) * shape(phase, floor, bumps)
+    }
+
+    // Value `time_s` after a heel strike, repeating every stride.
+    pub fn at_time(&self, channel: GaitChannel, time_s: f64) -> f64 {
+        self.at_phase(channel, (time_s / self.stride_period_s()).rem_euclid(1.0))
+    }
+
+    // `samples` evenly spaced (phase, value) pairs over one stride.
+    pub fn waveform(&self, channel: GaitChannel, samples: usize) -> Vec<(f64, f64)> {
+        let (_, floor, bumps) = channel.profile();
+        let scale = self.scale(channel);
+        (0..samples)
+            .map(|i| {
+                let phase = i as f64 / samples as f64;
+                (phase, scale * shape(phase, floor, bumps))
+            })
+            .collect()
+    }
+
+    pub fn peak(&self, channel: GaitChannel) -> f64 {
+        self.waveform(channel, SHAPE_SAMPLES)
+            .into_iter()
+            .map(|(_, v)| v)
+            .fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    // Cortical strain in `section` over one stride from the tibial shaft
+    // force, e.g. as apparent strains for `MicroFeModel::compress`.
+    pub fn tibial_strain_microstrain(&self, section: &BoneSection, samples: usize) -> Vec<f64> {
+        self.waveform(GaitChannel::TibialAxialForce, samples)
+            .into_iter()
+            .map(|(_, force)| section.microstrain(force))
+            .collect()
+    }
+
+    // A bout of walking as one strain cycle per stride of the loaded leg,
+    // for `WntSignaling::apply_load`.
+    pub fn load_cycles(&self, section: &BoneSection, minutes: f64) -> LoadCycles {
+        LoadCycles {
+            peak_microstrain: section.microstrain(self.peak(GaitChannel::TibialAxialForce)),
+            cycles: minutes.max(0.0) * 60.0 / self.stride_period_s(),
+        }
+    }
+}
+
+fn shape(phase: f64, floor: f64, bumps: &[(f64, f64, f64)]) -> f64 {
+    let bumps: f64 = bumps
+        .iter()
+        .map(|&(centre, width, height)| {
+            // Nearest image on the periodic stride.
+            let d = (phase - centre + 0.5).rem_euclid(1.0) - 0.5;
+            height * (-0.5 * (d / width).powi(2)).exp()
+        })
+        .sum();
+    floor + bumps
+}
+
+fn shape_max(floor: f64, bumps: &[(f64, f64, f64)]) -> f64 {
+    (0..SHAPE_SAMPLES)
+        .map(|i| shape(i as f64 / SHAPE_SAMPLES as f64, floor, bumps))
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::signaling::WntSignaling;
+
+    #[test]
+    fn test_contact_force_peaks_match_telemetry() {
+        let gait = GaitModel::new(REFERENCE_SPEED_M_S, 105.0, 75.0).unwrap();
+        let bw = gait.body_weight_n();
+        assert!((gait.peak(GaitChannel::HipContactForce) / bw - 2.38).abs() < 0.02);
+        assert!((gait.peak(GaitChannel::KneeContactForce) / bw - 2.61).abs() < 0.02);
+        // Double-humped stance, light swing.
+        let knee = |p: f64| gait.at_phase(GaitChannel::KneeContactForce, p) / bw;
+        assert!(knee(0.3) < 0.8 * knee(0.14));
+        assert!(knee(0.8) < 0.5);
+        // Hip moment reverses from extensor to flexor over stance.
+        assert!(gait.at_phase(GaitChannel::HipFlexionMoment, 0.08) > 0.0);
+        assert!(gait.at_phase(GaitChannel::HipFlexionMoment, 0.5) < 0.0);
+    }
+
+    #[test]
+    fn test_speed_and_cadence_scale_the_waveforms() {
+        let slow = GaitModel::new(0.8, 95.0, 70.0).unwrap();
+        let fast = GaitModel::new(1.8, 125.0, 70.0).unwrap();
+        for channel in GaitChannel::ALL {
+            assert!(fast.peak(channel) > slow.peak(channel), "{:?}", channel);
+        }
+        assert!((slow.stride_period_s() - 120.0 / 95.0).abs() < 1e-12);
+        let t = 0.3;
+        let later = t + 3.0 * fast.stride_period_s();
+        assert!(
+            (fast.at_time(GaitChannel::KneeContactForce, t)
+                - fast.at_time(GaitChannel::KneeContactForce, later))
+            .abs()
+                < 1e-9
+        );
+        assert!(GaitModel::new(4.0, 110.0, 70.0).is_err());
+        assert!(GaitModel::new(1.2, 0.0, 70.0).is_err());
+    }
+
+    #[test]
+    fn test_walking_feeds_bone_signalling() {
+        let gait = GaitModel::comfortable(70.0).unwrap();
+        let section = BoneSection::tibial_midshaft();
+        let strains = gait.tibial_strain_microstrain(&section, 100);
+        assert_eq!(strains.len(), 100);
+        let walk = gait.load_cycles(&section, 60.0);
+        assert!((200.0..1000.0).contains(&walk.peak_microstrain));
+        assert!((walk.cycles - 60.0 * 55.0).abs() < 1e-9);
+        let mut wnt = WntSignaling::new();
+        wnt.apply_load(&[walk]).unwrap();
+        assert!(wnt.strain_microstrain > walk.peak_microstrain);
+    }
+}
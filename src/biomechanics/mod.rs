@@ -0,0 +1,12 @@
+pub mod falls;
+pub mod gait;
+pub mod multibody;
+pub mod muscle;
+
+pub use falls::{FallKind, FallScenario, ImpactLoad, ProximalFemurStrength};
+pub use gait::{GaitChannel, GaitModel};
+pub use multibody::{
+    BoneSection, ExternalForce, InverseDynamics, JointKind, JointLoad, MultibodyState, Segment,
+    Skeleton,
+};
+pub use muscle::{Attachment, MuscleActuator};
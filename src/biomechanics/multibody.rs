@@ -0,0 +1,699 @@
+use crate::biology::signaling::LoadCycles;
+use crate::biology::{BiologyError, BiologyResult};
+use crate::biomechanics::muscle::{Attachment, MuscleActuator};
+use crate::config::presets::BoneMaterial;
+use nalgebra::{DMatrix, DVector, Matrix3, Rotation3, Unit, Vector3};
+use serde::{Deserialize, Serialize};
+
+// Rigid-segment skeleton as a tree rooted at a fixed base (ground or a
+// pelvis held in place), world y up. Inverse dynamics is the recursive
+// Newton-Euler algorithm; the force each parent exerts on its child at the
+// joint, including the compression added by muscles pulling across it, is
+// the joint reaction that loads the bone. Forward dynamics builds the mass
+// matrix column by column from inverse dynamics at unit accelerations.
+// Featherstone R (2008) Rigid Body Dynamics Algorithms, ch. 5
+// Walker MW, Orin DE (1982) J Dyn Syst Meas Control 104:205-211
+
+pub const STANDARD_GRAVITY_M_S2: f64 = 9.80665;
+
+// Lower-limb segment parameters as fractions of body mass and segment
+// length: (mass, centre of mass from proximal joint, radius of gyration
+// about the centre of mass).
+// Winter DA (2009) Biomechanics and Motor Control of Human Movement, 4th ed., Table 4.1
+const THIGH: (f64, f64, f64) = (0.100, 0.433, 0.323);
+const SHANK: (f64, f64, f64) = (0.0465, 0.433, 0.302);
+const FOOT: (f64, f64, f64) = (0.0145, 0.50, 0.475);
+// Long-axis inertia relative to transverse, about that of a solid
+// cylinder a fifth as wide as it is long.
+const LONG_AXIS_INERTIA_FRACTION: f64 = 0.1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub name: String,
+    pub mass_kg: f64,
+    pub length_m: f64,
+    // In the segment frame, from the proximal joint.
+    pub com_m: Vector3<f64>,
+    // About the centre of mass, in the segment frame.
+    pub inertia_kg_m2: Matrix3<f64>,
+    // Unit vector from proximal to distal end, in the segment frame.
+    pub long_axis: Vector3<f64>,
+}
+
+impl Segment {
+    pub fn new(
+        name: &str,
+        mass_kg: f64,
+        length_m: f64,
+        com_m: Vector3<f64>,
+        inertia_kg_m2: Matrix3<f64>,
+    ) -> BiologyResult<Self> {
+        if mass_kg.is_nan() || mass_kg <= 0.0 || length_m.is_nan() || length_m <= 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "segment {}: mass and length must be positive",
+                name
+            )));
+        }
+        let symmetric = (inertia_kg_m2 - inertia_kg_m2.transpose()).abs().max() < 1e-12;
+        if !symmetric || inertia_kg_m2.symmetric_eigenvalues().min() < 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "segment {}: inertia must be symmetric positive semi-definite",
+                name
+            )));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            mass_kg,
+            length_m,
+            com_m,
+            inertia_kg_m2,
+            long_axis: -Vector3::y(),
+        })
+    }
+
+    // A segment hanging along -y from its proximal joint.
+    pub fn from_proportions(
+        name: &str,
+        body_mass_kg: f64,
+        length_m: f64,
+        (mass_fraction, com_fraction, gyration_fraction): (f64, f64, f64),
+    ) -> BiologyResult<Self> {
+        let mass = mass_fraction * body_mass_kg;
+        let transverse = mass * (gyration_fraction * length_m).powi(2);
+        Self::new(
+            name,
+            mass,
+            length_m,
+            Vector3::new(0.0, -com_fraction * length_m, 0.0),
+            Matrix3::from_diagonal(&Vector3::new(
+                transverse,
+                LONG_AXIS_INERTIA_FRACTION * transverse,
+                transverse,
+            )),
+        )
+    }
+
+    pub fn thigh(body_mass_kg: f64, length_m: f64) -> BiologyResult<Self> {
+        Self::from_proportions("thigh", body_mass_kg, length_m, THIGH)
+    }
+
+    pub fn shank(body_mass_kg: f64, length_m: f64) -> BiologyResult<Self> {
+        Self::from_proportions("shank", body_mass_kg, length_m, SHANK)
+    }
+
+    pub fn foot(body_mass_kg: f64, length_m: f64) -> BiologyResult<Self> {
+        Self::from_proportions("foot", body_mass_kg, length_m, FOOT)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JointKind {
+    // Rotation about an axis fixed in the parent frame.
+    Hinge(Vector3<f64>),
+    // Three rotations about x, then y, then z of the successively rotated
+    // frames; gimbal-locked at ±90° about y.
+    Ball,
+}
+
+impl JointKind {
+    fn axes(&self) -> Vec<Vector3<f64>> {
+        match self {
+            JointKind::Hinge(axis) => vec![axis.normalize()],
+            JointKind::Ball => vec![Vector3::x(), Vector3::y(), Vector3::z()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Body {
+    segment: Segment,
+    parent: Option<usize>,
+    // Joint centre in the parent's frame (world for the root).
+    offset_m: Vector3<f64>,
+    joint: JointKind,
+    first_dof: usize,
+}
+
+// Generalised coordinates: joint angles in radians, in body order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MultibodyState {
+    pub q: Vec<f64>,
+    pub qd: Vec<f64>,
+}
+
+// A force applied at a world point on a body, e.g. the ground reaction.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExternalForce {
+    pub body: usize,
+    pub point_m: Vector3<f64>,
+    pub force_n: Vector3<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct JointLoad {
+    // Exerted by the parent on the child at the joint centre, world frame.
+    pub force_n: Vector3<f64>,
+    pub moment_nm: Vector3<f64>,
+    // Along the child's long axis, positive in compression.
+    pub axial_compression_n: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InverseDynamics {
+    // Torques still needed at each coordinate beyond what the muscles
+    // supply; residuals when the muscles are meant to do all the work.
+    pub generalized_forces: Vec<f64>,
+    pub joint_loads: Vec<JointLoad>,
+    pub muscle_forces_n: Vec<f64>,
+}
+
+// Body kinematics in the world frame.
+#[derive(Debug, Clone)]
+struct Frame {
+    rotation: Matrix3<f64>,
+    origin: Vector3<f64>,
+    velocity: Vector3<f64>,
+    acceleration: Vector3<f64>,
+    omega: Vector3<f64>,
+    alpha: Vector3<f64>,
+    axes: Vec<Vector3<f64>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Skeleton {
+    bodies: Vec<Body>,
+    pub muscles: Vec<MuscleActuator>,
+    pub gravity_m_s2: Vector3<f64>,
+}
+
+impl Default for Skeleton {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Skeleton {
+    pub fn new() -> Self {
+        Self {
+            bodies: Vec::new(),
+            muscles: Vec::new(),
+            gravity_m_s2: Vector3::new(0.0, -STANDARD_GRAVITY_M_S2, 0.0),
+        }
+    }
+
+    // Hip (ball) at the origin, knee and ankle hinges about z.
+    pub fn lower_limb(body_mass_kg: f64, height_m: f64) -> BiologyResult<Self> {
+        // Segment lengths as fractions of height, Winter (2009) Fig. 4.1.
+        let thigh = 0.245 * height_m;
+        let shank = 0.246 * height_m;
+        let mut skeleton = Self::new();
+        let flexion = JointKind::Hinge(Vector3::z());
+        skeleton.add_body(
+            Segment::thigh(body_mass_kg, thigh)?,
+            None,
+            Vector3::zeros(),
+            JointKind::Ball,
+        )?;
+        skeleton.add_body(
+            Segment::shank(body_mass_kg, shank)?,
+            Some("thigh"),
+            Vector3::new(0.0, -thigh, 0.0),
+            flexion,
+        )?;
+        skeleton.add_body(
+            Segment::foot(body_mass_kg, 0.152 * height_m)?,
+            Some("shank"),
+            Vector3::new(0.0, -shank, 0.0),
+            flexion,
+        )?;
+        Ok(skeleton)
+    }
+
+    pub fn add_body(
+        &mut self,
+        segment: Segment,
+        parent: Option<&str>,
+        offset_m: Vector3<f64>,
+        joint: JointKind,
+    ) -> BiologyResult<usize> {
+        if self.index(&segment.name).is_some() {
+            return Err(BiologyError::InvalidParameter(format!(
+                "segment {} already exists",
+                segment.name
+            )));
+        }
+        let parent = match parent {
+            Some(name) => Some(self.index(name).ok_or_else(|| {
+                BiologyError::InvalidParameter(format!("no segment named {}", name))
+            })?),
+            None if self.bodies.is_empty() => None,
+            None => {
+                return Err(BiologyError::InvalidParameter(
+                    "only the first segment may attach to the base".to_string(),
+                ))
+            }
+        };
+        if let JointKind::Hinge(axis) = joint {
+            if axis.norm() == 0.0 {
+                return Err(BiologyError::InvalidParameter(
+                    "hinge axis must be non-zero".to_string(),
+                ));
+            }
+        }
+        self.bodies.push(Body {
+            segment,
+            parent,
+            offset_m,
+            joint,
+            first_dof: self.dofs(),
+        });
+        Ok(self.bodies.len() - 1)
+    }
+
+    pub fn add_muscle(&mut self, muscle: MuscleActuator) -> BiologyResult<()> {
+        if [muscle.origin.body, muscle.insertion.body]
+            .iter()
+            .any(|&b| b >= self.bodies.len())
+        {
+            return Err(BiologyError::InvalidParameter(format!(
+                "muscle {} attaches to a missing segment",
+                muscle.name
+            )));
+        }
+        self.muscles.push(muscle);
+        Ok(())
+    }
+
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.bodies.iter().position(|b| b.segment.name == name)
+    }
+
+    pub fn segment(&self, body: usize) -> Option<&Segment> {
+        self.bodies.get(body).map(|b| &b.segment)
+    }
+
+    pub fn dofs(&self) -> usize {
+        self.bodies.iter().map(|b| b.joint.axes().len()).sum()
+    }
+
+    pub fn rest_state(&self) -> MultibodyState {
+        MultibodyState {
+            q: vec![0.0; self.dofs()],
+            qd: vec![0.0; self.dofs()],
+        }
+    }
+
+    fn check(&self, state: &MultibodyState, activations: &[f64]) -> BiologyResult<()> {
+        if state.q.len() != self.dofs() || state.qd.len() != self.dofs() {
+            return Err(BiologyError::InvalidState(format!(
+                "state has {}/{} coordinates, skeleton has {}",
+                state.q.len(),
+                state.qd.len(),
+                self.dofs()
+            )));
+        }
+        if activations.len() != self.muscles.len() {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{} activations for {} muscles",
+                activations.len(),
+                self.muscles.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn kinematics(&self, q: &[f64], qd: &[f64], qdd: &[f64]) -> Vec<Frame> {
+        let mut frames: Vec<Frame> = Vec::with_capacity(self.bodies.len());
+        for body in &self.bodies {
+            let base = Frame {
+                rotation: Matrix3::identity(),
+                origin: Vector3::zeros(),
+                velocity: Vector3::zeros(),
+                acceleration: Vector3::zeros(),
+                omega: Vector3::zeros(),
+                alpha: Vector3::zeros(),
+                axes: Vec::new(),
+            };
+            let parent = body.parent.map_or(&base, |p| &frames[p]);
+            let r = parent.rotation * body.offset_m;
+            let mut frame = Frame {
+                rotation: parent.rotation,
+                origin: parent.origin + r,
+                velocity: parent.velocity + parent.omega.cross(&r),
+                acceleration: parent.acceleration
+                    + parent.alpha.cross(&r)
+                    + parent.omega.cross(&parent.omega.cross(&r)),
+                omega: parent.omega,
+                alpha: parent.alpha,
+                axes: Vec::new(),
+            };
+            for (k, axis) in body.joint.axes().into_iter().enumerate() {
+                let dof = body.first_dof + k;
+                let s = frame.rotation * axis;
+                frame.alpha += s * qdd[dof] + frame.omega.cross(&(s * qd[dof]));
+                frame.omega += s * qd[dof];
+                frame.rotation *=
+                    Rotation3::from_axis_angle(&Unit::new_normalize(axis), q[dof]).into_inner();
+                frame.axes.push(s);
+            }
+            frames.push(frame);
+        }
+        frames
+    }
+
+    fn muscle_loads(
+        &self,
+        frames: &[Frame],
+        activations: &[f64],
+    ) -> (Vec<f64>, Vec<ExternalForce>) {
+        let mut tensions = Vec::with_capacity(self.muscles.len());
+        let mut loads = Vec::with_capacity(2 * self.muscles.len());
+        for (muscle, &activation) in self.muscles.iter().zip(activations) {
+            let point = |a: &Attachment| {
+                let frame = &frames[a.body];
+                let r = frame.rotation * a.point_m;
+                (frame.origin + r, frame.velocity + frame.omega.cross(&r))
+            };
+            let (origin, origin_velocity) = point(&muscle.origin);
+            let (insertion, insertion_velocity) = point(&muscle.insertion);
+            let path = insertion - origin;
+            let length = path.norm();
+            let direction = path / length.max(f64::MIN_POSITIVE);
+            let lengthening = (insertion_velocity - origin_velocity).dot(&direction);
+            let tension = muscle.force_n(activation, length, lengthening);
+            tensions.push(tension);
+            loads.push(ExternalForce {
+                body: muscle.origin.body,
+                point_m: origin,
+                force_n: direction * tension,
+            });
+            loads.push(ExternalForce {
+                body: muscle.insertion.body,
+                point_m: insertion,
+                force_n: -direction * tension,
+            });
+        }
+        (tensions, loads)
+    }
+
+    // Recursive Newton-Euler; returns generalised forces and joint loads.
+    fn rnea(
+        &self,
+        frames: &[Frame],
+        gravity: Vector3<f64>,
+        external: &[ExternalForce],
+    ) -> (Vec<f64>, Vec<JointLoad>) {
+        let n = self.bodies.len();
+        let mut forces = vec![Vector3::zeros(); n];
+        let mut moments = vec![Vector3::zeros(); n];
+        for (i, body) in self.bodies.iter().enumerate() {
+            let frame = &frames[i];
+            let segment = &body.segment;
+            let rc = frame.rotation * segment.com_m;
+            let com_acceleration = frame.acceleration
+                + frame.alpha.cross(&rc)
+                + frame.omega.cross(&frame.omega.cross(&rc));
+            let inertia = frame.rotation * segment.inertia_kg_m2 * frame.rotation.transpose();
+            let inertial_force = segment.mass_kg * (com_acceleration - gravity);
+            forces[i] = inertial_force;
+            moments[i] = inertia * frame.alpha
+                + frame.omega.cross(&(inertia * frame.omega))
+                + rc.cross(&inertial_force);
+        }
+        for load in external {
+            let origin = frames[load.body].origin;
+            forces[load.body] -= load.force_n;
+            moments[load.body] -= (load.point_m - origin).cross(&load.force_n);
+        }
+        let mut generalized = vec![0.0; self.dofs()];
+        for i in (0..n).rev() {
+            let body = &self.bodies[i];
+            for (k, s) in frames[i].axes.iter().enumerate() {
+                generalized[body.first_dof + k] = s.dot(&moments[i]);
+            }
+            if let Some(p) = body.parent {
+                let lever = frames[i].origin - frames[p].origin;
+                let (force, moment) = (forces[i], moments[i]);
+                forces[p] += force;
+                moments[p] += moment + lever.cross(&force);
+            }
+        }
+        let loads = (0..n)
+            .map(|i| {
+                let axis = frames[i].rotation * self.bodies[i].segment.long_axis;
+                JointLoad {
+                    force_n: forces[i],
+                    moment_nm: moments[i],
+                    axial_compression_n: forces[i].dot(&axis),
+                }
+            })
+            .collect();
+        (generalized, loads)
+    }
+
+    pub fn inverse_dynamics(
+        &self,
+        state: &MultibodyState,
+        qdd: &[f64],
+        activations: &[f64],
+        external: &[ExternalForce],
+    ) -> BiologyResult<InverseDynamics> {
+        self.check(state, activations)?;
+        if qdd.len() != self.dofs() {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{} accelerations for {} coordinates",
+                qdd.len(),
+                self.dofs()
+            )));
+        }
+        let frames = self.kinematics(&state.q, &state.qd, qdd);
+        let (muscle_forces_n, mut loads) = self.muscle_loads(&frames, activations);
+        loads.extend_from_slice(external);
+        let (generalized_forces, joint_loads) = self.rnea(&frames, self.gravity_m_s2, &loads);
+        Ok(InverseDynamics {
+            generalized_forces,
+            joint_loads,
+            muscle_forces_n,
+        })
+    }
+
+    // Joint accelerations under applied generalised forces `tau`, muscle
+    // activations and external loads.
+    pub fn forward_dynamics(
+        &self,
+        state: &MultibodyState,
+        tau: &[f64],
+        activations: &[f64],
+        external: &[ExternalForce],
+    ) -> BiologyResult<Vec<f64>> {
+        let n = self.dofs();
+        if tau.len() != n {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{} generalised forces for {} coordinates",
+                tau.len(),
+                n
+            )));
+        }
+        let zeros = vec![0.0; n];
+        let bias = self
+            .inverse_dynamics(state, &zeros, activations, external)?
+            .generalized_forces;
+        let mut mass = DMatrix::zeros(n, n);
+        let mut unit = zeros.clone();
+        for column in 0..n {
+            unit[column] = 1.0;
+            let frames = self.kinematics(&state.q, &zeros, &unit);
+            let (col, _) = self.rnea(&frames, Vector3::zeros(), &[]);
+            mass.set_column(column, &DVector::from_vec(col));
+            unit[column] = 0.0;
+        }
+        let rhs = DVector::from_iterator(n, tau.iter().zip(&bias).map(|(t, b)| t - b));
+        mass.cholesky()
+            .map(|c| c.solve(&rhs).as_slice().to_vec())
+            .ok_or_else(|| {
+                BiologyError::InvalidState("mass matrix is not positive definite".to_string())
+            })
+    }
+
+    // Semi-implicit Euler: velocities first, then angles with the new
+    // velocities.
+    pub fn step(
+        &self,
+        state: &mut MultibodyState,
+        tau: &[f64],
+        activations: &[f64],
+        external: &[ExternalForce],
+        dt_s: f64,
+    ) -> BiologyResult<()> {
+        let qdd = self.forward_dynamics(state, tau, activations, external)?;
+        for ((q, qd), a) in state.q.iter_mut().zip(state.qd.iter_mut()).zip(qdd) {
+            *qd += a * dt_s;
+            *q += *qd * dt_s;
+        }
+        Ok(())
+    }
+
+    pub fn energy_j(&self, state: &MultibodyState) -> f64 {
+        let frames = self.kinematics(&state.q, &state.qd, &vec![0.0; self.dofs()]);
+        self.bodies
+            .iter()
+            .zip(&frames)
+            .map(|(body, frame)| {
+                let segment = &body.segment;
+                let rc = frame.rotation * segment.com_m;
+                let v = frame.velocity + frame.omega.cross(&rc);
+                let inertia = frame.rotation * segment.inertia_kg_m2 * frame.rotation.transpose();
+                0.5 * segment.mass_kg * v.norm_squared()
+                    + 0.5 * frame.omega.dot(&(inertia * frame.omega))
+                    - segment.mass_kg * self.gravity_m_s2.dot(&(frame.origin + rc))
+            })
+            .sum()
+    }
+}
+
+// Cortical cross-section carrying a joint's axial load.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoneSection {
+    pub cortical_area_m2: f64,
+    pub youngs_modulus_pa: f64,
+}
+
+impl BoneSection {
+    // Adult tibial midshaft, ~3.5 cm² of cortex.
+    pub fn tibial_midshaft() -> Self {
+        Self {
+            cortical_area_m2: 3.5e-4,
+            youngs_modulus_pa: BoneMaterial::cortical().youngs_modulus_gpa * 1.0e9,
+        }
+    }
+
+    pub fn microstrain(&self, axial_force_n: f64) -> f64 {
+        axial_force_n.abs() / (self.youngs_modulus_pa * self.cortical_area_m2) * 1.0e6
+    }
+
+    // Peak strain over a loading cycle, repeated `cycles` times a day.
+    pub fn load_cycles(&self, loads: &[JointLoad], cycles: f64) -> LoadCycles {
+        LoadCycles {
+            peak_microstrain: loads
+                .iter()
+                .map(|l| self.microstrain(l.axial_compression_n))
+                .fold(0.0, f64::max),
+            cycles,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pendulum() -> Skeleton {
+        let mut skeleton = Skeleton::new();
+        let rod = Segment::new(
+            "rod",
+            2.0,
+            1.0,
+            Vector3::new(0.0, -0.5, 0.0),
+            Matrix3::from_diagonal(&Vector3::new(1.0 / 6.0, 0.0, 1.0 / 6.0)),
+        )
+        .unwrap();
+        skeleton
+            .add_body(rod, None, Vector3::zeros(), JointKind::Hinge(Vector3::z()))
+            .unwrap();
+        skeleton
+    }
+
+    #[test]
+    fn test_static_holding_torque_and_reaction() {
+        let skeleton = pendulum();
+        // Held horizontal: τ = m·g·d, the pivot carries the weight.
+        let state = MultibodyState {
+            q: vec![std::f64::consts::FRAC_PI_2],
+            qd: vec![0.0],
+        };
+        let id = skeleton.inverse_dynamics(&state, &[0.0], &[], &[]).unwrap();
+        assert!((id.generalized_forces[0] - 2.0 * STANDARD_GRAVITY_M_S2 * 0.5).abs() < 1e-9);
+        let reaction = id.joint_loads[0].force_n;
+        assert!((reaction - Vector3::new(0.0, 2.0 * STANDARD_GRAVITY_M_S2, 0.0)).norm() < 1e-9);
+        // Released, it swings with energy conserved to first order in dt.
+        let mut state = MultibodyState {
+            q: vec![1.0],
+            qd: vec![0.0],
+        };
+        let start = skeleton.energy_j(&state);
+        for _ in 0..2000 {
+            skeleton.step(&mut state, &[0.0], &[], &[], 1e-4).unwrap();
+        }
+        assert!((skeleton.energy_j(&state) - start).abs() < 1e-2);
+        assert!(state.q[0] < 1.0);
+    }
+
+    #[test]
+    fn test_forward_and_inverse_dynamics_agree() {
+        let skeleton = Skeleton::lower_limb(70.0, 1.75).unwrap();
+        assert_eq!(skeleton.dofs(), 5);
+        let state = MultibodyState {
+            q: vec![0.3, -0.2, 0.1, -0.6, 0.2],
+            qd: vec![0.5, 0.1, -0.3, 1.2, -0.4],
+        };
+        let tau = [5.0, -3.0, 2.0, 10.0, -1.0];
+        let qdd = skeleton.forward_dynamics(&state, &tau, &[], &[]).unwrap();
+        let id = skeleton.inverse_dynamics(&state, &qdd, &[], &[]).unwrap();
+        for (a, b) in id.generalized_forces.iter().zip(&tau) {
+            assert!((a - b).abs() < 1e-8, "{} vs {}", a, b);
+        }
+        assert!(skeleton.forward_dynamics(&state, &[0.0], &[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_quadriceps_compress_the_knee_in_stance() {
+        let mut skeleton = Skeleton::lower_limb(70.0, 1.75).unwrap();
+        let thigh = skeleton.index("thigh").unwrap();
+        let shank = skeleton.index("shank").unwrap();
+        let foot = skeleton.index("foot").unwrap();
+        // Vasti from the anterior femur to the tibial tuberosity.
+        let vasti = MuscleActuator::new(
+            "vasti",
+            Attachment {
+                body: thigh,
+                point_m: Vector3::new(0.03, -0.2, 0.0),
+            },
+            Attachment {
+                body: shank,
+                point_m: Vector3::new(0.04, -0.05, 0.0),
+            },
+            6000.0,
+            0.09,
+            0.15,
+        )
+        .unwrap();
+        skeleton.add_muscle(vasti).unwrap();
+        // Flexed-knee stance with one body weight under the foot.
+        let state = MultibodyState {
+            q: vec![0.0, 0.0, 0.4, -0.6, 0.2],
+            qd: vec![0.0; 5],
+        };
+        let ground = ExternalForce {
+            body: foot,
+            point_m: Vector3::new(0.0, -0.9, 0.0),
+            force_n: Vector3::new(0.0, 70.0 * STANDARD_GRAVITY_M_S2, 0.0),
+        };
+        let qdd = vec![0.0; 5];
+        let passive = skeleton
+            .inverse_dynamics(&state, &qdd, &[0.0], &[ground])
+            .unwrap();
+        let active = skeleton
+            .inverse_dynamics(&state, &qdd, &[0.5], &[ground])
+            .unwrap();
+        assert!(active.muscle_forces_n[0] > 1000.0);
+        let knee = &active.joint_loads[shank];
+        assert!(knee.axial_compression_n > passive.joint_loads[shank].axial_compression_n + 500.0);
+        let cycles =
+            BoneSection::tibial_midshaft().load_cycles(&active.joint_loads[shank..=shank], 5000.0);
+        assert!(
+            (100.0..2000.0).contains(&cycles.peak_microstrain),
+            "{}",
+            cycles.peak_microstrain
+        );
+    }
+}
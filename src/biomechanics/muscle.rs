@@ -0,0 +1,138 @@
+use crate::biology::{BiologyError, BiologyResult};
+use nalgebra::Vector3;
+use serde::{Deserialize, Serialize};
+
+// Hill-type muscle with a rigid tendon: fibre force is activation times
+// the active force-length and force-velocity curves, plus a passive
+// exponential once the fibre is stretched past optimal length. Fibre
+// velocity is normalised by the maximum shortening velocity, negative
+// when shortening.
+// Thelen DG (2003) J Biomech Eng 125:70-77
+const ACTIVE_WIDTH: f64 = 0.45;
+const PASSIVE_SHAPE: f64 = 4.0;
+const PASSIVE_STRAIN_AT_ISOMETRIC: f64 = 0.6;
+const CONCENTRIC_CURVATURE: f64 = 0.25;
+const ECCENTRIC_PLATEAU: f64 = 1.8;
+const ECCENTRIC_CURVATURE: f64 = 0.18;
+
+// Where a muscle path starts or ends: a point fixed in a body's frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Attachment {
+    pub body: usize,
+    pub point_m: Vector3<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MuscleActuator {
+    pub name: String,
+    pub origin: Attachment,
+    pub insertion: Attachment,
+    pub max_isometric_force_n: f64,
+    pub optimal_fibre_length_m: f64,
+    pub tendon_slack_length_m: f64,
+    // Optimal fibre lengths per second.
+    pub max_shortening_velocity: f64,
+}
+
+impl MuscleActuator {
+    pub fn new(
+        name: &str,
+        origin: Attachment,
+        insertion: Attachment,
+        max_isometric_force_n: f64,
+        optimal_fibre_length_m: f64,
+        tendon_slack_length_m: f64,
+    ) -> BiologyResult<Self> {
+        if max_isometric_force_n.is_nan()
+            || max_isometric_force_n <= 0.0
+            || optimal_fibre_length_m.is_nan()
+            || optimal_fibre_length_m <= 0.0
+            || tendon_slack_length_m.is_nan()
+            || tendon_slack_length_m < 0.0
+        {
+            return Err(BiologyError::InvalidParameter(format!(
+                "muscle {}: force and fibre length must be positive, tendon slack non-negative",
+                name
+            )));
+        }
+        if origin.body == insertion.body {
+            return Err(BiologyError::InvalidParameter(format!(
+                "muscle {} must cross a joint",
+                name
+            )));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            origin,
+            insertion,
+            max_isometric_force_n,
+            optimal_fibre_length_m,
+            tendon_slack_length_m,
+            max_shortening_velocity: 10.0,
+        })
+    }
+
+    // Gaussian active force-length relation.
+    pub fn active_force_length(normalised_length: f64) -> f64 {
+        (-(normalised_length - 1.0).powi(2) / ACTIVE_WIDTH).exp()
+    }
+
+    pub fn passive_force_length(normalised_length: f64) -> f64 {
+        if normalised_length <= 1.0 {
+            return 0.0;
+        }
+        ((PASSIVE_SHAPE * (normalised_length - 1.0) / PASSIVE_STRAIN_AT_ISOMETRIC).exp() - 1.0)
+            / (PASSIVE_SHAPE.exp() - 1.0)
+    }
+
+    // Hill's hyperbola when shortening; rising to 1.8 times isometric
+    // when lengthening.
+    pub fn force_velocity(normalised_velocity: f64) -> f64 {
+        let v = normalised_velocity.max(-1.0);
+        if v <= 0.0 {
+            (1.0 + v) / (1.0 - v / CONCENTRIC_CURVATURE)
+        } else {
+            1.0 + (ECCENTRIC_PLATEAU - 1.0) * v / (v + ECCENTRIC_CURVATURE)
+        }
+    }
+
+    // Tension along the path for a given musculotendon length and
+    // lengthening rate; never pushes.
+    pub fn force_n(&self, activation: f64, path_length_m: f64, path_velocity_m_s: f64) -> f64 {
+        let activation = activation.clamp(0.0, 1.0);
+        let fibre = (path_length_m - self.tendon_slack_length_m) / self.optimal_fibre_length_m;
+        let velocity =
+            path_velocity_m_s / (self.optimal_fibre_length_m * self.max_shortening_velocity);
+        let active = activation * Self::active_force_length(fibre) * Self::force_velocity(velocity);
+        (self.max_isometric_force_n * (active + Self::passive_force_length(fibre))).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_force_curves() {
+        assert_eq!(MuscleActuator::active_force_length(1.0), 1.0);
+        assert!(MuscleActuator::active_force_length(1.5) < 0.6);
+        assert_eq!(MuscleActuator::passive_force_length(0.9), 0.0);
+        assert!((MuscleActuator::passive_force_length(1.6) - 1.0).abs() < 1e-12);
+        assert_eq!(MuscleActuator::force_velocity(-1.0), 0.0);
+        assert_eq!(MuscleActuator::force_velocity(0.0), 1.0);
+        assert!(MuscleActuator::force_velocity(1.0) > 1.6);
+    }
+
+    #[test]
+    fn test_muscle_force_and_validation() {
+        let a = Attachment {
+            body: 0,
+            point_m: Vector3::zeros(),
+        };
+        let b = Attachment { body: 1, ..a };
+        let muscle = MuscleActuator::new("vasti", a, b, 6000.0, 0.09, 0.2).unwrap();
+        assert!((muscle.force_n(1.0, 0.29, 0.0) - 6000.0).abs() < 1e-9);
+        assert_eq!(muscle.force_n(0.0, 0.29, 0.0), 0.0);
+        assert!(MuscleActuator::new("x", a, a, 100.0, 0.1, 0.1).is_err());
+    }
+}
@@ -0,0 +1,409 @@
+//! C interface for embedding the coupled [`Engine`] in other simulators.
+//!
+//! Engines are opaque heap handles. Every fallible call returns an
+//! `HB_*` status code; on failure `hb_last_error()` describes the problem.
+//! Strings are NUL-terminated UTF-8 and are only borrowed for the duration
+//! of a call. The declarations live in `include/human_biology.h`.
+
+use crate::simulation::engine::{Coupling, Engine};
+use crate::simulation::scenario::{build_component, Scenario};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+pub const HB_OK: c_int = 0;
+pub const HB_ERROR_NULL_POINTER: c_int = -1;
+pub const HB_ERROR_INVALID_STRING: c_int = -2;
+pub const HB_ERROR_MODEL: c_int = -3;
+pub const HB_ERROR_PANIC: c_int = -4;
+
+pub struct HbEngine {
+    engine: Engine,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).expect("NULs removed");
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+}
+
+enum Failure {
+    Null,
+    InvalidString,
+    Model(String),
+}
+
+fn guarded(body: impl FnOnce() -> Result<(), Failure>) -> c_int {
+    match catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => HB_OK,
+        Ok(Err(Failure::Null)) => {
+            set_last_error("null pointer argument");
+            HB_ERROR_NULL_POINTER
+        }
+        Ok(Err(Failure::InvalidString)) => {
+            set_last_error("string argument is not valid UTF-8");
+            HB_ERROR_INVALID_STRING
+        }
+        Ok(Err(Failure::Model(message))) => {
+            set_last_error(&message);
+            HB_ERROR_MODEL
+        }
+        Err(_) => {
+            set_last_error("internal panic");
+            HB_ERROR_PANIC
+        }
+    }
+}
+
+unsafe fn text<'a>(s: *const c_char) -> Result<&'a str, Failure> {
+    if s.is_null() {
+        return Err(Failure::Null);
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| Failure::InvalidString)
+}
+
+unsafe fn handle<'a>(engine: *mut HbEngine) -> Result<&'a mut Engine, Failure> {
+    engine.as_mut().map(|h| &mut h.engine).ok_or(Failure::Null)
+}
+
+fn model<T, E: std::fmt::Display>(result: Result<T, E>) -> Result<T, Failure> {
+    result.map_err(|e| Failure::Model(e.to_string()))
+}
+
+fn boxed(engine: Engine) -> *mut HbEngine {
+    Box::into_raw(Box::new(HbEngine { engine }))
+}
+
+/// Crate version as a static NUL-terminated string.
+#[no_mangle]
+pub extern "C" fn hb_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Message for the last failed call on this thread; valid until the next
+/// failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn hb_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ptr())
+}
+
+/// Creates an empty engine; returns NULL if `dt_minutes` is not positive.
+#[no_mangle]
+pub extern "C" fn hb_engine_new(dt_minutes: f64) -> *mut HbEngine {
+    let mut created = ptr::null_mut();
+    guarded(|| {
+        created = boxed(model(Engine::new(dt_minutes))?);
+        Ok(())
+    });
+    created
+}
+
+/// Builds an engine from a TOML scenario; returns NULL on error.
+///
+/// # Safety
+/// `toml` must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn hb_engine_from_scenario(toml: *const c_char) -> *mut HbEngine {
+    let mut created = ptr::null_mut();
+    guarded(|| {
+        let scenario = model(Scenario::from_toml_str(text(toml)?))?;
+        created = boxed(model(scenario.build())?.engine);
+        Ok(())
+    });
+    created
+}
+
+/// Releases an engine; NULL is ignored.
+///
+/// # Safety
+/// `engine` must be NULL or a handle from this library not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn hb_engine_free(engine: *mut HbEngine) {
+    if !engine.is_null() {
+        drop(Box::from_raw(engine));
+    }
+}
+
+/// Adds a model instance; `preset` may be NULL for the model's default.
+///
+/// # Safety
+/// `engine` must be a live handle; strings must be NULL or NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn hb_engine_add_entity(
+    engine: *mut HbEngine,
+    name: *const c_char,
+    model_name: *const c_char,
+    preset: *const c_char,
+) -> c_int {
+    guarded(|| {
+        let engine = handle(engine)?;
+        let preset = if preset.is_null() {
+            None
+        } else {
+            Some(text(preset)?)
+        };
+        let component = model(build_component(text(model_name)?, preset))?;
+        model(engine.add_entity(text(name)?, component))
+    })
+}
+
+/// Drives input `to` with `offset + gain * from` before every step.
+///
+/// # Safety
+/// `engine` must be a live handle; strings must be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn hb_engine_couple(
+    engine: *mut HbEngine,
+    from: *const c_char,
+    to: *const c_char,
+    gain: f64,
+    offset: f64,
+) -> c_int {
+    guarded(|| {
+        let engine = handle(engine)?;
+        let coupling = Coupling::new(text(from)?, text(to)?)
+            .with_gain(gain)
+            .with_offset(offset);
+        model(engine.couple(coupling))
+    })
+}
+
+/// Sets an `entity.input` parameter.
+///
+/// # Safety
+/// `engine` must be a live handle; `path` must be NUL-terminated.
+#[no_mangle]
+pub unsafe extern "C" fn hb_engine_set_input(
+    engine: *mut HbEngine,
+    path: *const c_char,
+    value: f64,
+) -> c_int {
+    guarded(|| model(handle(engine)?.set_input(text(path)?, value)))
+}
+
+/// Reads an `entity.output` observable into `*value`.
+///
+/// # Safety
+/// `engine` must be a live handle, `path` NUL-terminated and `value` a
+/// writable pointer.
+#[no_mangle]
+pub unsafe extern "C" fn hb_engine_get_output(
+    engine: *mut HbEngine,
+    path: *const c_char,
+    value: *mut f64,
+) -> c_int {
+    guarded(|| {
+        let read = model(handle(engine)?.value(text(path)?))?;
+        *value.as_mut().ok_or(Failure::Null)? = read;
+        Ok(())
+    })
+}
+
+/// Advances `steps` fixed steps.
+///
+/// # Safety
+/// `engine` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn hb_engine_step(engine: *mut HbEngine, steps: u64) -> c_int {
+    guarded(|| {
+        let engine = handle(engine)?;
+        for _ in 0..steps {
+            model(engine.step())?;
+        }
+        Ok(())
+    })
+}
+
+/// Simulated time in minutes, or NaN for a NULL handle.
+///
+/// # Safety
+/// `engine` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn hb_engine_time_minutes(engine: *const HbEngine) -> f64 {
+    engine.as_ref().map_or(f64::NAN, |h| h.engine.time_minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    fn last_error() -> String {
+        unsafe { CStr::from_ptr(hb_last_error()) }
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    #[test]
+    fn test_engine_lifecycle_through_c_calls() {
+        unsafe {
+            let engine = hb_engine_new(1.0);
+            assert!(!engine.is_null());
+            let body = c("body");
+            let thermo = c("thermoregulation");
+            assert_eq!(
+                hb_engine_add_entity(engine, body.as_ptr(), thermo.as_ptr(), ptr::null()),
+                HB_OK
+            );
+            assert_eq!(
+                hb_engine_set_input(engine, c("body.activity_w").as_ptr(), 500.0),
+                HB_OK
+            );
+            assert_eq!(hb_engine_step(engine, 30), HB_OK);
+            assert_eq!(hb_engine_time_minutes(engine), 30.0);
+            let mut core = 0.0;
+            assert_eq!(
+                hb_engine_get_output(engine, c("body.core_temperature_c").as_ptr(), &mut core),
+                HB_OK
+            );
+            assert!(core > 37.0);
+            hb_engine_free(engine);
+        }
+    }
+
+    #[test]
+    fn test_errors_are_reported_not_panicked() {
+        unsafe {
+            assert!(hb_engine_new(0.0).is_null());
+            assert!(last_error().contains("time step"));
+            let engine = hb_engine_new(1.0);
+            let mut value = 0.0;
+            assert_eq!(
+                hb_engine_get_output(engine, c("heart.rate").as_ptr(), &mut value),
+                HB_ERROR_MODEL
+            );
+            assert!(last_error().contains("no entity named heart"));
+            assert_eq!(
+                hb_engine_get_output(engine, ptr::null(), &mut value),
+                HB_ERROR_NULL_POINTER
+            );
+            assert_eq!(hb_engine_step(ptr::null_mut(), 1), HB_ERROR_NULL_POINTER);
+            let invalid = [0xffu8 as c_char, 0];
+            assert_eq!(
+                hb_engine_set_input(engine, invalid.as_ptr(), 1.0),
+                HB_ERROR_INVALID_STRING
+            );
+            hb_engine_free(engine);
+            hb_engine_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_scenario_handle_and_version() {
+        let toml = c(include_str!(
+            "../config_examples/exercise_in_heat_scenario.toml"
+        ));
+        unsafe {
+            let engine = hb_engine_from_scenario(toml.as_ptr());
+            assert!(!engine.is_null());
+            assert_eq!(hb_engine_step(engine, 10), HB_OK);
+            hb_engine_free(engine);
+            assert_eq!(
+                CStr::from_ptr(hb_version()).to_str().unwrap(),
+                crate::VERSION
+            );
+        }
+    }
+
+    // The C spelling of each type the exports use.
+    fn c_type(rust: &str) -> &'static str {
+        match rust {
+            "*const c_char" => "const char *",
+            "*mut HbEngine" => "HbEngine *",
+            "*const HbEngine" => "const HbEngine *",
+            "*mut f64" => "double *",
+            "f64" => "double",
+            "u64" => "uint64_t",
+            "c_int" => "int",
+            "" => "void",
+            other => panic!("no C type for {}", other),
+        }
+    }
+
+    fn declare(c_type: &str, name: &str) -> String {
+        if c_type.ends_with('*') {
+            format!("{}{}", c_type, name)
+        } else {
+            format!("{} {}", c_type, name)
+        }
+    }
+
+    #[test]
+    fn test_header_prototypes_match_exports() {
+        let source = include_str!("capi.rs");
+        let exports: BTreeMap<String, String> = source
+            .split("extern \"C\" fn ")
+            .skip(1)
+            .filter(|rest| rest.starts_with("hb_"))
+            .map(|rest| {
+                let (name, rest) = rest.split_once('(').unwrap();
+                let (params, rest) = rest.split_once(')').unwrap();
+                let returns = rest.split('{').next().unwrap().trim();
+                let params: Vec<String> = params
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|p| !p.is_empty())
+                    .map(|p| {
+                        let (param, ty) = p.split_once(':').unwrap();
+                        declare(c_type(ty.trim()), param.trim())
+                    })
+                    .collect();
+                let params = if params.is_empty() {
+                    "void".to_string()
+                } else {
+                    params.join(", ")
+                };
+                let returns = c_type(returns.trim_start_matches("->").trim());
+                (
+                    name.to_string(),
+                    format!("{}({})", declare(returns, name), params),
+                )
+            })
+            .collect();
+
+        let header = include_str!("../include/human_biology.h");
+        let mut code = String::new();
+        let mut rest = header;
+        while let Some((before, after)) = rest.split_once("/*") {
+            code.push_str(before);
+            rest = after.split_once("*/").unwrap().1;
+        }
+        code.push_str(rest);
+        let code: String = code
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let declared: BTreeMap<String, String> = code
+            .split(';')
+            .filter_map(|statement| {
+                let start = statement.find("hb_")?;
+                let name: String = statement[start..]
+                    .chars()
+                    .take_while(|c| c.is_alphanumeric() || *c == '_')
+                    .collect();
+                let prototype = statement.split_whitespace().collect::<Vec<_>>().join(" ");
+                Some((name, prototype))
+            })
+            .collect();
+
+        assert!(!exports.is_empty());
+        assert_eq!(
+            declared.keys().collect::<Vec<_>>(),
+            exports.keys().collect::<Vec<_>>()
+        );
+        for (name, prototype) in &exports {
+            assert_eq!(&declared[name], prototype, "{} differs", name);
+        }
+    }
+}
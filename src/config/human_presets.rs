@@ -1,4 +1,5 @@
 use crate::config::baseline_params::BaselineHumanParams;
+use crate::systems::cardiovascular::hematology::BiologicalSex;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -15,6 +16,23 @@ pub enum PresetType {
     YoungAdultFemaleHealthy,
 }
 
+impl PresetType {
+    pub fn sex(&self) -> BiologicalSex {
+        match self {
+            PresetType::AdultMaleHealthy
+            | PresetType::AdultMaleAthlete
+            | PresetType::AdultMaleObesity
+            | PresetType::ElderlyMaleHealthy
+            | PresetType::YoungAdultMaleHealthy => BiologicalSex::Male,
+            PresetType::AdultFemaleHealthy
+            | PresetType::AdultFemaleAthlete
+            | PresetType::AdultFemaleObesity
+            | PresetType::ElderlyFemaleHealthy
+            | PresetType::YoungAdultFemaleHealthy => BiologicalSex::Female,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HumanPreset {
     pub preset_type: PresetType,
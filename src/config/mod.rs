@@ -1,7 +1,12 @@
 pub mod baseline_params;
 pub mod human_presets;
+pub mod presets;
 
 pub use baseline_params::{
     BaselineHumanParams, CardiovascularParams, MetabolicParams, RenalParams, RespiratoryParams,
 };
 pub use human_presets::{HumanPreset, PresetType};
+pub use presets::{
+    BoneMaterial, Citation, CollagenProperties, CollagenType, FluidProperties, NeuronPreset,
+    PresetCategory, PresetInfo, VaccinePreset,
+};
@@ -0,0 +1,495 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::immune::granuloma::{AntigenDepot, DepotKind};
+use crate::systems::nervous::action_potential::{ActionPotentialDynamics, NeuronBuilder};
+use serde::{Deserialize, Serialize};
+
+// Literature parameter sets for tissues, fluids, neurons and vaccines.
+// Every preset carries its sources, and `catalog()` lists them by key so
+// callers can report where a number came from at runtime.
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    pub citation: String,
+    pub pmid: Option<String>,
+    pub doi: Option<String>,
+}
+
+impl Citation {
+    fn new(citation: &str, pmid: Option<&str>, doi: Option<&str>) -> Self {
+        Self {
+            citation: citation.to_string(),
+            pmid: pmid.map(str::to_string),
+            doi: doi.map(str::to_string),
+        }
+    }
+
+    fn text(citation: &str) -> Self {
+        Self::new(citation, None, None)
+    }
+}
+
+fn require(condition: bool, what: &str, name: &str) -> BiologyResult<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(BiologyError::InvalidParameter(format!(
+            "preset {}: {}",
+            name, what
+        )))
+    }
+}
+
+// Apparent (whole-specimen) properties under longitudinal compression.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoneMaterial {
+    pub name: String,
+    pub youngs_modulus_gpa: f64,
+    pub poisson_ratio: f64,
+    pub apparent_density_g_cm3: f64,
+    pub compressive_strength_mpa: f64,
+    pub porosity_fraction: f64,
+    pub sources: Vec<Citation>,
+}
+
+impl BoneMaterial {
+    // Human femoral diaphysis, longitudinal: E 17.9 GPa, ultimate
+    // compressive strength 193 MPa, Poisson ratio 0.4.
+    // Reilly DT, Burstein AH (1975) J Biomech 8:393-405, PMID 1206042
+    // Porosity is the typical 5-10% of adult Haversian bone.
+    pub fn cortical() -> Self {
+        Self {
+            name: "cortical_bone".to_string(),
+            youngs_modulus_gpa: 17.9,
+            poisson_ratio: 0.4,
+            apparent_density_g_cm3: 1.85,
+            compressive_strength_mpa: 193.0,
+            porosity_fraction: 0.07,
+            sources: vec![Citation::new(
+                "Reilly DT, Burstein AH (1975) J Biomech 8:393-405",
+                Some("1206042"),
+                Some("10.1016/0021-9290(75)90075-5"),
+            )],
+        }
+    }
+
+    // Human vertebral trabecular bone, on-axis: E ~0.34 GPa, yield
+    // stress ~2 MPa at apparent density ~0.17 g/cm3 (porosity ~90%).
+    // Morgan EF, Keaveny TM (2001) J Biomech 34:569-577, PMID 11311697
+    pub fn trabecular() -> Self {
+        Self {
+            name: "trabecular_bone".to_string(),
+            youngs_modulus_gpa: 0.344,
+            poisson_ratio: 0.3,
+            apparent_density_g_cm3: 0.17,
+            compressive_strength_mpa: 2.0,
+            porosity_fraction: 0.9,
+            sources: vec![Citation::new(
+                "Morgan EF, Keaveny TM (2001) J Biomech 34:569-577",
+                Some("11311697"),
+                Some("10.1016/s0021-9290(01)00011-2"),
+            )],
+        }
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        require(
+            self.youngs_modulus_gpa > 0.0,
+            "modulus must be positive",
+            &self.name,
+        )?;
+        require(
+            self.poisson_ratio > -1.0 && self.poisson_ratio < 0.5,
+            "Poisson ratio must lie in (-1, 0.5)",
+            &self.name,
+        )?;
+        require(
+            self.apparent_density_g_cm3 > 0.0 && self.compressive_strength_mpa > 0.0,
+            "density and strength must be positive",
+            &self.name,
+        )?;
+        require(
+            (0.0..1.0).contains(&self.porosity_fraction),
+            "porosity must lie in [0, 1)",
+            &self.name,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollagenType {
+    TypeI,
+    TypeII,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollagenProperties {
+    pub collagen_type: CollagenType,
+    pub chain_composition: String,
+    pub triple_helix_length_nm: f64,
+    pub typical_fibril_diameter_nm: f64,
+    pub tissues: Vec<String>,
+    pub sources: Vec<Citation>,
+}
+
+impl CollagenProperties {
+    // Fibril-forming collagens share the ~300 nm triple helix; type I
+    // forms the thick heterotrimeric fibrils of bone, tendon and skin,
+    // type II the thin homotrimeric fibrils of cartilage.
+    // Ricard-Blum S (2011) Cold Spring Harb Perspect Biol 3:a004978, PMID 21421911
+    pub fn type_i() -> Self {
+        Self {
+            collagen_type: CollagenType::TypeI,
+            chain_composition: "[α1(I)]2 α2(I)".to_string(),
+            triple_helix_length_nm: 300.0,
+            typical_fibril_diameter_nm: 100.0,
+            tissues: ["bone", "tendon", "skin", "dentin"]
+                .map(str::to_string)
+                .to_vec(),
+            sources: vec![Self::ricard_blum_2011()],
+        }
+    }
+
+    pub fn type_ii() -> Self {
+        Self {
+            collagen_type: CollagenType::TypeII,
+            chain_composition: "[α1(II)]3".to_string(),
+            triple_helix_length_nm: 300.0,
+            typical_fibril_diameter_nm: 30.0,
+            tissues: ["hyaline cartilage", "vitreous humor", "nucleus pulposus"]
+                .map(str::to_string)
+                .to_vec(),
+            sources: vec![Self::ricard_blum_2011()],
+        }
+    }
+
+    pub fn from_type(collagen_type: CollagenType) -> Self {
+        match collagen_type {
+            CollagenType::TypeI => Self::type_i(),
+            CollagenType::TypeII => Self::type_ii(),
+        }
+    }
+
+    fn ricard_blum_2011() -> Citation {
+        Citation::new(
+            "Ricard-Blum S (2011) Cold Spring Harb Perspect Biol 3:a004978",
+            Some("21421911"),
+            Some("10.1101/cshperspect.a004978"),
+        )
+    }
+}
+
+// Newtonian approximation at the stated shear rate; blood and synovial
+// fluid are shear-thinning, so the viscosity only holds near it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FluidProperties {
+    pub name: String,
+    pub density_kg_m3: f64,
+    pub dynamic_viscosity_pa_s: f64,
+    pub temperature_c: f64,
+    pub shear_rate_per_s: Option<f64>,
+    pub sources: Vec<Citation>,
+}
+
+impl FluidProperties {
+    // Pure water at 37 °C.
+    // Haynes WM (ed.) CRC Handbook of Chemistry and Physics, 97th ed., §6
+    pub fn water() -> Self {
+        Self {
+            name: "water".to_string(),
+            density_kg_m3: 993.3,
+            dynamic_viscosity_pa_s: 0.692e-3,
+            temperature_c: 37.0,
+            shear_rate_per_s: None,
+            sources: vec![Citation::text(
+                "Haynes WM (ed.) CRC Handbook of Chemistry and Physics, 97th ed., section 6",
+            )],
+        }
+    }
+
+    // Whole blood, haematocrit ~45%, at high shear.
+    // Baskurt OK, Meiselman HJ (2003) Semin Thromb Hemost 29:435-450, PMID 14631543
+    pub fn blood() -> Self {
+        Self {
+            name: "blood".to_string(),
+            density_kg_m3: 1060.0,
+            dynamic_viscosity_pa_s: 3.5e-3,
+            temperature_c: 37.0,
+            shear_rate_per_s: Some(200.0),
+            sources: vec![Citation::new(
+                "Baskurt OK, Meiselman HJ (2003) Semin Thromb Hemost 29:435-450",
+                Some("14631543"),
+                Some("10.1055/s-2003-44551"),
+            )],
+        }
+    }
+
+    // Healthy knee synovial fluid; hyaluronan makes it strongly
+    // shear-thinning, ~1 Pa·s near 1/s.
+    // Fam H, Bryant JT, Kontopoulou M (2007) Biorheology 44:59-74
+    pub fn synovial_fluid() -> Self {
+        Self {
+            name: "synovial_fluid".to_string(),
+            density_kg_m3: 1010.0,
+            dynamic_viscosity_pa_s: 1.0,
+            temperature_c: 37.0,
+            shear_rate_per_s: Some(1.0),
+            sources: vec![Citation::text(
+                "Fam H, Bryant JT, Kontopoulou M (2007) Biorheology 44:59-74",
+            )],
+        }
+    }
+
+    pub fn kinematic_viscosity_m2_s(&self) -> f64 {
+        self.dynamic_viscosity_pa_s / self.density_kg_m3
+    }
+
+    pub fn reynolds_number(&self, velocity_m_s: f64, length_m: f64) -> f64 {
+        velocity_m_s * length_m / self.kinematic_viscosity_m2_s()
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        require(
+            self.density_kg_m3 > 0.0 && self.dynamic_viscosity_pa_s > 0.0,
+            "density and viscosity must be positive",
+            &self.name,
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NeuronPreset {
+    pub name: String,
+    pub resting_potential_mv: f64,
+    pub threshold_mv: f64,
+    pub sources: Vec<Citation>,
+}
+
+impl NeuronPreset {
+    // Neocortical layer 5 pyramidal soma.
+    // Stuart G, Spruston N, Sakmann B, Häusser M (1997) Trends Neurosci 20:125-131
+    pub fn pyramidal() -> Self {
+        Self {
+            name: "pyramidal".to_string(),
+            resting_potential_mv: -65.0,
+            threshold_mv: -50.0,
+            sources: vec![Citation::text(
+                "Stuart G, Spruston N, Sakmann B, Häusser M (1997) Trends Neurosci 20:125-131",
+            )],
+        }
+    }
+
+    // Spinal alpha motoneuron soma.
+    // Kandel ER et al. Principles of Neural Science, 5th ed., ch. 34
+    pub fn motor() -> Self {
+        Self {
+            name: "motor".to_string(),
+            resting_potential_mv: -70.0,
+            threshold_mv: -55.0,
+            sources: vec![Citation::text(
+                "Kandel ER et al. Principles of Neural Science, 5th ed., chapter 34",
+            )],
+        }
+    }
+
+    // Membrane with the preset potentials and default channel densities.
+    pub fn membrane(&self) -> BiologyResult<ActionPotentialDynamics> {
+        NeuronBuilder::new()
+            .resting_potential(self.resting_potential_mv)
+            .threshold(self.threshold_mv)
+            .build()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaccinePreset {
+    pub name: String,
+    pub depot: AntigenDepot,
+    // Days after the first dose.
+    pub dose_days: Vec<f64>,
+    pub route: String,
+    pub sources: Vec<Citation>,
+}
+
+impl VaccinePreset {
+    // Aluminium-adjuvanted recombinant HBsAg, 0/1/6 months.
+    // Schillie S et al. (2018) MMWR Recomm Rep 67(1):1-31, PMID 29939980
+    pub fn hepatitis_b() -> Self {
+        Self {
+            name: "hepatitis_b".to_string(),
+            depot: AntigenDepot::new(DepotKind::MineralAdjuvant, 3, 1.0).expect("valid depot"),
+            dose_days: vec![0.0, 30.0, 180.0],
+            route: "intramuscular".to_string(),
+            sources: vec![Citation::new(
+                "Schillie S et al. (2018) MMWR Recomm Rep 67(1):1-31",
+                Some("29939980"),
+                Some("10.15585/mmwr.rr6701a1"),
+            )],
+        }
+    }
+
+    // Live attenuated M. bovis, single intradermal dose.
+    // WHO (2018) Wkly Epidemiol Rec 93(8):73-96
+    pub fn bcg() -> Self {
+        Self {
+            name: "bcg".to_string(),
+            depot: AntigenDepot::new(DepotKind::Mycobacterial, 3, 1.0).expect("valid depot"),
+            dose_days: vec![0.0],
+            route: "intradermal".to_string(),
+            sources: vec![Citation::text("WHO (2018) Wkly Epidemiol Rec 93(8):73-96")],
+        }
+    }
+
+    // Unadjuvanted inactivated influenza, one dose per season.
+    // Grohskopf LA et al. (2023) MMWR Recomm Rep 72(2):1-25
+    pub fn inactivated_influenza() -> Self {
+        Self {
+            name: "inactivated_influenza".to_string(),
+            depot: AntigenDepot::new(DepotKind::SolubleAntigen, 2, 1.0).expect("valid depot"),
+            dose_days: vec![0.0],
+            route: "intramuscular".to_string(),
+            sources: vec![Citation::text(
+                "Grohskopf LA et al. (2023) MMWR Recomm Rep 72(2):1-25",
+            )],
+        }
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        require(
+            !self.dose_days.is_empty(),
+            "needs at least one dose",
+            &self.name,
+        )?;
+        require(
+            self.dose_days.windows(2).all(|w| w[1] > w[0]) && self.dose_days[0] >= 0.0,
+            "dose days must be increasing from zero",
+            &self.name,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PresetCategory {
+    Bone,
+    Collagen,
+    Fluid,
+    Neuron,
+    Vaccine,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresetInfo {
+    pub key: String,
+    pub category: PresetCategory,
+    pub sources: Vec<Citation>,
+}
+
+// Every preset with its sources, keyed "category/name".
+pub fn catalog() -> Vec<PresetInfo> {
+    let entry = |category: PresetCategory, name: &str, sources: Vec<Citation>| PresetInfo {
+        key: format!("{}/{}", format!("{:?}", category).to_lowercase(), name),
+        category,
+        sources,
+    };
+    let mut entries = Vec::new();
+    for bone in [BoneMaterial::cortical(), BoneMaterial::trabecular()] {
+        entries.push(entry(PresetCategory::Bone, &bone.name, bone.sources));
+    }
+    for (name, collagen) in [
+        ("type_i", CollagenProperties::type_i()),
+        ("type_ii", CollagenProperties::type_ii()),
+    ] {
+        entries.push(entry(PresetCategory::Collagen, name, collagen.sources));
+    }
+    for fluid in [
+        FluidProperties::water(),
+        FluidProperties::blood(),
+        FluidProperties::synovial_fluid(),
+    ] {
+        entries.push(entry(PresetCategory::Fluid, &fluid.name, fluid.sources));
+    }
+    for neuron in [NeuronPreset::pyramidal(), NeuronPreset::motor()] {
+        entries.push(entry(PresetCategory::Neuron, &neuron.name, neuron.sources));
+    }
+    for vaccine in [
+        VaccinePreset::hepatitis_b(),
+        VaccinePreset::bcg(),
+        VaccinePreset::inactivated_influenza(),
+    ] {
+        entries.push(entry(
+            PresetCategory::Vaccine,
+            &vaccine.name,
+            vaccine.sources,
+        ));
+    }
+    entries
+}
+
+pub fn sources(key: &str) -> Option<Vec<Citation>> {
+    catalog()
+        .into_iter()
+        .find(|entry| entry.key == key)
+        .map(|entry| entry.sources)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_preset_validates_and_is_cited() {
+        for bone in [BoneMaterial::cortical(), BoneMaterial::trabecular()] {
+            bone.validate().unwrap();
+        }
+        for fluid in [
+            FluidProperties::water(),
+            FluidProperties::blood(),
+            FluidProperties::synovial_fluid(),
+        ] {
+            fluid.validate().unwrap();
+        }
+        for neuron in [NeuronPreset::pyramidal(), NeuronPreset::motor()] {
+            let membrane = neuron.membrane().unwrap();
+            assert!(membrane.is_at_rest());
+        }
+        for vaccine in [
+            VaccinePreset::hepatitis_b(),
+            VaccinePreset::bcg(),
+            VaccinePreset::inactivated_influenza(),
+        ] {
+            vaccine.validate().unwrap();
+        }
+        let catalog = catalog();
+        assert_eq!(catalog.len(), 12);
+        assert!(catalog.iter().all(|entry| !entry.sources.is_empty()));
+    }
+
+    #[test]
+    fn test_sources_retrievable_by_key() {
+        let cortical = sources("bone/cortical_bone").unwrap();
+        assert_eq!(cortical[0].pmid.as_deref(), Some("1206042"));
+        assert!(sources("collagen/type_ii").unwrap()[0]
+            .citation
+            .contains("Ricard-Blum"));
+        assert!(sources("bone/marrow").is_none());
+    }
+
+    #[test]
+    fn test_relative_magnitudes() {
+        let cortical = BoneMaterial::cortical();
+        let trabecular = BoneMaterial::trabecular();
+        assert!(cortical.youngs_modulus_gpa > 20.0 * trabecular.youngs_modulus_gpa);
+        assert!(
+            CollagenProperties::type_i().typical_fibril_diameter_nm
+                > CollagenProperties::type_ii().typical_fibril_diameter_nm
+        );
+        let blood = FluidProperties::blood();
+        let water = FluidProperties::water();
+        assert!(blood.dynamic_viscosity_pa_s > 4.0 * water.dynamic_viscosity_pa_s);
+        // Aortic flow is laminar-to-transitional: Re of a few thousand.
+        let reynolds = blood.reynolds_number(0.3, 0.025);
+        assert!(reynolds > 1000.0 && reynolds < 4000.0);
+        let mut invalid = FluidProperties::water();
+        invalid.density_kg_m3 = 0.0;
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,368 @@
+//! Shared 3D vector type and SIMD batch kernels.
+//!
+//! [`Vec3`] is the single point/vector type for spatial models. Bulk work
+//! over many points goes through [`Vec3Batch`], which stores coordinates
+//! structure-of-arrays so four lanes of each axis load into one
+//! `f64x4` register.
+
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use wide::f64x4;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3::new(0.0, 0.0, 0.0);
+
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn dot(self, other: Vec3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn norm_squared(self) -> f64 {
+        self.dot(self)
+    }
+
+    pub fn norm(self) -> f64 {
+        self.norm_squared().sqrt()
+    }
+
+    pub fn distance(self, other: Vec3) -> f64 {
+        (self - other).norm()
+    }
+
+    // Zero stays zero rather than becoming NaN.
+    pub fn normalized(self) -> Vec3 {
+        let norm = self.norm();
+        if norm > 0.0 {
+            self * (1.0 / norm)
+        } else {
+            self
+        }
+    }
+}
+
+impl From<[f64; 3]> for Vec3 {
+    fn from([x, y, z]: [f64; 3]) -> Self {
+        Vec3::new(x, y, z)
+    }
+}
+
+impl From<Vec3> for [f64; 3] {
+    fn from(v: Vec3) -> Self {
+        [v.x, v.y, v.z]
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, s: f64) -> Vec3 {
+        Vec3::new(self.x * s, self.y * s, self.z * s)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, other: Vec3) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vec3 {
+    fn sub_assign(&mut self, other: Vec3) {
+        *self = *self - other;
+    }
+}
+
+const LANES: usize = 4;
+
+fn load(values: &[f64]) -> f64x4 {
+    f64x4::new([values[0], values[1], values[2], values[3]])
+}
+
+fn store(v: f64x4, out: &mut [f64]) {
+    out[..LANES].copy_from_slice(&v.to_array());
+}
+
+// Structure-of-arrays storage for many points. Operations run four
+// points at a time in SIMD lanes with a scalar tail, and give the same
+// results as the per-point `Vec3` methods.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vec3Batch {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub z: Vec<f64>,
+}
+
+impl Vec3Batch {
+    pub fn with_len(len: usize) -> Self {
+        Self {
+            x: vec![0.0; len],
+            y: vec![0.0; len],
+            z: vec![0.0; len],
+        }
+    }
+
+    // AoS -> SoA.
+    pub fn from_points(points: &[Vec3]) -> Self {
+        Self {
+            x: points.iter().map(|p| p.x).collect(),
+            y: points.iter().map(|p| p.y).collect(),
+            z: points.iter().map(|p| p.z).collect(),
+        }
+    }
+
+    // SoA -> AoS.
+    pub fn to_points(&self) -> Vec<Vec3> {
+        (0..self.len()).map(|i| self.get(i)).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    pub fn get(&self, i: usize) -> Vec3 {
+        Vec3::new(self.x[i], self.y[i], self.z[i])
+    }
+
+    pub fn set(&mut self, i: usize, v: Vec3) {
+        self.x[i] = v.x;
+        self.y[i] = v.y;
+        self.z[i] = v.z;
+    }
+
+    pub fn push(&mut self, v: Vec3) {
+        self.x.push(v.x);
+        self.y.push(v.y);
+        self.z.push(v.z);
+    }
+
+    fn axes_mut(&mut self) -> [&mut Vec<f64>; 3] {
+        [&mut self.x, &mut self.y, &mut self.z]
+    }
+
+    fn axes(&self) -> [&Vec<f64>; 3] {
+        [&self.x, &self.y, &self.z]
+    }
+
+    // self += a * other, e.g. positions += dt * velocities.
+    pub fn axpy(&mut self, a: f64, other: &Vec3Batch) {
+        assert_eq!(self.len(), other.len(), "batch lengths differ");
+        let n = self.len();
+        let split = n - n % LANES;
+        let scale = f64x4::splat(a);
+        for (axis, source) in self.axes_mut().into_iter().zip(other.axes()) {
+            for i in (0..split).step_by(LANES) {
+                let updated = load(&axis[i..]) + scale * load(&source[i..]);
+                store(updated, &mut axis[i..]);
+            }
+            for i in split..n {
+                axis[i] += a * source[i];
+            }
+        }
+    }
+
+    pub fn translate(&mut self, offset: Vec3) {
+        let offsets = [offset.x, offset.y, offset.z];
+        for (axis, d) in self.axes_mut().into_iter().zip(offsets) {
+            let n = axis.len();
+            let split = n - n % LANES;
+            let shift = f64x4::splat(d);
+            for i in (0..split).step_by(LANES) {
+                let moved = load(&axis[i..]) + shift;
+                store(moved, &mut axis[i..]);
+            }
+            for value in &mut axis[split..] {
+                *value += d;
+            }
+        }
+    }
+
+    pub fn scale(&mut self, s: f64) {
+        let factor = f64x4::splat(s);
+        for axis in self.axes_mut() {
+            let n = axis.len();
+            let split = n - n % LANES;
+            for i in (0..split).step_by(LANES) {
+                let scaled = load(&axis[i..]) * factor;
+                store(scaled, &mut axis[i..]);
+            }
+            for value in &mut axis[split..] {
+                *value *= s;
+            }
+        }
+    }
+
+    // Per-point dot products with `other`.
+    pub fn dots(&self, other: &Vec3Batch) -> Vec<f64> {
+        assert_eq!(self.len(), other.len(), "batch lengths differ");
+        let n = self.len();
+        let split = n - n % LANES;
+        let mut out = vec![0.0; n];
+        for i in (0..split).step_by(LANES) {
+            let dot = load(&self.x[i..]) * load(&other.x[i..])
+                + load(&self.y[i..]) * load(&other.y[i..])
+                + load(&self.z[i..]) * load(&other.z[i..]);
+            store(dot, &mut out[i..]);
+        }
+        for (i, value) in out.iter_mut().enumerate().skip(split) {
+            *value = self.get(i).dot(other.get(i));
+        }
+        out
+    }
+
+    pub fn norms(&self) -> Vec<f64> {
+        self.dots(self).into_iter().map(f64::sqrt).collect()
+    }
+
+    // Distance from every point to `target`.
+    pub fn distances_to(&self, target: Vec3) -> Vec<f64> {
+        let n = self.len();
+        let split = n - n % LANES;
+        let (tx, ty, tz) = (
+            f64x4::splat(target.x),
+            f64x4::splat(target.y),
+            f64x4::splat(target.z),
+        );
+        let mut out = vec![0.0; n];
+        for i in (0..split).step_by(LANES) {
+            let dx = load(&self.x[i..]) - tx;
+            let dy = load(&self.y[i..]) - ty;
+            let dz = load(&self.z[i..]) - tz;
+            store((dx * dx + dy * dy + dz * dz).sqrt(), &mut out[i..]);
+        }
+        for (i, value) in out.iter_mut().enumerate().skip(split) {
+            *value = self.get(i).distance(target);
+        }
+        out
+    }
+
+    pub fn centroid(&self) -> Option<Vec3> {
+        if self.is_empty() {
+            return None;
+        }
+        let n = self.len() as f64;
+        let [x, y, z] = self.axes().map(|axis| axis.iter().sum::<f64>() / n);
+        Some(Vec3::new(x, y, z))
+    }
+}
+
+impl FromIterator<Vec3> for Vec3Batch {
+    fn from_iter<I: IntoIterator<Item = Vec3>>(iter: I) -> Self {
+        let mut batch = Vec3Batch::default();
+        for v in iter {
+            batch.push(v);
+        }
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(n: usize) -> Vec<Vec3> {
+        (0..n)
+            .map(|i| {
+                let t = i as f64;
+                Vec3::new(t.sin() * 3.0, t.cos() - 0.5, 0.25 * t)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_vector_algebra() {
+        let a = Vec3::new(1.0, 0.0, 0.0);
+        let b = Vec3::new(0.0, 1.0, 0.0);
+        assert_eq!(a.cross(b), Vec3::new(0.0, 0.0, 1.0));
+        assert_eq!(a.dot(b), 0.0);
+        assert_eq!((a + b).norm_squared(), 2.0);
+        assert_eq!(Vec3::new(3.0, 4.0, 0.0).distance(Vec3::ZERO), 5.0);
+        assert_eq!(Vec3::ZERO.normalized(), Vec3::ZERO);
+        assert!((Vec3::new(2.0, 2.0, 1.0).normalized().norm() - 1.0).abs() < 1e-15);
+        let array: [f64; 3] = Vec3::from([1.0, 2.0, 3.0]).into();
+        assert_eq!(array, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_batch_matches_scalar_including_tail() {
+        for n in [0, 3, 4, 11] {
+            let p = points(n);
+            let v: Vec<Vec3> = p
+                .iter()
+                .map(|q| q.cross(Vec3::new(0.1, 0.2, 0.3)))
+                .collect();
+            let mut batch = Vec3Batch::from_points(&p);
+            let velocities = Vec3Batch::from_points(&v);
+            assert_eq!(batch.to_points(), p);
+
+            let target = Vec3::new(0.5, -1.0, 2.0);
+            let distances = batch.distances_to(target);
+            let dots = batch.dots(&velocities);
+            for i in 0..n {
+                assert!((distances[i] - p[i].distance(target)).abs() < 1e-12);
+                assert!((dots[i] - p[i].dot(v[i])).abs() < 1e-12);
+            }
+
+            batch.axpy(0.5, &velocities);
+            batch.translate(target);
+            for i in 0..n {
+                let expected = p[i] + v[i] * 0.5 + target;
+                assert!((batch.get(i) - expected).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_scale_and_centroid() {
+        let mut batch: Vec3Batch = points(9).into_iter().collect();
+        let centroid = batch.centroid().unwrap();
+        batch.scale(2.0);
+        assert!((batch.centroid().unwrap() - centroid * 2.0).norm() < 1e-12);
+        assert!(Vec3Batch::with_len(0).centroid().is_none());
+    }
+}
@@ -0,0 +1,536 @@
+use crate::biology::endocrine::EndocrineSignals;
+use crate::biology::signaling::{TgfBetaSignaling, WntSignaling};
+use crate::biology::{BiologyError, BiologyResult};
+use crate::metabolism::reaction_network::ReactionNetwork;
+use crate::simulation::engine::Engine;
+use crate::simulation::physiome::{Physiome, TransportKind};
+use crate::simulation::scenario::{build_component, MODELS};
+use crate::systems::skeletal::remodeling::RemodelingModifiers;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+// Mermaid flowcharts and Graphviz DOT generated from the live model
+// objects, so documentation diagrams are rebuilt rather than redrawn.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NodeShape {
+    Box,
+    Rounded,
+    Circle,
+    Stadium,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EdgeStyle {
+    Arrow,
+    // Blunt-ended arrow of signalling diagrams.
+    Inhibition,
+    Dashed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagramNode {
+    pub id: String,
+    pub label: String,
+    pub shape: NodeShape,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagramEdge {
+    pub from: String,
+    pub to: String,
+    pub label: Option<String>,
+    pub style: EdgeStyle,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagram {
+    pub title: String,
+    pub left_to_right: bool,
+    pub nodes: Vec<DiagramNode>,
+    pub edges: Vec<DiagramEdge>,
+}
+
+impl Diagram {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            left_to_right: true,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    // Ids are sanitised to [A-Za-z0-9_] so both formats accept them;
+    // adding an id twice keeps the first label.
+    pub fn add_node(&mut self, id: &str, label: &str, shape: NodeShape) -> String {
+        let id = sanitize(id);
+        if !self.nodes.iter().any(|n| n.id == id) {
+            self.nodes.push(DiagramNode {
+                id: id.clone(),
+                label: label.to_string(),
+                shape,
+            });
+        }
+        id
+    }
+
+    pub fn add_edge(
+        &mut self,
+        from: &str,
+        to: &str,
+        label: Option<&str>,
+        style: EdgeStyle,
+    ) -> BiologyResult<()> {
+        let (from, to) = (sanitize(from), sanitize(to));
+        for id in [&from, &to] {
+            if !self.nodes.iter().any(|n| &n.id == id) {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "edge references unknown node {}",
+                    id
+                )));
+            }
+        }
+        self.edges.push(DiagramEdge {
+            from,
+            to,
+            label: label.map(str::to_string),
+            style,
+        });
+        Ok(())
+    }
+
+    pub fn to_mermaid(&self) -> String {
+        let mut out = format!(
+            "---\ntitle: {}\n---\nflowchart {}\n",
+            self.title.replace('\n', " "),
+            if self.left_to_right { "LR" } else { "TB" }
+        );
+        for node in &self.nodes {
+            let label = mermaid_escape(&node.label);
+            let (open, close) = match node.shape {
+                NodeShape::Box => ("[", "]"),
+                NodeShape::Rounded => ("(", ")"),
+                NodeShape::Circle => ("((", "))"),
+                NodeShape::Stadium => ("([", "])"),
+            };
+            let _ = writeln!(out, "    {}{}\"{}\"{}", node.id, open, label, close);
+        }
+        for edge in &self.edges {
+            let arrow = match edge.style {
+                EdgeStyle::Arrow => "-->",
+                EdgeStyle::Inhibition => "--x",
+                EdgeStyle::Dashed => "-.->",
+            };
+            match &edge.label {
+                Some(label) => {
+                    let _ = writeln!(
+                        out,
+                        "    {} {}|\"{}\"| {}",
+                        edge.from,
+                        arrow,
+                        mermaid_escape(label),
+                        edge.to
+                    );
+                }
+                None => {
+                    let _ = writeln!(out, "    {} {} {}", edge.from, arrow, edge.to);
+                }
+            }
+        }
+        out
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut out = format!(
+            "digraph \"{}\" {{\n    rankdir={};\n",
+            dot_escape(&self.title),
+            if self.left_to_right { "LR" } else { "TB" }
+        );
+        for node in &self.nodes {
+            let shape = match node.shape {
+                NodeShape::Box => "box",
+                NodeShape::Rounded => "box, style=rounded",
+                NodeShape::Circle => "circle",
+                NodeShape::Stadium => "ellipse",
+            };
+            let _ = writeln!(
+                out,
+                "    {} [label=\"{}\", shape={}];",
+                node.id,
+                dot_escape(&node.label),
+                shape
+            );
+        }
+        for edge in &self.edges {
+            let mut attributes = Vec::new();
+            if let Some(label) = &edge.label {
+                attributes.push(format!("label=\"{}\"", dot_escape(label)));
+            }
+            match edge.style {
+                EdgeStyle::Arrow => {}
+                EdgeStyle::Inhibition => attributes.push("arrowhead=tee".to_string()),
+                EdgeStyle::Dashed => attributes.push("style=dashed".to_string()),
+            }
+            let _ = if attributes.is_empty() {
+                writeln!(out, "    {} -> {};", edge.from, edge.to)
+            } else {
+                writeln!(
+                    out,
+                    "    {} -> {} [{}];",
+                    edge.from,
+                    edge.to,
+                    attributes.join(", ")
+                )
+            };
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn write_mermaid<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_mermaid())
+    }
+
+    pub fn write_dot<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_dot())
+    }
+}
+
+fn sanitize(id: &str) -> String {
+    let mut out: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    // Mermaid reserves `end`, and neither format allows a leading digit.
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) || out == "end" {
+        out.insert(0, 'n');
+    }
+    out
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', "<br/>")
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn stoichiometry_label(coefficient: f64) -> Option<String> {
+    (coefficient != 1.0).then(|| format!("{}", coefficient))
+}
+
+// Bipartite species/reaction graph; coefficients other than one label
+// their edges.
+impl From<&ReactionNetwork> for Diagram {
+    fn from(network: &ReactionNetwork) -> Self {
+        let mut diagram = Diagram::new("Reaction network");
+        let species = |diagram: &mut Diagram, id| {
+            let name = network.molecules.name(id).unwrap_or("?");
+            diagram.add_node(&format!("s_{}", name), name, NodeShape::Rounded)
+        };
+        for (i, reaction) in network.reactions.iter().enumerate() {
+            let node = diagram.add_node(&format!("r{}", i), &reaction.name, NodeShape::Box);
+            for &(id, coefficient) in &reaction.reactants {
+                let s = species(&mut diagram, id);
+                let label = stoichiometry_label(coefficient);
+                diagram
+                    .add_edge(&s, &node, label.as_deref(), EdgeStyle::Arrow)
+                    .expect("nodes were just added");
+            }
+            for &(id, coefficient) in &reaction.products {
+                let s = species(&mut diagram, id);
+                let label = stoichiometry_label(coefficient);
+                diagram
+                    .add_edge(&node, &s, label.as_deref(), EdgeStyle::Arrow)
+                    .expect("nodes were just added");
+            }
+        }
+        diagram
+    }
+}
+
+// Entities labelled with their model kind, joined by their couplings.
+impl From<&Engine> for Diagram {
+    fn from(engine: &Engine) -> Self {
+        let mut diagram = Diagram::new("Engine wiring");
+        let mut names: Vec<&str> = engine.entity_names().collect();
+        names.sort_unstable();
+        for name in names {
+            let kind = engine.entity(name).map_or("?", |c| c.kind());
+            diagram.add_node(name, &format!("{}\n{}", name, kind), NodeShape::Box);
+        }
+        for coupling in engine.couplings() {
+            let (Some((from, out)), Some((to, input))) =
+                (coupling.from.split_once('.'), coupling.to.split_once('.'))
+            else {
+                continue;
+            };
+            let mut label = format!("{} → {}", out, input);
+            if coupling.gain != 1.0 {
+                let _ = write!(label, " ×{}", coupling.gain);
+            }
+            let _ = diagram.add_edge(from, to, Some(&label), EdgeStyle::Arrow);
+        }
+        diagram
+    }
+}
+
+// Compartments and their solute routes; diffusive exchange is dashed.
+impl From<&Physiome> for Diagram {
+    fn from(physiome: &Physiome) -> Self {
+        let mut diagram = Diagram::new("Physiome compartments");
+        for name in physiome.compartment_names() {
+            diagram.add_node(name, name, NodeShape::Stadium);
+        }
+        for route in physiome.routes() {
+            let style = match route.kind {
+                TransportKind::Diffusive { .. } => EdgeStyle::Dashed,
+                TransportKind::Bulk { .. } => EdgeStyle::Arrow,
+            };
+            let _ = diagram.add_edge(&route.from, &route.to, Some(&route.solute), style);
+        }
+        diagram
+    }
+}
+
+// The scenario model registry: each model kind with its presets and the
+// ports it exposes to the engine.
+pub fn model_ontology() -> BiologyResult<Diagram> {
+    let mut diagram = Diagram::new("Model ontology");
+    let root = diagram.add_node("models", "Scenario models", NodeShape::Circle);
+    for (kind, presets) in MODELS {
+        let component = build_component(kind, None)?;
+        let model = diagram.add_node(kind, kind, NodeShape::Box);
+        diagram.add_edge(&root, &model, None, EdgeStyle::Arrow)?;
+        for preset in presets {
+            let id = diagram.add_node(
+                &format!("{}_preset_{}", kind, preset),
+                &format!("preset: {}", preset),
+                NodeShape::Stadium,
+            );
+            diagram.add_edge(&model, &id, None, EdgeStyle::Dashed)?;
+        }
+        for (port, unit) in component.inputs() {
+            let id = diagram.add_node(
+                &format!("{}_in_{}", kind, port),
+                &format!("{} [{}]", port, unit),
+                NodeShape::Rounded,
+            );
+            diagram.add_edge(&id, &model, Some("input"), EdgeStyle::Arrow)?;
+        }
+        for (port, unit) in component.outputs() {
+            let id = diagram.add_node(
+                &format!("{}_out_{}", kind, port),
+                &format!("{} [{}]", port, unit),
+                NodeShape::Rounded,
+            );
+            diagram.add_edge(&model, &id, Some("output"), EdgeStyle::Arrow)?;
+        }
+    }
+    Ok(diagram)
+}
+
+// Sign of each link is read by perturbing the models themselves: an edge
+// activates if raising its source raises its target, and inhibits if it
+// lowers it, so the diagram follows the code when the models change.
+pub fn bone_signaling_pathway() -> BiologyResult<Diagram> {
+    let mut diagram = Diagram::new("Bone remodeling signaling");
+    diagram.left_to_right = false;
+    for (id, label) in [
+        ("strain", "Mechanical strain"),
+        ("sclerostin", "Sclerostin (osteocyte)"),
+        ("beta_catenin", "β-catenin (osteoblast)"),
+        ("tgf_beta", "Matrix TGF-β"),
+        ("smad23", "SMAD2/3"),
+        ("recruitment", "Osteoblast recruitment"),
+        ("pth", "PTH"),
+        ("cortisol", "Cortisol"),
+        ("activation", "BMU activation frequency"),
+        ("formation", "Osteoblast formation"),
+    ] {
+        diagram.add_node(id, label, NodeShape::Box);
+    }
+
+    let mut link = |from: &str, to: &str, delta: f64| -> BiologyResult<()> {
+        if delta.abs() < 1e-12 {
+            return Ok(());
+        }
+        let style = if delta > 0.0 {
+            EdgeStyle::Arrow
+        } else {
+            EdgeStyle::Inhibition
+        };
+        diagram.add_edge(from, to, None, style)
+    };
+
+    let baseline = WntSignaling::new();
+    let mut loaded = baseline.clone();
+    loaded.set_mechanical_strain(baseline.strain_microstrain * 1.5)?;
+    link(
+        "strain",
+        "sclerostin",
+        loaded.sclerostin_expression() - baseline.sclerostin_expression(),
+    )?;
+    let mut neutralised = baseline.clone();
+    neutralised.set_sclerostin_neutralization(0.5)?;
+    // Neutralisation lowers free sclerostin, so the response is reversed.
+    let d_sclerostin = neutralised.free_sclerostin() - baseline.free_sclerostin();
+    let d_beta = neutralised.beta_catenin_activity() - baseline.beta_catenin_activity();
+    link("sclerostin", "beta_catenin", d_beta / d_sclerostin)?;
+    let (before, after) = (
+        RemodelingModifiers::from_wnt(&baseline),
+        RemodelingModifiers::from_wnt(&neutralised),
+    );
+    link(
+        "beta_catenin",
+        "formation",
+        after.formation_factor - before.formation_factor,
+    )?;
+    link(
+        "beta_catenin",
+        "activation",
+        after.activation_frequency_factor - before.activation_frequency_factor,
+    )?;
+
+    let matrix = TgfBetaSignaling::new();
+    let rich = TgfBetaSignaling::new().with_matrix_content(1.5, 1.0)?;
+    link(
+        "tgf_beta",
+        "smad23",
+        rich.smad23_activity() - matrix.smad23_activity(),
+    )?;
+    link(
+        "smad23",
+        "recruitment",
+        rich.osteoblast_recruitment() - matrix.osteoblast_recruitment(),
+    )?;
+    link(
+        "recruitment",
+        "formation",
+        rich.collagen_synthesis_fold() - matrix.collagen_synthesis_fold(),
+    )?;
+
+    let reference = EndocrineSignals::reference_adult();
+    let base = RemodelingModifiers::from_endocrine(&reference);
+    let high_pth = RemodelingModifiers::from_endocrine(&EndocrineSignals {
+        pth_pg_ml: reference.pth_pg_ml * 2.0,
+        ..reference
+    });
+    link(
+        "pth",
+        "activation",
+        high_pth.activation_frequency_factor - base.activation_frequency_factor,
+    )?;
+    let high_cortisol = RemodelingModifiers::from_endocrine(&EndocrineSignals {
+        cortisol_ug_dl: reference.cortisol_ug_dl * 3.0,
+        ..reference
+    });
+    link(
+        "cortisol",
+        "formation",
+        high_cortisol.formation_factor - base.formation_factor,
+    )?;
+    Ok(diagram)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::engine::Coupling;
+    use crate::simulation::physiome::Physiome;
+
+    #[test]
+    fn test_reaction_network_renders_in_both_formats() {
+        let mut network = ReactionNetwork::new();
+        let a = network.add_species("glucose", 5.0);
+        let b = network.add_species("pyruvate", 0.0);
+        let reaction = crate::metabolism::reaction_network::Reaction::builder("glycolysis")
+            .reactant(a, 1.0)
+            .product(b, 2.0)
+            .mass_action(0.1)
+            .build()
+            .unwrap();
+        network.add_reaction(reaction).unwrap();
+        let diagram = Diagram::from(&network);
+        assert_eq!(diagram.nodes.len(), 3);
+        let mermaid = diagram.to_mermaid();
+        assert!(mermaid.contains("flowchart LR"));
+        assert!(mermaid.contains("r0 -->|\"2\"| s_pyruvate"));
+        let dot = diagram.to_dot();
+        assert!(dot.starts_with("digraph \"Reaction network\""));
+        assert!(dot.contains("s_glucose -> r0;"));
+    }
+
+    #[test]
+    fn test_engine_and_physiome_graphs_follow_the_models() {
+        let mut engine = Engine::new(1.0).unwrap();
+        engine
+            .add_entity(
+                "lungs",
+                build_component("respiratory_control", None).unwrap(),
+            )
+            .unwrap();
+        engine
+            .add_entity("body", build_component("thermoregulation", None).unwrap())
+            .unwrap();
+        let output = engine.entity("lungs").unwrap().outputs()[0].0;
+        let input = engine.entity("body").unwrap().inputs()[0].0;
+        engine
+            .couple(Coupling::new(
+                &format!("lungs.{}", output),
+                &format!("body.{}", input),
+            ))
+            .unwrap();
+        let diagram = Diagram::from(&engine);
+        assert_eq!(diagram.edges.len(), 1);
+        assert_eq!(diagram.edges[0].from, "lungs");
+        let physiome = Diagram::from(&Physiome::body_fluids());
+        assert!(physiome.edges.iter().any(|e| e.style == EdgeStyle::Dashed));
+        assert!(physiome.to_dot().contains("style=dashed"));
+    }
+
+    #[test]
+    fn test_model_ontology_covers_registry() {
+        let diagram = model_ontology().unwrap();
+        for (kind, presets) in MODELS {
+            assert!(diagram.nodes.iter().any(|n| n.id == kind));
+            assert!(presets.iter().all(|p| diagram
+                .nodes
+                .iter()
+                .any(|n| n.label == format!("preset: {}", p))));
+        }
+        assert!(diagram.to_mermaid().contains("bioreactor -->|\"output\"|"));
+    }
+
+    #[test]
+    fn test_signaling_signs_come_from_the_models() {
+        let diagram = bone_signaling_pathway().unwrap();
+        let style = |from: &str, to: &str| {
+            diagram
+                .edges
+                .iter()
+                .find(|e| e.from == from && e.to == to)
+                .map(|e| e.style)
+        };
+        assert_eq!(style("strain", "sclerostin"), Some(EdgeStyle::Inhibition));
+        assert_eq!(
+            style("sclerostin", "beta_catenin"),
+            Some(EdgeStyle::Inhibition)
+        );
+        assert_eq!(style("beta_catenin", "formation"), Some(EdgeStyle::Arrow));
+        assert_eq!(
+            style("beta_catenin", "activation"),
+            Some(EdgeStyle::Inhibition)
+        );
+        assert_eq!(style("pth", "activation"), Some(EdgeStyle::Arrow));
+        assert_eq!(style("cortisol", "formation"), Some(EdgeStyle::Inhibition));
+        assert!(diagram.to_mermaid().contains("flowchart TB"));
+        assert!(diagram.to_dot().contains("arrowhead=tee"));
+    }
+}
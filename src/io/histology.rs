@@ -0,0 +1,604 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::io::microct::Segmentation;
+use crate::systems::skeletal::nanoindentation::MineralizationField;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+// Synthetic histology for teaching: tissue models are rasterised to a
+// label map, dressed with cells, and coloured by a stain. Images export as
+// PNG (stored deflate, no compression library) or SVG.
+
+const OSTEOCYTE_SPACING_UM: f64 = 40.0;
+const LACUNA_RADIUS_UM: f64 = 4.0;
+const NUCLEUS_RADIUS_UM: f64 = 2.0;
+const MARROW_CELL_SPACING_UM: f64 = 12.0;
+// A cement line is where mineral content jumps between neighbouring
+// pixels, as at the reversal line around a secondary osteon.
+const CEMENT_LINE_MINERAL_STEP: f64 = 0.02;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TissueLabel {
+    Background,
+    Marrow,
+    Bone { mineral_fraction: f64 },
+    Osteoid,
+    CementLine,
+    Canal,
+    Cartilage { hypertrophic: bool, calcified: bool },
+    Lacuna,
+    Nucleus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stain {
+    HematoxylinEosin,
+    // Masson-Goldner: mineralised bone green, osteoid red.
+    MassonGoldner,
+    // Mineral black on a van Gieson counterstain.
+    VonKossa,
+    // Proteoglycan-rich cartilage orange-red on fast green.
+    SafraninO,
+}
+
+impl Stain {
+    pub fn color(&self, label: TissueLabel) -> [u8; 3] {
+        use TissueLabel::*;
+        match (self, label) {
+            (_, Background) | (_, Canal) | (_, Lacuna) => [246, 242, 246],
+            (Stain::HematoxylinEosin, Bone { mineral_fraction }) => {
+                shade([236, 160, 190], [214, 110, 160], mineral_fraction / 0.5)
+            }
+            (Stain::HematoxylinEosin, Osteoid) => [240, 190, 210],
+            (Stain::HematoxylinEosin, CementLine) => [120, 80, 160],
+            (Stain::HematoxylinEosin, Marrow) => [242, 208, 222],
+            (Stain::HematoxylinEosin, Cartilage { calcified, .. }) => {
+                if calcified {
+                    [150, 120, 190]
+                } else {
+                    [196, 178, 220]
+                }
+            }
+            (Stain::HematoxylinEosin, Nucleus) => [72, 42, 124],
+            (Stain::MassonGoldner, Bone { .. }) => [72, 150, 96],
+            (Stain::MassonGoldner, Osteoid) => [206, 64, 64],
+            (Stain::MassonGoldner, CementLine) => [40, 100, 70],
+            (Stain::MassonGoldner, Marrow) => [226, 160, 150],
+            (Stain::MassonGoldner, Cartilage { .. }) => [190, 214, 196],
+            (Stain::MassonGoldner, Nucleus) => [52, 32, 32],
+            (Stain::VonKossa, Bone { mineral_fraction }) => {
+                shade([90, 80, 80], [20, 20, 20], mineral_fraction / 0.5)
+            }
+            (
+                Stain::VonKossa,
+                Cartilage {
+                    calcified: true, ..
+                },
+            ) => [60, 55, 55],
+            (Stain::VonKossa, CementLine) => [10, 10, 10],
+            (Stain::VonKossa, Osteoid) => [222, 120, 120],
+            (Stain::VonKossa, Marrow) | (Stain::VonKossa, Cartilage { .. }) => [236, 196, 190],
+            (Stain::VonKossa, Nucleus) => [150, 110, 110],
+            (Stain::SafraninO, Cartilage { hypertrophic, .. }) => {
+                if hypertrophic {
+                    [226, 110, 80]
+                } else {
+                    [214, 56, 40]
+                }
+            }
+            (Stain::SafraninO, Bone { .. }) | (Stain::SafraninO, CementLine) => [96, 164, 118],
+            (Stain::SafraninO, Osteoid) => [140, 190, 150],
+            (Stain::SafraninO, Marrow) => [214, 226, 214],
+            (Stain::SafraninO, Nucleus) => [30, 30, 40],
+        }
+    }
+}
+
+fn shade(light: [u8; 3], dark: [u8; 3], t: f64) -> [u8; 3] {
+    let t = t.clamp(0.0, 1.0);
+    [0, 1, 2].map(|i| (light[i] as f64 + (dark[i] as f64 - light[i] as f64) * t).round() as u8)
+}
+
+// Rows run top to bottom, pixels left to right.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TissueSection {
+    pub width: usize,
+    pub height: usize,
+    pub pixel_size_um: f64,
+    pub labels: Vec<TissueLabel>,
+}
+
+impl TissueSection {
+    pub fn new(width: usize, height: usize, pixel_size_um: f64) -> BiologyResult<Self> {
+        if width == 0 || height == 0 || pixel_size_um.is_nan() || pixel_size_um <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "section needs positive dimensions and pixel size".to_string(),
+            ));
+        }
+        Ok(Self {
+            width,
+            height,
+            pixel_size_um,
+            labels: vec![TissueLabel::Background; width * height],
+        })
+    }
+
+    pub fn label(&self, x: usize, y: usize) -> TissueLabel {
+        self.labels[y * self.width + x]
+    }
+
+    pub fn fraction(&self, test: impl Fn(TissueLabel) -> bool) -> f64 {
+        self.labels.iter().filter(|&&l| test(l)).count() as f64 / self.labels.len() as f64
+    }
+
+    // Cortical bone from a mineralisation map: pores become Haversian
+    // canals lined by osteoid, mineral steps become cement lines, and
+    // osteocytes sit in lacunae through the matrix.
+    pub fn from_mineralization<R: Rng>(
+        field: &MineralizationField,
+        pixel_size_um: f64,
+        rng: &mut R,
+    ) -> BiologyResult<Self> {
+        let width = (field.width as f64 * field.spacing_um / pixel_size_um).round() as usize;
+        let height = (field.height as f64 * field.spacing_um / pixel_size_um).round() as usize;
+        let mut section = Self::new(width, height, pixel_size_um)?;
+        let mineral = |x: usize, y: usize| -> f64 {
+            let fx = ((x as f64 + 0.5) * pixel_size_um / field.spacing_um) as usize;
+            let fy = ((y as f64 + 0.5) * pixel_size_um / field.spacing_um) as usize;
+            field.mineral_volume_fraction
+                [fy.min(field.height - 1) * field.width + fx.min(field.width - 1)]
+        };
+        for y in 0..height {
+            for x in 0..width {
+                let here = mineral(x, y);
+                let neighbours = [
+                    (x.wrapping_sub(1), y),
+                    (x + 1, y),
+                    (x, y.wrapping_sub(1)),
+                    (x, y + 1),
+                ];
+                let mut label = if here.is_nan() {
+                    TissueLabel::Canal
+                } else {
+                    TissueLabel::Bone {
+                        mineral_fraction: here,
+                    }
+                };
+                for (nx, ny) in neighbours {
+                    if nx >= width || ny >= height || here.is_nan() {
+                        continue;
+                    }
+                    let other = mineral(nx, ny);
+                    if other.is_nan() {
+                        label = TissueLabel::Osteoid;
+                        break;
+                    }
+                    if other - here > CEMENT_LINE_MINERAL_STEP {
+                        label = TissueLabel::CementLine;
+                    }
+                }
+                section.labels[y * width + x] = label;
+            }
+        }
+        section.scatter_cells(OSTEOCYTE_SPACING_UM, LACUNA_RADIUS_UM, rng, |l| {
+            matches!(l, TissueLabel::Bone { .. })
+        });
+        Ok(section)
+    }
+
+    // Trabeculae from one micro-CT slice; marrow is packed with
+    // haematopoietic cells.
+    pub fn from_segmentation_slice<R: Rng>(
+        segmentation: &Segmentation,
+        z: usize,
+        pixel_size_um: f64,
+        rng: &mut R,
+    ) -> BiologyResult<Self> {
+        let [nx, ny, nz] = segmentation.dimensions;
+        if z >= nz {
+            return Err(BiologyError::InvalidValue(format!(
+                "slice {} outside volume of depth {}",
+                z, nz
+            )));
+        }
+        let scale = segmentation.voxel_size_um / pixel_size_um;
+        let width = (nx as f64 * scale).round() as usize;
+        let height = (ny as f64 * scale).round() as usize;
+        let mut section = Self::new(width, height, pixel_size_um)?;
+        for y in 0..height {
+            for x in 0..width {
+                let vx = ((x as f64 + 0.5) / scale) as usize;
+                let vy = ((y as f64 + 0.5) / scale) as usize;
+                section.labels[y * width + x] =
+                    if segmentation.is_bone(vx.min(nx - 1), vy.min(ny - 1), z) {
+                        TissueLabel::Bone {
+                            mineral_fraction: 0.44,
+                        }
+                    } else {
+                        TissueLabel::Marrow
+                    };
+            }
+        }
+        section.scatter_cells(OSTEOCYTE_SPACING_UM, LACUNA_RADIUS_UM, rng, |l| {
+            matches!(l, TissueLabel::Bone { .. })
+        });
+        section.scatter_cells(MARROW_CELL_SPACING_UM, NUCLEUS_RADIUS_UM * 1.5, rng, |l| {
+            l == TissueLabel::Marrow
+        });
+        Ok(section)
+    }
+
+    // Longitudinal section through a physis, epiphysis at the top: resting,
+    // proliferative (stacked flat chondrocytes), hypertrophic (swollen cells,
+    // calcifying matrix) zones, then primary spongiosa.
+    pub fn growth_plate<R: Rng>(
+        width_um: f64,
+        zone_heights_um: [f64; 4],
+        pixel_size_um: f64,
+        rng: &mut R,
+    ) -> BiologyResult<Self> {
+        if zone_heights_um.iter().any(|h| h.is_nan() || *h <= 0.0) {
+            return Err(BiologyError::InvalidValue(
+                "zone heights must be positive".to_string(),
+            ));
+        }
+        let px = |um: f64| (um / pixel_size_um).round() as usize;
+        let width = px(width_um);
+        let bounds: Vec<usize> = zone_heights_um
+            .iter()
+            .scan(0.0, |top, h| {
+                *top += h;
+                Some(px(*top))
+            })
+            .collect();
+        let mut section = Self::new(width, bounds[3], pixel_size_um)?;
+        let column_pitch = px(30.0).max(4);
+        for y in 0..section.height {
+            for x in 0..width {
+                let label = if y < bounds[1] {
+                    TissueLabel::Cartilage {
+                        hypertrophic: false,
+                        calcified: false,
+                    }
+                } else if y < bounds[2] {
+                    let lower_half = y >= (bounds[1] + bounds[2]) / 2;
+                    TissueLabel::Cartilage {
+                        hypertrophic: true,
+                        calcified: lower_half,
+                    }
+                } else if x % column_pitch < column_pitch / 2 {
+                    // Spongiosa trabeculae continue the cartilage septa.
+                    TissueLabel::Bone {
+                        mineral_fraction: 0.3,
+                    }
+                } else {
+                    TissueLabel::Marrow
+                };
+                section.labels[y * width + x] = label;
+            }
+        }
+        // Resting zone: sparse round chondrocytes.
+        section.scatter_cells_in(0, bounds[0], 45.0, 5.0, rng);
+        // Proliferative zone: flattened cells stacked in columns.
+        let mut y = bounds[0];
+        while y < bounds[1] {
+            let mut x = column_pitch / 2;
+            while x < width {
+                section.stamp_cell(
+                    x as f64,
+                    y as f64 + px(3.0) as f64,
+                    px(8.0) as f64,
+                    px(3.0) as f64,
+                );
+                x += column_pitch;
+            }
+            y += px(8.0).max(2);
+        }
+        // Hypertrophic zone: the same columns, cells swollen several-fold.
+        let mut y = bounds[1];
+        while y + px(8.0) < bounds[2] {
+            let mut x = column_pitch / 2;
+            while x < width {
+                section.stamp_cell(
+                    x as f64,
+                    y as f64 + px(9.0) as f64,
+                    px(11.0) as f64,
+                    px(9.0) as f64,
+                );
+                x += column_pitch;
+            }
+            y += px(20.0).max(2);
+        }
+        section.scatter_cells(MARROW_CELL_SPACING_UM, NUCLEUS_RADIUS_UM * 1.5, rng, |l| {
+            l == TissueLabel::Marrow
+        });
+        Ok(section)
+    }
+
+    fn scatter_cells<R: Rng>(
+        &mut self,
+        spacing_um: f64,
+        radius_um: f64,
+        rng: &mut R,
+        host: impl Fn(TissueLabel) -> bool,
+    ) {
+        let area = self.width as f64 * self.height as f64 * self.pixel_size_um.powi(2);
+        let count = (area / spacing_um.powi(2)).round() as usize;
+        let r = radius_um / self.pixel_size_um;
+        for _ in 0..count {
+            let x = rng.gen_range(0.0..self.width as f64);
+            let y = rng.gen_range(0.0..self.height as f64);
+            if host(self.label(x as usize, y as usize)) {
+                self.stamp_cell(x, y, r, r);
+            }
+        }
+    }
+
+    fn scatter_cells_in<R: Rng>(
+        &mut self,
+        top: usize,
+        bottom: usize,
+        spacing_um: f64,
+        radius_um: f64,
+        rng: &mut R,
+    ) {
+        let area = self.width as f64 * (bottom - top) as f64 * self.pixel_size_um.powi(2);
+        let count = (area / spacing_um.powi(2)).round() as usize;
+        let r = radius_um / self.pixel_size_um;
+        for _ in 0..count {
+            let x = rng.gen_range(0.0..self.width as f64);
+            let y = rng.gen_range(top as f64..bottom as f64);
+            self.stamp_cell(x, y, r, r);
+        }
+    }
+
+    // Elliptical lacuna with its nucleus at the centre.
+    fn stamp_cell(&mut self, cx: f64, cy: f64, rx: f64, ry: f64) {
+        let (rx, ry) = (rx.max(0.5), ry.max(0.5));
+        let x0 = (cx - rx).floor().max(0.0) as usize;
+        let y0 = (cy - ry).floor().max(0.0) as usize;
+        let x1 = ((cx + rx).ceil() as usize).min(self.width);
+        let y1 = ((cy + ry).ceil() as usize).min(self.height);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let dx = (x as f64 + 0.5 - cx) / rx;
+                let dy = (y as f64 + 0.5 - cy) / ry;
+                let d = dx * dx + dy * dy;
+                if d <= 0.25 {
+                    self.labels[y * self.width + x] = TissueLabel::Nucleus;
+                } else if d <= 1.0 {
+                    self.labels[y * self.width + x] = TissueLabel::Lacuna;
+                }
+            }
+        }
+    }
+
+    pub fn render(&self, stain: Stain) -> HistologyImage {
+        HistologyImage {
+            width: self.width,
+            height: self.height,
+            rgb: self.labels.iter().flat_map(|&l| stain.color(l)).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistologyImage {
+    pub width: usize,
+    pub height: usize,
+    pub rgb: Vec<u8>,
+}
+
+impl HistologyImage {
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        let i = 3 * (y * self.width + x);
+        [self.rgb[i], self.rgb[i + 1], self.rgb[i + 2]]
+    }
+
+    // 8-bit truecolour PNG; IDAT carries stored (uncompressed) deflate
+    // blocks, which every decoder accepts.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity((3 * self.width + 1) * self.height);
+        for row in self.rgb.chunks(3 * self.width) {
+            raw.push(0);
+            raw.extend_from_slice(row);
+        }
+        let mut zlib = vec![0x78, 0x01];
+        let blocks: Vec<&[u8]> = raw.chunks(65_535).collect();
+        for (i, block) in blocks.iter().enumerate() {
+            zlib.push(u8::from(i + 1 == blocks.len()));
+            let len = block.len() as u16;
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        if blocks.is_empty() {
+            zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib);
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    // One rect per horizontal run of equal colour.
+    pub fn to_svg(&self) -> String {
+        let mut out = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" shape-rendering=\"crispEdges\">\n",
+            w = self.width,
+            h = self.height
+        );
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                let colour = self.pixel(x, y);
+                let start = x;
+                while x < self.width && self.pixel(x, y) == colour {
+                    x += 1;
+                }
+                let _ = writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+                    start,
+                    y,
+                    x - start,
+                    colour[0],
+                    colour[1],
+                    colour[2]
+                );
+            }
+        }
+        out.push_str("</svg>\n");
+        out
+    }
+
+    pub fn write_png<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_png())
+    }
+
+    pub fn write_svg<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_osteon_section_shows_canal_cement_line_and_osteocytes() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let field = MineralizationField::osteon(61, 5.0).unwrap();
+        let section = TissueSection::from_mineralization(&field, 2.0, &mut rng).unwrap();
+        let centre = section.width / 2;
+        assert_eq!(section.label(centre, centre), TissueLabel::Canal);
+        assert!(section.fraction(|l| l == TissueLabel::CementLine) > 0.005);
+        assert!(section.fraction(|l| l == TissueLabel::Osteoid) > 0.0);
+        assert!(section.fraction(|l| l == TissueLabel::Nucleus) > 0.0);
+        let he = section.render(Stain::HematoxylinEosin);
+        let kossa = section.render(Stain::VonKossa);
+        let (x, y) = (2, 2);
+        assert!(
+            matches!(section.label(x, y), TissueLabel::Bone { .. })
+                || section.label(x, y) == TissueLabel::Lacuna
+        );
+        assert_ne!(he.pixel(centre, 0), kossa.pixel(centre, 0));
+    }
+
+    #[test]
+    fn test_trabecular_slice_preserves_bone_fraction() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let segmentation = Segmentation {
+            dimensions: [20, 20, 1],
+            voxel_size_um: 20.0,
+            bone: (0..400).map(|i| (i % 20) < 5).collect(),
+        };
+        let section =
+            TissueSection::from_segmentation_slice(&segmentation, 0, 4.0, &mut rng).unwrap();
+        let bone_like = section.fraction(|l| matches!(l, TissueLabel::Bone { .. }));
+        assert!(bone_like > 0.2 && bone_like <= 0.25);
+        assert!(section.fraction(|l| l == TissueLabel::Nucleus) > 0.02);
+        assert!(TissueSection::from_segmentation_slice(&segmentation, 1, 4.0, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_growth_plate_zones_stain_in_order() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let section =
+            TissueSection::growth_plate(300.0, [100.0, 150.0, 120.0, 100.0], 2.0, &mut rng)
+                .unwrap();
+        let safranin = section.render(Stain::SafraninO);
+        let row = |y: usize| -> Vec<TissueLabel> {
+            (0..section.width).map(|x| section.label(x, y)).collect()
+        };
+        assert!(row(10).iter().any(|&l| matches!(
+            l,
+            TissueLabel::Cartilage {
+                hypertrophic: false,
+                ..
+            }
+        )));
+        assert!(row(180).iter().any(|&l| matches!(
+            l,
+            TissueLabel::Cartilage {
+                calcified: true,
+                ..
+            }
+        )));
+        assert!(row(230)
+            .iter()
+            .any(|&l| matches!(l, TissueLabel::Bone { .. })));
+        // Cartilage stains orange-red, spongiosa bone green.
+        let cartilage = safranin.pixel(1, 10);
+        assert!(cartilage[0] > cartilage[1]);
+        assert!(TissueSection::growth_plate(300.0, [0.0, 1.0, 1.0, 1.0], 2.0, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_png_and_svg_encoding() {
+        let image = HistologyImage {
+            width: 3,
+            height: 2,
+            rgb: vec![
+                255, 0, 0, 255, 0, 0, 0, 0, 255, 0, 255, 0, 0, 255, 0, 0, 255, 0,
+            ],
+        };
+        let png = image.to_png();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(u32::from_be_bytes([png[16], png[17], png[18], png[19]]), 3);
+        // IHDR CRC covers type and data.
+        let crc = u32::from_be_bytes([png[29], png[30], png[31], png[32]]);
+        assert_eq!(crc, crc32(&png[12..29]));
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]));
+        let svg = image.to_svg();
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains("fill=\"#00ff00\""));
+    }
+}
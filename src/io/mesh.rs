@@ -0,0 +1,593 @@
+use crate::geometry::Vec3;
+use crate::io::microct::Segmentation;
+use crate::metabolism::structure::{Element, MolecularStructure};
+use crate::systems::cardiovascular::vascular_tree::VesselTree;
+use serde_json::json;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+// Triangle meshes for Wavefront OBJ and glTF 2.0 viewers. Coordinates keep
+// their source units, ångströms for molecules and millimetres for tissue;
+// glTF readers take them as metres, so scale on import.
+
+const ATOM_RADIUS_FRACTION: f64 = 0.5;
+const MIN_ATOM_RADIUS_ANGSTROM: f64 = 0.2;
+const STICK_RADIUS_ANGSTROM: f64 = 0.12;
+const VESSEL_COLOR: [f32; 4] = [0.75, 0.1, 0.12, 1.0];
+const BONE_COLOR: [f32; 4] = [0.93, 0.89, 0.8, 1.0];
+
+// glTF component and target codes.
+const GL_FLOAT: u32 = 5126;
+const GL_UNSIGNED_INT: u32 = 5125;
+const GL_ARRAY_BUFFER: u32 = 34962;
+const GL_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const GL_TRIANGLES: u32 = 4;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshGroup {
+    pub name: String,
+    pub color: [f32; 4],
+    pub triangles: Vec<[u32; 3]>,
+}
+
+// Vertex normals are either given for every vertex or left empty, in
+// which case both formats fall back to flat shading.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriangleMesh {
+    pub name: String,
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub groups: Vec<MeshGroup>,
+}
+
+fn to_f32(v: Vec3) -> [f32; 3] {
+    [v.x as f32, v.y as f32, v.z as f32]
+}
+
+// Two unit vectors completing an orthonormal frame around `axis`.
+fn frame(axis: Vec3) -> (Vec3, Vec3) {
+    let helper = if axis.x.abs() < 0.9 {
+        Vec3::new(1.0, 0.0, 0.0)
+    } else {
+        Vec3::new(0.0, 1.0, 0.0)
+    };
+    let u = axis.cross(helper).normalized();
+    (u, axis.cross(u))
+}
+
+impl TriangleMesh {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            positions: Vec::new(),
+            normals: Vec::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.groups.iter().map(|g| g.triangles.len()).sum()
+    }
+
+    // Index of the group called `name`, created with `color` if new.
+    pub fn group(&mut self, name: &str, color: [f32; 4]) -> usize {
+        if let Some(index) = self.groups.iter().position(|g| g.name == name) {
+            return index;
+        }
+        self.groups.push(MeshGroup {
+            name: name.to_string(),
+            color,
+            triangles: Vec::new(),
+        });
+        self.groups.len() - 1
+    }
+
+    fn vertex(&mut self, position: Vec3, normal: Vec3) -> u32 {
+        self.positions.push(to_f32(position));
+        self.normals.push(to_f32(normal));
+        (self.positions.len() - 1) as u32
+    }
+
+    // Closed UV sphere with `detail` stacks and twice as many slices.
+    pub fn add_sphere(&mut self, group: usize, center: Vec3, radius: f64, detail: usize) {
+        let stacks = detail.max(2);
+        let slices = 2 * stacks;
+        let top = self.vertex(
+            center + Vec3::new(0.0, 0.0, radius),
+            Vec3::new(0.0, 0.0, 1.0),
+        );
+        let first_ring = self.positions.len() as u32;
+        for stack in 1..stacks {
+            let polar = PI * stack as f64 / stacks as f64;
+            for slice in 0..slices {
+                let azimuth = 2.0 * PI * slice as f64 / slices as f64;
+                let normal = Vec3::new(
+                    polar.sin() * azimuth.cos(),
+                    polar.sin() * azimuth.sin(),
+                    polar.cos(),
+                );
+                self.vertex(center + normal * radius, normal);
+            }
+        }
+        let bottom = self.vertex(
+            center - Vec3::new(0.0, 0.0, radius),
+            Vec3::new(0.0, 0.0, -1.0),
+        );
+        let ring = |stack: usize, slice: usize| {
+            first_ring + ((stack - 1) * slices + slice % slices) as u32
+        };
+        let triangles = &mut self.groups[group].triangles;
+        for slice in 0..slices {
+            triangles.push([top, ring(1, slice), ring(1, slice + 1)]);
+            for stack in 1..stacks - 1 {
+                let (a, b) = (ring(stack, slice), ring(stack, slice + 1));
+                let (c, d) = (ring(stack + 1, slice), ring(stack + 1, slice + 1));
+                triangles.push([a, c, d]);
+                triangles.push([a, d, b]);
+            }
+            triangles.push([bottom, ring(stacks - 1, slice + 1), ring(stacks - 1, slice)]);
+        }
+    }
+
+    // Open-ended frustum from `start` to `end`; `capped` closes both ends
+    // with flat discs.
+    pub fn add_tube(
+        &mut self,
+        group: usize,
+        start: Vec3,
+        end: Vec3,
+        radius: f64,
+        slices: usize,
+        capped: bool,
+    ) {
+        let axis = (end - start).normalized();
+        if axis.norm() == 0.0 {
+            return;
+        }
+        let slices = slices.max(3);
+        let (u, v) = frame(axis);
+        let radial = |slice: usize| {
+            let angle = 2.0 * PI * slice as f64 / slices as f64;
+            u * angle.cos() + v * angle.sin()
+        };
+        let base = self.positions.len() as u32;
+        for slice in 0..slices {
+            let normal = radial(slice);
+            self.vertex(start + normal * radius, normal);
+            self.vertex(end + normal * radius, normal);
+        }
+        let side = |slice: usize| base + 2 * (slice % slices) as u32;
+        for slice in 0..slices {
+            let (a, b) = (side(slice), side(slice + 1));
+            self.groups[group].triangles.push([a, b, b + 1]);
+            self.groups[group].triangles.push([a, b + 1, a + 1]);
+        }
+        if capped {
+            for (center, normal) in [(start, -axis), (end, axis)] {
+                let hub = self.vertex(center, normal);
+                let rim = self.positions.len() as u32;
+                for slice in 0..slices {
+                    self.vertex(center + radial(slice) * radius, normal);
+                }
+                for slice in 0..slices as u32 {
+                    let (a, b) = (rim + slice, rim + (slice + 1) % slices as u32);
+                    let triangle = if normal.dot(axis) > 0.0 {
+                        [hub, a, b]
+                    } else {
+                        [hub, b, a]
+                    };
+                    self.groups[group].triangles.push(triangle);
+                }
+            }
+        }
+    }
+
+    // CPK-coloured atoms scaled from covalent radii, with each bond drawn
+    // as two half-sticks in the colours of the atoms they meet.
+    pub fn ball_and_stick(structure: &MolecularStructure, detail: usize) -> Self {
+        let mut mesh = TriangleMesh::new(&structure.name);
+        let group_for = |mesh: &mut TriangleMesh, element: Element| {
+            let [r, g, b] = element.cpk_color();
+            mesh.group(
+                element.symbol(),
+                [r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0],
+            )
+        };
+        for atom in &structure.atoms {
+            let group = group_for(&mut mesh, atom.element);
+            let radius = (atom.element.covalent_radius_angstrom() * ATOM_RADIUS_FRACTION)
+                .max(MIN_ATOM_RADIUS_ANGSTROM);
+            mesh.add_sphere(group, atom.position, radius, detail);
+        }
+        for bond in &structure.bonds {
+            let (a, b) = (structure.atoms[bond.first], structure.atoms[bond.second]);
+            let middle = (a.position + b.position) * 0.5;
+            for (atom, from) in [(a, a.position), (b, b.position)] {
+                let group = group_for(&mut mesh, atom.element);
+                mesh.add_tube(
+                    group,
+                    from,
+                    middle,
+                    STICK_RADIUS_ANGSTROM,
+                    2 * detail,
+                    false,
+                );
+            }
+        }
+        mesh
+    }
+
+    // Exposed faces of the bone voxels in millimetres, including faces on
+    // the volume boundary so the surface is closed. Corners are shared
+    // between faces and no normals are written, giving flat shading.
+    pub fn voxel_surface(segmentation: &Segmentation) -> Self {
+        let mut mesh = TriangleMesh::new("trabecular_bone");
+        let group = mesh.group("bone", BONE_COLOR);
+        let [nx, ny, nz] = segmentation.dimensions;
+        let size = segmentation.voxel_size_mm();
+        let mut corners: HashMap<[usize; 3], u32> = HashMap::new();
+        let bone = |x: isize, y: isize, z: isize| {
+            x >= 0
+                && y >= 0
+                && z >= 0
+                && (x as usize) < nx
+                && (y as usize) < ny
+                && (z as usize) < nz
+                && segmentation.is_bone(x as usize, y as usize, z as usize)
+        };
+        // Each face lists its corner offsets counter-clockwise seen from
+        // outside, paired with the neighbour across it.
+        let faces: [([isize; 3], [[usize; 3]; 4]); 6] = [
+            ([-1, 0, 0], [[0, 0, 0], [0, 0, 1], [0, 1, 1], [0, 1, 0]]),
+            ([1, 0, 0], [[1, 0, 0], [1, 1, 0], [1, 1, 1], [1, 0, 1]]),
+            ([0, -1, 0], [[0, 0, 0], [1, 0, 0], [1, 0, 1], [0, 0, 1]]),
+            ([0, 1, 0], [[0, 1, 0], [0, 1, 1], [1, 1, 1], [1, 1, 0]]),
+            ([0, 0, -1], [[0, 0, 0], [0, 1, 0], [1, 1, 0], [1, 0, 0]]),
+            ([0, 0, 1], [[0, 0, 1], [1, 0, 1], [1, 1, 1], [0, 1, 1]]),
+        ];
+        for z in 0..nz {
+            for y in 0..ny {
+                for x in 0..nx {
+                    if !segmentation.is_bone(x, y, z) {
+                        continue;
+                    }
+                    for (offset, quad) in &faces {
+                        let (ix, iy, iz) = (x as isize, y as isize, z as isize);
+                        if bone(ix + offset[0], iy + offset[1], iz + offset[2]) {
+                            continue;
+                        }
+                        let ids = quad.map(|[dx, dy, dz]| {
+                            let corner = [x + dx, y + dy, z + dz];
+                            *corners.entry(corner).or_insert_with(|| {
+                                mesh.positions.push([
+                                    (corner[0] as f64 * size) as f32,
+                                    (corner[1] as f64 * size) as f32,
+                                    (corner[2] as f64 * size) as f32,
+                                ]);
+                                (mesh.positions.len() - 1) as u32
+                            })
+                        });
+                        mesh.groups[group].triangles.push([ids[0], ids[1], ids[2]]);
+                        mesh.groups[group].triangles.push([ids[0], ids[2], ids[3]]);
+                    }
+                }
+            }
+        }
+        mesh
+    }
+
+    // Capped tubes per segment with spheres filling the joints.
+    pub fn vessel_tree(tree: &VesselTree, slices: usize) -> Self {
+        let mut mesh = TriangleMesh::new("vessel_tree");
+        let group = mesh.group("vessel", VESSEL_COLOR);
+        for (i, segment) in tree.segments.iter().enumerate() {
+            mesh.add_tube(
+                group,
+                segment.proximal,
+                segment.distal,
+                segment.radius_mm,
+                slices,
+                true,
+            );
+            if tree.children(i).next().is_some() {
+                mesh.add_sphere(group, segment.distal, segment.radius_mm, slices / 2);
+            }
+        }
+        mesh
+    }
+
+    pub fn bounds(&self) -> Option<([f32; 3], [f32; 3])> {
+        let first = *self.positions.first()?;
+        Some(self.positions.iter().fold((first, first), |(lo, hi), p| {
+            (
+                [lo[0].min(p[0]), lo[1].min(p[1]), lo[2].min(p[2])],
+                [hi[0].max(p[0]), hi[1].max(p[1]), hi[2].max(p[2])],
+            )
+        }))
+    }
+
+    // `material_library` names the companion .mtl file, if any.
+    pub fn to_obj(&self, material_library: Option<&str>) -> String {
+        let mut out = String::new();
+        if let Some(library) = material_library {
+            let _ = writeln!(out, "mtllib {}", library);
+        }
+        let _ = writeln!(out, "o {}", self.name);
+        for [x, y, z] in &self.positions {
+            let _ = writeln!(out, "v {} {} {}", x, y, z);
+        }
+        for [x, y, z] in &self.normals {
+            let _ = writeln!(out, "vn {} {} {}", x, y, z);
+        }
+        let with_normals = !self.normals.is_empty();
+        for group in &self.groups {
+            let _ = writeln!(out, "g {}", group.name);
+            let _ = writeln!(out, "usemtl {}", group.name);
+            for triangle in &group.triangles {
+                let [a, b, c] = triangle.map(|i| i + 1);
+                let _ = if with_normals {
+                    writeln!(out, "f {a}//{a} {b}//{b} {c}//{c}")
+                } else {
+                    writeln!(out, "f {} {} {}", a, b, c)
+                };
+            }
+        }
+        out
+    }
+
+    pub fn to_mtl(&self) -> String {
+        let mut out = String::new();
+        for group in &self.groups {
+            let [r, g, b, a] = group.color;
+            let _ = writeln!(
+                out,
+                "newmtl {}\nKd {} {} {}\nd {}\n",
+                group.name, r, g, b, a
+            );
+        }
+        out
+    }
+
+    // Writes the .obj and a .mtl beside it.
+    pub fn write_obj<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let path = path.as_ref();
+        let mtl = path.with_extension("mtl");
+        let library = mtl.file_name().and_then(|n| n.to_str());
+        fs::write(path, self.to_obj(library))?;
+        fs::write(&mtl, self.to_mtl())
+    }
+
+    // Little-endian vertex and index data in glTF buffer order: positions,
+    // normals, then one index block per group.
+    fn binary_buffer(&self) -> Vec<u8> {
+        let floats = self.positions.iter().chain(&self.normals).flatten();
+        let indices = self
+            .groups
+            .iter()
+            .flat_map(|g| g.triangles.iter().flatten());
+        floats
+            .flat_map(|f| f.to_le_bytes())
+            .chain(indices.flat_map(|i| i.to_le_bytes()))
+            .collect()
+    }
+
+    fn gltf_document(&self, buffer_length: usize, uri: Option<String>) -> serde_json::Value {
+        let vertex_bytes = self.positions.len() * 12;
+        let (lo, hi) = self.bounds().unwrap_or(([0.0; 3], [0.0; 3]));
+        let mut views = vec![json!({
+            "buffer": 0, "byteOffset": 0, "byteLength": vertex_bytes, "target": GL_ARRAY_BUFFER
+        })];
+        let mut accessors = vec![json!({
+            "bufferView": 0, "componentType": GL_FLOAT, "count": self.positions.len(),
+            "type": "VEC3", "min": lo, "max": hi
+        })];
+        let mut offset = vertex_bytes;
+        let mut attributes = json!({ "POSITION": 0 });
+        if !self.normals.is_empty() {
+            views.push(json!({
+                "buffer": 0, "byteOffset": offset, "byteLength": vertex_bytes,
+                "target": GL_ARRAY_BUFFER
+            }));
+            accessors.push(json!({
+                "bufferView": 1, "componentType": GL_FLOAT, "count": self.normals.len(),
+                "type": "VEC3"
+            }));
+            attributes["NORMAL"] = json!(1);
+            offset += vertex_bytes;
+        }
+        let mut primitives = Vec::new();
+        let mut materials = Vec::new();
+        for (material, group) in self.groups.iter().enumerate() {
+            let length = group.triangles.len() * 12;
+            views.push(json!({
+                "buffer": 0, "byteOffset": offset, "byteLength": length,
+                "target": GL_ELEMENT_ARRAY_BUFFER
+            }));
+            accessors.push(json!({
+                "bufferView": views.len() - 1, "componentType": GL_UNSIGNED_INT,
+                "count": group.triangles.len() * 3, "type": "SCALAR"
+            }));
+            primitives.push(json!({
+                "attributes": attributes, "indices": accessors.len() - 1,
+                "material": material, "mode": GL_TRIANGLES
+            }));
+            materials.push(json!({
+                "name": group.name,
+                "pbrMetallicRoughness": {
+                    "baseColorFactor": group.color, "metallicFactor": 0.0, "roughnessFactor": 0.6
+                },
+                "alphaMode": if group.color[3] < 1.0 { "BLEND" } else { "OPAQUE" },
+                "doubleSided": self.normals.is_empty()
+            }));
+            offset += length;
+        }
+        let mut buffer = json!({ "byteLength": buffer_length });
+        if let Some(uri) = uri {
+            buffer["uri"] = json!(uri);
+        }
+        json!({
+            "asset": { "version": "2.0", "generator": "human_biology" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0, "name": self.name }],
+            "meshes": [{ "name": self.name, "primitives": primitives }],
+            "materials": materials,
+            "accessors": accessors,
+            "bufferViews": views,
+            "buffers": [buffer]
+        })
+    }
+
+    // Self-contained .gltf with the buffer embedded as a data URI.
+    pub fn to_gltf(&self) -> String {
+        let buffer = self.binary_buffer();
+        let uri = format!(
+            "data:application/octet-stream;base64,{}",
+            base64_encode(&buffer)
+        );
+        self.gltf_document(buffer.len(), Some(uri)).to_string()
+    }
+
+    // Binary .glb: 12-byte header, then JSON and BIN chunks each padded to
+    // four bytes.
+    pub fn to_glb(&self) -> Vec<u8> {
+        let mut buffer = self.binary_buffer();
+        let mut document = self
+            .gltf_document(buffer.len(), None)
+            .to_string()
+            .into_bytes();
+        while !document.len().is_multiple_of(4) {
+            document.push(b' ');
+        }
+        while !buffer.len().is_multiple_of(4) {
+            buffer.push(0);
+        }
+        let total = 12 + 8 + document.len() + 8 + buffer.len();
+        let mut out = Vec::with_capacity(total);
+        out.extend_from_slice(b"glTF");
+        out.extend_from_slice(&2u32.to_le_bytes());
+        out.extend_from_slice(&(total as u32).to_le_bytes());
+        out.extend_from_slice(&(document.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"JSON");
+        out.extend_from_slice(&document);
+        out.extend_from_slice(&(buffer.len() as u32).to_le_bytes());
+        out.extend_from_slice(b"BIN\0");
+        out.extend_from_slice(&buffer);
+        out
+    }
+
+    pub fn write_gltf<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_gltf())
+    }
+
+    pub fn write_glb<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_glb())
+    }
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every edge of a closed surface borders exactly two triangles, once
+    // in each direction.
+    fn is_closed(triangles: &[[u32; 3]]) -> bool {
+        let mut edges: HashMap<(u32, u32), i32> = HashMap::new();
+        for t in triangles {
+            for k in 0..3 {
+                let (a, b) = (t[k], t[(k + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_default() += if a < b { 1 } else { -1 };
+            }
+        }
+        edges.values().all(|&balance| balance == 0)
+    }
+
+    #[test]
+    fn test_ball_and_stick_water() {
+        let mesh = TriangleMesh::ball_and_stick(&MolecularStructure::water(), 8);
+        let names: Vec<&str> = mesh.groups.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["O", "H"]);
+        assert_eq!(mesh.normals.len(), mesh.positions.len());
+        let mut sphere = TriangleMesh::new("sphere");
+        let group = sphere.group("s", [1.0; 4]);
+        sphere.add_sphere(group, Vec3::ZERO, 1.0, 6);
+        let v = sphere.positions.len() as i64;
+        let f = sphere.triangle_count() as i64;
+        assert_eq!(v - 3 * f / 2 + f, 2);
+        assert!(is_closed(&sphere.groups[0].triangles));
+    }
+
+    #[test]
+    fn test_voxel_surface_shares_corners() {
+        let segmentation = Segmentation {
+            dimensions: [2, 1, 1],
+            voxel_size_um: 20.0,
+            bone: vec![true, true],
+        };
+        let mesh = TriangleMesh::voxel_surface(&segmentation);
+        assert_eq!(mesh.positions.len(), 12);
+        assert_eq!(mesh.triangle_count(), 20);
+        assert!(is_closed(&mesh.groups[0].triangles));
+        let (_, hi) = mesh.bounds().unwrap();
+        assert!((hi[0] - 0.04).abs() < 1e-6);
+        let obj = mesh.to_obj(Some("bone.mtl"));
+        assert_eq!(obj.lines().filter(|l| l.starts_with("v ")).count(), 12);
+        assert_eq!(obj.lines().filter(|l| l.starts_with("f ")).count(), 20);
+        assert!(!obj.contains("vn "));
+    }
+
+    #[test]
+    fn test_gltf_buffer_matches_accessors() {
+        let tree = VesselTree::murray(2, 1.0, 10.0, 60.0).unwrap();
+        let mesh = TriangleMesh::vessel_tree(&tree, 12);
+        let document: serde_json::Value = serde_json::from_str(&mesh.to_gltf()).unwrap();
+        let buffer = &document["buffers"][0];
+        let uri = buffer["uri"].as_str().unwrap();
+        let encoded = uri.split(',').nth(1).unwrap();
+        let length = buffer["byteLength"].as_u64().unwrap() as usize;
+        assert_eq!(encoded.len(), length.div_ceil(3) * 4);
+        assert_eq!(
+            length,
+            mesh.positions.len() * 24 + mesh.triangle_count() * 12
+        );
+        assert_eq!(
+            document["accessors"][0]["count"].as_u64().unwrap() as usize,
+            mesh.positions.len()
+        );
+        let glb = mesh.to_glb();
+        assert_eq!(&glb[..4], b"glTF");
+        assert_eq!(
+            u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize,
+            glb.len()
+        );
+        assert_eq!(glb.len() % 4, 0);
+    }
+
+    #[test]
+    fn test_base64_padding() {
+        assert_eq!(base64_encode(b"M"), "TQ==");
+        assert_eq!(base64_encode(b"Ma"), "TWE=");
+        assert_eq!(base64_encode(b"Man"), "TWFu");
+    }
+}
@@ -0,0 +1,26 @@
+pub mod diagram;
+pub mod histology;
+pub mod mesh;
+pub mod microct;
+pub mod nonfinite;
+#[cfg(feature = "schema")]
+pub mod schema;
+pub mod versioned;
+pub mod vtk;
+
+pub use diagram::{
+    bone_signaling_pathway, model_ontology, Diagram, DiagramEdge, DiagramNode, EdgeStyle, NodeShape,
+};
+pub use histology::{HistologyImage, Stain, TissueLabel, TissueSection};
+pub use mesh::{MeshGroup, TriangleMesh};
+pub use microct::{MicroCtVolume, Morphometry, RawFormat, Segmentation, VoxelMesh};
+#[cfg(feature = "schema")]
+pub use schema::{
+    example_schema, example_schema_for_value, public_schema, type_schema, SchemaBundle,
+    JSON_SCHEMA_DIALECT,
+};
+pub use versioned::{
+    from_versioned_str, from_versioned_value, to_versioned_string, to_versioned_value, Envelope,
+    Migration, Versioned,
+};
+pub use vtk::{macrophage_agents, Cell, CellType, DataArray, ImageData, UnstructuredGrid};
@@ -0,0 +1,566 @@
+use serde::de::value::StringDeserializer;
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde::ser::{self, Serialize, Serializer};
+use serde::{forward_to_deserialize_any, Deserializer};
+use serde_json::{Error, Value};
+
+// JSON has no NaN or infinity, and serde_json writes them as null, which
+// then fails to load back into an f64. Saved simulation state (a diverged
+// solver, an infinite half-life) keeps them as the strings JavaScript and
+// Python use; they are decoded again wherever an f64 is expected, so a
+// genuine string field holding "NaN" is left alone.
+const NAN: &str = "NaN";
+const INFINITY: &str = "Infinity";
+const NEG_INFINITY: &str = "-Infinity";
+
+fn encode(v: f64) -> Option<&'static str> {
+    if v.is_nan() {
+        Some(NAN)
+    } else if v == f64::INFINITY {
+        Some(INFINITY)
+    } else if v == f64::NEG_INFINITY {
+        Some(NEG_INFINITY)
+    } else {
+        None
+    }
+}
+
+fn decode(text: &str) -> Option<f64> {
+    match text {
+        NAN => Some(f64::NAN),
+        INFINITY => Some(f64::INFINITY),
+        NEG_INFINITY => Some(f64::NEG_INFINITY),
+        _ => None,
+    }
+}
+
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, Error> {
+    serde_json::to_value(Lossless(value))
+}
+
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, Error> {
+    T::deserialize(Decoder(value))
+}
+
+// Serializes the wrapped value with non-finite floats spelled out.
+pub struct Lossless<'a, T: ?Sized>(pub &'a T);
+
+impl<T: Serialize + ?Sized> Serialize for Lossless<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(Encoder(serializer))
+    }
+}
+
+struct Encoder<S>(S);
+
+macro_rules! forward_scalars {
+    ($($method:ident: $ty:ty),*) => {
+        $(fn $method(self, v: $ty) -> Result<S::Ok, S::Error> {
+            self.0.$method(v)
+        })*
+    };
+}
+
+impl<S: Serializer> Serializer for Encoder<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Encoder<S::SerializeSeq>;
+    type SerializeTuple = Encoder<S::SerializeTuple>;
+    type SerializeTupleStruct = Encoder<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Encoder<S::SerializeTupleVariant>;
+    type SerializeMap = Encoder<S::SerializeMap>;
+    type SerializeStruct = Encoder<S::SerializeStruct>;
+    type SerializeStructVariant = Encoder<S::SerializeStructVariant>;
+
+    forward_scalars!(
+        serialize_bool: bool, serialize_i8: i8, serialize_i16: i16, serialize_i32: i32,
+        serialize_i64: i64, serialize_i128: i128, serialize_u8: u8, serialize_u16: u16,
+        serialize_u32: u32, serialize_u64: u64, serialize_u128: u128, serialize_char: char,
+        serialize_str: &str, serialize_bytes: &[u8]
+    );
+
+    fn serialize_f32(self, v: f32) -> Result<S::Ok, S::Error> {
+        match encode(v as f64) {
+            Some(text) => self.0.serialize_str(text),
+            None => self.0.serialize_f32(v),
+        }
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<S::Ok, S::Error> {
+        match encode(v) {
+            Some(text) => self.0.serialize_str(text),
+            None => self.0.serialize_f64(v),
+        }
+    }
+
+    fn serialize_none(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_none()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<S::Ok, S::Error> {
+        self.0.serialize_some(&Lossless(value))
+    }
+
+    fn serialize_unit(self) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_unit_variant(name, index, variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0.serialize_newtype_struct(name, &Lossless(value))
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<S::Ok, S::Error> {
+        self.0
+            .serialize_newtype_variant(name, index, variant, &Lossless(value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, S::Error> {
+        self.0.serialize_seq(len).map(Encoder)
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, S::Error> {
+        self.0.serialize_tuple(len).map(Encoder)
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, S::Error> {
+        self.0.serialize_tuple_struct(name, len).map(Encoder)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, S::Error> {
+        self.0
+            .serialize_tuple_variant(name, index, variant, len)
+            .map(Encoder)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+        self.0.serialize_map(len).map(Encoder)
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, S::Error> {
+        self.0.serialize_struct(name, len).map(Encoder)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, S::Error> {
+        self.0
+            .serialize_struct_variant(name, index, variant, len)
+            .map(Encoder)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+macro_rules! encode_compound {
+    ($($trait:ident::$method:ident),*) => {
+        $(impl<S: ser::$trait> ser::$trait for Encoder<S> {
+            type Ok = S::Ok;
+            type Error = S::Error;
+
+            fn $method<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+                self.0.$method(&Lossless(value))
+            }
+
+            fn end(self) -> Result<S::Ok, S::Error> {
+                self.0.end()
+            }
+        })*
+    };
+}
+
+encode_compound!(
+    SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field
+);
+
+impl<S: ser::SerializeMap> ser::SerializeMap for Encoder<S> {
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), S::Error> {
+        self.0.serialize_key(&Lossless(key))
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), S::Error> {
+        self.0.serialize_value(&Lossless(value))
+    }
+
+    fn end(self) -> Result<S::Ok, S::Error> {
+        self.0.end()
+    }
+}
+
+macro_rules! encode_fields {
+    ($($trait:ident),*) => {
+        $(impl<S: ser::$trait> ser::$trait for Encoder<S> {
+            type Ok = S::Ok;
+            type Error = S::Error;
+
+            fn serialize_field<T: Serialize + ?Sized>(
+                &mut self,
+                key: &'static str,
+                value: &T,
+            ) -> Result<(), S::Error> {
+                self.0.serialize_field(key, &Lossless(value))
+            }
+
+            fn skip_field(&mut self, key: &'static str) -> Result<(), S::Error> {
+                self.0.skip_field(key)
+            }
+
+            fn end(self) -> Result<S::Ok, S::Error> {
+                self.0.end()
+            }
+        })*
+    };
+}
+
+encode_fields!(SerializeStruct, SerializeStructVariant);
+
+// Reads a JSON value the way serde_json does, except that an f64 may also
+// be given as one of the spelled-out non-finite strings.
+struct Decoder(Value);
+
+impl<'de> Deserializer<'de> for Decoder {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(v) => visitor.visit_bool(v),
+            Value::Number(n) => match (n.as_u64(), n.as_i64()) {
+                (Some(v), _) => visitor.visit_u64(v),
+                (None, Some(v)) => visitor.visit_i64(v),
+                _ => visitor.visit_f64(n.as_f64().unwrap_or(f64::NAN)),
+            },
+            Value::String(v) => visitor.visit_string(v),
+            Value::Array(items) => visitor.visit_seq(Items(items.into_iter())),
+            Value::Object(map) => visitor.visit_map(Entries {
+                entries: map.into_iter(),
+                value: None,
+            }),
+        }
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0.as_str().and_then(decode) {
+            Some(v) => visitor.visit_f64(v),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(Decoder(value)),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::String(variant) => visitor.visit_enum(StringDeserializer::<Error>::new(variant)),
+            Value::Object(map) if map.len() == 1 => {
+                let (variant, value) = map.into_iter().next().expect("one entry");
+                visitor.visit_enum(Variant { variant, value })
+            }
+            other => Err(de::Error::custom(format!(
+                "expected an enum variant, found {}",
+                other
+            ))),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 char str string bytes byte_buf
+        unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct Items(std::vec::IntoIter<Value>);
+
+impl<'de> SeqAccess<'de> for Items {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(Decoder(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct Entries {
+    entries: serde_json::map::IntoIter,
+    value: Option<Value>,
+}
+
+impl<'de> MapAccess<'de> for Entries {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(Key(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("map value requested before its key"))?;
+        seed.deserialize(Decoder(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+// Object keys are always strings in JSON; maps keyed by numbers parse them
+// back, as serde_json does.
+struct Key(String);
+
+macro_rules! parse_key {
+    ($($method:ident => $visit:ident: $ty:ty),*) => {
+        $(fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            let parsed: $ty = self
+                .0
+                .parse()
+                .map_err(|_| de::Error::custom(format!("invalid map key {}", self.0)))?;
+            visitor.$visit(parsed)
+        })*
+    };
+}
+
+impl<'de> Deserializer<'de> for Key {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.0)
+    }
+
+    parse_key!(
+        deserialize_bool => visit_bool: bool, deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16, deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64, deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16, deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64, deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64
+    );
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(StringDeserializer::<Error>::new(self.0))
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char str string bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct Variant {
+    variant: String,
+    value: Value,
+}
+
+impl<'de> EnumAccess<'de> for Variant {
+    type Error = Error;
+    type Variant = Decoder;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Decoder), Error> {
+        let variant = seed.deserialize(StringDeserializer::<Error>::new(self.variant))?;
+        Ok((variant, Decoder(self.value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for Decoder {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.0 {
+            Value::Null => Ok(()),
+            other => Err(de::Error::custom(format!(
+                "expected a unit variant, found {}",
+                other
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_any(visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Clearance {
+        None,
+        HalfLife { hours: f64 },
+        Rates(Vec<f32>),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Model {
+        level: f64,
+        peak: Option<f64>,
+        label: String,
+        clearance: Vec<Clearance>,
+        by_compartment: BTreeMap<u32, f64>,
+    }
+
+    #[test]
+    fn test_non_finite_floats_round_trip() {
+        let model = Model {
+            level: f64::NEG_INFINITY,
+            peak: Some(f64::INFINITY),
+            label: "NaN".to_string(),
+            clearance: vec![
+                Clearance::None,
+                Clearance::HalfLife {
+                    hours: f64::INFINITY,
+                },
+                Clearance::Rates(vec![1.5, f32::NAN]),
+            ],
+            by_compartment: [(3, f64::NAN), (7, 0.1)].into_iter().collect(),
+        };
+        let text = serde_json::to_string(&Lossless(&model)).unwrap();
+        assert!(!text.contains("null"));
+        let restored: Model = from_value(serde_json::from_str(&text).unwrap()).unwrap();
+        assert_eq!(restored.level, f64::NEG_INFINITY);
+        assert_eq!(restored.peak, Some(f64::INFINITY));
+        assert_eq!(restored.label, "NaN");
+        assert_eq!(restored.clearance[1], model.clearance[1]);
+        assert!(matches!(&restored.clearance[2], Clearance::Rates(r) if r[1].is_nan()));
+        assert!(restored.by_compartment[&3].is_nan());
+        assert_eq!(restored.by_compartment[&7], 0.1);
+    }
+
+    #[test]
+    fn test_finite_values_match_plain_json() {
+        let model = Model {
+            level: 0.1 + 0.2,
+            peak: None,
+            label: "x".to_string(),
+            clearance: vec![Clearance::HalfLife { hours: 6.0 }],
+            by_compartment: BTreeMap::new(),
+        };
+        let plain = serde_json::to_value(&model).unwrap();
+        assert_eq!(to_value(&model).unwrap(), plain);
+        assert_eq!(from_value::<Model>(plain).unwrap(), model);
+        // A string where a number belongs is still an error.
+        let wrong = serde_json::json!({ "level": "high", "peak": null, "label": "",
+            "clearance": [], "by_compartment": {} });
+        assert!(from_value::<Model>(wrong).is_err());
+    }
+}
@@ -0,0 +1,808 @@
+use crate::config::{BaselineHumanParams, HumanPreset, PresetType};
+use crate::io::vtk::CellType;
+use crate::organism::Human;
+use crate::simulation::scenario::Scenario;
+use crate::simulation::{
+    CheckpointPolicy, CohortConfig, ParamValue, ParameterDistribution, PopulationSpec, RunRecord,
+    RunStatus,
+};
+use crate::systems::cardiovascular::hematology::BiologicalSex;
+use crate::systems::immune::granuloma::GranulomaParameters;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+// JSON Schema (draft 2020-12) descriptions of the serde data model, for
+// web tools that build or validate scenario files. The shape comes from
+// the serialized form of representative values; the type itself is then
+// probed through serde at every position of each example:
+// - an enum names all of its variants when handed an unknown one, so
+//   variants absent from the examples are accepted too (a data variant
+//   no example shows accepts any payload);
+// - a field the type still deserializes without is optional, whether
+//   serde fills it in (`#[serde(default)]`, `Option`) or not;
+// - a field that takes `null`, or is `None` and takes a scalar instead,
+//   is typed accordingly;
+// - an object that keeps an extra key through a round trip is a map, and
+//   a position that keeps any JSON value is a `serde_json::Value`.
+// What the probes cannot reach stays as the examples show it: a `None`
+// field of a struct type is typed as `null` only, one that is never
+// serialized (`skip_serializing_if`) is missing, and unknown fields, which
+// serde ignores unless a type denies them, are rejected.
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+// The schema of a value with no type to probe: every field required and
+// every string free-form.
+pub fn example_schema_for_value(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({ "type": "null" }),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(n) if n.is_f64() => json!({ "type": "number" }),
+        Value::Number(_) => json!({ "type": "integer" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Array(items) => {
+            let mut schema = json!({ "type": "array" });
+            if let Some(merged) = items.iter().map(example_schema_for_value).reduce(merge) {
+                schema["items"] = merged;
+            }
+            schema
+        }
+        Value::Object(fields) => {
+            let properties: Map<String, Value> = fields
+                .iter()
+                .map(|(k, v)| (k.clone(), example_schema_for_value(v)))
+                .collect();
+            let required: Vec<Value> = fields.keys().cloned().map(Value::String).collect();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": required,
+                "additionalProperties": false,
+            })
+        }
+    }
+}
+
+pub fn example_schema<T: Serialize>(example: &T) -> Result<Value, serde_json::Error> {
+    Ok(example_schema_for_value(&serde_json::to_value(example)?))
+}
+
+// A key or array index on the way from the root of an example to a value.
+#[derive(Debug, Clone)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+fn at_mut<'a>(value: &'a mut Value, path: &[Step]) -> Option<&'a mut Value> {
+    path.iter().try_fold(value, |value, step| match step {
+        Step::Key(key) => value.get_mut(key.as_str()),
+        Step::Index(i) => value.get_mut(*i),
+    })
+}
+
+fn round_trip<T: Serialize + DeserializeOwned>(value: Value) -> Result<Value, String> {
+    let typed: T = serde_json::from_value(value).map_err(|e| e.to_string())?;
+    serde_json::to_value(typed).map_err(|e| e.to_string())
+}
+
+// serde rejects an unknown variant with "unknown variant `x`, expected
+// one of `A`, `B`" (or "`A` or `B`"), naming every variant of the enum.
+fn variants_in(message: &str) -> Option<Vec<Value>> {
+    let (rejected, expected) = message.split_once(", expected ")?;
+    if !rejected.contains("unknown variant") {
+        return None;
+    }
+    let names: Vec<Value> = expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(|name| json!(name))
+        .collect();
+    (!names.is_empty()).then_some(names)
+}
+
+const PROBE_KEY: &str = "~probe";
+
+// Describes one example of `T` by editing it at each position and asking
+// serde whether `T` still accepts it.
+struct Prober {
+    example: Value,
+    round_trip: fn(Value) -> Result<Value, String>,
+}
+
+impl Prober {
+    fn try_with(&self, path: &[Step], edit: impl FnOnce(&mut Value)) -> Result<Value, String> {
+        let mut edited = self.example.clone();
+        edit(at_mut(&mut edited, path).expect("path taken from the example"));
+        (self.round_trip)(edited)
+    }
+
+    fn substitute(&self, path: &[Step], replacement: Value) -> Result<Value, String> {
+        self.try_with(path, |value| *value = replacement)
+    }
+
+    fn describe(&self, path: &mut Vec<Step>, value: &Value) -> Value {
+        match value {
+            Value::String(_) => match self
+                .substitute(path, json!(PROBE_KEY))
+                .err()
+                .and_then(|e| variants_in(&e))
+            {
+                Some(names) => self.describe_variants(path, None, names),
+                None => json!({ "type": "string" }),
+            },
+            Value::Array(_) | Value::Object(_) if self.holds_any_value(path) => json!({}),
+            Value::Array(items) => {
+                let mut schema = json!({ "type": "array" });
+                let merged = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| self.child(path, Step::Index(i), item))
+                    .reduce(merge);
+                if let Some(merged) = merged {
+                    schema["items"] = merged;
+                }
+                schema
+            }
+            Value::Object(fields) => {
+                if fields.len() == 1 {
+                    let probe = json!({ PROBE_KEY: null });
+                    if let Some(names) = self
+                        .substitute(path, probe)
+                        .err()
+                        .and_then(|e| variants_in(&e))
+                    {
+                        return self.describe_variants(path, fields.iter().next(), names);
+                    }
+                }
+                if self.is_map(path, fields) {
+                    let merged = fields
+                        .iter()
+                        .map(|(key, item)| self.child(path, Step::Key(key.clone()), item))
+                        .reduce(merge);
+                    return match merged {
+                        Some(values) => json!({ "type": "object", "additionalProperties": values }),
+                        None => json!({ "type": "object" }),
+                    };
+                }
+                // A field set in the example may still be an `Option`.
+                let properties: Map<String, Value> = fields
+                    .iter()
+                    .map(|(key, item)| {
+                        path.push(Step::Key(key.clone()));
+                        let mut schema = self.describe(path, item);
+                        if !item.is_null() && self.substitute(path, Value::Null).is_ok() {
+                            schema = with_null(schema);
+                        }
+                        path.pop();
+                        (key.clone(), schema)
+                    })
+                    .collect();
+                let required: Vec<Value> = fields
+                    .keys()
+                    .filter(|key| {
+                        self.try_with(path, |value| {
+                            value.as_object_mut().unwrap().remove(key.as_str());
+                        })
+                        .is_err()
+                    })
+                    .map(|key| json!(key))
+                    .collect();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+            Value::Null => self.describe_null(path),
+            scalar => example_schema_for_value(scalar),
+        }
+    }
+
+    fn child(&self, path: &mut Vec<Step>, step: Step, value: &Value) -> Value {
+        path.push(step);
+        let schema = self.describe(path, value);
+        path.pop();
+        schema
+    }
+
+    // A `serde_json::Value` field keeps whatever it is given.
+    fn holds_any_value(&self, path: &[Step]) -> bool {
+        self.substitute(path, json!(true))
+            .ok()
+            .and_then(|mut kept| at_mut(&mut kept, path).map(|v| *v == json!(true)))
+            .unwrap_or(false)
+    }
+
+    // A struct drops an unknown key on the way through serde; a map keeps
+    // it. An empty map is offered an entry of each kind of value, and one
+    // it rejects for its type rather than its name is still a map.
+    fn is_map(&self, path: &[Step], fields: &Map<String, Value>) -> bool {
+        let entries = match fields.values().next() {
+            Some(first) => vec![first.clone()],
+            None => vec![
+                json!(0),
+                json!(0.5),
+                json!(""),
+                json!(true),
+                json!({}),
+                json!([]),
+            ],
+        };
+        entries.into_iter().any(|entry| {
+            let kept = self.try_with(path, |value| {
+                value
+                    .as_object_mut()
+                    .unwrap()
+                    .insert(PROBE_KEY.to_string(), entry);
+            });
+            match kept {
+                Ok(mut kept) => at_mut(&mut kept, path).is_some_and(|v| v.get(PROBE_KEY).is_some()),
+                Err(e) => fields.is_empty() && !e.contains("unknown field"),
+            }
+        })
+    }
+
+    // A `None` field: the scalar values it would take instead.
+    fn describe_null(&self, path: &mut Vec<Step>) -> Value {
+        let mut schema = json!({ "type": "null" });
+        let accepts = |candidate: Value| self.substitute(path, candidate).is_ok();
+        if accepts(json!(0.5)) {
+            schema = merge(schema, json!({ "type": "number" }));
+        } else if accepts(json!(0)) {
+            schema = merge(schema, json!({ "type": "integer" }));
+        }
+        if accepts(json!(true)) {
+            schema = merge(schema, json!({ "type": "boolean" }));
+        }
+        match self.substitute(path, json!("")) {
+            Ok(_) => schema = merge(schema, json!({ "type": "string" })),
+            Err(e) => {
+                if let Some(names) = variants_in(&e) {
+                    schema = merge(schema, self.describe_variants(path, None, names));
+                }
+            }
+        }
+        schema
+    }
+
+    // An externally tagged enum. A unit variant serializes as its name,
+    // which is how the probe tells them apart; the variant shown with a
+    // payload is described in full and `finish` adds the others.
+    fn describe_variants(
+        &self,
+        path: &mut Vec<Step>,
+        shown: Option<(&String, &Value)>,
+        names: Vec<Value>,
+    ) -> Value {
+        let variants: Vec<(Value, bool)> = names
+            .into_iter()
+            .map(|name| {
+                let unit = self.substitute(path, name.clone()).is_ok();
+                (name, unit)
+            })
+            .collect();
+        if variants.iter().all(|(_, unit)| *unit) {
+            let names: Vec<Value> = variants.into_iter().map(|(name, _)| name).collect();
+            return json!({ "type": "string", "enum": names });
+        }
+        let options: Vec<Value> = shown
+            .map(|(name, payload)| {
+                json!({
+                    "type": "object",
+                    "properties": { name.clone(): self.child(path, Step::Key(name.clone()), payload) },
+                    "required": [name],
+                    "additionalProperties": false,
+                })
+            })
+            .into_iter()
+            .collect();
+        json!({ "anyOf": options, VARIANTS: variants })
+    }
+}
+
+// Describes `T` from its examples; each must survive a serde round trip
+// unchanged, so the probes start from a value `T` accepts.
+pub fn type_schema<T: Serialize + DeserializeOwned>(
+    examples: &[T],
+) -> Result<Value, serde_json::Error> {
+    let mut schemas = Vec::with_capacity(examples.len());
+    for example in examples {
+        let example = serde_json::to_value(example)?;
+        let prober = Prober {
+            example: example.clone(),
+            round_trip: round_trip::<T>,
+        };
+        if (prober.round_trip)(example.clone()).as_ref() != Ok(&example) {
+            return Err(serde::ser::Error::custom(
+                "example does not survive a serde round trip",
+            ));
+        }
+        schemas.push(prober.describe(&mut Vec::new(), &example));
+    }
+    Ok(finish(
+        schemas.into_iter().reduce(merge).unwrap_or(json!({})),
+    ))
+}
+
+fn type_names(schema: &Value) -> Vec<Value> {
+    match &schema["type"] {
+        Value::String(name) => vec![Value::String(name.clone())],
+        Value::Array(names) => names.clone(),
+        _ => Vec::new(),
+    }
+}
+
+fn with_null(mut schema: Value) -> Value {
+    let mut names = type_names(&schema);
+    if schema == json!({}) {
+        return schema;
+    }
+    if names.is_empty() {
+        return json!({ "anyOf": [schema, { "type": "null" }] });
+    }
+    if !names.contains(&json!("null")) {
+        names.push(json!("null"));
+    }
+    schema["type"] = Value::Array(names);
+    if let Some(Value::Array(values)) = schema.get_mut("enum") {
+        if !values.contains(&Value::Null) {
+            values.push(Value::Null);
+        }
+    }
+    schema
+}
+
+// Widens two schemas to one that accepts values of either.
+fn merge(a: Value, b: Value) -> Value {
+    if a == b {
+        return a;
+    }
+    if b == json!({ "type": "null" }) {
+        return with_null(a);
+    }
+    if a == json!({ "type": "null" }) {
+        return with_null(b);
+    }
+    if a == json!({}) || b == json!({}) {
+        return json!({});
+    }
+    let plain =
+        |s: &Value| !type_names(s).is_empty() && s.as_object().is_some_and(|o| o.len() == 1);
+    if plain(&a) && plain(&b) {
+        let mut names = type_names(&a);
+        for name in type_names(&b) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        // serde_json writes whole-valued f64 as floats, but a `u64` field
+        // next to it may not be: widen integer to number.
+        if names.contains(&json!("number")) {
+            names.retain(|n| n != "integer");
+        }
+        return match names.as_slice() {
+            [name] => json!({ "type": name }),
+            _ => json!({ "type": names }),
+        };
+    }
+    // Two examples of one enum: variants shown by either, each merged.
+    if a.get(VARIANTS).is_some() && a.get(VARIANTS) == b.get(VARIANTS) {
+        let mut shown: BTreeMap<String, Value> = BTreeMap::new();
+        for option in a["anyOf"]
+            .as_array()
+            .into_iter()
+            .chain(b["anyOf"].as_array())
+            .flatten()
+        {
+            let name = option["required"][0]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let merged = match shown.remove(&name) {
+                Some(seen) => merge(seen, option.clone()),
+                None => option.clone(),
+            };
+            shown.insert(name, merged);
+        }
+        return json!({ "anyOf": shown.into_values().collect::<Vec<_>>(), VARIANTS: a[VARIANTS] });
+    }
+    // An empty map says nothing about its values.
+    let empty_map = json!({ "type": "object" });
+    let is_map = |s: &Value| s.get("additionalProperties").is_some_and(Value::is_object);
+    if a == empty_map && is_map(&b) {
+        return b;
+    }
+    if b == empty_map && is_map(&a) {
+        return a;
+    }
+    let map_values = |s: &Value| {
+        s.get("additionalProperties")
+            .filter(|v| v.is_object())
+            .cloned()
+    };
+    if let (Some(x), Some(y)) = (map_values(&a), map_values(&b)) {
+        return json!({ "type": "object", "additionalProperties": merge(x, y) });
+    }
+    // Objects sharing fields are one struct with optional parts; disjoint
+    // ones are externally tagged enum variants and stay alternatives.
+    let shares_fields = match (
+        a.get("properties").and_then(Value::as_object),
+        b.get("properties").and_then(Value::as_object),
+    ) {
+        (Some(pa), Some(pb)) => pa.keys().any(|k| pb.contains_key(k)),
+        _ => false,
+    };
+    if shares_fields {
+        let (pa, pb) = (&a["properties"], &b["properties"]);
+        let mut keys: Vec<&String> = pa.as_object().unwrap().keys().collect();
+        keys.extend(pb.as_object().unwrap().keys());
+        keys.sort();
+        keys.dedup();
+        let required_in = |s: &Value, key: &String| {
+            s["required"]
+                .as_array()
+                .is_some_and(|r| r.contains(&json!(key)))
+        };
+        let mut properties = Map::new();
+        let mut required = Vec::new();
+        for key in keys {
+            let schema = match (pa.get(key), pb.get(key)) {
+                (Some(x), Some(y)) => merge(x.clone(), y.clone()),
+                (Some(x), None) | (None, Some(x)) => x.clone(),
+                (None, None) => unreachable!(),
+            };
+            if required_in(&a, key) && required_in(&b, key) {
+                required.push(json!(key));
+            }
+            properties.insert(key.clone(), schema);
+        }
+        let mut merged = a.clone();
+        merged["properties"] = Value::Object(properties);
+        merged["required"] = Value::Array(required);
+        return merged;
+    }
+    if a["type"] == "array" && b["type"] == "array" {
+        return match (a.get("items"), b.get("items")) {
+            (Some(x), Some(y)) => json!({ "type": "array", "items": merge(x.clone(), y.clone()) }),
+            (Some(_), None) => a,
+            _ => b,
+        };
+    }
+    let mut options = Vec::new();
+    for schema in [a, b] {
+        match schema.get("anyOf").and_then(Value::as_array) {
+            Some(inner) if schema.get(VARIANTS).is_none() => options.extend(inner.iter().cloned()),
+            _ => options.push(schema),
+        }
+    }
+    options.sort_by_cached_key(Value::to_string);
+    options.dedup();
+    json!({ "anyOf": options })
+}
+
+// Every variant of an externally tagged enum, held beside the variants the
+// examples show until `finish` adds the rest.
+const VARIANTS: &str = "x-variants";
+
+// Lets through the variants no example showed, by name, with any payload.
+fn finish(schema: Value) -> Value {
+    match schema {
+        Value::Object(mut fields) => {
+            let variants = fields.remove(VARIANTS);
+            let mut schema: Map<String, Value> = fields
+                .into_iter()
+                .map(|(key, value)| match key.as_str() {
+                    "properties" => {
+                        let properties = value.as_object().cloned().unwrap_or_default();
+                        let finished = properties
+                            .into_iter()
+                            .map(|(name, property)| (name, finish(property)))
+                            .collect();
+                        (key, Value::Object(finished))
+                    }
+                    "items" | "additionalProperties" => (key, finish(value)),
+                    "anyOf" => {
+                        let options = value.as_array().cloned().unwrap_or_default();
+                        (key, Value::Array(options.into_iter().map(finish).collect()))
+                    }
+                    _ => (key, value),
+                })
+                .collect();
+            if let Some(variants) = variants {
+                let variants: Vec<(Value, bool)> =
+                    serde_json::from_value(variants).expect("written by describe_variants");
+                let options = schema["anyOf"]
+                    .as_array_mut()
+                    .expect("variants are alternatives");
+                let (units, data): (Vec<_>, Vec<_>) =
+                    variants.into_iter().partition(|(_, unit)| *unit);
+                let units: Vec<Value> = units.into_iter().map(|(name, _)| name).collect();
+                let unseen: Vec<Value> = data
+                    .into_iter()
+                    .map(|(name, _)| name)
+                    .filter(|name| !options.iter().any(|o| o["required"][0] == *name))
+                    .collect();
+                if !units.is_empty() {
+                    options.push(json!({ "type": "string", "enum": units }));
+                }
+                if !unseen.is_empty() {
+                    options.push(json!({
+                        "type": "object",
+                        "propertyNames": { "enum": unseen },
+                        "minProperties": 1,
+                        "maxProperties": 1,
+                    }));
+                }
+            }
+            Value::Object(schema)
+        }
+        other => other,
+    }
+}
+
+// A named collection of schemas, emitted as one document under `$defs`.
+#[derive(Debug, Clone, Default)]
+pub struct SchemaBundle {
+    pub title: String,
+    pub definitions: BTreeMap<String, Value>,
+}
+
+impl SchemaBundle {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            definitions: BTreeMap::new(),
+        }
+    }
+
+    // Merges the schemas of every example; one value of an enum is enough
+    // to name its variants, but list an example of each data variant and
+    // of each optional field set to describe their contents.
+    pub fn add_examples<T: Serialize + DeserializeOwned>(
+        &mut self,
+        name: &str,
+        examples: &[T],
+    ) -> Result<&mut Self, serde_json::Error> {
+        self.definitions
+            .insert(name.to_string(), type_schema(examples)?);
+        Ok(self)
+    }
+
+    pub fn to_value(&self) -> Value {
+        json!({
+            "$schema": JSON_SCHEMA_DIALECT,
+            "title": self.title,
+            "description": format!("human_biology {} serde data model", crate::VERSION),
+            "$defs": self.definitions,
+        })
+    }
+
+    pub fn to_string_pretty(&self) -> String {
+        serde_json::to_string_pretty(&self.to_value()).expect("schema is plain JSON")
+    }
+}
+
+const PRESETS: [PresetType; 10] = [
+    PresetType::AdultMaleHealthy,
+    PresetType::AdultFemaleHealthy,
+    PresetType::AdultMaleAthlete,
+    PresetType::AdultFemaleAthlete,
+    PresetType::AdultMaleObesity,
+    PresetType::AdultFemaleObesity,
+    PresetType::ElderlyMaleHealthy,
+    PresetType::ElderlyFemaleHealthy,
+    PresetType::YoungAdultMaleHealthy,
+    PresetType::YoungAdultFemaleHealthy,
+];
+
+const EXAMPLE_SCENARIO: &str = include_str!("../../config_examples/exercise_in_heat_scenario.toml");
+
+// The input-facing types a scenario or web form is built from, and the
+// snapshots and run records a tool reads back.
+pub fn public_schema() -> Result<SchemaBundle, Box<dyn std::error::Error>> {
+    let scenario = Scenario::from_toml_str(EXAMPLE_SCENARIO)?;
+    let mut engine = scenario.build()?.engine;
+    engine.step()?;
+    let run = RunRecord {
+        id: 1,
+        scenario: scenario.name.clone(),
+        scenario_hash: "0".repeat(16),
+        parameters: [
+            ("fasted".to_string(), ParamValue::Flag(true)),
+            ("dose_mg".to_string(), ParamValue::Number(5.0)),
+            ("arm".to_string(), ParamValue::Text("control".to_string())),
+        ]
+        .into(),
+        metrics: [("peak_core_c".to_string(), 38.2)].into(),
+        artifacts: vec!["runs/1/series.csv".into()],
+        status: RunStatus::Completed,
+        created_unix_s: 0,
+    };
+    let mut bundle = SchemaBundle::new("human_biology");
+    bundle
+        .add_examples(
+            "BiologicalSex",
+            &[BiologicalSex::Male, BiologicalSex::Female],
+        )?
+        .add_examples("PresetType", &PRESETS)?
+        .add_examples("HumanPreset", &PRESETS.map(HumanPreset::from_preset_type))?
+        .add_examples(
+            "BaselineHumanParams",
+            &[
+                BaselineHumanParams::adult_male_default(),
+                BaselineHumanParams::adult_female_default(),
+            ],
+        )?
+        .add_examples(
+            "Human",
+            &[
+                Human::new(BiologicalSex::Female, 40.0, 165.0, 62.0)?,
+                Human::new(BiologicalSex::Male, 40.0, 178.0, 80.0)?,
+            ],
+        )?
+        .add_examples(
+            "ParameterDistribution",
+            &[
+                ParameterDistribution::Fixed(1.0),
+                ParameterDistribution::Uniform {
+                    low: 0.0,
+                    high: 1.0,
+                },
+                ParameterDistribution::Normal { mean: 0.0, sd: 1.0 },
+                ParameterDistribution::LogNormal {
+                    median: 1.0,
+                    geometric_sd: 1.5,
+                },
+                ParameterDistribution::Triangular {
+                    low: 0.0,
+                    mode: 0.5,
+                    high: 1.0,
+                },
+            ],
+        )?
+        .add_examples(
+            "PopulationSpec",
+            &[
+                PopulationSpec::us_adult(),
+                PopulationSpec::postmenopausal_women(),
+            ],
+        )?
+        .add_examples("CohortConfig", &[CohortConfig::new(100, 1)])?
+        .add_examples(
+            "CheckpointPolicy",
+            &[CheckpointPolicy::new("checkpoints", 100)],
+        )?
+        .add_examples("RunStatus", &[RunStatus::Completed, RunStatus::Failed])?
+        .add_examples("GranulomaParameters", &[GranulomaParameters::default()])?
+        .add_examples("Scenario", &[scenario])?
+        .add_examples("EngineSnapshot", &[engine.snapshot()])?
+        .add_examples("RunRecord", &[run])?
+        .add_examples(
+            "CellType",
+            &[
+                CellType::Vertex,
+                CellType::Line,
+                CellType::Triangle,
+                CellType::Quad,
+                CellType::Tetra,
+                CellType::Hexahedron,
+            ],
+        )?;
+    Ok(bundle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inferred_object_schema() {
+        let schema = example_schema(&json!({ "dose_mg": 5.0, "count": 3, "label": null })).unwrap();
+        assert_eq!(schema["type"], "object");
+        assert_eq!(schema["properties"]["dose_mg"]["type"], "number");
+        assert_eq!(schema["properties"]["count"]["type"], "integer");
+        assert_eq!(schema["required"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_examples_merge_into_nullable_and_variant_schemas() {
+        let merged = merge(
+            example_schema_for_value(&json!({ "a": 1.0, "b": "x" })),
+            example_schema_for_value(&json!({ "a": null })),
+        );
+        assert_eq!(merged["properties"]["a"]["type"], json!(["number", "null"]));
+        assert_eq!(merged["required"], json!(["a"]));
+
+        let mut bundle = SchemaBundle::new("test");
+        bundle
+            .add_examples("Sex", &[BiologicalSex::Male, BiologicalSex::Female])
+            .unwrap();
+        assert_eq!(
+            bundle.definitions["Sex"],
+            json!({ "type": "string", "enum": ["Male", "Female"] })
+        );
+    }
+
+    #[test]
+    fn test_alternatives_merge_in_a_fixed_order() {
+        let (a, b, c) = (json!({ "a": 1 }), json!("x"), json!([true]));
+        let schemas = [&a, &b, &c].map(example_schema_for_value);
+        let forward = merge(
+            merge(schemas[0].clone(), schemas[1].clone()),
+            schemas[2].clone(),
+        );
+        let backward = merge(
+            schemas[2].clone(),
+            merge(schemas[1].clone(), schemas[0].clone()),
+        );
+        assert_eq!(forward, backward);
+        let repeated = merge(
+            forward.clone(),
+            merge(schemas[1].clone(), schemas[0].clone()),
+        );
+        assert_eq!(repeated, forward);
+    }
+
+    #[test]
+    fn test_one_example_names_every_variant() {
+        let schema = type_schema(&[BiologicalSex::Female]).unwrap();
+        assert_eq!(
+            schema,
+            json!({ "type": "string", "enum": ["Male", "Female"] })
+        );
+
+        let schema = type_schema(&[ParameterDistribution::Fixed(1.0)]).unwrap();
+        let options = schema["anyOf"].as_array().unwrap();
+        assert_eq!(options[0]["required"], json!(["Fixed"]));
+        assert_eq!(
+            options[1]["propertyNames"]["enum"],
+            json!(["Uniform", "Normal", "LogNormal", "Triangular"])
+        );
+        assert!(!schema.to_string().contains(VARIANTS));
+    }
+
+    #[test]
+    fn test_defaulted_fields_optional_and_maps_open() {
+        let scenario = Scenario::from_toml_str(EXAMPLE_SCENARIO).unwrap();
+        let schema = type_schema(&[scenario]).unwrap();
+        assert_eq!(schema["required"], json!(["simulation"]));
+        let simulation = &schema["properties"]["simulation"];
+        assert_eq!(
+            simulation["required"],
+            json!(["dt_minutes", "duration_minutes"])
+        );
+        assert_eq!(
+            simulation["properties"]["worker_threads"]["type"],
+            json!(["integer", "null"])
+        );
+        let entity = &schema["properties"]["entities"]["additionalProperties"];
+        assert_eq!(entity["required"], json!(["model"]));
+        assert_eq!(
+            entity["properties"]["inputs"],
+            json!({ "type": "object", "additionalProperties": { "type": "number" } })
+        );
+    }
+
+    #[test]
+    fn test_public_schema_covers_scenarios_snapshots_and_runs() {
+        let document = public_schema().unwrap().to_value();
+        assert_eq!(document["$schema"], JSON_SCHEMA_DIALECT);
+        let defs = document["$defs"].as_object().unwrap();
+        for name in ["Human", "Scenario", "EngineSnapshot", "RunRecord"] {
+            assert!(defs.contains_key(name), "{} missing", name);
+        }
+        let variants = defs["ParameterDistribution"]["anyOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 5);
+        let human = &defs["Human"]["properties"];
+        assert_eq!(human["age_years"]["type"], "number");
+        assert_eq!(human["sex"]["enum"], json!(["Male", "Female"]));
+        let state = &defs["EngineSnapshot"]["properties"]["entities"]["additionalProperties"];
+        assert_eq!(state["properties"]["state"], json!({}));
+        let parameter = &defs["RunRecord"]["properties"]["parameters"]["additionalProperties"];
+        assert_eq!(parameter["type"].as_array().unwrap().len(), 3);
+    }
+}
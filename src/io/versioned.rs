@@ -0,0 +1,245 @@
+use crate::config::BaselineHumanParams;
+use crate::organism::Human;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+// Upgrades the serialized form of one version to the next.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+// A type whose saved JSON carries a version tag. Each time a field or
+// variant changes incompatibly, bump `VERSION` and append the migration
+// from the previous layout: `migrations()[i]` upgrades version i + 1 to
+// i + 2, so data written by any older release loads through the chain.
+pub trait Versioned: Serialize + DeserializeOwned {
+    const TYPE_NAME: &'static str;
+    const VERSION: u32;
+
+    fn migrations() -> Vec<Migration> {
+        Vec::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Envelope {
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub version: u32,
+    pub data: Value,
+}
+
+impl Envelope {
+    // Files written before versioning are bare JSON and count as version 1.
+    fn unwrap_for(value: Value, type_name: &str) -> Result<(u32, Value), String> {
+        let is_envelope = value.as_object().is_some_and(|o| {
+            o.len() == 3
+                && o.contains_key("type")
+                && o.contains_key("version")
+                && o.contains_key("data")
+        });
+        if !is_envelope {
+            return Ok((1, value));
+        }
+        let envelope: Envelope = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        if envelope.type_name != type_name {
+            return Err(format!(
+                "expected a {} document, found {}",
+                type_name, envelope.type_name
+            ));
+        }
+        Ok((envelope.version, envelope.data))
+    }
+}
+
+pub fn migrate<T: Versioned>(mut data: Value, from_version: u32) -> Result<Value, String> {
+    if from_version == 0 || from_version > T::VERSION {
+        return Err(format!(
+            "{} version {} is not supported (this build reads 1 to {})",
+            T::TYPE_NAME,
+            from_version,
+            T::VERSION
+        ));
+    }
+    let migrations = T::migrations();
+    if migrations.len() + 1 < T::VERSION as usize {
+        return Err(format!(
+            "{} is at version {} but has only {} migrations",
+            T::TYPE_NAME,
+            T::VERSION,
+            migrations.len()
+        ));
+    }
+    for migration in &migrations[(from_version - 1) as usize..(T::VERSION - 1) as usize] {
+        data = migration(data)?;
+    }
+    Ok(data)
+}
+
+pub fn to_versioned_value<T: Versioned>(value: &T) -> Result<Value, serde_json::Error> {
+    serde_json::to_value(Envelope {
+        type_name: T::TYPE_NAME.to_string(),
+        version: T::VERSION,
+        data: serde_json::to_value(value)?,
+    })
+}
+
+pub fn to_versioned_string<T: Versioned>(value: &T) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&to_versioned_value(value)?)
+}
+
+// Current-layout data from an envelope or bare JSON of any supported
+// version, for callers that deserialize it themselves.
+pub fn upgrade<T: Versioned>(value: Value) -> Result<Value, String> {
+    let (version, data) = Envelope::unwrap_for(value, T::TYPE_NAME)?;
+    migrate::<T>(data, version)
+}
+
+pub fn from_versioned_value<T: Versioned>(value: Value) -> Result<T, Box<dyn std::error::Error>> {
+    Ok(serde_json::from_value(upgrade::<T>(value)?)?)
+}
+
+pub fn from_versioned_str<T: Versioned>(json: &str) -> Result<T, Box<dyn std::error::Error>> {
+    from_versioned_value(serde_json::from_str(json)?)
+}
+
+// Building blocks for migrations. Each walks one object level; compose them
+// with `fields_mut` for nested structs.
+
+pub fn fields_mut<'a>(
+    value: &'a mut Value,
+    path: &[&str],
+) -> Result<&'a mut Map<String, Value>, String> {
+    let mut current = value;
+    for key in path {
+        current = current
+            .get_mut(*key)
+            .ok_or_else(|| format!("missing field {}", key))?;
+    }
+    current
+        .as_object_mut()
+        .ok_or_else(|| format!("{} is not an object", path.join(".")))
+}
+
+pub fn rename_field(fields: &mut Map<String, Value>, from: &str, to: &str) {
+    if let Some(value) = fields.remove(from) {
+        fields.insert(to.to_string(), value);
+    }
+}
+
+pub fn add_field(fields: &mut Map<String, Value>, name: &str, default: Value) {
+    fields.entry(name.to_string()).or_insert(default);
+}
+
+// Unit variants serialize as strings, data-carrying ones as single-key
+// objects; both are renamed.
+pub fn rename_variant(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::String(name) if name == from => *name = to.to_string(),
+        Value::Object(map) if map.len() == 1 && map.contains_key(from) => {
+            let inner = map.remove(from).unwrap_or(Value::Null);
+            map.insert(to.to_string(), inner);
+        }
+        _ => {}
+    }
+}
+
+impl Versioned for Human {
+    const TYPE_NAME: &'static str = "Human";
+    const VERSION: u32 = 1;
+}
+
+impl Versioned for BaselineHumanParams {
+    const TYPE_NAME: &'static str = "BaselineHumanParams";
+    const VERSION: u32 = 1;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::cardiovascular::hematology::BiologicalSex;
+    use serde_json::json;
+
+    // Version 1 stored `dose` in mg and a `Tablet` variant; version 2 renamed
+    // the field; version 3 renamed the variant and added a route.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Form {
+        OralTablet,
+        Infusion { hours: f64 },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Prescription {
+        dose_mg: f64,
+        form: Form,
+        route: String,
+    }
+
+    impl Versioned for Prescription {
+        const TYPE_NAME: &'static str = "Prescription";
+        const VERSION: u32 = 3;
+
+        fn migrations() -> Vec<Migration> {
+            vec![
+                |mut v| {
+                    rename_field(fields_mut(&mut v, &[])?, "dose", "dose_mg");
+                    Ok(v)
+                },
+                |mut v| {
+                    let fields = fields_mut(&mut v, &[])?;
+                    if let Some(form) = fields.get_mut("form") {
+                        rename_variant(form, "Tablet", "OralTablet");
+                    }
+                    add_field(fields, "route", json!("oral"));
+                    Ok(v)
+                },
+            ]
+        }
+    }
+
+    #[test]
+    fn test_old_documents_migrate_to_current() {
+        let v1 = json!({ "type": "Prescription", "version": 1, "data": { "dose": 5.0, "form": "Tablet" } });
+        let loaded: Prescription = from_versioned_value(v1).unwrap();
+        assert_eq!(
+            loaded,
+            Prescription {
+                dose_mg: 5.0,
+                form: Form::OralTablet,
+                route: "oral".to_string()
+            }
+        );
+        // Bare, pre-versioning JSON is read as version 1.
+        let bare = json!({ "dose": 2.0, "form": { "Infusion": { "hours": 1.0 } } });
+        let loaded: Prescription = from_versioned_value(bare).unwrap();
+        assert_eq!(loaded.form, Form::Infusion { hours: 1.0 });
+    }
+
+    #[test]
+    fn test_current_round_trip_and_rejections() {
+        let current = Prescription {
+            dose_mg: 1.0,
+            form: Form::OralTablet,
+            route: "oral".to_string(),
+        };
+        let json = to_versioned_string(&current).unwrap();
+        assert!(json.contains("\"version\": 3"));
+        assert_eq!(from_versioned_str::<Prescription>(&json).unwrap(), current);
+
+        let future = json!({ "type": "Prescription", "version": 4, "data": {} });
+        let message = from_versioned_value::<Prescription>(future)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("version 4"));
+        let wrong = json!({ "type": "Human", "version": 1, "data": {} });
+        assert!(from_versioned_value::<Prescription>(wrong).is_err());
+    }
+
+    #[test]
+    fn test_human_round_trips_through_envelope() {
+        let human = Human::new(BiologicalSex::Male, 30.0, 180.0, 75.0).unwrap();
+        let restored: Human = from_versioned_str(&to_versioned_string(&human).unwrap()).unwrap();
+        assert_eq!(restored.weight_kg, 75.0);
+        let bare: Human = from_versioned_str(&serde_json::to_string(&human).unwrap()).unwrap();
+        assert_eq!(bare.height_cm, 180.0);
+    }
+}
@@ -0,0 +1,534 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::immune::granuloma::GranulomaModel;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+// Writers for the VTK legacy (.vtk) and XML (.vti, .vtu) file formats, as
+// opened by ParaView and VisIt. Data are written as ASCII Float64.
+// Schroeder W, Martin K, Lorensen B (2006) The Visualization Toolkit, 4th ed.,
+// Kitware, ch. 19 "VTK File Formats"
+
+// Scalars (1 component), vectors (3) or symmetric/full tensors (9, row-major)
+// attached to points or cells.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataArray {
+    pub name: String,
+    pub components: usize,
+    pub values: Vec<f64>,
+}
+
+impl DataArray {
+    pub fn scalars(name: &str, values: Vec<f64>) -> Self {
+        Self {
+            name: name.to_string(),
+            components: 1,
+            values,
+        }
+    }
+
+    pub fn vectors(name: &str, values: &[[f64; 3]]) -> Self {
+        Self {
+            name: name.to_string(),
+            components: 3,
+            values: values.iter().flatten().copied().collect(),
+        }
+    }
+
+    pub fn tensors(name: &str, values: &[[[f64; 3]; 3]]) -> Self {
+        Self {
+            name: name.to_string(),
+            components: 9,
+            values: values.iter().flatten().flatten().copied().collect(),
+        }
+    }
+
+    pub fn tuples(&self) -> usize {
+        self.values.len() / self.components.max(1)
+    }
+
+    fn validate(&self, expected_tuples: usize) -> BiologyResult<()> {
+        if self.components == 0
+            || !self.values.len().is_multiple_of(self.components)
+            || self.tuples() != expected_tuples
+        {
+            return Err(BiologyError::InvalidValue(format!(
+                "array {} has {} values; expected {} tuples of {}",
+                self.name,
+                self.values.len(),
+                expected_tuples,
+                self.components
+            )));
+        }
+        if self.name.is_empty() || self.name.contains(char::is_whitespace) {
+            return Err(BiologyError::InvalidValue(format!(
+                "array name '{}' must be non-empty without whitespace",
+                self.name
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellType {
+    Vertex,
+    Line,
+    Triangle,
+    Quad,
+    Tetra,
+    Hexahedron,
+}
+
+impl CellType {
+    pub fn vtk_id(&self) -> u8 {
+        match self {
+            CellType::Vertex => 1,
+            CellType::Line => 3,
+            CellType::Triangle => 5,
+            CellType::Quad => 9,
+            CellType::Tetra => 10,
+            CellType::Hexahedron => 12,
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        match self {
+            CellType::Vertex => 1,
+            CellType::Line => 2,
+            CellType::Triangle => 3,
+            CellType::Quad => 4,
+            CellType::Tetra => 4,
+            CellType::Hexahedron => 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cell {
+    pub kind: CellType,
+    pub nodes: Vec<usize>,
+}
+
+// Regular grid with point-centred fields: reaction-diffusion lattices and
+// voxelised microstructure. Points run x fastest, then y, then z.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImageData {
+    pub dimensions: [usize; 3],
+    pub origin: [f64; 3],
+    pub spacing: [f64; 3],
+    pub point_data: Vec<DataArray>,
+}
+
+impl ImageData {
+    pub fn new(dimensions: [usize; 3], spacing: [f64; 3]) -> Self {
+        Self {
+            dimensions,
+            origin: [0.0; 3],
+            spacing,
+            point_data: Vec::new(),
+        }
+    }
+
+    pub fn point_count(&self) -> usize {
+        self.dimensions.iter().product()
+    }
+
+    pub fn with_point_data(mut self, array: DataArray) -> BiologyResult<Self> {
+        array.validate(self.point_count())?;
+        self.point_data.push(array);
+        Ok(self)
+    }
+
+    pub fn to_legacy(&self, title: &str) -> String {
+        let [nx, ny, nz] = self.dimensions;
+        let mut out = legacy_header(title);
+        out.push_str("DATASET STRUCTURED_POINTS\n");
+        let _ = writeln!(out, "DIMENSIONS {} {} {}", nx, ny, nz);
+        let _ = writeln!(out, "ORIGIN {}", join(&self.origin));
+        let _ = writeln!(out, "SPACING {}", join(&self.spacing));
+        legacy_attributes(&mut out, "POINT_DATA", self.point_count(), &self.point_data);
+        out
+    }
+
+    pub fn to_xml(&self) -> String {
+        let [nx, ny, nz] = self.dimensions;
+        let extent = format!(
+            "0 {} 0 {} 0 {}",
+            nx.saturating_sub(1),
+            ny.saturating_sub(1),
+            nz.saturating_sub(1)
+        );
+        let mut out = xml_header("ImageData");
+        let _ = writeln!(
+            out,
+            "  <ImageData WholeExtent=\"{}\" Origin=\"{}\" Spacing=\"{}\">",
+            extent,
+            join(&self.origin),
+            join(&self.spacing)
+        );
+        let _ = writeln!(out, "    <Piece Extent=\"{}\">", extent);
+        xml_attribute_block(&mut out, "PointData", &self.point_data);
+        out.push_str("    </Piece>\n  </ImageData>\n</VTKFile>\n");
+        out
+    }
+
+    pub fn write_legacy<P: AsRef<Path>>(&self, path: P, title: &str) -> std::io::Result<()> {
+        fs::write(path, self.to_legacy(title))
+    }
+
+    pub fn write_xml<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_xml())
+    }
+}
+
+// Points with explicit cells: FEM meshes (tetrahedra, hexahedra) and agent
+// populations (one vertex cell per agent).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnstructuredGrid {
+    pub points: Vec<[f64; 3]>,
+    pub cells: Vec<Cell>,
+    pub point_data: Vec<DataArray>,
+    pub cell_data: Vec<DataArray>,
+}
+
+impl UnstructuredGrid {
+    pub fn new(points: Vec<[f64; 3]>, cells: Vec<Cell>) -> BiologyResult<Self> {
+        for (i, cell) in cells.iter().enumerate() {
+            if cell.nodes.len() != cell.kind.node_count() {
+                return Err(BiologyError::InvalidValue(format!(
+                    "cell {} is a {:?} with {} nodes",
+                    i,
+                    cell.kind,
+                    cell.nodes.len()
+                )));
+            }
+            if let Some(&node) = cell.nodes.iter().find(|&&n| n >= points.len()) {
+                return Err(BiologyError::InvalidValue(format!(
+                    "cell {} references point {} of {}",
+                    i,
+                    node,
+                    points.len()
+                )));
+            }
+        }
+        Ok(Self {
+            points,
+            cells,
+            point_data: Vec::new(),
+            cell_data: Vec::new(),
+        })
+    }
+
+    // One vertex cell per point, so glyph and point-gaussian filters work.
+    pub fn point_cloud(points: Vec<[f64; 3]>) -> Self {
+        let cells = (0..points.len())
+            .map(|i| Cell {
+                kind: CellType::Vertex,
+                nodes: vec![i],
+            })
+            .collect();
+        Self {
+            points,
+            cells,
+            point_data: Vec::new(),
+            cell_data: Vec::new(),
+        }
+    }
+
+    pub fn with_point_data(mut self, array: DataArray) -> BiologyResult<Self> {
+        array.validate(self.points.len())?;
+        self.point_data.push(array);
+        Ok(self)
+    }
+
+    pub fn with_cell_data(mut self, array: DataArray) -> BiologyResult<Self> {
+        array.validate(self.cells.len())?;
+        self.cell_data.push(array);
+        Ok(self)
+    }
+
+    pub fn to_legacy(&self, title: &str) -> String {
+        let mut out = legacy_header(title);
+        out.push_str("DATASET UNSTRUCTURED_GRID\n");
+        let _ = writeln!(out, "POINTS {} double", self.points.len());
+        for point in &self.points {
+            let _ = writeln!(out, "{}", join(point));
+        }
+        let size: usize = self.cells.iter().map(|c| 1 + c.nodes.len()).sum();
+        let _ = writeln!(out, "CELLS {} {}", self.cells.len(), size);
+        for cell in &self.cells {
+            let _ = writeln!(out, "{} {}", cell.nodes.len(), join(&cell.nodes));
+        }
+        let _ = writeln!(out, "CELL_TYPES {}", self.cells.len());
+        for cell in &self.cells {
+            let _ = writeln!(out, "{}", cell.kind.vtk_id());
+        }
+        legacy_attributes(&mut out, "POINT_DATA", self.points.len(), &self.point_data);
+        legacy_attributes(&mut out, "CELL_DATA", self.cells.len(), &self.cell_data);
+        out
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut out = xml_header("UnstructuredGrid");
+        out.push_str("  <UnstructuredGrid>\n");
+        let _ = writeln!(
+            out,
+            "    <Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">",
+            self.points.len(),
+            self.cells.len()
+        );
+        xml_attribute_block(&mut out, "PointData", &self.point_data);
+        xml_attribute_block(&mut out, "CellData", &self.cell_data);
+        out.push_str("      <Points>\n");
+        let coordinates: Vec<f64> = self.points.iter().flatten().copied().collect();
+        xml_data_array(&mut out, "Float64", "Points", 3, &join(&coordinates));
+        out.push_str("      </Points>\n      <Cells>\n");
+        let connectivity: Vec<usize> = self.cells.iter().flat_map(|c| c.nodes.clone()).collect();
+        let offsets: Vec<usize> = self
+            .cells
+            .iter()
+            .scan(0, |end, c| {
+                *end += c.nodes.len();
+                Some(*end)
+            })
+            .collect();
+        let types: Vec<u8> = self.cells.iter().map(|c| c.kind.vtk_id()).collect();
+        xml_data_array(&mut out, "Int64", "connectivity", 1, &join(&connectivity));
+        xml_data_array(&mut out, "Int64", "offsets", 1, &join(&offsets));
+        xml_data_array(&mut out, "UInt8", "types", 1, &join(&types));
+        out.push_str("      </Cells>\n    </Piece>\n  </UnstructuredGrid>\n</VTKFile>\n");
+        out
+    }
+
+    pub fn write_legacy<P: AsRef<Path>>(&self, path: P, title: &str) -> std::io::Result<()> {
+        fs::write(path, self.to_legacy(title))
+    }
+
+    pub fn write_xml<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        fs::write(path, self.to_xml())
+    }
+}
+
+fn join<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Legacy 3.0 rather than 5.1: its CELLS layout is the one every ParaView
+// release reads.
+fn legacy_header(title: &str) -> String {
+    // The title line is limited to 256 characters and may not break.
+    let title: String = title.replace(['\n', '\r'], " ").chars().take(255).collect();
+    format!("# vtk DataFile Version 3.0\n{}\nASCII\n", title)
+}
+
+fn legacy_attributes(out: &mut String, section: &str, count: usize, arrays: &[DataArray]) {
+    if arrays.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "{} {}", section, count);
+    for array in arrays {
+        match array.components {
+            1 => {
+                let _ = writeln!(out, "SCALARS {} double 1\nLOOKUP_TABLE default", array.name);
+            }
+            3 => {
+                let _ = writeln!(out, "VECTORS {} double", array.name);
+            }
+            9 => {
+                let _ = writeln!(out, "TENSORS {} double", array.name);
+            }
+            n => {
+                let _ = writeln!(
+                    out,
+                    "FIELD FieldData 1\n{} {} {} double",
+                    array.name,
+                    n,
+                    array.tuples()
+                );
+            }
+        }
+        // Tensors are laid out one 3x3 row per line, as ParaView writes them.
+        let row = if array.components == 9 {
+            3
+        } else {
+            array.components
+        };
+        for tuple in array.values.chunks(row) {
+            let _ = writeln!(out, "{}", join(tuple));
+        }
+    }
+}
+
+fn xml_header(kind: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\"?>\n<VTKFile type=\"{}\" version=\"1.0\" byte_order=\"LittleEndian\" header_type=\"UInt64\">\n",
+        kind
+    )
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_data_array(out: &mut String, kind: &str, name: &str, components: usize, body: &str) {
+    let _ = writeln!(
+        out,
+        "        <DataArray type=\"{}\" Name=\"{}\" NumberOfComponents=\"{}\" format=\"ascii\">{}</DataArray>",
+        kind,
+        xml_escape(name),
+        components,
+        body
+    );
+}
+
+fn xml_attribute_block(out: &mut String, tag: &str, arrays: &[DataArray]) {
+    let _ = writeln!(out, "      <{}>", tag);
+    for array in arrays {
+        xml_data_array(
+            out,
+            "Float64",
+            &array.name,
+            array.components,
+            &join(&array.values),
+        );
+    }
+    let _ = writeln!(out, "      </{}>", tag);
+}
+
+// Lattice fields of the granuloma agent model on a unit-spaced grid.
+impl From<&GranulomaModel> for ImageData {
+    fn from(model: &GranulomaModel) -> Self {
+        let grid = ImageData::new([model.width, model.height, 1], [1.0, 1.0, 1.0]);
+        [
+            ("antigen", &model.antigen),
+            ("chemokine", &model.chemokine),
+            ("collagen", &model.collagen),
+        ]
+        .into_iter()
+        .try_fold(grid, |grid, (name, values)| {
+            grid.with_point_data(DataArray::scalars(name, values.clone()))
+        })
+        .expect("lattice fields match the grid")
+    }
+}
+
+// Macrophage agents as points at their lattice sites.
+pub fn macrophage_agents(model: &GranulomaModel) -> UnstructuredGrid {
+    let points = model
+        .macrophages
+        .iter()
+        .map(|m| [m.x as f64, m.y as f64, 0.0])
+        .collect();
+    let engaged = model.macrophages.iter().map(|m| m.engaged_hours).collect();
+    UnstructuredGrid::point_cloud(points)
+        .with_point_data(DataArray::scalars("engaged_hours", engaged))
+        .expect("one value per agent")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::immune::granuloma::{AntigenDepot, DepotKind, GranulomaParameters};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn tetrahedron() -> UnstructuredGrid {
+        let points = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+        ];
+        let cell = Cell {
+            kind: CellType::Tetra,
+            nodes: vec![0, 1, 2, 3],
+        };
+        UnstructuredGrid::new(points, vec![cell]).unwrap()
+    }
+
+    #[test]
+    fn test_legacy_mesh_with_stress_tensor() {
+        let stress = [[1.0, 0.5, 0.0], [0.5, 2.0, 0.0], [0.0, 0.0, 3.0]];
+        let mesh = tetrahedron()
+            .with_cell_data(DataArray::tensors("stress_MPa", &[stress]))
+            .unwrap()
+            .with_point_data(DataArray::vectors("displacement", &[[0.0; 3]; 4]))
+            .unwrap();
+        let text = mesh.to_legacy("femur\nsegment");
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "# vtk DataFile Version 3.0");
+        assert_eq!(lines[1], "femur segment");
+        assert!(text.contains("CELLS 1 5\n4 0 1 2 3\nCELL_TYPES 1\n10\n"));
+        assert!(text.contains("POINT_DATA 4\nVECTORS displacement double\n"));
+        assert!(text.contains("CELL_DATA 1\nTENSORS stress_MPa double\n1 0.5 0\n0.5 2 0\n0 0 3\n"));
+    }
+
+    #[test]
+    fn test_xml_unstructured_offsets_and_types() {
+        let xml = tetrahedron().to_xml();
+        assert!(xml.contains("type=\"UnstructuredGrid\""));
+        assert!(xml.contains("NumberOfPoints=\"4\" NumberOfCells=\"1\""));
+        assert!(xml.contains("Name=\"offsets\" NumberOfComponents=\"1\" format=\"ascii\">4<"));
+        assert!(xml.contains("Name=\"types\" NumberOfComponents=\"1\" format=\"ascii\">10<"));
+        assert!(xml.ends_with("</VTKFile>\n"));
+    }
+
+    #[test]
+    fn test_invalid_geometry_and_arrays_rejected() {
+        let bad_nodes = Cell {
+            kind: CellType::Triangle,
+            nodes: vec![0, 1],
+        };
+        assert!(UnstructuredGrid::new(vec![[0.0; 3]; 3], vec![bad_nodes]).is_err());
+        let out_of_range = Cell {
+            kind: CellType::Line,
+            nodes: vec![0, 7],
+        };
+        assert!(UnstructuredGrid::new(vec![[0.0; 3]; 3], vec![out_of_range]).is_err());
+        let grid = ImageData::new([2, 2, 1], [1.0; 3]);
+        assert!(grid
+            .clone()
+            .with_point_data(DataArray::scalars("c", vec![0.0; 3]))
+            .is_err());
+        assert!(grid
+            .with_point_data(DataArray::scalars("two words", vec![0.0; 4]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_granuloma_fields_and_agents_export() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let depot = AntigenDepot::new(DepotKind::Mycobacterial, 3, 1.0).unwrap();
+        let mut model =
+            GranulomaModel::new(GranulomaParameters::default(), 21, depot, 0.02, &mut rng).unwrap();
+        model.run_days(1, &mut rng);
+
+        let image = ImageData::from(&model);
+        assert_eq!(image.point_count(), 21 * 21);
+        let vti = image.to_xml();
+        assert!(vti.contains("WholeExtent=\"0 20 0 20 0 0\""));
+        assert!(vti.contains("Name=\"chemokine\""));
+        let legacy = image.to_legacy("granuloma");
+        assert!(legacy.contains("DIMENSIONS 21 21 1\n"));
+        assert_eq!(legacy.matches("LOOKUP_TABLE default").count(), 3);
+
+        let agents = macrophage_agents(&model);
+        assert_eq!(agents.points.len(), model.macrophages.len());
+        let path = std::env::temp_dir().join(format!("agents_{}.vtu", std::process::id()));
+        agents.write_xml(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), agents.to_xml());
+        fs::remove_file(&path).unwrap();
+        assert!(agents
+            .to_legacy("agents")
+            .contains(&format!("CELL_TYPES {}", model.macrophages.len())));
+    }
+}
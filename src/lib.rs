@@ -16,17 +16,31 @@
 //!
 //! See `VISION.md` for scope and non-goals.
 
+pub mod activity;
+pub mod aging;
 pub mod biology;
+pub mod biomechanics;
+pub mod capi;
 pub mod config;
+pub mod geometry;
+pub mod io;
 pub mod metabolism;
 pub mod nutrition;
+pub mod organism;
 pub mod pathology;
 pub mod pharmacology;
+pub mod physics;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod simulation;
 pub mod simulation_utils;
 pub mod systems;
 pub mod validation;
+#[cfg(feature = "viz")]
+pub mod viz;
 
 pub use biology::{BiologyError, BiologyResult};
+pub use simulation::interaction::Interactive;
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
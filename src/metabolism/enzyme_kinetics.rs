@@ -1,3 +1,7 @@
+use crate::biology::physiology::properties::{
+    q10_factor, ChemicalProperty, PropertyConsumer, TYPICAL_ENZYME_Q10,
+};
+use crate::biology::signaling::hypoxia::Hif1a;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +12,22 @@ pub struct MichaelisMentenEnzyme {
     pub ki: Option<f64>,
     pub kcat: f64,
     pub enzyme_concentration: f64,
+    #[serde(default = "typical_q10")]
+    pub q10: f64,
+    #[serde(default = "body_temperature")]
+    pub temperature_celsius: f64,
+}
+
+fn typical_q10() -> f64 {
+    TYPICAL_ENZYME_Q10
+}
+
+fn body_temperature() -> f64 {
+    37.0
+}
+
+fn unit_fold() -> f64 {
+    1.0
 }
 
 impl MichaelisMentenEnzyme {
@@ -19,11 +39,17 @@ impl MichaelisMentenEnzyme {
             ki: None,
             kcat,
             enzyme_concentration: 1.0,
+            q10: TYPICAL_ENZYME_Q10,
+            temperature_celsius: 37.0,
         }
     }
 
+    pub fn effective_vmax(&self) -> f64 {
+        self.vmax * self.enzyme_concentration * q10_factor(self.q10, self.temperature_celsius)
+    }
+
     pub fn reaction_velocity(&self, substrate_concentration: f64) -> f64 {
-        (self.vmax * substrate_concentration) / (self.km + substrate_concentration)
+        (self.effective_vmax() * substrate_concentration) / (self.km + substrate_concentration)
     }
 
     pub fn reaction_velocity_with_inhibitor(
@@ -33,7 +59,8 @@ impl MichaelisMentenEnzyme {
     ) -> f64 {
         if let Some(ki) = self.ki {
             let apparent_km = self.km * (1.0 + inhibitor_concentration / ki);
-            (self.vmax * substrate_concentration) / (apparent_km + substrate_concentration)
+            (self.effective_vmax() * substrate_concentration)
+                / (apparent_km + substrate_concentration)
         } else {
             self.reaction_velocity(substrate_concentration)
         }
@@ -56,6 +83,14 @@ impl MichaelisMentenEnzyme {
     }
 }
 
+impl PropertyConsumer for MichaelisMentenEnzyme {
+    fn consume(&mut self, property: ChemicalProperty) {
+        if let ChemicalProperty::Temperature { celsius } = property {
+            self.temperature_celsius = celsius;
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlycolysisWithKinetics {
     pub glucose: f64,
@@ -76,6 +111,9 @@ pub struct GlycolysisWithKinetics {
     pub hexokinase: MichaelisMentenEnzyme,
     pub phosphofructokinase: MichaelisMentenEnzyme,
     pub pyruvate_kinase: MichaelisMentenEnzyme,
+    // HIF-1α induction currently applied to the enzyme concentrations.
+    #[serde(default = "unit_fold")]
+    pub hypoxic_fold: f64,
 }
 
 impl GlycolysisWithKinetics {
@@ -96,12 +134,7 @@ impl GlycolysisWithKinetics {
             adp: 1.0,
             nad: 0.5,
             nadh: 0.05,
-            hexokinase: MichaelisMentenEnzyme::new(
-                "Hexokinase".to_string(),
-                100.0,
-                0.1,
-                1000.0,
-            ),
+            hexokinase: MichaelisMentenEnzyme::new("Hexokinase".to_string(), 100.0, 0.1, 1000.0),
             phosphofructokinase: MichaelisMentenEnzyme::new(
                 "Phosphofructokinase-1 (PFK-1)".to_string(),
                 150.0,
@@ -114,13 +147,13 @@ impl GlycolysisWithKinetics {
                 0.3,
                 2000.0,
             ),
+            hypoxic_fold: 1.0,
         }
     }
 
     pub fn step_with_kinetics(&mut self, dt: f64) {
-        let v_hexokinase = self.hexokinase.reaction_velocity(self.glucose)
-            * (self.atp / (self.atp + 0.1))
-            * dt;
+        let v_hexokinase =
+            self.hexokinase.reaction_velocity(self.glucose) * (self.atp / (self.atp + 0.1)) * dt;
 
         let v_pfk = self
             .phosphofructokinase
@@ -128,9 +161,8 @@ impl GlycolysisWithKinetics {
             * (self.atp / (self.atp + 0.05))
             * dt;
 
-        let v_pk = self.pyruvate_kinase.reaction_velocity(self.pep)
-            * (self.adp / (self.adp + 0.1))
-            * dt;
+        let v_pk =
+            self.pyruvate_kinase.reaction_velocity(self.pep) * (self.adp / (self.adp + 0.1)) * dt;
 
         let glycolytic_flux = v_hexokinase.min(v_pfk).min(v_pk);
 
@@ -194,6 +226,29 @@ impl GlycolysisWithKinetics {
     }
 }
 
+// HIF-1α induces the glycolytic enzymes together, so hypoxia raises each
+// Vmax by the same fold. A new oxygen tension replaces the previous fold
+// rather than compounding it, and leaves any other change to the enzyme
+// concentrations in place; temperature reaches every enzyme.
+impl PropertyConsumer for GlycolysisWithKinetics {
+    fn consume(&mut self, property: ChemicalProperty) {
+        if let ChemicalProperty::OxygenTension { mmhg } = property {
+            let fold = Hif1a::at_po2(mmhg).glycolytic_enzyme_fold();
+            for enzyme in [
+                &mut self.hexokinase,
+                &mut self.phosphofructokinase,
+                &mut self.pyruvate_kinase,
+            ] {
+                enzyme.enzyme_concentration *= fold / self.hypoxic_fold;
+            }
+            self.hypoxic_fold = fold;
+        }
+        self.hexokinase.consume(property);
+        self.phosphofructokinase.consume(property);
+        self.pyruvate_kinase.consume(property);
+    }
+}
+
 impl Default for GlycolysisWithKinetics {
     fn default() -> Self {
         Self::new()
@@ -228,6 +283,39 @@ mod tests {
         assert!(v_with_inhibitor < v_no_inhibitor);
     }
 
+    #[test]
+    fn test_velocity_follows_body_temperature() {
+        let mut enzyme = MichaelisMentenEnzyme::new("Test".to_string(), 100.0, 1.0, 1000.0);
+        let normothermic = enzyme.reaction_velocity(1.0);
+        enzyme.consume(ChemicalProperty::Temperature { celsius: 40.0 });
+        let febrile = enzyme.reaction_velocity(1.0);
+        assert!(febrile > 1.2 * normothermic && febrile < 1.3 * normothermic);
+    }
+
+    #[test]
+    fn test_enzyme_concentration_scales_vmax() {
+        let mut enzyme = MichaelisMentenEnzyme::new("Test".to_string(), 100.0, 1.0, 1000.0);
+        assert!((enzyme.effective_vmax() - 100.0).abs() < 1e-9);
+        enzyme.enzyme_concentration = 2.0;
+        assert!((enzyme.effective_vmax() - 200.0).abs() < 1e-9);
+        assert!((enzyme.reaction_velocity(1.0) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_enzyme_without_temperature_fields_deserializes() {
+        let enzyme: MichaelisMentenEnzyme = serde_json::from_value(serde_json::json!({
+            "name": "Test",
+            "vmax": 100.0,
+            "km": 1.0,
+            "ki": null,
+            "kcat": 1000.0,
+            "enzyme_concentration": 1.0,
+        }))
+        .unwrap();
+        assert_eq!(enzyme.q10, TYPICAL_ENZYME_Q10);
+        assert_eq!(enzyme.temperature_celsius, 37.0);
+    }
+
     #[test]
     fn test_glycolysis_kinetics() {
         let mut pathway = GlycolysisWithKinetics::new();
@@ -239,6 +327,29 @@ mod tests {
         assert!(pathway.pyruvate > 0.0);
     }
 
+    #[test]
+    fn test_hypoxia_induces_glycolytic_enzymes() {
+        let mut pathway = GlycolysisWithKinetics::new();
+        let normoxic = pathway.hexokinase.reaction_velocity(5.0);
+        pathway.consume(ChemicalProperty::OxygenTension { mmhg: 40.0 });
+        assert!((pathway.hexokinase.reaction_velocity(5.0) - normoxic).abs() < 1e-9);
+        pathway.consume(ChemicalProperty::OxygenTension { mmhg: 5.0 });
+        pathway.consume(ChemicalProperty::OxygenTension { mmhg: 5.0 });
+        let hypoxic = pathway.pyruvate_kinase.reaction_velocity(0.3)
+            / GlycolysisWithKinetics::new()
+                .pyruvate_kinase
+                .reaction_velocity(0.3);
+        assert!(hypoxic > 2.0 && hypoxic < 3.0);
+        // Induction multiplies a concentration set elsewhere and is undone
+        // when oxygen returns.
+        pathway.hexokinase.enzyme_concentration *= 0.5;
+        pathway.consume(ChemicalProperty::OxygenTension { mmhg: 40.0 });
+        let restored = pathway.hexokinase.enzyme_concentration;
+        assert!((restored - 0.5 * pathway.hypoxic_fold).abs() < 1e-9);
+        pathway.consume(ChemicalProperty::Temperature { celsius: 40.0 });
+        assert_eq!(pathway.phosphofructokinase.temperature_celsius, 40.0);
+    }
+
     #[test]
     fn test_rate_limiting_step() {
         let pathway = GlycolysisWithKinetics::new();
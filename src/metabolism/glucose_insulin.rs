@@ -0,0 +1,336 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::engine::{load, save, substep, unknown_input, Component};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Bergman minimal model:
+// dG/dt = −(S_G + X)·G + S_G·Gb + Ra/V_G
+// dX/dt = −p2·X + p2·S_I·(I − Ib)
+// dI/dt = −n·(I − Ib) + γ·(G − h)+
+// Bergman RN et al. (1979) Am J Physiol 236:E667, PMID 443421
+// Bergman RN et al. (1981) J Clin Invest 68:1456, PMID 7033284
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MinimalModelParameters {
+    pub glucose_effectiveness_per_min: f64,
+    pub insulin_action_rate_per_min: f64,
+    pub insulin_sensitivity: f64,
+    pub insulin_clearance_per_min: f64,
+    pub beta_cell_responsivity: f64,
+    pub secretion_threshold_mg_dl: f64,
+    pub basal_glucose_mg_dl: f64,
+    pub basal_insulin_uu_ml: f64,
+    pub glucose_distribution_dl_per_kg: f64,
+    pub body_weight_kg: f64,
+}
+
+impl MinimalModelParameters {
+    // S_I ~6×10⁻⁴ mL/µU/min and S_G ~0.025 /min in lean normoglycaemic adults.
+    // Bergman RN (1989) Diabetes 38:1512, PMID 2684710
+    pub fn healthy_adult() -> Self {
+        Self {
+            glucose_effectiveness_per_min: 0.025,
+            insulin_action_rate_per_min: 0.025,
+            insulin_sensitivity: 6.0e-4,
+            insulin_clearance_per_min: 0.14,
+            beta_cell_responsivity: 0.1,
+            secretion_threshold_mg_dl: 90.0,
+            basal_glucose_mg_dl: 90.0,
+            basal_insulin_uu_ml: 8.0,
+            glucose_distribution_dl_per_kg: 1.7,
+            body_weight_kg: 70.0,
+        }
+    }
+
+    // Type 2 diabetes: S_I falls ~4-fold, S_G ~halves, first-phase beta-cell
+    // responsivity is largely lost and fasting glucose settles near 150 mg/dL.
+    // Welch S, Gebhart SS, Bergman RN et al. (1990) J Clin Endocrinol Metab
+    // 71:1508, PMID 2229309; DeFronzo RA (2009) Diabetes 58:773, PMID 19336687
+    pub fn type2_diabetes() -> Self {
+        Self {
+            glucose_effectiveness_per_min: 0.013,
+            insulin_sensitivity: 1.5e-4,
+            beta_cell_responsivity: 0.03,
+            secretion_threshold_mg_dl: 150.0,
+            basal_glucose_mg_dl: 150.0,
+            basal_insulin_uu_ml: 14.0,
+            ..Self::healthy_adult()
+        }
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        let positive = [
+            self.glucose_effectiveness_per_min,
+            self.insulin_action_rate_per_min,
+            self.insulin_clearance_per_min,
+            self.basal_glucose_mg_dl,
+            self.glucose_distribution_dl_per_kg,
+            self.body_weight_kg,
+        ];
+        if positive.iter().any(|v| *v <= 0.0) {
+            return Err(BiologyError::InvalidParameter(
+                "minimal model rates, volumes and basal glucose must be positive".to_string(),
+            ));
+        }
+        if self.insulin_sensitivity < 0.0
+            || self.beta_cell_responsivity < 0.0
+            || self.basal_insulin_uu_ml < 0.0
+        {
+            return Err(BiologyError::InvalidParameter(
+                "insulin sensitivity, responsivity and basal insulin must be non-negative"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for MinimalModelParameters {
+    fn default() -> Self {
+        Self::healthy_adult()
+    }
+}
+
+// Gut carbohydrate appearance through two absorption compartments,
+// Ra(t) ∝ t·e^(−t/τ), τ ≈ 40 min, ~90% of the load reaching the circulation.
+// Hovorka R et al. (2004) Physiol Meas 25:905, PMID 15382830
+const GUT_ABSORPTION_TIME_MIN: f64 = 40.0;
+const CARBOHYDRATE_BIOAVAILABILITY: f64 = 0.9;
+
+// ADAG: mean glucose (mg/dL) = 28.7·HbA1c − 46.7
+// Nathan DM et al. (2008) Diabetes Care 31:1473, PMID 18540046
+const ADAG_SLOPE: f64 = 28.7;
+const ADAG_INTERCEPT: f64 = 46.7;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlucoseInsulinModel {
+    pub params: MinimalModelParameters,
+    pub glucose_mg_dl: f64,
+    pub remote_insulin_per_min: f64,
+    pub insulin_uu_ml: f64,
+    gut_stomach_mg: f64,
+    gut_intestine_mg: f64,
+    pub elapsed_minutes: f64,
+    glucose_minutes_integral: f64,
+}
+
+impl GlucoseInsulinModel {
+    pub fn new(params: MinimalModelParameters) -> BiologyResult<Self> {
+        params.validate()?;
+        Ok(Self {
+            params,
+            glucose_mg_dl: params.basal_glucose_mg_dl,
+            remote_insulin_per_min: 0.0,
+            insulin_uu_ml: params.basal_insulin_uu_ml,
+            gut_stomach_mg: 0.0,
+            gut_intestine_mg: 0.0,
+            elapsed_minutes: 0.0,
+            glucose_minutes_integral: 0.0,
+        })
+    }
+
+    pub fn new_healthy() -> Self {
+        Self::new(MinimalModelParameters::healthy_adult()).expect("valid preset")
+    }
+
+    pub fn type2_diabetes() -> Self {
+        Self::new(MinimalModelParameters::type2_diabetes()).expect("valid preset")
+    }
+
+    pub fn ingest_carbohydrate(&mut self, grams: f64) -> BiologyResult<()> {
+        check_carbohydrate(grams)?;
+        self.gut_stomach_mg += grams * 1000.0 * CARBOHYDRATE_BIOAVAILABILITY;
+        Ok(())
+    }
+
+    // mg/kg/min
+    pub fn rate_of_appearance(&self) -> f64 {
+        self.gut_intestine_mg / GUT_ABSORPTION_TIME_MIN / self.params.body_weight_kg
+    }
+
+    pub fn insulin_secretion_rate(&self) -> f64 {
+        self.params.beta_cell_responsivity
+            * (self.glucose_mg_dl - self.params.secretion_threshold_mg_dl).max(0.0)
+    }
+
+    pub fn step(&mut self, dt_minutes: f64) {
+        let p = self.params;
+        let ra = self.rate_of_appearance();
+
+        let d_glucose = -(p.glucose_effectiveness_per_min + self.remote_insulin_per_min)
+            * self.glucose_mg_dl
+            + p.glucose_effectiveness_per_min * p.basal_glucose_mg_dl
+            + ra / p.glucose_distribution_dl_per_kg;
+        let d_remote = -p.insulin_action_rate_per_min * self.remote_insulin_per_min
+            + p.insulin_action_rate_per_min
+                * p.insulin_sensitivity
+                * (self.insulin_uu_ml - p.basal_insulin_uu_ml);
+        let d_insulin = -p.insulin_clearance_per_min * (self.insulin_uu_ml - p.basal_insulin_uu_ml)
+            + self.insulin_secretion_rate();
+
+        let emptied = self.gut_stomach_mg * dt_minutes / GUT_ABSORPTION_TIME_MIN;
+        let absorbed = ra * p.body_weight_kg * dt_minutes;
+        self.gut_stomach_mg -= emptied;
+        self.gut_intestine_mg += emptied - absorbed;
+
+        self.glucose_mg_dl = (self.glucose_mg_dl + d_glucose * dt_minutes).max(1.0);
+        self.remote_insulin_per_min += d_remote * dt_minutes;
+        self.insulin_uu_ml = (self.insulin_uu_ml + d_insulin * dt_minutes).max(0.0);
+        self.elapsed_minutes += dt_minutes;
+        self.glucose_minutes_integral += self.glucose_mg_dl * dt_minutes;
+    }
+
+    pub fn run_minutes(&mut self, minutes: usize) {
+        for _ in 0..minutes {
+            self.step(1.0);
+        }
+    }
+
+    // Three mixed meals a day at 07:00, 12:00 and 18:00.
+    pub fn run_day_with_meals(&mut self, carbohydrate_g_per_meal: f64) -> BiologyResult<()> {
+        for minute in 0..24 * 60 {
+            if matches!(minute, 420 | 720 | 1080) {
+                self.ingest_carbohydrate(carbohydrate_g_per_meal)?;
+            }
+            self.step(1.0);
+        }
+        Ok(())
+    }
+
+    pub fn mean_glucose_mg_dl(&self) -> f64 {
+        if self.elapsed_minutes <= 0.0 {
+            return self.glucose_mg_dl;
+        }
+        self.glucose_minutes_integral / self.elapsed_minutes
+    }
+
+    pub fn estimated_hba1c_percent(&self) -> f64 {
+        (self.mean_glucose_mg_dl() + ADAG_INTERCEPT) / ADAG_SLOPE
+    }
+
+    pub fn reset_glucose_average(&mut self) {
+        self.elapsed_minutes = 0.0;
+        self.glucose_minutes_integral = 0.0;
+    }
+}
+
+impl Default for GlucoseInsulinModel {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+fn check_carbohydrate(grams: f64) -> BiologyResult<()> {
+    if grams < 0.0 {
+        return Err(BiologyError::InvalidValue(
+            "carbohydrate load must be non-negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl Component for GlucoseInsulinModel {
+    fn kind(&self) -> &'static str {
+        "glucose_insulin"
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+        // A meal: each value set is ingested once.
+        &[("carbohydrate_g", "g")]
+    }
+
+    fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("glucose_mg_dl", "mg/dL"),
+            ("insulin_uu_ml", "µU/mL"),
+            ("rate_of_appearance", "mg/kg/min"),
+        ]
+    }
+
+    fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "carbohydrate_g" => self.ingest_carbohydrate(value),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn check_input(&self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "carbohydrate_g" => check_carbohydrate(value),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn output(&self, name: &str) -> Option<f64> {
+        match name {
+            "glucose_mg_dl" => Some(self.glucose_mg_dl),
+            "insulin_uu_ml" => Some(self.insulin_uu_ml),
+            "rate_of_appearance" => Some(self.rate_of_appearance()),
+            _ => None,
+        }
+    }
+
+    fn step(&mut self, dt_minutes: f64) {
+        substep(dt_minutes, 1.0, |h| GlucoseInsulinModel::step(self, h));
+    }
+
+    fn save_state(&self) -> Value {
+        save(self)
+    }
+
+    fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+        load(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ogtt(mut model: GlucoseInsulinModel) -> (f64, f64, f64) {
+        model.ingest_carbohydrate(75.0).unwrap();
+        let mut peak_glucose: f64 = 0.0;
+        let mut peak_insulin: f64 = 0.0;
+        for _ in 0..120 {
+            model.step(1.0);
+            peak_glucose = peak_glucose.max(model.glucose_mg_dl);
+            peak_insulin = peak_insulin.max(model.insulin_uu_ml);
+        }
+        (peak_glucose, model.glucose_mg_dl, peak_insulin)
+    }
+
+    #[test]
+    fn test_fasting_state_is_steady() {
+        let mut model = GlucoseInsulinModel::new_healthy();
+        model.run_minutes(600);
+        assert!((model.glucose_mg_dl - 90.0).abs() < 1.0);
+        assert!((model.insulin_uu_ml - 8.0).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_healthy_ogtt_is_normal() {
+        let (peak, two_hour, peak_insulin) = ogtt(GlucoseInsulinModel::new_healthy());
+        assert!(peak > 130.0 && peak < 200.0, "peak {peak}");
+        // WHO normal glucose tolerance: 2-h value < 140 mg/dL.
+        assert!(two_hour < 140.0, "2-h {two_hour}");
+        assert!(peak_insulin > 30.0 && peak_insulin < 120.0);
+    }
+
+    #[test]
+    fn test_type2_diabetes_ogtt_is_diagnostic() {
+        let (_, two_hour, _) = ogtt(GlucoseInsulinModel::type2_diabetes());
+        assert!(two_hour >= 200.0, "2-h {two_hour}");
+    }
+
+    #[test]
+    fn test_chronic_hyperglycemia_raises_hba1c() {
+        let mut healthy = GlucoseInsulinModel::new_healthy();
+        let mut diabetic = GlucoseInsulinModel::type2_diabetes();
+        for _ in 0..3 {
+            healthy.run_day_with_meals(60.0).unwrap();
+            diabetic.run_day_with_meals(60.0).unwrap();
+        }
+        assert!(healthy.estimated_hba1c_percent() < 5.7);
+        assert!(diabetic.estimated_hba1c_percent() > 6.5);
+        assert!(healthy.ingest_carbohydrate(-1.0).is_err());
+    }
+}
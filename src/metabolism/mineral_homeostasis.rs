@@ -0,0 +1,394 @@
+use crate::biology::endocrine::calcium::{
+    CalciumRegulatoryAxis, REFERENCE_IONIZED_CALCIUM_MMOL_L, REFERENCE_PHOSPHATE_MG_DL,
+};
+use crate::biology::endocrine::hormone::{normalized_stimulation, normalized_suppression};
+use crate::biology::endocrine::signals::{
+    EndocrineSignals, FeedbackAxis, REFERENCE_CALCITRIOL_PG_ML, REFERENCE_FGF23_PG_ML,
+    REFERENCE_PTH_PG_ML,
+};
+use crate::biology::endocrine::vitamin_d::VitaminDMetabolism;
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::skeletal::remodeling::{BoneRemodelingModel, RemodelingModifiers};
+use serde::{Deserialize, Serialize};
+
+// Compartment structure follows the integrated calcium/bone model of
+// Peterson MC, Riggs MM (2010) Bone 46:49-63, PMID 19732857.
+
+// Total serum calcium 8.5-10.5 mg/dL, ~52% ionized.
+// Boron & Boulpaep, Medical Physiology 3e, Ch 52
+pub const REFERENCE_TOTAL_CALCIUM_MG_DL: f64 = 9.6;
+const IONIZED_FRACTION: f64 = 0.52;
+const CALCIUM_MG_PER_MMOL: f64 = 40.08;
+// ~60% of plasma calcium and ~90% of phosphate are ultrafilterable.
+const ULTRAFILTERABLE_CALCIUM: f64 = 0.6;
+const ULTRAFILTERABLE_PHOSPHATE: f64 = 0.9;
+// Proximal tubule and thick ascending limb reclaim ~90% of filtered calcium
+// independently of PTH; PTH sets distal reabsorption.
+const PROXIMAL_CALCIUM_REABSORPTION: f64 = 0.9;
+const REFERENCE_DISTAL_CALCIUM_REJECTION: f64 = 0.15;
+// Fractional phosphate excretion 10-20%.
+const REFERENCE_PHOSPHATE_FRACTIONAL_EXCRETION: f64 = 0.14;
+
+// Adult skeleton ~1 kg calcium; HA Ca:P mass ratio 2.15.
+pub const ADULT_SKELETAL_CALCIUM_MG: f64 = 1.0e6;
+const BONE_CA_TO_P_MASS_RATIO: f64 = 2.15;
+
+// Passive paracellular absorption ~10% plus calcitriol-dependent active
+// transport ~20% at normal vitamin D status; endogenous faecal loss ~150 mg/d.
+// Heaney RP (2003) J Am Coll Nutr 22:142S, PMID 12714573
+const PASSIVE_CALCIUM_ABSORPTION: f64 = 0.1;
+const ACTIVE_CALCIUM_ABSORPTION: f64 = 0.2;
+const ENDOGENOUS_FECAL_CALCIUM_MG_PER_DAY: f64 = 150.0;
+// Phosphate absorption ~60-70%, a minor share via calcitriol-induced NaPi-IIb.
+const PASSIVE_PHOSPHATE_ABSORPTION: f64 = 0.55;
+const ACTIVE_PHOSPHATE_ABSORPTION: f64 = 0.1;
+
+// PTH mobilises calcium from the rapidly exchangeable bone surface within
+// hours, ahead of any change in remodeling; the labile pool re-equilibrates
+// with ionized calcium, so release at low PTH stops at a lower serum level
+// instead of draining the ECF.
+const FAST_BONE_EXCHANGE_MG_PER_DAY: f64 = 500.0;
+const LABILE_POOL_CALCIUM_EXPONENT: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DietaryMinerals {
+    pub calcium_mg_per_day: f64,
+    pub phosphate_mg_per_day: f64,
+}
+
+impl DietaryMinerals {
+    // US adult RDA calcium 1000 mg; typical phosphate intake ~1200 mg.
+    // Ross AC et al. (2011) J Clin Endocrinol Metab 96:53-58, PMID 21118827
+    pub fn adult_reference() -> Self {
+        Self {
+            calcium_mg_per_day: 1000.0,
+            phosphate_mg_per_day: 1200.0,
+        }
+    }
+}
+
+impl Default for DietaryMinerals {
+    fn default() -> Self {
+        Self::adult_reference()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MineralFluxes {
+    pub gut_calcium_absorbed_mg_per_day: f64,
+    pub renal_calcium_excreted_mg_per_day: f64,
+    pub bone_calcium_resorbed_mg_per_day: f64,
+    pub bone_calcium_formed_mg_per_day: f64,
+    pub fast_bone_calcium_release_mg_per_day: f64,
+    pub gut_phosphate_absorbed_mg_per_day: f64,
+    pub renal_phosphate_excreted_mg_per_day: f64,
+}
+
+impl MineralFluxes {
+    pub fn net_bone_calcium_mg_per_day(&self) -> f64 {
+        self.bone_calcium_formed_mg_per_day
+            - self.bone_calcium_resorbed_mg_per_day
+            - self.fast_bone_calcium_release_mg_per_day
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MineralHomeostasis {
+    pub regulation: CalciumRegulatoryAxis,
+    pub vitamin_d: VitaminDMetabolism,
+    pub skeleton: BoneRemodelingModel,
+    pub diet: DietaryMinerals,
+    pub gfr_ml_per_min: f64,
+    // Host-external modifiers of fractional absorption, e.g. colonic SCFA.
+    pub gut_calcium_absorption_factor: f64,
+    pub ecf_volume_l: f64,
+    pub ecf_calcium_mg: f64,
+    pub ecf_phosphate_mg: f64,
+    pub skeletal_calcium_reference_mg: f64,
+    pub fluxes: MineralFluxes,
+    pub elapsed_hours: f64,
+    hours_since_remodeling_step: f64,
+}
+
+// GFR ~120 mL/min; ECF ~14 L (20% of 70 kg).
+const REFERENCE_GFR_ML_PER_MIN: f64 = 120.0;
+const REFERENCE_ECF_VOLUME_L: f64 = 14.0;
+
+impl MineralHomeostasis {
+    pub fn new_healthy() -> Self {
+        Self {
+            regulation: CalciumRegulatoryAxis::new_healthy(),
+            vitamin_d: VitaminDMetabolism::new_healthy(),
+            skeleton: BoneRemodelingModel::whole_adult_skeleton(),
+            diet: DietaryMinerals::adult_reference(),
+            gfr_ml_per_min: REFERENCE_GFR_ML_PER_MIN,
+            gut_calcium_absorption_factor: 1.0,
+            ecf_volume_l: REFERENCE_ECF_VOLUME_L,
+            ecf_calcium_mg: REFERENCE_TOTAL_CALCIUM_MG_DL * 10.0 * REFERENCE_ECF_VOLUME_L,
+            ecf_phosphate_mg: REFERENCE_PHOSPHATE_MG_DL * 10.0 * REFERENCE_ECF_VOLUME_L,
+            skeletal_calcium_reference_mg: ADULT_SKELETAL_CALCIUM_MG,
+            fluxes: MineralFluxes::default(),
+            elapsed_hours: 0.0,
+            hours_since_remodeling_step: 0.0,
+        }
+    }
+
+    // Nephron loss lowers filtration and 1α-hydroxylase together.
+    pub fn chronic_kidney_disease(gfr_ml_per_min: f64) -> BiologyResult<Self> {
+        if gfr_ml_per_min <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "GFR must be positive".to_string(),
+            ));
+        }
+        let mut model = Self::new_healthy();
+        model.gfr_ml_per_min = gfr_ml_per_min;
+        model.regulation.renal_hydroxylase_capacity =
+            (gfr_ml_per_min / REFERENCE_GFR_ML_PER_MIN).min(1.0);
+        Ok(model)
+    }
+
+    // Housebound or covered, with no supplement: stores run down over
+    // months.
+    pub fn without_vitamin_d() -> Self {
+        let mut model = Self::new_healthy();
+        model.vitamin_d.uv_sed_per_day = 0.0;
+        model.vitamin_d.oral_ug_per_day = 0.0;
+        model
+    }
+
+    pub fn set_gut_calcium_absorption_factor(&mut self, factor: f64) -> BiologyResult<()> {
+        if factor.is_nan() || factor < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "absorption factor must be non-negative".to_string(),
+            ));
+        }
+        self.gut_calcium_absorption_factor = factor;
+        Ok(())
+    }
+
+    pub fn hypoparathyroid() -> Self {
+        let mut model = Self::new_healthy();
+        model.regulation.parathyroid.gland_mass = 0.05;
+        model
+    }
+
+    pub fn serum_total_calcium_mg_dl(&self) -> f64 {
+        self.ecf_calcium_mg / (10.0 * self.ecf_volume_l)
+    }
+
+    pub fn serum_ionized_calcium_mmol_l(&self) -> f64 {
+        self.serum_total_calcium_mg_dl() * 10.0 / CALCIUM_MG_PER_MMOL * IONIZED_FRACTION
+    }
+
+    pub fn serum_phosphate_mg_dl(&self) -> f64 {
+        self.ecf_phosphate_mg / (10.0 * self.ecf_volume_l)
+    }
+
+    pub fn skeletal_calcium_mg(&self) -> f64 {
+        self.skeletal_calcium_reference_mg * self.skeleton.bone_mass_fraction
+    }
+
+    pub fn signals(&self) -> EndocrineSignals {
+        let mut signals = EndocrineSignals::default();
+        self.regulation.publish(&mut signals);
+        signals
+    }
+
+    fn gfr_l_per_day(&self) -> f64 {
+        self.gfr_ml_per_min * 1.44
+    }
+
+    fn compute_fluxes(&self) -> MineralFluxes {
+        let pth = self.regulation.pth.concentration;
+        let calcitriol = self.regulation.calcitriol.concentration;
+        let fgf23 = self.regulation.fgf23.concentration;
+        let vitamin_d_effect = normalized_stimulation(calcitriol, REFERENCE_CALCITRIOL_PG_ML, 1.0);
+
+        let ca_absorption_fraction = (PASSIVE_CALCIUM_ABSORPTION
+            + ACTIVE_CALCIUM_ABSORPTION * vitamin_d_effect)
+            * self.gut_calcium_absorption_factor;
+        let gut_calcium = self.diet.calcium_mg_per_day * ca_absorption_fraction
+            - ENDOGENOUS_FECAL_CALCIUM_MG_PER_DAY;
+
+        let filtered_calcium = self.gfr_l_per_day()
+            * self.serum_total_calcium_mg_dl()
+            * 10.0
+            * ULTRAFILTERABLE_CALCIUM;
+        let renal_calcium = filtered_calcium
+            * (1.0 - PROXIMAL_CALCIUM_REABSORPTION)
+            * REFERENCE_DISTAL_CALCIUM_REJECTION
+            * normalized_suppression(pth, REFERENCE_PTH_PG_ML, 1.0);
+
+        let skeletal = self.skeletal_calcium_mg();
+        let resorbed = self.skeleton.resorption_rate_per_day() * self.skeletal_calcium_reference_mg;
+        let formed = self.skeleton.formation_rate_per_day() * self.skeletal_calcium_reference_mg;
+        let calcium_ratio = self.serum_ionized_calcium_mmol_l() / REFERENCE_IONIZED_CALCIUM_MMOL_L;
+        let fast = if skeletal > 0.0 {
+            FAST_BONE_EXCHANGE_MG_PER_DAY
+                * (normalized_stimulation(pth, REFERENCE_PTH_PG_ML, 1.0)
+                    - calcium_ratio.powf(LABILE_POOL_CALCIUM_EXPONENT))
+        } else {
+            0.0
+        };
+
+        let p_absorption_fraction =
+            PASSIVE_PHOSPHATE_ABSORPTION + ACTIVE_PHOSPHATE_ABSORPTION * vitamin_d_effect;
+        let gut_phosphate = self.diet.phosphate_mg_per_day * p_absorption_fraction;
+        let filtered_phosphate =
+            self.gfr_l_per_day() * self.serum_phosphate_mg_dl() * 10.0 * ULTRAFILTERABLE_PHOSPHATE;
+        // PTH and FGF23 both internalise NaPi-IIa/IIc in the proximal tubule.
+        let reabsorbed = (1.0 - REFERENCE_PHOSPHATE_FRACTIONAL_EXCRETION)
+            * normalized_suppression(pth, REFERENCE_PTH_PG_ML, 1.0)
+            * normalized_suppression(fgf23, REFERENCE_FGF23_PG_ML, 1.0);
+        let phosphate_fe = 1.0 - reabsorbed.min(1.0);
+        let renal_phosphate = filtered_phosphate * phosphate_fe;
+
+        MineralFluxes {
+            gut_calcium_absorbed_mg_per_day: gut_calcium,
+            renal_calcium_excreted_mg_per_day: renal_calcium,
+            bone_calcium_resorbed_mg_per_day: resorbed,
+            bone_calcium_formed_mg_per_day: formed,
+            fast_bone_calcium_release_mg_per_day: fast,
+            gut_phosphate_absorbed_mg_per_day: gut_phosphate,
+            renal_phosphate_excreted_mg_per_day: renal_phosphate,
+        }
+    }
+
+    pub fn step(&mut self, dt_hours: f64) {
+        self.vitamin_d.set_renal_signals(
+            self.regulation.calcitriol.concentration,
+            self.regulation.fgf23_pg_ml(),
+        );
+        self.vitamin_d.step(dt_hours);
+        self.regulation
+            .set_calcidiol(self.vitamin_d.calcidiol_nmol_l);
+        self.regulation.set_serum_minerals(
+            self.serum_ionized_calcium_mmol_l(),
+            self.serum_phosphate_mg_dl(),
+        );
+        self.regulation.step(dt_hours);
+
+        let fluxes = self.compute_fluxes();
+        let dt_days = dt_hours / 24.0;
+        let d_calcium = fluxes.gut_calcium_absorbed_mg_per_day
+            - fluxes.renal_calcium_excreted_mg_per_day
+            - fluxes.net_bone_calcium_mg_per_day();
+        let net_bone_phosphate = fluxes.net_bone_calcium_mg_per_day() / BONE_CA_TO_P_MASS_RATIO;
+        let d_phosphate = fluxes.gut_phosphate_absorbed_mg_per_day
+            - fluxes.renal_phosphate_excreted_mg_per_day
+            - net_bone_phosphate;
+        self.ecf_calcium_mg = (self.ecf_calcium_mg + d_calcium * dt_days).max(0.0);
+        self.ecf_phosphate_mg = (self.ecf_phosphate_mg + d_phosphate * dt_days).max(0.0);
+        self.skeleton.bone_mass_fraction = (self.skeleton.bone_mass_fraction
+            - fluxes.fast_bone_calcium_release_mg_per_day * dt_days
+                / self.skeletal_calcium_reference_mg)
+            .max(0.0);
+        self.fluxes = fluxes;
+
+        // Remodeling advances on its own daily clock under current hormones.
+        self.hours_since_remodeling_step += dt_hours;
+        while self.hours_since_remodeling_step >= 24.0 {
+            let signals = self.signals();
+            self.skeleton
+                .set_modifiers(RemodelingModifiers::from_endocrine(&signals));
+            self.skeleton.step_day();
+            self.hours_since_remodeling_step -= 24.0;
+        }
+        self.elapsed_hours += dt_hours;
+    }
+
+    pub fn run_days(&mut self, days: f64) {
+        let steps = (days * 24.0).round() as usize;
+        for _ in 0..steps {
+            self.step(1.0);
+        }
+    }
+
+    pub fn is_normocalcemic(&self) -> bool {
+        (8.5..=10.5).contains(&self.serum_total_calcium_mg_dl())
+    }
+
+    pub fn ionized_calcium_deviation(&self) -> f64 {
+        self.serum_ionized_calcium_mmol_l() - REFERENCE_IONIZED_CALCIUM_MMOL_L
+    }
+}
+
+impl Default for MineralHomeostasis {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_healthy_balance() {
+        let mut model = MineralHomeostasis::new_healthy();
+        model.run_days(30.0);
+        assert!(model.is_normocalcemic());
+        assert!((2.5..=4.5).contains(&model.serum_phosphate_mg_dl()));
+        let urine_ca = model.fluxes.renal_calcium_excreted_mg_per_day;
+        assert!((100.0..=300.0).contains(&urine_ca), "urine Ca {urine_ca}");
+        assert!((15.0..=65.0).contains(&model.regulation.pth.concentration));
+    }
+
+    #[test]
+    fn test_low_calcium_diet_defended_at_bone_expense() {
+        let mut model = MineralHomeostasis::new_healthy();
+        model.diet.calcium_mg_per_day = 300.0;
+        model.run_days(365.0);
+        assert!(model.serum_total_calcium_mg_dl() > 8.0);
+        assert!(model.regulation.pth.concentration > REFERENCE_PTH_PG_ML);
+        assert!(model.regulation.calcitriol.concentration > REFERENCE_CALCITRIOL_PG_ML);
+        assert!(model.skeletal_calcium_mg() < ADULT_SKELETAL_CALCIUM_MG);
+    }
+
+    #[test]
+    fn test_ckd_mineral_bone_disorder() {
+        let mut model = MineralHomeostasis::chronic_kidney_disease(20.0).unwrap();
+        model.run_days(90.0);
+        assert!(model.serum_phosphate_mg_dl() > REFERENCE_PHOSPHATE_MG_DL);
+        assert!(model.regulation.fgf23_pg_ml() > 1.5 * REFERENCE_FGF23_PG_ML);
+        assert!(model.regulation.calcitriol.concentration < REFERENCE_CALCITRIOL_PG_ML);
+        // Secondary hyperparathyroidism develops from phosphate retention
+        // and calcitriol deficiency.
+        assert!(model.regulation.pth.concentration > 1.25 * REFERENCE_PTH_PG_ML);
+        assert!(MineralHomeostasis::chronic_kidney_disease(0.0).is_err());
+    }
+
+    #[test]
+    fn test_vitamin_d_deficiency_and_repletion() {
+        let mut model = MineralHomeostasis::without_vitamin_d();
+        model.run_days(240.0);
+        assert!(model.vitamin_d.is_deficient());
+        // Substrate-limited calcitriol lowers calcium absorption; PTH rises
+        // to defend serum calcium.
+        assert!(model.regulation.pth.concentration > 1.2 * REFERENCE_PTH_PG_ML);
+        assert!(model.is_normocalcemic());
+
+        // 4000 IU a day.
+        let deficient_pth = model.regulation.pth.concentration;
+        model.vitamin_d.set_supplement(100.0).unwrap();
+        model.run_days(180.0);
+        assert!(model.vitamin_d.calcidiol_nmol_l > 50.0);
+        assert!(model.regulation.pth.concentration < 0.85 * deficient_pth);
+    }
+
+    #[test]
+    fn test_hypoparathyroidism() {
+        let mut model = MineralHomeostasis::hypoparathyroid();
+        model.run_days(30.0);
+        assert!(model.serum_total_calcium_mg_dl() < 8.5);
+        assert!(model.serum_phosphate_mg_dl() > REFERENCE_PHOSPHATE_MG_DL);
+    }
+
+    #[test]
+    fn test_fgf23_excess_wastes_phosphate() {
+        let mut model = MineralHomeostasis::new_healthy();
+        model.regulation.set_fgf23_production_factor(4.0);
+        model.run_days(30.0);
+        assert!(model.serum_phosphate_mg_dl() < 2.5);
+    }
+}
@@ -1,8 +1,18 @@
 pub mod alcohol_metabolism;
 pub mod enzyme_kinetics;
+pub mod glucose_insulin;
+pub mod mineral_homeostasis;
+pub mod molecules;
+pub mod reaction_network;
+pub mod structure;
 
 pub use alcohol_metabolism::{
     ADH1BGenotype, ALDH2Genotype, AlcoholConsumptionLevel, AlcoholIngestion,
     AlcoholMetabolismPathway, AlcoholMetabolismSimulation, MetabolismTimePoint, Sex,
 };
 pub use enzyme_kinetics::{GlycolysisWithKinetics, MichaelisMentenEnzyme};
+pub use glucose_insulin::{GlucoseInsulinModel, MinimalModelParameters};
+pub use mineral_homeostasis::{DietaryMinerals, MineralFluxes, MineralHomeostasis};
+pub use molecules::{Molecule, MoleculeId, MoleculeRegistry};
+pub use reaction_network::{RateLaw, Reaction, ReactionBuilder, ReactionNetwork};
+pub use structure::{Atom, Bond, Element, MolecularStructure};
@@ -0,0 +1,171 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+// Compact handle for an interned molecule. Ids are dense, so state keyed
+// by molecule is a plain Vec indexed by `index()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MoleculeId(u32);
+
+impl MoleculeId {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Molecule {
+    pub name: String,
+    pub molar_mass_g_mol: Option<f64>,
+}
+
+impl Molecule {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            molar_mass_g_mol: None,
+        }
+    }
+
+    pub fn with_molar_mass(mut self, molar_mass_g_mol: f64) -> Self {
+        self.molar_mass_g_mol = Some(molar_mass_g_mol);
+        self
+    }
+}
+
+// Interner mapping molecule names to ids and ids back to the full
+// definition. Serialized as the ordered definition list; the name index
+// is rebuilt on load.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<Molecule>", into = "Vec<Molecule>")]
+pub struct MoleculeRegistry {
+    molecules: Vec<Molecule>,
+    by_name: HashMap<String, MoleculeId>,
+}
+
+impl MoleculeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns the existing id for `name`, or registers a bare definition.
+    pub fn intern(&mut self, name: &str) -> MoleculeId {
+        match self.by_name.get(name) {
+            Some(&id) => id,
+            None => self.insert(Molecule::new(name)),
+        }
+    }
+
+    // Registers a definition, replacing any previous one with that name
+    // while keeping its id.
+    pub fn register(&mut self, molecule: Molecule) -> MoleculeId {
+        match self.by_name.get(&molecule.name) {
+            Some(&id) => {
+                self.molecules[id.index()] = molecule;
+                id
+            }
+            None => self.insert(molecule),
+        }
+    }
+
+    fn insert(&mut self, molecule: Molecule) -> MoleculeId {
+        let id = MoleculeId(self.molecules.len() as u32);
+        self.by_name.insert(molecule.name.clone(), id);
+        self.molecules.push(molecule);
+        id
+    }
+
+    pub fn id(&self, name: &str) -> Option<MoleculeId> {
+        self.by_name.get(name).copied()
+    }
+
+    pub fn get(&self, id: MoleculeId) -> Option<&Molecule> {
+        self.molecules.get(id.index())
+    }
+
+    pub fn name(&self, id: MoleculeId) -> Option<&str> {
+        self.get(id).map(|m| m.name.as_str())
+    }
+
+    pub fn contains(&self, id: MoleculeId) -> bool {
+        id.index() < self.molecules.len()
+    }
+
+    pub fn resolve(&self, name: &str) -> BiologyResult<MoleculeId> {
+        self.id(name)
+            .ok_or_else(|| BiologyError::InvalidParameter(format!("unknown molecule {}", name)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.molecules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.molecules.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (MoleculeId, &Molecule)> {
+        self.molecules
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (MoleculeId(i as u32), m))
+    }
+}
+
+impl PartialEq for MoleculeRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.molecules == other.molecules
+    }
+}
+
+impl From<Vec<Molecule>> for MoleculeRegistry {
+    fn from(molecules: Vec<Molecule>) -> Self {
+        let mut registry = MoleculeRegistry::new();
+        for molecule in molecules {
+            registry.register(molecule);
+        }
+        registry
+    }
+}
+
+impl From<MoleculeRegistry> for Vec<Molecule> {
+    fn from(registry: MoleculeRegistry) -> Self {
+        registry.molecules
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning_is_stable() {
+        let mut registry = MoleculeRegistry::new();
+        let glucose = registry.intern("glucose");
+        let pyruvate = registry.intern("pyruvate");
+        assert_ne!(glucose, pyruvate);
+        assert_eq!(registry.intern("glucose"), glucose);
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.name(pyruvate), Some("pyruvate"));
+        assert!(registry.resolve("lactate").is_err());
+
+        let updated = registry.register(Molecule::new("glucose").with_molar_mass(180.16));
+        assert_eq!(updated, glucose);
+        assert_eq!(
+            registry.get(glucose).unwrap().molar_mass_g_mol,
+            Some(180.16)
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip_rebuilds_index() {
+        let mut registry = MoleculeRegistry::new();
+        registry.intern("ATP");
+        let adp = registry.intern("ADP");
+        let json = serde_json::to_string(&registry).unwrap();
+        assert!(json.starts_with('['));
+        let restored: MoleculeRegistry = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, registry);
+        assert_eq!(restored.id("ADP"), Some(adp));
+    }
+}
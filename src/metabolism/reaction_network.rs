@@ -0,0 +1,602 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::metabolism::molecules::{Molecule, MoleculeId, MoleculeRegistry};
+use crate::simulation::ode::{integrate, OdeSolution, OdeSystem, SolverMethod, SolverOptions};
+use nalgebra::DMatrix;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RateLaw {
+    // k * prod(c_i ^ stoichiometry_i) over reactants.
+    MassAction { k: f64 },
+    // vmax * S / (km + S) on the first reactant; the rest are cofactors
+    // that are consumed but do not limit the rate.
+    MichaelisMenten { vmax: f64, km: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reaction {
+    pub name: String,
+    pub reactants: Vec<(MoleculeId, f64)>,
+    pub products: Vec<(MoleculeId, f64)>,
+    pub rate: RateLaw,
+}
+
+impl Reaction {
+    pub fn builder(name: &str) -> ReactionBuilder {
+        ReactionBuilder::new(name)
+    }
+
+    pub fn rate(&self, c: &[f64]) -> f64 {
+        match self.rate {
+            RateLaw::MassAction { k } => self
+                .reactants
+                .iter()
+                .fold(k, |rate, &(s, n)| rate * c[s.index()].max(0.0).powf(n)),
+            RateLaw::MichaelisMenten { vmax, km } => match self.reactants.first() {
+                Some(&(s, _)) => {
+                    let substrate = c[s.index()].max(0.0);
+                    vmax * substrate / (km + substrate)
+                }
+                None => vmax,
+            },
+        }
+    }
+
+    // d(rate)/d(c_species).
+    fn rate_derivative(&self, c: &[f64], species: MoleculeId) -> f64 {
+        match self.rate {
+            RateLaw::MassAction { k } => {
+                let mut derivative = 0.0;
+                for (i, &(s, n)) in self.reactants.iter().enumerate() {
+                    if s != species || n == 0.0 {
+                        continue;
+                    }
+                    let others = self
+                        .reactants
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| j != i)
+                        .fold(k, |rate, (_, &(s2, n2))| {
+                            rate * c[s2.index()].max(0.0).powf(n2)
+                        });
+                    derivative += others * n * c[s.index()].max(0.0).powf(n - 1.0);
+                }
+                derivative
+            }
+            RateLaw::MichaelisMenten { vmax, km } => match self.reactants.first() {
+                Some(&(s, _)) if s == species => {
+                    let substrate = c[s.index()].max(0.0);
+                    vmax * km / (km + substrate).powi(2)
+                }
+                _ => 0.0,
+            },
+        }
+    }
+}
+
+// Fluent construction with validation deferred to `build()`:
+//   Reaction::builder("hydrolysis").reactant(atp, 1.0).product(adp, 1.0)
+//       .mass_action(0.1).build()?
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReactionBuilder {
+    name: String,
+    reactants: Vec<(MoleculeId, f64)>,
+    products: Vec<(MoleculeId, f64)>,
+    rate: Option<RateLaw>,
+}
+
+impl ReactionBuilder {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            reactants: Vec::new(),
+            products: Vec::new(),
+            rate: None,
+        }
+    }
+
+    pub fn reactant(mut self, species: MoleculeId, stoichiometry: f64) -> Self {
+        self.reactants.push((species, stoichiometry));
+        self
+    }
+
+    pub fn product(mut self, species: MoleculeId, stoichiometry: f64) -> Self {
+        self.products.push((species, stoichiometry));
+        self
+    }
+
+    pub fn mass_action(self, k: f64) -> Self {
+        self.rate_law(RateLaw::MassAction { k })
+    }
+
+    pub fn michaelis_menten(self, vmax: f64, km: f64) -> Self {
+        self.rate_law(RateLaw::MichaelisMenten { vmax, km })
+    }
+
+    pub fn rate_law(mut self, rate: RateLaw) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    pub fn build(self) -> BiologyResult<Reaction> {
+        let invalid = |message: String| {
+            Err(BiologyError::InvalidParameter(format!(
+                "reaction {}: {}",
+                self.name, message
+            )))
+        };
+        if self.name.trim().is_empty() {
+            return Err(BiologyError::InvalidParameter(
+                "reaction name must not be empty".to_string(),
+            ));
+        }
+        let Some(rate) = self.rate else {
+            return invalid("no rate law".to_string());
+        };
+        let positive = |x: f64| x.is_finite() && x > 0.0;
+        match rate {
+            RateLaw::MassAction { k } if !positive(k) => {
+                return invalid(format!("rate constant {} must be positive", k));
+            }
+            RateLaw::MichaelisMenten { vmax, km } if !positive(vmax) || !positive(km) => {
+                return invalid("vmax and km must be positive".to_string());
+            }
+            RateLaw::MichaelisMenten { .. } if self.reactants.is_empty() => {
+                return invalid("Michaelis-Menten needs a substrate".to_string());
+            }
+            _ => {}
+        }
+        if self.reactants.is_empty() && self.products.is_empty() {
+            return invalid("no reactants or products".to_string());
+        }
+        for side in [&self.reactants, &self.products] {
+            if side.iter().any(|&(_, n)| !positive(n)) {
+                return invalid("stoichiometry must be positive".to_string());
+            }
+            let mut ids: Vec<MoleculeId> = side.iter().map(|&(s, _)| s).collect();
+            ids.sort_unstable();
+            if ids.windows(2).any(|w| w[0] == w[1]) {
+                return invalid("molecule listed twice on one side".to_string());
+            }
+        }
+        Ok(Reaction {
+            name: self.name,
+            reactants: self.reactants,
+            products: self.products,
+            rate,
+        })
+    }
+}
+
+// Well-mixed biochemical network dc/dt = N v(c) with an analytic
+// Jacobian. Species are interned molecules; `concentrations` is indexed
+// by `MoleculeId::index()`. Networks with fast and slow reactions are stiff, so the
+// default solver detects stiffness and switches to BDF. Loading goes
+// through `SavedNetwork` so a file cannot index past the species list.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "SavedNetwork")]
+pub struct ReactionNetwork {
+    pub molecules: MoleculeRegistry,
+    pub concentrations: Vec<f64>,
+    pub reactions: Vec<Reaction>,
+    pub solver: SolverOptions,
+    pub time: f64,
+    // Largest |c| each species has reached, which bounds the error the
+    // solver may leave in it once it has drained.
+    #[serde(default)]
+    peak_concentrations: Vec<f64>,
+}
+
+// Wire form of `ReactionNetwork`. Molecules stay a plain list here because
+// `MoleculeRegistry::from` merges repeated names, which would silently
+// shift every later id.
+#[derive(Deserialize)]
+struct SavedNetwork {
+    molecules: Vec<Molecule>,
+    concentrations: Vec<f64>,
+    reactions: Vec<Reaction>,
+    solver: SolverOptions,
+    time: f64,
+    #[serde(default)]
+    peak_concentrations: Vec<f64>,
+}
+
+impl TryFrom<SavedNetwork> for ReactionNetwork {
+    type Error = BiologyError;
+
+    fn try_from(saved: SavedNetwork) -> BiologyResult<Self> {
+        let species = saved.molecules.len();
+        let mut names: Vec<&str> = saved.molecules.iter().map(|m| m.name.as_str()).collect();
+        names.sort_unstable();
+        if let Some(pair) = names.windows(2).find(|w| w[0] == w[1]) {
+            return Err(BiologyError::InvalidState(format!(
+                "molecule {} is listed twice",
+                pair[0]
+            )));
+        }
+        if saved.concentrations.len() != species {
+            return Err(BiologyError::InvalidState(format!(
+                "{} concentrations for {} molecules",
+                saved.concentrations.len(),
+                species
+            )));
+        }
+        if !saved.peak_concentrations.is_empty() && saved.peak_concentrations.len() != species {
+            return Err(BiologyError::InvalidState(format!(
+                "{} peak concentrations for {} molecules",
+                saved.peak_concentrations.len(),
+                species
+            )));
+        }
+        let mut network = ReactionNetwork {
+            molecules: MoleculeRegistry::from(saved.molecules),
+            concentrations: saved.concentrations,
+            reactions: Vec::new(),
+            solver: saved.solver,
+            time: saved.time,
+            peak_concentrations: saved.peak_concentrations,
+        };
+        for reaction in saved.reactions {
+            network.add_reaction(reaction)?;
+        }
+        Ok(network)
+    }
+}
+
+impl ReactionNetwork {
+    pub fn new() -> Self {
+        Self {
+            molecules: MoleculeRegistry::new(),
+            concentrations: Vec::new(),
+            reactions: Vec::new(),
+            solver: SolverOptions::default(),
+            time: 0.0,
+            peak_concentrations: Vec::new(),
+        }
+    }
+
+    // Adding a name twice resets its concentration and keeps its id.
+    pub fn add_species(&mut self, name: &str, initial_concentration: f64) -> MoleculeId {
+        self.add_molecule(Molecule::new(name), initial_concentration)
+    }
+
+    pub fn add_molecule(&mut self, molecule: Molecule, initial_concentration: f64) -> MoleculeId {
+        let id = self.molecules.register(molecule);
+        if id.index() == self.concentrations.len() {
+            self.concentrations.push(initial_concentration);
+        } else {
+            self.concentrations[id.index()] = initial_concentration;
+        }
+        self.peak_concentrations
+            .resize(self.concentrations.len(), 0.0);
+        self.peak_concentrations[id.index()] = initial_concentration.abs();
+        id
+    }
+
+    pub fn add_reaction(&mut self, reaction: Reaction) -> BiologyResult<()> {
+        if let Some(&(s, _)) = reaction
+            .reactants
+            .iter()
+            .chain(&reaction.products)
+            .find(|&&(s, _)| !self.molecules.contains(s))
+        {
+            return Err(BiologyError::InvalidParameter(format!(
+                "reaction {} refers to unknown molecule id {}",
+                reaction.name,
+                s.index()
+            )));
+        }
+        self.reactions.push(reaction);
+        Ok(())
+    }
+
+    pub fn with_solver(mut self, method: SolverMethod) -> Self {
+        self.solver.method = method;
+        self
+    }
+
+    pub fn with_solver_options(mut self, options: SolverOptions) -> Self {
+        self.solver = options;
+        self
+    }
+
+    pub fn id(&self, name: &str) -> Option<MoleculeId> {
+        self.molecules.id(name)
+    }
+
+    pub fn concentration(&self, name: &str) -> Option<f64> {
+        self.id(name).map(|id| self.concentrations[id.index()])
+    }
+
+    pub fn concentration_of(&self, id: MoleculeId) -> f64 {
+        self.concentrations[id.index()]
+    }
+
+    // Networks saved before peaks were tracked fall back to the current state.
+    pub fn peak_concentration(&self, id: MoleculeId) -> f64 {
+        let current = self.concentrations[id.index()].abs();
+        self.peak_concentrations
+            .get(id.index())
+            .map_or(current, |&peak| peak.max(current))
+    }
+
+    // Integrates for `duration` and keeps the final state.
+    pub fn simulate(&mut self, duration: f64) -> BiologyResult<OdeSolution> {
+        let solution = integrate(
+            &*self,
+            self.time,
+            &self.concentrations,
+            self.time + duration,
+            self.solver,
+        )?;
+        self.time += duration;
+        self.concentrations = solution.last().to_vec();
+        self.peak_concentrations
+            .resize(self.concentrations.len(), 0.0);
+        for state in &solution.y {
+            for (peak, c) in self.peak_concentrations.iter_mut().zip(state) {
+                *peak = peak.max(c.abs());
+            }
+        }
+        Ok(solution)
+    }
+}
+
+impl Default for ReactionNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OdeSystem for ReactionNetwork {
+    fn dimension(&self) -> usize {
+        self.molecules.len()
+    }
+
+    fn rhs(&self, _t: f64, y: &[f64], dydt: &mut [f64]) {
+        dydt.fill(0.0);
+        for reaction in &self.reactions {
+            let v = reaction.rate(y);
+            for &(s, n) in &reaction.reactants {
+                dydt[s.index()] -= n * v;
+            }
+            for &(s, n) in &reaction.products {
+                dydt[s.index()] += n * v;
+            }
+        }
+    }
+
+    fn jacobian(&self, _t: f64, y: &[f64]) -> Option<DMatrix<f64>> {
+        let n = self.molecules.len();
+        let mut j = DMatrix::zeros(n, n);
+        for reaction in &self.reactions {
+            let mut columns: Vec<MoleculeId> = reaction.reactants.iter().map(|&(s, _)| s).collect();
+            columns.sort_unstable();
+            columns.dedup();
+            for col in columns {
+                let dv = reaction.rate_derivative(y, col);
+                for &(row, stoichiometry) in &reaction.reactants {
+                    j[(row.index(), col.index())] -= stoichiometry * dv;
+                }
+                for &(row, stoichiometry) in &reaction.products {
+                    j[(row.index(), col.index())] += stoichiometry * dv;
+                }
+            }
+        }
+        Some(j)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Robertson's autocatalytic kinetics, rate constants spanning nine
+    // orders of magnitude.
+    // Robertson HH (1966) in Numerical Analysis, Academic Press, pp 178-182
+    fn robertson() -> ReactionNetwork {
+        let mut network = ReactionNetwork::new();
+        let a = network.add_species("A", 1.0);
+        let b = network.add_species("B", 0.0);
+        let c = network.add_species("C", 0.0);
+        network
+            .add_reaction(
+                Reaction::builder("r1")
+                    .mass_action(0.04)
+                    .reactant(a, 1.0)
+                    .product(b, 1.0)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        network
+            .add_reaction(
+                Reaction::builder("r2")
+                    .mass_action(3e7)
+                    .reactant(b, 2.0)
+                    .product(b, 1.0)
+                    .product(c, 1.0)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        network
+            .add_reaction(
+                Reaction::builder("r3")
+                    .mass_action(1e4)
+                    .reactant(b, 1.0)
+                    .reactant(c, 1.0)
+                    .product(a, 1.0)
+                    .product(c, 1.0)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        network
+    }
+
+    #[test]
+    fn test_robertson_matches_reference_with_bdf() {
+        let mut network = robertson().with_solver_options(
+            SolverOptions::default()
+                .with_method(SolverMethod::Bdf)
+                .with_tolerances(1e-6, 1e-10),
+        );
+        let solution = network.simulate(40.0).unwrap();
+        let reference = [0.7158270687193772, 9.185534764557333e-6, 0.2841637457458479];
+        for (value, expected) in network.concentrations.iter().zip(reference) {
+            assert!(
+                (value - expected).abs() < 1e-3 * expected,
+                "{} vs {}",
+                value,
+                expected
+            );
+        }
+        let total: f64 = network.concentrations.iter().sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!(solution.stats.accepted_steps < 2000);
+    }
+
+    #[test]
+    fn test_auto_switches_to_implicit_on_robertson() {
+        let mut network = robertson();
+        let auto = network.simulate(40.0).unwrap();
+        assert!(auto.stats.method_switches >= 1);
+        assert!(auto.stats.implicit_steps > 0);
+        assert!((network.concentration("A").unwrap() - 0.7158270687193772).abs() < 1e-3);
+        let explicit = robertson()
+            .with_solver_options(
+                SolverOptions::default()
+                    .with_method(SolverMethod::DormandPrince)
+                    .with_max_steps(2000),
+            )
+            .simulate(40.0);
+        assert!(explicit.is_err());
+    }
+
+    #[test]
+    fn test_analytic_jacobian_matches_finite_differences() {
+        let mut network = robertson();
+        let m = network.add_species("M", 2.0);
+        network
+            .add_reaction(
+                Reaction::builder("mm")
+                    .michaelis_menten(3.0, 0.5)
+                    .reactant(m, 1.0)
+                    .product(network.id("A").unwrap(), 1.0)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let y = [0.6, 1e-3, 0.3, 2.0];
+        let analytic = network.jacobian(0.0, &y).unwrap();
+        let mut f0 = [0.0; 4];
+        network.rhs(0.0, &y, &mut f0);
+        for col in 0..4 {
+            let mut shifted = y;
+            let delta = 1e-7 * y[col];
+            shifted[col] += delta;
+            let mut f1 = [0.0; 4];
+            network.rhs(0.0, &shifted, &mut f1);
+            for row in 0..4 {
+                let numeric = (f1[row] - f0[row]) / delta;
+                let tolerance = 1e-4 * numeric.abs().max(1.0);
+                assert!((analytic[(row, col)] - numeric).abs() < tolerance);
+            }
+        }
+        let mut other = ReactionNetwork::new();
+        for name in ["P", "Q", "R", "S", "T"] {
+            other.add_species(name, 0.0);
+        }
+        let foreign = other.id("T").unwrap();
+        assert!(network
+            .add_reaction(
+                Reaction::builder("bad")
+                    .mass_action(1.0)
+                    .reactant(foreign, 1.0)
+                    .build()
+                    .unwrap()
+            )
+            .is_err());
+        let a = network.id("A").unwrap();
+        assert_eq!(network.add_species("A", 0.5), a);
+        assert_eq!(network.concentration_of(a), 0.5);
+        assert_eq!(network.concentrations.len(), 4);
+    }
+
+    #[test]
+    fn test_builder_validates_at_build() {
+        let mut network = ReactionNetwork::new();
+        let atp = network.add_species("ATP", 1.0);
+        let adp = network.add_species("ADP", 0.0);
+        let hydrolysis = ReactionBuilder::new("hydrolysis")
+            .reactant(atp, 1.0)
+            .product(adp, 1.0)
+            .mass_action(0.1)
+            .build()
+            .unwrap();
+        assert_eq!(hydrolysis.rate(&network.concentrations), 0.1);
+
+        let base = || Reaction::builder("hydrolysis").reactant(atp, 1.0);
+        assert!(base().build().is_err());
+        assert!(base().mass_action(-1.0).build().is_err());
+        assert!(base().michaelis_menten(1.0, 0.0).build().is_err());
+        assert!(base().reactant(atp, 1.0).mass_action(1.0).build().is_err());
+        assert!(base().product(adp, 0.0).mass_action(1.0).build().is_err());
+        assert!(Reaction::builder("influx")
+            .product(adp, 1.0)
+            .michaelis_menten(1.0, 1.0)
+            .build()
+            .is_err());
+        let error = Reaction::builder("synthesis").mass_action(1.0).build();
+        assert!(error
+            .unwrap_err()
+            .to_string()
+            .contains("reaction synthesis"));
+    }
+
+    #[test]
+    fn test_deserialize_rejects_inconsistent_networks() {
+        use crate::io::nonfinite;
+
+        let mut network = ReactionNetwork::new();
+        let atp = network.add_species("ATP", 1.0);
+        let adp = network.add_species("ADP", 0.0);
+        network
+            .add_reaction(
+                Reaction::builder("hydrolysis")
+                    .reactant(atp, 1.0)
+                    .product(adp, 1.0)
+                    .mass_action(0.1)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        // The default max_step is infinite, so go through the lossless codec.
+        let json = nonfinite::to_value(&network).unwrap();
+        let loaded: ReactionNetwork = nonfinite::from_value(json.clone()).unwrap();
+        assert_eq!(loaded, network);
+
+        let load = |edit: &dyn Fn(&mut serde_json::Value)| {
+            let mut value = json.clone();
+            edit(&mut value);
+            nonfinite::from_value::<ReactionNetwork>(value)
+        };
+        let unknown_id = load(&|v| v["reactions"][0]["products"][0][0] = 7.into());
+        assert!(unknown_id
+            .unwrap_err()
+            .to_string()
+            .contains("unknown molecule id 7"));
+        assert!(load(&|v| v["concentrations"] = serde_json::json!([1.0])).is_err());
+        assert!(load(&|v| v["peak_concentrations"] = serde_json::json!([1.0])).is_err());
+        let duplicate = load(&|v| v["molecules"][1] = v["molecules"][0].clone());
+        assert!(duplicate
+            .unwrap_err()
+            .to_string()
+            .contains("ATP is listed twice"));
+        assert!(load(&|v| {
+            v.as_object_mut().unwrap().remove("peak_concentrations");
+        })
+        .is_ok());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::geometry::Vec3;
+use serde::{Deserialize, Serialize};
+
+// Atomic coordinates of a molecule in ångströms with its covalent bonds,
+// enough to draw ball-and-stick models and check bond geometry.
+
+// Bond search slack added to the summed covalent radii.
+const BOND_TOLERANCE_ANGSTROM: f64 = 0.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Element {
+    H,
+    C,
+    N,
+    O,
+    Na,
+    P,
+    S,
+    Cl,
+    Ca,
+    Fe,
+}
+
+impl Element {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Element::H => "H",
+            Element::C => "C",
+            Element::N => "N",
+            Element::O => "O",
+            Element::Na => "Na",
+            Element::P => "P",
+            Element::S => "S",
+            Element::Cl => "Cl",
+            Element::Ca => "Ca",
+            Element::Fe => "Fe",
+        }
+    }
+
+    // Cordero B et al. (2008) Dalton Trans 2832-2838, doi 10.1039/b801115j
+    pub fn covalent_radius_angstrom(self) -> f64 {
+        match self {
+            Element::H => 0.31,
+            Element::C => 0.76,
+            Element::N => 0.71,
+            Element::O => 0.66,
+            Element::Na => 1.66,
+            Element::P => 1.07,
+            Element::S => 1.05,
+            Element::Cl => 1.02,
+            Element::Ca => 1.76,
+            Element::Fe => 1.32,
+        }
+    }
+
+    // CPK colouring as used by Jmol and most molecular viewers.
+    pub fn cpk_color(self) -> [u8; 3] {
+        match self {
+            Element::H => [255, 255, 255],
+            Element::C => [144, 144, 144],
+            Element::N => [48, 80, 248],
+            Element::O => [255, 13, 13],
+            Element::Na => [171, 92, 242],
+            Element::P => [255, 128, 0],
+            Element::S => [255, 255, 48],
+            Element::Cl => [31, 240, 31],
+            Element::Ca => [61, 255, 0],
+            Element::Fe => [224, 102, 51],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Atom {
+    pub element: Element,
+    pub position: Vec3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bond {
+    pub first: usize,
+    pub second: usize,
+    pub order: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MolecularStructure {
+    pub name: String,
+    pub atoms: Vec<Atom>,
+    pub bonds: Vec<Bond>,
+}
+
+impl MolecularStructure {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            atoms: Vec::new(),
+            bonds: Vec::new(),
+        }
+    }
+
+    pub fn add_atom(&mut self, element: Element, position: Vec3) -> usize {
+        self.atoms.push(Atom { element, position });
+        self.atoms.len() - 1
+    }
+
+    pub fn add_bond(&mut self, first: usize, second: usize, order: u8) -> BiologyResult<()> {
+        if first == second || first >= self.atoms.len() || second >= self.atoms.len() {
+            return Err(BiologyError::InvalidParameter(format!(
+                "bond {}-{} does not join two atoms of {}",
+                first, second, self.name
+            )));
+        }
+        if order == 0 || order > 3 {
+            return Err(BiologyError::InvalidValue(format!(
+                "bond order must be 1-3, got {}",
+                order
+            )));
+        }
+        self.bonds.push(Bond {
+            first,
+            second,
+            order,
+        });
+        Ok(())
+    }
+
+    // Single bonds between every pair closer than their summed covalent
+    // radii plus a fixed slack, for coordinates read without connectivity.
+    pub fn infer_bonds(&mut self) {
+        self.bonds.clear();
+        for i in 0..self.atoms.len() {
+            for j in i + 1..self.atoms.len() {
+                let (a, b) = (self.atoms[i], self.atoms[j]);
+                let reach = a.element.covalent_radius_angstrom()
+                    + b.element.covalent_radius_angstrom()
+                    + BOND_TOLERANCE_ANGSTROM;
+                if a.position.distance(b.position) <= reach {
+                    self.bonds.push(Bond {
+                        first: i,
+                        second: j,
+                        order: 1,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn bond_length(&self, bond: &Bond) -> f64 {
+        self.atoms[bond.first]
+            .position
+            .distance(self.atoms[bond.second].position)
+    }
+
+    pub fn centroid(&self) -> Vec3 {
+        if self.atoms.is_empty() {
+            return Vec3::ZERO;
+        }
+        let sum = self
+            .atoms
+            .iter()
+            .fold(Vec3::ZERO, |sum, atom| sum + atom.position);
+        sum * (1.0 / self.atoms.len() as f64)
+    }
+
+    // Gas-phase geometry: O-H 0.9572 Å, H-O-H 104.52°.
+    // Hoy AR, Bunker PR (1979) J Mol Spectrosc 74:1-8
+    pub fn water() -> Self {
+        let mut water = MolecularStructure::new("water");
+        let half_angle = (104.52_f64 / 2.0).to_radians();
+        let (x, y) = (0.9572 * half_angle.sin(), 0.9572 * half_angle.cos());
+        let o = water.add_atom(Element::O, Vec3::ZERO);
+        let h1 = water.add_atom(Element::H, Vec3::new(x, y, 0.0));
+        let h2 = water.add_atom(Element::H, Vec3::new(-x, y, 0.0));
+        water.add_bond(o, h1, 1).expect("atoms exist");
+        water.add_bond(o, h2, 1).expect("atoms exist");
+        water
+    }
+
+    // Linear, C=O 1.160 Å.
+    pub fn carbon_dioxide() -> Self {
+        let mut co2 = MolecularStructure::new("carbon_dioxide");
+        let c = co2.add_atom(Element::C, Vec3::ZERO);
+        for x in [-1.160, 1.160] {
+            let o = co2.add_atom(Element::O, Vec3::new(x, 0.0, 0.0));
+            co2.add_bond(c, o, 2).expect("atoms exist");
+        }
+        co2
+    }
+
+    // Tetrahedral orthophosphate, P-O 1.54 Å as in apatite.
+    pub fn phosphate() -> Self {
+        let mut phosphate = MolecularStructure::new("phosphate");
+        let p = phosphate.add_atom(Element::P, Vec3::ZERO);
+        let scale = 1.54 / 3.0_f64.sqrt();
+        for corner in [
+            [1.0, 1.0, 1.0],
+            [1.0, -1.0, -1.0],
+            [-1.0, 1.0, -1.0],
+            [-1.0, -1.0, 1.0],
+        ] {
+            let o = phosphate.add_atom(Element::O, Vec3::from(corner) * scale);
+            phosphate.add_bond(p, o, 1).expect("atoms exist");
+        }
+        phosphate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_water_geometry() {
+        let water = MolecularStructure::water();
+        for bond in &water.bonds {
+            assert!((water.bond_length(bond) - 0.9572).abs() < 1e-9);
+        }
+        let (o, h1, h2) = (
+            water.atoms[0].position,
+            water.atoms[1].position,
+            water.atoms[2].position,
+        );
+        let angle = (h1 - o).normalized().dot((h2 - o).normalized()).acos();
+        assert!((angle.to_degrees() - 104.52).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_inferred_bonds_match_explicit() {
+        let mut phosphate = MolecularStructure::phosphate();
+        let explicit = phosphate.bonds.len();
+        phosphate.infer_bonds();
+        assert_eq!(phosphate.bonds.len(), explicit);
+        assert!(phosphate.centroid().norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_rejects_bad_bonds() {
+        let mut co2 = MolecularStructure::carbon_dioxide();
+        assert!(co2.add_bond(0, 0, 1).is_err());
+        assert!(co2.add_bond(0, 7, 1).is_err());
+        assert!(co2.add_bond(1, 2, 4).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::cardiovascular::hematology::BiologicalSex;
+use serde::{Deserialize, Serialize};
+
+// Reference individual that organ-level defaults are tuned for.
+pub const REFERENCE_WEIGHT_KG: f64 = 70.0;
+pub const REFERENCE_HEIGHT_CM: f64 = 170.0;
+// Whole-body mineral content by DXA is ~5% of fat-free mass.
+const BONE_MINERAL_FRACTION_OF_FFM: f64 = 0.05;
+
+// Du Bois D, Du Bois EF (1916) Arch Intern Med 17:863-871
+pub fn du_bois_bsa_m2(weight_kg: f64, height_cm: f64) -> f64 {
+    0.007184 * weight_kg.powf(0.425) * height_cm.powf(0.725)
+}
+
+// Mosteller RD (1987) N Engl J Med 317:1098, PMID 3657876
+pub fn mosteller_bsa_m2(weight_kg: f64, height_cm: f64) -> f64 {
+    (weight_kg * height_cm / 3600.0).sqrt()
+}
+
+pub fn body_mass_index(weight_kg: f64, height_cm: f64) -> f64 {
+    let height_m = height_cm / 100.0;
+    weight_kg / (height_m * height_m)
+}
+
+// Deurenberg P, Weststrate JA, Seidell JC (1991) Br J Nutr 65:105-114, PMID 2043597
+pub fn deurenberg_body_fat_fraction(bmi: f64, age_years: f64, sex: BiologicalSex) -> f64 {
+    let male = match sex {
+        BiologicalSex::Male => 1.0,
+        BiologicalSex::Female => 0.0,
+    };
+    ((1.2 * bmi + 0.23 * age_years - 10.8 * male - 5.4) / 100.0).clamp(0.03, 0.7)
+}
+
+// Quarter-power exponents for scaling a quantity from one body mass to
+// another: volumes ~M^1, rates and flows ~M^0.75, times ~M^0.25.
+// Kleiber M (1932) Hilgardia 6:315-353
+// West GB, Brown JH, Enquist BJ (1997) Science 276:122-126, PMID 9082983
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AllometricQuantity {
+    Volume,
+    Rate,
+    Time,
+}
+
+impl AllometricQuantity {
+    pub fn exponent(&self) -> f64 {
+        match self {
+            AllometricQuantity::Volume => 1.0,
+            AllometricQuantity::Rate => 0.75,
+            AllometricQuantity::Time => 0.25,
+        }
+    }
+}
+
+pub fn allometric_scale(
+    reference_value: f64,
+    reference_mass_kg: f64,
+    mass_kg: f64,
+    exponent: f64,
+) -> f64 {
+    reference_value * (mass_kg / reference_mass_kg).powf(exponent)
+}
+
+// Three-compartment split of body mass.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BodyComposition {
+    pub fat_mass_kg: f64,
+    pub lean_soft_tissue_kg: f64,
+    pub bone_mineral_kg: f64,
+}
+
+impl BodyComposition {
+    pub fn from_fat_fraction(weight_kg: f64, fat_fraction: f64) -> BiologyResult<Self> {
+        if weight_kg <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "body weight must be positive".to_string(),
+            ));
+        }
+        if !(0.0..1.0).contains(&fat_fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "body fat fraction must lie in [0, 1)".to_string(),
+            ));
+        }
+        let fat_mass_kg = weight_kg * fat_fraction;
+        let fat_free = weight_kg - fat_mass_kg;
+        let bone_mineral_kg = BONE_MINERAL_FRACTION_OF_FFM * fat_free;
+        Ok(Self {
+            fat_mass_kg,
+            lean_soft_tissue_kg: fat_free - bone_mineral_kg,
+            bone_mineral_kg,
+        })
+    }
+
+    // Estimate from anthropometry when no DXA or impedance measure exists.
+    pub fn estimate(
+        weight_kg: f64,
+        height_cm: f64,
+        age_years: f64,
+        sex: BiologicalSex,
+    ) -> BiologyResult<Self> {
+        let bmi = body_mass_index(weight_kg, height_cm);
+        Self::from_fat_fraction(weight_kg, deurenberg_body_fat_fraction(bmi, age_years, sex))
+    }
+
+    pub fn total_mass_kg(&self) -> f64 {
+        self.fat_mass_kg + self.fat_free_mass_kg()
+    }
+
+    pub fn fat_free_mass_kg(&self) -> f64 {
+        self.lean_soft_tissue_kg + self.bone_mineral_kg
+    }
+
+    pub fn fat_fraction(&self) -> f64 {
+        self.fat_mass_kg / self.total_mass_kg()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_area_formulas_agree() {
+        let du_bois = du_bois_bsa_m2(REFERENCE_WEIGHT_KG, REFERENCE_HEIGHT_CM);
+        let mosteller = mosteller_bsa_m2(REFERENCE_WEIGHT_KG, REFERENCE_HEIGHT_CM);
+        assert!((1.78..1.84).contains(&du_bois));
+        assert!((du_bois - mosteller).abs() < 0.03);
+        assert!((body_mass_index(70.0, 170.0) - 24.2).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_estimated_composition_differs_by_sex() {
+        let male = BodyComposition::estimate(75.0, 175.0, 35.0, BiologicalSex::Male).unwrap();
+        let female = BodyComposition::estimate(62.0, 162.0, 35.0, BiologicalSex::Female).unwrap();
+        assert!((0.15..0.25).contains(&male.fat_fraction()));
+        assert!((0.25..0.35).contains(&female.fat_fraction()));
+        assert!((male.total_mass_kg() - 75.0).abs() < 1e-9);
+        assert!((2.5..3.2).contains(&male.bone_mineral_kg));
+        assert!(BodyComposition::from_fat_fraction(70.0, 1.2).is_err());
+    }
+
+    #[test]
+    fn test_allometric_scale() {
+        let rate = AllometricQuantity::Rate.exponent();
+        assert_eq!(allometric_scale(100.0, 70.0, 70.0, rate), 100.0);
+        let doubled = allometric_scale(100.0, 70.0, 140.0, rate);
+        assert!((doubled - 100.0 * 2f64.powf(0.75)).abs() < 1e-9);
+        assert!(allometric_scale(1.0, 70.0, 20.0, AllometricQuantity::Time.exponent()) < 1.0);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::biology::endocrine::HpgAxis;
+use crate::biology::hepatic::HepaticFunction;
+use crate::biology::physiology::Thermoregulation;
+use crate::biology::renal::RenalFunction;
+use crate::biology::{BiologyError, BiologyResult};
+use crate::config::human_presets::HumanPreset;
+use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+use crate::organism::anthropometry::{
+    allometric_scale, body_mass_index, du_bois_bsa_m2, AllometricQuantity, BodyComposition,
+    REFERENCE_HEIGHT_CM, REFERENCE_WEIGHT_KG,
+};
+use crate::systems::cardiovascular::hematology::BiologicalSex;
+use serde::{Deserialize, Serialize};
+
+// A virtual individual: anthropometry plus the organ-level models, each
+// resized from its 70 kg / 170 cm defaults to this body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Human {
+    pub sex: BiologicalSex,
+    pub age_years: f64,
+    pub height_cm: f64,
+    pub weight_kg: f64,
+    pub composition: BodyComposition,
+    pub renal: RenalFunction,
+    pub hepatic: HepaticFunction,
+    pub glucose: GlucoseInsulinModel,
+    pub thermoregulation: Thermoregulation,
+    pub gonadal: HpgAxis,
+}
+
+impl Human {
+    pub fn new(
+        sex: BiologicalSex,
+        age_years: f64,
+        height_cm: f64,
+        weight_kg: f64,
+    ) -> BiologyResult<Self> {
+        validate_anthropometry(age_years, height_cm, weight_kg)?;
+        let mut human = Self {
+            sex,
+            age_years,
+            height_cm,
+            weight_kg,
+            composition: BodyComposition::estimate(weight_kg, height_cm, age_years, sex)?,
+            renal: RenalFunction::new_healthy_adult(),
+            hepatic: HepaticFunction::new_healthy(),
+            glucose: GlucoseInsulinModel::new_healthy(),
+            thermoregulation: Thermoregulation::new_adult(),
+            gonadal: HpgAxis::new(sex),
+        };
+        human.resize_organs(
+            REFERENCE_WEIGHT_KG,
+            du_bois_bsa_m2(REFERENCE_WEIGHT_KG, REFERENCE_HEIGHT_CM),
+        );
+        Ok(human)
+    }
+
+    pub fn from_preset(preset: &HumanPreset) -> BiologyResult<Self> {
+        Self::new(
+            preset.preset_type.sex(),
+            preset.age_years,
+            preset.height_cm,
+            preset.weight_kg,
+        )
+    }
+
+    // Replaces the anthropometric estimate with a measured (DXA, impedance)
+    // body fat fraction.
+    pub fn with_body_fat_fraction(mut self, fat_fraction: f64) -> BiologyResult<Self> {
+        self.composition = BodyComposition::from_fat_fraction(self.weight_kg, fat_fraction)?;
+        Ok(self)
+    }
+
+    pub fn bmi(&self) -> f64 {
+        body_mass_index(self.weight_kg, self.height_cm)
+    }
+
+    pub fn body_surface_area_m2(&self) -> f64 {
+        du_bois_bsa_m2(self.weight_kg, self.height_cm)
+    }
+
+    pub fn fat_free_mass_kg(&self) -> f64 {
+        self.composition.fat_free_mass_kg()
+    }
+
+    // Katch-McArdle: resting expenditure tracks fat-free mass.
+    // McArdle WD, Katch FI, Katch VL, Exercise Physiology 8e, Ch 8
+    pub fn basal_metabolic_rate_kcal_per_day(&self) -> f64 {
+        370.0 + 21.6 * self.fat_free_mass_kg()
+    }
+
+    // Scales a parameter quoted for the 70 kg reference to this body mass.
+    pub fn scale(&self, reference_value: f64, quantity: AllometricQuantity) -> f64 {
+        allometric_scale(
+            reference_value,
+            REFERENCE_WEIGHT_KG,
+            self.weight_kg,
+            quantity.exponent(),
+        )
+    }
+
+    // Weight change is taken up as fat; fat-free mass is held.
+    pub fn set_weight_kg(&mut self, weight_kg: f64) -> BiologyResult<()> {
+        validate_anthropometry(self.age_years, self.height_cm, weight_kg)?;
+        let fat_free = self.fat_free_mass_kg();
+        if weight_kg <= fat_free {
+            return Err(BiologyError::InvalidParameter(
+                "weight cannot fall below fat-free mass".to_string(),
+            ));
+        }
+        let previous_weight = self.weight_kg;
+        let previous_bsa = self.body_surface_area_m2();
+        self.weight_kg = weight_kg;
+        self.composition.fat_mass_kg = weight_kg - fat_free;
+        self.resize_organs(previous_weight, previous_bsa);
+        Ok(())
+    }
+
+    pub fn set_age_years(&mut self, age_years: f64) -> BiologyResult<()> {
+        validate_anthropometry(age_years, self.height_cm, self.weight_kg)?;
+        self.age_years = age_years;
+        self.renal.age_years = age_years;
+        Ok(())
+    }
+
+    // Filtration capacity is indexed to surface area; flows and clearances
+    // scale with M^0.75; pools with M.
+    fn resize_organs(&mut self, previous_weight_kg: f64, previous_bsa_m2: f64) {
+        let bsa_ratio = self.body_surface_area_m2() / previous_bsa_m2;
+        let mass_ratio = self.weight_kg / previous_weight_kg;
+        let flow_ratio = mass_ratio.powf(AllometricQuantity::Rate.exponent());
+
+        let glomeruli = &mut self.renal.glomeruli;
+        glomeruli.renal_plasma_flow_ml_min *= bsa_ratio;
+        glomeruli.ultrafiltration_coefficient_ml_min_mmhg *= bsa_ratio;
+        self.renal.weight_kg = self.weight_kg;
+        self.renal.age_years = self.age_years;
+        self.renal.is_female = self.sex == BiologicalSex::Female;
+
+        self.hepatic.blood_flow_ml_min *= flow_ratio;
+        self.hepatic.albumin_pool_g *= mass_ratio;
+        self.hepatic.weight_kg = self.weight_kg;
+
+        self.glucose.params.body_weight_kg = self.weight_kg;
+
+        self.thermoregulation.weight_kg = self.weight_kg;
+        self.thermoregulation.surface_area_m2 = self.body_surface_area_m2();
+    }
+}
+
+// The composition and surface-area equations used here are adult equations.
+fn validate_anthropometry(age_years: f64, height_cm: f64, weight_kg: f64) -> BiologyResult<()> {
+    if !(18.0..=110.0).contains(&age_years) {
+        return Err(BiologyError::InvalidParameter(
+            "age must lie in [18, 110] years".to_string(),
+        ));
+    }
+    if !(120.0..=230.0).contains(&height_cm) {
+        return Err(BiologyError::InvalidParameter(
+            "height must lie in [120, 230] cm".to_string(),
+        ));
+    }
+    if !(30.0..=300.0).contains(&weight_kg) {
+        return Err(BiologyError::InvalidParameter(
+            "weight must lie in [30, 300] kg".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::human_presets::PresetType;
+
+    #[test]
+    fn test_reference_individual_keeps_organ_defaults() {
+        let human = Human::new(
+            BiologicalSex::Male,
+            40.0,
+            REFERENCE_HEIGHT_CM,
+            REFERENCE_WEIGHT_KG,
+        )
+        .unwrap();
+        let renal = RenalFunction::new_healthy_adult();
+        assert!((human.renal.gfr_ml_min() - renal.gfr_ml_min()).abs() < 1e-9);
+        assert_eq!(
+            human.hepatic.blood_flow_ml_min,
+            HepaticFunction::new_healthy().blood_flow_ml_min
+        );
+        assert!((human.scale(10.0, AllometricQuantity::Rate) - 10.0).abs() < 1e-12);
+        assert!((1500.0..1900.0).contains(&human.basal_metabolic_rate_kcal_per_day()));
+    }
+
+    #[test]
+    fn test_larger_body_scales_organ_capacity() {
+        let small = Human::new(BiologicalSex::Female, 35.0, 155.0, 50.0).unwrap();
+        let large = Human::new(BiologicalSex::Male, 35.0, 190.0, 100.0).unwrap();
+        assert!(large.renal.gfr_ml_min() > 1.3 * small.renal.gfr_ml_min());
+        assert!(large.hepatic.blood_flow_ml_min > small.hepatic.blood_flow_ml_min);
+        assert_eq!(
+            large.thermoregulation.surface_area_m2,
+            large.body_surface_area_m2()
+        );
+        assert_eq!(large.glucose.params.body_weight_kg, 100.0);
+        assert!(small.renal.is_female && !large.renal.is_female);
+        assert!(
+            large.basal_metabolic_rate_kcal_per_day() > small.basal_metabolic_rate_kcal_per_day()
+        );
+    }
+
+    #[test]
+    fn test_from_preset_and_measured_composition() {
+        let preset = HumanPreset::from_preset_type(PresetType::ElderlyFemaleHealthy);
+        let human = Human::from_preset(&preset).unwrap();
+        assert_eq!(human.sex, BiologicalSex::Female);
+        assert_eq!(human.gonadal.sex, BiologicalSex::Female);
+        assert!((human.bmi() - preset.bmi()).abs() < 1e-9);
+
+        let lean = human.clone().with_body_fat_fraction(0.2).unwrap();
+        assert!(lean.fat_free_mass_kg() > human.fat_free_mass_kg());
+        assert!(human.with_body_fat_fraction(1.0).is_err());
+        assert!(Human::new(BiologicalSex::Male, 10.0, 140.0, 35.0).is_err());
+    }
+
+    #[test]
+    fn test_weight_gain_is_fat_and_rescales_organs() {
+        let mut human = Human::new(BiologicalSex::Male, 40.0, 175.0, 75.0).unwrap();
+        let fat_free = human.fat_free_mass_kg();
+        let flow = human.hepatic.blood_flow_ml_min;
+        human.set_weight_kg(95.0).unwrap();
+        assert!((human.fat_free_mass_kg() - fat_free).abs() < 1e-9);
+        assert!((human.composition.total_mass_kg() - 95.0).abs() < 1e-9);
+        let expected = flow * (95.0f64 / 75.0).powf(0.75);
+        assert!((human.hepatic.blood_flow_ml_min - expected).abs() < 1e-9);
+        assert!(human.set_weight_kg(fat_free - 1.0).is_err());
+
+        human.set_age_years(70.0).unwrap();
+        assert_eq!(human.renal.age_years, 70.0);
+    }
+}
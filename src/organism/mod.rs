@@ -0,0 +1,8 @@
+pub mod anthropometry;
+pub mod human;
+
+pub use anthropometry::{
+    allometric_scale, body_mass_index, du_bois_bsa_m2, mosteller_bsa_m2, AllometricQuantity,
+    BodyComposition,
+};
+pub use human::Human;
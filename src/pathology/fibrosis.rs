@@ -0,0 +1,316 @@
+use crate::aging::glycation::AgeCrosslinking;
+use crate::biology::physiology::properties::{
+    q10_factor, ChemicalProperty, PropertyConsumer, TYPICAL_ENZYME_Q10,
+};
+use crate::biology::signaling::hypoxia::{Hif1a, NORMOXIC_TISSUE_PO2_MMHG};
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Myofibroblast-driven repair: injury releases active TGF-β, which recruits
+// and activates myofibroblasts that secrete more TGF-β (autocrine loop) and
+// deposit collagen; myofibroblasts clear by apoptosis once the wound closes,
+// unless a stiff matrix keeps them activated.
+// Hinz B (2007) J Invest Dermatol 127:526-537, PMID 17299435
+// Desmoulière A et al. (1995) Am J Pathol 146:56-66, PMID 7856739
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FibroticTissue {
+    Skin,
+    Myocardium,
+    ImplantCapsule,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FibrosisParameters {
+    pub tgf_beta_decay_per_day: f64,
+    pub autocrine_tgf_beta_per_day: f64,
+    pub myofibroblast_activation_per_day: f64,
+    pub tgf_beta_half_max: f64,
+    pub myofibroblast_apoptosis_per_day: f64,
+    // Apoptosis is suppressed as the matrix stiffens.
+    pub stiffness_survival_gain: f64,
+    pub collagen_deposition_per_day: f64,
+    pub collagen_remodeling_per_day: f64,
+    pub lox_maturation_per_day: f64,
+}
+
+impl FibrosisParameters {
+    pub fn for_tissue(tissue: FibroticTissue) -> Self {
+        let skin = Self {
+            tgf_beta_decay_per_day: 1.0,
+            autocrine_tgf_beta_per_day: 0.1,
+            myofibroblast_activation_per_day: 0.3,
+            tgf_beta_half_max: 0.5,
+            myofibroblast_apoptosis_per_day: 0.08,
+            stiffness_survival_gain: 0.0,
+            collagen_deposition_per_day: 0.05,
+            collagen_remodeling_per_day: 0.02,
+            lox_maturation_per_day: 0.02,
+        };
+        match tissue {
+            FibroticTissue::Skin | FibroticTissue::ImplantCapsule => skin,
+            // Cardiac fibroblasts are mechanosensitive; myofibroblasts persist
+            // in human infarct scars for years.
+            // Willems IE et al. (1994) Am J Pathol 145:868-875, PMID 7943177
+            FibroticTissue::Myocardium => Self {
+                stiffness_survival_gain: 4.0,
+                collagen_remodeling_per_day: 0.01,
+                ..skin
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScarMechanics {
+    pub elastic_modulus_relative: f64,
+    pub tensile_strength_relative: f64,
+}
+
+impl ScarMechanics {
+    pub fn is_stiffer_and_weaker(&self) -> bool {
+        self.elastic_modulus_relative > 1.0 && self.tensile_strength_relative < 1.0
+    }
+}
+
+// Healed incisional skin plateaus near 80% of intact breaking strength.
+// Levenson SM et al. (1965) Ann Surg 161:293-308, PMID 14260029
+const MATURE_SCAR_STRENGTH: f64 = 0.8;
+// Mature pyridinoline crosslinks roughly double fibre stiffness.
+// Bailey AJ (2001) Mech Ageing Dev 122:735-755, PMID 11322995
+pub(crate) const LOX_CROSSLINK_STIFFENING: f64 = 1.0;
+// AGE crosslinks make collagen stiffer and more brittle.
+// Avery NC, Bailey AJ (2006) Pathol Biol 54:387-395, PMID 16962252
+const AGE_STIFFENING_PER_FOLD: f64 = 0.15;
+const AGE_EMBRITTLEMENT_PER_FOLD: f64 = 0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FibrosisModel {
+    pub tissue: FibroticTissue,
+    pub params: FibrosisParameters,
+    pub tgf_beta: f64,
+    pub myofibroblasts: f64,
+    // Collagen in units of the intact tissue content.
+    pub native_collagen: f64,
+    pub scar_collagen: f64,
+    pub scar_crosslink_maturity: f64,
+    pub age_crosslink_fold: f64,
+    pub defect_fraction: f64,
+    pub persistent_stimulus: f64,
+    // Tissue temperature sets the pace of lysyl oxidase crosslinking.
+    pub temperature_celsius: f64,
+    // Hypoxic wound beds induce LOX through HIF-1α.
+    pub tissue_po2_mmhg: f64,
+    injury_days_remaining: f64,
+    pub elapsed_days: f64,
+}
+
+impl FibrosisModel {
+    pub fn new(tissue: FibroticTissue) -> Self {
+        Self {
+            tissue,
+            params: FibrosisParameters::for_tissue(tissue),
+            tgf_beta: 0.0,
+            myofibroblasts: 0.0,
+            native_collagen: 1.0,
+            scar_collagen: 0.0,
+            scar_crosslink_maturity: 0.0,
+            age_crosslink_fold: 1.0,
+            defect_fraction: 0.0,
+            persistent_stimulus: 0.0,
+            temperature_celsius: 37.0,
+            tissue_po2_mmhg: NORMOXIC_TISSUE_PO2_MMHG,
+            injury_days_remaining: 0.0,
+            elapsed_days: 0.0,
+        }
+    }
+
+    // Glycated collagen carries its AGE burden into the scar.
+    pub fn with_glycation(mut self, glycation: &AgeCrosslinking) -> Self {
+        self.age_crosslink_fold = glycation.fold_over_age_matched().max(0.0);
+        self
+    }
+
+    // Destroys a fraction of native matrix; TGF-β is released during the
+    // ~3 day inflammatory phase and afterwards while the defect stays open.
+    pub fn injure(&mut self, defect_fraction: f64) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&defect_fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "defect fraction must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.native_collagen *= 1.0 - defect_fraction;
+        self.defect_fraction = (self.defect_fraction + defect_fraction).min(1.0);
+        self.injury_days_remaining = 3.0;
+        Ok(())
+    }
+
+    // A foreign body or unresolved depot keeps stimulating TGF-β release.
+    pub fn set_persistent_stimulus(&mut self, level: f64) -> BiologyResult<()> {
+        if level < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "persistent stimulus must be non-negative".to_string(),
+            ));
+        }
+        self.persistent_stimulus = level;
+        Ok(())
+    }
+
+    pub fn total_collagen(&self) -> f64 {
+        self.native_collagen + self.scar_collagen
+    }
+
+    pub fn step_day(&mut self) {
+        let p = self.params;
+        let dt = 1.0;
+        let open_defect = self.defect_fraction - self.scar_collagen.min(self.defect_fraction);
+        let injury = if self.injury_days_remaining > 0.0 {
+            self.injury_days_remaining -= dt;
+            self.defect_fraction
+        } else {
+            open_defect
+        };
+
+        let d_tgf =
+            injury + self.persistent_stimulus + p.autocrine_tgf_beta_per_day * self.myofibroblasts
+                - p.tgf_beta_decay_per_day * self.tgf_beta;
+
+        let stiffness_excess = (self.mechanics().elastic_modulus_relative - 1.0).max(0.0);
+        let apoptosis = p.myofibroblast_apoptosis_per_day
+            / (1.0 + p.stiffness_survival_gain * stiffness_excess);
+        let activation = p.myofibroblast_activation_per_day * self.tgf_beta
+            / (p.tgf_beta_half_max + self.tgf_beta);
+        let d_myo = activation * (1.0 - self.myofibroblasts) - apoptosis * self.myofibroblasts;
+
+        // Excess scar collagen beyond refilling the defect is slowly remodeled.
+        let deposited = p.collagen_deposition_per_day * self.myofibroblasts * dt;
+        let excess = (self.scar_collagen - self.defect_fraction).max(0.0);
+        let remodeled = p.collagen_remodeling_per_day * excess * dt;
+
+        // Newly laid collagen is uncrosslinked and dilutes maturity.
+        let new_total = self.scar_collagen + deposited - remodeled;
+        let lox_rate = p.lox_maturation_per_day
+            * q10_factor(TYPICAL_ENZYME_Q10, self.temperature_celsius)
+            * Hif1a::at_po2(self.tissue_po2_mmhg).lox_fold();
+        if new_total > 1e-12 {
+            let diluted =
+                self.scar_crosslink_maturity * (self.scar_collagen - remodeled) / new_total;
+            self.scar_crosslink_maturity = diluted + lox_rate * (1.0 - diluted) * dt;
+        }
+        self.scar_collagen = new_total.max(0.0);
+        self.tgf_beta = (self.tgf_beta + d_tgf * dt).max(0.0);
+        self.myofibroblasts = (self.myofibroblasts + d_myo * dt).clamp(0.0, 1.0);
+        self.elapsed_days += dt;
+    }
+
+    pub fn run_days(&mut self, days: usize) {
+        for _ in 0..days {
+            self.step_day();
+        }
+    }
+
+    // Modulus scales with collagen content and crosslinking; strength comes
+    // from intact matrix plus defect-bridging scar weighted by maturity.
+    pub fn mechanics(&self) -> ScarMechanics {
+        let age_excess = (self.age_crosslink_fold - 1.0).max(0.0);
+        let modulus = (self.native_collagen
+            + self.scar_collagen * (1.0 + LOX_CROSSLINK_STIFFENING * self.scar_crosslink_maturity))
+            * (1.0 + AGE_STIFFENING_PER_FOLD * age_excess);
+        let bridged = self.scar_collagen.min(self.defect_fraction);
+        let strength = (self.native_collagen
+            + bridged * MATURE_SCAR_STRENGTH * self.scar_crosslink_maturity)
+            / (1.0 + AGE_EMBRITTLEMENT_PER_FOLD * age_excess);
+        ScarMechanics {
+            elastic_modulus_relative: modulus,
+            tensile_strength_relative: strength,
+        }
+    }
+
+    pub fn has_resolved(&self) -> bool {
+        self.myofibroblasts < 0.05 && self.tgf_beta < 0.05
+    }
+}
+
+impl PropertyConsumer for FibrosisModel {
+    fn consume(&mut self, property: ChemicalProperty) {
+        match property {
+            ChemicalProperty::Temperature { celsius } => self.temperature_celsius = celsius,
+            ChemicalProperty::OxygenTension { mmhg } => self.tissue_po2_mmhg = mmhg,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::aging::glycation::CollagenTissue;
+
+    #[test]
+    fn test_skin_wound_regains_about_80_percent_strength() {
+        let mut wound = FibrosisModel::new(FibroticTissue::Skin);
+        wound.injure(1.0).unwrap();
+        wound.run_days(21);
+        assert!(wound.mechanics().tensile_strength_relative < 0.3);
+        wound.run_days(344);
+        let strength = wound.mechanics().tensile_strength_relative;
+        assert!(strength > 0.65 && strength < 0.9, "strength {strength}");
+        assert!(wound.has_resolved());
+    }
+
+    #[test]
+    fn test_mature_scar_is_stiffer_and_weaker() {
+        let mut wound = FibrosisModel::new(FibroticTissue::Skin);
+        wound.injure(1.0).unwrap();
+        wound.run_days(365);
+        assert!(wound.mechanics().is_stiffer_and_weaker());
+    }
+
+    #[test]
+    fn test_myocardial_fibrosis_persists() {
+        let mut infarct = FibrosisModel::new(FibroticTissue::Myocardium);
+        infarct.injure(0.3).unwrap();
+        infarct.run_days(365);
+        assert!(!infarct.has_resolved());
+        assert!(infarct.total_collagen() > 2.0);
+    }
+
+    #[test]
+    fn test_implant_capsule_forms_under_persistent_stimulus() {
+        let mut capsule = FibrosisModel::new(FibroticTissue::ImplantCapsule);
+        capsule.set_persistent_stimulus(0.2).unwrap();
+        capsule.run_days(180);
+        assert!(capsule.scar_collagen > 1.0);
+        assert!(capsule.mechanics().elastic_modulus_relative > 2.0);
+        assert!(capsule.set_persistent_stimulus(-1.0).is_err());
+        assert!(capsule.injure(1.5).is_err());
+    }
+
+    #[test]
+    fn test_glycated_collagen_makes_scar_stiffer_and_more_brittle() {
+        let mut glycated_skin = AgeCrosslinking::new(CollagenTissue::Skin, 50.0).unwrap();
+        for _ in 0..10 {
+            glycated_skin.step_years(200.0, 1.0);
+        }
+        let mut control = FibrosisModel::new(FibroticTissue::Skin);
+        let mut diabetic = FibrosisModel::new(FibroticTissue::Skin).with_glycation(&glycated_skin);
+        for wound in [&mut control, &mut diabetic] {
+            wound.injure(0.5).unwrap();
+            wound.run_days(365);
+        }
+        let (c, d) = (control.mechanics(), diabetic.mechanics());
+        assert!(d.elastic_modulus_relative > c.elastic_modulus_relative);
+        assert!(d.tensile_strength_relative < c.tensile_strength_relative);
+    }
+
+    #[test]
+    fn test_hypoxic_wound_bed_matures_crosslinks_faster() {
+        let mut perfused = FibrosisModel::new(FibroticTissue::Skin);
+        let mut ischaemic = perfused.clone();
+        ischaemic.consume(ChemicalProperty::OxygenTension { mmhg: 10.0 });
+        for wound in [&mut perfused, &mut ischaemic] {
+            wound.injure(1.0).unwrap();
+            wound.run_days(30);
+        }
+        assert!(ischaemic.scar_crosslink_maturity > 1.5 * perfused.scar_crosslink_maturity);
+    }
+}
@@ -1,6 +1,9 @@
+pub mod fibrosis;
 pub mod headache;
+pub mod oncology;
 
+pub use fibrosis::{FibrosisModel, FibrosisParameters, FibroticTissue, ScarMechanics};
 pub use headache::{
-    AuraSymptom, AutonomicSymptom, HeadacheProfile, HeadacheType, MigraineSubtype,
-    MigraineTrigger,
+    AuraSymptom, AutonomicSymptom, HeadacheProfile, HeadacheType, MigraineSubtype, MigraineTrigger,
 };
+pub use oncology::TumorModel;
@@ -0,0 +1,276 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::pathology::fibrosis::LOX_CROSSLINK_STIFFENING;
+use crate::systems::immune::exhaustion::TCellExhaustion;
+use serde::{Deserialize, Serialize};
+
+// Gompertzian growth, dN/dt = a N ln(K / N): near-exponential while small,
+// slowing as the tumour approaches a ~1 kg (1e12 cell) plateau.
+// Norton L (1988) Cancer Res 48:7067-7071
+const GOMPERTZ_RATE_PER_DAY: f64 = 0.002;
+const PLATEAU_CELLS: f64 = 1e12;
+// Effector recruitment, killing and inactivation follow the CTL/NK model
+// fitted to BCL1 lymphoma; killing is scaled by each clone's antigenicity.
+// Kuznetsov VA et al. (1994) Bull Math Biol 56:295-321
+const EFFECTOR_SOURCE_PER_DAY: f64 = 1.3e4;
+const EFFECTOR_RECRUITMENT_PER_DAY: f64 = 0.1245;
+const RECRUITMENT_HALF_SATURATION_CELLS: f64 = 2.019e7;
+const EFFECTOR_DEATH_PER_DAY: f64 = 0.0412;
+const EFFECTOR_INACTIVATION_PER_CELL: f64 = 3.42e-10;
+const KILLING_PER_EFFECTOR_PER_DAY: f64 = 1.101e-7;
+// Immunoediting: immune pressure selects variants that have lost antigen
+// presentation, which then grow out unopposed.
+// Dunn GP, Old LJ, Schreiber RD (2004) Annu Rev Immunol 22:329-360
+const ESCAPE_MUTATION_PER_DIVISION: f64 = 1e-6;
+const ESCAPE_ANTIGENICITY: f64 = 0.02;
+// Tumour cells per unit of antigen seen by the exhaustion model.
+const CELLS_PER_ANTIGEN_UNIT: f64 = 1e6;
+// Hypoxic tumour secretes LOX; crosslinked, stiffened stroma promotes
+// integrin signalling, proliferation and invasion.
+// Levental KR et al. (2009) Cell 139:891-906
+const HYPOXIA_HALF_CELLS: f64 = 1e9;
+const LOX_MATURATION_PER_DAY: f64 = 0.01;
+const STIFFNESS_GROWTH_GAIN: f64 = 0.5;
+// Linear-quadratic cell survival, α = 0.3 /Gy with α/β = 10 Gy.
+// Fowler JF (1989) Br J Radiol 62:679-694
+const ALPHA_PER_GY: f64 = 0.3;
+const BETA_PER_GY2: f64 = 0.03;
+// Radiation-killed cells release antigen and danger signals that prime
+// fresh effectors for about a week.
+const RADIATION_PRIMING_PER_GY: f64 = 0.05;
+const PRIMING_DECAY_PER_DAY: f64 = 0.15;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TumorModel {
+    pub days: f64,
+    pub immunogenic_cells: f64,
+    pub escape_cells: f64,
+    pub effector_cells: f64,
+    pub exhaustion: TCellExhaustion,
+    pub lox_crosslinking: f64,
+    pub lox_inhibition: f64,
+    pub immunocompetent: bool,
+    priming: f64,
+}
+
+impl TumorModel {
+    pub fn new(initial_cells: f64) -> BiologyResult<Self> {
+        if initial_cells.is_nan() || initial_cells < 1.0 {
+            return Err(BiologyError::InvalidValue(
+                "a tumour needs at least one cell".to_string(),
+            ));
+        }
+        Ok(Self {
+            days: 0.0,
+            immunogenic_cells: initial_cells,
+            escape_cells: 0.0,
+            effector_cells: EFFECTOR_SOURCE_PER_DAY / EFFECTOR_DEATH_PER_DAY,
+            exhaustion: TCellExhaustion::new(),
+            lox_crosslinking: 0.0,
+            lox_inhibition: 0.0,
+            immunocompetent: true,
+            priming: 0.0,
+        })
+    }
+
+    // No T or NK cells, as in a RAG/IL2RG-deficient host.
+    pub fn immunodeficient(mut self) -> Self {
+        self.immunocompetent = false;
+        self.effector_cells = 0.0;
+        self
+    }
+
+    pub fn total_cells(&self) -> f64 {
+        self.immunogenic_cells + self.escape_cells
+    }
+
+    pub fn escape_fraction(&self) -> f64 {
+        let total = self.total_cells();
+        if total > 0.0 {
+            self.escape_cells / total
+        } else {
+            0.0
+        }
+    }
+
+    pub fn hypoxic_fraction(&self) -> f64 {
+        let n = self.total_cells();
+        n / (n + HYPOXIA_HALF_CELLS)
+    }
+
+    // Stroma modulus relative to normal tissue.
+    pub fn matrix_stiffness(&self) -> f64 {
+        1.0 + LOX_CROSSLINK_STIFFENING * self.lox_crosslinking
+    }
+
+    // Invasive potential from hypoxia-driven motility in stiffened stroma.
+    pub fn invasion_index(&self) -> f64 {
+        self.hypoxic_fraction() * (self.matrix_stiffness() - 1.0)
+    }
+
+    pub fn set_checkpoint_blockade(&mut self, fraction: f64) -> BiologyResult<()> {
+        self.exhaustion.set_checkpoint_blockade(fraction)
+    }
+
+    pub fn set_lox_inhibition(&mut self, fraction: f64) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "LOX inhibition must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.lox_inhibition = fraction;
+        Ok(())
+    }
+
+    pub fn irradiate(&mut self, dose_gy: f64) -> BiologyResult<()> {
+        if dose_gy.is_nan() || dose_gy < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "radiation dose must be non-negative".to_string(),
+            ));
+        }
+        let survival = (-(ALPHA_PER_GY * dose_gy + BETA_PER_GY2 * dose_gy * dose_gy)).exp();
+        self.immunogenic_cells *= survival;
+        self.escape_cells *= survival;
+        if self.immunocompetent {
+            self.priming += RADIATION_PRIMING_PER_GY * dose_gy;
+        }
+        Ok(())
+    }
+
+    pub fn step(&mut self, dt_days: f64) {
+        let n = self.total_cells();
+        let growth_rate = if n >= 1.0 {
+            GOMPERTZ_RATE_PER_DAY
+                * (PLATEAU_CELLS / n).ln()
+                * (1.0 + STIFFNESS_GROWTH_GAIN * (self.matrix_stiffness() - 1.0))
+        } else {
+            0.0
+        };
+        let cytotoxicity = self.exhaustion.cytotoxicity();
+        let kill = KILLING_PER_EFFECTOR_PER_DAY * cytotoxicity * self.effector_cells;
+        let divisions = growth_rate.max(0.0) * self.immunogenic_cells;
+        let escaping = ESCAPE_MUTATION_PER_DIVISION * divisions;
+
+        let d_immunogenic =
+            growth_rate * self.immunogenic_cells - kill * self.immunogenic_cells - escaping;
+        let d_escape = growth_rate * self.escape_cells
+            - kill * ESCAPE_ANTIGENICITY * self.escape_cells
+            + escaping;
+        let visible = self.immunogenic_cells + ESCAPE_ANTIGENICITY * self.escape_cells;
+        let d_effector = if self.immunocompetent {
+            EFFECTOR_SOURCE_PER_DAY
+                + (EFFECTOR_RECRUITMENT_PER_DAY * visible
+                    / (RECRUITMENT_HALF_SATURATION_CELLS + visible)
+                    * self.exhaustion.il2_output()
+                    + self.priming)
+                    * self.effector_cells
+                - EFFECTOR_DEATH_PER_DAY * self.effector_cells
+                - EFFECTOR_INACTIVATION_PER_CELL * visible * self.effector_cells
+        } else {
+            0.0
+        };
+        let d_lox = LOX_MATURATION_PER_DAY
+            * self.hypoxic_fraction()
+            * (1.0 - self.lox_inhibition)
+            * (1.0 - self.lox_crosslinking);
+
+        if self.immunocompetent {
+            self.exhaustion
+                .step(visible / CELLS_PER_ANTIGEN_UNIT, dt_days);
+        }
+        self.immunogenic_cells = (self.immunogenic_cells + d_immunogenic * dt_days).max(0.0);
+        self.escape_cells = (self.escape_cells + d_escape * dt_days).max(0.0);
+        // Below one cell a clone is gone.
+        if self.immunogenic_cells < 1.0 {
+            self.immunogenic_cells = 0.0;
+        }
+        if self.escape_cells < 1.0 && d_escape <= 0.0 {
+            self.escape_cells = 0.0;
+        }
+        self.effector_cells = (self.effector_cells + d_effector * dt_days).max(0.0);
+        self.lox_crosslinking = (self.lox_crosslinking + d_lox * dt_days).min(1.0);
+        self.priming -= PRIMING_DECAY_PER_DAY * self.priming * dt_days;
+        self.days += dt_days;
+    }
+
+    pub fn run_days(&mut self, days: usize) {
+        for _ in 0..days * 10 {
+            self.step(0.1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gompertzian_growth_without_immunity() {
+        let mut tumor = TumorModel::new(1e6).unwrap().immunodeficient();
+        let mut sizes = vec![tumor.total_cells()];
+        for _ in 0..8 {
+            tumor.run_days(365);
+            sizes.push(tumor.total_cells());
+        }
+        let yearly_fold: Vec<f64> = sizes.windows(2).map(|w| w[1] / w[0]).collect();
+        assert!(yearly_fold.windows(2).all(|w| w[1] < w[0]));
+        assert!(tumor.total_cells() > 0.9 * PLATEAU_CELLS && tumor.total_cells() < PLATEAU_CELLS);
+        assert!(TumorModel::new(0.0).is_err());
+    }
+
+    #[test]
+    fn test_immunoediting_selects_escape_variants() {
+        let mut edited = TumorModel::new(1e5).unwrap();
+        let mut unopposed = TumorModel::new(1e5).unwrap().immunodeficient();
+        edited.run_days(365);
+        unopposed.run_days(365);
+        // Equilibrium: the immune system holds the tumour in check...
+        assert!(edited.total_cells() < 1e-3 * unopposed.total_cells());
+        // ...while selecting the variants it cannot see.
+        edited.run_days(2 * 365);
+        assert_eq!(edited.immunogenic_cells, 0.0);
+        assert!(edited.escape_fraction() > 0.99);
+        assert!(edited.total_cells() > 1e9);
+        assert!(unopposed.escape_fraction() < 0.01);
+    }
+
+    #[test]
+    fn test_lox_stiffening_drives_invasion() {
+        let mut tumor = TumorModel::new(1e9).unwrap().immunodeficient();
+        let mut treated = tumor.clone();
+        treated.set_lox_inhibition(0.9).unwrap();
+        assert_eq!(tumor.invasion_index(), 0.0);
+        tumor.run_days(365);
+        treated.run_days(365);
+        assert!(tumor.matrix_stiffness() > 1.8);
+        assert!(tumor.invasion_index() > 0.5);
+        assert!(treated.invasion_index() < 0.5 * tumor.invasion_index());
+        // Stiff stroma also feeds proliferation.
+        assert!(treated.total_cells() < tumor.total_cells());
+        assert!(tumor.set_lox_inhibition(2.0).is_err());
+    }
+
+    #[test]
+    fn test_radiotherapy_and_checkpoint_blockade() {
+        let mut untreated = TumorModel::new(1e9).unwrap();
+        let mut irradiated = untreated.clone();
+        // 30 x 2 Gy over six weeks.
+        for _ in 0..6 {
+            for _ in 0..5 {
+                irradiated.irradiate(2.0).unwrap();
+                irradiated.run_days(1);
+            }
+            irradiated.run_days(2);
+        }
+        untreated.run_days(42);
+        assert!(irradiated.total_cells() < 1e-3 * untreated.total_cells());
+        assert!(irradiated.irradiate(-1.0).is_err());
+
+        let mut blocked = TumorModel::new(1e9).unwrap();
+        let mut control = blocked.clone();
+        blocked.set_checkpoint_blockade(0.9).unwrap();
+        blocked.run_days(180);
+        control.run_days(180);
+        assert!(blocked.exhaustion.cytotoxicity() > control.exhaustion.cytotoxicity());
+        assert!(blocked.immunogenic_cells < control.immunogenic_cells);
+    }
+}
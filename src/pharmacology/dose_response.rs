@@ -0,0 +1,293 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::montecarlo::Ensemble;
+use nalgebra::{Matrix4, Vector4};
+use serde::{Deserialize, Serialize};
+
+// Four-parameter logistic (Hill) curve:
+//   y = bottom + (top - bottom) / (1 + (ec50 / dose)^hill_slope)
+// Fits are reported with bottom below top, so a negative Hill slope means
+// the response falls with dose and `ec50` is then an IC50.
+// DeLean A, Munson PJ, Rodbard D (1978) Am J Physiol 235:E97-E102
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FourParameterLogistic {
+    pub bottom: f64,
+    pub top: f64,
+    pub ec50: f64,
+    pub hill_slope: f64,
+}
+
+fn sigmoid(s: f64) -> f64 {
+    1.0 / (1.0 + (-s).exp())
+}
+
+impl FourParameterLogistic {
+    // Exponent of (ec50 / dose)^hill, kept in log space so steep curves
+    // at extreme doses neither overflow nor lose precision.
+    fn exponent(&self, dose: f64) -> f64 {
+        self.hill_slope * (self.ec50.ln() - dose.ln())
+    }
+
+    pub fn response(&self, dose: f64) -> f64 {
+        self.bottom + (self.top - self.bottom) * sigmoid(-self.exponent(dose))
+    }
+
+    // Dose giving `fraction` of the way from bottom to top, e.g. 0.9 for
+    // the EC90.
+    pub fn dose_at_fraction(&self, fraction: f64) -> Option<f64> {
+        if !(fraction > 0.0 && fraction < 1.0) || self.hill_slope == 0.0 {
+            return None;
+        }
+        Some(self.ec50 * (fraction / (1.0 - fraction)).powf(1.0 / self.hill_slope))
+    }
+
+    pub fn ic50(&self) -> Option<f64> {
+        (self.hill_slope < 0.0).then_some(self.ec50)
+    }
+
+    // With a single parameter vector [bottom, top, ln ec50, hill].
+    fn from_vector(p: &Vector4<f64>) -> Self {
+        Self {
+            bottom: p[0],
+            top: p[1],
+            ec50: p[2].exp(),
+            hill_slope: p[3],
+        }
+    }
+
+    fn gradient(&self, dose: f64) -> Vector4<f64> {
+        let s = self.exponent(dose);
+        let rising = sigmoid(s);
+        let falling = sigmoid(-s);
+        let spread = self.top - self.bottom;
+        Vector4::new(
+            rising,
+            falling,
+            -spread * self.hill_slope * rising * falling,
+            -spread * (self.ec50.ln() - dose.ln()) * rising * falling,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DoseResponseFit {
+    pub curve: FourParameterLogistic,
+    pub n: usize,
+    pub sum_squared_residuals: f64,
+    pub rmse: f64,
+    pub r_squared: f64,
+    pub iterations: usize,
+}
+
+const MAX_ITERATIONS: usize = 500;
+
+fn sum_squared_residuals(curve: &FourParameterLogistic, points: &[(f64, f64)]) -> f64 {
+    points
+        .iter()
+        .map(|&(dose, y)| (y - curve.response(dose)).powi(2))
+        .sum()
+}
+
+// Least-squares 4PL fit by Levenberg-Marquardt with Marquardt's diagonal
+// scaling, started from the plateaus at the dose extremes and the dose
+// whose response lies nearest their midpoint.
+// Marquardt DW (1963) J Soc Ind Appl Math 11:431-441, doi 10.1137/0111030
+pub fn fit_four_parameter_logistic(points: &[(f64, f64)]) -> BiologyResult<DoseResponseFit> {
+    if points
+        .iter()
+        .any(|&(dose, y)| !dose.is_finite() || dose <= 0.0 || !y.is_finite())
+    {
+        return Err(BiologyError::InvalidValue(
+            "dose-response points need positive finite doses and finite responses".to_string(),
+        ));
+    }
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut doses: Vec<f64> = sorted.iter().map(|p| p.0).collect();
+    doses.dedup();
+    if points.len() < 5 || doses.len() < 4 {
+        return Err(BiologyError::InvalidParameter(
+            "a four-parameter fit needs at least five points at four doses".to_string(),
+        ));
+    }
+
+    let mean_at = |dose: f64| {
+        let ys: Vec<f64> = sorted.iter().filter(|p| p.0 == dose).map(|p| p.1).collect();
+        ys.iter().sum::<f64>() / ys.len() as f64
+    };
+    let low = mean_at(doses[0]);
+    let high = mean_at(doses[doses.len() - 1]);
+    let midpoint = 0.5 * (low + high);
+    let ec50_guess = doses
+        .iter()
+        .copied()
+        .min_by(|&a, &b| {
+            (mean_at(a) - midpoint)
+                .abs()
+                .total_cmp(&(mean_at(b) - midpoint).abs())
+        })
+        .expect("at least four doses");
+    let mut p = Vector4::new(low, high, ec50_guess.ln(), 1.0);
+    let mut curve = FourParameterLogistic::from_vector(&p);
+    let mut sse = sum_squared_residuals(&curve, points);
+    let mut lambda = 1e-3;
+    let mut iterations = 0;
+
+    while iterations < MAX_ITERATIONS {
+        iterations += 1;
+        let mut jtj = Matrix4::zeros();
+        let mut jtr = Vector4::zeros();
+        for &(dose, y) in points {
+            let g = curve.gradient(dose);
+            jtj += g * g.transpose();
+            jtr += g * (y - curve.response(dose));
+        }
+        let mut improved = false;
+        while lambda < 1e12 {
+            let mut damped = jtj;
+            for i in 0..4 {
+                damped[(i, i)] += lambda * jtj[(i, i)].max(1e-12);
+            }
+            let Some(step) = damped.lu().solve(&jtr) else {
+                lambda *= 10.0;
+                continue;
+            };
+            let candidate_p = p + step;
+            let candidate = FourParameterLogistic::from_vector(&candidate_p);
+            let candidate_sse = sum_squared_residuals(&candidate, points);
+            if candidate_sse.is_finite() && candidate_sse <= sse {
+                let converged = sse - candidate_sse <= 1e-12 * sse.max(f64::MIN_POSITIVE)
+                    && step.norm() <= 1e-10 * (1.0 + p.norm());
+                p = candidate_p;
+                curve = candidate;
+                sse = candidate_sse;
+                lambda = (lambda / 10.0).max(1e-12);
+                improved = !converged;
+                break;
+            }
+            lambda *= 10.0;
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    // Report the plateaus in order; (b, t, h) and (t, b, -h) are the same
+    // curve.
+    if curve.top < curve.bottom {
+        std::mem::swap(&mut curve.top, &mut curve.bottom);
+        curve.hill_slope = -curve.hill_slope;
+    }
+    let n = points.len();
+    let mean = points.iter().map(|p| p.1).sum::<f64>() / n as f64;
+    let total: f64 = points.iter().map(|p| (p.1 - mean).powi(2)).sum();
+    Ok(DoseResponseFit {
+        curve,
+        n,
+        sum_squared_residuals: sse,
+        rmse: (sse / n as f64).sqrt(),
+        r_squared: if total > 0.0 { 1.0 - sse / total } else { 1.0 },
+        iterations,
+    })
+}
+
+// (dose, response) pairs from the replicates of a Monte Carlo ensemble
+// that recorded both, e.g. a sampled antigen dose against peak titre.
+pub fn dose_response_points(ensemble: &Ensemble, dose: &str, output: &str) -> Vec<(f64, f64)> {
+    ensemble
+        .replicates
+        .iter()
+        .filter_map(|r| Some((*r.parameters.values.get(dose)?, *r.outputs.get(output)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::montecarlo::{MonteCarlo, ParameterDistribution, RecordedOutputs};
+    use rand::Rng;
+
+    fn doses() -> Vec<f64> {
+        (0..10).map(|i| 0.01 * 3f64.powi(i)).collect()
+    }
+
+    #[test]
+    fn test_recovers_activation_curve() {
+        let truth = FourParameterLogistic {
+            bottom: 5.0,
+            top: 95.0,
+            ec50: 2.0,
+            hill_slope: 1.4,
+        };
+        // Duplicates with a fixed +/- 1 unit offset.
+        let points: Vec<(f64, f64)> = doses()
+            .into_iter()
+            .flat_map(|d| [(d, truth.response(d) + 1.0), (d, truth.response(d) - 1.0)])
+            .collect();
+        let fit = fit_four_parameter_logistic(&points).unwrap();
+        assert!((fit.curve.ec50 - 2.0).abs() < 0.01);
+        assert!((fit.curve.hill_slope - 1.4).abs() < 0.01);
+        assert!((fit.curve.top - 95.0).abs() < 0.1);
+        assert!((fit.rmse - 1.0).abs() < 1e-6);
+        assert!(fit.r_squared > 0.99);
+        assert!(fit.curve.ic50().is_none());
+        let ec90 = fit.curve.dose_at_fraction(0.9).unwrap();
+        assert!((fit.curve.response(ec90) - (5.0 + 0.9 * 90.0)).abs() < 0.2);
+    }
+
+    #[test]
+    fn test_inhibition_reports_ic50_with_negative_slope() {
+        // Competitive inhibition at [S] = Km: v/Vmax = 1 / (2 + I/Ki),
+        // so IC50 = 2 Ki with a Hill slope of -1 (Cheng-Prusoff).
+        let ki = 0.5;
+        let points: Vec<(f64, f64)> = doses()
+            .into_iter()
+            .map(|i| (i, 100.0 * 2.0 / (2.0 + i / ki)))
+            .collect();
+        let fit = fit_four_parameter_logistic(&points).unwrap();
+        let ic50 = fit.curve.ic50().unwrap();
+        assert!((ic50 - 2.0 * ki).abs() < 1e-4);
+        assert!((fit.curve.hill_slope + 1.0).abs() < 1e-4);
+        assert!(fit.curve.bottom.abs() < 0.1 && (fit.curve.top - 100.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_fits_recorded_ensemble_outputs() {
+        let truth = FourParameterLogistic {
+            bottom: 0.0,
+            top: 1.0,
+            ec50: 10.0,
+            hill_slope: 2.0,
+        };
+        let ensemble = MonteCarlo::new(60, 4)
+            .with_parameter(
+                "antigen_ug",
+                ParameterDistribution::LogNormal {
+                    median: 10.0,
+                    geometric_sd: 3.0,
+                },
+            )
+            .unwrap()
+            .with_worker_threads(1)
+            .run(|draw, rng| {
+                let dose = draw.get("antigen_ug")?;
+                let mut outputs = RecordedOutputs::new();
+                let noise = rng.gen_range(-0.02..0.02);
+                outputs.insert("seroconversion".to_string(), truth.response(dose) + noise);
+                Ok(outputs)
+            })
+            .unwrap();
+        let points = dose_response_points(&ensemble, "antigen_ug", "seroconversion");
+        assert_eq!(points.len(), 60);
+        assert!(dose_response_points(&ensemble, "adjuvant", "seroconversion").is_empty());
+        let fit = fit_four_parameter_logistic(&points).unwrap();
+        assert!((fit.curve.ec50 - 10.0).abs() < 0.5);
+        assert!(fit.rmse < 0.02);
+    }
+
+    #[test]
+    fn test_rejects_unusable_data() {
+        assert!(fit_four_parameter_logistic(&[(1.0, 1.0), (2.0, 2.0)]).is_err());
+        let with_zero: Vec<(f64, f64)> = (0..6).map(|i| (i as f64, i as f64)).collect();
+        assert!(fit_four_parameter_logistic(&with_zero).is_err());
+    }
+}
@@ -0,0 +1,454 @@
+use super::pharmacogenomics::PharmacogeneticGene;
+use super::pharmacokinetics::Pharmacokinetics;
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Hepatic CYP degradation rate constants (h⁻¹) used by turnover models.
+// Yang J et al. (2008) Curr Drug Metab 9(5):384-394, PMID 18537575.
+pub fn hepatic_degradation_rate_per_hour(enzyme: PharmacogeneticGene) -> Option<f64> {
+    match enzyme {
+        PharmacogeneticGene::CYP3A4 | PharmacogeneticGene::CYP3A5 => Some(0.019),
+        PharmacogeneticGene::CYP2D6 => Some(0.0226),
+        PharmacogeneticGene::CYP2C9 => Some(0.00963),
+        PharmacogeneticGene::CYP2C19 => Some(0.0267),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PerpetratorMechanism {
+    CompetitiveInhibition { ki_um: f64 },
+    MechanismBasedInhibition { kinact_per_hour: f64, ki_um: f64 },
+    Induction { emax_fold: f64, ec50_um: f64 },
+}
+
+impl PerpetratorMechanism {
+    // KI, kinact and EC50 divide or scale concentrations, so a zero or
+    // negative value gives infinite or negative clearance multipliers.
+    fn has_valid_constants(&self) -> bool {
+        let positive = |x: f64| x.is_finite() && x > 0.0;
+        match *self {
+            PerpetratorMechanism::CompetitiveInhibition { ki_um } => positive(ki_um),
+            PerpetratorMechanism::MechanismBasedInhibition {
+                kinact_per_hour,
+                ki_um,
+            } => positive(kinact_per_hour) && positive(ki_um),
+            PerpetratorMechanism::Induction { emax_fold, ec50_um } => {
+                emax_fold.is_finite() && emax_fold >= 0.0 && positive(ec50_um)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PerpetratorEffect {
+    pub enzyme: PharmacogeneticGene,
+    pub mechanism: PerpetratorMechanism,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubstrateFraction {
+    pub enzyme: PharmacogeneticGene,
+    pub fraction_metabolized: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegimenDrug {
+    pub name: String,
+    pub unbound_concentration_um: f64,
+    pub metabolized_by: Vec<SubstrateFraction>,
+    pub perpetrator_effects: Vec<PerpetratorEffect>,
+}
+
+impl RegimenDrug {
+    pub fn new(name: &str, unbound_concentration_um: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            unbound_concentration_um,
+            metabolized_by: Vec::new(),
+            perpetrator_effects: Vec::new(),
+        }
+    }
+
+    pub fn substrate_of(mut self, enzyme: PharmacogeneticGene, fraction_metabolized: f64) -> Self {
+        self.metabolized_by.push(SubstrateFraction {
+            enzyme,
+            fraction_metabolized,
+        });
+        self
+    }
+
+    pub fn perpetrator(
+        mut self,
+        enzyme: PharmacogeneticGene,
+        mechanism: PerpetratorMechanism,
+    ) -> Self {
+        self.perpetrator_effects
+            .push(PerpetratorEffect { enzyme, mechanism });
+        self
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        if self.unbound_concentration_um < 0.0 {
+            return Err(BiologyError::InvalidValue(format!(
+                "{}: unbound concentration must be non-negative",
+                self.name
+            )));
+        }
+        let total_fm: f64 = self
+            .metabolized_by
+            .iter()
+            .map(|s| s.fraction_metabolized)
+            .sum();
+        if self
+            .metabolized_by
+            .iter()
+            .any(|s| !(0.0..=1.0).contains(&s.fraction_metabolized))
+            || total_fm > 1.0 + 1e-9
+        {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{}: fractions metabolized must lie in [0, 1] and sum to at most 1",
+                self.name
+            )));
+        }
+        if let Some(effect) = self
+            .perpetrator_effects
+            .iter()
+            .find(|e| !e.mechanism.has_valid_constants())
+        {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{}: {:?} constants must be positive and finite",
+                self.name, effect.mechanism
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn turnover_terms(effects: &[(f64, &PerpetratorMechanism)]) -> (f64, f64) {
+    let mut synthesis_fold = 1.0;
+    let mut inactivation = 0.0;
+    for (conc, mechanism) in effects {
+        match mechanism {
+            PerpetratorMechanism::Induction { emax_fold, ec50_um } => {
+                synthesis_fold += emax_fold * conc / (ec50_um + conc);
+            }
+            PerpetratorMechanism::MechanismBasedInhibition {
+                kinact_per_hour,
+                ki_um,
+            } => {
+                inactivation += kinact_per_hour * conc / (ki_um + conc);
+            }
+            PerpetratorMechanism::CompetitiveInhibition { .. } => {}
+        }
+    }
+    (synthesis_fold, inactivation)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnzymePool {
+    pub enzyme: PharmacogeneticGene,
+    pub baseline_abundance: f64,
+    pub abundance: f64,
+    pub degradation_rate_per_hour: f64,
+}
+
+impl EnzymePool {
+    pub fn new(enzyme: PharmacogeneticGene) -> BiologyResult<Self> {
+        let kdeg = hepatic_degradation_rate_per_hour(enzyme).ok_or_else(|| {
+            BiologyError::InvalidParameter(format!("no cited turnover rate for {:?}", enzyme))
+        })?;
+        Ok(Self {
+            enzyme,
+            baseline_abundance: 1.0,
+            abundance: 1.0,
+            degradation_rate_per_hour: kdeg,
+        })
+    }
+
+    // Enzyme turnover with induction and mechanism-based inactivation:
+    // dE/dt = kdeg·E0·(1 + Emax·I/(EC50 + I)) - (kdeg + kinact·I/(KI + I))·E
+    // Mayhew BS et al. (2000) Drug Metab Dispos 28(9):1031-1037, PMID 10950845.
+    pub fn step(&mut self, dt_hours: f64, effects: &[(f64, &PerpetratorMechanism)]) {
+        let (synthesis_fold, inactivation) = turnover_terms(effects);
+
+        let synthesis = self.degradation_rate_per_hour * self.baseline_abundance * synthesis_fold;
+        let loss = (self.degradation_rate_per_hour + inactivation) * self.abundance;
+        self.abundance = (self.abundance + (synthesis - loss) * dt_hours).max(0.0);
+    }
+
+    // Reversible inhibition scales catalytic activity without changing abundance.
+    pub fn effective_activity(&self, effects: &[(f64, &PerpetratorMechanism)]) -> f64 {
+        let reversible: f64 = effects
+            .iter()
+            .map(|(conc, mechanism)| match mechanism {
+                PerpetratorMechanism::CompetitiveInhibition { ki_um } => conc / ki_um,
+                _ => 0.0,
+            })
+            .sum();
+        (self.abundance / self.baseline_abundance) / (1.0 + reversible)
+    }
+
+    pub fn steady_state_abundance(&self, effects: &[(f64, &PerpetratorMechanism)]) -> f64 {
+        let (synthesis_fold, inactivation) = turnover_terms(effects);
+        let kdeg = self.degradation_rate_per_hour;
+        self.baseline_abundance * kdeg * synthesis_fold / (kdeg + inactivation)
+    }
+}
+
+// Mechanistic static model: AUC ratio = 1 / Σ(fm_i·A_i + (1 − Σfm_i)),
+// where A_i is the relative activity of each enzyme pathway.
+// Fahmi OA et al. (2008) Drug Metab Dispos 36(8):1698-1708, PMID 18490435.
+pub fn auc_ratio(victim: &RegimenDrug, perpetrators: &[&RegimenDrug]) -> f64 {
+    let mut remaining = 1.0;
+    let mut metabolized = 0.0;
+    for pathway in &victim.metabolized_by {
+        remaining -= pathway.fraction_metabolized;
+        let effects: Vec<(f64, &PerpetratorMechanism)> = perpetrators
+            .iter()
+            .flat_map(|p| {
+                p.perpetrator_effects
+                    .iter()
+                    .filter(|e| e.enzyme == pathway.enzyme)
+                    .map(move |e| (p.unbound_concentration_um, &e.mechanism))
+            })
+            .collect();
+        let activity = match EnzymePool::new(pathway.enzyme) {
+            Ok(pool) => {
+                let steady = EnzymePool {
+                    abundance: pool.steady_state_abundance(&effects),
+                    ..pool
+                };
+                steady.effective_activity(&effects)
+            }
+            Err(_) => 1.0,
+        };
+        metabolized += pathway.fraction_metabolized * activity;
+    }
+    1.0 / (metabolized + remaining.max(0.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum InteractionSeverity {
+    None,
+    Weak,
+    Moderate,
+    Strong,
+}
+
+impl InteractionSeverity {
+    // FDA (2020) Clinical Drug Interaction Studies — Cytochrome P450 Enzyme-
+    // and Transporter-Mediated Drug Interactions, Guidance for Industry:
+    // inhibitors AUCR ≥5 strong, 2–5 moderate, 1.25–2 weak; inducers AUC
+    // decrease ≥80% strong, 50–80% moderate, 20–50% weak.
+    pub fn from_auc_ratio(ratio: f64) -> Self {
+        if ratio >= 5.0 || ratio <= 0.2 {
+            InteractionSeverity::Strong
+        } else if ratio >= 2.0 || ratio <= 0.5 {
+            InteractionSeverity::Moderate
+        } else if ratio >= 1.25 || ratio <= 0.8 {
+            InteractionSeverity::Weak
+        } else {
+            InteractionSeverity::None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractionWarning {
+    pub victim: String,
+    pub perpetrators: Vec<String>,
+    pub auc_ratio: f64,
+    pub severity: InteractionSeverity,
+    pub message: String,
+}
+
+pub fn check_regimen(regimen: &[RegimenDrug]) -> BiologyResult<Vec<InteractionWarning>> {
+    for drug in regimen {
+        drug.validate()?;
+    }
+
+    let mut warnings = Vec::new();
+    for (i, victim) in regimen.iter().enumerate() {
+        if victim.metabolized_by.is_empty() {
+            continue;
+        }
+        let perpetrators: Vec<&RegimenDrug> = regimen
+            .iter()
+            .enumerate()
+            .filter(|(j, p)| {
+                *j != i
+                    && p.perpetrator_effects
+                        .iter()
+                        .any(|e| victim.metabolized_by.iter().any(|s| s.enzyme == e.enzyme))
+            })
+            .map(|(_, p)| p)
+            .collect();
+        if perpetrators.is_empty() {
+            continue;
+        }
+
+        let ratio = auc_ratio(victim, &perpetrators);
+        let severity = InteractionSeverity::from_auc_ratio(ratio);
+        if severity == InteractionSeverity::None {
+            continue;
+        }
+        let names: Vec<String> = perpetrators.iter().map(|p| p.name.clone()).collect();
+        let direction = if ratio > 1.0 {
+            "increases"
+        } else {
+            "decreases"
+        };
+        warnings.push(InteractionWarning {
+            victim: victim.name.clone(),
+            message: format!(
+                "{:?} interaction: {} {} {} exposure {:.2}-fold",
+                severity,
+                names.join(" + "),
+                direction,
+                victim.name,
+                ratio
+            ),
+            perpetrators: names,
+            auc_ratio: ratio,
+            severity,
+        });
+    }
+
+    warnings.sort_by_key(|w| std::cmp::Reverse(w.severity));
+    Ok(warnings)
+}
+
+pub fn adjusted_pharmacokinetics(
+    pk: &Pharmacokinetics,
+    victim: &RegimenDrug,
+    regimen: &[RegimenDrug],
+) -> Pharmacokinetics {
+    let perpetrators: Vec<&RegimenDrug> =
+        regimen.iter().filter(|d| d.name != victim.name).collect();
+    pk.with_clearance_ratio(1.0 / auc_ratio(victim, &perpetrators))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strong_cyp3a4_inhibitor() -> RegimenDrug {
+        RegimenDrug::new("inhibitor", 1.0).perpetrator(
+            PharmacogeneticGene::CYP3A4,
+            PerpetratorMechanism::CompetitiveInhibition { ki_um: 0.05 },
+        )
+    }
+
+    fn cyp3a4_substrate() -> RegimenDrug {
+        RegimenDrug::new("substrate", 0.01).substrate_of(PharmacogeneticGene::CYP3A4, 0.9)
+    }
+
+    #[test]
+    fn test_competitive_inhibition_raises_exposure() {
+        let victim = cyp3a4_substrate();
+        let inhibitor = strong_cyp3a4_inhibitor();
+        let ratio = auc_ratio(&victim, &[&inhibitor]);
+        assert!(ratio > 5.0 && ratio < 10.0);
+    }
+
+    #[test]
+    fn test_induction_lowers_exposure() {
+        let victim = cyp3a4_substrate();
+        let inducer = RegimenDrug::new("inducer", 5.0).perpetrator(
+            PharmacogeneticGene::CYP3A4,
+            PerpetratorMechanism::Induction {
+                emax_fold: 8.0,
+                ec50_um: 0.5,
+            },
+        );
+        let ratio = auc_ratio(&victim, &[&inducer]);
+        assert!(ratio < 0.2);
+        assert_eq!(
+            InteractionSeverity::from_auc_ratio(ratio),
+            InteractionSeverity::Strong
+        );
+    }
+
+    #[test]
+    fn test_enzyme_pool_induction_time_course() {
+        let mut pool = EnzymePool::new(PharmacogeneticGene::CYP3A4).unwrap();
+        let induction = PerpetratorMechanism::Induction {
+            emax_fold: 4.0,
+            ec50_um: 1.0,
+        };
+        let effects = [(1.0, &induction)];
+        let target = pool.steady_state_abundance(&effects);
+
+        for _ in 0..(24 * 3) {
+            pool.step(1.0, &effects);
+        }
+        assert!(pool.abundance > 1.5 && pool.abundance < target);
+
+        for _ in 0..(24 * 30) {
+            pool.step(1.0, &effects);
+        }
+        assert!((pool.abundance - target).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_mechanism_based_inhibition_depletes_enzyme() {
+        let pool = EnzymePool::new(PharmacogeneticGene::CYP3A4).unwrap();
+        let mbi = PerpetratorMechanism::MechanismBasedInhibition {
+            kinact_per_hour: 2.0,
+            ki_um: 1.0,
+        };
+        let steady = pool.steady_state_abundance(&[(1.0, &mbi)]);
+        assert!(steady < 0.05);
+    }
+
+    #[test]
+    fn test_check_regimen_flags_and_ignores_unrelated_pathways() {
+        let regimen = vec![
+            cyp3a4_substrate(),
+            strong_cyp3a4_inhibitor(),
+            RegimenDrug::new("cyp2d6_substrate", 0.1)
+                .substrate_of(PharmacogeneticGene::CYP2D6, 0.8),
+        ];
+        let warnings = check_regimen(&regimen).unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].victim, "substrate");
+        assert_eq!(warnings[0].severity, InteractionSeverity::Strong);
+    }
+
+    #[test]
+    fn test_invalid_fraction_metabolized_rejected() {
+        let regimen =
+            vec![RegimenDrug::new("bad", 1.0).substrate_of(PharmacogeneticGene::CYP3A4, 1.4)];
+        assert!(check_regimen(&regimen).is_err());
+    }
+
+    #[test]
+    fn test_non_positive_perpetrator_constants_rejected() {
+        let victim = RegimenDrug::new("victim", 0.1).substrate_of(PharmacogeneticGene::CYP3A4, 0.9);
+        for mechanism in [
+            PerpetratorMechanism::CompetitiveInhibition { ki_um: 0.0 },
+            PerpetratorMechanism::MechanismBasedInhibition {
+                kinact_per_hour: -1.0,
+                ki_um: 1.0,
+            },
+            PerpetratorMechanism::Induction {
+                emax_fold: 5.0,
+                ec50_um: f64::NAN,
+            },
+        ] {
+            let perpetrator = RegimenDrug::new("perpetrator", 1.0)
+                .perpetrator(PharmacogeneticGene::CYP3A4, mechanism);
+            assert!(check_regimen(&[victim.clone(), perpetrator]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_adjusted_pharmacokinetics_prolongs_half_life() {
+        let pk = Pharmacokinetics::new(1.0, 3.0, 70.0);
+        let regimen = vec![cyp3a4_substrate(), strong_cyp3a4_inhibitor()];
+        let adjusted = adjusted_pharmacokinetics(&pk, &regimen[0], &regimen);
+        assert!(adjusted.metabolism.half_life_hours > 3.0 * 5.0);
+        assert!(adjusted.excretion.clearance_ml_per_min < pk.excretion.clearance_ml_per_min);
+    }
+}
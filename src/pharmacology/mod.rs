@@ -1,5 +1,20 @@
+pub mod bone_agents;
+pub mod dose_response;
+pub mod drug_interactions;
 pub mod pharmacogenomics;
 pub mod pharmacokinetics;
 
+pub use bone_agents::{
+    simulate_osteoporosis_treatment, Bisphosphonate, BisphosphonateRegimen, OsteoporosisTreatment,
+    ParathyroidHormoneRegimen, PthDelivery, RomosozumabRegimen, SerumRomosozumab,
+    SkeletalBisphosphonate, TreatmentOutcome,
+};
+pub use dose_response::{
+    dose_response_points, fit_four_parameter_logistic, DoseResponseFit, FourParameterLogistic,
+};
+pub use drug_interactions::{
+    auc_ratio, check_regimen, EnzymePool, InteractionSeverity, InteractionWarning,
+    PerpetratorEffect, PerpetratorMechanism, RegimenDrug, SubstrateFraction,
+};
 pub use pharmacogenomics::*;
 pub use pharmacokinetics::*;
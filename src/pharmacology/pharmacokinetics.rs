@@ -106,6 +106,15 @@ impl Pharmacokinetics {
         c0 * (-k * time_hours).exp()
     }
 
+    // CL = k·Vd, so a clearance ratio r scales t½ by 1/r at fixed Vd.
+    pub fn with_clearance_ratio(&self, clearance_ratio: f64) -> Self {
+        let ratio = clearance_ratio.max(1e-6);
+        let mut adjusted = self.clone();
+        adjusted.metabolism.half_life_hours /= ratio;
+        adjusted.excretion.clearance_ml_per_min *= ratio;
+        adjusted
+    }
+
     pub fn requires_dose_adjustment_renal(&self) -> bool {
         matches!(
             self.excretion.primary_route,
@@ -188,6 +197,16 @@ mod tests {
         assert!(c2 > 0.0);
     }
 
+    #[test]
+    fn test_clearance_ratio_scales_half_life() {
+        let pk = Pharmacokinetics::new(1.0, 4.0, 50.0);
+        let induced = pk.with_clearance_ratio(2.0);
+        assert!((induced.metabolism.half_life_hours - 2.0).abs() < 1e-9);
+        assert!(
+            induced.calculate_concentration(100.0, 4.0) < pk.calculate_concentration(100.0, 4.0)
+        );
+    }
+
     #[test]
     fn test_dose_adjustment_renal() {
         let adjustment = DoseAdjustment::renal_impairment(25.0);
@@ -0,0 +1,316 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::config::presets::FluidProperties;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+// Contact of two lubricated, curved, elastic surfaces, as in a joint
+// compartment. Dry contact follows Hertz for a point contact: contact
+// radius a = (3FR/4E*)^(1/3) and peak pressure p₀ = 3F/(2πa²), 1.5 times
+// the mean. Cartilage on bone is a thin layer, so Hertz overestimates the
+// contact area and underestimates pressure once a approaches the layer
+// thickness; it serves as the first estimate.
+// Johnson KL (1985) Contact Mechanics, ch. 4
+//
+// The fluid film is the soft-EHL (isoviscous-elastic) minimum thickness,
+// h = 2.8·R·Ū^0.65·W̄^-0.21 with Ū = η·u/(E*·R) and W̄ = F/(E*·R²), and the
+// film-to-roughness ratio Λ = h/σ sets the lubrication regime.
+// Hamrock BJ, Dowson D (1978) J Lubr Technol 100:236-245
+
+// Boundaries of the mixed regime in Λ.
+pub const BOUNDARY_LAMBDA: f64 = 1.0;
+pub const FULL_FILM_LAMBDA: f64 = 3.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ContactSurface {
+    // Radius of curvature; infinite for flat, negative for concave.
+    pub radius_m: f64,
+    pub youngs_modulus_pa: f64,
+    pub poisson_ratio: f64,
+    // RMS roughness.
+    pub roughness_m: f64,
+}
+
+impl ContactSurface {
+    pub fn new(
+        radius_m: f64,
+        youngs_modulus_pa: f64,
+        poisson_ratio: f64,
+        roughness_m: f64,
+    ) -> BiologyResult<Self> {
+        if radius_m.is_nan() || radius_m == 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "radius of curvature must be non-zero".to_string(),
+            ));
+        }
+        if youngs_modulus_pa.is_nan() || youngs_modulus_pa <= 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "Young's modulus must be positive, got {}",
+                youngs_modulus_pa
+            )));
+        }
+        if !(-1.0..0.5).contains(&poisson_ratio) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "Poisson's ratio must lie in [-1, 0.5), got {}",
+                poisson_ratio
+            )));
+        }
+        if roughness_m.is_nan() || roughness_m < 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "roughness must be non-negative, got {}",
+                roughness_m
+            )));
+        }
+        Ok(Self {
+            radius_m,
+            youngs_modulus_pa,
+            poisson_ratio,
+            roughness_m,
+        })
+    }
+
+    // Articular cartilage under gait-rate loading, which is too fast for
+    // interstitial fluid to flow out: the instantaneous modulus with near-
+    // incompressible response.
+    // Mow VC, Kuei SC, Lai WM, Armstrong CG (1980) J Biomech Eng 102:73-84
+    // Forster H, Fisher J (1999) Proc Inst Mech Eng H 213:329-345
+    pub fn articular_cartilage(radius_m: f64) -> BiologyResult<Self> {
+        Self::new(radius_m, 10.0e6, 0.45, 1.0e-6)
+    }
+
+    fn compliance(&self) -> f64 {
+        (1.0 - self.poisson_ratio * self.poisson_ratio) / self.youngs_modulus_pa
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LubricationRegime {
+    // Λ < 1: asperities carry the load.
+    Boundary,
+    Mixed,
+    // Λ > 3: the film separates the surfaces.
+    FullFilm,
+}
+
+impl LubricationRegime {
+    pub fn from_lambda(lambda: f64) -> Self {
+        if lambda < BOUNDARY_LAMBDA {
+            LubricationRegime::Boundary
+        } else if lambda < FULL_FILM_LAMBDA {
+            LubricationRegime::Mixed
+        } else {
+            LubricationRegime::FullFilm
+        }
+    }
+}
+
+// Coefficients at the two ends of the Stribeck curve.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Friction {
+    pub boundary: f64,
+    pub full_film: f64,
+}
+
+impl Friction {
+    // Cartilage on cartilage: ~0.15-0.3 once exudation leaves the solid
+    // matrix in contact, below 0.01 with the film and interstitial
+    // pressure carrying the load.
+    // Forster H, Fisher J (1996) Proc Inst Mech Eng H 210:109-119
+    pub fn cartilage() -> Self {
+        Self {
+            boundary: 0.2,
+            full_film: 0.005,
+        }
+    }
+
+    // Linear across the mixed regime in Λ.
+    pub fn coefficient(&self, lambda: f64) -> f64 {
+        let asperity_share =
+            ((FULL_FILM_LAMBDA - lambda) / (FULL_FILM_LAMBDA - BOUNDARY_LAMBDA)).clamp(0.0, 1.0);
+        self.full_film + (self.boundary - self.full_film) * asperity_share
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ContactState {
+    pub contact_radius_m: f64,
+    pub contact_area_m2: f64,
+    pub mean_pressure_pa: f64,
+    pub peak_pressure_pa: f64,
+    pub approach_m: f64,
+    pub film_thickness_m: f64,
+    pub lambda: f64,
+    pub regime: LubricationRegime,
+    pub friction_coefficient: f64,
+    pub friction_force_n: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JointContact {
+    pub first: ContactSurface,
+    pub second: ContactSurface,
+    pub lubricant: FluidProperties,
+    pub friction: Friction,
+}
+
+impl JointContact {
+    pub fn new(
+        first: ContactSurface,
+        second: ContactSurface,
+        lubricant: FluidProperties,
+        friction: Friction,
+    ) -> BiologyResult<Self> {
+        let contact = Self {
+            first,
+            second,
+            lubricant,
+            friction,
+        };
+        contact.lubricant.validate()?;
+        if contact.effective_radius_m().is_nan() || contact.effective_radius_m() <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "surfaces must be convex against each other".to_string(),
+            ));
+        }
+        Ok(contact)
+    }
+
+    // Medial tibiofemoral compartment: a femoral condyle of ~30 mm radius
+    // on a near-flat tibial plateau, both cartilage-covered.
+    // Kurosawa H, Fukubayashi T, Nakajima H (1980) Clin Orthop 149:283-290
+    pub fn knee_medial() -> Self {
+        Self::new(
+            ContactSurface::articular_cartilage(0.03).expect("valid preset"),
+            ContactSurface::articular_cartilage(f64::INFINITY).expect("valid preset"),
+            FluidProperties::synovial_fluid(),
+            Friction::cartilage(),
+        )
+        .expect("valid preset")
+    }
+
+    // 1/R* = 1/R₁ + 1/R₂.
+    pub fn effective_radius_m(&self) -> f64 {
+        1.0 / (1.0 / self.first.radius_m + 1.0 / self.second.radius_m)
+    }
+
+    // 1/E* = (1 - ν₁²)/E₁ + (1 - ν₂²)/E₂.
+    pub fn effective_modulus_pa(&self) -> f64 {
+        1.0 / (self.first.compliance() + self.second.compliance())
+    }
+
+    pub fn composite_roughness_m(&self) -> f64 {
+        self.first.roughness_m.hypot(self.second.roughness_m)
+    }
+
+    // Contact under normal `load_n`, with surfaces entraining lubricant at
+    // the mean of their surface speeds and sliding at their difference.
+    pub fn analyse(
+        &self,
+        load_n: f64,
+        entraining_speed_m_s: f64,
+        sliding_speed_m_s: f64,
+    ) -> BiologyResult<ContactState> {
+        if load_n.is_nan() || load_n <= 0.0 {
+            return Err(BiologyError::InvalidValue(format!(
+                "contact load must be positive, got {}",
+                load_n
+            )));
+        }
+        let r = self.effective_radius_m();
+        let e = self.effective_modulus_pa();
+        let a = (3.0 * load_n * r / (4.0 * e)).cbrt();
+        let area = PI * a * a;
+        let film = self.film_thickness_m(load_n, entraining_speed_m_s.abs());
+        let roughness = self.composite_roughness_m();
+        let lambda = if roughness > 0.0 {
+            film / roughness
+        } else {
+            f64::INFINITY
+        };
+        let friction_coefficient = if sliding_speed_m_s == 0.0 {
+            0.0
+        } else {
+            self.friction.coefficient(lambda)
+        };
+        Ok(ContactState {
+            contact_radius_m: a,
+            contact_area_m2: area,
+            mean_pressure_pa: load_n / area,
+            peak_pressure_pa: 1.5 * load_n / area,
+            approach_m: a * a / r,
+            film_thickness_m: film,
+            lambda,
+            regime: LubricationRegime::from_lambda(lambda),
+            friction_coefficient,
+            friction_force_n: friction_coefficient * load_n,
+        })
+    }
+
+    fn film_thickness_m(&self, load_n: f64, entraining_speed_m_s: f64) -> f64 {
+        if entraining_speed_m_s == 0.0 {
+            return 0.0;
+        }
+        let r = self.effective_radius_m();
+        let e = self.effective_modulus_pa();
+        let speed = self.lubricant.dynamic_viscosity_pa_s * entraining_speed_m_s / (e * r);
+        let load = load_n / (e * r * r);
+        2.8 * r * speed.powf(0.65) * load.powf(-0.21)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hertz_pressures_for_stance_load() {
+        let knee = JointContact::knee_medial();
+        // ~3 body weights for 70 kg at mid-stance.
+        let state = knee.analyse(2100.0, 0.05, 0.02).unwrap();
+        assert!(
+            (1.0e6..1.0e7).contains(&state.peak_pressure_pa),
+            "{}",
+            state.peak_pressure_pa
+        );
+        assert!((state.peak_pressure_pa / state.mean_pressure_pa - 1.5).abs() < 1e-12);
+        // a ∝ F^(1/3): eight times the load doubles the contact radius.
+        let heavier = knee.analyse(8.0 * 2100.0, 0.05, 0.02).unwrap();
+        assert!((heavier.contact_radius_m / state.contact_radius_m - 2.0).abs() < 1e-9);
+        assert!(knee.analyse(0.0, 0.05, 0.02).is_err());
+    }
+
+    #[test]
+    fn test_synovial_fluid_separates_surfaces_water_does_not() {
+        let knee = JointContact::knee_medial();
+        let synovial = knee.analyse(2100.0, 0.05, 0.02).unwrap();
+        assert_eq!(synovial.regime, LubricationRegime::FullFilm);
+        assert!(synovial.friction_coefficient < 0.01);
+        let watery = JointContact {
+            lubricant: FluidProperties::water(),
+            ..knee.clone()
+        };
+        let dry = watery.analyse(2100.0, 0.05, 0.02).unwrap();
+        assert_eq!(dry.regime, LubricationRegime::Boundary);
+        assert!((dry.friction_force_n - 0.2 * 2100.0).abs() < 1e-9);
+        // Standing still squeezes the film out.
+        assert_eq!(
+            knee.analyse(2100.0, 0.0, 0.0).unwrap().film_thickness_m,
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_invalid_geometry_rejected() {
+        assert!(ContactSurface::new(0.0, 1e7, 0.4, 1e-6).is_err());
+        assert!(ContactSurface::new(0.03, 1e7, 0.5, 1e-6).is_err());
+        let ball = ContactSurface::articular_cartilage(0.025).unwrap();
+        let tighter_cup = ContactSurface::articular_cartilage(-0.02).unwrap();
+        assert!(JointContact::new(
+            ball,
+            tighter_cup,
+            FluidProperties::synovial_fluid(),
+            Friction::cartilage()
+        )
+        .is_err());
+        assert!((Friction::cartilage().coefficient(2.0) - 0.1025).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,276 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::config::presets::FluidProperties;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tracing::warn;
+
+// Dimensionless groups that decide which simplifications a transport or
+// flow model may make, all from SI inputs. `check` compares a model's
+// assumed regime against the computed number and explains any mismatch.
+
+// Pipe flow stays laminar below ~2000, matching `BloodFlow::is_laminar`.
+// Reynolds O (1883) Phil Trans R Soc 174:935-982
+pub const LAMINAR_REYNOLDS_LIMIT: f64 = 2000.0;
+// Below α ≈ 1 viscous forces keep the pulsatile profile parabolic, so
+// Poiseuille resistance applies instant by instant.
+// Womersley JR (1955) J Physiol 127:553-563
+pub const QUASI_STEADY_WOMERSLEY_LIMIT: f64 = 1.0;
+// Lumped (spatially uniform) temperature or concentration inside a body
+// is accurate to ~5% below Bi = 0.1.
+// Incropera FP et al. (2007) Fundamentals of Heat and Mass Transfer, 6th ed., §5.2
+pub const LUMPED_BIOT_LIMIT: f64 = 0.1;
+// Péclet and Damköhler numbers change regime at order one.
+pub const TRANSPORT_CROSSOVER: f64 = 1.0;
+
+fn positive(value: f64, what: &str) -> BiologyResult<f64> {
+    if value.is_nan() || value <= 0.0 || value.is_infinite() {
+        return Err(BiologyError::InvalidValue(format!(
+            "{} must be positive and finite, got {}",
+            what, value
+        )));
+    }
+    Ok(value)
+}
+
+fn non_negative(value: f64, what: &str) -> BiologyResult<f64> {
+    if value.is_nan() || value < 0.0 || value.is_infinite() {
+        return Err(BiologyError::InvalidValue(format!(
+            "{} must be non-negative and finite, got {}",
+            what, value
+        )));
+    }
+    Ok(value)
+}
+
+// Inertial over viscous forces, ρ·U·L / μ.
+pub fn reynolds(
+    density_kg_m3: f64,
+    velocity_m_s: f64,
+    length_m: f64,
+    viscosity_pa_s: f64,
+) -> BiologyResult<f64> {
+    Ok(positive(density_kg_m3, "density")?
+        * non_negative(velocity_m_s.abs(), "velocity")?
+        * positive(length_m, "length")?
+        / positive(viscosity_pa_s, "viscosity")?)
+}
+
+// Pulsatile inertia over viscous forces, R·sqrt(ω·ρ / μ).
+pub fn womersley(
+    radius_m: f64,
+    angular_frequency_rad_s: f64,
+    density_kg_m3: f64,
+    viscosity_pa_s: f64,
+) -> BiologyResult<f64> {
+    Ok(positive(radius_m, "radius")?
+        * (non_negative(angular_frequency_rad_s, "angular frequency")?
+            * positive(density_kg_m3, "density")?
+            / positive(viscosity_pa_s, "viscosity")?)
+        .sqrt())
+}
+
+// Advective over diffusive transport, U·L / D.
+pub fn peclet(velocity_m_s: f64, length_m: f64, diffusivity_m2_s: f64) -> BiologyResult<f64> {
+    Ok(
+        non_negative(velocity_m_s.abs(), "velocity")? * positive(length_m, "length")?
+            / positive(diffusivity_m2_s, "diffusivity")?,
+    )
+}
+
+// Reaction rate over the transport rate that supplies it: k·L / U when
+// flow delivers the reactant, k·L² / D when diffusion does. Above one the
+// process is transport-limited.
+pub fn damkohler_advective(
+    rate_constant_per_s: f64,
+    length_m: f64,
+    velocity_m_s: f64,
+) -> BiologyResult<f64> {
+    Ok(
+        non_negative(rate_constant_per_s, "rate constant")? * positive(length_m, "length")?
+            / positive(velocity_m_s.abs(), "velocity")?,
+    )
+}
+
+pub fn damkohler_diffusive(
+    rate_constant_per_s: f64,
+    length_m: f64,
+    diffusivity_m2_s: f64,
+) -> BiologyResult<f64> {
+    let length = positive(length_m, "length")?;
+    Ok(
+        non_negative(rate_constant_per_s, "rate constant")? * length * length
+            / positive(diffusivity_m2_s, "diffusivity")?,
+    )
+}
+
+// Surface transfer over internal conduction, h·L / k with L = volume /
+// surface area. Also the mass-transfer analogue with k a diffusivity and
+// h a mass-transfer coefficient.
+pub fn biot(
+    transfer_coefficient: f64,
+    characteristic_length_m: f64,
+    conductivity: f64,
+) -> BiologyResult<f64> {
+    Ok(non_negative(transfer_coefficient, "transfer coefficient")?
+        * positive(characteristic_length_m, "characteristic length")?
+        / positive(conductivity, "conductivity")?)
+}
+
+// Reynolds and Womersley numbers of pulsatile flow in a vessel.
+pub fn vessel_flow(
+    fluid: &FluidProperties,
+    diameter_m: f64,
+    mean_velocity_m_s: f64,
+    heart_rate_bpm: f64,
+) -> BiologyResult<(f64, f64)> {
+    let omega = 2.0 * std::f64::consts::PI * non_negative(heart_rate_bpm, "heart rate")? / 60.0;
+    Ok((
+        reynolds(
+            fluid.density_kg_m3,
+            mean_velocity_m_s,
+            diameter_m,
+            fluid.dynamic_viscosity_pa_s,
+        )?,
+        womersley(
+            diameter_m / 2.0,
+            omega,
+            fluid.density_kg_m3,
+            fluid.dynamic_viscosity_pa_s,
+        )?,
+    ))
+}
+
+// Regime a model assumes, and the number that must agree with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Assumption {
+    // Re below the laminar limit.
+    LaminarFlow,
+    // α below one: Poiseuille resistance at every instant.
+    QuasiSteadyFlow,
+    // Pe below one: transport by diffusion alone.
+    DiffusionDominated,
+    // Pe above one: diffusion negligible along the flow.
+    AdvectionDominated,
+    // Da below one: uniform concentration, kinetics set the rate.
+    ReactionLimited,
+    // Da above one: supply sets the rate.
+    TransportLimited,
+    // Bi below 0.1: one well-mixed compartment.
+    LumpedCompartment,
+}
+
+impl Assumption {
+    pub fn number(self) -> &'static str {
+        match self {
+            Assumption::LaminarFlow => "Reynolds",
+            Assumption::QuasiSteadyFlow => "Womersley",
+            Assumption::DiffusionDominated | Assumption::AdvectionDominated => "Péclet",
+            Assumption::ReactionLimited | Assumption::TransportLimited => "Damköhler",
+            Assumption::LumpedCompartment => "Biot",
+        }
+    }
+
+    // (threshold, whether the number must stay below it).
+    pub fn bound(self) -> (f64, bool) {
+        match self {
+            Assumption::LaminarFlow => (LAMINAR_REYNOLDS_LIMIT, true),
+            Assumption::QuasiSteadyFlow => (QUASI_STEADY_WOMERSLEY_LIMIT, true),
+            Assumption::DiffusionDominated | Assumption::ReactionLimited => {
+                (TRANSPORT_CROSSOVER, true)
+            }
+            Assumption::AdvectionDominated | Assumption::TransportLimited => {
+                (TRANSPORT_CROSSOVER, false)
+            }
+            Assumption::LumpedCompartment => (LUMPED_BIOT_LIMIT, true),
+        }
+    }
+
+    pub fn holds(self, value: f64) -> bool {
+        let (threshold, below) = self.bound();
+        if below {
+            value < threshold
+        } else {
+            value > threshold
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegimeWarning {
+    pub model: String,
+    pub assumption: Assumption,
+    pub value: f64,
+}
+
+impl fmt::Display for RegimeWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (threshold, below) = self.assumption.bound();
+        write!(
+            f,
+            "{} assumes {:?} but the {} number is {:.3} (needs {} {})",
+            self.model,
+            self.assumption,
+            self.assumption.number(),
+            self.value,
+            if below { "<" } else { ">" },
+            threshold
+        )
+    }
+}
+
+// A warning, also logged, when `value` contradicts the assumption.
+pub fn check(model: &str, assumption: Assumption, value: f64) -> Option<RegimeWarning> {
+    if assumption.holds(value) {
+        return None;
+    }
+    let warning = RegimeWarning {
+        model: model.to_string(),
+        assumption,
+        value,
+    };
+    warn!(%warning, "model regime inconsistent with dimensionless number");
+    Some(warning)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aortic_flow_is_pulsatile_but_laminar_on_average() {
+        // Ascending aorta: 25 mm, 0.2 m/s mean, 70 bpm.
+        let (re, alpha) = vessel_flow(&FluidProperties::blood(), 0.025, 0.2, 70.0).unwrap();
+        assert!((1400.0..1600.0).contains(&re), "{}", re);
+        // Womersley's aortic α of 10-20.
+        assert!((12.0..20.0).contains(&alpha), "{}", alpha);
+        assert!(check("aorta", Assumption::LaminarFlow, re).is_none());
+        let warning = check("aorta", Assumption::QuasiSteadyFlow, alpha).unwrap();
+        assert!(warning.to_string().contains("Womersley"));
+        // Capillary: 8 µm, 1 mm/s.
+        let (re, alpha) = vessel_flow(&FluidProperties::blood(), 8e-6, 1e-3, 70.0).unwrap();
+        assert!(re < 0.01 && alpha < 0.01);
+    }
+
+    #[test]
+    fn test_transport_regimes() {
+        // Oxygen (D = 2e-9 m²/s) across 100 µm of tissue consumed at 1/s.
+        let da = damkohler_diffusive(1.0, 100e-6, 2e-9).unwrap();
+        assert!((da - 5.0).abs() < 1e-9);
+        assert!(check("tissue", Assumption::ReactionLimited, da).is_some());
+        assert!(check("tissue", Assumption::TransportLimited, da).is_none());
+        // Albumin (D = 6e-11 m²/s) carried 1 mm at 1 mm/s is advected.
+        let pe = peclet(1e-3, 1e-3, 6e-11).unwrap();
+        assert!(Assumption::AdvectionDominated.holds(pe));
+        assert!(damkohler_advective(1.0, 1e-3, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_biot_and_validation() {
+        // Forearm cooled in still air: h = 10 W/m²K, L = 1 cm, k = 0.5 W/mK.
+        let bi = biot(10.0, 0.01, 0.5).unwrap();
+        assert!((bi - 0.2).abs() < 1e-12);
+        assert!(check("forearm", Assumption::LumpedCompartment, bi).is_some());
+        assert!(reynolds(1000.0, 1.0, 0.01, 0.0).is_err());
+        assert!(womersley(f64::NAN, 1.0, 1000.0, 1e-3).is_err());
+    }
+}
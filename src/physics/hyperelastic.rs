@@ -0,0 +1,486 @@
+use crate::biology::{BiologyError, BiologyResult};
+use nalgebra::{Matrix3, Vector3};
+use serde::{Deserialize, Serialize};
+
+// Large-strain constitutive laws for soft tissue. Each model splits the
+// strain energy into an isochoric part, written in the modified invariants
+// of b̄ = J^(-2/3)·F·Fᵀ, and a volumetric penalty κ/2·(J - 1)², so that a
+// large bulk modulus makes the tissue nearly incompressible. Stresses are
+// in the units of the parameters (kPa for the presets).
+// Holzapfel GA (2000) Nonlinear Solid Mechanics, §6.4-6.5
+
+pub trait Hyperelastic {
+    // Strain energy per reference volume.
+    fn strain_energy(&self, f: &Matrix3<f64>) -> BiologyResult<f64>;
+    fn cauchy_stress(&self, f: &Matrix3<f64>) -> BiologyResult<Matrix3<f64>>;
+
+    // P = J·σ·F⁻ᵀ.
+    fn first_piola_kirchhoff(&self, f: &Matrix3<f64>) -> BiologyResult<Matrix3<f64>> {
+        let sigma = self.cauchy_stress(f)?;
+        let inverse = f.try_inverse().ok_or_else(inverted)?;
+        Ok(f.determinant() * sigma * inverse.transpose())
+    }
+
+    // S = F⁻¹·P.
+    fn second_piola_kirchhoff(&self, f: &Matrix3<f64>) -> BiologyResult<Matrix3<f64>> {
+        let inverse = f.try_inverse().ok_or_else(inverted)?;
+        Ok(inverse * self.first_piola_kirchhoff(f)?)
+    }
+}
+
+fn inverted() -> BiologyError {
+    BiologyError::InvalidState("deformation gradient must have positive determinant".to_string())
+}
+
+// J and the isochoric left Cauchy-Green tensor b̄.
+fn kinematics(f: &Matrix3<f64>) -> BiologyResult<(f64, Matrix3<f64>)> {
+    let j = f.determinant();
+    if j.is_nan() || j <= 0.0 {
+        return Err(inverted());
+    }
+    Ok((j, j.powf(-2.0 / 3.0) * f * f.transpose()))
+}
+
+fn deviatoric(a: &Matrix3<f64>) -> Matrix3<f64> {
+    a - Matrix3::identity() * (a.trace() / 3.0)
+}
+
+fn volumetric_energy(bulk_modulus: f64, j: f64) -> f64 {
+    0.5 * bulk_modulus * (j - 1.0).powi(2)
+}
+
+fn pressure(bulk_modulus: f64, j: f64) -> Matrix3<f64> {
+    Matrix3::identity() * (bulk_modulus * (j - 1.0))
+}
+
+fn require_positive(values: &[(f64, &str)]) -> BiologyResult<()> {
+    for &(value, what) in values {
+        if value.is_nan() || value <= 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{} must be positive, got {}",
+                what, value
+            )));
+        }
+    }
+    Ok(())
+}
+
+// W = μ/2·(Ī₁ - 3).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NeoHookean {
+    pub shear_modulus: f64,
+    pub bulk_modulus: f64,
+}
+
+impl NeoHookean {
+    pub fn new(shear_modulus: f64, bulk_modulus: f64) -> BiologyResult<Self> {
+        require_positive(&[
+            (shear_modulus, "shear modulus"),
+            (bulk_modulus, "bulk modulus"),
+        ])?;
+        Ok(Self {
+            shear_modulus,
+            bulk_modulus,
+        })
+    }
+}
+
+impl Hyperelastic for NeoHookean {
+    fn strain_energy(&self, f: &Matrix3<f64>) -> BiologyResult<f64> {
+        let (j, b) = kinematics(f)?;
+        Ok(0.5 * self.shear_modulus * (b.trace() - 3.0) + volumetric_energy(self.bulk_modulus, j))
+    }
+
+    fn cauchy_stress(&self, f: &Matrix3<f64>) -> BiologyResult<Matrix3<f64>> {
+        let (j, b) = kinematics(f)?;
+        Ok(self.shear_modulus / j * deviatoric(&b) + pressure(self.bulk_modulus, j))
+    }
+}
+
+// W = c₁₀·(Ī₁ - 3) + c₀₁·(Ī₂ - 3); small-strain shear modulus 2·(c₁₀ + c₀₁).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MooneyRivlin {
+    pub c10: f64,
+    pub c01: f64,
+    pub bulk_modulus: f64,
+}
+
+impl MooneyRivlin {
+    pub fn new(c10: f64, c01: f64, bulk_modulus: f64) -> BiologyResult<Self> {
+        require_positive(&[(bulk_modulus, "bulk modulus")])?;
+        if c10.is_nan() || c01.is_nan() || c10 + c01 <= 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "c10 + c01 must be positive, got {} + {}",
+                c10, c01
+            )));
+        }
+        Ok(Self {
+            c10,
+            c01,
+            bulk_modulus,
+        })
+    }
+}
+
+impl Hyperelastic for MooneyRivlin {
+    fn strain_energy(&self, f: &Matrix3<f64>) -> BiologyResult<f64> {
+        let (j, b) = kinematics(f)?;
+        let i1 = b.trace();
+        let i2 = 0.5 * (i1 * i1 - (b * b).trace());
+        Ok(self.c10 * (i1 - 3.0) + self.c01 * (i2 - 3.0) + volumetric_energy(self.bulk_modulus, j))
+    }
+
+    fn cauchy_stress(&self, f: &Matrix3<f64>) -> BiologyResult<Matrix3<f64>> {
+        let (j, b) = kinematics(f)?;
+        let i1 = b.trace();
+        let isochoric = (self.c10 + self.c01 * i1) * b - self.c01 * b * b;
+        Ok(2.0 / j * deviatoric(&isochoric) + pressure(self.bulk_modulus, j))
+    }
+}
+
+// W = Σ μₚ/αₚ·(λ̄₁^αₚ + λ̄₂^αₚ + λ̄₃^αₚ - 3) over isochoric principal
+// stretches; small-strain shear modulus ½·Σ μₚ·αₚ.
+// Ogden RW (1972) Proc R Soc Lond A 326:565-584
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ogden {
+    // (μₚ, αₚ) pairs.
+    pub terms: Vec<(f64, f64)>,
+    pub bulk_modulus: f64,
+}
+
+impl Ogden {
+    pub fn new(terms: Vec<(f64, f64)>, bulk_modulus: f64) -> BiologyResult<Self> {
+        require_positive(&[(bulk_modulus, "bulk modulus")])?;
+        let shear: f64 = terms.iter().map(|(mu, alpha)| mu * alpha).sum();
+        if terms.is_empty()
+            || terms
+                .iter()
+                .any(|(mu, alpha)| !(mu.is_finite() && alpha.is_finite()))
+            || terms.iter().any(|&(_, alpha)| alpha == 0.0)
+            || shear.is_nan()
+            || shear <= 0.0
+        {
+            return Err(BiologyError::InvalidParameter(
+                "Ogden terms need non-zero exponents and Σ μ·α > 0".to_string(),
+            ));
+        }
+        Ok(Self {
+            terms,
+            bulk_modulus,
+        })
+    }
+
+    // Pig dorsal skin in tension, one-term fit; μ in kPa, bulk modulus
+    // taken 1000 times larger for near-incompressibility.
+    // Shergold OA, Fleck NA, Radford D (2006) Int J Impact Eng 32:1384-1402
+    pub fn skin() -> Self {
+        Self::new(vec![(400.0, 9.0)], 4.0e5).expect("valid preset")
+    }
+
+    fn principal(&self, f: &Matrix3<f64>) -> BiologyResult<(f64, Vector3<f64>, Matrix3<f64>)> {
+        let (j, b) = kinematics(f)?;
+        let eigen = b.symmetric_eigen();
+        let stretches = eigen.eigenvalues.map(|e| e.max(0.0).sqrt());
+        Ok((j, stretches, eigen.eigenvectors))
+    }
+}
+
+impl Hyperelastic for Ogden {
+    fn strain_energy(&self, f: &Matrix3<f64>) -> BiologyResult<f64> {
+        let (j, stretches, _) = self.principal(f)?;
+        let isochoric: f64 = self
+            .terms
+            .iter()
+            .map(|&(mu, alpha)| mu / alpha * (stretches.map(|l| l.powf(alpha)).sum() - 3.0))
+            .sum();
+        Ok(isochoric + volumetric_energy(self.bulk_modulus, j))
+    }
+
+    fn cauchy_stress(&self, f: &Matrix3<f64>) -> BiologyResult<Matrix3<f64>> {
+        let (j, stretches, directions) = self.principal(f)?;
+        let mut principal = Vector3::zeros();
+        for &(mu, alpha) in &self.terms {
+            let powered = stretches.map(|l| l.powf(alpha));
+            let mean = powered.sum() / 3.0;
+            principal += powered.map(|p| mu * (p - mean));
+        }
+        let mut sigma = pressure(self.bulk_modulus, j);
+        for a in 0..3 {
+            let n = directions.column(a);
+            sigma += principal[a] / j * n * n.transpose();
+        }
+        Ok(sigma)
+    }
+}
+
+// Neo-Hookean matrix reinforced by collagen fibre families that stiffen
+// exponentially in tension and buckle in compression:
+// Ψ = μ/2·(Ī₁ - 3) + k₁/(2k₂)·Σᵢ [exp(k₂·Ēᵢ²) - 1],
+// Ēᵢ = κ·(Ī₁ - 3) + (1 - 3κ)·(Ī₄ᵢ - 1), counted only when Ēᵢ > 0, where
+// Ī₄ᵢ is the squared isochoric stretch along fibre i and κ ∈ [0, ⅓] the
+// dispersion (0 aligned, ⅓ isotropic).
+// Holzapfel GA, Gasser TC, Ogden RW (2000) J Elasticity 61:1-48
+// Gasser TC, Ogden RW, Holzapfel GA (2006) J R Soc Interface 3:15-35
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HolzapfelGasserOgden {
+    pub shear_modulus: f64,
+    pub k1: f64,
+    pub k2: f64,
+    pub dispersion: f64,
+    // Unit fibre directions in the reference configuration.
+    pub fibres: Vec<Vector3<f64>>,
+    pub bulk_modulus: f64,
+}
+
+impl HolzapfelGasserOgden {
+    pub fn new(
+        shear_modulus: f64,
+        k1: f64,
+        k2: f64,
+        dispersion: f64,
+        fibres: Vec<Vector3<f64>>,
+        bulk_modulus: f64,
+    ) -> BiologyResult<Self> {
+        require_positive(&[
+            (shear_modulus, "shear modulus"),
+            (k1, "k1"),
+            (k2, "k2"),
+            (bulk_modulus, "bulk modulus"),
+        ])?;
+        if !(0.0..=1.0 / 3.0).contains(&dispersion) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "dispersion must lie in [0, 1/3], got {}",
+                dispersion
+            )));
+        }
+        let fibres = fibres
+            .into_iter()
+            .map(|a| {
+                a.try_normalize(1e-12).ok_or_else(|| {
+                    BiologyError::InvalidParameter("fibre direction is zero".to_string())
+                })
+            })
+            .collect::<BiologyResult<Vec<_>>>()?;
+        Ok(Self {
+            shear_modulus,
+            k1,
+            k2,
+            dispersion,
+            fibres,
+            bulk_modulus,
+        })
+    }
+
+    // Two symmetric helical families at ±angle to the circumferential (x)
+    // axis in the circumferential-axial (x-z) plane.
+    pub fn helical(
+        shear_modulus: f64,
+        k1: f64,
+        k2: f64,
+        angle_deg: f64,
+        bulk_modulus: f64,
+    ) -> BiologyResult<Self> {
+        let (s, c) = angle_deg.to_radians().sin_cos();
+        Self::new(
+            shear_modulus,
+            k1,
+            k2,
+            0.0,
+            vec![Vector3::new(c, 0.0, s), Vector3::new(c, 0.0, -s)],
+            bulk_modulus,
+        )
+    }
+
+    // Rabbit carotid media, kPa; Holzapfel et al. (2000) Table 1.
+    pub fn arterial_media() -> Self {
+        Self::helical(3.0, 2.3632, 0.8393, 29.0, 3.0e3).expect("valid preset")
+    }
+
+    // Rabbit carotid adventitia, kPa; Holzapfel et al. (2000) Table 1.
+    pub fn arterial_adventitia() -> Self {
+        Self::helical(0.3, 0.562, 0.7112, 62.0, 3.0e2).expect("valid preset")
+    }
+
+    // Ēᵢ and the fibre's isochoric spatial direction ā = F̄·a₀.
+    fn fibre_strains(
+        &self,
+        f: &Matrix3<f64>,
+        j: f64,
+        b: &Matrix3<f64>,
+    ) -> Vec<(f64, Vector3<f64>)> {
+        let f_bar = j.powf(-1.0 / 3.0) * f;
+        let i1 = b.trace();
+        self.fibres
+            .iter()
+            .map(|a0| {
+                let a = f_bar * a0;
+                let strain = self.dispersion * (i1 - 3.0)
+                    + (1.0 - 3.0 * self.dispersion) * (a.norm_squared() - 1.0);
+                (strain, a)
+            })
+            .collect()
+    }
+}
+
+impl Hyperelastic for HolzapfelGasserOgden {
+    fn strain_energy(&self, f: &Matrix3<f64>) -> BiologyResult<f64> {
+        let (j, b) = kinematics(f)?;
+        let fibres: f64 = self
+            .fibre_strains(f, j, &b)
+            .iter()
+            .filter(|(e, _)| *e > 0.0)
+            .map(|(e, _)| self.k1 / (2.0 * self.k2) * ((self.k2 * e * e).exp() - 1.0))
+            .sum();
+        Ok(0.5 * self.shear_modulus * (b.trace() - 3.0)
+            + fibres
+            + volumetric_energy(self.bulk_modulus, j))
+    }
+
+    fn cauchy_stress(&self, f: &Matrix3<f64>) -> BiologyResult<Matrix3<f64>> {
+        let (j, b) = kinematics(f)?;
+        let mut isochoric = self.shear_modulus * b;
+        for (e, a) in self.fibre_strains(f, j, &b) {
+            if e <= 0.0 {
+                continue;
+            }
+            let slope = self.k1 * e * (self.k2 * e * e).exp();
+            isochoric += 2.0
+                * slope
+                * (self.dispersion * b + (1.0 - 3.0 * self.dispersion) * a * a.transpose());
+        }
+        Ok(deviatoric(&isochoric) / j + pressure(self.bulk_modulus, j))
+    }
+}
+
+// Isochoric uniaxial stretch along x.
+pub fn uniaxial(stretch: f64) -> Matrix3<f64> {
+    let lateral = 1.0 / stretch.sqrt();
+    Matrix3::from_diagonal(&Vector3::new(stretch, lateral, lateral))
+}
+
+// Isochoric equibiaxial stretch in the x-y plane.
+pub fn equibiaxial(stretch: f64) -> Matrix3<f64> {
+    Matrix3::from_diagonal(&Vector3::new(stretch, stretch, 1.0 / (stretch * stretch)))
+}
+
+// Shear of x along y.
+pub fn simple_shear(amount: f64) -> Matrix3<f64> {
+    let mut f = Matrix3::identity();
+    f[(0, 1)] = amount;
+    f
+}
+
+// Axial Cauchy stress of an incompressible uniaxial test: the hydrostatic
+// pressure is fixed by the traction-free lateral face (σ₂₂ = 0).
+pub fn uniaxial_stress<M: Hyperelastic + ?Sized>(material: &M, stretch: f64) -> BiologyResult<f64> {
+    if stretch.is_nan() || stretch <= 0.0 {
+        return Err(BiologyError::InvalidValue(format!(
+            "stretch must be positive, got {}",
+            stretch
+        )));
+    }
+    let sigma = material.cauchy_stress(&uniaxial(stretch))?;
+    Ok(sigma[(0, 0)] - sigma[(1, 1)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn general_deformation() -> Matrix3<f64> {
+        Matrix3::new(1.15, 0.12, 0.03, -0.05, 0.92, 0.08, 0.02, -0.04, 1.04)
+    }
+
+    // Central differences of W against P = ∂W/∂F.
+    fn assert_stress_is_energy_gradient(material: &dyn Hyperelastic) {
+        let f = general_deformation();
+        let p = material.first_piola_kirchhoff(&f).unwrap();
+        let h = 1e-6;
+        for i in 0..3 {
+            for k in 0..3 {
+                let mut plus = f;
+                let mut minus = f;
+                plus[(i, k)] += h;
+                minus[(i, k)] -= h;
+                let numeric = (material.strain_energy(&plus).unwrap()
+                    - material.strain_energy(&minus).unwrap())
+                    / (2.0 * h);
+                let scale = p.abs().max().max(1.0);
+                assert!(
+                    (numeric - p[(i, k)]).abs() < 1e-5 * scale,
+                    "P[{}{}] {} vs {}",
+                    i,
+                    k,
+                    p[(i, k)],
+                    numeric
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_stresses_derive_from_strain_energy() {
+        let models: Vec<Box<dyn Hyperelastic>> = vec![
+            Box::new(NeoHookean::new(10.0, 200.0).unwrap()),
+            Box::new(MooneyRivlin::new(4.0, 1.5, 200.0).unwrap()),
+            Box::new(Ogden::new(vec![(6.0, 3.0), (-0.5, -2.0)], 200.0).unwrap()),
+            Box::new(HolzapfelGasserOgden::arterial_media()),
+            Box::new(
+                HolzapfelGasserOgden::new(3.0, 2.0, 1.5, 0.2, vec![Vector3::x()], 300.0).unwrap(),
+            ),
+        ];
+        for model in &models {
+            assert_stress_is_energy_gradient(model.as_ref());
+            let rest = model.cauchy_stress(&Matrix3::identity()).unwrap();
+            assert!(rest.abs().max() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_small_strain_limits_agree() {
+        // Young's modulus 3μ for every isotropic model with the same μ.
+        let neo = NeoHookean::new(10.0, 1e6).unwrap();
+        let mooney = MooneyRivlin::new(3.0, 2.0, 1e6).unwrap();
+        let ogden = Ogden::new(vec![(10.0, 2.0)], 1e6).unwrap();
+        for material in [&neo as &dyn Hyperelastic, &mooney, &ogden] {
+            let sigma = uniaxial_stress(material, 1.001).unwrap();
+            assert!((sigma / 0.001 - 30.0).abs() < 0.1, "{}", sigma);
+        }
+        // Ogden with α = 2 is Neo-Hookean at any strain.
+        let large = equibiaxial(1.4);
+        assert!(
+            (neo.cauchy_stress(&large).unwrap() - ogden.cauchy_stress(&large).unwrap())
+                .abs()
+                .max()
+                < 1e-9
+        );
+        assert!(neo
+            .cauchy_stress(&Matrix3::from_diagonal_element(-1.0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_collagen_fibres_stiffen_tissue_in_tension_only() {
+        let media = HolzapfelGasserOgden::arterial_media();
+        let matrix = NeoHookean::new(media.shear_modulus, media.bulk_modulus).unwrap();
+        // Circumferential stretch loads both fibre families.
+        let stretched = uniaxial_stress(&media, 1.3).unwrap();
+        assert!(stretched > 3.0 * uniaxial_stress(&matrix, 1.3).unwrap());
+        // Shortening the wall's plane buckles both families; only the
+        // matrix resists.
+        let f = Matrix3::from_diagonal(&Vector3::new(0.9, 1.0 / 0.81, 0.9));
+        assert!(
+            (media.cauchy_stress(&f).unwrap() - matrix.cauchy_stress(&f).unwrap())
+                .abs()
+                .max()
+                < 1e-9
+        );
+        // Skin stiffens sharply: σ = μ·(λ⁹ - λ^-4.5) has a tangent at 30%
+        // stretch about 5.5 times that at rest.
+        let skin = Ogden::skin();
+        let tangent = |l: f64| {
+            (uniaxial_stress(&skin, l + 1e-4).unwrap() - uniaxial_stress(&skin, l).unwrap()) / 1e-4
+        };
+        assert!(tangent(1.3) > 5.0 * tangent(1.0));
+    }
+}
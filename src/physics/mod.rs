@@ -0,0 +1,7 @@
+pub mod contact;
+pub mod dimensionless;
+pub mod hyperelastic;
+
+pub use contact::{ContactState, ContactSurface, Friction, JointContact, LubricationRegime};
+pub use dimensionless::{Assumption, RegimeWarning};
+pub use hyperelastic::{HolzapfelGasserOgden, Hyperelastic, MooneyRivlin, NeoHookean, Ogden};
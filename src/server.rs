@@ -0,0 +1,643 @@
+//! REST server for remote, multi-client use of long-running simulations.
+//!
+//! Plain HTTP/1.1 with JSON bodies, one request per connection:
+//!
+//! | Method | Path                        | Body / query          | Reply                 |
+//! |--------|-----------------------------|-----------------------|-----------------------|
+//! | POST   | `/sessions`                 | scenario (TOML/JSON)  | `{"id": n}`           |
+//! | GET    | `/sessions`                 |                       | session ids           |
+//! | GET    | `/sessions/{id}`            |                       | current frame         |
+//! | PUT    | `/sessions/{id}/scenario`   | scenario              | frame at t = 0        |
+//! | POST   | `/sessions/{id}/step`       | `?steps=n`            | frame                 |
+//! | POST   | `/sessions/{id}/run`        |                       | frame at the end      |
+//! | GET    | `/sessions/{id}/stream`     | `?steps=n&every=k`    | NDJSON frames         |
+//! | GET    | `/sessions/{id}/checkpoint` |                       | checkpoint            |
+//! | PUT    | `/sessions/{id}/checkpoint` | checkpoint            | frame                 |
+//! | DELETE | `/sessions/{id}`            |                       | 204                   |
+//!
+//! Frames are [`StreamFrame`]s over the scenario's `outputs`. Checkpoints
+//! are the versioned JSON envelope of
+//! [`Checkpointed::to_json`](crate::simulation::checkpoint::Checkpointed::to_json),
+//! the same payload the library writes to checkpoint files. Sessions are
+//! locked individually, so clients driving different sessions run in
+//! parallel. `steps` above [`SimulationServer::max_steps`], or a `run`
+//! that would need more, is refused with 400, and connections that stall are dropped after
+//! [`SimulationServer::io_timeout`].
+
+use crate::simulation::checkpoint::Checkpointed;
+use crate::simulation::engine::{Engine, EngineSnapshot};
+use crate::simulation::scenario::Scenario;
+use crate::simulation::streaming::StreamFrame;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+const MAX_BODY_BYTES: usize = 1 << 20;
+// Request line plus all header lines.
+const MAX_HEAD_BYTES: usize = 16 << 10;
+const DEFAULT_MAX_STEPS: u64 = 1_000_000;
+const DEFAULT_IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Reads one line without letting it grow past the remaining head budget.
+fn read_head_line<R: BufRead>(
+    reader: &mut R,
+    line: &mut String,
+    budget: &mut usize,
+) -> io::Result<usize> {
+    line.clear();
+    let read = reader.take(*budget as u64 + 1).read_line(line)?;
+    if read > *budget {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "request head too large",
+        ));
+    }
+    *budget -= read;
+    Ok(read)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: Vec<String>,
+    pub query: BTreeMap<String, String>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn new(method: &str, target: &str, body: &str) -> Self {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        Self {
+            method: method.to_string(),
+            path: path
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+            query: query
+                .split('&')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            body: body.to_string(),
+        }
+    }
+
+    pub fn read_from<R: Read>(stream: R) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        let mut budget = MAX_HEAD_BYTES;
+        read_head_line(&mut reader, &mut line, &mut budget)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(invalid("malformed request line"));
+        };
+        let (method, target) = (method.to_string(), target.to_string());
+        let mut length = 0;
+        loop {
+            if read_head_line(&mut reader, &mut line, &mut budget)? == 0 || line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    length = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| invalid("bad content length"))?;
+                }
+            }
+        }
+        if length > MAX_BODY_BYTES {
+            return Err(invalid("request body too large"));
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        let body = String::from_utf8(body).map_err(|_| invalid("body is not UTF-8"))?;
+        Ok(Self::new(&method, &target, &body))
+    }
+
+    fn query_count(&self, name: &str, default: u64) -> Result<u64, Response> {
+        match self.query.get(name) {
+            None => Ok(default),
+            Some(value) => value
+                .parse()
+                .map_err(|_| Response::error(400, &format!("{} must be an integer", name))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: u16, value: serde_json::Value) -> Self {
+        Self {
+            status,
+            body: value.to_string(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, json!({ "error": message }))
+    }
+
+    fn reason(status: u16) -> &'static str {
+        match status {
+            200 => "OK",
+            201 => "Created",
+            204 => "No Content",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            500 => "Internal Server Error",
+            _ => "Unprocessable Entity",
+        }
+    }
+
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            Self::reason(self.status),
+            self.body.len(),
+            self.body
+        )?;
+        out.flush()
+    }
+}
+
+struct Session {
+    scenario: Scenario,
+    engine: Engine,
+    steps: u64,
+}
+
+impl Session {
+    fn new(scenario: Scenario) -> Result<Self, Response> {
+        let run = scenario
+            .build()
+            .map_err(|e| Response::error(422, &e.to_string()))?;
+        Ok(Self {
+            scenario,
+            engine: run.engine,
+            steps: 0,
+        })
+    }
+
+    fn frame(&self) -> StreamFrame {
+        StreamFrame {
+            sequence: self.steps,
+            time: self.engine.time_minutes,
+            observables: self
+                .scenario
+                .outputs
+                .iter()
+                .map(|path| (path.clone(), self.engine.value(path).unwrap_or(f64::NAN)))
+                .collect(),
+        }
+    }
+
+    fn step(&mut self) -> Result<(), Response> {
+        self.engine
+            .step()
+            .map_err(|e| Response::error(422, &e.to_string()))?;
+        self.steps += 1;
+        Ok(())
+    }
+
+    fn frame_response(&self, status: u16) -> Response {
+        Response {
+            status,
+            body: self.frame().to_json_text(),
+        }
+    }
+}
+
+fn parse_scenario(body: &str) -> Result<Scenario, Response> {
+    let parsed = if body.trim_start().starts_with('{') {
+        Scenario::from_json_str(body)
+    } else {
+        Scenario::from_toml_str(body)
+    };
+    parsed.map_err(|e| Response::error(400, &e.to_string()))
+}
+
+// A session whose engine panicked mid-step is left poisoned; it answers
+// 500 until deleted.
+fn lock_session(session: &Mutex<Session>) -> Result<MutexGuard<'_, Session>, Response> {
+    session
+        .lock()
+        .map_err(|_| Response::error(500, "session failed during an earlier request"))
+}
+
+pub struct SimulationServer {
+    sessions: Mutex<BTreeMap<u64, Arc<Mutex<Session>>>>,
+    next_id: AtomicU64,
+    // Largest `steps` one step or stream request may ask for.
+    pub max_steps: u64,
+    pub io_timeout: Duration,
+}
+
+impl Default for SimulationServer {
+    fn default() -> Self {
+        Self {
+            sessions: Mutex::default(),
+            next_id: AtomicU64::new(0),
+            max_steps: DEFAULT_MAX_STEPS,
+            io_timeout: DEFAULT_IO_TIMEOUT,
+        }
+    }
+}
+
+impl SimulationServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_steps(mut self, max_steps: u64) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn with_io_timeout(mut self, timeout: Duration) -> Self {
+        self.io_timeout = timeout;
+        self
+    }
+
+    // The table is only held for map operations, which cannot panic.
+    fn table(&self) -> MutexGuard<'_, BTreeMap<u64, Arc<Mutex<Session>>>> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn session_count(&self) -> usize {
+        self.table().len()
+    }
+
+    fn session(&self, id: &str) -> Result<Arc<Mutex<Session>>, Response> {
+        id.parse::<u64>()
+            .ok()
+            .and_then(|id| self.table().get(&id).cloned())
+            .ok_or_else(|| Response::error(404, &format!("no session {}", id)))
+    }
+
+    fn step_count(&self, request: &Request) -> Result<u64, Response> {
+        let steps = request.query_count("steps", 1)?;
+        if steps > self.max_steps {
+            return Err(Response::error(
+                400,
+                &format!("steps must be at most {}", self.max_steps),
+            ));
+        }
+        Ok(steps)
+    }
+
+    // Answers one request, writing the complete HTTP response; `stream`
+    // replies are written frame by frame as the simulation advances.
+    pub fn handle<W: Write>(&self, request: &Request, out: &mut W) -> io::Result<()> {
+        if request.method == "GET" && request.path.len() == 3 && request.path[2] == "stream" {
+            return match self.stream(request, out) {
+                Ok(()) => Ok(()),
+                Err(Ok(response)) => response.write_to(out),
+                Err(Err(e)) => Err(e),
+            };
+        }
+        self.respond(request)
+            .unwrap_or_else(|response| response)
+            .write_to(out)
+    }
+
+    fn respond(&self, request: &Request) -> Result<Response, Response> {
+        let path: Vec<&str> = request.path.iter().map(String::as_str).collect();
+        match (request.method.as_str(), path.as_slice()) {
+            ("POST", ["sessions"]) => {
+                let session = Session::new(parse_scenario(&request.body)?)?;
+                let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+                self.table().insert(id, Arc::new(Mutex::new(session)));
+                Ok(Response::json(201, json!({ "id": id })))
+            }
+            ("GET", ["sessions"]) => {
+                let ids: Vec<u64> = self.table().keys().copied().collect();
+                Ok(Response::json(200, json!({ "sessions": ids })))
+            }
+            ("DELETE", ["sessions", id]) => {
+                self.session(id)?;
+                let id: u64 = id.parse().expect("checked by session()");
+                self.table().remove(&id);
+                Ok(Response {
+                    status: 204,
+                    body: String::new(),
+                })
+            }
+            (method, ["sessions", id, rest @ ..]) => {
+                let session = self.session(id)?;
+                let mut session = lock_session(&session)?;
+                match (method, rest) {
+                    ("GET", []) => Ok(session.frame_response(200)),
+                    ("PUT", ["scenario"]) => {
+                        *session = Session::new(parse_scenario(&request.body)?)?;
+                        Ok(session.frame_response(200))
+                    }
+                    ("POST", ["step"]) => {
+                        for _ in 0..self.step_count(request)? {
+                            session.step()?;
+                        }
+                        Ok(session.frame_response(200))
+                    }
+                    ("POST", ["run"]) => {
+                        let end = session.scenario.simulation.duration_minutes;
+                        let dt = session.engine.dt_minutes;
+                        let remaining = ((end - session.engine.time_minutes) / dt - 1e-9)
+                            .ceil()
+                            .max(0.0);
+                        if remaining > self.max_steps as f64 {
+                            return Err(Response::error(
+                                400,
+                                &format!(
+                                    "run needs {} steps, more than the limit of {}",
+                                    remaining, self.max_steps
+                                ),
+                            ));
+                        }
+                        for _ in 0..remaining as u64 {
+                            session.step()?;
+                        }
+                        Ok(session.frame_response(200))
+                    }
+                    ("GET", ["checkpoint"]) => {
+                        let checkpoint = Checkpointed {
+                            steps: session.steps,
+                            ..Checkpointed::of_engine(&session.engine, 0)
+                        };
+                        let body = checkpoint
+                            .to_json()
+                            .map_err(|e| Response::error(500, &e.to_string()))?;
+                        Ok(Response::json(200, body))
+                    }
+                    ("PUT", ["checkpoint"]) => {
+                        let checkpoint = serde_json::from_str(&request.body)
+                            .map_err(|e| e.into())
+                            .and_then(Checkpointed::<EngineSnapshot>::from_json)
+                            .map_err(|e| Response::error(400, &e.to_string()))?;
+                        checkpoint
+                            .restore_engine(&mut session.engine)
+                            .map_err(|e| Response::error(422, &e.to_string()))?;
+                        session.steps = checkpoint.steps;
+                        Ok(session.frame_response(200))
+                    }
+                    _ => Err(Response::error(405, "unsupported method or path")),
+                }
+            }
+            _ => Err(Response::error(404, "unknown path")),
+        }
+    }
+
+    fn stream<W: Write>(
+        &self,
+        request: &Request,
+        out: &mut W,
+    ) -> Result<(), Result<Response, io::Error>> {
+        let session = self.session(&request.path[1]).map_err(Ok)?;
+        let steps = self.step_count(request).map_err(Ok)?;
+        let every = request.query_count("every", 1).map_err(Ok)?.max(1);
+        let mut session = lock_session(&session).map_err(Ok)?;
+        // No length: the stream ends when the connection closes.
+        write!(
+            out,
+            "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n"
+        )
+        .map_err(Err)?;
+        for i in 1..=steps {
+            if let Err(response) = session.step() {
+                writeln!(out, "{}", response.body).map_err(Err)?;
+                break;
+            }
+            if i % every == 0 || i == steps {
+                writeln!(out, "{}", session.frame().to_json_text()).map_err(Err)?;
+                out.flush().map_err(Err)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn serve_connection(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(self.io_timeout))?;
+        stream.set_write_timeout(Some(self.io_timeout))?;
+        match Request::read_from(&stream) {
+            Ok(request) => self.handle(&request, &mut stream),
+            Err(e) => Response::error(400, &e.to_string()).write_to(&mut stream),
+        }
+    }
+
+    // Accepts connections on a background thread, one thread per
+    // connection, until the handle is shut down.
+    pub fn spawn<A: ToSocketAddrs>(self: Arc<Self>, address: A) -> io::Result<ServerHandle> {
+        let listener = TcpListener::bind(address)?;
+        let local_addr = listener.local_addr()?;
+        let stopping = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&stopping);
+        let thread = thread::spawn(move || {
+            for stream in listener.incoming() {
+                if flag.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                let server = Arc::clone(&self);
+                thread::spawn(move || {
+                    let _ = server.serve_connection(stream);
+                });
+            }
+        });
+        Ok(ServerHandle {
+            local_addr,
+            stopping,
+            thread: Some(thread),
+        })
+    }
+}
+
+pub struct ServerHandle {
+    pub local_addr: SocketAddr,
+    stopping: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ServerHandle {
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        self.stopping.store(true, Ordering::SeqCst);
+        // Wakes the blocking accept so the loop sees the flag.
+        let _ = TcpStream::connect(self.local_addr);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for ServerHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = include_str!("../config_examples/exercise_in_heat_scenario.toml");
+
+    fn call(server: &SimulationServer, method: &str, target: &str, body: &str) -> (u16, String) {
+        let mut out = Vec::new();
+        server
+            .handle(&Request::new(method, target, body), &mut out)
+            .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let status = text[9..12].parse().unwrap();
+        let body = text.split_once("\r\n\r\n").unwrap().1.to_string();
+        (status, body)
+    }
+
+    #[test]
+    fn test_session_lifecycle() {
+        let server = SimulationServer::new();
+        let (status, body) = call(&server, "POST", "/sessions", SCENARIO);
+        assert_eq!(status, 201);
+        assert_eq!(body, r#"{"id":1}"#);
+
+        let (status, body) = call(&server, "POST", "/sessions/1/step?steps=10", "");
+        assert_eq!(status, 200);
+        let frame = StreamFrame::from_json_text(&body).unwrap();
+        assert_eq!(frame.sequence, 10);
+        assert_eq!(frame.time, 5.0);
+        assert!(frame.get("body.core_temperature_c").is_some());
+
+        let (_, checkpoint) = call(&server, "GET", "/sessions/1/checkpoint", "");
+        let (_, end) = call(&server, "POST", "/sessions/1/run", "");
+        assert_eq!(StreamFrame::from_json_text(&end).unwrap().time, 60.0);
+        let (status, restored) = call(&server, "PUT", "/sessions/1/checkpoint", &checkpoint);
+        assert_eq!(status, 200);
+        let restored = StreamFrame::from_json_text(&restored).unwrap();
+        assert_eq!((restored.sequence, restored.time), (10, 5.0));
+        let value: serde_json::Value = serde_json::from_str(&checkpoint).unwrap();
+        assert_eq!(value["type"], "Checkpoint");
+        assert!(Checkpointed::<EngineSnapshot>::from_json(value).is_ok());
+
+        assert_eq!(call(&server, "DELETE", "/sessions/1", "").0, 204);
+        assert_eq!(server.session_count(), 0);
+        assert_eq!(call(&server, "GET", "/sessions/1", "").0, 404);
+    }
+
+    #[test]
+    fn test_stream_emits_ndjson_frames() {
+        let server = SimulationServer::new();
+        call(&server, "POST", "/sessions", SCENARIO);
+        let (status, body) = call(&server, "GET", "/sessions/1/stream?steps=20&every=5", "");
+        assert_eq!(status, 200);
+        let frames: Vec<StreamFrame> = body
+            .lines()
+            .map(|line| StreamFrame::from_json_text(line).unwrap())
+            .collect();
+        assert_eq!(frames.len(), 4);
+        assert_eq!(frames[3].sequence, 20);
+    }
+
+    #[test]
+    fn test_bad_requests_are_reported() {
+        let server = SimulationServer::new();
+        let (status, body) = call(&server, "POST", "/sessions", "[simulation]\nduration = 1");
+        assert_eq!(status, 400);
+        assert!(body.contains("error"));
+        let invalid = SCENARIO.replace("respiratory_control", "kidney");
+        let (status, body) = call(&server, "POST", "/sessions", &invalid);
+        assert_eq!(status, 422);
+        assert!(body.contains("entities.lungs.model"));
+        call(&server, "POST", "/sessions", SCENARIO);
+        assert_eq!(call(&server, "POST", "/sessions/1/step?steps=x", "").0, 400);
+        assert_eq!(call(&server, "PATCH", "/sessions/1", "").0, 405);
+        assert_eq!(call(&server, "GET", "/metrics", "").0, 404);
+    }
+
+    #[test]
+    fn test_oversized_and_poisoned_requests_are_refused() {
+        let server = SimulationServer::new().with_max_steps(50);
+        call(&server, "POST", "/sessions", SCENARIO);
+        assert_eq!(
+            call(&server, "POST", "/sessions/1/step?steps=50", "").0,
+            200
+        );
+        let (status, body) = call(&server, "POST", "/sessions/1/step?steps=51", "");
+        assert_eq!(status, 400);
+        assert!(body.contains("at most 50"));
+        assert_eq!(
+            call(&server, "GET", "/sessions/1/stream?steps=51", "").0,
+            400
+        );
+        // 60 minutes at 0.5 leaves 70 steps after the first 50.
+        let (status, body) = call(&server, "POST", "/sessions/1/run", "");
+        assert_eq!(status, 400);
+        assert!(body.contains("run needs 70 steps"));
+
+        let long_line = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_HEAD_BYTES));
+        assert!(Request::read_from(long_line.as_bytes()).is_err());
+        let many_headers = format!("GET / HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(4096));
+        assert!(Request::read_from(many_headers.as_bytes()).is_err());
+
+        let session = server.session("1").unwrap();
+        let _ = thread::spawn(move || {
+            let _guard = session.lock().unwrap();
+            panic!("engine failure");
+        })
+        .join();
+        assert_eq!(call(&server, "GET", "/sessions/1", "").0, 500);
+        assert_eq!(call(&server, "DELETE", "/sessions/1", "").0, 204);
+    }
+
+    #[test]
+    fn test_stalled_client_times_out() {
+        let server = SimulationServer::new().with_io_timeout(Duration::from_millis(100));
+        let handle = Arc::new(server).spawn("127.0.0.1:0").unwrap();
+        let mut stream = TcpStream::connect(handle.local_addr).unwrap();
+        stream.write_all(b"POST /sessions HTTP/1.1\r\n").unwrap();
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        assert!(reply.starts_with("HTTP/1.1 400"));
+        handle.shutdown();
+    }
+
+    #[test]
+    fn test_serves_over_tcp() {
+        let handle = Arc::new(SimulationServer::new())
+            .spawn("127.0.0.1:0")
+            .unwrap();
+        let send = |request: String| {
+            let mut stream = TcpStream::connect(handle.local_addr).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut reply = String::new();
+            stream.read_to_string(&mut reply).unwrap();
+            reply
+        };
+        let reply = send(format!(
+            "POST /sessions HTTP/1.1\r\nHost: x\r\nContent-Length: {}\r\n\r\n{}",
+            SCENARIO.len(),
+            SCENARIO
+        ));
+        assert!(reply.starts_with("HTTP/1.1 201 Created"));
+        let reply = send("POST /sessions/1/step?steps=2 HTTP/1.1\r\n\r\n".to_string());
+        assert!(reply.contains("\"sequence\":2"));
+        handle.shutdown();
+    }
+}
@@ -0,0 +1,429 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::geometry::Vec3;
+use crate::systems::immune::granuloma::{GranulomaModel, LatticeMacrophage};
+use serde::{Deserialize, Serialize};
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+// Entity-component storage for large agent populations. Each component
+// type lives in its own sparse set: a dense, contiguous Vec<T> for
+// iteration plus an entity-index -> slot table for O(1) lookup. Entities
+// are generational indices, so a handle to a despawned agent never
+// aliases the agent that later reuses its slot.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Entity {
+    index: u32,
+    generation: u32,
+}
+
+impl Entity {
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Position(pub Vec3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Velocity(pub Vec3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AgentKind {
+    Macrophage,
+    Lymphocyte,
+    Fibroblast,
+    Neuron,
+    Other,
+}
+
+const EMPTY: u32 = u32::MAX;
+
+struct ComponentStore<T> {
+    sparse: Vec<u32>,
+    dense: Vec<T>,
+    owners: Vec<Entity>,
+}
+
+impl<T> ComponentStore<T> {
+    fn new() -> Self {
+        Self {
+            sparse: Vec::new(),
+            dense: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    fn slot(&self, index: u32) -> Option<usize> {
+        match self.sparse.get(index as usize) {
+            Some(&slot) if slot != EMPTY => Some(slot as usize),
+            _ => None,
+        }
+    }
+
+    fn insert(&mut self, entity: Entity, value: T) -> Option<T> {
+        if let Some(slot) = self.slot(entity.index) {
+            return Some(std::mem::replace(&mut self.dense[slot], value));
+        }
+        let index = entity.index();
+        if index >= self.sparse.len() {
+            self.sparse.resize(index + 1, EMPTY);
+        }
+        self.sparse[index] = self.dense.len() as u32;
+        self.dense.push(value);
+        self.owners.push(entity);
+        None
+    }
+
+    // Swap-remove keeps the dense array gap-free.
+    fn remove(&mut self, index: u32) -> Option<T> {
+        let slot = self.slot(index)?;
+        self.sparse[index as usize] = EMPTY;
+        let value = self.dense.swap_remove(slot);
+        self.owners.swap_remove(slot);
+        if let Some(moved) = self.owners.get(slot) {
+            self.sparse[moved.index()] = slot as u32;
+        }
+        Some(value)
+    }
+}
+
+trait AnyStore: Send {
+    fn remove_entity(&mut self, index: u32);
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Send + 'static> AnyStore for ComponentStore<T> {
+    fn remove_entity(&mut self, index: u32) {
+        self.remove(index);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+#[derive(Default)]
+pub struct World {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<u32>,
+    live: usize,
+    stores: HashMap<TypeId, Box<dyn AnyStore>>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn spawn(&mut self) -> Entity {
+        self.live += 1;
+        if let Some(index) = self.free.pop() {
+            self.alive[index as usize] = true;
+            return Entity {
+                index,
+                generation: self.generations[index as usize],
+            };
+        }
+        self.generations.push(0);
+        self.alive.push(true);
+        Entity {
+            index: (self.generations.len() - 1) as u32,
+            generation: 0,
+        }
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.alive.get(entity.index()) == Some(&true)
+            && self.generations[entity.index()] == entity.generation
+    }
+
+    // Drops every component of `entity`; false if it was already gone.
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        for store in self.stores.values_mut() {
+            store.remove_entity(entity.index);
+        }
+        self.alive[entity.index()] = false;
+        self.generations[entity.index()] += 1;
+        self.free.push(entity.index);
+        self.live -= 1;
+        true
+    }
+
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    fn store<T: Send + 'static>(&self) -> Option<&ComponentStore<T>> {
+        self.stores
+            .get(&TypeId::of::<T>())
+            .and_then(|s| s.as_any().downcast_ref())
+    }
+
+    fn store_mut<T: Send + 'static>(&mut self) -> Option<&mut ComponentStore<T>> {
+        self.stores
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|s| s.as_any_mut().downcast_mut())
+    }
+
+    // Attaches a component, returning the one it replaced.
+    pub fn insert<T: Send + 'static>(
+        &mut self,
+        entity: Entity,
+        component: T,
+    ) -> BiologyResult<Option<T>> {
+        if !self.is_alive(entity) {
+            return Err(BiologyError::InvalidState(format!(
+                "entity {} is not alive",
+                entity.index
+            )));
+        }
+        let store = self
+            .stores
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(ComponentStore::<T>::new()))
+            .as_any_mut()
+            .downcast_mut::<ComponentStore<T>>()
+            .expect("store keyed by its TypeId");
+        Ok(store.insert(entity, component))
+    }
+
+    pub fn remove<T: Send + 'static>(&mut self, entity: Entity) -> Option<T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        self.store_mut::<T>()?.remove(entity.index)
+    }
+
+    pub fn get<T: Send + 'static>(&self, entity: Entity) -> Option<&T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        let store = self.store::<T>()?;
+        store.slot(entity.index).map(|slot| &store.dense[slot])
+    }
+
+    pub fn get_mut<T: Send + 'static>(&mut self, entity: Entity) -> Option<&mut T> {
+        if !self.is_alive(entity) {
+            return None;
+        }
+        let store = self.store_mut::<T>()?;
+        let slot = store.slot(entity.index)?;
+        Some(&mut store.dense[slot])
+    }
+
+    pub fn count<T: Send + 'static>(&self) -> usize {
+        self.store::<T>().map_or(0, |s| s.dense.len())
+    }
+
+    // Every `T` as one contiguous slice, in no particular entity order.
+    pub fn components<T: Send + 'static>(&self) -> &[T] {
+        self.store::<T>().map_or(&[], |s| &s.dense)
+    }
+
+    pub fn components_mut<T: Send + 'static>(&mut self) -> &mut [T] {
+        self.store_mut::<T>().map_or(&mut [], |s| &mut s.dense)
+    }
+
+    pub fn query<T: Send + 'static>(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.store::<T>()
+            .into_iter()
+            .flat_map(|s| s.owners.iter().copied().zip(&s.dense))
+    }
+
+    pub fn query_mut<T: Send + 'static>(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.store_mut::<T>()
+            .into_iter()
+            .flat_map(|s| s.owners.iter().copied().zip(&mut s.dense))
+    }
+
+    // Entities holding both `A` and `B`, iterating A's dense storage.
+    pub fn query2<A: Send + 'static, B: Send + 'static>(
+        &self,
+    ) -> impl Iterator<Item = (Entity, &A, &B)> {
+        let a = self.store::<A>();
+        let b = self.store::<B>();
+        a.zip(b).into_iter().flat_map(|(a, b)| {
+            a.owners
+                .iter()
+                .zip(&a.dense)
+                .filter_map(move |(&e, va)| b.slot(e.index).map(|slot| (e, va, &b.dense[slot])))
+        })
+    }
+
+    // Runs `system` over entities holding both components, with `A`
+    // mutable and `B` shared.
+    pub fn for_each2_mut<A: Send + 'static, B: Send + 'static>(
+        &mut self,
+        mut system: impl FnMut(Entity, &mut A, &B),
+    ) {
+        assert_ne!(
+            TypeId::of::<A>(),
+            TypeId::of::<B>(),
+            "component types must differ"
+        );
+        let [Some(a), Some(b)] = self
+            .stores
+            .get_disjoint_mut([&TypeId::of::<A>(), &TypeId::of::<B>()])
+        else {
+            return;
+        };
+        let a = a
+            .as_any_mut()
+            .downcast_mut::<ComponentStore<A>>()
+            .expect("store keyed by its TypeId");
+        let b = b
+            .as_any()
+            .downcast_ref::<ComponentStore<B>>()
+            .expect("store keyed by its TypeId");
+        for (&entity, value) in a.owners.iter().zip(&mut a.dense) {
+            if let Some(slot) = b.slot(entity.index) {
+                system(entity, value, &b.dense[slot]);
+            }
+        }
+    }
+
+    // Explicit Euler drift for every agent with a velocity.
+    pub fn advance_positions(&mut self, dt: f64) {
+        self.for_each2_mut::<Position, Velocity>(|_, position, velocity| {
+            position.0 += velocity.0 * dt;
+        });
+    }
+
+    pub fn entities_of_kind(&self, kind: AgentKind) -> impl Iterator<Item = Entity> + '_ {
+        self.query::<AgentKind>()
+            .filter(move |(_, &k)| k == kind)
+            .map(|(e, _)| e)
+    }
+}
+
+// One entity per lattice macrophage, carrying its lattice state plus a
+// Position at the site centre and AgentKind::Macrophage.
+impl From<&GranulomaModel> for World {
+    fn from(model: &GranulomaModel) -> Self {
+        let mut world = World::new();
+        for macrophage in &model.macrophages {
+            let entity = world.spawn();
+            let position = Vec3::new(macrophage.x as f64, macrophage.y as f64, 0.0);
+            world
+                .insert(entity, Position(position))
+                .expect("freshly spawned");
+            world
+                .insert(entity, AgentKind::Macrophage)
+                .expect("freshly spawned");
+            world
+                .insert::<LatticeMacrophage>(entity, *macrophage)
+                .expect("freshly spawned");
+        }
+        world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::immune::granuloma::{AntigenDepot, DepotKind, GranulomaParameters};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_generational_handles_do_not_alias() {
+        let mut world = World::new();
+        let first = world.spawn();
+        world
+            .insert(first, Position(Vec3::new(1.0, 0.0, 0.0)))
+            .unwrap();
+        assert!(world.despawn(first));
+        assert!(!world.despawn(first));
+        let second = world.spawn();
+        assert_eq!(second.index(), first.index());
+        assert!(world.get::<Position>(first).is_none());
+        assert!(world.get::<Position>(second).is_none());
+        assert!(world.insert(first, AgentKind::Neuron).is_err());
+        assert_eq!(world.len(), 1);
+    }
+
+    #[test]
+    fn test_swap_remove_keeps_lookups_valid() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..100)
+            .map(|i| {
+                let e = world.spawn();
+                world.insert(e, i as f64).unwrap();
+                e
+            })
+            .collect();
+        for e in entities.iter().step_by(3) {
+            world.despawn(*e);
+        }
+        assert_eq!(world.count::<f64>(), 66);
+        assert_eq!(world.components::<f64>().len(), 66);
+        for (i, e) in entities.iter().enumerate() {
+            let expected = (i % 3 != 0).then_some(i as f64);
+            assert_eq!(world.get::<f64>(*e).copied(), expected);
+        }
+        assert_eq!(world.remove::<f64>(entities[1]), Some(1.0));
+        assert_eq!(world.insert(entities[2], 9.0).unwrap(), Some(2.0));
+    }
+
+    #[test]
+    fn test_queries_by_component() {
+        let mut world = World::new();
+        for i in 0..10 {
+            let e = world.spawn();
+            world.insert(e, Position(Vec3::ZERO)).unwrap();
+            let kind = if i < 4 {
+                AgentKind::Neuron
+            } else {
+                AgentKind::Lymphocyte
+            };
+            world.insert(e, kind).unwrap();
+            if i % 2 == 0 {
+                world.insert(e, Velocity(Vec3::new(1.0, 2.0, 0.0))).unwrap();
+            }
+        }
+        assert_eq!(world.entities_of_kind(AgentKind::Neuron).count(), 4);
+        assert_eq!(world.query2::<Position, Velocity>().count(), 5);
+        world.advance_positions(0.5);
+        let moved = world
+            .query::<Position>()
+            .filter(|(_, p)| p.0 == Vec3::new(0.5, 1.0, 0.0))
+            .count();
+        assert_eq!(moved, 5);
+        for (_, kind) in world.query_mut::<AgentKind>() {
+            *kind = AgentKind::Other;
+        }
+        assert_eq!(world.entities_of_kind(AgentKind::Other).count(), 10);
+    }
+
+    #[test]
+    fn test_granuloma_macrophages_become_entities() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let depot = AntigenDepot::new(DepotKind::Mycobacterial, 4, 1.0).unwrap();
+        let model =
+            GranulomaModel::new(GranulomaParameters::default(), 40, depot, 0.02, &mut rng).unwrap();
+        let world = World::from(&model);
+        assert_eq!(world.len(), model.macrophages.len());
+        assert_eq!(
+            world.entities_of_kind(AgentKind::Macrophage).count(),
+            model.macrophages.len()
+        );
+        for (_, position, macrophage) in world.query2::<Position, LatticeMacrophage>() {
+            assert_eq!(position.0.x, macrophage.x as f64);
+        }
+    }
+}
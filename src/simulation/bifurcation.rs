@@ -0,0 +1,374 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::ode::{Counter, OdeSystem, SolverStats};
+use crate::simulation::steady_state::{equilibrium, SteadyStateOptions};
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+// Local stability of an equilibrium from the eigenvalues of the Jacobian
+// there, and one-parameter continuation of equilibrium branches. Along a
+// branch, a real eigenvalue crossing zero marks a fold or branch point and
+// a complex pair crossing the imaginary axis a Hopf bifurcation, where a
+// feedback loop starts to oscillate with period 2π/ω.
+// Kuznetsov YA (2004) Elements of Applied Bifurcation Theory, 3rd ed.
+
+// Real parts within this distance of zero count as neutral when
+// classifying a single point.
+const NEUTRAL_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Stability {
+    Stable,
+    Unstable,
+    // Largest real part is zero to within tolerance.
+    Neutral,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearStability {
+    // (real, imaginary), sorted by decreasing real part.
+    pub eigenvalues: Vec<(f64, f64)>,
+}
+
+impl LinearStability {
+    pub fn from_jacobian(jacobian: &DMatrix<f64>) -> BiologyResult<Self> {
+        if !jacobian.is_square() || jacobian.iter().any(|x| !x.is_finite()) {
+            return Err(BiologyError::InvalidValue(
+                "Jacobian must be square and finite".to_string(),
+            ));
+        }
+        let mut eigenvalues: Vec<(f64, f64)> = jacobian
+            .complex_eigenvalues()
+            .iter()
+            .map(|z| (z.re, z.im))
+            .collect();
+        eigenvalues.sort_by(|a, b| b.0.total_cmp(&a.0).then(b.1.total_cmp(&a.1)));
+        Ok(Self { eigenvalues })
+    }
+
+    pub fn leading_real_part(&self) -> f64 {
+        self.eigenvalues.first().map_or(f64::NEG_INFINITY, |e| e.0)
+    }
+
+    pub fn unstable_count(&self) -> usize {
+        self.eigenvalues.iter().filter(|e| e.0 > 0.0).count()
+    }
+
+    pub fn stability(&self) -> Stability {
+        let leading = self.leading_real_part();
+        if leading.abs() <= NEUTRAL_TOLERANCE {
+            Stability::Neutral
+        } else if leading < 0.0 {
+            Stability::Stable
+        } else {
+            Stability::Unstable
+        }
+    }
+
+    // Perturbations spiral rather than decay or grow monotonically.
+    pub fn is_oscillatory(&self) -> bool {
+        self.eigenvalues
+            .first()
+            .is_some_and(|e| e.1.abs() > NEUTRAL_TOLERANCE)
+    }
+}
+
+pub fn linear_stability<S: OdeSystem>(
+    system: &S,
+    t: f64,
+    state: &[f64],
+) -> BiologyResult<LinearStability> {
+    let mut counter = Counter {
+        system,
+        stats: SolverStats::default(),
+    };
+    let f = counter.f(t, state);
+    LinearStability::from_jacobian(&counter.jacobian(t, state, &f))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BifurcationKind {
+    // A real eigenvalue crosses zero: saddle-node, transcritical or
+    // pitchfork, depending on the branches that meet there.
+    RealCrossing,
+    // A complex pair crosses the imaginary axis; oscillations begin.
+    Hopf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bifurcation {
+    pub kind: BifurcationKind,
+    // Interpolated to where the crossing eigenvalue's real part is zero.
+    pub parameter: f64,
+    // Angular frequency of the emerging oscillation, Hopf only.
+    pub frequency: Option<f64>,
+    // True when the branch gains instability in the direction of travel.
+    pub destabilising: bool,
+}
+
+impl Bifurcation {
+    pub fn period(&self) -> Option<f64> {
+        self.frequency.map(|w| 2.0 * std::f64::consts::PI / w)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchPoint {
+    pub parameter: f64,
+    pub state: Vec<f64>,
+    pub stability: LinearStability,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Branch {
+    pub points: Vec<BranchPoint>,
+    pub bifurcations: Vec<Bifurcation>,
+    // Set when the corrector lost the branch before the end of the range,
+    // typically at a fold where it turns back.
+    pub terminated_at: Option<f64>,
+}
+
+impl Branch {
+    // Maximal runs of consecutive points sharing a stability, as
+    // (first parameter, last parameter, stability).
+    pub fn segments(&self) -> Vec<(f64, f64, Stability)> {
+        let mut segments: Vec<(f64, f64, Stability)> = Vec::new();
+        for point in &self.points {
+            let stability = point.stability.stability();
+            match segments.last_mut() {
+                Some(last) if last.2 == stability => last.1 = point.parameter,
+                _ => segments.push((point.parameter, point.parameter, stability)),
+            }
+        }
+        segments
+    }
+
+    pub fn oscillation_onset(&self) -> Option<f64> {
+        self.bifurcations
+            .iter()
+            .find(|b| b.kind == BifurcationKind::Hopf && b.destabilising)
+            .map(|b| b.parameter)
+    }
+}
+
+// Natural-parameter continuation: `model(p)` builds the system at each of
+// `steps + 1` evenly spaced parameters from `start` to `end`; each point is
+// predicted by secant extrapolation from the last two and corrected by
+// Newton, so unstable branches are followed as well as stable ones. The
+// first point is found from `guess` by `equilibrium`.
+pub fn continuation<S, F>(
+    mut model: F,
+    start: f64,
+    end: f64,
+    steps: usize,
+    guess: &[f64],
+    options: SteadyStateOptions,
+) -> BiologyResult<Branch>
+where
+    S: OdeSystem,
+    F: FnMut(f64) -> S,
+{
+    if steps == 0 || !(start.is_finite() && end.is_finite()) || start == end {
+        return Err(BiologyError::InvalidParameter(
+            "continuation needs a finite, non-empty range and at least one step".to_string(),
+        ));
+    }
+    let first = equilibrium(&model(start), 0.0, guess, options)?;
+    let mut points = vec![BranchPoint {
+        parameter: start,
+        stability: linear_stability(&model(start), 0.0, &first.state)?,
+        state: first.state,
+    }];
+    let mut terminated_at = None;
+    for i in 1..=steps {
+        let parameter = start + (end - start) * i as f64 / steps as f64;
+        let predicted: Vec<f64> = match points.as_slice() {
+            [.., a, b] => b
+                .state
+                .iter()
+                .zip(&a.state)
+                .map(|(y1, y0)| 2.0 * y1 - y0)
+                .collect(),
+            [.., b] => b.state.clone(),
+            [] => unreachable!("branch starts with one point"),
+        };
+        let system = model(parameter);
+        match newton(&system, &predicted, options) {
+            Some(state) => points.push(BranchPoint {
+                parameter,
+                stability: linear_stability(&system, 0.0, &state)?,
+                state,
+            }),
+            None => {
+                debug!(parameter, "continuation lost the branch");
+                terminated_at = Some(parameter);
+                break;
+            }
+        }
+    }
+    let bifurcations = points
+        .windows(2)
+        .filter_map(|pair| detect(&pair[0], &pair[1]))
+        .collect();
+    Ok(Branch {
+        points,
+        bifurcations,
+        terminated_at,
+    })
+}
+
+// Plain Newton on f(y) = 0; converges to unstable equilibria too.
+fn newton<S: OdeSystem>(
+    system: &S,
+    guess: &[f64],
+    options: SteadyStateOptions,
+) -> Option<Vec<f64>> {
+    let mut counter = Counter {
+        system,
+        stats: SolverStats::default(),
+    };
+    let mut y = guess.to_vec();
+    for _ in 0..options.max_iterations.min(50) {
+        let f = counter.f(0.0, &y);
+        let settled = f
+            .iter()
+            .zip(&y)
+            .all(|(r, v)| r.abs() <= options.atol + options.rtol * v.abs());
+        if settled {
+            return Some(y);
+        }
+        let jacobian = counter.jacobian(0.0, &y, &f);
+        let delta = jacobian.lu().solve(&-DVector::from_column_slice(&f))?;
+        if delta.iter().any(|d| !d.is_finite()) {
+            return None;
+        }
+        for (yi, di) in y.iter_mut().zip(delta.iter()) {
+            *yi += di;
+        }
+    }
+    None
+}
+
+// The eigenvalue that changes sign between two points, located by linear
+// interpolation of its real part.
+fn detect(a: &BranchPoint, b: &BranchPoint) -> Option<Bifurcation> {
+    let (before, after) = (a.stability.unstable_count(), b.stability.unstable_count());
+    if before == after {
+        return None;
+    }
+    let destabilising = after > before;
+    // On the unstable side the crossing eigenvalue is the smallest
+    // positive one; pair it with the stable side's largest non-positive.
+    let (unstable_side, stable_side) = if destabilising { (b, a) } else { (a, b) };
+    let rank = before.min(after);
+    let crossing = unstable_side.stability.eigenvalues[rank];
+    let partner = stable_side.stability.eigenvalues[rank];
+    let fraction = partner.0 / (partner.0 - crossing.0);
+    let parameter =
+        stable_side.parameter + fraction * (unstable_side.parameter - stable_side.parameter);
+    let hopf = crossing.1.abs() > NEUTRAL_TOLERANCE && partner.1.abs() > NEUTRAL_TOLERANCE;
+    Some(Bifurcation {
+        kind: if hopf {
+            BifurcationKind::Hopf
+        } else {
+            BifurcationKind::RealCrossing
+        },
+        parameter,
+        frequency: hopf.then(|| 0.5 * (crossing.1.abs() + partner.1.abs())),
+        destabilising,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hopf normal form: the origin loses stability at μ = 0 with ω = 1.
+    struct HopfNormalForm {
+        mu: f64,
+    }
+
+    impl OdeSystem for HopfNormalForm {
+        fn dimension(&self) -> usize {
+            2
+        }
+
+        fn rhs(&self, _t: f64, y: &[f64], dydt: &mut [f64]) {
+            let r2 = y[0] * y[0] + y[1] * y[1];
+            dydt[0] = self.mu * y[0] - y[1] - y[0] * r2;
+            dydt[1] = y[0] + self.mu * y[1] - y[1] * r2;
+        }
+    }
+
+    // Saddle-node: equilibria ±sqrt(μ) exist only for μ > 0, the upper
+    // one stable.
+    struct SaddleNode {
+        mu: f64,
+    }
+
+    impl OdeSystem for SaddleNode {
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn rhs(&self, _t: f64, y: &[f64], dydt: &mut [f64]) {
+            dydt[0] = self.mu - y[0] * y[0];
+        }
+    }
+
+    #[test]
+    fn test_linear_stability_classifies_spirals() {
+        let stable = linear_stability(&HopfNormalForm { mu: -0.5 }, 0.0, &[0.0, 0.0]).unwrap();
+        assert_eq!(stable.stability(), Stability::Stable);
+        assert!(stable.is_oscillatory());
+        assert!((stable.leading_real_part() + 0.5).abs() < 1e-6);
+        let unstable = linear_stability(&HopfNormalForm { mu: 0.2 }, 0.0, &[0.0, 0.0]).unwrap();
+        assert_eq!(unstable.stability(), Stability::Unstable);
+        assert_eq!(unstable.unstable_count(), 2);
+        assert!(LinearStability::from_jacobian(&DMatrix::zeros(2, 3)).is_err());
+    }
+
+    #[test]
+    fn test_continuation_finds_hopf_onset() {
+        let branch = continuation(
+            |mu| HopfNormalForm { mu },
+            -1.0,
+            1.0,
+            40,
+            &[0.01, 0.0],
+            SteadyStateOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(branch.points.len(), 41);
+        assert_eq!(branch.bifurcations.len(), 1);
+        let hopf = branch.bifurcations[0];
+        assert_eq!(hopf.kind, BifurcationKind::Hopf);
+        assert!(hopf.parameter.abs() < 1e-6);
+        assert!((hopf.period().unwrap() - 2.0 * std::f64::consts::PI).abs() < 1e-3);
+        assert!(branch.oscillation_onset().is_some());
+        let segments = branch.segments();
+        assert_eq!(segments.first().unwrap().2, Stability::Stable);
+        assert_eq!(segments.last().unwrap().2, Stability::Unstable);
+    }
+
+    #[test]
+    fn test_fold_terminates_branch() {
+        // Follow the stable upper branch downwards into the fold at μ = 0.
+        let branch = continuation(
+            |mu| SaddleNode { mu },
+            1.0,
+            -0.5,
+            30,
+            &[1.0],
+            SteadyStateOptions::default(),
+        )
+        .unwrap();
+        let fold = branch.terminated_at.unwrap();
+        assert!((-0.5..=0.05).contains(&fold), "{}", fold);
+        assert!(branch
+            .points
+            .iter()
+            .all(|p| (p.state[0] - p.parameter.sqrt()).abs() < 1e-4));
+        assert!(branch.oscillation_onset().is_none());
+    }
+}
@@ -0,0 +1,430 @@
+use crate::biology::BiologyResult;
+use crate::io::nonfinite;
+use crate::io::versioned::{upgrade, Envelope, Versioned};
+use crate::simulation::engine::{Engine, EngineSnapshot};
+use rand::SeedableRng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+// ChaCha12, the generator behind `StdRng`, with its state exposed to serde
+// so a restored run continues the exact random stream.
+pub type SimulationRng = rand_chacha::ChaCha12Rng;
+
+// File layout, little-endian:
+//   magic "HBCK" | format version u32 | payload length u64 | FNV-1a u64
+//   | payload
+// The format version covers this framing only. The payload is JSON in a
+// versioned `Envelope` (bare JSON in files written before envelopes), so
+// layout changes to the state migrate like any other `Versioned` type.
+// Finite f64 round-trip exactly with `float_roundtrip`; NaN and infinities
+// are spelled out (`io::nonfinite`).
+const MAGIC: &[u8; 4] = b"HBCK";
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+const HEADER_BYTES: usize = 4 + 4 + 8 + 8;
+const EXTENSION: &str = "hbck";
+
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for &byte in bytes {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+// Writes every `every_steps` steps into `directory`, keeping the newest
+// `keep` files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointPolicy {
+    pub directory: PathBuf,
+    pub every_steps: u64,
+    pub keep: usize,
+}
+
+impl CheckpointPolicy {
+    pub fn new<P: AsRef<Path>>(directory: P, every_steps: u64) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            every_steps: every_steps.max(1),
+            keep: 3,
+        }
+    }
+
+    fn path_for(&self, steps: u64) -> PathBuf {
+        self.directory
+            .join(format!("checkpoint_{steps:012}.{EXTENSION}"))
+    }
+
+    // Checkpoint files in step order; names sort with their zero-padded
+    // step counts.
+    pub fn existing(&self) -> std::io::Result<Vec<PathBuf>> {
+        if !self.directory.exists() {
+            return Ok(Vec::new());
+        }
+        let mut paths: Vec<PathBuf> = fs::read_dir(&self.directory)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e == EXTENSION))
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+}
+
+// A long-running simulation: the model state (any serializable bundle of
+// entities, e.g. a `Human` with its `AgingEngine`, or the `EngineSnapshot`
+// of a component engine), the clock and the RNG, saved and restored as a
+// unit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpointed<S> {
+    pub state: S,
+    pub time: f64,
+    pub steps: u64,
+    pub rng: SimulationRng,
+    #[serde(skip)]
+    pub autosave: Option<CheckpointPolicy>,
+}
+
+impl<S: Serialize + DeserializeOwned> Versioned for Checkpointed<S> {
+    const TYPE_NAME: &'static str = "Checkpoint";
+    const VERSION: u32 = 1;
+}
+
+impl<S: Serialize + DeserializeOwned> Checkpointed<S> {
+    pub fn new(state: S, seed: u64) -> Self {
+        Self {
+            state,
+            time: 0.0,
+            steps: 0,
+            rng: SimulationRng::seed_from_u64(seed),
+            autosave: None,
+        }
+    }
+
+    pub fn with_autosave(mut self, policy: CheckpointPolicy) -> Self {
+        self.autosave = Some(policy);
+        self
+    }
+
+    pub fn step<F>(&mut self, dt: f64, step: &mut F) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: FnMut(&mut S, &mut SimulationRng, f64),
+    {
+        step(&mut self.state, &mut self.rng, dt);
+        self.steps += 1;
+        self.time += dt;
+        if let Some(policy) = &self.autosave {
+            if self.steps.is_multiple_of(policy.every_steps) {
+                self.save_to_policy(policy)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn run<F>(
+        &mut self,
+        steps: u64,
+        dt: f64,
+        mut step: F,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        F: FnMut(&mut S, &mut SimulationRng, f64),
+    {
+        for _ in 0..steps {
+            self.step(dt, &mut step)?;
+        }
+        Ok(())
+    }
+
+    fn save_to_policy(&self, policy: &CheckpointPolicy) -> Result<(), Box<dyn std::error::Error>> {
+        fs::create_dir_all(&policy.directory)?;
+        self.save(policy.path_for(self.steps))?;
+        let existing = policy.existing()?;
+        let stale = existing.len().saturating_sub(policy.keep.max(1));
+        for path in &existing[..stale] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    // The versioned JSON payload `to_bytes` frames, for transports that
+    // carry JSON themselves, such as the REST server.
+    pub fn to_json(&self) -> Result<Value, Box<dyn std::error::Error>> {
+        Ok(serde_json::to_value(Envelope {
+            type_name: Self::TYPE_NAME.to_string(),
+            version: Self::VERSION,
+            data: nonfinite::to_value(self)?,
+        })?)
+    }
+
+    pub fn from_json(value: Value) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(nonfinite::from_value(upgrade::<Self>(value)?)?)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let payload = serde_json::to_vec(&self.to_json()?)?;
+        let mut bytes = Vec::with_capacity(HEADER_BYTES + payload.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&CHECKPOINT_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&fnv1a(&payload).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn std::error::Error>> {
+        if bytes.len() < HEADER_BYTES || &bytes[..4] != MAGIC {
+            return Err("not a checkpoint file".into());
+        }
+        let version = u32::from_le_bytes(bytes[4..8].try_into()?);
+        if version == 0 || version > CHECKPOINT_FORMAT_VERSION {
+            return Err(format!(
+                "checkpoint format version {version} is not supported (expected {CHECKPOINT_FORMAT_VERSION})"
+            )
+            .into());
+        }
+        let length = u64::from_le_bytes(bytes[8..16].try_into()?) as usize;
+        let checksum = u64::from_le_bytes(bytes[16..24].try_into()?);
+        let payload = &bytes[HEADER_BYTES..];
+        if payload.len() != length || fnv1a(payload) != checksum {
+            return Err("checkpoint is truncated or corrupt".into());
+        }
+        Self::from_json(serde_json::from_slice(payload)?)
+    }
+
+    // Written to a sibling temporary file and renamed into place, so a run
+    // killed mid-write never leaves a half-written checkpoint behind.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let temporary = path.with_extension("partial");
+        let mut file = File::create(&temporary)?;
+        file.write_all(&self.to_bytes()?)?;
+        file.sync_all()?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut bytes = Vec::new();
+        File::open(path)?.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+
+    // Newest readable checkpoint under the policy, with autosave re-armed.
+    pub fn resume(policy: CheckpointPolicy) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        for path in policy.existing()?.iter().rev() {
+            if let Ok(restored) = Self::load(path) {
+                return Ok(Some(restored.with_autosave(policy)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// An engine is checkpointed through its snapshot of entity states, the
+// clock and any conserved exchange windows still open. Couplings are
+// configuration, so a run resumes into an engine rebuilt from the same
+// scenario.
+impl Checkpointed<EngineSnapshot> {
+    pub fn of_engine(engine: &Engine, seed: u64) -> Self {
+        Self {
+            time: engine.time_minutes,
+            ..Self::new(engine.snapshot(), seed)
+        }
+    }
+
+    // Steps the engine, refreshing the snapshot whenever one is saved and
+    // once at the end.
+    pub fn run_engine(
+        &mut self,
+        engine: &mut Engine,
+        steps: u64,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        for _ in 0..steps {
+            engine.step()?;
+            self.steps += 1;
+            self.time = engine.time_minutes;
+            if let Some(policy) = &self.autosave {
+                if self.steps.is_multiple_of(policy.every_steps) {
+                    self.state = engine.snapshot();
+                    self.save_to_policy(policy)?;
+                }
+            }
+        }
+        self.state = engine.snapshot();
+        Ok(())
+    }
+
+    pub fn restore_engine(&self, engine: &mut Engine) -> BiologyResult<()> {
+        engine.restore(&self.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aging::AgingEngine;
+    use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+    use crate::organism::Human;
+    use crate::systems::cardiovascular::hematology::BiologicalSex;
+    use rand::Rng;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct RandomWalk {
+        position: f64,
+        path: Vec<f64>,
+    }
+
+    fn walk(state: &mut RandomWalk, rng: &mut SimulationRng, dt: f64) {
+        state.position += rng.gen_range(-1.0..1.0) * dt.sqrt();
+        state.path.push(state.position);
+    }
+
+    fn fresh() -> Checkpointed<RandomWalk> {
+        Checkpointed::new(
+            RandomWalk {
+                position: 0.0,
+                path: Vec::new(),
+            },
+            21,
+        )
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_restored_run_continues_identically() {
+        let mut uninterrupted = fresh();
+        uninterrupted.run(200, 0.1, walk).unwrap();
+
+        let mut first_half = fresh();
+        first_half.run(100, 0.1, walk).unwrap();
+        let bytes = first_half.to_bytes().unwrap();
+        let mut resumed = Checkpointed::<RandomWalk>::from_bytes(&bytes).unwrap();
+        resumed.run(100, 0.1, walk).unwrap();
+
+        assert_eq!(resumed.state, uninterrupted.state);
+        assert_eq!(resumed.steps, 200);
+        assert_eq!(resumed.time, uninterrupted.time);
+    }
+
+    #[test]
+    fn test_corrupt_or_foreign_files_are_rejected() {
+        let mut bytes = fresh().to_bytes().unwrap();
+        let last = bytes.len() - 2;
+        bytes[last] ^= 0x01;
+        assert!(Checkpointed::<RandomWalk>::from_bytes(&bytes).is_err());
+        let mut future = fresh().to_bytes().unwrap();
+        future[4] = 9;
+        let message = Checkpointed::<RandomWalk>::from_bytes(&future)
+            .unwrap_err()
+            .to_string();
+        assert!(message.contains("version 9"));
+        assert!(Checkpointed::<RandomWalk>::from_bytes(b"{}").is_err());
+    }
+
+    #[test]
+    fn test_autosave_keeps_newest_and_resumes_from_it() {
+        let dir = temp_dir("checkpoint_autosave");
+        let policy = CheckpointPolicy {
+            keep: 2,
+            ..CheckpointPolicy::new(&dir, 25)
+        };
+        let mut run = fresh().with_autosave(policy.clone());
+        run.run(110, 0.1, walk).unwrap();
+        let files = policy.existing().unwrap();
+        assert_eq!(files.len(), 2);
+        assert!(files[1].ends_with("checkpoint_000000000100.hbck"));
+
+        let mut resumed = Checkpointed::<RandomWalk>::resume(policy).unwrap().unwrap();
+        assert_eq!(resumed.steps, 100);
+        resumed.run(10, 0.1, walk).unwrap();
+        assert_eq!(resumed.state, run.state);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_organism_and_aging_engine_round_trip() {
+        let human = Human::new(BiologicalSex::Female, 48.0, 165.0, 64.0).unwrap();
+        let engine = AgingEngine::new(&human).unwrap();
+        let mut run = Checkpointed::new((human, engine), 3);
+        run.run(5, 1.0, |(human, engine), _, _| {
+            engine.advance_years(human, 1, &mut []).unwrap();
+        })
+        .unwrap();
+        let restored =
+            Checkpointed::<(Human, AgingEngine)>::from_bytes(&run.to_bytes().unwrap()).unwrap();
+        assert_eq!(restored.state.0.age_years, 53.0);
+        assert_eq!(
+            serde_json::to_string(&restored.state).unwrap(),
+            serde_json::to_string(&run.state).unwrap()
+        );
+    }
+
+    fn meal_engine() -> Engine {
+        let mut engine = Engine::new(1.0).unwrap();
+        engine
+            .add_entity("gut", Box::new(GlucoseInsulinModel::new_healthy()))
+            .unwrap();
+        engine.set_input("gut.carbohydrate_g", 75.0).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_engine_resumes_from_checkpoint_file() {
+        let mut uninterrupted = meal_engine();
+        for _ in 0..120 {
+            uninterrupted.step().unwrap();
+        }
+
+        let dir = temp_dir("checkpoint_engine");
+        let policy = CheckpointPolicy::new(&dir, 50);
+        let mut engine = meal_engine();
+        let mut run = Checkpointed::of_engine(&engine, 0).with_autosave(policy.clone());
+        run.run_engine(&mut engine, 70).unwrap();
+        drop(engine);
+
+        let resumed = Checkpointed::<EngineSnapshot>::resume(policy)
+            .unwrap()
+            .unwrap();
+        let mut engine = meal_engine();
+        resumed.restore_engine(&mut engine).unwrap();
+        assert_eq!(engine.time_minutes, 50.0);
+        for _ in 0..70 {
+            engine.step().unwrap();
+        }
+        assert_eq!(
+            engine.value("gut.glucose_mg_dl").unwrap(),
+            uninterrupted.value("gut.glucose_mg_dl").unwrap()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_non_finite_state_and_pre_envelope_payloads_load() {
+        let mut run = fresh();
+        run.state.position = f64::NAN;
+        run.state.path = vec![f64::INFINITY, -1.0, f64::NEG_INFINITY];
+        let restored = Checkpointed::<RandomWalk>::from_bytes(&run.to_bytes().unwrap()).unwrap();
+        assert!(restored.state.position.is_nan());
+        assert_eq!(restored.state.path, run.state.path);
+
+        // Files written before the envelope hold the bare state.
+        let payload = serde_json::to_vec(&fresh()).unwrap();
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&fnv1a(&payload).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+        let old = Checkpointed::<RandomWalk>::from_bytes(&bytes).unwrap();
+        assert_eq!(old.rng, fresh().rng);
+    }
+}
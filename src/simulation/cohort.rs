@@ -0,0 +1,449 @@
+use crate::biology::{BiologyError, BiologyResult};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use tracing::{info, warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CohortConfig {
+    pub subjects: usize,
+    pub base_seed: u64,
+    pub worker_threads: usize,
+    pub memory_budget_bytes: usize,
+    pub estimated_bytes_per_subject: usize,
+    pub max_retries: u32,
+}
+
+impl CohortConfig {
+    pub fn new(subjects: usize, base_seed: u64) -> Self {
+        Self {
+            subjects,
+            base_seed,
+            worker_threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            memory_budget_bytes: 1 << 30,
+            estimated_bytes_per_subject: 1 << 20,
+            max_retries: 2,
+        }
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        if self.worker_threads == 0 {
+            return Err(BiologyError::InvalidParameter(
+                "at least one worker thread is required".to_string(),
+            ));
+        }
+        if self.estimated_bytes_per_subject > self.memory_budget_bytes {
+            return Err(BiologyError::InvalidParameter(
+                "memory budget cannot hold a single subject".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // Simultaneous runs are capped by both threads and the memory budget.
+    pub fn concurrency(&self) -> usize {
+        let by_memory = self.memory_budget_bytes / self.estimated_bytes_per_subject.max(1);
+        self.worker_threads.min(by_memory).min(self.subjects).max(1)
+    }
+}
+
+// Per-subject, per-attempt seed so results do not depend on scheduling.
+// SplitMix64 finaliser: Steele GL et al. (2014) OOPSLA, doi 10.1145/2660193.2660195
+pub fn subject_seed(base_seed: u64, subject: usize, attempt: u32) -> u64 {
+    let mut z = base_seed
+        ^ (subject as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (attempt as u64).wrapping_mul(0xD1B5_4A32_D192_ED03);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubjectRecord<T> {
+    pub subject: usize,
+    pub seed: u64,
+    pub attempts: u32,
+    pub result: T,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubjectFailure {
+    pub subject: usize,
+    pub attempts: u32,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CohortReport<T> {
+    pub records: Vec<SubjectRecord<T>>,
+    pub failures: Vec<SubjectFailure>,
+    // Records loaded from an earlier run rather than simulated now.
+    pub resumed: usize,
+}
+
+impl<T> CohortReport<T> {
+    pub fn completed(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn results(&self) -> impl Iterator<Item = &T> {
+        self.records.iter().map(|r| &r.result)
+    }
+}
+
+// Runs one simulation per subject across a bounded worker pool. Failed or
+// panicking subjects are retried with a fresh seed; finished subjects are
+// appended to a JSON-lines file so an interrupted study resumes where it
+// stopped.
+#[derive(Debug, Clone)]
+pub struct CohortRunner {
+    pub config: CohortConfig,
+    pub results_path: Option<PathBuf>,
+}
+
+impl CohortRunner {
+    pub fn new(config: CohortConfig) -> BiologyResult<Self> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            results_path: None,
+        })
+    }
+
+    pub fn with_persistence<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.results_path = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn run<T, F>(&self, simulate: F) -> Result<CohortReport<T>, Box<dyn std::error::Error>>
+    where
+        T: Serialize + DeserializeOwned + Send,
+        F: Fn(usize, &mut StdRng) -> BiologyResult<T> + Sync,
+    {
+        let mut records = match &self.results_path {
+            Some(path) if path.exists() => load_records::<T>(path)?,
+            _ => Vec::new(),
+        };
+        records.retain(|r| r.subject < self.config.subjects);
+        let resumed = records.len();
+        let done: HashSet<usize> = records.iter().map(|r| r.subject).collect();
+        let pending: Vec<usize> = (0..self.config.subjects)
+            .filter(|s| !done.contains(s))
+            .collect();
+
+        let sink = match &self.results_path {
+            Some(path) => Some(Mutex::new(
+                OpenOptions::new().create(true).append(true).open(path)?,
+            )),
+            None => None,
+        };
+        let next = AtomicUsize::new(0);
+        let finished = Mutex::new(Vec::new());
+        let failures = Mutex::new(Vec::new());
+        let io_error: Mutex<Option<String>> = Mutex::new(None);
+
+        thread::scope(|scope| {
+            for _ in 0..self.config.concurrency() {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&subject) = pending.get(i) else {
+                        break;
+                    };
+                    match self.run_subject(subject, &simulate) {
+                        Ok(record) => {
+                            if let Some(sink) = &sink {
+                                if let Err(e) = append_record(sink, &record) {
+                                    *io_error.lock().unwrap() = Some(e.to_string());
+                                }
+                            }
+                            finished.lock().unwrap().push(record);
+                        }
+                        Err(failure) => failures.lock().unwrap().push(failure),
+                    }
+                });
+            }
+        });
+
+        if let Some(message) = io_error.into_inner().unwrap() {
+            return Err(message.into());
+        }
+        records.extend(finished.into_inner().unwrap());
+        records.sort_by_key(|r| r.subject);
+        let mut failures = failures.into_inner().unwrap();
+        failures.sort_by_key(|f| f.subject);
+        info!(
+            completed = records.len(),
+            failed = failures.len(),
+            resumed,
+            "cohort finished"
+        );
+        Ok(CohortReport {
+            records,
+            failures,
+            resumed,
+        })
+    }
+
+    fn run_subject<T, F>(
+        &self,
+        subject: usize,
+        simulate: &F,
+    ) -> Result<SubjectRecord<T>, SubjectFailure>
+    where
+        F: Fn(usize, &mut StdRng) -> BiologyResult<T> + Sync,
+    {
+        let mut message = String::new();
+        for attempt in 0..=self.config.max_retries {
+            let seed = subject_seed(self.config.base_seed, subject, attempt);
+            let mut rng = StdRng::seed_from_u64(seed);
+            match panic::catch_unwind(AssertUnwindSafe(|| simulate(subject, &mut rng))) {
+                Ok(Ok(result)) => {
+                    return Ok(SubjectRecord {
+                        subject,
+                        seed,
+                        attempts: attempt + 1,
+                        result,
+                    })
+                }
+                Ok(Err(e)) => message = e.to_string(),
+                Err(_) => message = "simulation panicked".to_string(),
+            }
+            warn!(subject, attempt, seed, error = %message, "subject attempt failed");
+        }
+        Err(SubjectFailure {
+            subject,
+            attempts: self.config.max_retries + 1,
+            message,
+        })
+    }
+}
+
+// A run killed mid-write leaves a torn final line. It is cut off so the
+// resumed run appends onto a clean line boundary; a bad line anywhere
+// else means the file is damaged and is reported instead.
+fn load_records<T: DeserializeOwned>(
+    path: &Path,
+) -> Result<Vec<SubjectRecord<T>>, Box<dyn std::error::Error>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut records = Vec::new();
+    let mut good_bytes = 0u64;
+    let mut torn = false;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 {
+            break;
+        }
+        if torn {
+            return Err(format!(
+                "{}: unreadable record at byte {}",
+                path.display(),
+                good_bytes
+            )
+            .into());
+        }
+        let text = std::str::from_utf8(&line).map(str::trim);
+        match text {
+            Ok("") => good_bytes += read as u64,
+            Ok(text) if line.ends_with(b"\n") => match serde_json::from_str(text) {
+                Ok(record) => {
+                    records.push(record);
+                    good_bytes += read as u64;
+                }
+                Err(_) => torn = true,
+            },
+            _ => torn = true,
+        }
+    }
+    if torn {
+        warn!(path = %path.display(), offset = good_bytes, "dropping torn final record");
+        OpenOptions::new()
+            .write(true)
+            .open(path)?
+            .set_len(good_bytes)?;
+    }
+    Ok(records)
+}
+
+fn append_record<T: Serialize>(
+    sink: &Mutex<File>,
+    record: &SubjectRecord<T>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut line = serde_json::to_string(record)?;
+    line.push('\n');
+    let mut file = sink.lock().unwrap();
+    file.write_all(line.as_bytes())?;
+    file.flush()?;
+    Ok(())
+}
+
+pub fn clear_results<P: AsRef<Path>>(path: P) -> std::io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+    use rand::Rng;
+
+    fn fasting_glucose(_subject: usize, rng: &mut StdRng) -> BiologyResult<f64> {
+        let mut model = GlucoseInsulinModel::new_healthy();
+        model.ingest_carbohydrate(rng.gen_range(30.0..90.0))?;
+        model.run_minutes(120);
+        Ok(model.glucose_mg_dl)
+    }
+
+    #[test]
+    fn test_results_independent_of_thread_count() {
+        let mut config = CohortConfig::new(24, 7);
+        config.worker_threads = 1;
+        let serial = CohortRunner::new(config)
+            .unwrap()
+            .run(fasting_glucose)
+            .unwrap();
+        config.worker_threads = 6;
+        let parallel = CohortRunner::new(config)
+            .unwrap()
+            .run(fasting_glucose)
+            .unwrap();
+        assert_eq!(serial.completed(), 24);
+        assert_eq!(serial.records, parallel.records);
+    }
+
+    #[test]
+    fn test_memory_budget_limits_concurrency() {
+        let mut config = CohortConfig::new(40, 1);
+        config.worker_threads = 8;
+        config.estimated_bytes_per_subject = 100;
+        config.memory_budget_bytes = 250;
+        assert_eq!(config.concurrency(), 2);
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let report = CohortRunner::new(config)
+            .unwrap()
+            .run(|_, _| {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                thread::sleep(std::time::Duration::from_millis(1));
+                active.fetch_sub(1, Ordering::SeqCst);
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(report.completed(), 40);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        config.memory_budget_bytes = 50;
+        assert!(CohortRunner::new(config).is_err());
+    }
+
+    #[test]
+    fn test_failed_subjects_are_retried_then_reported() {
+        let mut config = CohortConfig::new(10, 3);
+        config.max_retries = 1;
+        let seen = Mutex::new(HashSet::new());
+        let report = CohortRunner::new(config)
+            .unwrap()
+            .run(|subject, _| {
+                if subject == 9 {
+                    return Err(BiologyError::InvalidState("diverged".to_string()));
+                }
+                if subject % 2 == 0 && seen.lock().unwrap().insert(subject) {
+                    panic!("transient failure");
+                }
+                Ok(subject)
+            })
+            .unwrap();
+        assert_eq!(report.completed(), 9);
+        assert!(report
+            .records
+            .iter()
+            .all(|r| r.attempts == 1 + (r.subject % 2 == 0) as u32));
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].subject, 9);
+        assert_eq!(report.failures[0].attempts, 2);
+    }
+
+    #[test]
+    fn test_interrupted_study_resumes_from_persisted_results() {
+        let path = std::env::temp_dir().join(format!("cohort_resume_{}.jsonl", std::process::id()));
+        clear_results(&path).unwrap();
+        let config = CohortConfig::new(12, 11);
+
+        let partial = CohortRunner::new(CohortConfig {
+            subjects: 5,
+            ..config
+        })
+        .unwrap()
+        .with_persistence(&path)
+        .run(fasting_glucose)
+        .unwrap();
+        assert_eq!(partial.completed(), 5);
+
+        let calls = AtomicUsize::new(0);
+        let resumed = CohortRunner::new(config)
+            .unwrap()
+            .with_persistence(&path)
+            .run(|s, rng| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                fasting_glucose(s, rng)
+            })
+            .unwrap();
+        let fresh = CohortRunner::new(config)
+            .unwrap()
+            .run(fasting_glucose)
+            .unwrap();
+        clear_results(&path).unwrap();
+
+        assert_eq!(resumed.resumed, 5);
+        assert_eq!(calls.load(Ordering::SeqCst), 7);
+        assert_eq!(resumed.records, fresh.records);
+    }
+
+    #[test]
+    fn test_torn_final_line_is_cut_before_resuming() {
+        let path = std::env::temp_dir().join(format!("cohort_torn_{}.jsonl", std::process::id()));
+        clear_results(&path).unwrap();
+        let config = CohortConfig::new(8, 5);
+        CohortRunner::new(CohortConfig {
+            subjects: 3,
+            ..config
+        })
+        .unwrap()
+        .with_persistence(&path)
+        .run(fasting_glucose)
+        .unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"subject\":3,\"se").unwrap();
+        drop(file);
+
+        let resumed = CohortRunner::new(config)
+            .unwrap()
+            .with_persistence(&path)
+            .run(fasting_glucose)
+            .unwrap();
+        assert_eq!(resumed.resumed, 3);
+        assert_eq!(resumed.completed(), 8);
+        // Every later resume reads the whole file back.
+        let reloaded = load_records::<f64>(&path).unwrap();
+        assert_eq!(reloaded.len(), 8);
+
+        // Damage before the last line is not silently skipped.
+        let text = fs::read_to_string(&path).unwrap();
+        fs::write(&path, format!("{{\"torn\n{}", text)).unwrap();
+        assert!(load_records::<f64>(&path).is_err());
+        clear_results(&path).unwrap();
+    }
+}
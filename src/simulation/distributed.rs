@@ -0,0 +1,663 @@
+use crate::biology::BiologyResult;
+use crate::simulation::cohort::{
+    subject_seed, CohortConfig, CohortReport, SubjectFailure, SubjectRecord,
+};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+// Newline-delimited JSON frames exchanged between coordinator and workers.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Frame {
+    Task {
+        id: usize,
+        attempt: u32,
+        input: Value,
+    },
+    Done {
+        id: usize,
+        output: Value,
+    },
+    Failed {
+        id: usize,
+        message: String,
+    },
+    Shutdown,
+}
+
+fn send(stream: &mut TcpStream, frame: &Frame) -> io::Result<()> {
+    let mut line = serde_json::to_string(frame).map_err(io::Error::other)?;
+    line.push('\n');
+    stream.write_all(line.as_bytes())?;
+    stream.flush()
+}
+
+fn receive(reader: &mut BufReader<TcpStream>) -> io::Result<Frame> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "peer closed"));
+    }
+    serde_json::from_str(&line).map_err(io::Error::other)
+}
+
+struct WorkerLink {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+enum Outcome {
+    Done(Value, u32),
+    Failed(String, u32),
+}
+
+// A queued task keeps its attempt number across rounds. Disconnects are
+// counted apart from failed attempts so a lost worker does not shift the
+// seed a cohort subject is retried with.
+#[derive(Clone, Copy)]
+struct Pending {
+    id: usize,
+    attempt: u32,
+    disconnects: u32,
+}
+
+// Coordinator side of a pull-based task farm. Workers connect over TCP,
+// take one task at a time and return the output. Results are placed by task
+// id, so they do not depend on worker count or completion order.
+pub struct Coordinator {
+    listener: TcpListener,
+    workers: Vec<WorkerLink>,
+    pub max_retries: u32,
+    // A worker silent for longer than this is treated as lost.
+    pub io_timeout: Option<Duration>,
+}
+
+impl Coordinator {
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            workers: Vec::new(),
+            max_retries: 2,
+            io_timeout: Some(Duration::from_secs(300)),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.workers.len()
+    }
+
+    pub fn accept_workers(&mut self, count: usize) -> io::Result<()> {
+        for _ in 0..count {
+            let (stream, _) = self.listener.accept()?;
+            stream.set_nodelay(true)?;
+            let reader = BufReader::new(stream.try_clone()?);
+            self.workers.push(WorkerLink { stream, reader });
+        }
+        Ok(())
+    }
+
+    // A worker that drops its connection, times out or answers for a task
+    // it was not given loses its in-flight task back to the queue and is
+    // removed from the pool. A task that has taken down more than
+    // max_retries workers is failed.
+    fn dispatch(&mut self, inputs: Vec<Value>) -> io::Result<Vec<Option<Outcome>>> {
+        let queue = Mutex::new(
+            (0..inputs.len())
+                .map(|id| Pending {
+                    id,
+                    attempt: 0,
+                    disconnects: 0,
+                })
+                .collect::<VecDeque<_>>(),
+        );
+        let outcomes: Mutex<Vec<Option<Outcome>>> =
+            Mutex::new((0..inputs.len()).map(|_| None).collect());
+        let max_retries = self.max_retries;
+        while !queue.lock().unwrap().is_empty() {
+            if self.workers.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "all workers disconnected with tasks outstanding",
+                ));
+            }
+            for link in &self.workers {
+                link.stream.set_read_timeout(self.io_timeout)?;
+                link.stream.set_write_timeout(self.io_timeout)?;
+            }
+            let lost: Vec<bool> = thread::scope(|scope| {
+                let handles: Vec<_> = self
+                    .workers
+                    .iter_mut()
+                    .map(|link| {
+                        let (queue, outcomes, inputs) = (&queue, &outcomes, &inputs);
+                        scope.spawn(move || loop {
+                            let Some(task) = queue.lock().unwrap().pop_front() else {
+                                return false;
+                            };
+                            let Pending { id, attempt, .. } = task;
+                            let frame = Frame::Task {
+                                id,
+                                attempt,
+                                input: inputs[id].clone(),
+                            };
+                            let reply = send(&mut link.stream, &frame)
+                                .and_then(|_| receive(&mut link.reader));
+                            match reply {
+                                Ok(Frame::Done {
+                                    id: answered,
+                                    output,
+                                }) if answered == id => {
+                                    outcomes.lock().unwrap()[id] =
+                                        Some(Outcome::Done(output, attempt + 1));
+                                }
+                                Ok(Frame::Failed { id: answered, .. })
+                                    if answered == id && attempt < max_retries =>
+                                {
+                                    queue.lock().unwrap().push_back(Pending {
+                                        attempt: attempt + 1,
+                                        ..task
+                                    });
+                                }
+                                Ok(Frame::Failed {
+                                    id: answered,
+                                    message,
+                                }) if answered == id => {
+                                    outcomes.lock().unwrap()[id] =
+                                        Some(Outcome::Failed(message, attempt + 1));
+                                }
+                                _ if task.disconnects < max_retries => {
+                                    queue.lock().unwrap().push_front(Pending {
+                                        disconnects: task.disconnects + 1,
+                                        ..task
+                                    });
+                                    return true;
+                                }
+                                _ => {
+                                    outcomes.lock().unwrap()[id] = Some(Outcome::Failed(
+                                        format!("lost {} workers", task.disconnects + 1),
+                                        attempt + 1,
+                                    ));
+                                    return true;
+                                }
+                            }
+                        })
+                    })
+                    .collect();
+                handles
+                    .into_iter()
+                    .map(|h| h.join().unwrap_or(true))
+                    .collect()
+            });
+            let mut flags = lost.into_iter();
+            self.workers.retain(|_| !flags.next().unwrap_or(false));
+        }
+        Ok(outcomes.into_inner().unwrap())
+    }
+
+    pub fn map<I, O>(
+        &mut self,
+        inputs: &[I],
+    ) -> Result<Vec<BiologyResult<O>>, Box<dyn std::error::Error>>
+    where
+        I: Serialize,
+        O: DeserializeOwned,
+    {
+        let encoded = inputs
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut results = Vec::with_capacity(inputs.len());
+        for outcome in self.dispatch(encoded)? {
+            results.push(match outcome {
+                Some(Outcome::Done(value, _)) => Ok(serde_json::from_value(value)?),
+                Some(Outcome::Failed(message, _)) => {
+                    Err(crate::biology::BiologyError::InvalidState(message))
+                }
+                None => Err(crate::biology::BiologyError::InvalidState(
+                    "task was never completed".to_string(),
+                )),
+            });
+        }
+        Ok(results)
+    }
+
+    // Same subjects and seeds as the in-process runner, so the report is
+    // identical whether a study runs locally or across nodes.
+    pub fn run_cohort<T>(
+        &mut self,
+        config: &CohortConfig,
+    ) -> Result<CohortReport<T>, Box<dyn std::error::Error>>
+    where
+        T: DeserializeOwned,
+    {
+        let inputs: Vec<Value> = (0..config.subjects)
+            .map(|subject| serde_json::json!([subject, config.base_seed]))
+            .collect();
+        let saved_retries = self.max_retries;
+        self.max_retries = config.max_retries;
+        let outcomes = self.dispatch(inputs);
+        self.max_retries = saved_retries;
+
+        let mut records = Vec::new();
+        let mut failures = Vec::new();
+        for (subject, outcome) in outcomes?.into_iter().enumerate() {
+            match outcome {
+                Some(Outcome::Done(value, attempts)) => records.push(SubjectRecord {
+                    subject,
+                    seed: subject_seed(config.base_seed, subject, attempts - 1),
+                    attempts,
+                    result: serde_json::from_value(value)?,
+                }),
+                Some(Outcome::Failed(message, attempts)) => failures.push(SubjectFailure {
+                    subject,
+                    attempts,
+                    message,
+                }),
+                None => failures.push(SubjectFailure {
+                    subject,
+                    attempts: 0,
+                    message: "task was never completed".to_string(),
+                }),
+            }
+        }
+        Ok(CohortReport {
+            records,
+            failures,
+            resumed: 0,
+        })
+    }
+
+    pub fn shutdown(mut self) -> io::Result<()> {
+        for link in &mut self.workers {
+            send(&mut link.stream, &Frame::Shutdown)?;
+        }
+        Ok(())
+    }
+}
+
+// Worker loop: serves tasks until the coordinator sends Shutdown or closes
+// the connection. Returns the number of tasks handled. A handler that
+// panics fails its task, which the coordinator retries as it would an
+// error, rather than taking the worker down.
+pub fn run_worker<A, I, O, F>(addr: A, handler: F) -> io::Result<usize>
+where
+    A: ToSocketAddrs,
+    I: DeserializeOwned,
+    O: Serialize,
+    F: Fn(I, u32) -> BiologyResult<O>,
+{
+    let mut stream = TcpStream::connect(addr)?;
+    stream.set_nodelay(true)?;
+    // Reads stay blocking: a worker idles between batches for as long as
+    // the coordinator likes.
+    stream.set_write_timeout(Some(Duration::from_secs(60)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut served = 0;
+    loop {
+        let frame = match receive(&mut reader) {
+            Ok(frame) => frame,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(served),
+            Err(e) => return Err(e),
+        };
+        let reply = match frame {
+            Frame::Task { id, attempt, input } => {
+                served += 1;
+                let result = serde_json::from_value(input)
+                    .map_err(|e| e.to_string())
+                    .and_then(|input| {
+                        panic::catch_unwind(AssertUnwindSafe(|| handler(input, attempt)))
+                            .map_err(|_| "task panicked".to_string())?
+                            .map_err(|e| e.to_string())
+                    })
+                    .and_then(|output| serde_json::to_value(output).map_err(|e| e.to_string()));
+                match result {
+                    Ok(output) => Frame::Done { id, output },
+                    Err(message) => Frame::Failed { id, message },
+                }
+            }
+            Frame::Shutdown => return Ok(served),
+            _ => continue,
+        };
+        send(&mut stream, &reply)?;
+    }
+}
+
+pub fn run_cohort_worker<A, T, F>(addr: A, simulate: F) -> io::Result<usize>
+where
+    A: ToSocketAddrs,
+    T: Serialize,
+    F: Fn(usize, &mut StdRng) -> BiologyResult<T>,
+{
+    run_worker(addr, |(subject, base_seed): (usize, u64), attempt| {
+        let mut rng = StdRng::seed_from_u64(subject_seed(base_seed, subject, attempt));
+        simulate(subject, &mut rng)
+    })
+}
+
+// Contiguous, near-equal partition of a 1-D index space.
+pub fn partition_ranges(len: usize, parts: usize) -> Vec<Range<usize>> {
+    let parts = parts.clamp(1, len.max(1));
+    let (base, extra) = (len / parts, len % parts);
+    let mut start = 0;
+    (0..parts)
+        .map(|p| {
+            let end = start + base + usize::from(p < extra);
+            let range = start..end;
+            start = end;
+            range
+        })
+        .collect()
+}
+
+// A partition of a spatial field carrying ghost cells from its neighbours;
+// boundary slabs have narrower halos on the outer side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HaloSlab {
+    pub owned: Range<usize>,
+    pub left_halo: usize,
+    pub values: Vec<f64>,
+}
+
+impl HaloSlab {
+    pub fn owned_values(&self) -> &[f64] {
+        &self.values[self.left_halo..self.left_halo + self.owned.len()]
+    }
+}
+
+pub fn split_with_halo(field: &[f64], parts: usize, halo: usize) -> Vec<HaloSlab> {
+    partition_ranges(field.len(), parts)
+        .into_iter()
+        .map(|owned| {
+            let lo = owned.start.saturating_sub(halo);
+            let hi = (owned.end + halo).min(field.len());
+            HaloSlab {
+                left_halo: owned.start - lo,
+                values: field[lo..hi].to_vec(),
+                owned,
+            }
+        })
+        .collect()
+}
+
+pub fn stitch(len: usize, slabs: &[HaloSlab]) -> Vec<f64> {
+    let mut field = vec![0.0; len];
+    for slab in slabs {
+        field[slab.owned.clone()].copy_from_slice(slab.owned_values());
+    }
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::BiologyError;
+    use crate::simulation::cohort::CohortRunner;
+    use rand::Rng;
+
+    fn noisy_decay(_subject: usize, rng: &mut StdRng) -> BiologyResult<f64> {
+        let mut level = 100.0;
+        for _ in 0..50 {
+            level *= 1.0 - rng.gen_range(0.0..0.05);
+        }
+        Ok(level)
+    }
+
+    fn spawn_workers<F>(
+        coordinator: &mut Coordinator,
+        count: usize,
+        worker: F,
+    ) -> Vec<thread::JoinHandle<usize>>
+    where
+        F: Fn(std::net::SocketAddr) -> usize + Send + Sync + Clone + 'static,
+    {
+        let addr = coordinator.local_addr().unwrap();
+        let handles = (0..count)
+            .map(|_| {
+                let worker = worker.clone();
+                thread::spawn(move || worker(addr))
+            })
+            .collect();
+        coordinator.accept_workers(count).unwrap();
+        handles
+    }
+
+    #[test]
+    fn test_distributed_cohort_matches_local_run() {
+        let mut config = CohortConfig::new(30, 17);
+        config.worker_threads = 2;
+        let local = CohortRunner::new(config).unwrap().run(noisy_decay).unwrap();
+
+        for nodes in [1, 3] {
+            let mut coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+            let workers = spawn_workers(&mut coordinator, nodes, |addr| {
+                run_cohort_worker(addr, noisy_decay).unwrap()
+            });
+            let remote: CohortReport<f64> = coordinator.run_cohort(&config).unwrap();
+            coordinator.shutdown().unwrap();
+            let served: usize = workers.into_iter().map(|h| h.join().unwrap()).sum();
+            assert_eq!(served, 30);
+            assert_eq!(remote.records, local.records);
+        }
+    }
+
+    #[test]
+    fn test_failures_are_retried_on_remote_workers() {
+        let mut coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+        coordinator.max_retries = 1;
+        let workers = spawn_workers(&mut coordinator, 2, |addr| {
+            run_worker(addr, |x: i64, attempt| {
+                if x < 0 {
+                    Err(BiologyError::InvalidValue("negative".to_string()))
+                } else if x % 3 == 0 && attempt == 0 {
+                    Err(BiologyError::InvalidState("transient".to_string()))
+                } else {
+                    Ok(x * x)
+                }
+            })
+            .unwrap()
+        });
+        let results: Vec<BiologyResult<i64>> = coordinator.map(&[1, 3, -2, 6]).unwrap();
+        coordinator.shutdown().unwrap();
+        workers.into_iter().for_each(|h| {
+            h.join().unwrap();
+        });
+        assert_eq!(results[0], Ok(1));
+        assert_eq!(results[1], Ok(9));
+        assert!(results[2].is_err());
+        assert_eq!(results[3], Ok(36));
+    }
+
+    #[test]
+    fn test_panicking_task_is_retried() {
+        let mut coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+        coordinator.max_retries = 1;
+        let workers = spawn_workers(&mut coordinator, 1, |addr| {
+            run_worker(addr, |x: u32, attempt| {
+                if x == 2 && attempt == 0 {
+                    panic!("transient failure");
+                }
+                if x == 3 {
+                    panic!("persistent failure");
+                }
+                Ok(x * 10)
+            })
+            .unwrap()
+        });
+        let results: Vec<BiologyResult<u32>> = coordinator.map(&[1, 2, 3]).unwrap();
+        assert_eq!(coordinator.worker_count(), 1);
+        coordinator.shutdown().unwrap();
+        let served: usize = workers.into_iter().map(|h| h.join().unwrap()).sum();
+        assert_eq!(served, 5);
+        assert_eq!(results[0], Ok(10));
+        assert_eq!(results[1], Ok(20));
+        assert_eq!(
+            results[2],
+            Err(BiologyError::InvalidState("task panicked".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_reply_for_another_task_drops_the_worker() {
+        let mut coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+        // This peer answers every task under the wrong id.
+        let confused = spawn_workers(&mut coordinator, 1, |addr| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut answered = 0;
+            while let Ok(Frame::Task { id, input, .. }) = receive(&mut reader) {
+                let frame = Frame::Done {
+                    id: id + 1,
+                    output: input,
+                };
+                if send(&mut stream, &frame).is_err() {
+                    break;
+                }
+                answered += 1;
+            }
+            answered
+        });
+        let steady = spawn_workers(&mut coordinator, 1, |addr| {
+            run_worker(addr, |x: u32, _| Ok(x + 1)).unwrap()
+        });
+        let inputs: Vec<u32> = (0..10).collect();
+        let results: Vec<BiologyResult<u32>> = coordinator.map(&inputs).unwrap();
+        assert_eq!(coordinator.worker_count(), 1);
+        coordinator.shutdown().unwrap();
+        for handle in confused.into_iter().chain(steady) {
+            handle.join().unwrap();
+        }
+        assert!(results
+            .iter()
+            .enumerate()
+            .all(|(i, r)| *r == Ok(i as u32 + 1)));
+    }
+
+    #[test]
+    fn test_lost_worker_tasks_are_reassigned() {
+        let mut coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+        let addr = coordinator.local_addr().unwrap();
+        // This peer hangs up without answering its first task.
+        let dropout = thread::spawn(move || {
+            let stream = TcpStream::connect(addr).unwrap();
+            let mut reader = BufReader::new(stream);
+            let _ = receive(&mut reader);
+        });
+        coordinator.accept_workers(1).unwrap();
+        let steady = spawn_workers(&mut coordinator, 1, |addr| {
+            run_worker(addr, |x: u32, _| Ok(x + 1)).unwrap()
+        });
+        let inputs: Vec<u32> = (0..20).collect();
+        let results: Vec<BiologyResult<u32>> = coordinator.map(&inputs).unwrap();
+        assert_eq!(coordinator.worker_count(), 1);
+        coordinator.shutdown().unwrap();
+        dropout.join().unwrap();
+        steady.into_iter().for_each(|h| {
+            h.join().unwrap();
+        });
+        assert!(results
+            .iter()
+            .enumerate()
+            .all(|(i, r)| *r == Ok(i as u32 + 1)));
+    }
+
+    #[test]
+    fn test_task_that_keeps_losing_workers_is_failed() {
+        let mut coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+        coordinator.max_retries = 1;
+        coordinator.io_timeout = Some(Duration::from_millis(200));
+        // Peers that hang up on task 0 and answer everything else.
+        let fragile = spawn_workers(&mut coordinator, 3, |addr| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut served = 0;
+            while let Ok(Frame::Task { id, input, .. }) = receive(&mut reader) {
+                if input == 0 {
+                    break;
+                }
+                served += 1;
+                let output = Value::from(input.as_u64().unwrap() + 1);
+                send(&mut stream, &Frame::Done { id, output }).unwrap();
+            }
+            served
+        });
+        // This peer takes a task and never answers it.
+        let silent = spawn_workers(&mut coordinator, 1, |addr| {
+            let stream = TcpStream::connect(addr).unwrap();
+            let mut reader = BufReader::new(stream);
+            let _ = receive(&mut reader);
+            let _ = receive(&mut reader);
+            0
+        });
+        let inputs: Vec<u64> = (0..12).collect();
+        let results: Vec<BiologyResult<u64>> = coordinator.map(&inputs).unwrap();
+        assert!(results[0].is_err());
+        assert!(results[1..]
+            .iter()
+            .zip(2..)
+            .all(|(r, expected)| *r == Ok(expected)));
+        assert!(coordinator.worker_count() >= 1);
+        coordinator.shutdown().unwrap();
+        for handle in fragile.into_iter().chain(silent) {
+            handle.join().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_partitioned_diffusion_is_partition_independent() {
+        let n = 64;
+        let mut initial = vec![0.0; n];
+        initial[20] = 1.0;
+        initial[45] = 0.5;
+        let stencil = |slab: &HaloSlab| -> Vec<f64> {
+            let v = &slab.values;
+            (0..slab.owned.len())
+                .map(|k| {
+                    let i = slab.left_halo + k;
+                    let left = if i == 0 { v[i] } else { v[i - 1] };
+                    let right = if i + 1 == v.len() { v[i] } else { v[i + 1] };
+                    v[i] + 0.25 * (left - 2.0 * v[i] + right)
+                })
+                .collect()
+        };
+        let mut serial = initial.clone();
+        for _ in 0..40 {
+            let whole = split_with_halo(&serial, 1, 1);
+            serial = stencil(&whole[0]);
+        }
+
+        let mut coordinator = Coordinator::bind("127.0.0.1:0").unwrap();
+        let workers = spawn_workers(&mut coordinator, 3, move |addr| {
+            run_worker(addr, move |slab: HaloSlab, _| Ok(stencil(&slab))).unwrap()
+        });
+        let mut field = initial;
+        for _ in 0..40 {
+            let mut slabs = split_with_halo(&field, 5, 1);
+            let updated: Vec<BiologyResult<Vec<f64>>> = coordinator.map(&slabs).unwrap();
+            for (slab, values) in slabs.iter_mut().zip(updated) {
+                let values = values.unwrap();
+                let start = slab.left_halo;
+                slab.values[start..start + values.len()].copy_from_slice(&values);
+            }
+            field = stitch(n, &slabs);
+        }
+        coordinator.shutdown().unwrap();
+        workers.into_iter().for_each(|h| {
+            h.join().unwrap();
+        });
+        assert_eq!(field, serial);
+        assert_eq!(partition_ranges(10, 3), vec![0..4, 4..7, 7..10]);
+    }
+}
@@ -0,0 +1,1416 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::io::nonfinite;
+use crate::simulation::events::{Event, EventBus, EventKind, EventRecord};
+use crate::simulation::interaction::{InputEffect, InteractionContext, InteractionMatrix};
+use crate::simulation::recorder::{Recorder, TimeSeriesTable};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, BTreeSet};
+use std::thread;
+use tracing::{debug, debug_span, info, info_span, trace};
+
+// A time-stepped model with named inputs and outputs that the engine can
+// wire together. The engine clock runs in minutes; components substep
+// internally where their integrators need finer steps.
+pub trait Component: Any + Send {
+    fn kind(&self) -> &'static str;
+    // (name, unit) pairs.
+    fn inputs(&self) -> &'static [(&'static str, &'static str)];
+    fn outputs(&self) -> &'static [(&'static str, &'static str)];
+    fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()>;
+    // Whether `set_input` would accept the value, without applying it. The
+    // engine checks every input of a step before changing any entity, so a
+    // model whose `set_input` rejects values repeats those checks here.
+    fn check_input(&self, name: &str, _value: f64) -> BiologyResult<()> {
+        if self.inputs().iter().any(|(input, _)| *input == name) {
+            Ok(())
+        } else {
+            Err(unknown_input(self.kind(), name))
+        }
+    }
+    fn output(&self, name: &str) -> Option<f64>;
+    fn step(&mut self, dt_minutes: f64);
+    // Complete model state, for snapshots and remote checkpoints.
+    fn save_state(&self) -> Value;
+    fn load_state(&mut self, state: Value) -> BiologyResult<()>;
+    // Domain events raised since the last call; the engine drains these
+    // after every step and tags them with the entity name.
+    fn take_events(&mut self) -> Vec<Event> {
+        Vec::new()
+    }
+    // The step this model wants when it differs from the engine's: finer
+    // steps are subcycled within each engine step, coarser ones taken once
+    // every whole number of engine steps.
+    fn natural_dt_minutes(&self) -> Option<f64> {
+        None
+    }
+}
+
+// Non-finite values are spelled out so a diverged model still restores.
+pub(crate) fn save<T: Serialize>(model: &T) -> Value {
+    nonfinite::to_value(model).expect("model state is plain data")
+}
+
+pub(crate) fn load<T: DeserializeOwned>(model: &mut T, state: Value) -> BiologyResult<()> {
+    *model = nonfinite::from_value(state)
+        .map_err(|e| BiologyError::InvalidValue(format!("unreadable model state: {}", e)))?;
+    Ok(())
+}
+
+pub(crate) fn substep(dt: f64, max_step: f64, mut step: impl FnMut(f64)) {
+    let n = (dt / max_step).ceil().max(1.0) as usize;
+    for _ in 0..n {
+        step(dt / n as f64);
+    }
+}
+
+pub(crate) fn unknown_input(kind: &str, name: &str) -> BiologyError {
+    BiologyError::InvalidParameter(format!("{} has no input {}", kind, name))
+}
+
+// `entity.port` addressing of a component input or output.
+pub fn split_path(path: &str) -> BiologyResult<(&str, &str)> {
+    path.split_once('.')
+        .filter(|(entity, port)| !entity.is_empty() && !port.is_empty())
+        .ok_or_else(|| {
+            BiologyError::InvalidParameter(format!("{} is not of the form entity.port", path))
+        })
+}
+
+// Before every step the target input is set to `offset + gain * source`.
+// A conserved coupling carries a rate (an amount per minute) whose time
+// integral must arrive intact: a target that steps less often than the
+// engine receives its mean over the window, and a subcycled target holds
+// it rather than interpolating.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Coupling {
+    pub from: String,
+    pub to: String,
+    #[serde(default = "unit_gain")]
+    pub gain: f64,
+    #[serde(default)]
+    pub offset: f64,
+    #[serde(default)]
+    pub conserved: bool,
+}
+
+fn unit_gain() -> f64 {
+    1.0
+}
+
+impl Coupling {
+    pub fn new(from: &str, to: &str) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            gain: 1.0,
+            offset: 0.0,
+            conserved: false,
+        }
+    }
+
+    pub fn with_gain(mut self, gain: f64) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn conserved(mut self) -> Self {
+        self.conserved = true;
+        self
+    }
+}
+
+// How an entity advances within one engine step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Schedule {
+    EveryStep,
+    // `n` equal substeps.
+    Subcycle(usize),
+    // One step of `k` engine steps' length, at the end of every `k`th.
+    Every(usize),
+}
+
+// Per driven input: the value last applied, the start of the ramp across
+// the next step's substeps, and the integral awaiting an entity that steps
+// less often than the engine. Carried in snapshots so a restored engine
+// picks up mid-window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputExchange {
+    last: Option<f64>,
+    window_sum: f64,
+}
+
+// Time integral of a conserved input, in its unit × minutes: what the
+// couplings produced, what the target has integrated, and what is held for
+// a target that has not stepped yet. `residual` is zero when nothing was
+// lost at the rate boundary.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExchangeBalance {
+    pub sent: f64,
+    pub delivered: f64,
+    pub pending: f64,
+}
+
+impl ExchangeBalance {
+    pub fn residual(&self) -> f64 {
+        self.sent - self.delivered - self.pending
+    }
+}
+
+// Inputs driven this step. A coupled input accumulates in its coupling's
+// slot; one only events or interactions drive is collected by path.
+struct Driven {
+    slots: Vec<Option<f64>>,
+    others: BTreeMap<String, Option<f64>>,
+}
+
+impl Driven {
+    fn add(total: &mut Option<f64>, contribution: f64) {
+        *total = Some(total.map_or(contribution, |t| t + contribution));
+    }
+
+    fn slot(&mut self, slot: usize, contribution: f64) {
+        Self::add(&mut self.slots[slot], contribution);
+    }
+
+    fn path(&mut self, routes: &BTreeMap<String, usize>, path: String, contribution: f64) {
+        match routes.get(&path) {
+            Some(&slot) => self.slot(slot, contribution),
+            None => Self::add(self.others.entry(path).or_default(), contribution),
+        }
+    }
+}
+
+// Below this many entities per worker, spawning threads costs more than
+// the update saves.
+const MIN_ENTITIES_PER_WORKER: usize = 64;
+
+// Substeps one entity takes this engine step, with the inputs ramped
+// linearly across them.
+struct Plan {
+    steps: usize,
+    h: f64,
+    ramps: Vec<(String, f64, f64)>,
+}
+
+fn advance(component: &mut dyn Component, plan: &Plan) -> BiologyResult<()> {
+    for i in 0..plan.steps {
+        let fraction = (i + 1) as f64 / plan.steps as f64;
+        for (port, from, to) in &plan.ramps {
+            component.set_input(port, from + (to - from) * fraction)?;
+        }
+        component.step(plan.h);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    pub kind: String,
+    pub state: Value,
+}
+
+// Clock, model states and the exchange windows and balances in flight;
+// couplings are configuration and not included.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineSnapshot {
+    pub time_minutes: f64,
+    pub entities: BTreeMap<String, EntitySnapshot>,
+    #[serde(default)]
+    pub exchanges: BTreeMap<String, InputExchange>,
+    #[serde(default)]
+    pub balances: BTreeMap<String, ExchangeBalance>,
+}
+
+// Named components advanced together on a fixed step, exchanging values
+// through couplings, registered pairwise interactions and events. Each
+// step has two phases: a serial exchange phase that applies effects queued
+// by event subscribers, then evaluates every coupling and every
+// interaction between ordered entity pairs against the state at the start
+// of the step; then an update phase in which components advance
+// independently, spread over up to `worker_threads` (each given at least
+// `MIN_ENTITIES_PER_WORKER`). Events raised during the update are
+// published once it completes. Results do not depend on the thread count.
+//
+// Entities with a natural step of their own run at their own rate. One
+// finer than the engine's is subcycled, with coupled inputs ramped
+// linearly from the previous exchange to this one; one coarser steps once
+// per window of whole engine steps and holds its outputs in between.
+// Conserved couplings are tracked in `exchange_balances`.
+pub struct Engine {
+    pub dt_minutes: f64,
+    pub time_minutes: f64,
+    pub worker_threads: usize,
+    entities: BTreeMap<String, Box<dyn Component>>,
+    // Entity names by concrete component type, for the interaction phase.
+    by_type: BTreeMap<TypeId, Vec<String>>,
+    couplings: Vec<Coupling>,
+    // Driven input path to the index of the coupling that drives it.
+    routes: BTreeMap<String, usize>,
+    interactions: InteractionMatrix,
+    events: EventBus,
+    natural_dt: BTreeMap<String, f64>,
+    exchanges: BTreeMap<String, InputExchange>,
+    balances: BTreeMap<String, ExchangeBalance>,
+}
+
+impl Engine {
+    pub fn new(dt_minutes: f64) -> BiologyResult<Self> {
+        if !(dt_minutes > 0.0 && dt_minutes.is_finite()) {
+            return Err(BiologyError::InvalidParameter(
+                "time step must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            dt_minutes,
+            time_minutes: 0.0,
+            worker_threads: 1,
+            entities: BTreeMap::new(),
+            by_type: BTreeMap::new(),
+            couplings: Vec::new(),
+            routes: BTreeMap::new(),
+            interactions: InteractionMatrix::new(),
+            events: EventBus::new(),
+            natural_dt: BTreeMap::new(),
+            exchanges: BTreeMap::new(),
+            balances: BTreeMap::new(),
+        })
+    }
+
+    pub fn with_worker_threads(mut self, threads: usize) -> Self {
+        self.worker_threads = threads.max(1);
+        self
+    }
+
+    pub fn with_interactions(mut self, interactions: InteractionMatrix) -> Self {
+        self.interactions = interactions;
+        self
+    }
+
+    pub fn interactions_mut(&mut self) -> &mut InteractionMatrix {
+        &mut self.interactions
+    }
+
+    pub fn add_entity(&mut self, name: &str, component: Box<dyn Component>) -> BiologyResult<()> {
+        if name.is_empty() || name.contains('.') {
+            return Err(BiologyError::InvalidParameter(format!(
+                "entity name '{}' must be non-empty without '.'",
+                name
+            )));
+        }
+        if self.entities.contains_key(name) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "entity {} defined twice",
+                name
+            )));
+        }
+        debug!(entity = name, kind = component.kind(), "entity added");
+        let any: &dyn Any = component.as_ref();
+        self.by_type
+            .entry(any.type_id())
+            .or_default()
+            .push(name.to_string());
+        self.entities.insert(name.to_string(), component);
+        Ok(())
+    }
+
+    pub fn entity(&self, name: &str) -> Option<&dyn Component> {
+        self.entities.get(name).map(|c| c.as_ref())
+    }
+
+    pub fn entity_names(&self) -> impl Iterator<Item = &str> {
+        self.entities.keys().map(String::as_str)
+    }
+
+    fn component(&self, entity: &str) -> BiologyResult<&dyn Component> {
+        self.entity(entity)
+            .ok_or_else(|| BiologyError::InvalidParameter(format!("no entity named {}", entity)))
+    }
+
+    // Unit of an output, checking that it exists.
+    pub fn output_unit(&self, path: &str) -> BiologyResult<&'static str> {
+        let (entity, port) = split_path(path)?;
+        let component = self.component(entity)?;
+        component
+            .outputs()
+            .iter()
+            .find(|(name, _)| *name == port)
+            .map(|(_, unit)| *unit)
+            .ok_or_else(|| {
+                BiologyError::InvalidParameter(format!(
+                    "{} ({}) has no output {}",
+                    entity,
+                    component.kind(),
+                    port
+                ))
+            })
+    }
+
+    pub fn input_unit(&self, path: &str) -> BiologyResult<&'static str> {
+        let (entity, port) = split_path(path)?;
+        let component = self.component(entity)?;
+        component
+            .inputs()
+            .iter()
+            .find(|(name, _)| *name == port)
+            .map(|(_, unit)| *unit)
+            .ok_or_else(|| {
+                BiologyError::InvalidParameter(format!(
+                    "{} ({}) has no input {}",
+                    entity,
+                    component.kind(),
+                    port
+                ))
+            })
+    }
+
+    pub fn value(&self, path: &str) -> BiologyResult<f64> {
+        self.output_unit(path)?;
+        let (entity, port) = split_path(path)?;
+        Ok(self.entities[entity].output(port).unwrap_or(f64::NAN))
+    }
+
+    pub fn set_input(&mut self, path: &str, value: f64) -> BiologyResult<()> {
+        self.input_unit(path)?;
+        let (entity, port) = split_path(path)?;
+        self.entities
+            .get_mut(entity)
+            .expect("checked above")
+            .set_input(port, value)
+    }
+
+    pub fn couple(&mut self, coupling: Coupling) -> BiologyResult<()> {
+        self.output_unit(&coupling.from)?;
+        self.input_unit(&coupling.to)?;
+        if self.routes.contains_key(&coupling.to) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{} is already driven by another coupling",
+                coupling.to
+            )));
+        }
+        self.routes
+            .insert(coupling.to.clone(), self.couplings.len());
+        self.couplings.push(coupling);
+        Ok(())
+    }
+
+    pub fn couplings(&self) -> &[Coupling] {
+        &self.couplings
+    }
+
+    // Overrides the entity's own `natural_dt_minutes`.
+    pub fn set_natural_dt(&mut self, entity: &str, dt_minutes: f64) -> BiologyResult<()> {
+        self.component(entity)?;
+        if !(dt_minutes > 0.0 && dt_minutes.is_finite()) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "natural step of {} must be positive",
+                entity
+            )));
+        }
+        self.natural_dt.insert(entity.to_string(), dt_minutes);
+        Ok(())
+    }
+
+    pub fn natural_dt(&self, entity: &str) -> Option<f64> {
+        self.natural_dt
+            .get(entity)
+            .copied()
+            .or_else(|| self.entities.get(entity)?.natural_dt_minutes())
+    }
+
+    // A coarser natural step is rounded to whole engine steps.
+    fn schedule(&self, entity: &str) -> Schedule {
+        let Some(natural) = self.natural_dt(entity) else {
+            return Schedule::EveryStep;
+        };
+        let substeps = (self.dt_minutes / natural * (1.0 - 1e-9)).ceil() as usize;
+        let window = (natural / self.dt_minutes).round() as usize;
+        if substeps > 1 {
+            Schedule::Subcycle(substeps)
+        } else if window > 1 {
+            Schedule::Every(window)
+        } else {
+            Schedule::EveryStep
+        }
+    }
+
+    pub fn exchange_balances(&self) -> &BTreeMap<String, ExchangeBalance> {
+        &self.balances
+    }
+
+    // Fails if any conserved input's integral has drifted by more than
+    // `tolerance` relative to what was sent.
+    pub fn check_conservation(&self, tolerance: f64) -> BiologyResult<()> {
+        for (path, balance) in &self.balances {
+            if balance.residual().abs() > tolerance * balance.sent.abs().max(1e-12) {
+                return Err(BiologyError::InvalidState(format!(
+                    "{}: sent {} but delivered {} with {} pending",
+                    path, balance.sent, balance.delivered, balance.pending
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    // Runs `handler` for every `kind` event; its effects are applied to
+    // the inputs of `target` at the start of the next step.
+    pub fn subscribe(
+        &mut self,
+        kind: EventKind,
+        target: &str,
+        handler: impl FnMut(&EventRecord) -> Vec<InputEffect> + Send + 'static,
+    ) -> BiologyResult<()> {
+        self.component(target)?;
+        self.events.subscribe(kind, target, handler);
+        Ok(())
+    }
+
+    pub fn watch_changes(&mut self, path: &str, min_delta: f64) -> BiologyResult<()> {
+        self.output_unit(path)?;
+        if min_delta.is_nan() || min_delta < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "change watch delta must be non-negative".to_string(),
+            ));
+        }
+        self.events.watch_changes(path, min_delta);
+        Ok(())
+    }
+
+    pub fn watch_threshold(&mut self, path: &str, threshold: f64) -> BiologyResult<()> {
+        self.output_unit(path)?;
+        self.events.watch_threshold(path, threshold);
+        Ok(())
+    }
+
+    pub fn events(&self) -> &EventBus {
+        &self.events
+    }
+
+    pub fn snapshot(&self) -> EngineSnapshot {
+        EngineSnapshot {
+            time_minutes: self.time_minutes,
+            entities: self
+                .entities
+                .iter()
+                .map(|(name, component)| {
+                    let snapshot = EntitySnapshot {
+                        kind: component.kind().to_string(),
+                        state: component.save_state(),
+                    };
+                    (name.clone(), snapshot)
+                })
+                .collect(),
+            exchanges: self.exchanges.clone(),
+            balances: self.balances.clone(),
+        }
+    }
+
+    // Restores into an engine with the same entities, e.g. one rebuilt from
+    // the scenario the snapshot was taken from.
+    pub fn restore(&mut self, snapshot: &EngineSnapshot) -> BiologyResult<()> {
+        let names: Vec<&String> = snapshot.entities.keys().collect();
+        if names != self.entities.keys().collect::<Vec<_>>() {
+            return Err(BiologyError::InvalidState(format!(
+                "snapshot entities {:?} do not match this engine",
+                names
+            )));
+        }
+        for (name, saved) in &snapshot.entities {
+            let kind = self.entities[name].kind();
+            if saved.kind != kind {
+                return Err(BiologyError::InvalidState(format!(
+                    "snapshot entity {} is a {}, not a {}",
+                    name, saved.kind, kind
+                )));
+            }
+        }
+        for path in snapshot.exchanges.keys().chain(snapshot.balances.keys()) {
+            self.input_unit(path)?;
+        }
+        for (name, saved) in &snapshot.entities {
+            let component = self.entities.get_mut(name).expect("names checked");
+            component.load_state(saved.state.clone())?;
+        }
+        self.time_minutes = snapshot.time_minutes;
+        self.exchanges = snapshot.exchanges.clone();
+        self.balances = snapshot.balances.clone();
+        Ok(())
+    }
+
+    pub fn step(&mut self) -> BiologyResult<()> {
+        let _span = debug_span!("step", t_min = self.time_minutes).entered();
+        // Exchange: a fixed-order reduction, so sums are bit-identical
+        // from run to run.
+        let mut driven = Driven {
+            slots: vec![None; self.couplings.len()],
+            others: BTreeMap::new(),
+        };
+        for (path, value) in self.events.pending() {
+            driven.path(&self.routes, path.clone(), *value);
+        }
+        for (slot, c) in self.couplings.iter().enumerate() {
+            driven.slot(slot, c.offset + c.gain * self.value(&c.from)?);
+        }
+        if !self.interactions.is_empty() {
+            let context = InteractionContext {
+                time_minutes: self.time_minutes,
+                dt_minutes: self.dt_minutes,
+            };
+            // Only entities of registered type pairs are visited, in name
+            // order so the reduction stays fixed.
+            let mut pairs: Vec<(&String, &String)> = Vec::new();
+            for (source_type, target_type) in self.interactions.type_pairs() {
+                let (Some(sources), Some(targets)) = (
+                    self.by_type.get(&source_type),
+                    self.by_type.get(&target_type),
+                ) else {
+                    continue;
+                };
+                for source in sources {
+                    pairs.extend(targets.iter().filter(|t| *t != source).map(|t| (source, t)));
+                }
+            }
+            pairs.sort_unstable();
+            for (source_name, target_name) in pairs {
+                let effects = self
+                    .interactions
+                    .resolve(
+                        self.entities[source_name].as_ref(),
+                        self.entities[target_name].as_ref(),
+                        &context,
+                    )
+                    .unwrap_or_default();
+                for effect in effects {
+                    let path = format!("{}.{}", target_name, effect.input);
+                    driven.path(&self.routes, path, effect.value);
+                }
+            }
+        }
+        for path in driven.others.keys() {
+            self.input_unit(path)?;
+        }
+        let driven: Vec<(String, bool, f64)> = self
+            .couplings
+            .iter()
+            .zip(driven.slots)
+            .filter_map(|(c, value)| Some((c.to.clone(), c.conserved, value?)))
+            .chain(
+                driven
+                    .others
+                    .into_iter()
+                    .filter_map(|(path, value)| Some((path, false, value?))),
+            )
+            .collect();
+        trace!(inputs = driven.len(), "exchange phase applied");
+        let dt = self.dt_minutes;
+        let index = (self.time_minutes / dt).round() as usize;
+        let schedules: BTreeMap<String, Schedule> = self
+            .entities
+            .keys()
+            .map(|name| (name.clone(), self.schedule(name)))
+            .collect();
+        let due = |schedule: Schedule| match schedule {
+            Schedule::Every(k) => (index + 1).is_multiple_of(k),
+            _ => true,
+        };
+        // Exchanges and balances are staged and only written back once
+        // every entity has stepped, so a rejected input leaves the engine
+        // as it was before the step.
+        let mut exchanges: BTreeMap<String, InputExchange> = BTreeMap::new();
+        let mut balances: BTreeMap<String, ExchangeBalance> = BTreeMap::new();
+        let mut inputs: Vec<(String, f64)> = Vec::new();
+        let mut ramps: BTreeMap<String, Vec<(String, f64, f64)>> = BTreeMap::new();
+        for (path, conserved, value) in driven {
+            let (entity, port) = split_path(&path)?;
+            let schedule = schedules[entity];
+            let exchange = exchanges
+                .entry(path.clone())
+                .or_insert_with(|| self.exchanges.get(&path).copied().unwrap_or_default());
+            let previous = exchange.last.replace(value).unwrap_or(value);
+            if !conserved {
+                match schedule {
+                    Schedule::Subcycle(_) => ramps.entry(entity.to_string()).or_default().push((
+                        port.to_string(),
+                        previous,
+                        value,
+                    )),
+                    _ => inputs.push((path, value)),
+                }
+                continue;
+            }
+            let balance = balances
+                .entry(path.clone())
+                .or_insert_with(|| self.balances.get(&path).copied().unwrap_or_default());
+            balance.sent += value * dt;
+            match schedule {
+                Schedule::Every(_) => {
+                    exchange.window_sum += value * dt;
+                    balance.pending = exchange.window_sum;
+                }
+                _ => {
+                    balance.delivered += value * dt;
+                    inputs.push((path, value));
+                }
+            }
+        }
+        // Entities closing a window receive the mean of each conserved
+        // input over it, zero included, so a source that stops is not
+        // held at its last mean.
+        let conserved: BTreeSet<String> = self
+            .balances
+            .keys()
+            .chain(balances.keys())
+            .cloned()
+            .collect();
+        for path in conserved {
+            let (entity, _) = split_path(&path)?;
+            let Schedule::Every(k) = schedules[entity] else {
+                continue;
+            };
+            if !due(Schedule::Every(k)) {
+                continue;
+            }
+            let exchange = exchanges
+                .entry(path.clone())
+                .or_insert_with(|| self.exchanges.get(&path).copied().unwrap_or_default());
+            let balance = balances
+                .entry(path.clone())
+                .or_insert_with(|| self.balances[&path]);
+            inputs.push((path, exchange.window_sum / (k as f64 * dt)));
+            balance.delivered += exchange.window_sum;
+            balance.pending = 0.0;
+            exchange.window_sum = 0.0;
+        }
+        // A ramp runs between values the input has accepted, so checking
+        // where it ends covers every point on it.
+        for (path, value) in &inputs {
+            let (entity, port) = split_path(path)?;
+            self.entities[entity].check_input(port, *value)?;
+        }
+        for (entity, ramps) in &ramps {
+            for (port, _, to) in ramps {
+                self.entities[entity].check_input(port, *to)?;
+            }
+        }
+        self.events.take_pending();
+        for (path, value) in inputs {
+            self.set_input(&path, value)?;
+        }
+
+        // Update: contiguous blocks of entities per worker.
+        let mut plans: Vec<(&mut Box<dyn Component>, Plan)> = self
+            .entities
+            .iter_mut()
+            .map(|(name, component)| {
+                let schedule = schedules[name];
+                let (steps, h) = match schedule {
+                    Schedule::EveryStep => (1, dt),
+                    Schedule::Subcycle(n) => (n, dt / n as f64),
+                    Schedule::Every(k) if due(schedule) => (1, k as f64 * dt),
+                    Schedule::Every(_) => (0, 0.0),
+                };
+                let ramps = ramps.remove(name).unwrap_or_default();
+                (component, Plan { steps, h, ramps })
+            })
+            .collect();
+        let threads = self
+            .worker_threads
+            .min(plans.len() / MIN_ENTITIES_PER_WORKER)
+            .max(1);
+        let results: Vec<BiologyResult<()>> = if threads <= 1 {
+            plans
+                .iter_mut()
+                .map(|(c, plan)| advance(c.as_mut(), plan))
+                .collect()
+        } else {
+            let block = plans.len().div_ceil(threads);
+            thread::scope(|scope| {
+                let workers: Vec<_> = plans
+                    .chunks_mut(block)
+                    .map(|chunk| {
+                        scope.spawn(move || {
+                            chunk
+                                .iter_mut()
+                                .map(|(c, plan)| advance(c.as_mut(), plan))
+                                .collect::<Vec<_>>()
+                        })
+                    })
+                    .collect();
+                workers
+                    .into_iter()
+                    .flat_map(|w| w.join().expect("component step panicked"))
+                    .collect()
+            })
+        };
+        results.into_iter().collect::<BiologyResult<()>>()?;
+        self.exchanges.extend(exchanges);
+        self.balances.extend(balances);
+        self.time_minutes += self.dt_minutes;
+
+        let mut raised = Vec::new();
+        for (name, component) in &mut self.entities {
+            raised.extend(
+                component
+                    .take_events()
+                    .into_iter()
+                    .map(|event| EventRecord {
+                        time_minutes: self.time_minutes,
+                        entity: name.clone(),
+                        event,
+                    }),
+            );
+        }
+        let entities = &self.entities;
+        raised.extend(self.events.observe(self.time_minutes, |path| {
+            let (entity, port) = path.split_once('.').expect("validated when watched");
+            entities[entity].output(port).unwrap_or(f64::NAN)
+        }));
+        self.events.publish(raised);
+        Ok(())
+    }
+
+    // Steps until `duration_minutes` have elapsed, sampling into the
+    // recorder after the initial state and after every step.
+    pub fn run(
+        &mut self,
+        duration_minutes: f64,
+        mut recorder: Recorder<Engine>,
+    ) -> Result<TimeSeriesTable, Box<dyn std::error::Error>> {
+        let _span = info_span!(
+            "run",
+            duration_min = duration_minutes,
+            dt_min = self.dt_minutes,
+            entities = self.entities.len()
+        )
+        .entered();
+        let end = self.time_minutes + duration_minutes;
+        recorder.observe(self.time_minutes, self)?;
+        let mut steps = 0usize;
+        while self.time_minutes + 1e-9 * self.dt_minutes < end {
+            self.step()?;
+            recorder.observe(self.time_minutes, self)?;
+            steps += 1;
+        }
+        info!(steps, t_min = self.time_minutes, "run finished");
+        Ok(recorder.finish()?)
+    }
+
+    // A recorder probe for an output path, with its unit; checked now so
+    // the probe itself cannot fail.
+    pub fn probe(&self, path: &str) -> BiologyResult<(&'static str, impl Fn(&Engine) -> f64)> {
+        let unit = self.output_unit(path)?;
+        let path = path.to_string();
+        Ok((unit, move |engine: &Engine| {
+            engine.value(&path).unwrap_or(f64::NAN)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity::ActivitySchedule;
+    use crate::biology::physiology::thermoregulation::CORE_SET_POINT_C;
+    use crate::biology::physiology::Thermoregulation;
+    use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+    use crate::systems::cardiovascular::IronHomeostasis;
+    use crate::systems::nervous::{CircadianClock, SleepWakeRegulation};
+    use crate::systems::respiratory::RespiratoryControl;
+
+    fn engine() -> Engine {
+        let mut engine = Engine::new(1.0).unwrap();
+        engine
+            .add_entity("body", Box::new(Thermoregulation::new_adult()))
+            .unwrap();
+        engine
+            .add_entity("lungs", Box::new(RespiratoryControl::new_healthy()))
+            .unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_exercise_heat_drives_ventilation_through_coupling() {
+        // Whole-body VO2 ~2.98 mL/min per watt of metabolic heat
+        // (4.8 kcal/L O2), VCO2 at RQ 0.8 ~2.4 mL/min per watt.
+        let mut coupled = engine();
+        coupled
+            .couple(Coupling::new("body.metabolic_w", "lungs.co2_production_ml_min").with_gain(2.4))
+            .unwrap();
+        let mut resting = engine();
+        coupled.set_input("body.activity_w", 400.0).unwrap();
+        resting.set_input("body.activity_w", 400.0).unwrap();
+        for _ in 0..10 {
+            coupled.step().unwrap();
+            resting.step().unwrap();
+        }
+        assert!((coupled.time_minutes - 10.0).abs() < 1e-9);
+        assert!(
+            coupled.value("lungs.minute_ventilation_l_min").unwrap()
+                > 1.5 * resting.value("lungs.minute_ventilation_l_min").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_interaction_matrix_drives_ventilation_without_coupling() {
+        let mut interacting = engine().with_interactions(InteractionMatrix::physiological());
+        let mut resting = engine();
+        interacting.set_input("body.activity_w", 400.0).unwrap();
+        resting.set_input("body.activity_w", 400.0).unwrap();
+        for _ in 0..10 {
+            interacting.step().unwrap();
+            resting.step().unwrap();
+        }
+        assert!(
+            interacting.value("lungs.minute_ventilation_l_min").unwrap()
+                > 1.5 * resting.value("lungs.minute_ventilation_l_min").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_interactions_reach_every_registered_pair_of_entities() {
+        let mut engine = Engine::new(1.0)
+            .unwrap()
+            .with_interactions(InteractionMatrix::physiological());
+        for name in ["body_a", "body_b"] {
+            let mut body = Thermoregulation::new_adult();
+            body.set_activity_w(400.0).unwrap();
+            engine.add_entity(name, Box::new(body)).unwrap();
+        }
+        for name in ["lungs_a", "lungs_b"] {
+            engine
+                .add_entity(name, Box::new(RespiratoryControl::new_healthy()))
+                .unwrap();
+        }
+        engine
+            .add_entity("pool", Box::new(Pool::default()))
+            .unwrap();
+        engine.step().unwrap();
+        // Each lung receives the CO2 output of both bodies.
+        let metabolic = |name: &str| engine.value(&format!("{}.metabolic_w", name)).unwrap();
+        let lungs: &dyn Any = engine.entity("lungs_b").unwrap();
+        let lungs = lungs.downcast_ref::<RespiratoryControl>().unwrap();
+        let expected = (metabolic("body_a") + metabolic("body_b")) * 60.0 / 20.1 * 0.8;
+        assert!((lungs.co2_production_ml_min - expected).abs() < 1e-6 * expected);
+    }
+
+    // Loads a bone until it cracks once.
+    #[derive(Serialize, Deserialize)]
+    struct LoadedBone {
+        strain: f64,
+        cracked: bool,
+        #[serde(skip)]
+        raised: Vec<Event>,
+    }
+
+    impl Component for LoadedBone {
+        fn kind(&self) -> &'static str {
+            "loaded_bone"
+        }
+
+        fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+            &[]
+        }
+
+        fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+            &[("strain", "1")]
+        }
+
+        fn set_input(&mut self, name: &str, _: f64) -> BiologyResult<()> {
+            Err(unknown_input(self.kind(), name))
+        }
+
+        fn output(&self, name: &str) -> Option<f64> {
+            (name == "strain").then_some(self.strain)
+        }
+
+        fn step(&mut self, dt_minutes: f64) {
+            self.strain += 0.004 * dt_minutes;
+            if self.strain > 0.01 && !self.cracked {
+                self.cracked = true;
+                self.raised.push(Event::Fracture {
+                    site: "midshaft".to_string(),
+                    severity: 0.5,
+                });
+            }
+        }
+
+        fn save_state(&self) -> Value {
+            save(self)
+        }
+
+        fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+            load(self, state)
+        }
+
+        fn take_events(&mut self) -> Vec<Event> {
+            std::mem::take(&mut self.raised)
+        }
+    }
+
+    // Integrates an inflow and logs each step it is given.
+    #[derive(Default, Serialize, Deserialize)]
+    struct Pool {
+        inflow: f64,
+        amount: f64,
+        steps: Vec<(f64, f64)>,
+    }
+
+    impl Component for Pool {
+        fn kind(&self) -> &'static str {
+            "pool"
+        }
+
+        fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+            &[("inflow", "1/min")]
+        }
+
+        fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+            &[("amount", "1"), ("clock", "min")]
+        }
+
+        fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()> {
+            match name {
+                "inflow" => {
+                    self.inflow = value;
+                    Ok(())
+                }
+                _ => Err(unknown_input(self.kind(), name)),
+            }
+        }
+
+        fn output(&self, name: &str) -> Option<f64> {
+            match name {
+                "amount" => Some(self.amount),
+                "clock" => Some(self.steps.iter().map(|(h, _)| h).sum()),
+                _ => None,
+            }
+        }
+
+        fn step(&mut self, dt_minutes: f64) {
+            self.amount += self.inflow * dt_minutes;
+            self.steps.push((dt_minutes, self.inflow));
+        }
+
+        fn save_state(&self) -> Value {
+            save(self)
+        }
+
+        fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+            load(self, state)
+        }
+    }
+
+    fn pool<'a>(engine: &'a Engine, name: &str) -> &'a Pool {
+        let pool: &dyn Any = engine.entity(name).unwrap();
+        pool.downcast_ref::<Pool>().unwrap()
+    }
+
+    #[test]
+    fn test_fine_entity_is_subcycled_with_ramped_inputs() {
+        let mut engine = Engine::new(1.0).unwrap();
+        engine
+            .add_entity("source", Box::new(Pool::default()))
+            .unwrap();
+        engine
+            .add_entity("fast", Box::new(Pool::default()))
+            .unwrap();
+        engine.set_natural_dt("fast", 0.25).unwrap();
+        engine
+            .couple(Coupling::new("source.clock", "fast.inflow"))
+            .unwrap();
+        engine.set_input("source.inflow", 0.0).unwrap();
+        for _ in 0..3 {
+            engine.step().unwrap();
+        }
+        let fast = pool(&engine, "fast");
+        assert_eq!(fast.steps.len(), 12);
+        assert!(fast.steps.iter().all(|(h, _)| (h - 0.25).abs() < 1e-12));
+        // The source clock reads 0, 1, 2 before each step; the second step
+        // ramps from 0 to 1 across its substeps.
+        let ramp: Vec<f64> = fast.steps[4..8].iter().map(|(_, v)| *v).collect();
+        assert_eq!(ramp, vec![0.25, 0.5, 0.75, 1.0]);
+        assert!(engine.set_natural_dt("fast", 0.0).is_err());
+        assert!(engine.set_natural_dt("missing", 1.0).is_err());
+    }
+
+    #[test]
+    fn test_slow_entity_receives_conserved_window_mean() {
+        let mut engine = Engine::new(1.0).unwrap();
+        engine
+            .add_entity("source", Box::new(Pool::default()))
+            .unwrap();
+        engine
+            .add_entity("slow", Box::new(Pool::default()))
+            .unwrap();
+        engine.set_natural_dt("slow", 3.0).unwrap();
+        engine
+            .couple(Coupling::new("source.clock", "slow.inflow").conserved())
+            .unwrap();
+        engine.step().unwrap();
+        engine.step().unwrap();
+        let balance = engine.exchange_balances()["slow.inflow"];
+        assert!(balance.pending > 0.0 && balance.delivered == 0.0);
+        assert!(pool(&engine, "slow").steps.is_empty());
+        for _ in 0..4 {
+            engine.step().unwrap();
+        }
+        // Clock 0..5 sent over six one-minute steps, in two windows.
+        let slow = pool(&engine, "slow");
+        assert_eq!(slow.steps, vec![(3.0, 1.0), (3.0, 4.0)]);
+        assert!((slow.amount - 15.0).abs() < 1e-12);
+        engine.check_conservation(1e-12).unwrap();
+    }
+
+    #[test]
+    fn test_window_closes_when_source_drops_to_zero() {
+        let mut engine = Engine::new(1.0).unwrap();
+        engine
+            .add_entity("source", Box::new(Pool::default()))
+            .unwrap();
+        engine
+            .add_entity("slow", Box::new(Pool::default()))
+            .unwrap();
+        engine.set_natural_dt("slow", 3.0).unwrap();
+        engine
+            .couple(Coupling::new("source.amount", "slow.inflow").conserved())
+            .unwrap();
+        // The source amount reads 0, 1, 2, then 3, 2, 1, then stays at 0.
+        for inflow in [1.0, -1.0, 0.0] {
+            engine.set_input("source.inflow", inflow).unwrap();
+            for _ in 0..3 {
+                engine.step().unwrap();
+            }
+        }
+        let slow = pool(&engine, "slow");
+        assert_eq!(slow.steps, vec![(3.0, 1.0), (3.0, 2.0), (3.0, 0.0)]);
+        let balance = engine.exchange_balances()["slow.inflow"];
+        assert_eq!(balance.delivered, balance.sent);
+        assert_eq!(slow.amount, balance.delivered);
+        engine.check_conservation(1e-12).unwrap();
+    }
+
+    #[test]
+    fn test_restore_mid_window_matches_continuous_run() {
+        let build = || {
+            let mut engine = Engine::new(1.0).unwrap();
+            engine
+                .add_entity("source", Box::new(Pool::default()))
+                .unwrap();
+            engine
+                .add_entity("slow", Box::new(Pool::default()))
+                .unwrap();
+            engine.set_natural_dt("slow", 3.0).unwrap();
+            engine
+                .couple(Coupling::new("source.clock", "slow.inflow").conserved())
+                .unwrap();
+            engine
+        };
+        let mut continuous = build();
+        for _ in 0..9 {
+            continuous.step().unwrap();
+        }
+
+        let mut first = build();
+        for _ in 0..4 {
+            first.step().unwrap();
+        }
+        let saved = nonfinite::to_value(&first.snapshot()).unwrap();
+        let mut resumed = build();
+        resumed
+            .restore(&nonfinite::from_value(saved).unwrap())
+            .unwrap();
+        assert!(resumed.exchange_balances()["slow.inflow"].pending > 0.0);
+        for _ in 0..5 {
+            resumed.step().unwrap();
+        }
+        assert_eq!(resumed.snapshot(), continuous.snapshot());
+        resumed.check_conservation(1e-12).unwrap();
+    }
+
+    #[test]
+    fn test_rejected_input_leaves_engine_unstepped() {
+        let mut engine = engine();
+        engine
+            .add_entity("source", Box::new(Pool::default()))
+            .unwrap();
+        engine
+            .add_entity("slow", Box::new(Pool::default()))
+            .unwrap();
+        engine.set_natural_dt("slow", 3.0).unwrap();
+        engine
+            .couple(Coupling::new("source.clock", "slow.inflow").conserved())
+            .unwrap();
+        // CO2 production reads 200, 100, then 0, which the lungs reject.
+        engine
+            .couple(
+                Coupling::new("source.amount", "lungs.co2_production_ml_min")
+                    .with_gain(100.0)
+                    .with_offset(200.0),
+            )
+            .unwrap();
+        engine.set_input("source.inflow", -1.0).unwrap();
+        engine.step().unwrap();
+        engine.step().unwrap();
+        let before = engine.snapshot();
+        assert!(before.balances["slow.inflow"].pending > 0.0);
+        assert!(engine.step().is_err());
+        assert_eq!(engine.snapshot(), before);
+        assert!(engine.step().is_err());
+        assert_eq!(engine.time_minutes, 2.0);
+    }
+
+    #[test]
+    fn test_fracture_event_triggers_subscribed_inflammation() {
+        let mut engine = engine();
+        let bone = LoadedBone {
+            strain: 0.0,
+            cracked: false,
+            raised: Vec::new(),
+        };
+        engine.add_entity("femur", Box::new(bone)).unwrap();
+        engine
+            .subscribe(EventKind::Fracture, "body", |record| match record.event {
+                Event::Fracture { severity, .. } => {
+                    vec![InputEffect::new("fever_shift_c", 2.0 * severity)]
+                }
+                _ => Vec::new(),
+            })
+            .unwrap();
+        engine.watch_threshold("femur.strain", 0.008).unwrap();
+        assert!(engine
+            .subscribe(EventKind::Fracture, "spleen", |_| Vec::new())
+            .is_err());
+        assert!(engine.watch_changes("femur.stress", 1.0).is_err());
+
+        let mut log = Vec::new();
+        for _ in 0..4 {
+            engine.step().unwrap();
+            log.extend(engine.events().published().iter().cloned());
+        }
+        let kinds: Vec<EventKind> = log.iter().map(|r| r.event.kind()).collect();
+        assert_eq!(
+            kinds,
+            vec![EventKind::ThresholdCrossed, EventKind::Fracture]
+        );
+        assert_eq!(log[1].entity, "femur");
+        assert_eq!(log[1].time_minutes, 3.0);
+        // Applied at the start of step 4: a 1 °C set-point shift.
+        let body: &dyn Any = engine.entity("body").unwrap();
+        let body = body.downcast_ref::<Thermoregulation>().unwrap();
+        assert!((body.core_set_point_c - CORE_SET_POINT_C - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_run_records_probes() {
+        let mut engine = engine();
+        let mut recorder = Recorder::new(5.0);
+        let (unit, probe) = engine.probe("body.core_temperature_c").unwrap();
+        assert_eq!(unit, "°C");
+        recorder
+            .register("body.core_temperature_c", unit, probe)
+            .unwrap();
+        let table = engine.run(30.0, recorder).unwrap();
+        assert_eq!(table.len(), 7);
+        let core = table.column("body.core_temperature_c").unwrap();
+        assert!(core.iter().all(|t| (36.0..38.0).contains(t)));
+    }
+
+    #[test]
+    fn test_snapshot_restores_state_and_clock() {
+        let mut original = engine();
+        original.set_input("body.activity_w", 300.0).unwrap();
+        for _ in 0..5 {
+            original.step().unwrap();
+        }
+        let snapshot = original.snapshot();
+        let mut restored = engine();
+        restored.restore(&snapshot).unwrap();
+        for _ in 0..5 {
+            original.step().unwrap();
+            restored.step().unwrap();
+        }
+        assert_eq!(restored.time_minutes, original.time_minutes);
+        assert_eq!(
+            restored.value("body.core_temperature_c").unwrap(),
+            original.value("body.core_temperature_c").unwrap()
+        );
+
+        let mut other = Engine::new(1.0).unwrap();
+        other
+            .add_entity("body", Box::new(GlucoseInsulinModel::new_healthy()))
+            .unwrap();
+        assert!(other.restore(&snapshot).is_err());
+    }
+
+    #[test]
+    fn test_parallel_update_matches_serial() {
+        let build = |threads: usize| {
+            let mut engine = Engine::new(0.5).unwrap().with_worker_threads(threads);
+            // Enough pairs that four workers each get a full share.
+            for i in 0..2 * MIN_ENTITIES_PER_WORKER {
+                let (body, lungs) = (format!("body{:03}", i), format!("lungs{:03}", i));
+                engine
+                    .add_entity(&body, Box::new(Thermoregulation::new_adult()))
+                    .unwrap();
+                engine
+                    .add_entity(&lungs, Box::new(RespiratoryControl::new_healthy()))
+                    .unwrap();
+                engine
+                    .set_input(&format!("{}.activity_w", body), 10.0 * (i % 24) as f64)
+                    .unwrap();
+                engine
+                    .couple(
+                        Coupling::new(
+                            &format!("{}.metabolic_w", body),
+                            &format!("{}.co2_production_ml_min", lungs),
+                        )
+                        .with_gain(2.4),
+                    )
+                    .unwrap();
+            }
+            engine
+        };
+        let mut serial = build(1);
+        let mut parallel = build(4);
+        for _ in 0..20 {
+            serial.step().unwrap();
+            parallel.step().unwrap();
+        }
+        assert_eq!(
+            serde_json::to_string(&serial.snapshot()).unwrap(),
+            serde_json::to_string(&parallel.snapshot()).unwrap()
+        );
+        let active = serial.value("lungs023.minute_ventilation_l_min").unwrap();
+        assert!(active > serial.value("lungs000.minute_ventilation_l_min").unwrap());
+    }
+
+    #[test]
+    fn test_clock_modulators_are_engine_outputs() {
+        let mut engine = Engine::new(60.0).unwrap();
+        engine
+            .add_entity("clock", Box::new(CircadianClock::new()))
+            .unwrap();
+        assert_eq!(engine.output_unit("clock.internal_hour").unwrap(), "h");
+        assert!(engine.value("clock.growth_hormone").is_err());
+        let midnight = engine.value("clock.melatonin").unwrap();
+        for _ in 0..12 {
+            engine.step().unwrap();
+        }
+        let hour = engine.value("clock.internal_hour").unwrap();
+        assert!((hour - 12.0).abs() < 1.5, "{}", hour);
+        assert!(engine.value("clock.melatonin").unwrap() < 0.5 * midnight);
+    }
+
+    #[test]
+    fn test_sleep_follows_clock_through_coupling() {
+        let mut engine = Engine::new(15.0).unwrap();
+        engine
+            .add_entity("clock", Box::new(CircadianClock::new()))
+            .unwrap();
+        let mut sleep = SleepWakeRegulation::new();
+        sleep.set_internal_hour(0.0).unwrap();
+        sleep.homeostatic_pressure = 0.5;
+        engine.add_entity("sleep", Box::new(sleep)).unwrap();
+        engine
+            .couple(Coupling::new("clock.internal_hour", "sleep.internal_hour"))
+            .unwrap();
+        let mut asleep_hours = 0.0;
+        for _ in 0..96 {
+            engine.step().unwrap();
+            asleep_hours += 0.25 * engine.value("sleep.asleep").unwrap();
+        }
+        assert!((6.0..10.0).contains(&asleep_hours), "{}", asleep_hours);
+        assert_eq!(engine.value("sleep.recovery").unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_altitude_polycythaemia_raises_oxygen_content() {
+        let mut engine = Engine::new(1440.0).unwrap();
+        let mut lungs = RespiratoryControl::new_healthy();
+        // ~3500 m.
+        lungs.set_barometric_pressure(495.0).unwrap();
+        engine.add_entity("lungs", Box::new(lungs)).unwrap();
+        engine
+            .add_entity("blood", Box::new(IronHomeostasis::new_healthy()))
+            .unwrap();
+        engine
+            .couple(Coupling::new(
+                "lungs.arterial_po2_mmhg",
+                "blood.arterial_po2_mmhg",
+            ))
+            .unwrap();
+        engine
+            .couple(Coupling::new(
+                "blood.hemoglobin_g_dl",
+                "lungs.hemoglobin_g_dl",
+            ))
+            .unwrap();
+        engine.step().unwrap();
+        let early = engine.value("lungs.arterial_o2_content_ml_dl").unwrap();
+        assert!(engine.value("blood.epo_u_l").unwrap() > 10.0);
+        for _ in 0..30 {
+            engine.step().unwrap();
+        }
+        assert!(engine.value("blood.hemoglobin_g_dl").unwrap() > 15.5);
+        assert!(engine.value("lungs.arterial_o2_content_ml_dl").unwrap() > early + 0.5);
+    }
+
+    #[test]
+    fn test_exercise_schedule_heats_the_body() {
+        use crate::activity::{Exercise, ExerciseCapacity, ExercisePrescription};
+        let plan = ExercisePrescription::new()
+            .session(Exercise::Running { speed_m_s: 3.0 }, 10.0, 30.0)
+            .unwrap();
+        let capacity = ExerciseCapacity::new(70.0, 30.0, 50.0).unwrap();
+        let mut engine = engine();
+        engine
+            .add_entity("exercise", Box::new(ActivitySchedule::new(plan, capacity)))
+            .unwrap();
+        engine
+            .couple(Coupling::new("exercise.activity_w", "body.activity_w"))
+            .unwrap();
+        let resting_core = engine.value("body.core_temperature_c").unwrap();
+        for _ in 0..40 {
+            engine.step().unwrap();
+        }
+        assert!(engine.value("body.core_temperature_c").unwrap() > resting_core + 0.3);
+        assert_eq!(engine.value("exercise.activity_w").unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_invalid_wiring_rejected() {
+        let mut engine = engine();
+        assert!(engine
+            .add_entity("body", Box::new(GlucoseInsulinModel::new_healthy()))
+            .is_err());
+        assert!(engine
+            .couple(Coupling::new("body.nothing", "lungs.co2_production_ml_min"))
+            .is_err());
+        assert!(engine
+            .couple(Coupling::new("body.metabolic_w", "lungs.arterial_ph"))
+            .is_err());
+        assert!(engine
+            .couple(Coupling::new("heart.rate", "lungs.co2_production_ml_min"))
+            .is_err());
+        assert!(engine.value("body").is_err());
+        engine
+            .couple(Coupling::new(
+                "body.metabolic_w",
+                "lungs.co2_production_ml_min",
+            ))
+            .unwrap();
+        assert!(engine
+            .couple(Coupling::new(
+                "body.core_temperature_c",
+                "lungs.co2_production_ml_min"
+            ))
+            .is_err());
+    }
+}
@@ -0,0 +1,301 @@
+use crate::simulation::interaction::InputEffect;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+// Something a component or the engine reports during a step. Components
+// publish their own domain events (a crack, a wave of cell death); the
+// engine publishes state changes and threshold crossings on watched
+// outputs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Event {
+    StateChanged {
+        output: String,
+        previous: f64,
+        current: f64,
+    },
+    ThresholdCrossed {
+        output: String,
+        threshold: f64,
+        rising: bool,
+    },
+    Fracture {
+        site: String,
+        // Fraction of the cross-section cracked, 0-1.
+        severity: f64,
+    },
+    Apoptosis {
+        cell_type: String,
+        cells: f64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    StateChanged,
+    ThresholdCrossed,
+    Fracture,
+    Apoptosis,
+}
+
+impl Event {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Event::StateChanged { .. } => EventKind::StateChanged,
+            Event::ThresholdCrossed { .. } => EventKind::ThresholdCrossed,
+            Event::Fracture { .. } => EventKind::Fracture,
+            Event::Apoptosis { .. } => EventKind::Apoptosis,
+        }
+    }
+}
+
+// An event as delivered: which entity it came from and when.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub time_minutes: f64,
+    pub entity: String,
+    pub event: Event,
+}
+
+type Handler = Box<dyn FnMut(&EventRecord) -> Vec<InputEffect> + Send>;
+
+struct Subscription {
+    kind: EventKind,
+    // Entity whose inputs the handler's effects are applied to.
+    target: String,
+    handler: Handler,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum WatchRule {
+    Change { min_delta: f64 },
+    Threshold { threshold: f64 },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Watch {
+    path: String,
+    rule: WatchRule,
+    // Last value reported (changes) or seen (thresholds).
+    last: Option<f64>,
+}
+
+// Publish/subscribe between engine entities. Handlers run when an event
+// is published at the end of a step; the input effects they return are
+// applied to their target entity in the next step's exchange phase,
+// summed with couplings and interactions.
+#[derive(Default)]
+pub struct EventBus {
+    subscriptions: Vec<Subscription>,
+    watches: Vec<Watch>,
+    pending: Vec<(String, f64)>,
+    published: Vec<EventRecord>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(
+        &mut self,
+        kind: EventKind,
+        target: &str,
+        handler: impl FnMut(&EventRecord) -> Vec<InputEffect> + Send + 'static,
+    ) {
+        self.subscriptions.push(Subscription {
+            kind,
+            target: target.to_string(),
+            handler: Box::new(handler),
+        });
+    }
+
+    // Publishes StateChanged whenever `path` has moved at least
+    // `min_delta` from the value last reported.
+    pub fn watch_changes(&mut self, path: &str, min_delta: f64) {
+        self.watch(path, WatchRule::Change { min_delta });
+    }
+
+    // Publishes ThresholdCrossed whenever `path` crosses `threshold` in
+    // either direction.
+    pub fn watch_threshold(&mut self, path: &str, threshold: f64) {
+        self.watch(path, WatchRule::Threshold { threshold });
+    }
+
+    fn watch(&mut self, path: &str, rule: WatchRule) {
+        self.watches.push(Watch {
+            path: path.to_string(),
+            rule,
+            last: None,
+        });
+    }
+
+    pub fn subscription_count(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    pub fn watched_paths(&self) -> impl Iterator<Item = &str> {
+        self.watches.iter().map(|w| w.path.as_str())
+    }
+
+    // Events published during the most recent step, in publication order.
+    pub fn published(&self) -> &[EventRecord] {
+        &self.published
+    }
+
+    // Compares watched outputs against their previous values; `value`
+    // reads an `entity.port` path. The first observation only primes the
+    // watch.
+    pub(crate) fn observe(
+        &mut self,
+        time_minutes: f64,
+        mut value: impl FnMut(&str) -> f64,
+    ) -> Vec<EventRecord> {
+        let mut events = Vec::new();
+        for watch in &mut self.watches {
+            let current = value(&watch.path);
+            let Some(previous) = watch.last else {
+                watch.last = Some(current);
+                continue;
+            };
+            let (entity, output) = watch
+                .path
+                .split_once('.')
+                .expect("watched paths are validated");
+            let event = match watch.rule {
+                WatchRule::Change { min_delta } => {
+                    if (current - previous).abs() < min_delta {
+                        continue;
+                    }
+                    watch.last = Some(current);
+                    Event::StateChanged {
+                        output: output.to_string(),
+                        previous,
+                        current,
+                    }
+                }
+                WatchRule::Threshold { threshold } => {
+                    watch.last = Some(current);
+                    let rising = previous < threshold && current >= threshold;
+                    let falling = previous >= threshold && current < threshold;
+                    if !(rising || falling) {
+                        continue;
+                    }
+                    Event::ThresholdCrossed {
+                        output: output.to_string(),
+                        threshold,
+                        rising,
+                    }
+                }
+            };
+            events.push(EventRecord {
+                time_minutes,
+                entity: entity.to_string(),
+                event,
+            });
+        }
+        events
+    }
+
+    // Delivers events to matching subscribers, in subscription order, and
+    // queues their effects for the next step.
+    pub(crate) fn publish(&mut self, events: Vec<EventRecord>) {
+        for record in &events {
+            debug!(
+                t_min = record.time_minutes,
+                entity = %record.entity,
+                event = ?record.event,
+                "event published"
+            );
+            for subscription in &mut self.subscriptions {
+                if subscription.kind != record.event.kind() {
+                    continue;
+                }
+                for effect in (subscription.handler)(record) {
+                    self.pending.push((
+                        format!("{}.{}", subscription.target, effect.input),
+                        effect.value,
+                    ));
+                }
+            }
+        }
+        self.published = events;
+    }
+
+    pub(crate) fn pending(&self) -> &[(String, f64)] {
+        &self.pending
+    }
+
+    pub(crate) fn take_pending(&mut self) -> Vec<(String, f64)> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watches_report_changes_and_crossings() {
+        let mut bus = EventBus::new();
+        bus.watch_changes("body.core_temperature_c", 0.5);
+        bus.watch_threshold("body.core_temperature_c", 38.0);
+
+        let mut readings = [37.0, 37.3, 38.1, 37.9].into_iter();
+        let mut kinds = Vec::new();
+        for t in 0..4 {
+            let current = readings.next().unwrap();
+            let events = bus.observe(t as f64, |_| current);
+            kinds.push(events.iter().map(|r| r.event.kind()).collect::<Vec<_>>());
+            bus.publish(events);
+        }
+        assert!(kinds[0].is_empty() && kinds[1].is_empty());
+        assert_eq!(
+            kinds[2],
+            vec![EventKind::StateChanged, EventKind::ThresholdCrossed]
+        );
+        // 37.9 is within 0.5 of the last reported 38.1, but back below 38.
+        assert_eq!(kinds[3], vec![EventKind::ThresholdCrossed]);
+        assert_eq!(
+            bus.published()[0].event,
+            Event::ThresholdCrossed {
+                output: "core_temperature_c".to_string(),
+                threshold: 38.0,
+                rising: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_subscribers_receive_only_their_kind() {
+        let mut bus = EventBus::new();
+        bus.subscribe(EventKind::Fracture, "body", |record| match &record.event {
+            Event::Fracture { severity, .. } => {
+                vec![InputEffect::new("fever_shift_c", 2.0 * severity)]
+            }
+            _ => Vec::new(),
+        });
+        bus.publish(vec![
+            EventRecord {
+                time_minutes: 1.0,
+                entity: "femur".to_string(),
+                event: Event::Apoptosis {
+                    cell_type: "osteocyte".to_string(),
+                    cells: 1e4,
+                },
+            },
+            EventRecord {
+                time_minutes: 1.0,
+                entity: "femur".to_string(),
+                event: Event::Fracture {
+                    site: "midshaft".to_string(),
+                    severity: 0.25,
+                },
+            },
+        ]);
+        assert_eq!(
+            bus.take_pending(),
+            vec![("body.fever_shift_c".to_string(), 0.5)]
+        );
+        assert!(bus.take_pending().is_empty());
+    }
+}
@@ -0,0 +1,475 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::cohort::{CohortConfig, CohortRunner, SubjectFailure};
+use crate::simulation::montecarlo::{ParameterDraw, RecordedOutputs};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+// One setting of a factor: a label for tables (e.g. "alum") and the value
+// handed to the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Level {
+    pub label: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Factor {
+    pub name: String,
+    pub levels: Vec<Level>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DesignKind {
+    // Every combination of levels.
+    FullFactorial,
+    // Three factors of n levels each in n^2 arms: the third factor's level
+    // is (row + column) mod n, so each level meets every level of the
+    // other two factors exactly once. Main effects only; interactions are
+    // confounded.
+    LatinSquare,
+}
+
+// One treatment combination of a design.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Arm {
+    pub index: usize,
+    pub labels: BTreeMap<String, String>,
+    pub parameters: ParameterDraw,
+}
+
+// An in-silico experiment laid out like a wet-lab one: factors crossed
+// into arms, each arm run `replicates` times on independent seeds. Runs go
+// through the cohort runner, so results are reproducible from the master
+// seed at any thread count.
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    pub kind: DesignKind,
+    pub factors: Vec<Factor>,
+    pub replicates: usize,
+    pub config: CohortConfig,
+}
+
+impl Experiment {
+    pub fn new(kind: DesignKind, replicates: usize, master_seed: u64) -> Self {
+        Self {
+            kind,
+            factors: Vec::new(),
+            replicates,
+            config: CohortConfig::new(0, master_seed),
+        }
+    }
+
+    pub fn with_factor(mut self, name: &str, levels: &[(&str, f64)]) -> BiologyResult<Self> {
+        if self.factors.iter().any(|f| f.name == name) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "factor {} specified twice",
+                name
+            )));
+        }
+        if levels.is_empty() {
+            return Err(BiologyError::InvalidParameter(format!(
+                "factor {} has no levels",
+                name
+            )));
+        }
+        for (i, (label, value)) in levels.iter().enumerate() {
+            if label.is_empty() || label.contains(',') || !value.is_finite() {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "invalid level {:?} of factor {}",
+                    label, name
+                )));
+            }
+            if levels[..i].iter().any(|(other, _)| other == label) {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "level {} of factor {} specified twice",
+                    label, name
+                )));
+            }
+        }
+        self.factors.push(Factor {
+            name: name.to_string(),
+            levels: levels
+                .iter()
+                .map(|&(label, value)| Level {
+                    label: label.to_string(),
+                    value,
+                })
+                .collect(),
+        });
+        Ok(self)
+    }
+
+    pub fn with_worker_threads(mut self, worker_threads: usize) -> Self {
+        self.config.worker_threads = worker_threads;
+        self
+    }
+
+    pub fn validate(&self) -> BiologyResult<()> {
+        if self.factors.is_empty() || self.replicates == 0 {
+            return Err(BiologyError::InvalidParameter(
+                "an experiment needs at least one factor and one replicate".to_string(),
+            ));
+        }
+        if self.kind == DesignKind::LatinSquare {
+            let n = self.factors[0].levels.len();
+            if self.factors.len() != 3 || self.factors.iter().any(|f| f.levels.len() != n) {
+                return Err(BiologyError::InvalidParameter(
+                    "a Latin square needs three factors with equal level counts".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Level index per factor for every arm, last factor varying fastest.
+    fn layout(&self) -> Vec<Vec<usize>> {
+        match self.kind {
+            DesignKind::FullFactorial => {
+                let mut rows = vec![Vec::new()];
+                for factor in &self.factors {
+                    rows = rows
+                        .into_iter()
+                        .flat_map(|row| {
+                            (0..factor.levels.len()).map(move |level| {
+                                let mut next = row.clone();
+                                next.push(level);
+                                next
+                            })
+                        })
+                        .collect();
+                }
+                rows
+            }
+            DesignKind::LatinSquare => {
+                let n = self.factors[0].levels.len();
+                (0..n)
+                    .flat_map(|row| (0..n).map(move |column| vec![row, column, (row + column) % n]))
+                    .collect()
+            }
+        }
+    }
+
+    pub fn arms(&self) -> BiologyResult<Vec<Arm>> {
+        self.validate()?;
+        Ok(self
+            .layout()
+            .into_iter()
+            .enumerate()
+            .map(|(index, levels)| {
+                let chosen = self
+                    .factors
+                    .iter()
+                    .zip(levels)
+                    .map(|(f, l)| (f, &f.levels[l]));
+                Arm {
+                    index,
+                    labels: chosen
+                        .clone()
+                        .map(|(f, level)| (f.name.clone(), level.label.clone()))
+                        .collect(),
+                    parameters: ParameterDraw {
+                        values: chosen
+                            .map(|(f, level)| (f.name.clone(), level.value))
+                            .collect(),
+                    },
+                }
+            })
+            .collect())
+    }
+
+    pub fn run<F>(&self, simulate: F) -> Result<ExperimentResults, Box<dyn std::error::Error>>
+    where
+        F: Fn(&Arm, &mut StdRng) -> BiologyResult<RecordedOutputs> + Sync,
+    {
+        let arms = self.arms()?;
+        let mut config = self.config;
+        config.subjects = arms.len() * self.replicates;
+        let report = CohortRunner::new(config)?
+            .run(|run, rng| simulate(&arms[run / self.replicates], rng))?;
+        let mut observations = Vec::new();
+        for record in report.records {
+            let arm = &arms[record.subject / self.replicates];
+            for (output, value) in record.result {
+                observations.push(Observation {
+                    arm: arm.index,
+                    replicate: record.subject % self.replicates,
+                    seed: record.seed,
+                    labels: arm.labels.clone(),
+                    output,
+                    value,
+                });
+            }
+        }
+        Ok(ExperimentResults {
+            factors: self.factors.iter().map(|f| f.name.clone()).collect(),
+            observations,
+            failures: report.failures,
+        })
+    }
+}
+
+// One tidy row: a single output of a single run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    pub arm: usize,
+    pub replicate: usize,
+    pub seed: u64,
+    pub labels: BTreeMap<String, String>,
+    pub output: String,
+    pub value: f64,
+}
+
+// Mean response at one level of a factor, and its deviation from the
+// grand mean of the output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MainEffect {
+    pub factor: String,
+    pub level: String,
+    pub n: usize,
+    pub mean: f64,
+    pub effect: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentResults {
+    pub factors: Vec<String>,
+    pub observations: Vec<Observation>,
+    pub failures: Vec<SubjectFailure>,
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn sample_variance(values: &[f64]) -> f64 {
+    let m = mean(values);
+    values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
+
+impl ExperimentResults {
+    fn values(&self, output: &str, factor: Option<(&str, &str)>) -> Vec<f64> {
+        self.observations
+            .iter()
+            .filter(|o| o.output == output)
+            .filter(|o| match factor {
+                Some((name, level)) => o.labels.get(name).map(String::as_str) == Some(level),
+                None => true,
+            })
+            .map(|o| o.value)
+            .collect()
+    }
+
+    // Level means of every factor in design order, relative to the grand
+    // mean. Balanced designs make these the classical main effects.
+    pub fn main_effects(&self, output: &str) -> Vec<MainEffect> {
+        let all = self.values(output, None);
+        if all.is_empty() {
+            return Vec::new();
+        }
+        let grand = mean(&all);
+        let mut effects = Vec::new();
+        for factor in &self.factors {
+            let mut levels: Vec<&str> = Vec::new();
+            for o in self.observations.iter().filter(|o| o.output == output) {
+                let label = o.labels[factor].as_str();
+                if !levels.contains(&label) {
+                    levels.push(label);
+                }
+            }
+            for level in levels {
+                let values = self.values(output, Some((factor, level)));
+                let level_mean = mean(&values);
+                effects.push(MainEffect {
+                    factor: factor.clone(),
+                    level: level.to_string(),
+                    n: values.len(),
+                    mean: level_mean,
+                    effect: level_mean - grand,
+                });
+            }
+        }
+        effects
+    }
+
+    // Standardised difference between two levels of a factor, (mean_b -
+    // mean_a) / pooled SD; None without at least two runs per level or
+    // with zero spread.
+    // Cohen J (1988) Statistical Power Analysis for the Behavioral
+    // Sciences, 2nd ed., eq. 2.2.1
+    pub fn cohens_d(
+        &self,
+        output: &str,
+        factor: &str,
+        level_a: &str,
+        level_b: &str,
+    ) -> Option<f64> {
+        let a = self.values(output, Some((factor, level_a)));
+        let b = self.values(output, Some((factor, level_b)));
+        if a.len() < 2 || b.len() < 2 {
+            return None;
+        }
+        let (na, nb) = (a.len() as f64, b.len() as f64);
+        let pooled = (((na - 1.0) * sample_variance(&a) + (nb - 1.0) * sample_variance(&b))
+            / (na + nb - 2.0))
+            .sqrt();
+        (pooled > 0.0).then(|| (mean(&b) - mean(&a)) / pooled)
+    }
+
+    // Long-format CSV: arm, replicate, one column per factor, output,
+    // value.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("arm,replicate");
+        for factor in &self.factors {
+            csv.push(',');
+            csv.push_str(factor);
+        }
+        csv.push_str(",output,value\n");
+        for o in &self.observations {
+            write!(csv, "{},{}", o.arm, o.replicate).expect("writing to a String");
+            for factor in &self.factors {
+                write!(csv, ",{}", o.labels[factor]).expect("writing to a String");
+            }
+            writeln!(csv, ",{},{}", o.output, o.value).expect("writing to a String");
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::physiology::Thermoregulation;
+    use crate::simulation::engine::Engine;
+    use rand::Rng;
+
+    fn heat_stress(kind: DesignKind, threads: usize) -> ExperimentResults {
+        let mut experiment = Experiment::new(kind, 4, 11)
+            .with_factor(
+                "activity_w",
+                &[("rest", 0.0), ("walk", 200.0), ("run", 600.0)],
+            )
+            .unwrap()
+            .with_factor(
+                "ambient_temperature_c",
+                &[("cool", 15.0), ("mild", 24.0), ("hot", 35.0)],
+            )
+            .unwrap();
+        if kind == DesignKind::LatinSquare {
+            experiment = experiment
+                .with_factor(
+                    "fever_shift_c",
+                    &[("none", 0.0), ("low", 1.0), ("high", 2.0)],
+                )
+                .unwrap();
+        }
+        experiment
+            .with_worker_threads(threads)
+            .run(|arm, rng| {
+                let mut engine = Engine::new(1.0)?;
+                engine.add_entity("body", Box::new(Thermoregulation::new_adult()))?;
+                for (name, value) in &arm.parameters.values {
+                    // Subject-to-subject spread in workload.
+                    let value = if name == "activity_w" {
+                        value * rng.gen_range(0.9..1.1)
+                    } else {
+                        *value
+                    };
+                    engine.set_input(&format!("body.{}", name), value)?;
+                }
+                for _ in 0..30 {
+                    engine.step()?;
+                }
+                let mut outputs = RecordedOutputs::new();
+                outputs.insert(
+                    "core_temperature_c".to_string(),
+                    engine.value("body.core_temperature_c")?,
+                );
+                Ok(outputs)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_full_factorial_crosses_every_level() {
+        let results = heat_stress(DesignKind::FullFactorial, 2);
+        assert_eq!(results.observations.len(), 9 * 4);
+        assert!(results.failures.is_empty());
+
+        let effects = results.main_effects("core_temperature_c");
+        assert_eq!(effects.len(), 6);
+        assert!(effects.iter().all(|e| e.n == 12));
+        let effect = |factor: &str, level: &str| {
+            effects
+                .iter()
+                .find(|e| e.factor == factor && e.level == level)
+                .unwrap()
+                .effect
+        };
+        assert!(effect("activity_w", "run") > effect("activity_w", "rest"));
+        let total: f64 = ["rest", "walk", "run"]
+            .iter()
+            .map(|l| effect("activity_w", l))
+            .sum();
+        assert!(total.abs() < 1e-9);
+        assert!(
+            results
+                .cohens_d("core_temperature_c", "activity_w", "rest", "run")
+                .unwrap()
+                > 0.0
+        );
+
+        let csv = results.to_csv();
+        assert!(csv.starts_with("arm,replicate,activity_w,ambient_temperature_c,output,value\n"));
+        assert_eq!(csv.lines().count(), 1 + 36);
+    }
+
+    #[test]
+    fn test_latin_square_balances_levels_and_is_reproducible() {
+        let results = heat_stress(DesignKind::LatinSquare, 1);
+        let arms: Vec<&Observation> = results
+            .observations
+            .iter()
+            .filter(|o| o.replicate == 0)
+            .collect();
+        assert_eq!(arms.len(), 9);
+        for factor in &results.factors {
+            for other in &results.factors {
+                if factor == other {
+                    continue;
+                }
+                let mut pairs: Vec<(&str, &str)> = arms
+                    .iter()
+                    .map(|o| (o.labels[factor].as_str(), o.labels[other].as_str()))
+                    .collect();
+                pairs.sort();
+                pairs.dedup();
+                assert_eq!(pairs.len(), 9);
+            }
+        }
+        let fever = results.main_effects("core_temperature_c");
+        let high = fever.iter().find(|e| e.level == "high").unwrap();
+        let none = fever.iter().find(|e| e.level == "none").unwrap();
+        assert!(high.mean > none.mean);
+        assert_eq!(
+            results.observations,
+            heat_stress(DesignKind::LatinSquare, 3).observations
+        );
+    }
+
+    #[test]
+    fn test_invalid_designs_rejected() {
+        assert!(Experiment::new(DesignKind::FullFactorial, 1, 0)
+            .with_factor("dose", &[("low", 1.0), ("low", 2.0)])
+            .is_err());
+        let square = Experiment::new(DesignKind::LatinSquare, 1, 0)
+            .with_factor("a", &[("1", 1.0), ("2", 2.0)])
+            .unwrap()
+            .with_factor("b", &[("1", 1.0), ("2", 2.0)])
+            .unwrap();
+        assert!(square.arms().is_err());
+        let square = square.with_factor("c", &[("1", 1.0)]).unwrap();
+        assert!(square.arms().is_err());
+    }
+}
@@ -0,0 +1,411 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::recorder::TimeSeriesTable;
+use crate::simulation::runs::ParamValue;
+use crate::validation::provenance::Provenance;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::Path;
+
+// Target chunk size; HDF5 reads whole chunks, and ~64 KiB-1 MiB keeps both
+// partial reads and per-chunk overhead small.
+const TARGET_CHUNK_BYTES: usize = 1 << 16;
+const NPY_MAGIC: &[u8; 6] = b"\x93NUMPY";
+const ATTRIBUTES_FILE: &str = "attributes.json";
+
+// An n-dimensional row-major f64 array with its physical unit and storage
+// hints for a chunked, compressed HDF5 dataset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dataset {
+    pub name: String,
+    pub unit: String,
+    pub shape: Vec<usize>,
+    pub chunk_shape: Vec<usize>,
+    pub gzip_level: Option<u8>,
+    pub data: Vec<f64>,
+}
+
+impl Dataset {
+    pub fn new(name: &str, unit: &str, shape: Vec<usize>, data: Vec<f64>) -> BiologyResult<Self> {
+        if shape.iter().product::<usize>() != data.len() {
+            return Err(BiologyError::InvalidValue(format!(
+                "dataset {} has {} values for shape {:?}",
+                name,
+                data.len(),
+                shape
+            )));
+        }
+        let chunk_shape = default_chunk_shape(&shape);
+        Ok(Self {
+            name: name.to_string(),
+            unit: unit.to_string(),
+            shape,
+            chunk_shape,
+            gzip_level: Some(4),
+            data,
+        })
+    }
+
+    pub fn vector(name: &str, unit: &str, data: Vec<f64>) -> Self {
+        let len = data.len();
+        Self::new(name, unit, vec![len], data).expect("1-D shape matches data")
+    }
+}
+
+// Whole trailing dimensions, as many leading rows as fit the target size.
+pub fn default_chunk_shape(shape: &[usize]) -> Vec<usize> {
+    let Some((&rows, rest)) = shape.split_first() else {
+        return Vec::new();
+    };
+    let row_bytes = rest.iter().product::<usize>().max(1) * std::mem::size_of::<f64>();
+    let chunk_rows = (TARGET_CHUNK_BYTES / row_bytes).clamp(1, rows.max(1));
+    std::iter::once(chunk_rows)
+        .chain(rest.iter().copied())
+        .collect()
+}
+
+// HDF5-style group: named attributes, datasets and subgroups.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    pub name: String,
+    pub attributes: BTreeMap<String, ParamValue>,
+    pub datasets: Vec<Dataset>,
+    pub groups: Vec<Group>,
+}
+
+impl Group {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn attribute<V: Into<ParamValue>>(mut self, name: &str, value: V) -> Self {
+        self.attributes.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn with_dataset(mut self, dataset: Dataset) -> Self {
+        self.datasets.push(dataset);
+        self
+    }
+
+    pub fn with_group(mut self, group: Group) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    // Recorder output as one group: a shared `time` axis and one dataset
+    // per observable.
+    pub fn from_table(name: &str, table: &TimeSeriesTable, time_unit: &str) -> Self {
+        let mut group =
+            Self::new(name).with_dataset(Dataset::vector("time", time_unit, table.time.clone()));
+        for (column, values) in table.columns.iter().zip(&table.values) {
+            group = group.with_dataset(Dataset::vector(&column.name, &column.unit, values.clone()));
+        }
+        group
+    }
+
+    // Slash-separated path below this group, e.g. "patients/0001/time".
+    pub fn dataset(&self, path: &str) -> Option<&Dataset> {
+        match path.rsplit_once('/') {
+            Some((groups, name)) => self.group(groups)?.dataset(name),
+            None => self.datasets.iter().find(|d| d.name == path),
+        }
+    }
+
+    pub fn group(&self, path: &str) -> Option<&Group> {
+        path.split('/')
+            .filter(|part| !part.is_empty())
+            .try_fold(self, |group, part| {
+                group.groups.iter().find(|g| g.name == part)
+            })
+    }
+
+    fn validate_names(&self) -> BiologyResult<()> {
+        let names = self
+            .datasets
+            .iter()
+            .map(|d| &d.name)
+            .chain(self.groups.iter().map(|g| &g.name));
+        let mut seen = std::collections::HashSet::new();
+        for name in names {
+            if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "'{}' is not a valid group or dataset name",
+                    name
+                )));
+            }
+            if !seen.insert(name) {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "{} appears twice in group {}",
+                    name, self.name
+                )));
+            }
+        }
+        self.groups.iter().try_for_each(Group::validate_names)
+    }
+}
+
+// Provenance stamped on the root group of every export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportMetadata {
+    pub crate_version: String,
+    // Set at build time through HUMAN_BIOLOGY_GIT_HASH.
+    pub git_hash: Option<String>,
+    pub parameters: BTreeMap<String, ParamValue>,
+    // Sources of the presets and parameters the run used, keyed as in a
+    // `ProvenanceCatalog`.
+    pub provenance: BTreeMap<String, Provenance>,
+}
+
+impl ExportMetadata {
+    pub fn capture() -> Self {
+        Self {
+            crate_version: crate::VERSION.to_string(),
+            git_hash: option_env!("HUMAN_BIOLOGY_GIT_HASH").map(str::to_string),
+            parameters: BTreeMap::new(),
+            provenance: BTreeMap::new(),
+        }
+    }
+
+    pub fn param<V: Into<ParamValue>>(mut self, name: &str, value: V) -> Self {
+        self.parameters.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn cite(mut self, key: &str, provenance: &Provenance) -> Self {
+        self.provenance.insert(key.to_string(), provenance.clone());
+        self
+    }
+
+    pub fn stamp(&self, mut root: Group) -> Group {
+        root.attributes.insert(
+            "crate_version".to_string(),
+            self.crate_version.as_str().into(),
+        );
+        if let Some(hash) = &self.git_hash {
+            root.attributes
+                .insert("git_hash".to_string(), hash.as_str().into());
+        }
+        for (name, value) in &self.parameters {
+            root.attributes
+                .insert(format!("param.{}", name), value.clone());
+        }
+        for (key, provenance) in &self.provenance {
+            root.attributes
+                .extend(provenance.to_attributes(&format!("provenance.{}", key)));
+        }
+        root
+    }
+}
+
+// Per-group sidecar: group attributes plus each dataset's unit and HDF5
+// storage hints.
+#[derive(Debug, Serialize, Deserialize)]
+struct GroupAttributes {
+    attributes: BTreeMap<String, ParamValue>,
+    datasets: BTreeMap<String, DatasetAttributes>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct DatasetAttributes {
+    unit: String,
+    chunk_shape: Vec<usize>,
+    gzip_level: Option<u8>,
+}
+
+// The build carries no libhdf5, so a tree is written as a directory
+// hierarchy mirroring the HDF5 groups: `<dataset>.npy` for each array
+// (`numpy.load`) and `attributes.json` for attributes, units and chunking.
+pub fn write_npy_tree<P: AsRef<Path>>(
+    root: &Group,
+    directory: P,
+) -> Result<(), Box<dyn std::error::Error>> {
+    root.validate_names()?;
+    write_group(root, directory.as_ref())
+}
+
+fn write_group(group: &Group, directory: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(directory)?;
+    let sidecar = GroupAttributes {
+        attributes: group.attributes.clone(),
+        datasets: group
+            .datasets
+            .iter()
+            .map(|d| {
+                (
+                    d.name.clone(),
+                    DatasetAttributes {
+                        unit: d.unit.clone(),
+                        chunk_shape: d.chunk_shape.clone(),
+                        gzip_level: d.gzip_level,
+                    },
+                )
+            })
+            .collect(),
+    };
+    fs::write(
+        directory.join(ATTRIBUTES_FILE),
+        serde_json::to_string_pretty(&sidecar)?,
+    )?;
+    for dataset in &group.datasets {
+        write_npy(&directory.join(format!("{}.npy", dataset.name)), dataset)?;
+    }
+    for child in &group.groups {
+        write_group(child, &directory.join(&child.name))?;
+    }
+    Ok(())
+}
+
+// NPY format 1.0: magic, version, header length, a Python dict literal
+// padded to a 64-byte boundary, then little-endian float64 data.
+// https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html
+fn write_npy(path: &Path, dataset: &Dataset) -> std::io::Result<()> {
+    let shape = match dataset.shape.as_slice() {
+        [n] => format!("({},)", n),
+        dims => format!(
+            "({})",
+            dims.iter()
+                .map(|d| d.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let mut header = format!(
+        "{{'descr': '<f8', 'fortran_order': False, 'shape': {}, }}",
+        shape
+    );
+    let unpadded = NPY_MAGIC.len() + 2 + 2 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    let mut file = BufWriter::new(File::create(path)?);
+    file.write_all(NPY_MAGIC)?;
+    file.write_all(&[1, 0])?;
+    file.write_all(&(header.len() as u16).to_le_bytes())?;
+    file.write_all(header.as_bytes())?;
+    for value in &dataset.data {
+        file.write_all(&value.to_le_bytes())?;
+    }
+    file.flush()
+}
+
+// Reads back a float64 C-order file written by `write_npy_tree`.
+pub fn read_npy<P: AsRef<Path>>(
+    path: P,
+) -> Result<(Vec<usize>, Vec<f64>), Box<dyn std::error::Error>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    if bytes.len() < 10 || &bytes[..6] != NPY_MAGIC || bytes[6] != 1 {
+        return Err("not an NPY 1.x file".into());
+    }
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    let header = std::str::from_utf8(&bytes[10..10 + header_len])?;
+    if !header.contains("'<f8'") || header.contains("'fortran_order': True") {
+        return Err("only C-order float64 arrays are supported".into());
+    }
+    let shape_text = header
+        .split("'shape': (")
+        .nth(1)
+        .and_then(|rest| rest.split(')').next())
+        .ok_or("NPY header has no shape")?;
+    let shape = shape_text
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<usize>, _>>()?;
+    let data = bytes[10 + header_len..]
+        .chunks_exact(8)
+        .map(|c| f64::from_le_bytes(c.try_into().expect("8-byte chunk")))
+        .collect::<Vec<_>>();
+    if data.len() != shape.iter().product::<usize>() {
+        return Err("NPY data length does not match its shape".into());
+    }
+    Ok((shape, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::recorder::Column;
+    use crate::validation::provenance::ProvenanceCatalog;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("{name}_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_chunking_keeps_rows_whole() {
+        assert_eq!(default_chunk_shape(&[1_000_000]), vec![8192]);
+        assert_eq!(default_chunk_shape(&[10, 3]), vec![10, 3]);
+        assert_eq!(default_chunk_shape(&[500, 64, 64]), vec![2, 64, 64]);
+        assert!(Dataset::new("field", "mV", vec![2, 3], vec![0.0; 5]).is_err());
+    }
+
+    #[test]
+    fn test_tree_round_trips_through_npy_files() {
+        let mut table = TimeSeriesTable::new(vec![Column {
+            name: "glucose".to_string(),
+            unit: "mg/dL".to_string(),
+        }]);
+        for minute in 0..3 {
+            table
+                .push_row(minute as f64, &[90.0 + minute as f64])
+                .unwrap();
+        }
+        let field = Dataset::new(
+            "strain",
+            "",
+            vec![2, 3],
+            vec![0.1, 0.2, 0.3, 0.4, 0.5, std::f64::consts::PI],
+        )
+        .unwrap();
+        let kinetics = "systems::immune::antibody::AntibodyKinetics::igg.long_half_life_days";
+        let source = ProvenanceCatalog::builtin().get(kinetics).cloned().unwrap();
+        let root = ExportMetadata::capture()
+            .param("dose_mg", 5.0)
+            .cite(kinetics, &source)
+            .stamp(
+                Group::new("run")
+                    .with_group(
+                        Group::from_table("patient_0001", &table, "min").attribute("sex", "F"),
+                    )
+                    .with_group(Group::new("fem").with_dataset(field.clone())),
+            );
+        assert_eq!(root.dataset("patient_0001/glucose").unwrap().unit, "mg/dL");
+        assert_eq!(root.attributes["param.dose_mg"], ParamValue::Number(5.0));
+        assert_eq!(
+            root.attributes[&format!("provenance.{}.pmid", kinetics)],
+            ParamValue::Text("17989383".to_string())
+        );
+
+        let dir = temp_dir("export_tree");
+        write_npy_tree(&root, &dir).unwrap();
+        let (shape, data) = read_npy(dir.join("fem/strain.npy")).unwrap();
+        assert_eq!(shape, vec![2, 3]);
+        assert_eq!(data, field.data);
+        let (shape, data) = read_npy(dir.join("patient_0001/time.npy")).unwrap();
+        assert_eq!((shape, data), (vec![3], vec![0.0, 1.0, 2.0]));
+        let raw = fs::read(dir.join("fem/strain.npy")).unwrap();
+        assert_eq!((raw.len() - 6 * 8) % 64, 0);
+
+        let sidecar: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(dir.join("patient_0001").join(ATTRIBUTES_FILE)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(sidecar["datasets"]["glucose"]["unit"], "mg/dL");
+        assert_eq!(sidecar["attributes"]["sex"], "F");
+        fs::remove_dir_all(&dir).unwrap();
+
+        let clash = Group::new("bad")
+            .with_dataset(Dataset::vector("x", "", vec![]))
+            .with_group(Group::new("x"));
+        assert!(write_npy_tree(&clash, temp_dir("export_clash")).is_err());
+    }
+}
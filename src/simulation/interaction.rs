@@ -0,0 +1,173 @@
+use crate::biology::physiology::Thermoregulation;
+use crate::simulation::engine::Component;
+use crate::systems::respiratory::RespiratoryControl;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+// State shared by every interaction resolved in one engine step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InteractionContext {
+    pub time_minutes: f64,
+    pub dt_minutes: f64,
+}
+
+// One contribution to a target input. Like couplings, all contributions
+// into the same input during a step are summed and the total is applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputEffect {
+    pub input: String,
+    pub value: f64,
+}
+
+impl InputEffect {
+    pub fn new(input: &str, value: f64) -> Self {
+        Self {
+            input: input.to_string(),
+            value,
+        }
+    }
+}
+
+// How `Self` acts on a `Target`. Implementations whose outcome is a list
+// of input effects can be registered in an `InteractionMatrix`, which the
+// engine consults for every ordered pair of entities.
+pub trait Interactive<Target: ?Sized> {
+    type Outcome;
+
+    fn interact(&self, target: &Target, context: &InteractionContext) -> Self::Outcome;
+}
+
+type Rule = Box<dyn Fn(&dyn Any, &dyn Any, &InteractionContext) -> Vec<InputEffect> + Send + Sync>;
+
+// Registry of pairwise interactions keyed by the concrete (source,
+// target) component types.
+#[derive(Default)]
+pub struct InteractionMatrix {
+    rules: HashMap<(TypeId, TypeId), Rule>,
+}
+
+impl InteractionMatrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The interactions this crate defines between its engine components.
+    pub fn physiological() -> Self {
+        let mut matrix = Self::new();
+        matrix.register::<Thermoregulation, RespiratoryControl>();
+        matrix
+    }
+
+    // Registers `S: Interactive<T>`, replacing any earlier rule for the
+    // same ordered pair.
+    pub fn register<S, T>(&mut self)
+    where
+        S: Interactive<T, Outcome = Vec<InputEffect>> + 'static,
+        T: 'static,
+    {
+        let rule: Rule = Box::new(|source, target, context| {
+            match (source.downcast_ref::<S>(), target.downcast_ref::<T>()) {
+                (Some(source), Some(target)) => source.interact(target, context),
+                _ => Vec::new(),
+            }
+        });
+        self.rules
+            .insert((TypeId::of::<S>(), TypeId::of::<T>()), rule);
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    // The (source, target) type pairs that have a rule, so the engine only
+    // visits entities of those types.
+    pub fn type_pairs(&self) -> impl Iterator<Item = (TypeId, TypeId)> + '_ {
+        self.rules.keys().copied()
+    }
+
+    pub fn applies(&self, source: &dyn Component, target: &dyn Component) -> bool {
+        let (source, target): (&dyn Any, &dyn Any) = (source, target);
+        self.rules
+            .contains_key(&(source.type_id(), target.type_id()))
+    }
+
+    // Effects of `source` on `target`, or None when no rule is registered
+    // for their types.
+    pub fn resolve(
+        &self,
+        source: &dyn Component,
+        target: &dyn Component,
+        context: &InteractionContext,
+    ) -> Option<Vec<InputEffect>> {
+        let (source, target): (&dyn Any, &dyn Any) = (source, target);
+        self.rules
+            .get(&(source.type_id(), target.type_id()))
+            .map(|rule| rule(source, target, context))
+    }
+}
+
+// Metabolic heat sets CO2 output: 20.1 J per ml O2 and a respiratory
+// quotient of 0.8 give ~2.4 ml/min CO2 per watt.
+// Brockway JM (1987) Hum Nutr Clin Nutr 41:463-471, PMID 3429265
+const CO2_ML_MIN_PER_METABOLIC_W: f64 = 60.0 / 20.1 * 0.8;
+
+impl Interactive<RespiratoryControl> for Thermoregulation {
+    type Outcome = Vec<InputEffect>;
+
+    fn interact(&self, _lungs: &RespiratoryControl, _: &InteractionContext) -> Vec<InputEffect> {
+        let metabolic_w = self.output("metabolic_w").unwrap_or(0.0);
+        vec![InputEffect::new(
+            "co2_production_ml_min",
+            metabolic_w * CO2_ML_MIN_PER_METABOLIC_W,
+        )]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+
+    fn context() -> InteractionContext {
+        InteractionContext {
+            time_minutes: 0.0,
+            dt_minutes: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_matrix_dispatches_on_concrete_types() {
+        let matrix = InteractionMatrix::physiological();
+        let body = Thermoregulation::new_adult();
+        let lungs = RespiratoryControl::new_healthy();
+        let glucose = GlucoseInsulinModel::new_healthy();
+        assert!(matrix.applies(&body, &lungs));
+        assert!(!matrix.applies(&lungs, &body));
+        assert!(matrix.resolve(&body, &glucose, &context()).is_none());
+
+        let effects = matrix.resolve(&body, &lungs, &context()).unwrap();
+        assert_eq!(effects.len(), 1);
+        assert_eq!(effects[0].input, "co2_production_ml_min");
+        // ~105 W resting heat -> ~250 ml/min CO2, the textbook resting VCO2.
+        assert!(effects[0].value > 200.0 && effects[0].value < 300.0);
+    }
+
+    struct Doubler;
+
+    impl Interactive<f64> for Doubler {
+        type Outcome = f64;
+
+        fn interact(&self, target: &f64, _: &InteractionContext) -> f64 {
+            2.0 * target
+        }
+    }
+
+    #[test]
+    fn test_outcome_type_is_free_outside_the_engine() {
+        assert_eq!(Doubler.interact(&1.5, &context()), 3.0);
+    }
+}
@@ -0,0 +1,272 @@
+use crate::biology::{BiologyError, BiologyResult};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LifeEventKind {
+    MissedDose,
+    IntercurrentInfection,
+    Fall,
+    BedRest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LifeEvent {
+    pub kind: LifeEventKind,
+    pub start_day: f64,
+    pub duration_days: f64,
+    // Uniform in [0, 1]; consumers map it onto their own effect sizes.
+    pub severity: f64,
+}
+
+impl LifeEvent {
+    pub fn is_active(&self, day: f64) -> bool {
+        day >= self.start_day && day < self.start_day + self.duration_days.max(1.0)
+    }
+}
+
+// Events arrive as independent Poisson processes.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EventHazard {
+    pub kind: LifeEventKind,
+    pub rate_per_year: f64,
+    pub mean_duration_days: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifeEventGenerator {
+    pub hazards: Vec<EventHazard>,
+    pub dose_adherence: f64,
+    // Share of falls serious enough to confine the person to bed.
+    pub injurious_fall_fraction: f64,
+    pub post_fall_bed_rest_days: f64,
+}
+
+impl LifeEventGenerator {
+    pub fn new(dose_adherence: f64) -> BiologyResult<Self> {
+        if !(0.0..=1.0).contains(&dose_adherence) {
+            return Err(BiologyError::InvalidParameter(
+                "dose adherence must lie in [0, 1]".to_string(),
+            ));
+        }
+        Ok(Self {
+            hazards: Vec::new(),
+            dose_adherence,
+            injurious_fall_fraction: 0.0,
+            post_fall_bed_rest_days: 0.0,
+        })
+    }
+
+    pub fn with_hazard(
+        mut self,
+        kind: LifeEventKind,
+        rate_per_year: f64,
+        mean_duration_days: f64,
+    ) -> BiologyResult<Self> {
+        if kind == LifeEventKind::MissedDose {
+            return Err(BiologyError::InvalidParameter(
+                "missed doses follow adherence, not a hazard rate".to_string(),
+            ));
+        }
+        if rate_per_year < 0.0 || mean_duration_days < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "hazard rate and duration must be non-negative".to_string(),
+            ));
+        }
+        self.hazards.push(EventHazard {
+            kind,
+            rate_per_year,
+            mean_duration_days,
+        });
+        Ok(self)
+    }
+
+    // Community-dwelling adults over 65:
+    // ~32% fall each year, ~10% of falls cause serious injury.
+    // Tinetti ME et al. (1988) N Engl J Med 319:1701-1707, PMID 3205267
+    // Adults catch 2-4 colds a year lasting about a week.
+    // Heikkinen T, Järvinen A (2003) Lancet 361:51-59, PMID 12517470
+    // Chronic-therapy adherence averages ~80% in trials.
+    // Osterberg L, Blaschke T (2005) N Engl J Med 353:487-497, PMID 16079372
+    pub fn older_adult() -> Self {
+        let mut generator = Self::new(0.8)
+            .and_then(|g| g.with_hazard(LifeEventKind::Fall, 0.32, 0.0))
+            .and_then(|g| g.with_hazard(LifeEventKind::IntercurrentInfection, 2.0, 7.0))
+            .and_then(|g| g.with_hazard(LifeEventKind::BedRest, 0.2, 5.0))
+            .expect("valid preset");
+        generator.injurious_fall_fraction = 0.1;
+        generator.post_fall_bed_rest_days = 14.0;
+        generator
+    }
+
+    pub fn sample<R: Rng>(
+        &self,
+        horizon_days: f64,
+        dose_days: &[f64],
+        rng: &mut R,
+    ) -> LifeEventSchedule {
+        let mut events = Vec::new();
+        for hazard in &self.hazards {
+            let rate_per_day = hazard.rate_per_year / 365.25;
+            if rate_per_day <= 0.0 {
+                continue;
+            }
+            let mut day = exponential(rng, 1.0 / rate_per_day);
+            while day < horizon_days {
+                let severity = rng.gen::<f64>();
+                events.push(LifeEvent {
+                    kind: hazard.kind,
+                    start_day: day,
+                    duration_days: exponential(rng, hazard.mean_duration_days),
+                    severity,
+                });
+                if hazard.kind == LifeEventKind::Fall
+                    && severity > 1.0 - self.injurious_fall_fraction
+                {
+                    events.push(LifeEvent {
+                        kind: LifeEventKind::BedRest,
+                        start_day: day,
+                        duration_days: self.post_fall_bed_rest_days,
+                        severity,
+                    });
+                }
+                day += exponential(rng, 1.0 / rate_per_day);
+            }
+        }
+        for &dose_day in dose_days.iter().filter(|d| **d < horizon_days) {
+            if rng.gen::<f64>() >= self.dose_adherence {
+                events.push(LifeEvent {
+                    kind: LifeEventKind::MissedDose,
+                    start_day: dose_day,
+                    duration_days: 0.0,
+                    severity: 1.0,
+                });
+            }
+        }
+        events.sort_by(|a, b| a.start_day.total_cmp(&b.start_day));
+        LifeEventSchedule { events }
+    }
+}
+
+fn exponential<R: Rng>(rng: &mut R, mean: f64) -> f64 {
+    if mean <= 0.0 {
+        return 0.0;
+    }
+    -mean * (1.0 - rng.gen::<f64>()).ln()
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LifeEventSchedule {
+    pub events: Vec<LifeEvent>,
+}
+
+impl LifeEventSchedule {
+    pub fn count(&self, kind: LifeEventKind) -> usize {
+        self.events.iter().filter(|e| e.kind == kind).count()
+    }
+
+    pub fn active_at(&self, day: f64) -> impl Iterator<Item = &LifeEvent> {
+        self.events.iter().filter(move |e| e.is_active(day))
+    }
+
+    pub fn is_active(&self, kind: LifeEventKind, day: f64) -> bool {
+        self.active_at(day).any(|e| e.kind == kind)
+    }
+
+    pub fn is_dose_missed(&self, dose_day: f64) -> bool {
+        self.events
+            .iter()
+            .any(|e| e.kind == LifeEventKind::MissedDose && (e.start_day - dose_day).abs() < 1e-9)
+    }
+
+    pub fn days_affected(&self, kind: LifeEventKind, horizon_days: usize) -> usize {
+        (0..horizon_days)
+            .filter(|d| self.is_active(kind, *d as f64))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pharmacology::bone_agents::{BisphosphonateRegimen, SkeletalBisphosphonate};
+    use crate::systems::skeletal::remodeling::BoneRemodelingModel;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_event_rates_match_hazards() {
+        let generator = LifeEventGenerator::older_adult();
+        let mut rng = StdRng::seed_from_u64(42);
+        let years = 200.0;
+        let schedule = generator.sample(years * 365.25, &[], &mut rng);
+        let falls_per_year = schedule.count(LifeEventKind::Fall) as f64 / years;
+        let infections_per_year =
+            schedule.count(LifeEventKind::IntercurrentInfection) as f64 / years;
+        assert!(falls_per_year > 0.2 && falls_per_year < 0.45);
+        assert!(infections_per_year > 1.7 && infections_per_year < 2.3);
+    }
+
+    #[test]
+    fn test_missed_doses_follow_adherence() {
+        let generator = LifeEventGenerator::new(0.7).unwrap();
+        let doses: Vec<f64> = (0..1000).map(|w| w as f64 * 7.0).collect();
+        let mut rng = StdRng::seed_from_u64(1);
+        let schedule = generator.sample(7000.0, &doses, &mut rng);
+        let missed = schedule.count(LifeEventKind::MissedDose) as f64 / doses.len() as f64;
+        assert!(missed > 0.25 && missed < 0.35);
+        assert!(LifeEventGenerator::new(1.2).is_err());
+        assert!(LifeEventGenerator::new(0.5)
+            .unwrap()
+            .with_hazard(LifeEventKind::MissedDose, 1.0, 0.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_injurious_falls_trigger_bed_rest() {
+        let mut generator = LifeEventGenerator::new(1.0)
+            .unwrap()
+            .with_hazard(LifeEventKind::Fall, 5.0, 0.0)
+            .unwrap();
+        generator.injurious_fall_fraction = 1.0;
+        generator.post_fall_bed_rest_days = 14.0;
+        let mut rng = StdRng::seed_from_u64(9);
+        let schedule = generator.sample(3650.0, &[], &mut rng);
+        assert_eq!(
+            schedule.count(LifeEventKind::Fall),
+            schedule.count(LifeEventKind::BedRest)
+        );
+        let fall = schedule.events[0];
+        assert!(schedule.is_active(LifeEventKind::BedRest, fall.start_day + 7.0));
+    }
+
+    #[test]
+    fn test_poor_adherence_blunts_bisphosphonate_response() {
+        let regimen = BisphosphonateRegimen::alendronate_70mg_weekly();
+        let days = 3 * 365;
+        let doses: Vec<f64> = (0..days)
+            .step_by(regimen.interval_days as usize)
+            .map(|d| d as f64)
+            .collect();
+        let gain = |adherence: f64| {
+            let mut rng = StdRng::seed_from_u64(5);
+            let schedule =
+                LifeEventGenerator::new(adherence)
+                    .unwrap()
+                    .sample(days as f64, &doses, &mut rng);
+            let mut spine = BoneRemodelingModel::postmenopausal_spine();
+            let mut pool = SkeletalBisphosphonate::new(regimen.drug);
+            for day in 0..days {
+                let d = day as f64;
+                if doses.contains(&d) && !schedule.is_dose_missed(d) {
+                    pool.dose(&regimen);
+                }
+                spine.set_modifiers(pool.remodeling_modifiers().unwrap());
+                spine.step_day();
+                pool.step_days(1.0);
+            }
+            spine.bmd_change_percent()
+        };
+        assert!(gain(0.4) < gain(1.0) - 0.5);
+    }
+}
@@ -0,0 +1,68 @@
+pub mod agents;
+pub mod bifurcation;
+pub mod checkpoint;
+pub mod cohort;
+pub mod distributed;
+pub mod engine;
+pub mod events;
+pub mod experiments;
+pub mod export;
+pub mod interaction;
+pub mod life_events;
+pub mod montecarlo;
+pub mod ode;
+pub mod physiome;
+pub mod population;
+pub mod recorder;
+pub mod replay;
+pub mod runs;
+pub mod scenario;
+pub mod steady_state;
+pub mod streaming;
+#[cfg(feature = "trace")]
+pub mod trace;
+
+pub use agents::{AgentKind, Entity, Position, Velocity, World};
+pub use bifurcation::{
+    continuation, linear_stability, Bifurcation, BifurcationKind, Branch, LinearStability,
+    Stability,
+};
+pub use checkpoint::{CheckpointPolicy, Checkpointed, SimulationRng, CHECKPOINT_FORMAT_VERSION};
+pub use cohort::{
+    clear_results, subject_seed, CohortConfig, CohortReport, CohortRunner, SubjectFailure,
+    SubjectRecord,
+};
+pub use distributed::{
+    partition_ranges, run_cohort_worker, run_worker, split_with_halo, stitch, Coordinator, Frame,
+    HaloSlab,
+};
+pub use engine::{Component, Coupling, Engine, EngineSnapshot, EntitySnapshot};
+pub use events::{Event, EventBus, EventKind, EventRecord};
+pub use experiments::{
+    Arm, DesignKind, Experiment, ExperimentResults, Factor, Level, MainEffect, Observation,
+};
+pub use export::{default_chunk_shape, read_npy, write_npy_tree, Dataset, ExportMetadata, Group};
+pub use interaction::{InputEffect, InteractionContext, InteractionMatrix, Interactive};
+pub use life_events::{
+    EventHazard, LifeEvent, LifeEventGenerator, LifeEventKind, LifeEventSchedule,
+};
+pub use montecarlo::{
+    Ensemble, EnsembleSummary, FailedReplicate, MonteCarlo, ParameterDistribution, ParameterDraw,
+    ParameterSpec, RecordedOutputs, Replicate,
+};
+pub use ode::{integrate, OdeSolution, OdeSystem, SolverMethod, SolverOptions, SolverStats};
+pub use physiome::{Compartment, Localized, Physiome, Transport, TransportKind};
+pub use population::{
+    bmd_retained_fraction, LocusFrequencies, PopulationSpec, SexCovariates, VirtualIndividual,
+    VirtualPopulation,
+};
+pub use recorder::{Column, CsvSink, JsonLinesSink, OutputSink, Recorder, TimeSeriesTable};
+pub use replay::{record, ExternalInputs, ReplayLog, ReplayStep, REPLAY_FORMAT_VERSION};
+pub use runs::{scenario_hash, NewRun, ParamFilter, ParamValue, RunRecord, RunStatus, RunStore};
+pub use scenario::{EntitySpec, Scenario, ScenarioRun, SimulationSettings};
+pub use steady_state::{equilibrium, relax, Equilibrium, SteadyStateOptions};
+pub use streaming::{
+    Backpressure, NextFrame, SimulationStream, StreamConfig, StreamFrame, StreamHub, Subscription,
+};
+#[cfg(feature = "trace")]
+pub use trace::{Verbosity, LOG_ENV};
@@ -0,0 +1,441 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::cohort::{CohortConfig, CohortRunner};
+use rand::rngs::StdRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ParameterDistribution {
+    Fixed(f64),
+    Uniform { low: f64, high: f64 },
+    Normal { mean: f64, sd: f64 },
+    // Parameterised as pharmacometric variability usually is: a typical
+    // value and the geometric SD (e.g. 1.3 for ~30% CV).
+    LogNormal { median: f64, geometric_sd: f64 },
+    Triangular { low: f64, mode: f64, high: f64 },
+}
+
+impl ParameterDistribution {
+    pub fn validate(&self) -> BiologyResult<()> {
+        let valid = match *self {
+            ParameterDistribution::Fixed(value) => value.is_finite(),
+            ParameterDistribution::Uniform { low, high } => low < high,
+            ParameterDistribution::Normal { mean, sd } => mean.is_finite() && sd >= 0.0,
+            ParameterDistribution::LogNormal {
+                median,
+                geometric_sd,
+            } => median > 0.0 && geometric_sd >= 1.0,
+            ParameterDistribution::Triangular { low, mode, high } => {
+                low < high && (low..=high).contains(&mode)
+            }
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(BiologyError::InvalidParameter(format!(
+                "invalid distribution {:?}",
+                self
+            )))
+        }
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        match *self {
+            ParameterDistribution::Fixed(value) => value,
+            ParameterDistribution::Uniform { low, high } => rng.gen_range(low..high),
+            ParameterDistribution::Normal { mean, sd } => mean + sd * standard_normal(rng),
+            ParameterDistribution::LogNormal {
+                median,
+                geometric_sd,
+            } => median * (geometric_sd.ln() * standard_normal(rng)).exp(),
+            ParameterDistribution::Triangular { low, mode, high } => {
+                // Inverse CDF.
+                let u: f64 = rng.gen();
+                let split = (mode - low) / (high - low);
+                if u < split {
+                    low + (u * (high - low) * (mode - low)).sqrt()
+                } else {
+                    high - ((1.0 - u) * (high - low) * (high - mode)).sqrt()
+                }
+            }
+        }
+    }
+}
+
+// Box-Muller.
+fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParameterSpec {
+    pub name: String,
+    pub distribution: ParameterDistribution,
+}
+
+// One replicate's sampled parameter values, keyed by name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParameterDraw {
+    pub values: BTreeMap<String, f64>,
+}
+
+impl ParameterDraw {
+    pub fn get(&self, name: &str) -> BiologyResult<f64> {
+        self.values
+            .get(name)
+            .copied()
+            .ok_or_else(|| BiologyError::InvalidParameter(format!("no parameter named {}", name)))
+    }
+}
+
+pub type RecordedOutputs = BTreeMap<String, f64>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replicate {
+    pub index: usize,
+    pub seed: u64,
+    pub parameters: ParameterDraw,
+    pub outputs: RecordedOutputs,
+}
+
+// A replicate whose model run failed, kept with the parameters it drew so
+// failures can be located in parameter space.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedReplicate {
+    pub index: usize,
+    pub seed: u64,
+    pub parameters: ParameterDraw,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EnsembleSummary {
+    pub n: usize,
+    // Replicates that failed and so are missing from the distribution.
+    pub failed: usize,
+    pub mean: f64,
+    pub sd: f64,
+    pub min: f64,
+    pub p05: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+// Linear interpolation between order statistics (Hyndman-Fan type 7, the R
+// and NumPy default) on an ascending slice.
+// Hyndman RJ, Fan Y (1996) Am Stat 50:361-365, doi 10.2307/2684934
+fn sorted_quantile(sorted: &[f64], q: f64) -> f64 {
+    let h = (sorted.len() - 1) as f64 * q.clamp(0.0, 1.0);
+    let lower = h.floor() as usize;
+    let upper = h.ceil() as usize;
+    sorted[lower] + (h - lower as f64) * (sorted[upper] - sorted[lower])
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ensemble {
+    pub master_seed: u64,
+    pub replicates: Vec<Replicate>,
+    pub failures: Vec<FailedReplicate>,
+}
+
+impl Ensemble {
+    pub fn failure_fraction(&self) -> f64 {
+        let total = self.replicates.len() + self.failures.len();
+        if total == 0 {
+            0.0
+        } else {
+            self.failures.len() as f64 / total as f64
+        }
+    }
+
+    pub fn output_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .replicates
+            .iter()
+            .flat_map(|r| r.outputs.keys().cloned())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    // Full distribution of one output across the replicates that recorded
+    // it, in replicate order.
+    pub fn samples(&self, output: &str) -> Vec<f64> {
+        self.replicates
+            .iter()
+            .filter_map(|r| r.outputs.get(output).copied())
+            .collect()
+    }
+
+    pub fn quantile(&self, output: &str, q: f64) -> Option<f64> {
+        let mut values = self.samples(output);
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        Some(sorted_quantile(&values, q))
+    }
+
+    pub fn summary(&self, output: &str) -> Option<EnsembleSummary> {
+        let mut values = self.samples(output);
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let n = values.len();
+        let mean = values.iter().sum::<f64>() / n as f64;
+        let sd = if n > 1 {
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        Some(EnsembleSummary {
+            n,
+            failed: self.failures.len(),
+            mean,
+            sd,
+            min: values[0],
+            p05: sorted_quantile(&values, 0.05),
+            median: sorted_quantile(&values, 0.5),
+            p95: sorted_quantile(&values, 0.95),
+            max: values[n - 1],
+        })
+    }
+
+    pub fn summaries(&self) -> BTreeMap<String, EnsembleSummary> {
+        self.output_names()
+            .into_iter()
+            .filter_map(|name| self.summary(&name).map(|s| (name, s)))
+            .collect()
+    }
+}
+
+// Propagates parameter uncertainty: each replicate draws its parameters and
+// then runs the model on the same per-replicate RNG, so an ensemble is fully
+// determined by the master seed regardless of thread count. A failed run is
+// never retried: a fresh draw would favour parameters the model tolerates
+// and bias the ensemble, so failures are reported alongside it instead.
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    pub parameters: Vec<ParameterSpec>,
+    pub config: CohortConfig,
+}
+
+impl MonteCarlo {
+    pub fn new(replicates: usize, master_seed: u64) -> Self {
+        Self {
+            parameters: Vec::new(),
+            config: CohortConfig::new(replicates, master_seed),
+        }
+    }
+
+    pub fn with_parameter(
+        mut self,
+        name: &str,
+        distribution: ParameterDistribution,
+    ) -> BiologyResult<Self> {
+        distribution.validate()?;
+        if self.parameters.iter().any(|p| p.name == name) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "parameter {} specified twice",
+                name
+            )));
+        }
+        self.parameters.push(ParameterSpec {
+            name: name.to_string(),
+            distribution,
+        });
+        Ok(self)
+    }
+
+    pub fn with_worker_threads(mut self, worker_threads: usize) -> Self {
+        self.config.worker_threads = worker_threads;
+        self
+    }
+
+    pub fn draw<R: Rng>(&self, rng: &mut R) -> ParameterDraw {
+        ParameterDraw {
+            values: self
+                .parameters
+                .iter()
+                .map(|p| (p.name.clone(), p.distribution.sample(rng)))
+                .collect(),
+        }
+    }
+
+    pub fn run<F>(&self, simulate: F) -> Result<Ensemble, Box<dyn std::error::Error>>
+    where
+        F: Fn(&ParameterDraw, &mut StdRng) -> BiologyResult<RecordedOutputs> + Sync,
+    {
+        let report = CohortRunner::new(self.config)?.run(|_, rng| {
+            let parameters = self.draw(rng);
+            let outputs = simulate(&parameters, rng).map_err(|e| e.to_string());
+            Ok((parameters, outputs))
+        })?;
+        let mut replicates = Vec::new();
+        let mut failures = Vec::new();
+        for record in report.records {
+            let (parameters, outputs) = record.result;
+            match outputs {
+                Ok(outputs) => replicates.push(Replicate {
+                    index: record.subject,
+                    seed: record.seed,
+                    parameters,
+                    outputs,
+                }),
+                Err(message) => failures.push(FailedReplicate {
+                    index: record.subject,
+                    seed: record.seed,
+                    parameters,
+                    message,
+                }),
+            }
+        }
+        Ok(Ensemble {
+            master_seed: self.config.base_seed,
+            replicates,
+            failures,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+    use rand::SeedableRng;
+
+    fn glucose_study(threads: usize, seed: u64) -> Ensemble {
+        MonteCarlo::new(40, seed)
+            .with_parameter(
+                "carbohydrate_g",
+                ParameterDistribution::Triangular {
+                    low: 30.0,
+                    mode: 60.0,
+                    high: 90.0,
+                },
+            )
+            .unwrap()
+            .with_worker_threads(threads)
+            .run(|draw, _| {
+                let mut model = GlucoseInsulinModel::new_healthy();
+                model.ingest_carbohydrate(draw.get("carbohydrate_g")?)?;
+                model.run_minutes(60);
+                let mut outputs = RecordedOutputs::new();
+                outputs.insert("glucose_60min".to_string(), model.glucose_mg_dl);
+                Ok(outputs)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_distributions_match_their_moments() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let n = 20_000;
+        let mean = |d: ParameterDistribution, rng: &mut StdRng| {
+            (0..n).map(|_| d.sample(rng)).sum::<f64>() / n as f64
+        };
+        let normal = ParameterDistribution::Normal {
+            mean: 10.0,
+            sd: 2.0,
+        };
+        assert!((mean(normal, &mut rng) - 10.0).abs() < 0.1);
+        let triangular = ParameterDistribution::Triangular {
+            low: 0.0,
+            mode: 3.0,
+            high: 6.0,
+        };
+        assert!((mean(triangular, &mut rng) - 3.0).abs() < 0.1);
+        let lognormal = ParameterDistribution::LogNormal {
+            median: 5.0,
+            geometric_sd: 1.5,
+        };
+        let mut draws: Vec<f64> = (0..n).map(|_| lognormal.sample(&mut rng)).collect();
+        draws.sort_by(f64::total_cmp);
+        assert!((sorted_quantile(&draws, 0.5) - 5.0).abs() < 0.1);
+        assert!(draws[0] > 0.0);
+        assert!(ParameterDistribution::Uniform {
+            low: 2.0,
+            high: 1.0
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_master_seed_reproduces_ensemble_across_thread_counts() {
+        let serial = glucose_study(1, 17);
+        let parallel = glucose_study(4, 17);
+        assert_eq!(serial.replicates, parallel.replicates);
+        assert_ne!(glucose_study(4, 18).replicates, serial.replicates);
+    }
+
+    #[test]
+    fn test_ensemble_statistics() {
+        let ensemble = glucose_study(4, 3);
+        assert_eq!(ensemble.output_names(), vec!["glucose_60min".to_string()]);
+        let summary = ensemble.summary("glucose_60min").unwrap();
+        assert_eq!(summary.n, 40);
+        assert!(summary.min <= summary.p05 && summary.p05 <= summary.median);
+        assert!(summary.median <= summary.p95 && summary.p95 <= summary.max);
+        assert!(summary.sd > 0.0);
+        // More carbohydrate, higher glucose: the ordering of draws carries
+        // through to the output.
+        let low = ensemble
+            .replicates
+            .iter()
+            .min_by(|a, b| {
+                a.parameters.values["carbohydrate_g"]
+                    .total_cmp(&b.parameters.values["carbohydrate_g"])
+            })
+            .unwrap();
+        assert!(low.outputs["glucose_60min"] < summary.median);
+        assert!(ensemble.summary("missing").is_none());
+        assert_eq!(sorted_quantile(&[1.0, 2.0, 3.0, 4.0], 0.5), 2.5);
+    }
+
+    #[test]
+    fn test_failed_draws_are_reported_not_redrawn() {
+        let study = MonteCarlo::new(400, 9)
+            .with_parameter(
+                "k",
+                ParameterDistribution::Uniform {
+                    low: 0.0,
+                    high: 1.0,
+                },
+            )
+            .unwrap();
+        let ensemble = study
+            .run(|draw, _| {
+                let k = draw.get("k")?;
+                if k > 0.5 {
+                    return Err(BiologyError::InvalidValue("unstable".to_string()));
+                }
+                Ok([("k".to_string(), k)].into_iter().collect())
+            })
+            .unwrap();
+        let summary = ensemble.summary("k").unwrap();
+        assert_eq!(summary.n + summary.failed, 400);
+        assert!((ensemble.failure_fraction() - 0.5).abs() < 0.1);
+        assert!(summary.max <= 0.5);
+        assert!(ensemble
+            .failures
+            .iter()
+            .all(|f| f.parameters.values["k"] > 0.5 && f.message.contains("unstable")));
+    }
+
+    #[test]
+    fn test_duplicate_parameter_rejected() {
+        let spec = MonteCarlo::new(1, 0)
+            .with_parameter("k", ParameterDistribution::Fixed(1.0))
+            .unwrap();
+        assert!(spec
+            .with_parameter("k", ParameterDistribution::Fixed(2.0))
+            .is_err());
+    }
+}
@@ -0,0 +1,668 @@
+use crate::biology::{BiologyError, BiologyResult};
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, debug_span, trace, warn};
+
+// dy/dt = f(t, y), optionally with an analytic Jacobian df/dy. Without one,
+// the implicit solver differentiates `rhs` numerically.
+pub trait OdeSystem {
+    fn dimension(&self) -> usize;
+    fn rhs(&self, t: f64, y: &[f64], dydt: &mut [f64]);
+    fn jacobian(&self, _t: f64, _y: &[f64]) -> Option<DMatrix<f64>> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SolverMethod {
+    // Explicit Dormand-Prince 5(4); cheap per step, unstable on stiff
+    // problems unless the step is tiny.
+    // Dormand JR, Prince PJ (1980) J Comput Appl Math 6:19-26
+    DormandPrince,
+    // Implicit variable-step BDF of orders 1-2 with Newton iterations;
+    // A-stable, so the step follows accuracy rather than stability.
+    Bdf,
+    // Starts explicit and switches to BDF when the stiffness test fires,
+    // and back once the problem relaxes.
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SolverOptions {
+    pub method: SolverMethod,
+    pub rtol: f64,
+    pub atol: f64,
+    pub initial_step: Option<f64>,
+    pub max_step: f64,
+    pub max_steps: usize,
+}
+
+impl Default for SolverOptions {
+    fn default() -> Self {
+        Self {
+            method: SolverMethod::Auto,
+            rtol: 1e-6,
+            atol: 1e-10,
+            initial_step: None,
+            max_step: f64::INFINITY,
+            max_steps: 100_000,
+        }
+    }
+}
+
+impl SolverOptions {
+    pub fn with_method(mut self, method: SolverMethod) -> Self {
+        self.method = method;
+        self
+    }
+
+    pub fn with_tolerances(mut self, rtol: f64, atol: f64) -> Self {
+        self.rtol = rtol;
+        self.atol = atol;
+        self
+    }
+
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    fn validate(&self) -> BiologyResult<()> {
+        if !(self.rtol > 0.0 && self.atol > 0.0 && self.max_step > 0.0 && self.max_steps > 0) {
+            return Err(BiologyError::InvalidParameter(
+                "tolerances, maximum step and step budget must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SolverStats {
+    pub accepted_steps: usize,
+    pub rejected_steps: usize,
+    pub rhs_evaluations: usize,
+    pub jacobian_evaluations: usize,
+    pub newton_failures: usize,
+    pub implicit_steps: usize,
+    pub method_switches: usize,
+}
+
+// Accepted steps, starting with the initial state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OdeSolution {
+    pub t: Vec<f64>,
+    pub y: Vec<Vec<f64>>,
+    pub stats: SolverStats,
+}
+
+impl OdeSolution {
+    pub fn last(&self) -> &[f64] {
+        self.y.last().expect("solution holds the initial state")
+    }
+
+    // Linear interpolation between accepted steps.
+    pub fn at(&self, time: f64) -> Option<Vec<f64>> {
+        let i = self.t.iter().position(|&t| t >= time)?;
+        if i == 0 {
+            return (time == self.t[0]).then(|| self.y[0].clone());
+        }
+        let w = (time - self.t[i - 1]) / (self.t[i] - self.t[i - 1]);
+        Some(
+            self.y[i - 1]
+                .iter()
+                .zip(&self.y[i])
+                .map(|(a, b)| a + w * (b - a))
+                .collect(),
+        )
+    }
+}
+
+// Weighted RMS norm used for every error and convergence test.
+fn wrms(v: &[f64], scale_a: &[f64], scale_b: &[f64], o: &SolverOptions) -> f64 {
+    let sum: f64 = v
+        .iter()
+        .zip(scale_a.iter().zip(scale_b))
+        .map(|(e, (a, b))| {
+            let w = o.atol + o.rtol * a.abs().max(b.abs());
+            (e / w).powi(2)
+        })
+        .sum();
+    (sum / v.len().max(1) as f64).sqrt()
+}
+
+pub(crate) struct Counter<'a, S: OdeSystem> {
+    pub(crate) system: &'a S,
+    pub(crate) stats: SolverStats,
+}
+
+impl<S: OdeSystem> Counter<'_, S> {
+    pub(crate) fn f(&mut self, t: f64, y: &[f64]) -> Vec<f64> {
+        self.stats.rhs_evaluations += 1;
+        let mut dydt = vec![0.0; y.len()];
+        self.system.rhs(t, y, &mut dydt);
+        dydt
+    }
+
+    pub(crate) fn jacobian(&mut self, t: f64, y: &[f64], f0: &[f64]) -> DMatrix<f64> {
+        self.stats.jacobian_evaluations += 1;
+        if let Some(j) = self.system.jacobian(t, y) {
+            return j;
+        }
+        // Forward differences, one column per state.
+        let n = y.len();
+        let mut j = DMatrix::zeros(n, n);
+        let mut shifted = y.to_vec();
+        for col in 0..n {
+            let delta = f64::EPSILON.sqrt() * y[col].abs().max(1e-8);
+            shifted[col] = y[col] + delta;
+            let f1 = self.f(t, &shifted);
+            for row in 0..n {
+                j[(row, col)] = (f1[row] - f0[row]) / delta;
+            }
+            shifted[col] = y[col];
+        }
+        j
+    }
+}
+
+// Dormand-Prince 5(4) tableau.
+const C: [f64; 7] = [0.0, 0.2, 0.3, 0.8, 8.0 / 9.0, 1.0, 1.0];
+const A: [[f64; 6]; 7] = [
+    [0.0; 6],
+    [0.2, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+        0.0,
+        0.0,
+    ],
+    [
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+        0.0,
+    ],
+    [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+// Fifth-order weights minus the embedded fourth-order ones.
+const E: [f64; 7] = [
+    35.0 / 384.0 - 5179.0 / 57600.0,
+    0.0,
+    500.0 / 1113.0 - 7571.0 / 16695.0,
+    125.0 / 192.0 - 393.0 / 640.0,
+    -2187.0 / 6784.0 + 92097.0 / 339200.0,
+    11.0 / 84.0 - 187.0 / 2100.0,
+    -1.0 / 40.0,
+];
+
+// Hairer's test: h|lambda| estimated from the last two stages (both at
+// t + h) beyond 3.25, the edge of the DOPRI5 stability region, on 15
+// accepted steps marks the problem stiff; 6 calm steps in a row clear the
+// count.
+// Hairer E, Wanner G (1996) Solving ODEs II, 2nd ed., Springer, §IV.2
+const STIFF_H_LAMBDA: f64 = 3.25;
+const STIFF_STEPS_TO_SWITCH: usize = 15;
+const CALM_STEPS_TO_CLEAR: usize = 6;
+const NONSTIFF_STEPS_TO_SWITCH: usize = 25;
+
+struct ExplicitStep {
+    y: Vec<f64>,
+    f_end: Vec<f64>,
+    error: f64,
+    h_lambda: f64,
+}
+
+fn dormand_prince_step<S: OdeSystem>(
+    counter: &mut Counter<S>,
+    t: f64,
+    y: &[f64],
+    f0: &[f64],
+    h: f64,
+    o: &SolverOptions,
+) -> ExplicitStep {
+    let n = y.len();
+    let mut k: Vec<Vec<f64>> = vec![f0.to_vec()];
+    let mut stage = vec![0.0; n];
+    let mut previous_stage = y.to_vec();
+    for s in 1..7 {
+        previous_stage.clone_from(&stage);
+        for i in 0..n {
+            stage[i] = y[i] + h * (0..s).map(|j| A[s][j] * k[j][i]).sum::<f64>();
+        }
+        k.push(counter.f(t + C[s] * h, &stage));
+    }
+    let error: Vec<f64> = (0..n)
+        .map(|i| h * (0..7).map(|s| E[s] * k[s][i]).sum::<f64>())
+        .collect();
+    let num: f64 = (0..n).map(|i| (k[6][i] - k[5][i]).powi(2)).sum();
+    let den: f64 = (0..n).map(|i| (stage[i] - previous_stage[i]).powi(2)).sum();
+    let h_lambda = if den > 0.0 {
+        h * (num / den).sqrt()
+    } else {
+        0.0
+    };
+    ExplicitStep {
+        error: wrms(&error, y, &stage, o),
+        h_lambda,
+        f_end: k[6].clone(),
+        y: stage,
+    }
+}
+
+// Accepted points kept for the BDF predictor and formula.
+struct History {
+    t: Vec<f64>,
+    y: Vec<Vec<f64>>,
+}
+
+impl History {
+    fn push(&mut self, t: f64, y: Vec<f64>) {
+        self.t.push(t);
+        self.y.push(y);
+        if self.t.len() > 3 {
+            self.t.remove(0);
+            self.y.remove(0);
+        }
+    }
+
+    fn reset(&mut self, t: f64, y: Vec<f64>) {
+        self.t = vec![t];
+        self.y = vec![y];
+    }
+}
+
+struct ImplicitStep {
+    y: Vec<f64>,
+    error: f64,
+    order: usize,
+    h_norm_j: f64,
+}
+
+// One variable-step BDF step from the history's newest point. The formula
+// interpolates y_{n+1} with the last `order` accepted values and matches
+// its derivative to f at t_{n+1}:
+//   a0 y_{n+1} + a1 y_n + a2 y_{n-1} = f(t_{n+1}, y_{n+1})
+// The local error follows Milne's device from the corrector-predictor
+// difference, with the predictor extrapolating one order higher.
+// Shampine LF, Reichelt MW (1997) SIAM J Sci Comput 18:1-22
+fn bdf_step<S: OdeSystem>(
+    counter: &mut Counter<S>,
+    history: &History,
+    f_n: &[f64],
+    h: f64,
+    o: &SolverOptions,
+) -> Option<ImplicitStep> {
+    let n = f_n.len();
+    let last = history.t.len() - 1;
+    let (t_n, y_n) = (history.t[last], &history.y[last]);
+    let t_next = t_n + h;
+    let order = if last >= 2 { 2 } else { 1 };
+
+    let (a0, psi, predictor, q, p) = if order == 2 {
+        let h1 = t_n - history.t[last - 1];
+        let h2 = history.t[last - 1] - history.t[last - 2];
+        let y_1 = &history.y[last - 1];
+        let y_2 = &history.y[last - 2];
+        let a0 = 1.0 / h + 1.0 / (h + h1);
+        let a1 = -(h + h1) / (h * h1);
+        let a2 = h / (h1 * (h + h1));
+        let psi: Vec<f64> = (0..n).map(|i| -(a1 * y_n[i] + a2 * y_1[i]) / a0).collect();
+        // Quadratic extrapolation through the last three points.
+        let (t0, t1, t2) = (history.t[last - 2], history.t[last - 1], t_n);
+        let l0 = (t_next - t1) * (t_next - t2) / ((t0 - t1) * (t0 - t2));
+        let l1 = (t_next - t0) * (t_next - t2) / ((t1 - t0) * (t1 - t2));
+        let l2 = (t_next - t0) * (t_next - t1) / ((t2 - t0) * (t2 - t1));
+        let predictor: Vec<f64> = (0..n)
+            .map(|i| l0 * y_2[i] + l1 * y_1[i] + l2 * y_n[i])
+            .collect();
+        let q = h * (h + h1) / (6.0 * a0);
+        let p = h * (h + h1) * (h + h1 + h2) / 6.0;
+        (a0, psi, predictor, q, p)
+    } else {
+        // Backward Euler, predicted by explicit Euler.
+        let predictor: Vec<f64> = (0..n).map(|i| y_n[i] + h * f_n[i]).collect();
+        (1.0 / h, y_n.clone(), predictor, h * h / 2.0, h * h / 2.0)
+    };
+    let gamma = 1.0 / a0;
+
+    // Newton on G(y) = y - psi - gamma f(y) with the Jacobian at the
+    // predictor held fixed.
+    let mut y = predictor.clone();
+    let f_pred = counter.f(t_next, &y);
+    let jacobian = counter.jacobian(t_next, &y, &f_pred);
+    let h_norm_j = h * jacobian
+        .row_iter()
+        .map(|r| r.abs().sum())
+        .fold(0.0, f64::max);
+    let lu = (DMatrix::identity(n, n) - gamma * jacobian).lu();
+    let mut f_y = f_pred;
+    let mut previous_delta = f64::INFINITY;
+    let mut converged = false;
+    for _ in 0..7 {
+        let residual = DVector::from_iterator(n, (0..n).map(|i| -(y[i] - psi[i] - gamma * f_y[i])));
+        let delta = lu.solve(&residual)?;
+        for i in 0..n {
+            y[i] += delta[i];
+        }
+        let norm = wrms(delta.as_slice(), &y, y_n, o);
+        if !norm.is_finite() || norm > 2.0 * previous_delta {
+            break;
+        }
+        if norm < 1e-3 {
+            converged = true;
+            break;
+        }
+        previous_delta = norm;
+        f_y = counter.f(t_next, &y);
+    }
+    if !converged {
+        counter.stats.newton_failures += 1;
+        return None;
+    }
+    let factor = q / (p + q);
+    let error: Vec<f64> = (0..n).map(|i| factor * (y[i] - predictor[i])).collect();
+    Some(ImplicitStep {
+        error: wrms(&error, &y, y_n, o),
+        y,
+        order,
+        h_norm_j,
+    })
+}
+
+// Integrates from `t0` to `t_end`, recording every accepted step.
+pub fn integrate<S: OdeSystem>(
+    system: &S,
+    t0: f64,
+    y0: &[f64],
+    t_end: f64,
+    options: SolverOptions,
+) -> BiologyResult<OdeSolution> {
+    options.validate()?;
+    if y0.len() != system.dimension() {
+        return Err(BiologyError::InvalidValue(format!(
+            "initial state has {} values for a {}-dimensional system",
+            y0.len(),
+            system.dimension()
+        )));
+    }
+    if t_end.is_nan() || t_end <= t0 {
+        return Err(BiologyError::InvalidParameter(
+            "end time must follow start time".to_string(),
+        ));
+    }
+    let _span = debug_span!(
+        "integrate",
+        dimension = y0.len(),
+        t0,
+        t_end,
+        method = ?options.method
+    )
+    .entered();
+    let o = &options;
+    let mut counter = Counter {
+        system,
+        stats: SolverStats::default(),
+    };
+    let mut t = t0;
+    let mut y = y0.to_vec();
+    let mut f = counter.f(t, &y);
+    let mut solution = OdeSolution {
+        t: vec![t0],
+        y: vec![y.clone()],
+        stats: SolverStats::default(),
+    };
+    let span = t_end - t0;
+    let mut h = options.initial_step.unwrap_or_else(|| {
+        // Hairer-Wanner starting step from |y| / |f|.
+        let d0 = wrms(&y, &y, &y, o);
+        let d1 = wrms(&f, &y, &y, o);
+        if d0 < 1e-5 || d1 < 1e-5 {
+            1e-6 * span
+        } else {
+            (0.01 * d0 / d1).min(span)
+        }
+    });
+    let mut implicit = options.method == SolverMethod::Bdf;
+    let mut history = History {
+        t: vec![t],
+        y: vec![y.clone()],
+    };
+    let (mut stiff_count, mut calm_run, mut nonstiff_run) = (0, 0, 0);
+
+    while t < t_end {
+        if solution.stats.accepted_steps + solution.stats.rejected_steps >= options.max_steps {
+            warn!(t, max_steps = options.max_steps, "step budget exhausted");
+            return Err(BiologyError::InvalidState(format!(
+                "step budget of {} exhausted at t = {}",
+                options.max_steps, t
+            )));
+        }
+        h = h.min(options.max_step).min(t_end - t);
+        if h <= 1e-14 * t.abs().max(1.0) {
+            warn!(t, h, "step size underflow");
+            return Err(BiologyError::InvalidState(format!(
+                "step size underflow at t = {}",
+                t
+            )));
+        }
+
+        if implicit {
+            let Some(step) = bdf_step(&mut counter, &history, &f, h, o) else {
+                debug!(t, h, "BDF step rejected: Newton iteration diverged");
+                solution.stats.rejected_steps += 1;
+                h *= 0.25;
+                continue;
+            };
+            let exponent = 1.0 / (step.order as f64 + 1.0);
+            let scale = 0.9 * step.error.max(1e-10).powf(-exponent);
+            if step.error > 1.0 {
+                debug!(t, h, error = step.error, "BDF step rejected");
+                solution.stats.rejected_steps += 1;
+                h *= scale.clamp(0.2, 0.9);
+                continue;
+            }
+            t += h;
+            y = step.y;
+            f = counter.f(t, &y);
+            history.push(t, y.clone());
+            solution.stats.implicit_steps += 1;
+            // Step-size ratios above ~2 degrade BDF2 stability.
+            let h_next = h * scale.clamp(0.5, 2.0);
+            if options.method == SolverMethod::Auto {
+                // Explicit would be stable at this step if h|J| stays
+                // inside its stability region.
+                nonstiff_run = if h_next * step.h_norm_j / h < STIFF_H_LAMBDA * 0.5 {
+                    nonstiff_run + 1
+                } else {
+                    0
+                };
+                if nonstiff_run >= NONSTIFF_STEPS_TO_SWITCH {
+                    debug!(t, "problem no longer stiff, switching to Dormand-Prince");
+                    implicit = false;
+                    nonstiff_run = 0;
+                    solution.stats.method_switches += 1;
+                }
+            }
+            h = h_next;
+        } else {
+            let step = dormand_prince_step(&mut counter, t, &y, &f, h, o);
+            let scale = 0.9 * step.error.max(1e-10).powf(-0.2);
+            if step.error > 1.0 || !step.error.is_finite() {
+                debug!(t, h, error = step.error, "Dormand-Prince step rejected");
+                solution.stats.rejected_steps += 1;
+                h *= if step.error.is_finite() {
+                    scale.clamp(0.2, 0.9)
+                } else {
+                    0.2
+                };
+                continue;
+            }
+            t += h;
+            y = step.y;
+            f = step.f_end;
+            history.push(t, y.clone());
+            h *= scale.clamp(0.2, 5.0);
+            if options.method == SolverMethod::Auto {
+                if step.h_lambda > STIFF_H_LAMBDA {
+                    stiff_count += 1;
+                    calm_run = 0;
+                } else {
+                    calm_run += 1;
+                    if calm_run >= CALM_STEPS_TO_CLEAR {
+                        stiff_count = 0;
+                    }
+                }
+                if stiff_count >= STIFF_STEPS_TO_SWITCH {
+                    debug!(
+                        t,
+                        h_lambda = step.h_lambda,
+                        "stiffness detected, switching to BDF"
+                    );
+                    implicit = true;
+                    stiff_count = 0;
+                    // Restart BDF at order 1 from the current point.
+                    history.reset(t, y.clone());
+                    solution.stats.method_switches += 1;
+                }
+            }
+        }
+        trace!(t, h_next = h, implicit, "step accepted");
+        solution.stats.accepted_steps += 1;
+        solution.t.push(t);
+        solution.y.push(y.clone());
+    }
+    let stats = solution.stats;
+    solution.stats = SolverStats {
+        rhs_evaluations: counter.stats.rhs_evaluations,
+        jacobian_evaluations: counter.stats.jacobian_evaluations,
+        newton_failures: counter.stats.newton_failures,
+        ..stats
+    };
+    debug!(
+        accepted = solution.stats.accepted_steps,
+        rejected = solution.stats.rejected_steps,
+        rhs_evaluations = solution.stats.rhs_evaluations,
+        method_switches = solution.stats.method_switches,
+        "integration finished"
+    );
+    Ok(solution)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Decay(f64);
+
+    impl OdeSystem for Decay {
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn rhs(&self, _t: f64, y: &[f64], dydt: &mut [f64]) {
+            dydt[0] = -self.0 * y[0];
+        }
+    }
+
+    // Slow manifold y2 = cos(t) attracting at rate 1e4.
+    struct StiffForcing;
+
+    impl OdeSystem for StiffForcing {
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn rhs(&self, t: f64, y: &[f64], dydt: &mut [f64]) {
+            dydt[0] = -1e4 * (y[0] - t.cos()) - t.sin();
+        }
+    }
+
+    #[test]
+    fn test_explicit_and_implicit_match_exponential_decay() {
+        for method in [SolverMethod::DormandPrince, SolverMethod::Bdf] {
+            let options = SolverOptions::default()
+                .with_method(method)
+                .with_tolerances(1e-8, 1e-12);
+            let solution = integrate(&Decay(0.5), 0.0, &[2.0], 4.0, options).unwrap();
+            let exact = 2.0 * (-2.0f64).exp();
+            assert!(
+                (solution.last()[0] - exact).abs() < 1e-5,
+                "{:?}: {} vs {}",
+                method,
+                solution.last()[0],
+                exact
+            );
+            assert_eq!(*solution.t.last().unwrap(), 4.0);
+        }
+    }
+
+    #[test]
+    fn test_implicit_takes_large_steps_on_stiff_problem() {
+        let bdf = integrate(
+            &StiffForcing,
+            0.0,
+            &[0.0],
+            10.0,
+            SolverOptions::default()
+                .with_method(SolverMethod::Bdf)
+                .with_tolerances(1e-6, 1e-9),
+        )
+        .unwrap();
+        assert!((bdf.last()[0] - 10f64.cos()).abs() < 1e-4);
+        let explicit = integrate(
+            &StiffForcing,
+            0.0,
+            &[0.0],
+            10.0,
+            SolverOptions::default()
+                .with_method(SolverMethod::DormandPrince)
+                .with_tolerances(1e-6, 1e-9),
+        )
+        .unwrap();
+        // Stability caps DOPRI5 near h = 3.3e-4 over the whole interval.
+        assert!(explicit.stats.accepted_steps > 20 * bdf.stats.accepted_steps);
+        let auto = integrate(
+            &StiffForcing,
+            0.0,
+            &[0.0],
+            10.0,
+            SolverOptions::default().with_tolerances(1e-6, 1e-9),
+        )
+        .unwrap();
+        assert!(auto.stats.method_switches >= 1);
+        assert!(auto.stats.accepted_steps < explicit.stats.accepted_steps / 5);
+        assert!((auto.last()[0] - 10f64.cos()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_budget_and_validation_errors() {
+        let options = SolverOptions::default()
+            .with_method(SolverMethod::DormandPrince)
+            .with_max_steps(50);
+        assert!(integrate(&StiffForcing, 0.0, &[0.0], 10.0, options).is_err());
+        assert!(integrate(&Decay(1.0), 0.0, &[1.0, 2.0], 1.0, SolverOptions::default()).is_err());
+        assert!(integrate(&Decay(1.0), 1.0, &[1.0], 0.0, SolverOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_interpolated_output() {
+        let solution = integrate(&Decay(1.0), 0.0, &[1.0], 2.0, SolverOptions::default()).unwrap();
+        let mid = solution.at(1.0).unwrap()[0];
+        assert!((mid - (-1.0f64).exp()).abs() < 1e-3);
+        assert!(solution.at(3.0).is_none());
+    }
+}
@@ -0,0 +1,491 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::cardiovascular::blood_cells::{Neutrophil, NeutrophilLocation};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::trace;
+
+// Something that lives in exactly one named compartment and may only move
+// along the routes its biology allows.
+pub trait Localized {
+    fn compartment(&self) -> &'static str;
+    fn can_move_to(&self, compartment: &str) -> bool;
+    fn set_compartment(&mut self, compartment: &str) -> BiologyResult<()>;
+}
+
+// A well-mixed space: solute concentrations in mmol/L, its own
+// temperature and pH, and the ids of the entities it contains.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Compartment {
+    pub volume_l: f64,
+    pub temperature_c: f64,
+    pub ph: f64,
+    pub concentrations_mmol_l: BTreeMap<String, f64>,
+    pub entities: Vec<String>,
+}
+
+impl Compartment {
+    pub fn new(volume_l: f64) -> BiologyResult<Self> {
+        if volume_l.is_nan() || volume_l <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "compartment volume must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            volume_l,
+            temperature_c: 37.0,
+            ph: 7.4,
+            concentrations_mmol_l: BTreeMap::new(),
+            entities: Vec::new(),
+        })
+    }
+
+    pub fn with_concentration(mut self, solute: &str, mmol_l: f64) -> Self {
+        self.concentrations_mmol_l
+            .insert(solute.to_string(), mmol_l);
+        self
+    }
+
+    pub fn with_temperature(mut self, temperature_c: f64) -> Self {
+        self.temperature_c = temperature_c;
+        self
+    }
+
+    pub fn with_ph(mut self, ph: f64) -> Self {
+        self.ph = ph;
+        self
+    }
+
+    pub fn concentration(&self, solute: &str) -> f64 {
+        self.concentrations_mmol_l
+            .get(solute)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    pub fn amount_mmol(&self, solute: &str) -> f64 {
+        self.concentration(solute) * self.volume_l
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TransportKind {
+    // Passive exchange across a barrier: flux = PS * (C_from - C_to), so
+    // solute also flows back when the gradient reverses.
+    Diffusive { permeability_l_min: f64 },
+    // One-way carriage by a fluid stream: flux = Q * C_from.
+    Bulk { flow_l_min: f64 },
+}
+
+impl TransportKind {
+    fn rate_l_min(self) -> f64 {
+        match self {
+            TransportKind::Diffusive { permeability_l_min } => permeability_l_min,
+            TransportKind::Bulk { flow_l_min } => flow_l_min,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transport {
+    pub from: String,
+    pub to: String,
+    pub solute: String,
+    pub kind: TransportKind,
+}
+
+impl Transport {
+    pub fn diffusive(from: &str, to: &str, solute: &str, permeability_l_min: f64) -> Self {
+        Self::new(
+            from,
+            to,
+            solute,
+            TransportKind::Diffusive { permeability_l_min },
+        )
+    }
+
+    pub fn bulk(from: &str, to: &str, solute: &str, flow_l_min: f64) -> Self {
+        Self::new(from, to, solute, TransportKind::Bulk { flow_l_min })
+    }
+
+    fn new(from: &str, to: &str, solute: &str, kind: TransportKind) -> Self {
+        Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            solute: solute.to_string(),
+            kind,
+        }
+    }
+}
+
+// Named compartments and the transport routes between them, advanced on
+// the engine's minute clock. Fluxes within a step are all evaluated from
+// the same state, so route order does not matter.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Physiome {
+    pub time_minutes: f64,
+    compartments: BTreeMap<String, Compartment>,
+    routes: Vec<Transport>,
+}
+
+// Body water of a 70 kg adult: 3 L plasma, 11 L interstitial fluid and
+// 28 L intracellular fluid; capillary walls pass small solutes freely,
+// cell membranes only through carriers.
+// Guyton AC, Hall JE. Textbook of Medical Physiology, 13th ed., ch. 25
+const PLASMA_L: f64 = 3.0;
+const INTERSTITIAL_L: f64 = 11.0;
+const INTRACELLULAR_L: f64 = 28.0;
+// Whole-body capillary permeability-surface product for glucose, from
+// single-pass indicator-diffusion extraction.
+// Crone C (1963) Acta Physiol Scand 58:292-305
+const GLUCOSE_CAPILLARY_PS_L_MIN: f64 = 1.5;
+
+impl Physiome {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Plasma, interstitial and intracellular fluid at fasting
+    // electrolyte and glucose levels, with capillary glucose exchange.
+    pub fn body_fluids() -> Self {
+        let mut physiome = Self::new();
+        let plasma = Compartment::new(PLASMA_L)
+            .expect("positive volume")
+            .with_concentration("sodium", 142.0)
+            .with_concentration("potassium", 4.2)
+            .with_concentration("glucose", 5.0);
+        let interstitial = Compartment::new(INTERSTITIAL_L)
+            .expect("positive volume")
+            .with_concentration("sodium", 139.0)
+            .with_concentration("potassium", 4.0)
+            .with_concentration("glucose", 5.0);
+        let intracellular = Compartment::new(INTRACELLULAR_L)
+            .expect("positive volume")
+            .with_concentration("sodium", 14.0)
+            .with_concentration("potassium", 140.0)
+            .with_ph(7.0);
+        for (name, compartment) in [
+            ("plasma", plasma),
+            ("interstitial", interstitial),
+            ("intracellular", intracellular),
+        ] {
+            physiome
+                .add_compartment(name, compartment)
+                .expect("distinct names");
+        }
+        physiome
+            .connect(Transport::diffusive(
+                "plasma",
+                "interstitial",
+                "glucose",
+                GLUCOSE_CAPILLARY_PS_L_MIN,
+            ))
+            .expect("compartments exist");
+        physiome
+    }
+
+    pub fn add_compartment(&mut self, name: &str, compartment: Compartment) -> BiologyResult<()> {
+        if name.is_empty() {
+            return Err(BiologyError::InvalidParameter(
+                "compartment name must be non-empty".to_string(),
+            ));
+        }
+        if self.compartments.contains_key(name) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "compartment {} defined twice",
+                name
+            )));
+        }
+        self.compartments.insert(name.to_string(), compartment);
+        Ok(())
+    }
+
+    pub fn compartment(&self, name: &str) -> Option<&Compartment> {
+        self.compartments.get(name)
+    }
+
+    pub fn compartment_names(&self) -> impl Iterator<Item = &str> {
+        self.compartments.keys().map(String::as_str)
+    }
+
+    fn compartment_mut(&mut self, name: &str) -> BiologyResult<&mut Compartment> {
+        self.compartments
+            .get_mut(name)
+            .ok_or_else(|| BiologyError::InvalidParameter(format!("no compartment named {}", name)))
+    }
+
+    pub fn set_concentration(
+        &mut self,
+        compartment: &str,
+        solute: &str,
+        mmol_l: f64,
+    ) -> BiologyResult<()> {
+        if mmol_l.is_nan() || mmol_l < 0.0 {
+            return Err(BiologyError::InvalidValue(format!(
+                "concentration of {} must be non-negative",
+                solute
+            )));
+        }
+        self.compartment_mut(compartment)?
+            .concentrations_mmol_l
+            .insert(solute.to_string(), mmol_l);
+        Ok(())
+    }
+
+    pub fn set_temperature(&mut self, compartment: &str, temperature_c: f64) -> BiologyResult<()> {
+        if !(20.0..=45.0).contains(&temperature_c) {
+            return Err(BiologyError::InvalidValue(format!(
+                "{} °C is outside the survivable tissue range",
+                temperature_c
+            )));
+        }
+        self.compartment_mut(compartment)?.temperature_c = temperature_c;
+        Ok(())
+    }
+
+    pub fn set_ph(&mut self, compartment: &str, ph: f64) -> BiologyResult<()> {
+        if !(4.0..=9.0).contains(&ph) {
+            return Err(BiologyError::InvalidValue(format!(
+                "pH {} is outside the physiological range",
+                ph
+            )));
+        }
+        self.compartment_mut(compartment)?.ph = ph;
+        Ok(())
+    }
+
+    pub fn connect(&mut self, transport: Transport) -> BiologyResult<()> {
+        for name in [&transport.from, &transport.to] {
+            self.compartment_mut(name)?;
+        }
+        if transport.from == transport.to {
+            return Err(BiologyError::InvalidParameter(format!(
+                "transport from {} to itself",
+                transport.from
+            )));
+        }
+        let rate = transport.kind.rate_l_min();
+        if rate.is_nan() || rate < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "transport rate must be non-negative".to_string(),
+            ));
+        }
+        self.routes.push(transport);
+        Ok(())
+    }
+
+    pub fn routes(&self) -> &[Transport] {
+        &self.routes
+    }
+
+    // Records a new entity in the compartment it reports.
+    pub fn place(&mut self, id: &str, entity: &dyn Localized) -> BiologyResult<()> {
+        let compartment = self.compartment_mut(entity.compartment())?;
+        if !compartment.entities.iter().any(|e| e == id) {
+            compartment.entities.push(id.to_string());
+        }
+        Ok(())
+    }
+
+    // Moves an entity, refusing moves its biology does not allow.
+    pub fn relocate(
+        &mut self,
+        id: &str,
+        entity: &mut dyn Localized,
+        to: &str,
+    ) -> BiologyResult<()> {
+        let from = entity.compartment();
+        self.compartment_mut(to)?;
+        if !self.compartment_mut(from)?.entities.iter().any(|e| e == id) {
+            return Err(BiologyError::InvalidState(format!(
+                "{} is not placed in {}",
+                id, from
+            )));
+        }
+        if !entity.can_move_to(to) {
+            return Err(BiologyError::InvalidState(format!(
+                "{} cannot move from {} to {}",
+                id, from, to
+            )));
+        }
+        entity.set_compartment(to)?;
+        self.compartment_mut(from)?.entities.retain(|e| e != id);
+        self.compartment_mut(to)?.entities.push(id.to_string());
+        Ok(())
+    }
+
+    pub fn step(&mut self, dt_minutes: f64) {
+        // Explicit exchange is stable while no route moves more than half
+        // the smaller pool's excess per substep.
+        let stiffest = self
+            .routes
+            .iter()
+            .map(|route| {
+                let rate = route.kind.rate_l_min();
+                rate / self.compartments[&route.from].volume_l
+                    + rate / self.compartments[&route.to].volume_l
+            })
+            .fold(0.0, f64::max);
+        let n = (stiffest * dt_minutes / 0.5).ceil().max(1.0) as usize;
+        let h = dt_minutes / n as f64;
+        trace!(substeps = n, h_min = h, "transport substepping");
+        for _ in 0..n {
+            self.transport(h);
+        }
+        self.time_minutes += dt_minutes;
+    }
+
+    fn transport(&mut self, h: f64) {
+        let fluxes: Vec<f64> = self
+            .routes
+            .iter()
+            .map(|route| {
+                let from = self.compartments[&route.from].concentration(&route.solute);
+                let to = self.compartments[&route.to].concentration(&route.solute);
+                match route.kind {
+                    TransportKind::Diffusive { permeability_l_min } => {
+                        permeability_l_min * (from - to)
+                    }
+                    TransportKind::Bulk { flow_l_min } => flow_l_min * from,
+                }
+            })
+            .collect();
+        for (route, flux) in self.routes.iter().zip(fluxes) {
+            let moved_mmol = flux * h;
+            for (name, sign) in [(&route.from, -1.0), (&route.to, 1.0)] {
+                let compartment = self.compartments.get_mut(name).expect("checked on connect");
+                let volume = compartment.volume_l;
+                let c = compartment
+                    .concentrations_mmol_l
+                    .entry(route.solute.clone())
+                    .or_insert(0.0);
+                *c = (*c + sign * moved_mmol / volume).max(0.0);
+            }
+        }
+    }
+
+    pub fn total_amount_mmol(&self, solute: &str) -> f64 {
+        self.compartments
+            .values()
+            .map(|c| c.amount_mmol(solute))
+            .sum()
+    }
+}
+
+// Neutrophils leave the marrow into blood, marginate along venules and
+// either return to the flow or transmigrate into tissue; they do not
+// re-enter the marrow alive or leave tissue back into blood in this model.
+impl Localized for Neutrophil {
+    fn compartment(&self) -> &'static str {
+        match self.location {
+            NeutrophilLocation::BoneMarrow => "bone_marrow",
+            NeutrophilLocation::Circulation => "blood",
+            NeutrophilLocation::Marginated => "marginated",
+            NeutrophilLocation::Tissue => "tissue",
+        }
+    }
+
+    fn can_move_to(&self, compartment: &str) -> bool {
+        matches!(
+            (self.location, compartment),
+            (NeutrophilLocation::BoneMarrow, "blood")
+                | (NeutrophilLocation::Circulation, "marginated")
+                | (NeutrophilLocation::Marginated, "blood")
+                | (NeutrophilLocation::Marginated, "tissue")
+        )
+    }
+
+    fn set_compartment(&mut self, compartment: &str) -> BiologyResult<()> {
+        self.location = match compartment {
+            "bone_marrow" => NeutrophilLocation::BoneMarrow,
+            "blood" => NeutrophilLocation::Circulation,
+            "marginated" => NeutrophilLocation::Marginated,
+            "tissue" => NeutrophilLocation::Tissue,
+            _ => {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "neutrophils have no compartment {}",
+                    compartment
+                )))
+            }
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capillary_exchange_equilibrates_and_conserves_mass() {
+        let mut physiome = Physiome::body_fluids();
+        physiome
+            .set_concentration("plasma", "glucose", 15.0)
+            .unwrap();
+        let total = physiome.total_amount_mmol("glucose");
+        for _ in 0..60 {
+            physiome.step(1.0);
+        }
+        let plasma = physiome
+            .compartment("plasma")
+            .unwrap()
+            .concentration("glucose");
+        let interstitial = physiome
+            .compartment("interstitial")
+            .unwrap()
+            .concentration("glucose");
+        // 3 * 15 + 11 * 5 = 100 mmol spread over 14 L.
+        assert!((plasma - 100.0 / 14.0).abs() < 0.01);
+        assert!((interstitial - plasma).abs() < 0.01);
+        assert!((physiome.total_amount_mmol("glucose") - total).abs() < 1e-9);
+        assert_eq!(physiome.time_minutes, 60.0);
+    }
+
+    #[test]
+    fn test_bulk_flow_is_one_way_and_never_negative() {
+        let mut physiome = Physiome::new();
+        let gut = Compartment::new(1.0)
+            .unwrap()
+            .with_concentration("drug", 10.0);
+        physiome.add_compartment("gut", gut).unwrap();
+        physiome
+            .add_compartment("portal", Compartment::new(0.5).unwrap())
+            .unwrap();
+        physiome
+            .connect(Transport::bulk("gut", "portal", "drug", 5.0))
+            .unwrap();
+        assert!(physiome
+            .connect(Transport::bulk("gut", "liver", "drug", 1.0))
+            .is_err());
+        assert!(physiome
+            .connect(Transport::bulk("gut", "gut", "drug", 1.0))
+            .is_err());
+        physiome.step(20.0);
+        let gut = physiome.compartment("gut").unwrap().concentration("drug");
+        assert!((0.0..1e-6).contains(&gut));
+        assert!((physiome.total_amount_mmol("drug") - 10.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_relocation_enforces_can_move_to() {
+        let mut physiome = Physiome::new();
+        for name in ["bone_marrow", "blood", "marginated", "tissue"] {
+            physiome
+                .add_compartment(name, Compartment::new(1.0).unwrap())
+                .unwrap();
+        }
+        let mut cell = Neutrophil::new_mature();
+        let id = cell.id.clone();
+        physiome.place(&id, &cell).unwrap();
+        assert!(physiome.relocate(&id, &mut cell, "tissue").is_err());
+        physiome.relocate(&id, &mut cell, "marginated").unwrap();
+        physiome.relocate(&id, &mut cell, "tissue").unwrap();
+        assert_eq!(cell.location, NeutrophilLocation::Tissue);
+        assert_eq!(
+            physiome.compartment("tissue").unwrap().entities,
+            vec![id.clone()]
+        );
+        assert!(physiome.compartment("blood").unwrap().entities.is_empty());
+        assert!(physiome.relocate(&id, &mut cell, "blood").is_err());
+        assert_eq!(cell.location, NeutrophilLocation::Tissue);
+    }
+}
@@ -0,0 +1,447 @@
+use crate::aging::engine::MEDIAN_MENOPAUSE_AGE_YEARS;
+use crate::biology::genetics::variants::{VariantCall, VariantEffects, VariantRegistry};
+use crate::biology::{BiologyError, BiologyResult};
+use crate::organism::Human;
+use crate::simulation::cohort::{subject_seed, CohortConfig, CohortReport, CohortRunner};
+use crate::simulation::montecarlo::ParameterDistribution;
+use crate::systems::cardiovascular::hematology::BiologicalSex;
+use nalgebra::Matrix3;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Covariates are drawn on their own seed stream so an individual is the
+// same whether generated alone or inside a batch run, and independent of
+// the simulation's random draws.
+const COVARIATE_STREAM: u64 = 0x5EED_C0FA_1A7E_0001;
+// WHO densitometric osteoporosis; T-scores use the young white female
+// femoral-neck reference for both sexes.
+// Looker AC et al. (1998) Osteoporos Int 8:468-489, PMID 9850356
+pub const YOUNG_FEMALE_FEMORAL_NECK_BMD_G_CM2: f64 = 0.858;
+pub const YOUNG_FEMALE_FEMORAL_NECK_SD_G_CM2: f64 = 0.120;
+pub const OSTEOPOROSIS_T_SCORE: f64 = -2.5;
+const AGE_RANGE_YEARS: (f64, f64) = (18.0, 110.0);
+const BMI_RANGE: (f64, f64) = (15.0, 60.0);
+// Femoral-neck loss of ~1.5%/year for the decade after menopause, ~0.8%/year
+// thereafter and ~0.7%/year in men after 50, which reproduces the NHANES III
+// decade means of the reference above.
+const BMD_LOSS_ONSET_MALE_YEARS: f64 = 50.0;
+const EARLY_POSTMENOPAUSAL_LOSS_PER_YEAR: f64 = 0.015;
+const LATE_POSTMENOPAUSAL_LOSS_PER_YEAR: f64 = 0.008;
+const EARLY_POSTMENOPAUSAL_YEARS: f64 = 10.0;
+const MALE_LOSS_PER_YEAR: f64 = 0.007;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SexCovariates {
+    pub height_mean_cm: f64,
+    pub height_sd_cm: f64,
+    pub bmi_median: f64,
+    pub bmi_geometric_sd: f64,
+    pub peak_bmd_g_cm2: f64,
+    pub peak_bmd_sd_g_cm2: f64,
+}
+
+impl SexCovariates {
+    // US adults, NHANES 2015-2018 anthropometry and NHANES III femoral neck.
+    // Fryar CD et al. (2021) Vital Health Stat 3(46), NCHS
+    pub fn us_adult(sex: BiologicalSex) -> Self {
+        match sex {
+            BiologicalSex::Female => Self {
+                height_mean_cm: 161.3,
+                height_sd_cm: 7.1,
+                bmi_median: 28.0,
+                bmi_geometric_sd: 1.25,
+                peak_bmd_g_cm2: YOUNG_FEMALE_FEMORAL_NECK_BMD_G_CM2,
+                peak_bmd_sd_g_cm2: YOUNG_FEMALE_FEMORAL_NECK_SD_G_CM2,
+            },
+            BiologicalSex::Male => Self {
+                height_mean_cm: 175.3,
+                height_sd_cm: 7.6,
+                bmi_median: 28.3,
+                bmi_geometric_sd: 1.21,
+                peak_bmd_g_cm2: 0.934,
+                peak_bmd_sd_g_cm2: 0.137,
+            },
+        }
+    }
+}
+
+// Alleles at one gene with their population frequencies; the remainder is
+// the reference allele. Each of the two haplotypes is drawn independently
+// (Hardy-Weinberg).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocusFrequencies {
+    pub gene: String,
+    pub alleles: Vec<(String, f64)>,
+}
+
+impl LocusFrequencies {
+    pub fn new(gene: &str, alleles: &[(&str, f64)]) -> Self {
+        Self {
+            gene: gene.to_string(),
+            alleles: alleles
+                .iter()
+                .map(|&(name, frequency)| (name.to_string(), frequency))
+                .collect(),
+        }
+    }
+
+    fn draw_haplotype<R: Rng>(&self, rng: &mut R) -> Option<&str> {
+        let mut u: f64 = rng.gen();
+        for (name, frequency) in &self.alleles {
+            if u < *frequency {
+                return Some(name);
+            }
+            u -= frequency;
+        }
+        None
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Vec<VariantCall> {
+        let mut copies: BTreeMap<&str, u8> = BTreeMap::new();
+        for _ in 0..2 {
+            if let Some(name) = self.draw_haplotype(rng) {
+                *copies.entry(name).or_insert(0) += 1;
+            }
+        }
+        copies
+            .into_iter()
+            .map(|(variant, copies)| VariantCall {
+                variant: variant.to_string(),
+                copies,
+            })
+            .collect()
+    }
+}
+
+// Covariate model for a virtual population. Height, log BMI and peak BMD
+// are drawn as correlated standard normals; age, sex and genotype are
+// independent of them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PopulationSpec {
+    pub female_fraction: f64,
+    pub age_years: ParameterDistribution,
+    pub female: SexCovariates,
+    pub male: SexCovariates,
+    // Correlation of the height, log BMI and BMD z-scores.
+    pub correlation: [[f64; 3]; 3],
+    pub loci: Vec<LocusFrequencies>,
+}
+
+impl PopulationSpec {
+    // Heavier people have denser bones; taller people slightly so.
+    // Felson DT et al. (1993) J Bone Miner Res 8:567-573, PMID 8511983
+    // Allele frequencies in Europeans from the CPIC frequency tables.
+    // Gaedigk A et al. (2017) Genet Med 19:69-76, PMID 27388693
+    pub fn us_adult() -> Self {
+        Self {
+            female_fraction: 0.5,
+            age_years: ParameterDistribution::Uniform {
+                low: 20.0,
+                high: 80.0,
+            },
+            female: SexCovariates::us_adult(BiologicalSex::Female),
+            male: SexCovariates::us_adult(BiologicalSex::Male),
+            correlation: [[1.0, -0.05, 0.15], [-0.05, 1.0, 0.35], [0.15, 0.35, 1.0]],
+            loci: vec![
+                LocusFrequencies::new(
+                    "CYP2D6",
+                    &[
+                        ("CYP2D6*4", 0.185),
+                        ("CYP2D6*10", 0.016),
+                        ("CYP2D6*1xN", 0.02),
+                    ],
+                ),
+                LocusFrequencies::new("CYP2C9", &[("CYP2C9*2", 0.127), ("CYP2C9*3", 0.076)]),
+                LocusFrequencies::new("CYP2C19", &[("CYP2C19*2", 0.147), ("CYP2C19*17", 0.215)]),
+                LocusFrequencies::new("SLCO1B1", &[("SLCO1B1*5", 0.16)]),
+                LocusFrequencies::new("VKORC1", &[("VKORC1 -1639G>A", 0.39)]),
+            ],
+        }
+    }
+
+    // Postmenopausal women only, as in osteoporosis screening cohorts.
+    pub fn postmenopausal_women() -> Self {
+        Self {
+            female_fraction: 1.0,
+            age_years: ParameterDistribution::Uniform {
+                low: 50.0,
+                high: 85.0,
+            },
+            ..Self::us_adult()
+        }
+    }
+
+    fn cholesky(&self) -> BiologyResult<Matrix3<f64>> {
+        let c = self.correlation;
+        let matrix = Matrix3::new(
+            c[0][0], c[0][1], c[0][2], c[1][0], c[1][1], c[1][2], c[2][0], c[2][1], c[2][2],
+        );
+        if matrix != matrix.transpose() || (0..3).any(|i| matrix[(i, i)] != 1.0) {
+            return Err(BiologyError::InvalidParameter(
+                "covariate correlation must be symmetric with unit diagonal".to_string(),
+            ));
+        }
+        matrix.cholesky().map(|c| c.l()).ok_or_else(|| {
+            BiologyError::InvalidParameter(
+                "covariate correlation must be positive definite".to_string(),
+            )
+        })
+    }
+
+    pub fn validate(&self, registry: &VariantRegistry) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&self.female_fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "female fraction must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.age_years.validate()?;
+        self.cholesky()?;
+        for locus in &self.loci {
+            let total: f64 = locus.alleles.iter().map(|(_, f)| f).sum();
+            if locus.alleles.iter().any(|(_, f)| *f < 0.0) || total > 1.0 {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "{} allele frequencies must be non-negative and sum to at most 1",
+                    locus.gene
+                )));
+            }
+            for (name, _) in &locus.alleles {
+                match registry.get(name) {
+                    Some(variant) if variant.gene == locus.gene => {}
+                    _ => {
+                        return Err(BiologyError::InvalidParameter(format!(
+                            "{} is not a registered {} variant",
+                            name, locus.gene
+                        )))
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn sample<R: Rng>(&self, index: usize, rng: &mut R) -> BiologyResult<VirtualIndividual> {
+        let sex = if rng.gen::<f64>() < self.female_fraction {
+            BiologicalSex::Female
+        } else {
+            BiologicalSex::Male
+        };
+        let covariates = match sex {
+            BiologicalSex::Female => &self.female,
+            BiologicalSex::Male => &self.male,
+        };
+        let age_years = self
+            .age_years
+            .sample(rng)
+            .clamp(AGE_RANGE_YEARS.0, AGE_RANGE_YEARS.1);
+
+        let standard = ParameterDistribution::Normal { mean: 0.0, sd: 1.0 };
+        let independent = nalgebra::Vector3::from_fn(|_, _| standard.sample(rng));
+        let z = self.cholesky()? * independent;
+        let height_cm = covariates.height_mean_cm + covariates.height_sd_cm * z[0];
+        let bmi = (covariates.bmi_median * covariates.bmi_geometric_sd.powf(z[1]))
+            .clamp(BMI_RANGE.0, BMI_RANGE.1);
+        let weight_kg = bmi * (height_cm / 100.0).powi(2);
+        let peak_bmd = covariates.peak_bmd_g_cm2 + covariates.peak_bmd_sd_g_cm2 * z[2];
+        let femoral_neck_bmd_g_cm2 = (peak_bmd * bmd_retained_fraction(sex, age_years)).max(0.1);
+
+        let genotype = self.loci.iter().flat_map(|l| l.sample(rng)).collect();
+        Ok(VirtualIndividual {
+            index,
+            human: Human::new(sex, age_years, height_cm, weight_kg)?,
+            femoral_neck_bmd_g_cm2,
+            genotype,
+        })
+    }
+}
+
+impl Default for PopulationSpec {
+    fn default() -> Self {
+        Self::us_adult()
+    }
+}
+
+// Fraction of peak femoral-neck BMD left at a given age.
+pub fn bmd_retained_fraction(sex: BiologicalSex, age_years: f64) -> f64 {
+    match sex {
+        BiologicalSex::Female => {
+            let years_past = (age_years - MEDIAN_MENOPAUSE_AGE_YEARS).max(0.0);
+            let early = years_past.min(EARLY_POSTMENOPAUSAL_YEARS);
+            let late = years_past - early;
+            (1.0 - EARLY_POSTMENOPAUSAL_LOSS_PER_YEAR).powf(early)
+                * (1.0 - LATE_POSTMENOPAUSAL_LOSS_PER_YEAR).powf(late)
+        }
+        BiologicalSex::Male => {
+            (1.0 - MALE_LOSS_PER_YEAR).powf((age_years - BMD_LOSS_ONSET_MALE_YEARS).max(0.0))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VirtualIndividual {
+    pub index: usize,
+    pub human: Human,
+    pub femoral_neck_bmd_g_cm2: f64,
+    pub genotype: Vec<VariantCall>,
+}
+
+impl VirtualIndividual {
+    pub fn t_score(&self) -> f64 {
+        (self.femoral_neck_bmd_g_cm2 - YOUNG_FEMALE_FEMORAL_NECK_BMD_G_CM2)
+            / YOUNG_FEMALE_FEMORAL_NECK_SD_G_CM2
+    }
+
+    pub fn is_osteoporotic(&self) -> bool {
+        self.t_score() <= OSTEOPOROSIS_T_SCORE
+    }
+
+    pub fn variant_effects(&self, registry: &VariantRegistry) -> BiologyResult<VariantEffects> {
+        registry.resolve(&self.genotype)
+    }
+}
+
+// Generates N reproducible virtual individuals and runs a study over them
+// on the cohort worker pool.
+#[derive(Debug, Clone)]
+pub struct VirtualPopulation {
+    pub spec: PopulationSpec,
+    pub config: CohortConfig,
+}
+
+impl VirtualPopulation {
+    pub fn new(
+        spec: PopulationSpec,
+        size: usize,
+        seed: u64,
+        registry: &VariantRegistry,
+    ) -> BiologyResult<Self> {
+        spec.validate(registry)?;
+        Ok(Self {
+            spec,
+            config: CohortConfig::new(size, seed),
+        })
+    }
+
+    pub fn individual(&self, index: usize) -> BiologyResult<VirtualIndividual> {
+        let seed = subject_seed(self.config.base_seed ^ COVARIATE_STREAM, index, 0);
+        self.spec.sample(index, &mut StdRng::seed_from_u64(seed))
+    }
+
+    pub fn generate(&self) -> BiologyResult<Vec<VirtualIndividual>> {
+        (0..self.config.subjects)
+            .map(|i| self.individual(i))
+            .collect()
+    }
+
+    pub fn run<T, F>(&self, simulate: F) -> Result<CohortReport<T>, Box<dyn std::error::Error>>
+    where
+        T: Serialize + DeserializeOwned + Send,
+        F: Fn(&VirtualIndividual, &mut StdRng) -> BiologyResult<T> + Sync,
+    {
+        CohortRunner::new(self.config)?.run(|index, rng| {
+            let individual = self.individual(index)?;
+            simulate(&individual, rng)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pharmacology::pharmacogenomics::PharmacogeneticGene;
+
+    fn correlation(x: &[f64], y: &[f64]) -> f64 {
+        let n = x.len() as f64;
+        let (mx, my) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+        let cov: f64 = x.iter().zip(y).map(|(a, b)| (a - mx) * (b - my)).sum();
+        let vx: f64 = x.iter().map(|a| (a - mx).powi(2)).sum();
+        let vy: f64 = y.iter().map(|b| (b - my).powi(2)).sum();
+        cov / (vx * vy).sqrt()
+    }
+
+    #[test]
+    fn test_population_reproduces_covariate_structure() {
+        let registry = VariantRegistry::standard();
+        let population =
+            VirtualPopulation::new(PopulationSpec::us_adult(), 2000, 9, &registry).unwrap();
+        let people = population.generate().unwrap();
+        let women = people
+            .iter()
+            .filter(|p| p.human.sex == BiologicalSex::Female)
+            .count();
+        assert!((900..1100).contains(&women));
+        let men: Vec<&VirtualIndividual> = people
+            .iter()
+            .filter(|p| p.human.sex == BiologicalSex::Male)
+            .collect();
+        let mean_height = men.iter().map(|p| p.human.height_cm).sum::<f64>() / men.len() as f64;
+        assert!((mean_height - 175.3).abs() < 1.0);
+
+        let bmi: Vec<f64> = people.iter().map(|p| p.human.bmi()).collect();
+        let bmd: Vec<f64> = people.iter().map(|p| p.femoral_neck_bmd_g_cm2).collect();
+        assert!(correlation(&bmi, &bmd) > 0.2);
+
+        // CYP2D6*4 at q = 0.185 leaves ~3.4% poor metabolizers by *4/*4.
+        let poor = people
+            .iter()
+            .filter(|p| {
+                p.variant_effects(&registry)
+                    .unwrap()
+                    .activity(PharmacogeneticGene::CYP2D6)
+                    == 0.0
+            })
+            .count();
+        assert!((30..110).contains(&poor), "{poor}");
+    }
+
+    #[test]
+    fn test_individuals_are_reproducible_and_batch_consistent() {
+        let registry = VariantRegistry::standard();
+        let population =
+            VirtualPopulation::new(PopulationSpec::us_adult(), 16, 4, &registry).unwrap();
+        let alone = population.individual(7).unwrap();
+        let report = population
+            .run(|person, _| Ok((person.human.weight_kg, person.genotype.clone())))
+            .unwrap();
+        assert_eq!(report.records[7].result.0, alone.human.weight_kg);
+        assert_eq!(report.records[7].result.1, alone.genotype);
+    }
+
+    #[test]
+    fn test_osteoporosis_prevalence_rises_with_age() {
+        let registry = VariantRegistry::standard();
+        let population =
+            VirtualPopulation::new(PopulationSpec::postmenopausal_women(), 3000, 2, &registry)
+                .unwrap();
+        let people = population.generate().unwrap();
+        let prevalence = |low: f64, high: f64| {
+            let band: Vec<_> = people
+                .iter()
+                .filter(|p| (low..high).contains(&p.human.age_years))
+                .collect();
+            band.iter().filter(|p| p.is_osteoporotic()).count() as f64 / band.len() as f64
+        };
+        // NHANES femoral-neck osteoporosis: a few percent at 50-59, roughly a
+        // quarter past 80.
+        let young = prevalence(50.0, 60.0);
+        let old = prevalence(75.0, 85.0);
+        assert!(young < 0.08, "{young}");
+        assert!(old > 2.0 * young && old > 0.1, "{old}");
+    }
+
+    #[test]
+    fn test_spec_validation() {
+        let registry = VariantRegistry::standard();
+        let mut spec = PopulationSpec::us_adult();
+        spec.correlation[0][1] = 0.5;
+        assert!(spec.validate(&registry).is_err());
+        let mut spec = PopulationSpec::us_adult();
+        spec.loci[0].alleles.push(("CYP2C9*3".to_string(), 0.1));
+        assert!(spec.validate(&registry).is_err());
+        let mut spec = PopulationSpec::us_adult();
+        spec.correlation = [[1.0, 0.99, 0.99], [0.99, 1.0, -0.99], [0.99, -0.99, 1.0]];
+        assert!(spec.validate(&registry).is_err());
+    }
+}
@@ -0,0 +1,410 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation_utils::TimeSeriesData;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    pub unit: String,
+}
+
+// Receives sampled rows as the run progresses. `begin` is called once with
+// the column layout before the first row.
+pub trait OutputSink: Send {
+    fn begin(&mut self, columns: &[Column]) -> io::Result<()>;
+    fn write_row(&mut self, time: f64, values: &[f64]) -> io::Result<()>;
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Comma-separated values with a `time,name [unit],...` header. Values are
+// written with Rust's shortest round-trip formatting.
+pub struct CsvSink<W: Write + Send> {
+    writer: W,
+}
+
+impl<W: Write + Send> CsvSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+impl<W: Write + Send> OutputSink for CsvSink<W> {
+    fn begin(&mut self, columns: &[Column]) -> io::Result<()> {
+        let mut header = vec!["time".to_string()];
+        header.extend(columns.iter().map(|c| {
+            if c.unit.is_empty() {
+                csv_field(&c.name)
+            } else {
+                csv_field(&format!("{} [{}]", c.name, c.unit))
+            }
+        }));
+        writeln!(self.writer, "{}", header.join(","))
+    }
+
+    fn write_row(&mut self, time: f64, values: &[f64]) -> io::Result<()> {
+        let mut line = time.to_string();
+        for value in values {
+            line.push(',');
+            line.push_str(&value.to_string());
+        }
+        writeln!(self.writer, "{}", line)
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+// One JSON object per sample, keyed by observable name.
+pub struct JsonLinesSink<W: Write + Send> {
+    writer: W,
+    columns: Vec<Column>,
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            columns: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write + Send> OutputSink for JsonLinesSink<W> {
+    fn begin(&mut self, columns: &[Column]) -> io::Result<()> {
+        self.columns = columns.to_vec();
+        Ok(())
+    }
+
+    fn write_row(&mut self, time: f64, values: &[f64]) -> io::Result<()> {
+        let mut row = serde_json::Map::new();
+        row.insert("time".to_string(), time.into());
+        for (column, value) in self.columns.iter().zip(values) {
+            row.insert(column.name.clone(), (*value).into());
+        }
+        writeln!(self.writer, "{}", serde_json::Value::Object(row))
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+// Column-major in-memory table: one time axis and one vector per
+// observable, the layout dataframe and Arrow libraries take without copies.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimeSeriesTable {
+    pub columns: Vec<Column>,
+    pub time: Vec<f64>,
+    pub values: Vec<Vec<f64>>,
+}
+
+impl TimeSeriesTable {
+    pub fn new(columns: Vec<Column>) -> Self {
+        let values = vec![Vec::new(); columns.len()];
+        Self {
+            columns,
+            time: Vec::new(),
+            values,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+
+    pub fn push_row(&mut self, time: f64, row: &[f64]) -> BiologyResult<()> {
+        if row.len() != self.columns.len() {
+            return Err(BiologyError::InvalidValue(format!(
+                "row has {} values for {} columns",
+                row.len(),
+                self.columns.len()
+            )));
+        }
+        self.time.push(time);
+        for (column, &value) in self.values.iter_mut().zip(row) {
+            column.push(value);
+        }
+        Ok(())
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c.name == name)
+    }
+
+    pub fn column(&self, name: &str) -> Option<&[f64]> {
+        self.index_of(name).map(|i| self.values[i].as_slice())
+    }
+
+    pub fn row(&self, index: usize) -> Option<(f64, Vec<f64>)> {
+        let time = *self.time.get(index)?;
+        Some((time, self.values.iter().map(|c| c[index]).collect()))
+    }
+
+    pub fn series(&self, name: &str) -> Option<TimeSeriesData> {
+        let values = self.column(name)?;
+        Some(TimeSeriesData {
+            times: self.time.clone(),
+            values: values.to_vec(),
+        })
+    }
+
+    // Linear interpolation between samples; clamps outside the record.
+    pub fn value_at(&self, name: &str, time: f64) -> Option<f64> {
+        let values = self.column(name)?;
+        let after = self.time.partition_point(|&t| t < time);
+        if after == 0 {
+            return values.first().copied();
+        }
+        if after == self.time.len() {
+            return values.last().copied();
+        }
+        let (t0, t1) = (self.time[after - 1], self.time[after]);
+        let (v0, v1) = (values[after - 1], values[after]);
+        Some(v0 + (v1 - v0) * (time - t0) / (t1 - t0))
+    }
+
+    // Rows with `start <= time < end`.
+    pub fn between(&self, start: f64, end: f64) -> Self {
+        let keep: Vec<usize> = (0..self.len())
+            .filter(|&i| (start..end).contains(&self.time[i]))
+            .collect();
+        Self {
+            columns: self.columns.clone(),
+            time: keep.iter().map(|&i| self.time[i]).collect(),
+            values: self
+                .values
+                .iter()
+                .map(|c| keep.iter().map(|&i| c[i]).collect())
+                .collect(),
+        }
+    }
+
+    pub fn write_to(&self, sink: &mut dyn OutputSink) -> io::Result<()> {
+        sink.begin(&self.columns)?;
+        for i in 0..self.len() {
+            let row: Vec<f64> = self.values.iter().map(|c| c[i]).collect();
+            sink.write_row(self.time[i], &row)?;
+        }
+        sink.finish()
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut sink = CsvSink::new(Vec::new());
+        self.write_to(&mut sink).expect("writing to memory");
+        String::from_utf8(sink.into_inner()).expect("CSV is UTF-8")
+    }
+}
+
+type Probe<S> = Box<dyn Fn(&S) -> f64 + Send>;
+
+// Named observables of a simulation state `S`, sampled every
+// `interval` time units and fanned out to the attached sinks. The columns
+// are fixed at the first sample.
+pub struct Recorder<S> {
+    pub interval: f64,
+    pub keep_in_memory: bool,
+    columns: Vec<Column>,
+    probes: Vec<Probe<S>>,
+    sinks: Vec<Box<dyn OutputSink>>,
+    table: TimeSeriesTable,
+    next_sample: Option<f64>,
+}
+
+impl<S> Recorder<S> {
+    pub fn new(interval: f64) -> Self {
+        Self {
+            interval: interval.max(0.0),
+            keep_in_memory: true,
+            columns: Vec::new(),
+            probes: Vec::new(),
+            sinks: Vec::new(),
+            table: TimeSeriesTable::default(),
+            next_sample: None,
+        }
+    }
+
+    pub fn register<F>(&mut self, name: &str, unit: &str, probe: F) -> BiologyResult<()>
+    where
+        F: Fn(&S) -> f64 + Send + 'static,
+    {
+        if self.next_sample.is_some() {
+            return Err(BiologyError::InvalidState(
+                "observables must be registered before the first sample".to_string(),
+            ));
+        }
+        if self.columns.iter().any(|c| c.name == name) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "observable {} registered twice",
+                name
+            )));
+        }
+        self.columns.push(Column {
+            name: name.to_string(),
+            unit: unit.to_string(),
+        });
+        self.probes.push(Box::new(probe));
+        Ok(())
+    }
+
+    pub fn with_sink<K: OutputSink + 'static>(mut self, sink: K) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    pub fn columns(&self) -> &[Column] {
+        &self.columns
+    }
+
+    // Call after every step; records when the sampling time has been
+    // reached and returns whether it did.
+    pub fn observe(&mut self, time: f64, state: &S) -> io::Result<bool> {
+        if self.next_sample.is_none() {
+            self.table = TimeSeriesTable::new(self.columns.clone());
+            for sink in &mut self.sinks {
+                sink.begin(&self.columns)?;
+            }
+        }
+        // Tolerates the rounding of accumulated time steps.
+        let due = self.next_sample.unwrap_or(time);
+        if time + 1e-9 * self.interval.max(1.0) < due {
+            return Ok(false);
+        }
+        let row: Vec<f64> = self.probes.iter().map(|probe| probe(state)).collect();
+        for sink in &mut self.sinks {
+            sink.write_row(time, &row)?;
+        }
+        if self.keep_in_memory {
+            self.table
+                .push_row(time, &row)
+                .expect("row matches registered columns");
+        }
+        self.next_sample = Some(due + self.interval);
+        Ok(true)
+    }
+
+    pub fn table(&self) -> &TimeSeriesTable {
+        &self.table
+    }
+
+    // Flushes the sinks and hands back the in-memory table.
+    pub fn finish(mut self) -> io::Result<TimeSeriesTable> {
+        for sink in &mut self.sinks {
+            sink.finish()?;
+        }
+        Ok(self.table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+    use std::sync::{Arc, Mutex};
+
+    // Lets a test read what a sink wrote after the recorder owns it.
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn glucose_recorder(interval: f64) -> Recorder<GlucoseInsulinModel> {
+        let mut recorder = Recorder::new(interval);
+        recorder
+            .register("glucose", "mg/dL", |m: &GlucoseInsulinModel| {
+                m.glucose_mg_dl
+            })
+            .unwrap();
+        recorder
+            .register("insulin", "uU/mL", |m: &GlucoseInsulinModel| {
+                m.insulin_uu_ml
+            })
+            .unwrap();
+        recorder
+    }
+
+    #[test]
+    fn test_samples_at_interval_and_streams_csv() {
+        let buffer = SharedBuffer::default();
+        let mut recorder = glucose_recorder(10.0).with_sink(CsvSink::new(buffer.clone()));
+        let mut model = GlucoseInsulinModel::new_healthy();
+        model.ingest_carbohydrate(75.0).unwrap();
+        let mut samples = 0;
+        for minute in 0..=120 {
+            if recorder.observe(minute as f64, &model).unwrap() {
+                samples += 1;
+            }
+            model.run_minutes(1);
+        }
+        assert_eq!(samples, 13);
+        let table = recorder.finish().unwrap();
+        assert_eq!(table.len(), 13);
+        assert_eq!(table.time[1], 10.0);
+
+        let csv = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("time,glucose [mg/dL],insulin [uU/mL]"));
+        assert_eq!(csv.lines().count(), 14);
+        assert_eq!(csv, table.to_csv());
+    }
+
+    #[test]
+    fn test_table_queries() {
+        let mut table = TimeSeriesTable::new(vec![Column {
+            name: "bmd".to_string(),
+            unit: "g/cm2".to_string(),
+        }]);
+        for year in 0..5 {
+            table
+                .push_row(year as f64, &[1.0 - 0.01 * year as f64])
+                .unwrap();
+        }
+        assert!(table.push_row(5.0, &[0.9, 0.1]).is_err());
+        assert!((table.value_at("bmd", 2.5).unwrap() - 0.975).abs() < 1e-12);
+        assert_eq!(table.value_at("bmd", 10.0), Some(0.96));
+        assert_eq!(table.between(1.0, 3.0).len(), 2);
+        assert_eq!(table.series("bmd").unwrap().min(), Some(0.96));
+        assert!(table.column("missing").is_none());
+    }
+
+    #[test]
+    fn test_json_lines_sink_and_registration_rules() {
+        let buffer = SharedBuffer::default();
+        let mut recorder = glucose_recorder(1.0).with_sink(JsonLinesSink::new(buffer.clone()));
+        assert!(recorder.register("glucose", "mg/dL", |_| 0.0).is_err());
+        let model = GlucoseInsulinModel::new_healthy();
+        recorder.observe(0.0, &model).unwrap();
+        assert!(recorder.register("late", "", |_| 0.0).is_err());
+        recorder.finish().unwrap();
+        let text = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let row: serde_json::Value = serde_json::from_str(text.trim()).unwrap();
+        assert_eq!(row["glucose"].as_f64(), Some(model.glucose_mg_dl));
+    }
+}
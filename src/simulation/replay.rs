@@ -0,0 +1,353 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::checkpoint::{fnv1a, SimulationRng};
+use crate::simulation::engine::{Engine, EngineSnapshot};
+use rand::{RngCore, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// Record/replay of everything that enters an engine run from outside:
+// inputs set between steps (loads, doses, activity) and the random words
+// drawn to choose them. The engine itself is deterministic for a given
+// state and inputs, so replaying the log against an engine built the same
+// way reproduces the run bit for bit; the fingerprint of the final state
+// confirms it.
+
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+// One draw from the random stream, at the width it was requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Draw {
+    U32(u32),
+    U64(u64),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReplayStep {
+    pub inputs: Vec<(String, f64)>,
+    pub draws: Vec<Draw>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayLog {
+    pub format_version: u32,
+    pub seed: u64,
+    pub initial: EngineSnapshot,
+    pub steps: Vec<ReplayStep>,
+    // FNV-1a of the final snapshot's JSON.
+    pub final_fingerprint: u64,
+}
+
+pub fn fingerprint(engine: &Engine) -> u64 {
+    let json = serde_json::to_vec(&engine.snapshot()).expect("snapshots serialize");
+    fnv1a(&json)
+}
+
+// `SimulationRng` that keeps every word it hands out.
+#[derive(Debug, Clone)]
+pub struct RecordingRng {
+    inner: SimulationRng,
+    draws: Vec<Draw>,
+}
+
+impl RecordingRng {
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self {
+            inner: SimulationRng::seed_from_u64(seed),
+            draws: Vec::new(),
+        }
+    }
+
+    fn take_draws(&mut self) -> Vec<Draw> {
+        std::mem::take(&mut self.draws)
+    }
+}
+
+impl RngCore for RecordingRng {
+    fn next_u32(&mut self) -> u32 {
+        let word = self.inner.next_u32();
+        self.draws.push(Draw::U32(word));
+        word
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let word = self.inner.next_u64();
+        self.draws.push(Draw::U64(word));
+        word
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_via_u64(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+// Hands back recorded words in order. A request of the wrong width or past
+// the end of the recording marks the replay as diverged and yields zero.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayRng {
+    draws: Vec<Draw>,
+    next: usize,
+    diverged: bool,
+}
+
+impl ReplayRng {
+    fn load(&mut self, draws: &[Draw]) {
+        self.draws = draws.to_vec();
+        self.next = 0;
+    }
+
+    fn pop(&mut self) -> Option<Draw> {
+        let draw = self.draws.get(self.next).copied();
+        self.next += 1;
+        draw
+    }
+
+    fn finished_cleanly(&self) -> bool {
+        !self.diverged && self.next == self.draws.len()
+    }
+}
+
+impl RngCore for ReplayRng {
+    fn next_u32(&mut self) -> u32 {
+        match self.pop() {
+            Some(Draw::U32(word)) => word,
+            _ => {
+                self.diverged = true;
+                0
+            }
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self.pop() {
+            Some(Draw::U64(word)) => word,
+            _ => {
+                self.diverged = true;
+                0
+            }
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        fill_via_u64(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+fn fill_via_u64<R: RngCore>(rng: &mut R, dest: &mut [u8]) {
+    for chunk in dest.chunks_mut(8) {
+        let bytes = rng.next_u64().to_le_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
+
+// What a driver sees before each step: the clock, a random stream, and a
+// place to queue inputs for the engine.
+pub struct ExternalInputs<'a> {
+    pub step: usize,
+    pub time_minutes: f64,
+    pub rng: &'a mut dyn RngCore,
+    inputs: Vec<(String, f64)>,
+}
+
+impl ExternalInputs<'_> {
+    pub fn set(&mut self, path: &str, value: f64) {
+        self.inputs.push((path.to_string(), value));
+    }
+}
+
+// Runs `steps` engine steps, calling `driver` before each to choose that
+// step's inputs, and logs what it chose.
+pub fn record<F>(
+    engine: &mut Engine,
+    seed: u64,
+    steps: usize,
+    mut driver: F,
+) -> BiologyResult<ReplayLog>
+where
+    F: FnMut(&mut ExternalInputs),
+{
+    let initial = engine.snapshot();
+    let mut rng = RecordingRng::seed_from_u64(seed);
+    let mut log = Vec::with_capacity(steps);
+    for step in 0..steps {
+        let mut external = ExternalInputs {
+            step,
+            time_minutes: engine.time_minutes,
+            rng: &mut rng,
+            inputs: Vec::new(),
+        };
+        driver(&mut external);
+        let inputs = external.inputs;
+        for (path, value) in &inputs {
+            engine.set_input(path, *value)?;
+        }
+        engine.step()?;
+        log.push(ReplayStep {
+            inputs,
+            draws: rng.take_draws(),
+        });
+    }
+    Ok(ReplayLog {
+        format_version: REPLAY_FORMAT_VERSION,
+        seed,
+        initial,
+        steps: log,
+        final_fingerprint: fingerprint(engine),
+    })
+}
+
+impl ReplayLog {
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let log: Self = serde_json::from_slice(&fs::read(path)?)?;
+        if log.format_version != REPLAY_FORMAT_VERSION {
+            return Err(format!(
+                "replay format {} is not supported (expected {})",
+                log.format_version, REPLAY_FORMAT_VERSION
+            )
+            .into());
+        }
+        Ok(log)
+    }
+
+    // Re-applies the logged inputs from the initial state, without the
+    // original driver.
+    pub fn replay(&self, engine: &mut Engine) -> BiologyResult<()> {
+        self.run(engine, None::<fn(&mut ExternalInputs)>)
+    }
+
+    // Re-runs `driver` against the recorded random stream and checks that
+    // it draws the same words and asks for the same inputs, so a driver
+    // that only adds logging or assertions reproduces the run exactly.
+    pub fn replay_with<F>(&self, engine: &mut Engine, driver: F) -> BiologyResult<()>
+    where
+        F: FnMut(&mut ExternalInputs),
+    {
+        self.run(engine, Some(driver))
+    }
+
+    fn run<F>(&self, engine: &mut Engine, mut driver: Option<F>) -> BiologyResult<()>
+    where
+        F: FnMut(&mut ExternalInputs),
+    {
+        engine.restore(&self.initial)?;
+        let mut rng = ReplayRng::default();
+        for (step, logged) in self.steps.iter().enumerate() {
+            if let Some(driver) = driver.as_mut() {
+                rng.load(&logged.draws);
+                let mut external = ExternalInputs {
+                    step,
+                    time_minutes: engine.time_minutes,
+                    rng: &mut rng,
+                    inputs: Vec::new(),
+                };
+                driver(&mut external);
+                let requested = external.inputs;
+                if !rng.finished_cleanly() || !same_inputs(&requested, &logged.inputs) {
+                    return Err(BiologyError::InvalidState(format!(
+                        "replay diverged from the recording at step {}",
+                        step
+                    )));
+                }
+            }
+            for (path, value) in &logged.inputs {
+                engine.set_input(path, *value)?;
+            }
+            engine.step()?;
+        }
+        if fingerprint(engine) != self.final_fingerprint {
+            return Err(BiologyError::InvalidState(
+                "replayed final state differs from the recording".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn same_inputs(a: &[(String, f64)], b: &[(String, f64)]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|((pa, va), (pb, vb))| pa == pb && va.to_bits() == vb.to_bits())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::physiology::Thermoregulation;
+    use crate::simulation::engine::Coupling;
+    use crate::systems::respiratory::RespiratoryControl;
+    use rand::Rng;
+
+    fn engine() -> Engine {
+        let mut engine = Engine::new(1.0).unwrap();
+        engine
+            .add_entity("body", Box::new(Thermoregulation::new_adult()))
+            .unwrap();
+        engine
+            .add_entity("lungs", Box::new(RespiratoryControl::new_healthy()))
+            .unwrap();
+        engine
+            .couple(Coupling::new("body.metabolic_w", "lungs.co2_production_ml_min").with_gain(2.4))
+            .unwrap();
+        engine
+    }
+
+    // Random bouts of exercise, as a stochastic activity schedule would
+    // produce.
+    fn bouts(inputs: &mut ExternalInputs) {
+        if inputs.step.is_multiple_of(5) {
+            let watts = inputs.rng.gen_range(0.0..600.0);
+            inputs.set("body.activity_w", watts);
+        }
+    }
+
+    #[test]
+    fn test_replay_reproduces_run_bit_for_bit() {
+        let mut original = engine();
+        let log = record(&mut original, 7, 30, bouts).unwrap();
+        assert_eq!(log.steps.iter().filter(|s| !s.inputs.is_empty()).count(), 6);
+
+        let path = std::env::temp_dir().join(format!("replay_{}.json", std::process::id()));
+        log.save(&path).unwrap();
+        let loaded = ReplayLog::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let mut rebuilt = engine();
+        loaded.replay(&mut rebuilt).unwrap();
+        assert_eq!(rebuilt.snapshot(), original.snapshot());
+        let mut rerun = engine();
+        loaded.replay_with(&mut rerun, bouts).unwrap();
+    }
+
+    #[test]
+    fn test_divergent_driver_is_reported() {
+        let log = record(&mut engine(), 7, 10, bouts).unwrap();
+        let greedy = |inputs: &mut ExternalInputs| {
+            let watts = inputs.rng.gen_range(0.0..600.0);
+            inputs.set("body.activity_w", watts);
+        };
+        let error = log.replay_with(&mut engine(), greedy).unwrap_err();
+        assert!(error.to_string().contains("step 1"));
+
+        let mut tampered = log.clone();
+        tampered.steps[3]
+            .inputs
+            .push(("body.activity_w".to_string(), 50.0));
+        assert!(tampered.replay(&mut engine()).is_err());
+    }
+}
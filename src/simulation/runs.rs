@@ -0,0 +1,471 @@
+use crate::biology::BiologyError;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    Flag(bool),
+    Number(f64),
+    Text(String),
+}
+
+impl From<f64> for ParamValue {
+    fn from(v: f64) -> Self {
+        ParamValue::Number(v)
+    }
+}
+
+impl From<bool> for ParamValue {
+    fn from(v: bool) -> Self {
+        ParamValue::Flag(v)
+    }
+}
+
+impl From<&str> for ParamValue {
+    fn from(v: &str) -> Self {
+        ParamValue::Text(v.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunStatus {
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: u64,
+    pub scenario: String,
+    pub scenario_hash: String,
+    pub parameters: BTreeMap<String, ParamValue>,
+    pub metrics: BTreeMap<String, f64>,
+    pub artifacts: Vec<PathBuf>,
+    pub status: RunStatus,
+    pub created_unix_s: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NewRun {
+    pub scenario: String,
+    pub parameters: BTreeMap<String, ParamValue>,
+    pub metrics: BTreeMap<String, f64>,
+    pub artifacts: Vec<PathBuf>,
+    pub failed: bool,
+}
+
+impl NewRun {
+    pub fn new(scenario: &str) -> Self {
+        Self {
+            scenario: scenario.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn param<V: Into<ParamValue>>(mut self, name: &str, value: V) -> Self {
+        self.parameters.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn metric(mut self, name: &str, value: f64) -> Self {
+        self.metrics.insert(name.to_string(), value);
+        self
+    }
+
+    pub fn artifact<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.artifacts.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn failed(mut self) -> Self {
+        self.failed = true;
+        self
+    }
+}
+
+// FNV-1a over the canonical JSON of scenario name and parameters (keys are
+// sorted), so identical set-ups hash identically across runs and machines.
+pub fn scenario_hash(scenario: &str, parameters: &BTreeMap<String, ParamValue>) -> String {
+    let canonical =
+        serde_json::to_string(&(scenario, parameters)).expect("parameters are plain data");
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in canonical.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("{hash:016x}")
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Clause {
+    ParamEquals(String, ParamValue),
+    ParamRange(String, f64, f64),
+    MetricAtLeast(String, f64),
+    MetricAtMost(String, f64),
+    Scenario(String),
+    Status(RunStatus),
+}
+
+// Conjunction of clauses; an empty filter matches every run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParamFilter {
+    clauses: Vec<Clause>,
+}
+
+impl ParamFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn eq<V: Into<ParamValue>>(mut self, name: &str, value: V) -> Self {
+        self.clauses
+            .push(Clause::ParamEquals(name.to_string(), value.into()));
+        self
+    }
+
+    pub fn range(mut self, name: &str, min: f64, max: f64) -> Self {
+        self.clauses
+            .push(Clause::ParamRange(name.to_string(), min, max));
+        self
+    }
+
+    pub fn metric_at_least(mut self, name: &str, min: f64) -> Self {
+        self.clauses
+            .push(Clause::MetricAtLeast(name.to_string(), min));
+        self
+    }
+
+    pub fn metric_at_most(mut self, name: &str, max: f64) -> Self {
+        self.clauses
+            .push(Clause::MetricAtMost(name.to_string(), max));
+        self
+    }
+
+    pub fn scenario(mut self, scenario: &str) -> Self {
+        self.clauses.push(Clause::Scenario(scenario.to_string()));
+        self
+    }
+
+    pub fn status(mut self, status: RunStatus) -> Self {
+        self.clauses.push(Clause::Status(status));
+        self
+    }
+
+    pub fn matches(&self, run: &RunRecord) -> bool {
+        self.clauses.iter().all(|clause| match clause {
+            Clause::ParamEquals(name, value) => run.parameters.get(name) == Some(value),
+            Clause::ParamRange(name, min, max) => matches!(
+                run.parameters.get(name),
+                Some(ParamValue::Number(v)) if v >= min && v <= max
+            ),
+            Clause::MetricAtLeast(name, min) => run.metrics.get(name).is_some_and(|v| v >= min),
+            Clause::MetricAtMost(name, max) => run.metrics.get(name).is_some_and(|v| v <= max),
+            Clause::Scenario(scenario) => &run.scenario == scenario,
+            Clause::Status(status) => run.status == *status,
+        })
+    }
+}
+
+// Embedded, append-only run history: one JSON record per line, loaded into
+// memory on open. Suited to the hundreds-to-thousands of runs of a study.
+// Several processes may share one file: each append happens under an
+// exclusive file lock after catching up on lines the others wrote, so ids
+// stay unique.
+#[derive(Debug, Clone, Default)]
+pub struct RunStore {
+    path: Option<PathBuf>,
+    records: Vec<RunRecord>,
+    read_bytes: u64,
+    read_lines: usize,
+    skipped_lines: Vec<usize>,
+}
+
+impl RunStore {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut store = Self {
+            path: Some(path.as_ref().to_path_buf()),
+            ..Self::default()
+        };
+        if path.as_ref().exists() {
+            let mut file = File::open(path.as_ref())?;
+            file.lock_shared()?;
+            store.catch_up(&mut file)?;
+        }
+        Ok(store)
+    }
+
+    // Reads lines appended since the last call. A line that does not parse
+    // is skipped and reported rather than making the whole history
+    // unreadable.
+    fn catch_up(&mut self, file: &mut File) -> std::io::Result<()> {
+        file.seek(SeekFrom::Start(self.read_bytes))?;
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 {
+                return Ok(());
+            }
+            self.read_bytes += read as u64;
+            self.read_lines += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(&line) {
+                Ok(record) => self.records.push(record),
+                Err(e) => {
+                    warn!(line = self.read_lines, error = %e, "skipping unreadable run record");
+                    self.skipped_lines.push(self.read_lines);
+                }
+            }
+        }
+    }
+
+    // 1-based line numbers of records that could not be read.
+    pub fn skipped_lines(&self) -> &[usize] {
+        &self.skipped_lines
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn record(&mut self, run: NewRun) -> Result<u64, Box<dyn std::error::Error>> {
+        // JSON has no NaN or infinity; serde would write null and the line
+        // could never be read back.
+        let numbers =
+            run.metrics
+                .iter()
+                .map(|(name, v)| (name, *v))
+                .chain(run.parameters.iter().filter_map(|(name, v)| match v {
+                    ParamValue::Number(v) => Some((name, *v)),
+                    _ => None,
+                }));
+        for (name, value) in numbers {
+            if !value.is_finite() {
+                return Err(BiologyError::InvalidValue(format!("{name} is {value}")).into());
+            }
+        }
+        let mut file = match &self.path {
+            Some(path) => {
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .read(true)
+                    .append(true)
+                    .open(path)?;
+                file.lock()?;
+                self.catch_up(&mut file)?;
+                Some(file)
+            }
+            None => None,
+        };
+        let id = self
+            .records
+            .iter()
+            .map(|r| r.id)
+            .max()
+            .map_or(1, |id| id + 1);
+        let record = RunRecord {
+            id,
+            scenario_hash: scenario_hash(&run.scenario, &run.parameters),
+            scenario: run.scenario,
+            parameters: run.parameters,
+            metrics: run.metrics,
+            artifacts: run.artifacts,
+            status: if run.failed {
+                RunStatus::Failed
+            } else {
+                RunStatus::Completed
+            },
+            created_unix_s: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+        };
+        if let Some(file) = &mut file {
+            let line = format!("{}\n", serde_json::to_string(&record)?);
+            file.write_all(line.as_bytes())?;
+            self.read_bytes += line.len() as u64;
+            self.read_lines += 1;
+        }
+        self.records.push(record);
+        Ok(id)
+    }
+
+    pub fn get(&self, id: u64) -> Option<&RunRecord> {
+        self.records.iter().find(|r| r.id == id)
+    }
+
+    pub fn find(&self, filter: &ParamFilter) -> Vec<&RunRecord> {
+        self.records.iter().filter(|r| filter.matches(r)).collect()
+    }
+
+    // Earlier runs with an identical set-up, e.g. to skip re-running them.
+    pub fn with_hash(&self, hash: &str) -> Vec<&RunRecord> {
+        self.records
+            .iter()
+            .filter(|r| r.scenario_hash == hash)
+            .collect()
+    }
+
+    pub fn best_by<'a>(&'a self, filter: &ParamFilter, metric: &str) -> Option<&'a RunRecord> {
+        self.find(filter)
+            .into_iter()
+            .filter(|r| r.metrics.contains_key(metric))
+            .max_by(|a, b| a.metrics[metric].total_cmp(&b.metrics[metric]))
+    }
+}
+
+pub fn find<'a>(store: &'a RunStore, filter: &ParamFilter) -> Vec<&'a RunRecord> {
+    store.find(filter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sweep(store: &mut RunStore) {
+        for (adjuvant, dose, titer) in [
+            ("alum", 0.5, 120.0),
+            ("alum", 1.0, 240.0),
+            ("none", 1.0, 40.0),
+            ("alum", 2.0, 300.0),
+        ] {
+            store
+                .record(
+                    NewRun::new("vaccine_sweep")
+                        .param("adjuvant", adjuvant)
+                        .param("dose_mg", dose)
+                        .metric("peak_titer", titer)
+                        .artifact(format!("out/{adjuvant}_{dose}.json")),
+                )
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_find_filters_parameters_and_metrics() {
+        let mut store = RunStore::in_memory();
+        sweep(&mut store);
+        let alum = find(&store, &ParamFilter::new().eq("adjuvant", "alum"));
+        assert_eq!(alum.len(), 3);
+        let mid_dose = ParamFilter::new()
+            .eq("adjuvant", "alum")
+            .range("dose_mg", 0.8, 2.5)
+            .metric_at_least("peak_titer", 250.0);
+        let hits = find(&store, &mid_dose);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].parameters["dose_mg"], ParamValue::Number(2.0));
+        let best = store.best_by(&ParamFilter::new(), "peak_titer").unwrap();
+        assert_eq!(best.artifacts[0], PathBuf::from("out/alum_2.json"));
+    }
+
+    #[test]
+    fn test_scenario_hash_is_order_independent_and_content_sensitive() {
+        let a = NewRun::new("s").param("x", 1.0).param("y", "b");
+        let b = NewRun::new("s").param("y", "b").param("x", 1.0);
+        let c = NewRun::new("s").param("x", 1.5).param("y", "b");
+        assert_eq!(
+            scenario_hash(&a.scenario, &a.parameters),
+            scenario_hash(&b.scenario, &b.parameters)
+        );
+        assert_ne!(
+            scenario_hash(&a.scenario, &a.parameters),
+            scenario_hash(&c.scenario, &c.parameters)
+        );
+        let mut store = RunStore::in_memory();
+        store.record(a.clone()).unwrap();
+        store.record(a.failed()).unwrap();
+        let hash = store.get(1).unwrap().scenario_hash.clone();
+        assert_eq!(store.with_hash(&hash).len(), 2);
+        assert_eq!(
+            store
+                .find(&ParamFilter::new().status(RunStatus::Failed))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_history_persists_across_reopen() {
+        let path = std::env::temp_dir().join(format!("run_store_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let mut store = RunStore::open(&path).unwrap();
+            sweep(&mut store);
+        }
+        let mut reopened = RunStore::open(&path).unwrap();
+        assert_eq!(reopened.len(), 4);
+        let id = reopened
+            .record(NewRun::new("vaccine_sweep").param("adjuvant", "saponin"))
+            .unwrap();
+        assert_eq!(id, 5);
+        let again = RunStore::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            again
+                .find(&ParamFilter::new().scenario("vaccine_sweep"))
+                .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_non_finite_metrics_rejected_and_bad_lines_skipped() {
+        let path = std::env::temp_dir().join(format!("run_store_bad_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut store = RunStore::open(&path).unwrap();
+        assert!(store
+            .record(NewRun::new("s").metric("auc", f64::NAN))
+            .is_err());
+        assert!(store
+            .record(NewRun::new("s").param("dose", f64::INFINITY))
+            .is_err());
+        assert!(store.is_empty());
+        store.record(NewRun::new("s").metric("auc", 1.0)).unwrap();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(file, "{{\"id\":2,\"metrics\":{{\"auc\":null}}}}").unwrap();
+        drop(file);
+        store.record(NewRun::new("s").metric("auc", 2.0)).unwrap();
+
+        let reopened = RunStore::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.skipped_lines(), &[2]);
+    }
+
+    #[test]
+    fn test_stores_sharing_a_file_hand_out_unique_ids() {
+        let path =
+            std::env::temp_dir().join(format!("run_store_shared_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut a = RunStore::open(&path).unwrap();
+        let mut b = RunStore::open(&path).unwrap();
+        let ids = [
+            a.record(NewRun::new("a")).unwrap(),
+            b.record(NewRun::new("b")).unwrap(),
+            a.record(NewRun::new("a")).unwrap(),
+            b.record(NewRun::new("b")).unwrap(),
+        ];
+        assert_eq!(ids, [1, 2, 3, 4]);
+        assert_eq!(a.len(), 3);
+        assert_eq!(b.len(), 4);
+        let again = RunStore::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(again.len(), 4);
+    }
+}
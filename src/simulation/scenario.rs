@@ -0,0 +1,399 @@
+use crate::activity::{ActivitySchedule, Exercise, ExerciseCapacity, ExercisePrescription};
+use crate::biology::physiology::Thermoregulation;
+use crate::biology::{BiologyError, BiologyResult};
+use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+use crate::simulation::engine::{Component, Coupling, Engine};
+use crate::simulation::recorder::{Recorder, TimeSeriesTable};
+use crate::systems::cardiovascular::IronHomeostasis;
+use crate::systems::nervous::{CircadianClock, SleepWakeRegulation};
+use crate::systems::respiratory::RespiratoryControl;
+use crate::systems::skeletal::Bioreactor;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+// Declarative description of a run, so a model can be set up without
+// writing Rust:
+//
+//   [simulation]
+//   duration_minutes = 60.0
+//   dt_minutes = 0.5
+//   record_every_minutes = 5.0
+//
+//   [entities.body]
+//   model = "thermoregulation"
+//   inputs = { activity_w = 300.0 }
+//
+//   [[couplings]]
+//   from = "body.metabolic_w"
+//   to = "lungs.co2_production_ml_min"
+//   gain = 2.4
+//
+//   outputs = ["body.core_temperature_c"]
+//
+// Entity `inputs` are applied once before the first step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    #[serde(default)]
+    pub name: String,
+    pub simulation: SimulationSettings,
+    #[serde(default)]
+    pub entities: BTreeMap<String, EntitySpec>,
+    #[serde(default)]
+    pub couplings: Vec<Coupling>,
+    #[serde(default)]
+    pub outputs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SimulationSettings {
+    pub duration_minutes: f64,
+    pub dt_minutes: f64,
+    // Defaults to every step.
+    pub record_every_minutes: Option<f64>,
+    // Threads for the entity update phase; defaults to one.
+    pub worker_threads: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntitySpec {
+    pub model: String,
+    pub preset: Option<String>,
+    #[serde(default)]
+    pub inputs: BTreeMap<String, f64>,
+}
+
+// Model names and their presets; the first preset is the default.
+pub const MODELS: [(&str, &[&str]); 8] = [
+    ("activity_schedule", &["sedentary", "morning_run"]),
+    ("bioreactor", &["perfusion", "static"]),
+    ("circadian_clock", &["entrained"]),
+    ("glucose_insulin", &["healthy", "type2_diabetes"]),
+    ("iron_erythropoiesis", &["healthy", "menstruating"]),
+    ("respiratory_control", &["healthy"]),
+    ("sleep_wake", &["adult"]),
+    ("thermoregulation", &["adult"]),
+];
+
+// A 70 kg, 40-year-old adult of average fitness; `morning_run` adds 30
+// minutes at 3 m/s from 07:00 on the first day.
+fn activity_preset(morning_run: bool) -> BiologyResult<ActivitySchedule> {
+    let mut prescription = ExercisePrescription::new();
+    if morning_run {
+        prescription = prescription.session(Exercise::Running { speed_m_s: 3.0 }, 420.0, 30.0)?;
+    }
+    Ok(ActivitySchedule::new(
+        prescription,
+        ExerciseCapacity::new(70.0, 40.0, 40.0)?,
+    ))
+}
+
+pub fn build_component(model: &str, preset: Option<&str>) -> BiologyResult<Box<dyn Component>> {
+    let Some((_, presets)) = MODELS.iter().find(|(name, _)| *name == model) else {
+        let known: Vec<&str> = MODELS.iter().map(|(name, _)| *name).collect();
+        return Err(BiologyError::InvalidParameter(format!(
+            "unknown model '{}'; expected one of {}",
+            model,
+            known.join(", ")
+        )));
+    };
+    let preset = preset.unwrap_or(presets[0]);
+    let component: Box<dyn Component> = match (model, preset) {
+        ("activity_schedule", "sedentary") => Box::new(activity_preset(false)?),
+        ("activity_schedule", "morning_run") => Box::new(activity_preset(true)?),
+        ("bioreactor", "perfusion") => Box::new(Bioreactor::perfusion()),
+        ("bioreactor", "static") => Box::new(Bioreactor::static_culture()),
+        ("circadian_clock", "entrained") => Box::new(CircadianClock::new()),
+        ("glucose_insulin", "healthy") => Box::new(GlucoseInsulinModel::new_healthy()),
+        ("glucose_insulin", "type2_diabetes") => Box::new(GlucoseInsulinModel::type2_diabetes()),
+        ("iron_erythropoiesis", "healthy") => Box::new(IronHomeostasis::new_healthy()),
+        ("iron_erythropoiesis", "menstruating") => {
+            Box::new(IronHomeostasis::new_healthy().with_iron_loss(2.0)?)
+        }
+        ("respiratory_control", "healthy") => Box::new(RespiratoryControl::new_healthy()),
+        ("sleep_wake", "adult") => Box::new(SleepWakeRegulation::new()),
+        ("thermoregulation", "adult") => Box::new(Thermoregulation::new_adult()),
+        _ => {
+            return Err(BiologyError::InvalidParameter(format!(
+                "unknown preset '{}' for {}; expected one of {}",
+                preset,
+                model,
+                presets.join(", ")
+            )))
+        }
+    };
+    Ok(component)
+}
+
+// Prefixes a validation failure with the location in the file.
+fn at(path: &str, error: BiologyError) -> BiologyError {
+    let (BiologyError::InvalidValue(message)
+    | BiologyError::InvalidState(message)
+    | BiologyError::InvalidParameter(message)) = error;
+    BiologyError::InvalidParameter(format!("{}: {}", path, message))
+}
+
+impl Scenario {
+    pub fn from_toml_str(text: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    pub fn from_json_str(text: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    // Format chosen by extension: .toml or .json.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml_str(&text),
+            Some("json") => Self::from_json_str(&text),
+            other => Err(format!(
+                "{}: unsupported scenario format {:?}; use .toml or .json",
+                path.display(),
+                other.unwrap_or("")
+            )
+            .into()),
+        }
+    }
+
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string(self)
+    }
+
+    // Checks every part against the model catalogue and assembles the
+    // engine and recorder. Errors name the offending path in the file,
+    // e.g. `couplings[1].to: lungs (respiratory_control) has no input co2`.
+    pub fn build(&self) -> BiologyResult<ScenarioRun> {
+        let settings = &self.simulation;
+        if !(settings.duration_minutes >= 0.0 && settings.duration_minutes.is_finite()) {
+            return Err(at(
+                "simulation.duration_minutes",
+                BiologyError::InvalidParameter("must be non-negative and finite".to_string()),
+            ));
+        }
+        let mut engine = Engine::new(settings.dt_minutes)
+            .map_err(|e| at("simulation.dt_minutes", e))?
+            .with_worker_threads(settings.worker_threads.unwrap_or(1));
+        let interval = settings.record_every_minutes.unwrap_or(settings.dt_minutes);
+        if interval.is_nan() || interval <= 0.0 {
+            return Err(at(
+                "simulation.record_every_minutes",
+                BiologyError::InvalidParameter("must be positive".to_string()),
+            ));
+        }
+
+        for (name, spec) in &self.entities {
+            let path = format!("entities.{}", name);
+            let component = build_component(&spec.model, spec.preset.as_deref())
+                .map_err(|e| at(&format!("{}.model", path), e))?;
+            engine
+                .add_entity(name, component)
+                .map_err(|e| at(&path, e))?;
+            for (input, value) in &spec.inputs {
+                engine
+                    .set_input(&format!("{}.{}", name, input), *value)
+                    .map_err(|e| at(&format!("{}.inputs.{}", path, input), e))?;
+            }
+        }
+
+        for (i, coupling) in self.couplings.iter().enumerate() {
+            engine
+                .output_unit(&coupling.from)
+                .map_err(|e| at(&format!("couplings[{}].from", i), e))?;
+            engine
+                .input_unit(&coupling.to)
+                .map_err(|e| at(&format!("couplings[{}].to", i), e))?;
+            engine
+                .couple(coupling.clone())
+                .map_err(|e| at(&format!("couplings[{}]", i), e))?;
+        }
+
+        let mut recorder = Recorder::new(interval);
+        for (i, output) in self.outputs.iter().enumerate() {
+            let path = format!("outputs[{}]", i);
+            let (unit, probe) = engine.probe(output).map_err(|e| at(&path, e))?;
+            recorder
+                .register(output, unit, probe)
+                .map_err(|e| at(&path, e))?;
+        }
+
+        Ok(ScenarioRun {
+            engine,
+            recorder,
+            duration_minutes: settings.duration_minutes,
+        })
+    }
+}
+
+// An assembled scenario, ready to run.
+pub struct ScenarioRun {
+    pub engine: Engine,
+    pub recorder: Recorder<Engine>,
+    pub duration_minutes: f64,
+}
+
+impl ScenarioRun {
+    pub fn run(mut self) -> Result<TimeSeriesTable, Box<dyn std::error::Error>> {
+        self.engine.run(self.duration_minutes, self.recorder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../config_examples/exercise_in_heat_scenario.toml");
+
+    #[test]
+    fn test_example_scenario_runs() {
+        let scenario = Scenario::from_toml_str(EXAMPLE).unwrap();
+        let table = scenario.build().unwrap().run().unwrap();
+        let core = table.column("body.core_temperature_c").unwrap();
+        assert!(core.last().unwrap() > &core[0]);
+        let ventilation = table.column("lungs.minute_ventilation_l_min").unwrap();
+        assert!(ventilation.last().unwrap() > &(1.5 * ventilation[0]));
+        assert!(table.column("meal.glucose_mg_dl").unwrap()[6] > 140.0);
+        assert_eq!(table.len(), 13);
+    }
+
+    #[test]
+    fn test_toml_and_json_agree() {
+        let scenario = Scenario::from_toml_str(EXAMPLE).unwrap();
+        let json = serde_json::to_string(&scenario).unwrap();
+        assert_eq!(Scenario::from_json_str(&json).unwrap(), scenario);
+        let toml = scenario.to_toml_string().unwrap();
+        assert_eq!(Scenario::from_toml_str(&toml).unwrap(), scenario);
+    }
+
+    fn error_of(edit: impl FnOnce(&mut Scenario)) -> String {
+        let mut scenario = Scenario::from_toml_str(EXAMPLE).unwrap();
+        edit(&mut scenario);
+        match scenario.build() {
+            Err(e) => e.to_string(),
+            Ok(_) => panic!("scenario should be rejected"),
+        }
+    }
+
+    #[test]
+    fn test_errors_name_the_offending_path() {
+        let message = error_of(|s| s.entities.get_mut("meal").unwrap().model = "liver".into());
+        assert!(message.contains("entities.meal.model: unknown model 'liver'"));
+        let message = error_of(|s| {
+            s.entities.get_mut("meal").unwrap().preset = Some("athlete".into());
+        });
+        assert!(message.contains("entities.meal.model: unknown preset 'athlete'"));
+        let message = error_of(|s| {
+            s.entities
+                .get_mut("body")
+                .unwrap()
+                .inputs
+                .insert("activity_w".into(), -5.0);
+        });
+        assert!(message.contains("entities.body.inputs.activity_w"));
+        let message = error_of(|s| s.couplings[0].to = "lungs.co2".into());
+        assert!(message.contains("couplings[0].to: lungs (respiratory_control) has no input co2"));
+        let message = error_of(|s| s.outputs.push("heart.rate".into()));
+        assert!(message.contains("outputs[3]: no entity named heart"));
+        let message = error_of(|s| s.simulation.dt_minutes = 0.0);
+        assert_eq!(
+            message,
+            "Invalid parameter: simulation.dt_minutes: time step must be positive"
+        );
+    }
+
+    #[test]
+    fn test_bioreactor_scenario_runs() {
+        let text = include_str!("../../config_examples/perfusion_bioreactor_scenario.toml");
+        let table = Scenario::from_toml_str(text)
+            .unwrap()
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+        let perfused = table.column("perfused.cells_per_cm3").unwrap();
+        let still = table.column("static.cells_per_cm3").unwrap();
+        assert!(perfused.last().unwrap() > still.last().unwrap());
+        assert!(
+            table
+                .column("static.min_oxygen_mm")
+                .unwrap()
+                .last()
+                .unwrap()
+                < &0.01
+        );
+        let modulus = table.column("perfused.construct_modulus_kpa").unwrap();
+        assert!(modulus.last().unwrap() > &modulus[0]);
+    }
+
+    #[test]
+    fn test_every_registered_model_builds() {
+        for (model, presets) in MODELS {
+            for preset in presets {
+                let component = build_component(model, Some(preset)).unwrap();
+                assert_eq!(component.kind(), model);
+            }
+        }
+    }
+
+    #[test]
+    fn test_day_scenario_couples_clock_sleep_activity_and_iron() {
+        let text = r#"
+            outputs = ["sleep.asleep", "body.core_temperature_c", "iron.epo_u_l"]
+
+            [simulation]
+            duration_minutes = 1440.0
+            dt_minutes = 5.0
+            record_every_minutes = 30.0
+
+            [entities.clock]
+            model = "circadian_clock"
+
+            [entities.sleep]
+            model = "sleep_wake"
+
+            [entities.exercise]
+            model = "activity_schedule"
+            preset = "morning_run"
+
+            [entities.body]
+            model = "thermoregulation"
+
+            [entities.iron]
+            model = "iron_erythropoiesis"
+            inputs = { arterial_po2_mmhg = 60.0 }
+
+            [[couplings]]
+            from = "clock.internal_hour"
+            to = "sleep.internal_hour"
+
+            [[couplings]]
+            from = "exercise.activity_w"
+            to = "body.activity_w"
+        "#;
+        let table = Scenario::from_toml_str(text)
+            .unwrap()
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+        let asleep = table.column("sleep.asleep").unwrap();
+        assert!(asleep.contains(&1.0) && asleep.contains(&0.0));
+        // The run is recorded at 07:30, just after it ends.
+        let core = table.column("body.core_temperature_c").unwrap();
+        assert!(core[15] > core[14] + 0.3);
+        let epo = table.column("iron.epo_u_l").unwrap();
+        assert!(epo.last().unwrap() > &epo[0]);
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        let text = EXAMPLE.replace("dt_minutes", "timestep");
+        assert!(Scenario::from_toml_str(&text).is_err());
+    }
+}
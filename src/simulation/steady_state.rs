@@ -0,0 +1,347 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::engine::{Engine, EngineSnapshot};
+use crate::simulation::ode::{integrate, Counter, OdeSystem, SolverOptions, SolverStats};
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::debug;
+
+// Equilibria for initialising homeostatic models before a perturbation.
+// An engine is stepped until its observables stop moving. An ODE system is
+// solved for f(y) = 0 by pseudo-transient continuation: Newton steps on
+// (I/τ - J)·Δ = f with τ grown as the residual falls, which behaves like
+// implicit Euler far from the root and like Newton near it. Since every
+// iterate is a backward-Euler step, linear conservation laws (c·f = 0) are
+// kept, so closed networks settle on the equilibrium of their own pools
+// instead of hitting a singular Jacobian.
+// Kelley CT, Keyes DE (1998) SIAM J Numer Anal 35:508-523
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SteadyStateOptions {
+    // Settled when every rate of change is within atol + rtol·|value| per
+    // unit time (minutes for the engine).
+    pub rtol: f64,
+    pub atol: f64,
+    // Consecutive engine steps that must meet the tolerance.
+    pub settle_steps: usize,
+    // Simulated time allowed before giving up.
+    pub max_time: f64,
+    pub max_iterations: usize,
+    // First pseudo-time step of the continuation.
+    pub initial_pseudo_step: f64,
+}
+
+impl Default for SteadyStateOptions {
+    fn default() -> Self {
+        Self {
+            rtol: 1e-6,
+            atol: 1e-9,
+            settle_steps: 10,
+            max_time: 1.0e6,
+            max_iterations: 200,
+            initial_pseudo_step: 1e-3,
+        }
+    }
+}
+
+impl SteadyStateOptions {
+    pub fn with_tolerances(mut self, rtol: f64, atol: f64) -> Self {
+        self.rtol = rtol;
+        self.atol = atol;
+        self
+    }
+
+    pub fn with_max_time(mut self, max_time: f64) -> Self {
+        self.max_time = max_time;
+        self
+    }
+
+    fn validate(&self) -> BiologyResult<()> {
+        if !(self.rtol > 0.0
+            && self.atol > 0.0
+            && self.max_time > 0.0
+            && self.initial_pseudo_step > 0.0
+            && self.max_iterations > 0)
+        {
+            return Err(BiologyError::InvalidParameter(
+                "steady-state tolerances, time and iteration budgets must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    // Largest rate relative to its tolerance; settled below 1.
+    fn scaled_rate(&self, rates: &[f64], values: &[f64]) -> f64 {
+        rates
+            .iter()
+            .zip(values)
+            .map(|(r, v)| r.abs() / (self.atol + self.rtol * v.abs()))
+            .fold(0.0, f64::max)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineSteadyState {
+    pub time_minutes: f64,
+    pub values: BTreeMap<String, f64>,
+    // Full state at equilibrium, for restoring before each perturbation.
+    pub snapshot: EngineSnapshot,
+}
+
+// Steps `engine` until every observable changes by less than the tolerance
+// per minute for `settle_steps` steps in a row; the engine is left there.
+pub fn relax(
+    engine: &mut Engine,
+    observables: &[&str],
+    options: SteadyStateOptions,
+) -> BiologyResult<EngineSteadyState> {
+    options.validate()?;
+    if observables.is_empty() {
+        return Err(BiologyError::InvalidParameter(
+            "no observables to settle".to_string(),
+        ));
+    }
+    let read = |engine: &Engine| -> BiologyResult<Vec<f64>> {
+        observables.iter().map(|path| engine.value(path)).collect()
+    };
+    let start = engine.time_minutes;
+    let mut previous = read(engine)?;
+    let mut settled = 0;
+    while engine.time_minutes - start < options.max_time {
+        engine.step()?;
+        let current = read(engine)?;
+        let rates: Vec<f64> = current
+            .iter()
+            .zip(&previous)
+            .map(|(c, p)| (c - p) / engine.dt_minutes)
+            .collect();
+        if current.iter().any(|v| !v.is_finite()) {
+            return Err(BiologyError::InvalidState(
+                "observable became non-finite while settling".to_string(),
+            ));
+        }
+        settled = if options.scaled_rate(&rates, &current) <= 1.0 {
+            settled + 1
+        } else {
+            0
+        };
+        previous = current;
+        if settled >= options.settle_steps.max(1) {
+            debug!(
+                minutes = engine.time_minutes - start,
+                "engine reached steady state"
+            );
+            return Ok(EngineSteadyState {
+                time_minutes: engine.time_minutes,
+                values: observables
+                    .iter()
+                    .map(|path| path.to_string())
+                    .zip(previous)
+                    .collect(),
+                snapshot: engine.snapshot(),
+            });
+        }
+    }
+    Err(BiologyError::InvalidState(format!(
+        "observables still moving after {} minutes",
+        options.max_time
+    )))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Equilibrium {
+    pub state: Vec<f64>,
+    // Scaled residual max |f_i| / (atol + rtol·|y_i|), below 1 on success.
+    pub residual: f64,
+    pub iterations: usize,
+    pub stats: SolverStats,
+}
+
+// Root of the autonomous system's right-hand side (evaluated at `t`),
+// starting from `guess`.
+pub fn equilibrium<S: OdeSystem>(
+    system: &S,
+    t: f64,
+    guess: &[f64],
+    options: SteadyStateOptions,
+) -> BiologyResult<Equilibrium> {
+    options.validate()?;
+    let n = system.dimension();
+    if guess.len() != n {
+        return Err(BiologyError::InvalidParameter(format!(
+            "guess has {} states, system has {}",
+            guess.len(),
+            n
+        )));
+    }
+    let mut counter = Counter {
+        system,
+        stats: SolverStats::default(),
+    };
+    let mut y = guess.to_vec();
+    let mut f = counter.f(t, &y);
+    let mut norm = norm2(&f);
+    let mut tau = options.initial_pseudo_step;
+    for iteration in 0..options.max_iterations {
+        let residual = options.scaled_rate(&f, &y);
+        if residual <= 1.0 {
+            debug!(iterations = iteration, "equilibrium found");
+            return Ok(Equilibrium {
+                state: y,
+                residual,
+                iterations: iteration,
+                stats: counter.stats,
+            });
+        }
+        let jacobian = counter.jacobian(t, &y, &f);
+        let matrix = DMatrix::identity(n, n) / tau - jacobian;
+        let delta = matrix
+            .lu()
+            .solve(&DVector::from_column_slice(&f))
+            .filter(|d| d.iter().all(|x| x.is_finite()));
+        let trial: Option<Vec<f64>> =
+            delta.map(|d| y.iter().zip(d.iter()).map(|(a, b)| a + b).collect());
+        let accepted = trial.and_then(|trial| {
+            let f_trial = counter.f(t, &trial);
+            let norm_trial = norm2(&f_trial);
+            norm_trial
+                .is_finite()
+                .then_some((trial, f_trial, norm_trial))
+        });
+        match accepted {
+            Some((trial, f_trial, norm_trial)) => {
+                // Switched evolution relaxation, τ growing with the fall
+                // in ‖f‖ and at least doubling, so transients that raise
+                // ‖f‖ on the way to the root do not stall it; a sharp rise
+                // cuts it back.
+                let factor = if norm_trial <= 4.0 * norm {
+                    (norm / norm_trial.max(f64::MIN_POSITIVE)).max(2.0)
+                } else {
+                    0.5
+                };
+                tau = (tau * factor).min(1.0e12);
+                y = trial;
+                f = f_trial;
+                norm = norm_trial;
+            }
+            None => {
+                counter.stats.newton_failures += 1;
+                tau *= 0.25;
+            }
+        }
+    }
+    Err(BiologyError::InvalidState(format!(
+        "no equilibrium within {} iterations (scaled residual {:.3e})",
+        options.max_iterations,
+        options.scaled_rate(&f, &y)
+    )))
+}
+
+// Integrates forward until the state settles, for systems whose basin is
+// hard to reach by continuation; the endpoint is polished by `equilibrium`.
+pub fn relax_ode<S: OdeSystem>(
+    system: &S,
+    t: f64,
+    initial: &[f64],
+    solver: SolverOptions,
+    options: SteadyStateOptions,
+) -> BiologyResult<Equilibrium> {
+    options.validate()?;
+    let solution = integrate(system, t, initial, t + options.max_time, solver)?;
+    let mut polished = equilibrium(system, t + options.max_time, solution.last(), options)?;
+    polished.stats.accepted_steps += solution.stats.accepted_steps;
+    polished.stats.rhs_evaluations += solution.stats.rhs_evaluations;
+    Ok(polished)
+}
+
+fn norm2(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::physiology::Thermoregulation;
+    use crate::metabolism::reaction_network::{Reaction, ReactionNetwork};
+
+    // Logistic growth toward a carrying capacity of 5.
+    struct Logistic;
+
+    impl OdeSystem for Logistic {
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn rhs(&self, _t: f64, y: &[f64], dydt: &mut [f64]) {
+            dydt[0] = 0.8 * y[0] * (1.0 - y[0] / 5.0);
+        }
+    }
+
+    #[test]
+    fn test_equilibrium_of_logistic_growth() {
+        let found = equilibrium(&Logistic, 0.0, &[0.1], SteadyStateOptions::default()).unwrap();
+        assert!((found.state[0] - 5.0).abs() < 1e-5);
+        assert!(found.residual <= 1.0);
+        let relaxed = relax_ode(
+            &Logistic,
+            0.0,
+            &[0.1],
+            SolverOptions::default(),
+            SteadyStateOptions::default().with_max_time(20.0),
+        )
+        .unwrap();
+        assert!((relaxed.state[0] - 5.0).abs() < 1e-5);
+        assert!(equilibrium(&Logistic, 0.0, &[1.0, 2.0], SteadyStateOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_closed_network_keeps_its_pool() {
+        // A <-> B with Keq = 3; 4 units in total.
+        let mut network = ReactionNetwork::new();
+        let a = network.add_species("A", 4.0);
+        let b = network.add_species("B", 0.0);
+        network
+            .add_reaction(
+                Reaction::builder("forward")
+                    .reactant(a, 1.0)
+                    .product(b, 1.0)
+                    .mass_action(0.3)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        network
+            .add_reaction(
+                Reaction::builder("reverse")
+                    .reactant(b, 1.0)
+                    .product(a, 1.0)
+                    .mass_action(0.1)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let found = equilibrium(
+            &network,
+            0.0,
+            &network.concentrations,
+            SteadyStateOptions::default(),
+        )
+        .unwrap();
+        assert!((found.state[0] - 1.0).abs() < 1e-6);
+        assert!((found.state[1] - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_engine_relaxes_to_thermal_balance() {
+        let mut engine = Engine::new(1.0).unwrap();
+        let mut body = Thermoregulation::new_adult();
+        body.core_temperature_c += 1.5;
+        engine.add_entity("body", Box::new(body)).unwrap();
+        let options = SteadyStateOptions::default().with_tolerances(1e-5, 1e-6);
+        let settled = relax(&mut engine, &["body.core_temperature_c"], options).unwrap();
+        let core = settled.values["body.core_temperature_c"];
+        assert!((36.0..38.0).contains(&core), "{}", core);
+        assert_eq!(settled.snapshot.time_minutes, engine.time_minutes);
+        assert!(relax(&mut engine, &["body.nothing"], options).is_err());
+    }
+}
@@ -0,0 +1,613 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// One update of named observables; serialises to a single JSON text frame
+// suitable for a WebSocket or server-sent-events endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamFrame {
+    pub sequence: u64,
+    pub time: f64,
+    pub observables: BTreeMap<String, f64>,
+}
+
+impl StreamFrame {
+    pub fn to_json_text(&self) -> String {
+        serde_json::to_string(self).expect("frame is plain data")
+    }
+
+    pub fn from_json_text(text: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(text)
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.observables.get(name).copied()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Backpressure {
+    // The simulation waits for a slow subscriber.
+    Block,
+    // A full subscriber loses its oldest queued frame, so a dashboard that
+    // falls behind still catches up to the current state.
+    DropOldest,
+    // A full subscriber misses new frames until it drains its queue.
+    DropNewest,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StreamConfig {
+    pub dt: f64,
+    // Simulation time of the initial state; frame times count from here.
+    pub start_time: f64,
+    pub emit_every_steps: usize,
+    pub max_steps: Option<usize>,
+    pub backpressure: Backpressure,
+    // Wall-clock pause between steps to pace a live display.
+    pub step_delay: Option<Duration>,
+}
+
+impl StreamConfig {
+    pub fn new(dt: f64, emit_every_steps: usize) -> Self {
+        Self {
+            dt,
+            start_time: 0.0,
+            emit_every_steps: emit_every_steps.max(1),
+            max_steps: None,
+            backpressure: Backpressure::DropOldest,
+            step_delay: None,
+        }
+    }
+}
+
+struct Queue {
+    frames: VecDeque<StreamFrame>,
+    // Set when the run ends or the hub lets go of this subscriber.
+    closed: bool,
+    // Set when the `Subscription` is dropped.
+    abandoned: bool,
+    waker: Option<Waker>,
+}
+
+// A bounded frame queue between the publisher and one subscriber. The
+// publisher applies the backpressure policy itself, which a plain mpsc
+// channel cannot do for drop-oldest.
+struct Channel {
+    capacity: usize,
+    queue: Mutex<Queue>,
+    // Signalled on every push and on close, for blocking receivers.
+    arrived: Condvar,
+    // Signalled on every pop, for a blocked publisher.
+    space: Condvar,
+    dropped: AtomicU64,
+}
+
+enum Offer {
+    Sent,
+    Full(StreamFrame),
+    Abandoned,
+}
+
+impl Channel {
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap()
+    }
+
+    fn offer(&self, frame: StreamFrame, policy: Backpressure) -> Offer {
+        let mut queue = self.lock();
+        if queue.abandoned {
+            return Offer::Abandoned;
+        }
+        if queue.frames.len() >= self.capacity {
+            match policy {
+                Backpressure::Block => return Offer::Full(frame),
+                Backpressure::DropNewest => {
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Offer::Sent;
+                }
+                Backpressure::DropOldest => {
+                    queue.frames.pop_front();
+                    self.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+        }
+        queue.frames.push_back(frame);
+        let waker = queue.waker.take();
+        drop(queue);
+        self.arrived.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+        Offer::Sent
+    }
+
+    fn close(&self) {
+        let mut queue = self.lock();
+        queue.closed = true;
+        let waker = queue.waker.take();
+        drop(queue);
+        self.arrived.notify_all();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    fn pop(&self, queue: &mut Queue) -> Option<StreamFrame> {
+        let frame = queue.frames.pop_front();
+        if frame.is_some() {
+            self.space.notify_all();
+        }
+        frame
+    }
+}
+
+// The hub's handle on a subscriber; dropping the last one ends that
+// subscriber's stream.
+struct Subscriber {
+    channel: Arc<Channel>,
+}
+
+impl Drop for Subscriber {
+    fn drop(&mut self) {
+        self.channel.close();
+    }
+}
+
+#[derive(Default)]
+struct Hub {
+    subscribers: Vec<Arc<Subscriber>>,
+}
+
+// Fan-out point shared by a running simulation and its subscribers; create
+// it first so no early frames are missed, and subscribe late joiners at any
+// time.
+#[derive(Clone, Default)]
+pub struct StreamHub {
+    inner: Arc<Mutex<Hub>>,
+}
+
+impl StreamHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn subscribe(&self, buffer_frames: usize) -> Subscription {
+        let channel = Arc::new(Channel {
+            capacity: buffer_frames.max(1),
+            queue: Mutex::new(Queue {
+                frames: VecDeque::new(),
+                closed: false,
+                abandoned: false,
+                waker: None,
+            }),
+            arrived: Condvar::new(),
+            space: Condvar::new(),
+            dropped: AtomicU64::new(0),
+        });
+        self.inner
+            .lock()
+            .unwrap()
+            .subscribers
+            .push(Arc::new(Subscriber {
+                channel: Arc::clone(&channel),
+            }));
+        Subscription { channel }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.inner.lock().unwrap().subscribers.len()
+    }
+}
+
+// How often a blocked publisher re-checks the stop flag.
+const BLOCK_POLL: Duration = Duration::from_millis(1);
+
+// Offers the frame to every subscriber without holding the hub lock while
+// waiting, so late joiners and `stop` are never stuck behind a reader that
+// has stopped reading. Only `Block` waits.
+fn publish(hub: &Mutex<Hub>, frame: &StreamFrame, policy: Backpressure, stop: &AtomicBool) {
+    let subscribers = hub.lock().unwrap().subscribers.clone();
+    let mut abandoned = Vec::new();
+    for sub in subscribers {
+        let mut pending = frame.clone();
+        loop {
+            match sub.channel.offer(pending, policy) {
+                Offer::Sent => break,
+                Offer::Full(_) if stop.load(Ordering::Relaxed) => return,
+                Offer::Full(frame) => {
+                    pending = frame;
+                    let queue = sub.channel.lock();
+                    if queue.frames.len() >= sub.channel.capacity && !queue.abandoned {
+                        let _ = sub.channel.space.wait_timeout(queue, BLOCK_POLL);
+                    }
+                }
+                Offer::Abandoned => {
+                    abandoned.push(sub);
+                    break;
+                }
+            }
+        }
+    }
+    if !abandoned.is_empty() {
+        hub.lock()
+            .unwrap()
+            .subscribers
+            .retain(|sub| !abandoned.iter().any(|gone| Arc::ptr_eq(sub, gone)));
+    }
+}
+
+// The receiving end of one subscription. Blocking callers use `recv` or
+// `iter`; async callers await `next`, which works under any executor
+// (tokio included) without the simulation depending on one.
+pub struct Subscription {
+    channel: Arc<Channel>,
+}
+
+impl Subscription {
+    pub fn dropped_frames(&self) -> u64 {
+        self.channel.dropped.load(Ordering::Relaxed)
+    }
+
+    // Waits for the next frame; `None` once the run has ended and the
+    // queue is drained.
+    pub fn recv(&self) -> Option<StreamFrame> {
+        let mut queue = self.channel.lock();
+        loop {
+            if let Some(frame) = self.channel.pop(&mut queue) {
+                return Some(frame);
+            }
+            if queue.closed {
+                return None;
+            }
+            queue = self.channel.arrived.wait(queue).unwrap();
+        }
+    }
+
+    pub fn try_recv(&self) -> Option<StreamFrame> {
+        let mut queue = self.channel.lock();
+        self.channel.pop(&mut queue)
+    }
+
+    // Frames until the run ends.
+    pub fn iter(&self) -> impl Iterator<Item = StreamFrame> + '_ {
+        std::iter::from_fn(|| self.recv())
+    }
+
+    // Newest frame currently queued, discarding older ones.
+    pub fn latest(&self) -> Option<StreamFrame> {
+        let mut queue = self.channel.lock();
+        let frame = queue.frames.pop_back();
+        queue.frames.clear();
+        self.channel.space.notify_all();
+        frame
+    }
+
+    pub fn next(&self) -> NextFrame<'_> {
+        NextFrame { subscription: self }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.channel.lock().abandoned = true;
+        self.channel.space.notify_all();
+    }
+}
+
+// Future returned by `Subscription::next`.
+pub struct NextFrame<'a> {
+    subscription: &'a Subscription,
+}
+
+impl Future for NextFrame<'_> {
+    type Output = Option<StreamFrame>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let channel = &self.subscription.channel;
+        let mut queue = channel.lock();
+        if let Some(frame) = channel.pop(&mut queue) {
+            return Poll::Ready(Some(frame));
+        }
+        if queue.closed {
+            return Poll::Ready(None);
+        }
+        queue.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+// Runs a simulation on its own thread and pushes observable frames to any
+// number of subscribers over bounded queues. The facade is runtime-free:
+// an async server awaits `Subscription::next` directly, and the simulation
+// never knows which runtime, if any, is reading.
+pub struct SimulationStream<S> {
+    hub: StreamHub,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<S>>,
+}
+
+impl<S: Send + 'static> SimulationStream<S> {
+    pub fn spawn<F, O>(
+        hub: &StreamHub,
+        mut state: S,
+        config: StreamConfig,
+        mut step: F,
+        observe: O,
+    ) -> Self
+    where
+        F: FnMut(&mut S, f64) + Send + 'static,
+        O: Fn(&S) -> Vec<(String, f64)> + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let (thread_hub, thread_stop) = (Arc::clone(&hub.inner), Arc::clone(&stop));
+        let handle = thread::spawn(move || {
+            let mut sequence = 0;
+            let mut steps = 0;
+            while !thread_stop.load(Ordering::Relaxed)
+                && config.max_steps.is_none_or(|max| steps < max)
+            {
+                step(&mut state, config.dt);
+                steps += 1;
+                if steps % config.emit_every_steps == 0 {
+                    let frame = StreamFrame {
+                        sequence,
+                        time: config.start_time + steps as f64 * config.dt,
+                        observables: observe(&state).into_iter().collect(),
+                    };
+                    sequence += 1;
+                    publish(&thread_hub, &frame, config.backpressure, &thread_stop);
+                }
+                if let Some(delay) = config.step_delay {
+                    thread::sleep(delay);
+                }
+            }
+            // Closing the queues tells subscribers the run has ended.
+            thread_hub.lock().unwrap().subscribers.clear();
+            state
+        });
+        Self {
+            hub: hub.clone(),
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    pub fn hub(&self) -> &StreamHub {
+        &self.hub
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(|h| h.is_finished())
+    }
+
+    // Stops the run and hands back the final simulation state.
+    pub fn stop(mut self) -> S {
+        self.stop.store(true, Ordering::Relaxed);
+        self.join_inner()
+    }
+
+    pub fn join(mut self) -> S {
+        self.join_inner()
+    }
+
+    fn join_inner(&mut self) -> S {
+        self.handle
+            .take()
+            .expect("stream joined once")
+            .join()
+            .expect("simulation thread panicked")
+    }
+}
+
+impl<S> Drop for SimulationStream<S> {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metabolism::glucose_insulin::GlucoseInsulinModel;
+
+    fn glucose_observables(model: &GlucoseInsulinModel) -> Vec<(String, f64)> {
+        vec![
+            ("glucose_mg_dl".to_string(), model.glucose_mg_dl),
+            ("insulin_uu_ml".to_string(), model.insulin_uu_ml),
+        ]
+    }
+
+    #[test]
+    fn test_frames_arrive_in_order_until_run_ends() {
+        let mut model = GlucoseInsulinModel::new_healthy();
+        model.ingest_carbohydrate(75.0).unwrap();
+        let mut config = StreamConfig::new(1.0, 10);
+        config.max_steps = Some(180);
+        config.backpressure = Backpressure::Block;
+        let hub = StreamHub::new();
+        let subscription = hub.subscribe(64);
+        let stream =
+            SimulationStream::spawn(&hub, model, config, |m, dt| m.step(dt), glucose_observables);
+        let frames: Vec<StreamFrame> = subscription.iter().collect();
+        let model = stream.join();
+        assert!(!frames.is_empty());
+        assert!(frames
+            .windows(2)
+            .all(|w| w[1].sequence == w[0].sequence + 1));
+        let peak = frames
+            .iter()
+            .filter_map(|f| f.get("glucose_mg_dl"))
+            .fold(0.0, f64::max);
+        assert!(peak > frames[0].get("glucose_mg_dl").unwrap());
+        assert_eq!(
+            frames.last().unwrap().get("glucose_mg_dl"),
+            Some(model.glucose_mg_dl)
+        );
+    }
+
+    #[test]
+    fn test_slow_dashboard_drops_frames_without_stalling_others() {
+        let mut config = StreamConfig::new(1.0, 1);
+        config.max_steps = Some(500);
+        let hub = StreamHub::new();
+        let slow = hub.subscribe(2);
+        let fast = hub.subscribe(1000);
+        let stream = SimulationStream::spawn(
+            &hub,
+            0.0_f64,
+            config,
+            |x, dt| *x += dt,
+            |x| vec![("x".to_string(), *x)],
+        );
+        stream.join();
+        assert!(slow.dropped_frames() > 0);
+        assert_eq!(fast.dropped_frames(), 0);
+        let latest = fast.latest().unwrap();
+        assert!(latest.get("x").unwrap() > 400.0);
+    }
+
+    #[test]
+    fn test_stop_ends_unbounded_run() {
+        let mut config = StreamConfig::new(0.5, 1);
+        config.step_delay = Some(Duration::from_micros(50));
+        let hub = StreamHub::new();
+        let stream = SimulationStream::spawn(
+            &hub,
+            0_u64,
+            config,
+            |n, _| *n += 1,
+            |n| vec![("steps".to_string(), *n as f64)],
+        );
+        // Late joiners subscribe through the running stream's hub.
+        let subscription = stream.hub().subscribe(8);
+        let first = subscription.recv().unwrap();
+        let steps = stream.stop();
+        assert!(steps as f64 >= first.get("steps").unwrap());
+        assert!(subscription.iter().count() <= 8);
+        assert_eq!(hub.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn test_stop_returns_while_blocked_on_idle_subscriber() {
+        let mut config = StreamConfig::new(1.0, 1);
+        config.backpressure = Backpressure::Block;
+        let hub = StreamHub::new();
+        let idle = hub.subscribe(1);
+        let stream = SimulationStream::spawn(
+            &hub,
+            0_u64,
+            config,
+            |n, _| *n += 1,
+            |n| vec![("steps".to_string(), *n as f64)],
+        );
+        thread::sleep(Duration::from_millis(20));
+        // The run is waiting on the full channel but still lets others in.
+        let _late = hub.subscribe(4);
+        let steps = stream.stop();
+        assert!(steps <= 2);
+        assert!(idle.iter().count() <= 1);
+    }
+
+    fn run_counter(backpressure: Backpressure, buffer_frames: usize) -> Vec<StreamFrame> {
+        let mut config = StreamConfig::new(1.0, 1);
+        config.max_steps = Some(100);
+        config.start_time = 50.0;
+        config.backpressure = backpressure;
+        let hub = StreamHub::new();
+        let subscription = hub.subscribe(buffer_frames);
+        SimulationStream::spawn(
+            &hub,
+            0_u64,
+            config,
+            |n, _| *n += 1,
+            |n| vec![("steps".to_string(), *n as f64)],
+        )
+        .join();
+        assert_eq!(subscription.dropped_frames(), 97);
+        subscription.iter().collect()
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_current_state() {
+        let times =
+            |frames: Vec<StreamFrame>| -> Vec<f64> { frames.iter().map(|f| f.time).collect() };
+        assert_eq!(
+            StreamConfig::new(1.0, 1).backpressure,
+            Backpressure::DropOldest
+        );
+        assert_eq!(
+            times(run_counter(Backpressure::DropOldest, 3)),
+            vec![148.0, 149.0, 150.0]
+        );
+        assert_eq!(
+            times(run_counter(Backpressure::DropNewest, 3)),
+            vec![51.0, 52.0, 53.0]
+        );
+    }
+
+    struct Unpark(thread::Thread);
+
+    impl std::task::Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    // Minimal executor: polls on the current thread, parking between wakes.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(Unpark(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+            thread::park();
+        }
+    }
+
+    #[test]
+    fn test_async_subscriber_awaits_frames_until_run_ends() {
+        let mut config = StreamConfig::new(1.0, 1);
+        config.max_steps = Some(20);
+        config.step_delay = Some(Duration::from_micros(200));
+        config.backpressure = Backpressure::Block;
+        let hub = StreamHub::new();
+        let subscription = hub.subscribe(4);
+        let stream = SimulationStream::spawn(
+            &hub,
+            0_u64,
+            config,
+            |n, _| *n += 1,
+            |n| vec![("steps".to_string(), *n as f64)],
+        );
+        let sequences = block_on(async {
+            let mut sequences = Vec::new();
+            while let Some(frame) = subscription.next().await {
+                sequences.push(frame.sequence);
+            }
+            sequences
+        });
+        assert_eq!(stream.join(), 20);
+        assert_eq!(sequences, (0..20).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    fn test_json_text_frame_round_trip() {
+        let frame = StreamFrame {
+            sequence: 3,
+            time: 12.5,
+            observables: [("titer".to_string(), 1.25)].into_iter().collect(),
+        };
+        let text = frame.to_json_text();
+        assert!(text.contains("\"titer\""));
+        assert_eq!(StreamFrame::from_json_text(&text).unwrap(), frame);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::biology::{BiologyError, BiologyResult};
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::EnvFilter;
+
+// Subscriber for the crate's tracing instrumentation. The engine opens an
+// info span per run and a debug span per step; published events (state
+// changes, threshold crossings, domain events) log at debug; the ODE
+// solver logs rejected steps and method switches at debug and every
+// accepted step at trace. Failures that abort a run log at warn.
+
+// Overrides the verbosity with an `EnvFilter` directive, e.g.
+// "human_biology::simulation::ode=trace,human_biology=info".
+pub const LOG_ENV: &str = "HUMAN_BIOLOGY_LOG";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    // Warnings only: exhausted step budgets, failed subjects.
+    Quiet,
+    // Run and cohort summaries.
+    Summary,
+    // Every engine step, published event and solver rejection.
+    Steps,
+    // Every accepted solver step and substep.
+    Solver,
+}
+
+impl Verbosity {
+    pub fn directive(self) -> &'static str {
+        match self {
+            Verbosity::Quiet => "human_biology=warn",
+            Verbosity::Summary => "human_biology=info",
+            Verbosity::Steps => "human_biology=debug",
+            Verbosity::Solver => "human_biology=trace",
+        }
+    }
+}
+
+pub fn subscriber<W>(verbosity: Verbosity, writer: W) -> impl Subscriber + Send + Sync
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    build(EnvFilter::new(verbosity.directive()), writer)
+}
+
+fn build<W>(filter: EnvFilter, writer: W) -> impl Subscriber + Send + Sync
+where
+    W: for<'a> MakeWriter<'a> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .finish()
+}
+
+// Installs a stderr subscriber for the whole process; fails if one is
+// already installed.
+pub fn init(verbosity: Verbosity) -> BiologyResult<()> {
+    install(EnvFilter::new(verbosity.directive()))
+}
+
+// As `init`, with `LOG_ENV` taking precedence when set.
+pub fn init_from_env(default: Verbosity) -> BiologyResult<()> {
+    let filter = match std::env::var(LOG_ENV) {
+        Ok(directive) => EnvFilter::try_new(&directive).map_err(|e| {
+            BiologyError::InvalidParameter(format!("{} = {:?}: {}", LOG_ENV, directive, e))
+        })?,
+        Err(_) => EnvFilter::new(default.directive()),
+    };
+    install(filter)
+}
+
+fn install(filter: EnvFilter) -> BiologyResult<()> {
+    tracing::subscriber::set_global_default(build(filter, std::io::stderr))
+        .map_err(|e| BiologyError::InvalidState(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::ode::{integrate, OdeSystem, SolverMethod, SolverOptions};
+    use std::io::{self, Write};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(bytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // Stiff enough that an over-long first step is rejected.
+    struct FastDecay;
+
+    impl OdeSystem for FastDecay {
+        fn dimension(&self) -> usize {
+            1
+        }
+
+        fn rhs(&self, _t: f64, y: &[f64], dydt: &mut [f64]) {
+            dydt[0] = -50.0 * y[0];
+        }
+    }
+
+    fn captured(verbosity: Verbosity) -> String {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = subscriber(verbosity, move || writer.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let options = SolverOptions {
+                initial_step: Some(1.0),
+                ..SolverOptions::default().with_method(SolverMethod::DormandPrince)
+            };
+            integrate(&FastDecay, 0.0, &[1.0], 1.0, options).unwrap();
+        });
+        let bytes = buffer.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[test]
+    fn test_verbosity_selects_solver_diagnostics() {
+        let summary = captured(Verbosity::Summary);
+        assert!(summary.is_empty(), "{}", summary);
+        let steps = captured(Verbosity::Steps);
+        assert!(steps.contains("step rejected") && steps.contains("integration finished"));
+        assert!(!steps.contains("step accepted"));
+        assert!(captured(Verbosity::Solver).contains("step accepted"));
+    }
+}
@@ -100,19 +100,11 @@ impl BiomarkerState {
     }
 }
 
-pub fn simulate_exponential_decay(
-    initial: f64,
-    half_life: f64,
-    time: f64,
-) -> f64 {
+pub fn simulate_exponential_decay(initial: f64, half_life: f64, time: f64) -> f64 {
     initial * 0.5_f64.powf(time / half_life)
 }
 
-pub fn simulate_exponential_growth(
-    initial: f64,
-    doubling_time: f64,
-    time: f64,
-) -> f64 {
+pub fn simulate_exponential_growth(initial: f64, doubling_time: f64, time: f64) -> f64 {
     initial * 2.0_f64.powf(time / doubling_time)
 }
 
@@ -122,14 +114,11 @@ pub fn simulate_logistic_growth(
     growth_rate: f64,
     time: f64,
 ) -> f64 {
-    carrying_capacity / (1.0 + ((carrying_capacity - initial) / initial) * (-growth_rate * time).exp())
+    carrying_capacity
+        / (1.0 + ((carrying_capacity - initial) / initial) * (-growth_rate * time).exp())
 }
 
-pub fn simulate_first_order_kinetics(
-    initial: f64,
-    rate_constant: f64,
-    time: f64,
-) -> f64 {
+pub fn simulate_first_order_kinetics(initial: f64, rate_constant: f64, time: f64) -> f64 {
     initial * (-rate_constant * time).exp()
 }
 
@@ -144,14 +133,11 @@ pub fn calculate_hill_equation(
     (max_response * conc_n) / (ec50_n + conc_n)
 }
 
-pub fn calculate_michaelis_menten(
-    vmax: f64,
-    substrate: f64,
-    km: f64,
-) -> f64 {
+pub fn calculate_michaelis_menten(vmax: f64, substrate: f64, km: f64) -> f64 {
     (vmax * substrate) / (km + substrate)
 }
 
+#[derive(Default)]
 pub struct TimeSeriesData {
     pub times: Vec<f64>,
     pub values: Vec<f64>,
@@ -178,11 +164,17 @@ impl TimeSeriesData {
     }
 
     pub fn max(&self) -> Option<f64> {
-        self.values.iter().copied().max_by(|a, b| a.partial_cmp(b).unwrap())
+        self.values
+            .iter()
+            .copied()
+            .max_by(|a, b| a.partial_cmp(b).unwrap())
     }
 
     pub fn min(&self) -> Option<f64> {
-        self.values.iter().copied().min_by(|a, b| a.partial_cmp(b).unwrap())
+        self.values
+            .iter()
+            .copied()
+            .min_by(|a, b| a.partial_cmp(b).unwrap())
     }
 
     pub fn integrate_trapezoidal(&self) -> f64 {
@@ -233,16 +225,15 @@ pub fn calculate_fold_change(initial: f64, current: f64) -> f64 {
     current / initial
 }
 
+pub type DerivativeFn = Box<dyn Fn(&[f64], f64) -> Vec<f64>>;
+
 pub struct OrdinaryDifferentialEquation {
     pub state: Vec<f64>,
-    pub derivatives: Box<dyn Fn(&[f64], f64) -> Vec<f64>>,
+    pub derivatives: DerivativeFn,
 }
 
 impl OrdinaryDifferentialEquation {
-    pub fn new(
-        initial_state: Vec<f64>,
-        derivatives: Box<dyn Fn(&[f64], f64) -> Vec<f64>>,
-    ) -> Self {
+    pub fn new(initial_state: Vec<f64>, derivatives: DerivativeFn) -> Self {
         Self {
             state: initial_state,
             derivatives,
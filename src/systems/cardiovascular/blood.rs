@@ -154,6 +154,7 @@ pub struct CellCount {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::large_enum_variant)]
 pub enum BloodComponent {
     Plasma(PlasmaComposition),
     Cells(CellCount),
@@ -280,29 +281,29 @@ impl Blood {
     }
 
     pub fn can_donate_to(&self, recipient: BloodType) -> bool {
-        match (self.blood_type, recipient) {
-            (BloodType::ONegative, _) => true,
-            (BloodType::OPositive, BloodType::OPositive) => true,
-            (BloodType::OPositive, BloodType::APositive) => true,
-            (BloodType::OPositive, BloodType::BPositive) => true,
-            (BloodType::OPositive, BloodType::ABPositive) => true,
-            (BloodType::ANegative, BloodType::ANegative) => true,
-            (BloodType::ANegative, BloodType::APositive) => true,
-            (BloodType::ANegative, BloodType::ABNegative) => true,
-            (BloodType::ANegative, BloodType::ABPositive) => true,
-            (BloodType::APositive, BloodType::APositive) => true,
-            (BloodType::APositive, BloodType::ABPositive) => true,
-            (BloodType::BNegative, BloodType::BNegative) => true,
-            (BloodType::BNegative, BloodType::BPositive) => true,
-            (BloodType::BNegative, BloodType::ABNegative) => true,
-            (BloodType::BNegative, BloodType::ABPositive) => true,
-            (BloodType::BPositive, BloodType::BPositive) => true,
-            (BloodType::BPositive, BloodType::ABPositive) => true,
-            (BloodType::ABNegative, BloodType::ABNegative) => true,
-            (BloodType::ABNegative, BloodType::ABPositive) => true,
-            (BloodType::ABPositive, BloodType::ABPositive) => true,
-            _ => false,
-        }
+        matches!(
+            (self.blood_type, recipient),
+            (BloodType::ONegative, _)
+                | (BloodType::OPositive, BloodType::OPositive)
+                | (BloodType::OPositive, BloodType::APositive)
+                | (BloodType::OPositive, BloodType::BPositive)
+                | (BloodType::OPositive, BloodType::ABPositive)
+                | (BloodType::ANegative, BloodType::ANegative)
+                | (BloodType::ANegative, BloodType::APositive)
+                | (BloodType::ANegative, BloodType::ABNegative)
+                | (BloodType::ANegative, BloodType::ABPositive)
+                | (BloodType::APositive, BloodType::APositive)
+                | (BloodType::APositive, BloodType::ABPositive)
+                | (BloodType::BNegative, BloodType::BNegative)
+                | (BloodType::BNegative, BloodType::BPositive)
+                | (BloodType::BNegative, BloodType::ABNegative)
+                | (BloodType::BNegative, BloodType::ABPositive)
+                | (BloodType::BPositive, BloodType::BPositive)
+                | (BloodType::BPositive, BloodType::ABPositive)
+                | (BloodType::ABNegative, BloodType::ABNegative)
+                | (BloodType::ABNegative, BloodType::ABPositive)
+                | (BloodType::ABPositive, BloodType::ABPositive)
+        )
     }
 }
 
@@ -180,6 +180,13 @@ impl Lymphocyte {
         }
     }
 
+    pub fn new_cytotoxic_t() -> Self {
+        Self {
+            lineage: LymphocyteLineage::TCell(TCellSubtype::CD8Cytotoxic),
+            ..Self::new_t_helper()
+        }
+    }
+
     pub fn new_b_cell() -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
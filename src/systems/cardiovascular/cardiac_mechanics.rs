@@ -335,7 +335,7 @@ mod tests {
     #[test]
     fn test_pressure_volume_loop() {
         let pv_loop = PressureVolumeLoop::generate_normal_lv();
-        assert!(pv_loop.points.len() > 0);
+        assert!(!pv_loop.points.is_empty());
         assert!(pv_loop.stroke_work_j > 0.0);
         assert!(pv_loop.cardiac_efficiency() > 0.0);
         assert!(pv_loop.cardiac_efficiency() < 1.0);
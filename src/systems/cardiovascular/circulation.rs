@@ -131,9 +131,10 @@ impl CirculatorySystem {
 
 impl SystemicCirculation {
     pub fn new_adult() -> Self {
-        let mut major_arteries = Vec::new();
-        major_arteries.push(BloodVessel::new(VesselType::Artery));
-        major_arteries.push(BloodVessel::new(VesselType::Artery));
+        let major_arteries = vec![
+            BloodVessel::new(VesselType::Artery),
+            BloodVessel::new(VesselType::Artery),
+        ];
 
         let capillary_beds = vec![
             CapillaryBed::new_for_organ(OrganSupplied::Brain, 750.0),
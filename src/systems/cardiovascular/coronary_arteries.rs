@@ -206,10 +206,9 @@ impl AtheroscleroticPlaque {
     }
 
     pub fn assess_stability(&self) -> PlaqueStability {
-        if self.composition.fibrous_cap_thickness_um < 65.0 {
-            PlaqueStability::Vulnerable
-        } else if self.composition.lipid_core_percent > 40.0
-            && self.composition.inflammatory_cells_percent > 10.0
+        if self.composition.fibrous_cap_thickness_um < 65.0
+            || (self.composition.lipid_core_percent > 40.0
+                && self.composition.inflammatory_cells_percent > 10.0)
         {
             PlaqueStability::Vulnerable
         } else {
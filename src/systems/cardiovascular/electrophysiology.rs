@@ -377,6 +377,7 @@ impl Arrhythmia {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn stroke_risk_chadsvasc(
         &self,
         age: u32,
@@ -1,3 +1,4 @@
+use crate::physics::dimensionless::{check, Assumption, RegimeWarning};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -122,6 +123,11 @@ impl BloodFlow {
     pub fn is_laminar(&self) -> bool {
         self.reynolds_number < 2000.0
     }
+
+    // Set when a model that treats this flow as laminar should not.
+    pub fn laminar_warning(&self, model: &str) -> Option<RegimeWarning> {
+        check(model, Assumption::LaminarFlow, self.reynolds_number)
+    }
 }
 
 #[cfg(test)]
@@ -151,6 +157,10 @@ mod tests {
         let flow = BloodFlow::new(5000.0, 2.0);
         assert!(flow.velocity_cm_s > 0.0);
         assert!(flow.reynolds_number > 0.0);
+        assert!(flow.laminar_warning("aorta").is_none());
+        assert!(BloodFlow::new(20000.0, 2.0)
+            .laminar_warning("aorta")
+            .is_some());
     }
 
     #[test]
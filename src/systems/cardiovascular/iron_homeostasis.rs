@@ -0,0 +1,461 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::engine::{load, save, substep, unknown_input, Component};
+use crate::systems::renal::hormones::Erythropoietin;
+use crate::systems::respiratory::oxygen_transport::Hemoglobin;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Adult male body iron ~4 g: ~2.5 g in haemoglobin, ~1 g stored as
+// ferritin, ~3 mg bound to transferrin. Macrophages recycle 20-25 mg/day
+// from senescent red cells; absorption replaces the ~1 mg/day lost.
+// Andrews NC (1999) N Engl J Med 341:1986-1995
+const IRON_MG_PER_G_HEMOGLOBIN: f64 = 3.4;
+const REFERENCE_HEMOGLOBIN_G_DL: f64 = 15.0;
+const BLOOD_VOLUME_DL: f64 = 50.0;
+const PLASMA_VOLUME_DL: f64 = 30.0;
+const REFERENCE_STORAGE_IRON_MG: f64 = 1000.0;
+const REFERENCE_MACROPHAGE_IRON_MG: f64 = 20.0;
+// TIBC ~300 µg/dL, 30% saturated.
+const TRANSFERRIN_CAPACITY_MG: f64 = 9.0;
+const REFERENCE_TRANSFERRIN_SATURATION: f64 = 0.3;
+const REFERENCE_DIETARY_IRON_MG_PER_DAY: f64 = 15.0;
+const REFERENCE_IRON_LOSS_MG_PER_DAY: f64 = 1.0;
+// Hepatocytes exchange a few mg/day with plasma.
+const REFERENCE_STORAGE_RELEASE_MG_PER_DAY: f64 = 5.0;
+// Mean corpuscular Hb concentration 33 g/dL.
+const MCHC_G_DL: f64 = 33.0;
+// Red cells survive ~120 days; erythroblasts and marrow reticulocytes take
+// about 5 days to reach the circulation, where reticulocytes mature in ~1.
+const RED_CELL_LIFESPAN_DAYS: f64 = 120.0;
+const PRECURSOR_TRANSIT_DAYS: f64 = 5.0;
+const CIRCULATING_RETICULOCYTE_DAYS: f64 = 1.0;
+// Marrow output rises up to 6-fold under sustained EPO, but only if
+// transferrin delivers the iron; saturation below ~16% restricts it.
+// Finch CA (1982) Blood 60:1241-1246
+const MAX_ERYTHROPOIESIS_FOLD: f64 = 6.0;
+const MARROW_EPO_HALF_MAX_U_L: f64 = 50.0;
+const ERYTHROID_SATURATION_HALF_MAX: f64 = 0.1;
+// Serum ferritin 1 ng/mL reflects ~8 mg of storage iron.
+// Walters GO, Miller FM, Worwood M (1973) J Clin Pathol 26:770-772
+const STORAGE_IRON_MG_PER_FERRITIN_NG_ML: f64 = 8.0;
+// Macrophage export saturates, so when hepcidin removes ferroportin,
+// recycled iron accumulates in macrophages instead of reaching plasma.
+const MACROPHAGE_EXPORT_HALF_MAX_MG: f64 = REFERENCE_MACROPHAGE_IRON_MG;
+// Inflammatory cytokines also blunt the marrow response to EPO and
+// shorten red cell survival.
+// Weiss G, Goodnough LT (2005) N Engl J Med 352:1011-1023
+const INFLAMMATORY_MARROW_SUPPRESSION: f64 = 0.3;
+const INFLAMMATORY_HEMOLYSIS: f64 = 0.2;
+// Hepcidin binds ferroportin and triggers its degradation, closing iron
+// export from enterocytes, macrophages and hepatocytes.
+// Nemeth E et al. (2004) Science 306:2090-2093
+const FERROPORTIN_HEPCIDIN_SENSITIVITY: f64 = 3.0;
+// Hepcidin transcription follows stored iron (BMP6) and transferrin
+// saturation, is induced by IL-6 and suppressed by erythroferrone from
+// EPO-stimulated erythroblasts; it re-equilibrates within hours. An IL-6
+// infusion raises urinary hepcidin ~7-fold.
+// Nemeth E et al. (2004) J Clin Invest 113:1271-1276
+// Kautz L et al. (2014) Nat Genet 46:678-684
+const HEPCIDIN_STORAGE_EXPONENT: f64 = 0.5;
+const HEPCIDIN_INFLAMMATION_GAIN: f64 = 3.0;
+const HEPCIDIN_TIME_CONSTANT_DAYS: f64 = 0.25;
+
+// Whole-body iron and red cell kinetics: duodenal absorption, transferrin
+// transport, macrophage recycling, ferritin storage, hepcidin control and
+// EPO-driven erythropoiesis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IronHomeostasis {
+    pub erythropoietin: Erythropoietin,
+    pub arterial_po2_mmhg: f64,
+    pub dietary_iron_mg_per_day: f64,
+    pub iron_loss_mg_per_day: f64,
+    // 0 at rest, 1 for a moderate and 2 for a strong acute-phase response.
+    pub inflammation: f64,
+    pub circulating_hemoglobin_g: f64,
+    pub precursor_hemoglobin_g: f64,
+    pub transferrin_iron_mg: f64,
+    pub macrophage_iron_mg: f64,
+    pub storage_iron_mg: f64,
+    // Relative to the healthy steady state.
+    pub hepcidin: f64,
+    pub days: f64,
+}
+
+impl Default for IronHomeostasis {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+impl IronHomeostasis {
+    pub fn new_healthy() -> Self {
+        let production = reference_production_g_per_day();
+        Self {
+            erythropoietin: Erythropoietin::new_normal(),
+            arterial_po2_mmhg: 95.0,
+            dietary_iron_mg_per_day: REFERENCE_DIETARY_IRON_MG_PER_DAY,
+            iron_loss_mg_per_day: REFERENCE_IRON_LOSS_MG_PER_DAY,
+            inflammation: 0.0,
+            circulating_hemoglobin_g: REFERENCE_HEMOGLOBIN_G_DL * BLOOD_VOLUME_DL,
+            precursor_hemoglobin_g: production * PRECURSOR_TRANSIT_DAYS,
+            transferrin_iron_mg: REFERENCE_TRANSFERRIN_SATURATION * TRANSFERRIN_CAPACITY_MG,
+            macrophage_iron_mg: REFERENCE_MACROPHAGE_IRON_MG,
+            storage_iron_mg: REFERENCE_STORAGE_IRON_MG,
+            hepcidin: 1.0,
+            days: 0.0,
+        }
+    }
+
+    // Menstruating women lose ~2 mg/day on average.
+    pub fn with_iron_loss(mut self, mg_per_day: f64) -> BiologyResult<Self> {
+        if mg_per_day.is_nan() || mg_per_day < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "iron loss must be non-negative".to_string(),
+            ));
+        }
+        self.iron_loss_mg_per_day = mg_per_day;
+        Ok(self)
+    }
+
+    pub fn with_dietary_iron(mut self, mg_per_day: f64) -> BiologyResult<Self> {
+        if mg_per_day.is_nan() || mg_per_day < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "dietary iron must be non-negative".to_string(),
+            ));
+        }
+        self.dietary_iron_mg_per_day = mg_per_day;
+        Ok(self)
+    }
+
+    pub fn set_inflammation(&mut self, level: f64) -> BiologyResult<()> {
+        check_inflammation(level)?;
+        self.inflammation = level;
+        Ok(())
+    }
+
+    pub fn set_arterial_po2(&mut self, mmhg: f64) -> BiologyResult<()> {
+        check_arterial_po2(mmhg)?;
+        self.arterial_po2_mmhg = mmhg;
+        Ok(())
+    }
+
+    // Haemorrhage or donation; plasma refills the volume, diluting the
+    // remaining red cells.
+    pub fn bleed(&mut self, volume_ml: f64) -> BiologyResult<()> {
+        let fraction = volume_ml / (BLOOD_VOLUME_DL * 100.0);
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(BiologyError::InvalidValue(
+                "blood loss must lie between zero and the blood volume".to_string(),
+            ));
+        }
+        self.circulating_hemoglobin_g *= 1.0 - fraction;
+        self.transferrin_iron_mg *= 1.0 - fraction;
+        Ok(())
+    }
+
+    pub fn hemoglobin_g_dl(&self) -> f64 {
+        self.circulating_hemoglobin_g / BLOOD_VOLUME_DL
+    }
+
+    pub fn hematocrit_percent(&self) -> f64 {
+        100.0 * self.hemoglobin_g_dl() / MCHC_G_DL
+    }
+
+    // Current red cell mass for oxygen-carrying models such as
+    // `OxygenTransport` and `RespiratoryControl`.
+    pub fn hemoglobin(&self) -> Hemoglobin {
+        Hemoglobin::new_with_concentration(self.hemoglobin_g_dl())
+    }
+
+    pub fn transferrin_saturation(&self) -> f64 {
+        (self.transferrin_iron_mg / TRANSFERRIN_CAPACITY_MG).min(1.0)
+    }
+
+    pub fn serum_iron_ug_dl(&self) -> f64 {
+        1000.0 * self.transferrin_iron_mg / PLASMA_VOLUME_DL
+    }
+
+    // Ferritin is also an acute-phase reactant.
+    pub fn serum_ferritin_ng_ml(&self) -> f64 {
+        (self.storage_iron_mg + self.macrophage_iron_mg) / STORAGE_IRON_MG_PER_FERRITIN_NG_ML
+            * (1.0 + self.inflammation)
+    }
+
+    pub fn reticulocyte_percent(&self) -> f64 {
+        100.0 * self.precursor_hemoglobin_g / PRECURSOR_TRANSIT_DAYS * CIRCULATING_RETICULOCYTE_DAYS
+            / self.circulating_hemoglobin_g.max(1e-9)
+    }
+
+    pub fn total_body_iron_mg(&self) -> f64 {
+        (self.circulating_hemoglobin_g + self.precursor_hemoglobin_g) * IRON_MG_PER_G_HEMOGLOBIN
+            + self.transferrin_iron_mg
+            + self.macrophage_iron_mg
+            + self.storage_iron_mg
+    }
+
+    pub fn is_anemic(&self) -> bool {
+        self.hemoglobin().is_anemic()
+    }
+
+    pub fn is_iron_deficient(&self) -> bool {
+        self.serum_ferritin_ng_ml() < 30.0
+    }
+
+    // Export through ferroportin relative to the healthy state.
+    pub fn ferroportin_activity(&self) -> f64 {
+        (1.0 + FERROPORTIN_HEPCIDIN_SENSITIVITY)
+            / (1.0 + FERROPORTIN_HEPCIDIN_SENSITIVITY * self.hepcidin)
+    }
+
+    // EPO drive on the marrow relative to normal, before iron limits it.
+    pub fn erythropoietic_drive(&self) -> f64 {
+        let epo = self.erythropoietin.plasma_concentration_u_l;
+        let normal = Erythropoietin::new_normal().plasma_concentration_u_l;
+        let response = |e: f64| MAX_ERYTHROPOIESIS_FOLD * e / (e + MARROW_EPO_HALF_MAX_U_L);
+        response(epo) / response(normal)
+    }
+
+    fn iron_supply_factor(&self) -> f64 {
+        let supply = |t: f64| t / (t + ERYTHROID_SATURATION_HALF_MAX);
+        supply(self.transferrin_saturation()) / supply(REFERENCE_TRANSFERRIN_SATURATION)
+    }
+
+    fn hepcidin_target(&self) -> f64 {
+        (self.storage_iron_mg / REFERENCE_STORAGE_IRON_MG)
+            .max(0.0)
+            .powf(HEPCIDIN_STORAGE_EXPONENT)
+            * self.transferrin_saturation()
+            / REFERENCE_TRANSFERRIN_SATURATION
+            * (1.0 + HEPCIDIN_INFLAMMATION_GAIN * self.inflammation)
+            / self.erythropoietic_drive()
+    }
+
+    pub fn step(&mut self, dt_days: f64) {
+        self.erythropoietin
+            .respond_to_oxygen_delivery(self.arterial_po2_mmhg, self.hemoglobin_g_dl());
+        self.hepcidin += (self.hepcidin_target() - self.hepcidin)
+            * (1.0 - (-dt_days / HEPCIDIN_TIME_CONSTANT_DAYS).exp());
+        let ferroportin = self.ferroportin_activity();
+
+        let synthesis = (reference_production_g_per_day()
+            * self.erythropoietic_drive()
+            * self.iron_supply_factor()
+            / (1.0 + INFLAMMATORY_MARROW_SUPPRESSION * self.inflammation))
+            .min(self.transferrin_iron_mg / IRON_MG_PER_G_HEMOGLOBIN / dt_days);
+        let released = self.precursor_hemoglobin_g / PRECURSOR_TRANSIT_DAYS;
+        let senescent = self.circulating_hemoglobin_g / RED_CELL_LIFESPAN_DAYS
+            * (1.0 + INFLAMMATORY_HEMOLYSIS * self.inflammation);
+
+        let absorbed = self.dietary_iron_mg_per_day * REFERENCE_IRON_LOSS_MG_PER_DAY
+            / REFERENCE_DIETARY_IRON_MG_PER_DAY
+            * ferroportin;
+        let recycled = reference_recycling_capacity() * ferroportin * self.macrophage_iron_mg
+            / (MACROPHAGE_EXPORT_HALF_MAX_MG + self.macrophage_iron_mg);
+        let mobilised = self.storage_iron_mg * REFERENCE_STORAGE_RELEASE_MG_PER_DAY
+            / REFERENCE_STORAGE_IRON_MG
+            * ferroportin;
+        let stored = self.transferrin_iron_mg * reference_storage_uptake_rate();
+
+        self.precursor_hemoglobin_g += (synthesis - released) * dt_days;
+        self.circulating_hemoglobin_g += (released - senescent) * dt_days;
+        // Obligate losses leave through shed enterocytes, skin and blood.
+        self.transferrin_iron_mg += (absorbed + recycled + mobilised
+            - stored
+            - synthesis * IRON_MG_PER_G_HEMOGLOBIN
+            - self.iron_loss_mg_per_day)
+            * dt_days;
+        self.macrophage_iron_mg += (senescent * IRON_MG_PER_G_HEMOGLOBIN - recycled) * dt_days;
+        self.storage_iron_mg = (self.storage_iron_mg + (stored - mobilised) * dt_days).max(0.0);
+        self.transferrin_iron_mg = self.transferrin_iron_mg.max(0.0);
+        self.days += dt_days;
+    }
+
+    pub fn run_days(&mut self, days: f64) {
+        let steps = (days / 0.02).round() as usize;
+        for _ in 0..steps {
+            self.step(0.02);
+        }
+    }
+}
+
+fn reference_production_g_per_day() -> f64 {
+    REFERENCE_HEMOGLOBIN_G_DL * BLOOD_VOLUME_DL / RED_CELL_LIFESPAN_DAYS
+}
+
+// Maximal macrophage export at normal ferroportin.
+fn reference_recycling_capacity() -> f64 {
+    reference_production_g_per_day()
+        * IRON_MG_PER_G_HEMOGLOBIN
+        * (MACROPHAGE_EXPORT_HALF_MAX_MG + REFERENCE_MACROPHAGE_IRON_MG)
+        / REFERENCE_MACROPHAGE_IRON_MG
+}
+
+fn reference_storage_uptake_rate() -> f64 {
+    REFERENCE_STORAGE_RELEASE_MG_PER_DAY
+        / (REFERENCE_TRANSFERRIN_SATURATION * TRANSFERRIN_CAPACITY_MG)
+}
+
+// Red cell mass changes over days; couple `hemoglobin_g_dl` into
+// `respiratory_control` and its arterial PO2 back for the EPO response.
+fn check_inflammation(level: f64) -> BiologyResult<()> {
+    if level.is_nan() || level < 0.0 {
+        return Err(BiologyError::InvalidValue(
+            "inflammation must be non-negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_arterial_po2(mmhg: f64) -> BiologyResult<()> {
+    if mmhg.is_nan() || mmhg <= 0.0 {
+        return Err(BiologyError::InvalidValue(
+            "arterial PO2 must be positive".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl Component for IronHomeostasis {
+    fn kind(&self) -> &'static str {
+        "iron_erythropoiesis"
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[("arterial_po2_mmhg", "mmHg"), ("inflammation", "1")]
+    }
+
+    fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("hemoglobin_g_dl", "g/dL"),
+            ("hematocrit_percent", "%"),
+            ("epo_u_l", "U/L"),
+            ("transferrin_saturation", "1"),
+            ("ferritin_ng_ml", "ng/mL"),
+            ("hepcidin", "1"),
+        ]
+    }
+
+    fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "arterial_po2_mmhg" => self.set_arterial_po2(value),
+            "inflammation" => self.set_inflammation(value),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn check_input(&self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "arterial_po2_mmhg" => check_arterial_po2(value),
+            "inflammation" => check_inflammation(value),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn output(&self, name: &str) -> Option<f64> {
+        match name {
+            "hemoglobin_g_dl" => Some(self.hemoglobin_g_dl()),
+            "hematocrit_percent" => Some(self.hematocrit_percent()),
+            "epo_u_l" => Some(self.erythropoietin.plasma_concentration_u_l),
+            "transferrin_saturation" => Some(self.transferrin_saturation()),
+            "ferritin_ng_ml" => Some(self.serum_ferritin_ng_ml()),
+            "hepcidin" => Some(self.hepcidin),
+            _ => None,
+        }
+    }
+
+    fn step(&mut self, dt_minutes: f64) {
+        // Plasma iron turns over ~10 times a day.
+        substep(dt_minutes, 30.0, |h| {
+            IronHomeostasis::step(self, h / 1440.0)
+        });
+    }
+
+    fn natural_dt_minutes(&self) -> Option<f64> {
+        Some(30.0)
+    }
+
+    fn save_state(&self) -> Value {
+        save(self)
+    }
+
+    fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+        load(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_healthy_steady_state() {
+        let mut iron = IronHomeostasis::new_healthy();
+        let body_iron = iron.total_body_iron_mg();
+        iron.run_days(120.0);
+        assert!((iron.hemoglobin_g_dl() - 15.0).abs() < 0.1);
+        assert!((iron.hematocrit_percent() - 45.0).abs() < 1.0);
+        assert!((iron.transferrin_saturation() - 0.3).abs() < 0.02);
+        assert!((iron.serum_ferritin_ng_ml() - 127.5).abs() < 5.0);
+        assert!((iron.reticulocyte_percent() - 0.83).abs() < 0.05);
+        assert!((iron.total_body_iron_mg() - body_iron).abs() < 10.0);
+        assert!((iron.hepcidin - 1.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_recovery_after_blood_donation() {
+        let mut iron = IronHomeostasis::new_healthy();
+        iron.bleed(500.0).unwrap();
+        assert!((iron.hemoglobin_g_dl() - 13.5).abs() < 0.01);
+        iron.run_days(7.0);
+        assert!(iron.erythropoietin.plasma_concentration_u_l > 10.0);
+        assert!(iron.reticulocyte_percent() > 1.0);
+        assert!(iron.hepcidin < 1.0);
+        iron.run_days(49.0);
+        assert!(
+            iron.hemoglobin_g_dl() > 14.5,
+            "Hb {}",
+            iron.hemoglobin_g_dl()
+        );
+        // The ~250 mg replaced in red cells came out of the stores.
+        assert!(iron.serum_ferritin_ng_ml() < 110.0);
+        assert!(iron.bleed(6000.0).is_err());
+    }
+
+    #[test]
+    fn test_iron_poor_diet_depletes_stores_before_hemoglobin() {
+        let mut iron = IronHomeostasis::new_healthy()
+            .with_dietary_iron(4.0)
+            .unwrap()
+            .with_iron_loss(3.0)
+            .unwrap();
+        iron.run_days(365.0);
+        assert!(iron.hepcidin < 0.5);
+        assert!(iron.storage_iron_mg < REFERENCE_STORAGE_IRON_MG);
+        iron.run_days(3.0 * 365.0);
+        assert!(iron.is_iron_deficient());
+        assert!(iron.transferrin_saturation() < 0.16);
+        assert!(iron.is_anemic(), "Hb {}", iron.hemoglobin_g_dl());
+    }
+
+    #[test]
+    fn test_inflammation_traps_iron() {
+        let mut iron = IronHomeostasis::new_healthy();
+        iron.set_inflammation(2.0).unwrap();
+        iron.run_days(60.0);
+        assert!(iron.hepcidin > 1.5);
+        assert!(iron.transferrin_saturation() < 0.2);
+        assert!(iron.serum_ferritin_ng_ml() > 200.0);
+        assert!(iron.hemoglobin_g_dl() < 14.5);
+        assert!(iron.set_inflammation(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_altitude_raises_hemoglobin() {
+        let mut iron = IronHomeostasis::new_healthy();
+        iron.set_arterial_po2(55.0).unwrap();
+        iron.run_days(90.0);
+        assert!(iron.hemoglobin_g_dl() > 16.0);
+        let carrier = iron.hemoglobin();
+        assert!(carrier.oxygen_binding_capacity_ml_dl() > 1.34 * 16.0);
+    }
+}
@@ -0,0 +1,456 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::cardiovascular::circulation::OrganSupplied;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+// Closed-loop lumped circulation: time-varying elastance ventricles,
+// diode valves and compliance/resistance vascular compartments.
+// Smith BW, Chase JG et al. (2004) Med Eng Phys 26:131-139, PMID 15036180
+
+// Normalized elastance as a double-Hill function of the cardiac cycle.
+// Stergiopulos N, Meister JJ, Westerhof N (1996) Am J Physiol
+// 270:H2050-H2059, PMID 8764256
+const ELASTANCE_ALPHA_1: f64 = 0.303;
+const ELASTANCE_ALPHA_2: f64 = 0.508;
+const ELASTANCE_N_1: f64 = 1.32;
+const ELASTANCE_N_2: f64 = 21.9;
+
+static DOUBLE_HILL_PEAK: Lazy<f64> = Lazy::new(|| {
+    (1..1000)
+        .map(|i| TimeVaryingElastance::double_hill(i as f64 / 1000.0))
+        .fold(0.0, f64::max)
+});
+
+const ACTIVATION_SEGMENTS: usize = 8;
+const INTEGRATION_STEP_S: f64 = 2.0e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeVaryingElastance {
+    pub end_systolic_elastance_mmhg_per_ml: f64,
+    pub end_diastolic_elastance_mmhg_per_ml: f64,
+    pub unstressed_volume_ml: f64,
+    // Stretches the time to peak elastance, e.g. with slowed activation.
+    pub contraction_duration_factor: f64,
+    // Extra spread of regional activation times as a fraction of the cycle;
+    // dyssynchronous segments blunt the global elastance peak.
+    pub activation_dispersion: f64,
+}
+
+impl TimeVaryingElastance {
+    // LV Ees ~2.5 mmHg/mL, V0 ~10 mL.
+    // Suga H, Sagawa K (1974) Circ Res 35:117-126, PMID 4841253
+    pub fn left_ventricle() -> Self {
+        Self {
+            end_systolic_elastance_mmhg_per_ml: 2.5,
+            end_diastolic_elastance_mmhg_per_ml: 0.08,
+            unstressed_volume_ml: 10.0,
+            contraction_duration_factor: 1.0,
+            activation_dispersion: 0.0,
+        }
+    }
+
+    // RV Ees ~0.6 mmHg/mL.
+    // Maughan WL et al. (1979) Circ Res 44:309-315, PMID 761311
+    pub fn right_ventricle() -> Self {
+        Self {
+            end_systolic_elastance_mmhg_per_ml: 0.6,
+            end_diastolic_elastance_mmhg_per_ml: 0.05,
+            unstressed_volume_ml: 10.0,
+            contraction_duration_factor: 1.0,
+            activation_dispersion: 0.0,
+        }
+    }
+
+    fn double_hill(phase: f64) -> f64 {
+        let rise = (phase / ELASTANCE_ALPHA_1).powf(ELASTANCE_N_1);
+        let relax = (phase / ELASTANCE_ALPHA_2).powf(ELASTANCE_N_2);
+        rise / (1.0 + rise) / (1.0 + relax)
+    }
+
+    // Normalized activation in [0, 1] at a fraction of the cardiac cycle.
+    pub fn activation(&self, cycle_phase: f64) -> f64 {
+        if self.activation_dispersion <= 0.0 {
+            return self.segment_activation(cycle_phase);
+        }
+        let segments = ACTIVATION_SEGMENTS as f64;
+        (0..ACTIVATION_SEGMENTS)
+            .map(|i| {
+                let delay = self.activation_dispersion * i as f64 / (segments - 1.0);
+                self.segment_activation(cycle_phase - delay)
+            })
+            .sum::<f64>()
+            / segments
+    }
+
+    fn segment_activation(&self, cycle_phase: f64) -> f64 {
+        let phase = cycle_phase.rem_euclid(1.0) / self.contraction_duration_factor.max(1e-6);
+        Self::double_hill(phase) / *DOUBLE_HILL_PEAK
+    }
+
+    pub fn elastance(&self, cycle_phase: f64) -> f64 {
+        let e = self.activation(cycle_phase);
+        self.end_diastolic_elastance_mmhg_per_ml
+            + (self.end_systolic_elastance_mmhg_per_ml - self.end_diastolic_elastance_mmhg_per_ml)
+                * e
+    }
+
+    pub fn pressure(&self, volume_ml: f64, cycle_phase: f64) -> f64 {
+        self.elastance(cycle_phase) * (volume_ml - self.unstressed_volume_ml)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VascularCompartment {
+    pub compliance_ml_per_mmhg: f64,
+    pub unstressed_volume_ml: f64,
+    pub volume_ml: f64,
+}
+
+impl VascularCompartment {
+    pub fn pressure(&self) -> f64 {
+        (self.volume_ml - self.unstressed_volume_ml) / self.compliance_ml_per_mmhg
+    }
+}
+
+// Cardiac output shares at rest.
+// Williams LR, Leggett RW (1989) Clin Phys Physiol Meas 10:187-217, PMID 2697487
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OrganBed {
+    pub organ: OrganSupplied,
+    pub reference_fraction: f64,
+    pub vasomotor_tone: f64,
+}
+
+fn reference_organ_beds() -> Vec<OrganBed> {
+    [
+        (OrganSupplied::Brain, 0.12),
+        (OrganSupplied::Heart, 0.04),
+        (OrganSupplied::Kidneys, 0.19),
+        (OrganSupplied::Liver, 0.065),
+        (OrganSupplied::Intestines, 0.18),
+        (OrganSupplied::SkeletalMuscle, 0.17),
+        (OrganSupplied::Skin, 0.05),
+        (OrganSupplied::Bone, 0.05),
+        (OrganSupplied::AdiposeTissue, 0.05),
+    ]
+    .into_iter()
+    .map(|(organ, reference_fraction)| OrganBed {
+        organ,
+        reference_fraction,
+        vasomotor_tone: 1.0,
+    })
+    .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CirculationResistances {
+    pub systemic_mmhg_s_per_ml: f64,
+    pub pulmonary_mmhg_s_per_ml: f64,
+    pub aortic_valve: f64,
+    pub mitral_valve: f64,
+    pub tricuspid_valve: f64,
+    pub pulmonic_valve: f64,
+}
+
+impl CirculationResistances {
+    // TPR ~1.0 and PVR ~0.08 mmHg·s/mL in resting adults.
+    pub fn adult_reference() -> Self {
+        Self {
+            systemic_mmhg_s_per_ml: 1.0,
+            pulmonary_mmhg_s_per_ml: 0.08,
+            aortic_valve: 0.02,
+            mitral_valve: 0.01,
+            tricuspid_valve: 0.01,
+            pulmonic_valve: 0.01,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CirculationSample {
+    pub time_s: f64,
+    pub left_ventricular_pressure_mmhg: f64,
+    pub aortic_pressure_mmhg: f64,
+    pub left_ventricular_volume_ml: f64,
+    pub aortic_flow_ml_per_s: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardiacCycleSummary {
+    pub systolic_pressure_mmhg: f64,
+    pub diastolic_pressure_mmhg: f64,
+    pub mean_arterial_pressure_mmhg: f64,
+    pub end_diastolic_volume_ml: f64,
+    pub end_diastolic_pressure_mmhg: f64,
+    pub end_systolic_volume_ml: f64,
+    pub stroke_volume_ml: f64,
+    pub ejection_fraction: f64,
+    pub cardiac_output_l_min: f64,
+    pub central_venous_pressure_mmhg: f64,
+    pub pulmonary_artery_mean_mmhg: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindkesselCirculation {
+    pub heart_rate_bpm: f64,
+    pub left_ventricle: TimeVaryingElastance,
+    pub right_ventricle: TimeVaryingElastance,
+    pub resistances: CirculationResistances,
+    pub organ_beds: Vec<OrganBed>,
+    pub left_ventricular_volume_ml: f64,
+    pub right_ventricular_volume_ml: f64,
+    pub systemic_arteries: VascularCompartment,
+    pub systemic_veins: VascularCompartment,
+    pub pulmonary_arteries: VascularCompartment,
+    pub pulmonary_veins: VascularCompartment,
+    pub elapsed_s: f64,
+}
+
+impl WindkesselCirculation {
+    // Compartment compliances and unstressed volumes for a 5 L adult.
+    // Smith BW et al. (2004) Med Eng Phys 26:131-139; Stergiopulos N et al.
+    // (1999) Am J Physiol 276:H81-H88, PMID 9887020 (arterial C ~1-2 mL/mmHg)
+    pub fn new_adult() -> Self {
+        Self {
+            heart_rate_bpm: 70.0,
+            left_ventricle: TimeVaryingElastance::left_ventricle(),
+            right_ventricle: TimeVaryingElastance::right_ventricle(),
+            resistances: CirculationResistances::adult_reference(),
+            organ_beds: reference_organ_beds(),
+            left_ventricular_volume_ml: 120.0,
+            right_ventricular_volume_ml: 120.0,
+            systemic_arteries: VascularCompartment {
+                compliance_ml_per_mmhg: 1.2,
+                unstressed_volume_ml: 600.0,
+                volume_ml: 710.0,
+            },
+            systemic_veins: VascularCompartment {
+                compliance_ml_per_mmhg: 60.0,
+                unstressed_volume_ml: 3100.0,
+                volume_ml: 3450.0,
+            },
+            pulmonary_arteries: VascularCompartment {
+                compliance_ml_per_mmhg: 4.0,
+                unstressed_volume_ml: 90.0,
+                volume_ml: 150.0,
+            },
+            pulmonary_veins: VascularCompartment {
+                compliance_ml_per_mmhg: 15.0,
+                unstressed_volume_ml: 300.0,
+                volume_ml: 420.0,
+            },
+            elapsed_s: 0.0,
+        }
+    }
+
+    pub fn set_heart_rate(&mut self, bpm: f64) -> BiologyResult<()> {
+        if !(20.0..=250.0).contains(&bpm) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "heart rate {bpm} bpm outside 20-250"
+            )));
+        }
+        self.heart_rate_bpm = bpm;
+        Ok(())
+    }
+
+    pub fn set_vasomotor_tone(&mut self, organ: OrganSupplied, tone: f64) -> BiologyResult<()> {
+        if tone <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "vasomotor tone must be positive".to_string(),
+            ));
+        }
+        let bed = self
+            .organ_beds
+            .iter_mut()
+            .find(|b| b.organ == organ)
+            .ok_or_else(|| BiologyError::InvalidParameter(format!("no bed for {organ:?}")))?;
+        bed.vasomotor_tone = tone;
+        Ok(())
+    }
+
+    pub fn total_blood_volume_ml(&self) -> f64 {
+        self.left_ventricular_volume_ml
+            + self.right_ventricular_volume_ml
+            + self.systemic_arteries.volume_ml
+            + self.systemic_veins.volume_ml
+            + self.pulmonary_arteries.volume_ml
+            + self.pulmonary_veins.volume_ml
+    }
+
+    fn cycle_period_s(&self) -> f64 {
+        60.0 / self.heart_rate_bpm
+    }
+
+    // Parallel organ beds, each R_ref / fraction scaled by its tone.
+    pub fn systemic_conductance(&self) -> f64 {
+        self.organ_beds
+            .iter()
+            .map(|b| {
+                b.reference_fraction / (b.vasomotor_tone * self.resistances.systemic_mmhg_s_per_ml)
+            })
+            .sum()
+    }
+
+    pub fn organ_perfusion_fractions(&self) -> Vec<(OrganSupplied, f64)> {
+        let total = self.systemic_conductance();
+        self.organ_beds
+            .iter()
+            .map(|b| {
+                let g = b.reference_fraction
+                    / (b.vasomotor_tone * self.resistances.systemic_mmhg_s_per_ml);
+                (b.organ, g / total)
+            })
+            .collect()
+    }
+
+    pub fn step(&mut self, dt_s: f64) -> CirculationSample {
+        let phase = self.elapsed_s / self.cycle_period_s();
+        let p_lv = self
+            .left_ventricle
+            .pressure(self.left_ventricular_volume_ml, phase);
+        let p_rv = self
+            .right_ventricle
+            .pressure(self.right_ventricular_volume_ml, phase);
+        let p_sa = self.systemic_arteries.pressure();
+        let p_sv = self.systemic_veins.pressure();
+        let p_pa = self.pulmonary_arteries.pressure();
+        let p_pv = self.pulmonary_veins.pressure();
+        let r = self.resistances;
+
+        let q_aortic = ((p_lv - p_sa) / r.aortic_valve).max(0.0);
+        let q_systemic = (p_sa - p_sv) * self.systemic_conductance();
+        let q_tricuspid = ((p_sv - p_rv) / r.tricuspid_valve).max(0.0);
+        let q_pulmonic = ((p_rv - p_pa) / r.pulmonic_valve).max(0.0);
+        let q_lung = (p_pa - p_pv) / r.pulmonary_mmhg_s_per_ml;
+        let q_mitral = ((p_pv - p_lv) / r.mitral_valve).max(0.0);
+
+        self.left_ventricular_volume_ml += (q_mitral - q_aortic) * dt_s;
+        self.systemic_arteries.volume_ml += (q_aortic - q_systemic) * dt_s;
+        self.systemic_veins.volume_ml += (q_systemic - q_tricuspid) * dt_s;
+        self.right_ventricular_volume_ml += (q_tricuspid - q_pulmonic) * dt_s;
+        self.pulmonary_arteries.volume_ml += (q_pulmonic - q_lung) * dt_s;
+        self.pulmonary_veins.volume_ml += (q_lung - q_mitral) * dt_s;
+        self.elapsed_s += dt_s;
+
+        CirculationSample {
+            time_s: self.elapsed_s,
+            left_ventricular_pressure_mmhg: p_lv,
+            aortic_pressure_mmhg: p_sa,
+            left_ventricular_volume_ml: self.left_ventricular_volume_ml,
+            aortic_flow_ml_per_s: q_aortic,
+        }
+    }
+
+    pub fn simulate_beat(&mut self) -> Vec<CirculationSample> {
+        let steps = (self.cycle_period_s() / INTEGRATION_STEP_S).round() as usize;
+        (0..steps).map(|_| self.step(INTEGRATION_STEP_S)).collect()
+    }
+
+    // Runs `beats` cycles and summarises the last one.
+    pub fn run_beats(&mut self, beats: usize) -> CardiacCycleSummary {
+        let mut waveform = Vec::new();
+        let mut cvp = 0.0;
+        let mut pa = 0.0;
+        for _ in 0..beats.max(1) {
+            waveform = self.simulate_beat();
+            cvp = self.systemic_veins.pressure();
+            pa = self.pulmonary_arteries.pressure();
+        }
+        self.summarize(&waveform, cvp, pa)
+    }
+
+    fn summarize(&self, beat: &[CirculationSample], cvp: f64, pa: f64) -> CardiacCycleSummary {
+        let period = self.cycle_period_s();
+        let aortic = beat.iter().map(|s| s.aortic_pressure_mmhg);
+        let systolic = aortic.clone().fold(f64::MIN, f64::max);
+        let diastolic = aortic.clone().fold(f64::MAX, f64::min);
+        let mean = aortic.sum::<f64>() / beat.len().max(1) as f64;
+        let volumes = beat.iter().map(|s| s.left_ventricular_volume_ml);
+        let edv = volumes.clone().fold(f64::MIN, f64::max);
+        let edp = beat
+            .iter()
+            .find(|s| s.left_ventricular_volume_ml == edv)
+            .map_or(0.0, |s| s.left_ventricular_pressure_mmhg);
+        let esv = volumes.fold(f64::MAX, f64::min);
+        let ejected: f64 = beat
+            .iter()
+            .map(|s| s.aortic_flow_ml_per_s * INTEGRATION_STEP_S)
+            .sum();
+        CardiacCycleSummary {
+            systolic_pressure_mmhg: systolic,
+            diastolic_pressure_mmhg: diastolic,
+            mean_arterial_pressure_mmhg: mean,
+            end_diastolic_volume_ml: edv,
+            end_diastolic_pressure_mmhg: edp,
+            end_systolic_volume_ml: esv,
+            stroke_volume_ml: ejected,
+            ejection_fraction: ejected / edv.max(1e-9),
+            cardiac_output_l_min: ejected * 60.0 / period / 1000.0,
+            central_venous_pressure_mmhg: cvp,
+            pulmonary_artery_mean_mmhg: pa,
+        }
+    }
+}
+
+impl Default for WindkesselCirculation {
+    fn default() -> Self {
+        Self::new_adult()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::validation::ground_truth::GroundTruthDatabase;
+
+    #[test]
+    fn test_resting_hemodynamics_match_reference() {
+        let db = GroundTruthDatabase::new();
+        let cv = db.get_dataset("cardiovascular").unwrap();
+        let summary = WindkesselCirculation::new_adult().run_beats(20);
+        assert!(cv.is_within_expected_range("systolic_bp_mmhg", summary.systolic_pressure_mmhg));
+        assert!(cv.is_within_expected_range("diastolic_bp_mmhg", summary.diastolic_pressure_mmhg));
+        assert!(summary.cardiac_output_l_min > 4.0 && summary.cardiac_output_l_min < 6.5);
+        assert!(summary.ejection_fraction > 0.5 && summary.ejection_fraction < 0.7);
+        assert!(summary.central_venous_pressure_mmhg > 2.0);
+        assert!(summary.central_venous_pressure_mmhg < 8.0);
+        assert!(summary.pulmonary_artery_mean_mmhg > 9.0);
+        assert!(summary.pulmonary_artery_mean_mmhg < 20.0);
+    }
+
+    #[test]
+    fn test_blood_volume_is_conserved() {
+        let mut circulation = WindkesselCirculation::new_adult();
+        let before = circulation.total_blood_volume_ml();
+        circulation.run_beats(5);
+        assert!((circulation.total_blood_volume_ml() - before).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_depressed_contractility_lowers_ejection_fraction() {
+        let normal = WindkesselCirculation::new_adult().run_beats(20);
+        let mut failing = WindkesselCirculation::new_adult();
+        failing.left_ventricle.end_systolic_elastance_mmhg_per_ml = 0.8;
+        let failing = failing.run_beats(20);
+        assert!(failing.ejection_fraction < normal.ejection_fraction - 0.15);
+        assert!(failing.end_diastolic_volume_ml > normal.end_diastolic_volume_ml);
+        assert!(failing.cardiac_output_l_min < normal.cardiac_output_l_min);
+    }
+
+    #[test]
+    fn test_vasoconstriction_redistributes_perfusion() {
+        let mut circulation = WindkesselCirculation::new_adult();
+        let skin_before = circulation.organ_perfusion_fractions()[6].1;
+        circulation
+            .set_vasomotor_tone(OrganSupplied::Skin, 4.0)
+            .unwrap();
+        let fractions = circulation.organ_perfusion_fractions();
+        assert!((fractions.iter().map(|(_, f)| f).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(fractions[6].1 < 0.5 * skin_before);
+        assert!(circulation.set_heart_rate(300.0).is_err());
+        assert!(circulation
+            .set_vasomotor_tone(OrganSupplied::Lungs, 2.0)
+            .is_err());
+    }
+}
@@ -12,6 +12,10 @@ pub mod heart;
 pub mod hematology;
 pub mod hematopoiesis;
 pub mod hemodynamics;
+pub mod iron_homeostasis;
+pub mod lumped_circulation;
+pub mod vascular_tree;
+pub mod whole_heart;
 
 pub use blood::{Blood, BloodCell, BloodComponent, BloodType, CellCount, PlasmaComposition};
 pub use blood_vessel::{BloodVessel, VesselLayer, VesselType};
@@ -45,3 +49,9 @@ pub use hematopoiesis::{
     Thrombopoiesis,
 };
 pub use hemodynamics::{BloodFlow, BloodPressure, Hemodynamics};
+pub use iron_homeostasis::IronHomeostasis;
+pub use lumped_circulation::{
+    CardiacCycleSummary, CirculationResistances, CirculationSample, OrganBed, TimeVaryingElastance,
+    VascularCompartment, WindkesselCirculation,
+};
+pub use whole_heart::WholeHeartPump;
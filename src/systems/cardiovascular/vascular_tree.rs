@@ -0,0 +1,191 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::geometry::Vec3;
+use serde::{Deserialize, Serialize};
+
+// Branching vessel geometry as straight segments joined end to start,
+// each with a parent, so a tree can be traversed root to leaves.
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct VesselSegment {
+    pub parent: Option<usize>,
+    pub proximal: Vec3,
+    pub distal: Vec3,
+    pub radius_mm: f64,
+}
+
+impl VesselSegment {
+    pub fn length_mm(&self) -> f64 {
+        self.proximal.distance(self.distal)
+    }
+
+    pub fn volume_mm3(&self) -> f64 {
+        std::f64::consts::PI * self.radius_mm.powi(2) * self.length_mm()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VesselTree {
+    pub segments: Vec<VesselSegment>,
+}
+
+impl VesselTree {
+    pub fn new(proximal: Vec3, distal: Vec3, radius_mm: f64) -> BiologyResult<Self> {
+        let mut tree = Self::default();
+        tree.push(None, proximal, distal, radius_mm)?;
+        Ok(tree)
+    }
+
+    // Grows a child from the distal end of `parent`.
+    pub fn branch(&mut self, parent: usize, distal: Vec3, radius_mm: f64) -> BiologyResult<usize> {
+        let start = self
+            .segments
+            .get(parent)
+            .ok_or_else(|| BiologyError::InvalidParameter(format!("no segment {}", parent)))?
+            .distal;
+        self.push(Some(parent), start, distal, radius_mm)
+    }
+
+    fn push(
+        &mut self,
+        parent: Option<usize>,
+        proximal: Vec3,
+        distal: Vec3,
+        radius_mm: f64,
+    ) -> BiologyResult<usize> {
+        if radius_mm.is_nan() || radius_mm <= 0.0 {
+            return Err(BiologyError::InvalidValue(format!(
+                "vessel radius must be positive, got {}",
+                radius_mm
+            )));
+        }
+        self.segments.push(VesselSegment {
+            parent,
+            proximal,
+            distal,
+            radius_mm,
+        });
+        Ok(self.segments.len() - 1)
+    }
+
+    // Symmetric dichotomous tree along +z. Daughter radii follow Murray's
+    // cube law, r0³ = r1³ + r2³, and lengths keep the parent's
+    // length-to-radius ratio; sister branches split by `branch_angle_deg`
+    // in planes that rotate 90° each generation.
+    // Murray CD (1926) Proc Natl Acad Sci USA 12:207-214
+    pub fn murray(
+        generations: usize,
+        root_radius_mm: f64,
+        root_length_mm: f64,
+        branch_angle_deg: f64,
+    ) -> BiologyResult<Self> {
+        if root_length_mm.is_nan() || root_length_mm <= 0.0 {
+            return Err(BiologyError::InvalidValue(format!(
+                "root length must be positive, got {}",
+                root_length_mm
+            )));
+        }
+        if !(0.0..180.0).contains(&branch_angle_deg) {
+            return Err(BiologyError::InvalidValue(format!(
+                "branch angle must be in [0, 180), got {}",
+                branch_angle_deg
+            )));
+        }
+        let mut tree = VesselTree::new(
+            Vec3::ZERO,
+            Vec3::new(0.0, 0.0, root_length_mm),
+            root_radius_mm,
+        )?;
+        let ratio = 0.5_f64.powf(1.0 / 3.0);
+        let half_angle = (branch_angle_deg / 2.0).to_radians();
+        let mut frontier = vec![(0, Vec3::new(0.0, 0.0, 1.0))];
+        for generation in 1..=generations {
+            let mut next = Vec::with_capacity(frontier.len() * 2);
+            for (parent, direction) in frontier {
+                let segment = tree.segments[parent];
+                let radius = segment.radius_mm * ratio;
+                let length = segment.length_mm() * ratio;
+                let reference = if generation % 2 == 1 {
+                    Vec3::new(1.0, 0.0, 0.0)
+                } else {
+                    Vec3::new(0.0, 1.0, 0.0)
+                };
+                let side = reference - direction * reference.dot(direction);
+                let side = if side.norm() > 1e-9 {
+                    side.normalized()
+                } else {
+                    direction.cross(Vec3::new(0.0, 0.0, 1.0)).normalized()
+                };
+                for sign in [1.0, -1.0] {
+                    let child = (direction * half_angle.cos() + side * (sign * half_angle.sin()))
+                        .normalized();
+                    let index = tree.branch(parent, segment.distal + child * length, radius)?;
+                    next.push((index, child));
+                }
+            }
+            frontier = next;
+        }
+        Ok(tree)
+    }
+
+    pub fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.segments
+            .iter()
+            .enumerate()
+            .filter(move |(_, s)| s.parent == Some(index))
+            .map(|(i, _)| i)
+    }
+
+    pub fn terminal_count(&self) -> usize {
+        (0..self.segments.len())
+            .filter(|&i| self.children(i).next().is_none())
+            .count()
+    }
+
+    pub fn volume_ml(&self) -> f64 {
+        self.segments.iter().map(|s| s.volume_mm3()).sum::<f64>() / 1000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_murray_tree_obeys_cube_law() {
+        let tree = VesselTree::murray(4, 2.0, 20.0, 70.0).unwrap();
+        assert_eq!(tree.segments.len(), 31);
+        assert_eq!(tree.terminal_count(), 16);
+        for (i, segment) in tree.segments.iter().enumerate() {
+            let children: Vec<usize> = tree.children(i).collect();
+            if children.is_empty() {
+                continue;
+            }
+            let daughters: f64 = children
+                .iter()
+                .map(|&c| tree.segments[c].radius_mm.powi(3))
+                .sum();
+            assert!((daughters - segment.radius_mm.powi(3)).abs() < 1e-9);
+            for &c in &children {
+                assert!(tree.segments[c].proximal.distance(segment.distal) < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_branch_angle_between_sisters() {
+        let tree = VesselTree::murray(1, 1.0, 10.0, 60.0).unwrap();
+        let direction = |s: &VesselSegment| (s.distal - s.proximal).normalized();
+        let angle = direction(&tree.segments[1])
+            .dot(direction(&tree.segments[2]))
+            .acos();
+        assert!((angle.to_degrees() - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rejects_invalid_geometry() {
+        assert!(VesselTree::murray(2, -1.0, 10.0, 60.0).is_err());
+        assert!(VesselTree::murray(2, 1.0, 10.0, 180.0).is_err());
+        let mut tree = VesselTree::new(Vec3::ZERO, Vec3::new(0.0, 0.0, 1.0), 1.0).unwrap();
+        assert!(tree.branch(3, Vec3::ZERO, 0.5).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::pathology::fibrosis::{FibrosisModel, FibroticTissue};
+use crate::systems::cardiovascular::electrophysiology::{ActionPotential, ECG};
+use crate::systems::cardiovascular::lumped_circulation::{
+    CardiacCycleSummary, TimeVaryingElastance, WindkesselCirculation,
+};
+use serde::{Deserialize, Serialize};
+
+// Longitudinal conduction in working ventricular myocardium ~0.6 m/s.
+// Kléber AG, Rudy Y (2004) Physiol Rev 84:431-488, PMID 15044680
+pub const MYOCARDIAL_CONDUCTION_VELOCITY_M_PER_S: f64 = 0.6;
+// Activation path giving a ~100 ms QRS at normal velocity.
+const LV_ACTIVATION_PATH_MM: f64 = 60.0;
+// Interstitial collagen septa force zig-zag conduction; slowing rises with
+// collagen excess over normal content.
+// Kawara T et al. (2001) Circulation 104:3069-3075, PMID 11748102
+const COLLAGEN_CONDUCTION_SLOWING: f64 = 0.1;
+// Titin dominates passive myocardial tension in the working sarcomere
+// range; collagen carries the minority share.
+// Granzier HL, Irving TC (1995) Biophys J 68:1027-1044, PMID 7756523
+const COLLAGEN_SHARE_OF_PASSIVE_STIFFNESS: f64 = 0.25;
+const REFERENCE_VENTRICULAR_APD_MS: f64 = 300.0;
+
+// Organ-level pump assembled from the myocyte action potential, the
+// myocardial fibrosis state and the closed-loop circulation: scar replaces
+// contractile mass and stiffens the wall, interstitial collagen slows
+// conduction and desynchronises contraction, and ejection fraction falls
+// out of the coupled circulation rather than being prescribed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WholeHeartPump {
+    pub circulation: WindkesselCirculation,
+    pub myocardium: FibrosisModel,
+    pub action_potential: ActionPotential,
+    pub healthy_left_ventricle: TimeVaryingElastance,
+}
+
+impl WholeHeartPump {
+    pub fn new_healthy() -> Self {
+        let circulation = WindkesselCirculation::new_adult();
+        Self {
+            healthy_left_ventricle: circulation.left_ventricle,
+            circulation,
+            myocardium: FibrosisModel::new(FibroticTissue::Myocardium),
+            action_potential: ActionPotential::new_ventricular(),
+        }
+    }
+
+    pub fn myocardial_infarction(&mut self, infarct_fraction: f64) -> BiologyResult<()> {
+        if !(0.0..0.8).contains(&infarct_fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "infarct fraction must lie in [0, 0.8)".to_string(),
+            ));
+        }
+        self.myocardium.injure(infarct_fraction)
+    }
+
+    // Diffuse reactive fibrosis, e.g. from pressure overload.
+    pub fn set_interstitial_fibrosis_drive(&mut self, level: f64) -> BiologyResult<()> {
+        self.myocardium.set_persistent_stimulus(level)
+    }
+
+    pub fn remodel_days(&mut self, days: usize) {
+        self.myocardium.run_days(days);
+    }
+
+    pub fn conduction_velocity_m_per_s(&self) -> f64 {
+        let excess = (self.myocardium.total_collagen() - 1.0).max(0.0);
+        MYOCARDIAL_CONDUCTION_VELOCITY_M_PER_S / (1.0 + COLLAGEN_CONDUCTION_SLOWING * excess)
+    }
+
+    pub fn qrs_duration_ms(&self) -> f64 {
+        LV_ACTIVATION_PATH_MM / self.conduction_velocity_m_per_s()
+    }
+
+    pub fn ecg(&self) -> ECG {
+        let mut ecg = ECG::new_normal(self.circulation.heart_rate_bpm);
+        ecg.qrs_duration_ms = self.qrs_duration_ms();
+        ecg.qt_interval_ms += self.action_potential.duration_ms - REFERENCE_VENTRICULAR_APD_MS
+            + (ecg.qrs_duration_ms - 100.0);
+        ecg
+    }
+
+    // Maps tissue state onto the time-varying elastance of the LV.
+    pub fn coupled_left_ventricle(&self) -> TimeVaryingElastance {
+        let healthy = self.healthy_left_ventricle;
+        let scar = self.myocardium.defect_fraction;
+        let matrix = self.myocardium.mechanics().elastic_modulus_relative;
+        let passive_scale = 1.0 - COLLAGEN_SHARE_OF_PASSIVE_STIFFNESS
+            + COLLAGEN_SHARE_OF_PASSIVE_STIFFNESS * matrix;
+        let period_ms = 60_000.0 / self.circulation.heart_rate_bpm;
+        let baseline_qrs = LV_ACTIVATION_PATH_MM / MYOCARDIAL_CONDUCTION_VELOCITY_M_PER_S;
+
+        TimeVaryingElastance {
+            end_systolic_elastance_mmhg_per_ml: healthy.end_systolic_elastance_mmhg_per_ml
+                * (1.0 - scar),
+            end_diastolic_elastance_mmhg_per_ml: healthy.end_diastolic_elastance_mmhg_per_ml
+                * passive_scale,
+            unstressed_volume_ml: healthy.unstressed_volume_ml,
+            contraction_duration_factor: healthy.contraction_duration_factor
+                * self.action_potential.duration_ms
+                / REFERENCE_VENTRICULAR_APD_MS,
+            activation_dispersion: ((self.qrs_duration_ms() - baseline_qrs) / period_ms).max(0.0),
+        }
+    }
+
+    pub fn pump(&mut self, beats: usize) -> CardiacCycleSummary {
+        self.circulation.left_ventricle = self.coupled_left_ventricle();
+        self.circulation.run_beats(beats)
+    }
+}
+
+impl Default for WholeHeartPump {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_healthy_heart_has_normal_ejection_and_qrs() {
+        let mut heart = WholeHeartPump::new_healthy();
+        let summary = heart.pump(20);
+        assert!(summary.ejection_fraction > 0.5);
+        assert!((heart.qrs_duration_ms() - 100.0).abs() < 1.0);
+        assert!(!heart.ecg().has_bundle_branch_block());
+    }
+
+    #[test]
+    fn test_infarct_scar_degrades_ejection_fraction() {
+        let healthy = WholeHeartPump::new_healthy().pump(20);
+        let mut heart = WholeHeartPump::new_healthy();
+        heart.myocardial_infarction(0.3).unwrap();
+        heart.remodel_days(365);
+        let remodeled = heart.pump(20);
+        assert!(remodeled.ejection_fraction < healthy.ejection_fraction - 0.07);
+        assert!(remodeled.end_diastolic_pressure_mmhg > healthy.end_diastolic_pressure_mmhg);
+        assert!(heart.ecg().has_bundle_branch_block());
+        assert!(heart.myocardial_infarction(0.9).is_err());
+    }
+
+    #[test]
+    fn test_dyssynchrony_alone_lowers_ejection() {
+        let synchronous = WindkesselCirculation::new_adult().run_beats(20);
+        let mut circulation = WindkesselCirculation::new_adult();
+        circulation.left_ventricle.activation_dispersion = 0.1;
+        let dyssynchronous = circulation.run_beats(20);
+        assert!(dyssynchronous.ejection_fraction < synchronous.ejection_fraction);
+    }
+
+    #[test]
+    fn test_diffuse_fibrosis_raises_filling_pressure_with_preserved_ejection() {
+        let healthy = WholeHeartPump::new_healthy().pump(20);
+        let mut heart = WholeHeartPump::new_healthy();
+        heart.set_interstitial_fibrosis_drive(0.05).unwrap();
+        heart.remodel_days(365);
+        let stiff = heart.pump(20);
+        assert!(stiff.end_diastolic_pressure_mmhg > 1.4 * healthy.end_diastolic_pressure_mmhg);
+        assert!(stiff.end_diastolic_volume_ml < healthy.end_diastolic_volume_ml);
+        assert!(stiff.ejection_fraction > 0.5);
+    }
+}
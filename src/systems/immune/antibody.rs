@@ -0,0 +1,359 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::immune::adjuvant::{Adjuvant, ApcActivation};
+use crate::systems::immune::cold_chain::VaccineLot;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AntibodyClass {
+    IgM,
+    IgG,
+    IgA,
+}
+
+// Serum antibody after a response peaks is the sum of two pools: output of
+// short-lived plasmablasts, which die within weeks, and of long-lived
+// bone-marrow plasma cells, which sustain titres for years.
+//   titre(t) = peak * (f * 2^(-t / t_short) + (1 - f) * 2^(-t / t_long))
+// Amanna IJ, Carlson NE, Slifka MK (2007) N Engl J Med 357:1903-1915
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AntibodyKinetics {
+    pub short_lived_fraction: f64,
+    pub short_half_life_days: f64,
+    pub long_half_life_days: f64,
+}
+
+impl AntibodyKinetics {
+    pub fn new(
+        short_lived_fraction: f64,
+        short_half_life_days: f64,
+        long_half_life_days: f64,
+    ) -> BiologyResult<Self> {
+        if !(0.0..=1.0).contains(&short_lived_fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "short-lived fraction must lie in [0, 1]".to_string(),
+            ));
+        }
+        if short_half_life_days.is_nan()
+            || long_half_life_days.is_nan()
+            || short_half_life_days <= 0.0
+            || long_half_life_days < short_half_life_days
+        {
+            return Err(BiologyError::InvalidParameter(
+                "half-lives must be positive with the long-lived phase the slower".to_string(),
+            ));
+        }
+        Ok(Self {
+            short_lived_fraction,
+            short_half_life_days,
+            long_half_life_days,
+        })
+    }
+
+    // Primary IgM comes from short-lived cells only; its ~5 day serum
+    // half-life sets the whole decay.
+    pub fn igm() -> Self {
+        Self::new(1.0, 5.0, 5.0).expect("valid preset")
+    }
+
+    // Vaccine IgG: most of the peak is plasmablast output lost over
+    // months, with a long-lived tail like anti-tetanus (~11 years).
+    pub fn igg() -> Self {
+        Self::new(0.95, 35.0, 11.0 * 365.25).expect("valid preset")
+    }
+
+    // Serum IgA, ~6 day half-life, without a durable plasma-cell phase.
+    pub fn iga() -> Self {
+        Self::new(1.0, 6.0, 6.0).expect("valid preset")
+    }
+
+    pub fn for_class(class: AntibodyClass) -> Self {
+        match class {
+            AntibodyClass::IgM => Self::igm(),
+            AntibodyClass::IgG => Self::igg(),
+            AntibodyClass::IgA => Self::iga(),
+        }
+    }
+
+    pub fn fraction_remaining(&self, days_since_peak: f64) -> f64 {
+        let t = days_since_peak.max(0.0);
+        let f = self.short_lived_fraction;
+        f * 0.5f64.powf(t / self.short_half_life_days)
+            + (1.0 - f) * 0.5f64.powf(t / self.long_half_life_days)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AntibodyTiter {
+    pub peak: f64,
+    pub peak_day: f64,
+    pub kinetics: AntibodyKinetics,
+}
+
+// Correlates of protection, in the units the titres are recorded in.
+// Anti-HBs >= 10 mIU/mL: Schillie S et al. (2018) MMWR Recomm Rep 67(1):1-31
+pub const ANTI_HBS_SEROPROTECTION_MIU_ML: f64 = 10.0;
+// Measles neutralising antibody >= 120 mIU/mL:
+// Chen RT et al. (1990) J Infect Dis 162:1036-1042
+pub const MEASLES_SEROPROTECTION_MIU_ML: f64 = 120.0;
+// Anti-tetanus toxoid >= 0.1 IU/mL by ELISA: WHO (2018) Immunological
+// Basis for Immunization Series, module 3
+pub const TETANUS_SEROPROTECTION_IU_ML: f64 = 0.1;
+
+// Peak titres per unit of antigen presented (µg x presentation
+// integral), scaled so 20 µg of unadjuvanted protein gives an IgG peak of
+// a few hundred mIU/mL.
+const IGG_PEAK_PER_PRESENTATION: f64 = 40.0;
+const IGM_PEAK_PER_PRESENTATION: f64 = 4.0;
+// Germinal-centre delay from antigen presentation to the class peaks.
+const IGM_PEAK_DELAY_DAYS: f64 = 7.0;
+const IGG_PEAK_DELAY_DAYS: f64 = 14.0;
+const PRESENTATION_WINDOW_DAYS: usize = 60;
+
+// Antibody titres by class after a response, with per-class protection
+// thresholds. Days are counted on the caller's clock, e.g. from the first
+// vaccine dose.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImmuneResponse {
+    pub titers: BTreeMap<AntibodyClass, AntibodyTiter>,
+    pub seroprotection_thresholds: BTreeMap<AntibodyClass, f64>,
+}
+
+impl ImmuneResponse {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Primary response to one dose: the injection-site APC model sets how
+    // much antigen is presented and when, and titres follow with a fixed
+    // germinal-centre delay.
+    pub fn from_vaccine(antigen_ug: f64, adjuvant: Option<&Adjuvant>) -> BiologyResult<Self> {
+        if antigen_ug.is_nan() || antigen_ug <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "antigen dose must be positive".to_string(),
+            ));
+        }
+        let mut site = ApcActivation::after_injection(adjuvant);
+        site.run_days(PRESENTATION_WINDOW_DAYS);
+        let presented = antigen_ug * site.presented;
+        let centre = site.mean_presentation_day();
+        Self::new()
+            .with_titer(
+                AntibodyClass::IgM,
+                IGM_PEAK_PER_PRESENTATION * presented,
+                centre + IGM_PEAK_DELAY_DAYS,
+                AntibodyKinetics::igm(),
+            )?
+            .with_titer(
+                AntibodyClass::IgG,
+                IGG_PEAK_PER_PRESENTATION * presented,
+                centre + IGG_PEAK_DELAY_DAYS,
+                AntibodyKinetics::igg(),
+            )
+    }
+
+    // As `from_vaccine`, with the dose scaled by what storage left of the
+    // lot's potency.
+    pub fn from_vaccine_lot(
+        lot: &VaccineLot,
+        antigen_ug: f64,
+        adjuvant: Option<&Adjuvant>,
+    ) -> BiologyResult<Self> {
+        Self::from_vaccine(antigen_ug * lot.potency, adjuvant)
+    }
+
+    // Replaces any earlier titre of the class, as a booster resets the
+    // peak.
+    pub fn with_titer(
+        mut self,
+        class: AntibodyClass,
+        peak: f64,
+        peak_day: f64,
+        kinetics: AntibodyKinetics,
+    ) -> BiologyResult<Self> {
+        if peak.is_nan() || peak < 0.0 || !peak_day.is_finite() {
+            return Err(BiologyError::InvalidValue(
+                "peak titre must be non-negative at a finite day".to_string(),
+            ));
+        }
+        self.titers.insert(
+            class,
+            AntibodyTiter {
+                peak,
+                peak_day,
+                kinetics,
+            },
+        );
+        Ok(self)
+    }
+
+    pub fn with_seroprotection_threshold(
+        mut self,
+        class: AntibodyClass,
+        threshold: f64,
+    ) -> BiologyResult<Self> {
+        if threshold.is_nan() || threshold <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "seroprotection threshold must be positive".to_string(),
+            ));
+        }
+        self.seroprotection_thresholds.insert(class, threshold);
+        Ok(self)
+    }
+
+    // Titre on `day`; the rise to peak is not modelled, so days before
+    // the peak read as zero.
+    pub fn titer(&self, class: AntibodyClass, day: f64) -> f64 {
+        match self.titers.get(&class) {
+            Some(t) if day >= t.peak_day => {
+                t.peak * t.kinetics.fraction_remaining(day - t.peak_day)
+            }
+            _ => 0.0,
+        }
+    }
+
+    pub fn is_seroprotected(&self, class: AntibodyClass, day: f64) -> bool {
+        self.seroprotection_thresholds
+            .get(&class)
+            .is_some_and(|&threshold| self.titer(class, day) >= threshold)
+    }
+
+    // Day the titre falls below the class threshold. None without a titre
+    // or threshold for the class, or when the peak never reached it.
+    pub fn time_to_seronegative(&self, class: AntibodyClass) -> Option<f64> {
+        let titer = self.titers.get(&class)?;
+        let threshold = *self.seroprotection_thresholds.get(&class)?;
+        if titer.peak < threshold {
+            return None;
+        }
+        // Both phases decay, so the titre is monotone after the peak:
+        // bracket the crossing by doubling, then bisect.
+        let target = threshold / titer.peak;
+        let mut high = titer.kinetics.short_half_life_days;
+        while titer.kinetics.fraction_remaining(high) >= target {
+            high *= 2.0;
+        }
+        let mut low = 0.0;
+        for _ in 0..100 {
+            let mid = 0.5 * (low + high);
+            if titer.kinetics.fraction_remaining(mid) >= target {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Some(titer.peak_day + high)
+    }
+
+    // Classes protective on `day`, in class order.
+    pub fn protective_classes(&self, day: f64) -> Vec<AntibodyClass> {
+        self.seroprotection_thresholds
+            .keys()
+            .copied()
+            .filter(|&class| self.is_seroprotected(class, day))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hepatitis_b(peak_miu_ml: f64) -> ImmuneResponse {
+        ImmuneResponse::new()
+            .with_titer(
+                AntibodyClass::IgG,
+                peak_miu_ml,
+                210.0,
+                AntibodyKinetics::igg(),
+            )
+            .unwrap()
+            .with_titer(AntibodyClass::IgM, 50.0, 14.0, AntibodyKinetics::igm())
+            .unwrap()
+            .with_seroprotection_threshold(AntibodyClass::IgG, ANTI_HBS_SEROPROTECTION_MIU_ML)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_biphasic_decay_slows_onto_long_lived_tail() {
+        let kinetics = AntibodyKinetics::igg();
+        assert_eq!(kinetics.fraction_remaining(0.0), 1.0);
+        // After the plasmablast phase is gone only the 5% tail remains,
+        // which then halves on the long half-life.
+        let one_year = kinetics.fraction_remaining(365.25);
+        assert!((one_year - 0.05 * 0.5f64.powf(1.0 / 11.0)).abs() < 1e-3);
+        let early_rate = 1.0 - kinetics.fraction_remaining(30.0);
+        let late_rate = one_year - kinetics.fraction_remaining(365.25 + 30.0);
+        assert!(early_rate > 50.0 * late_rate);
+        assert!((AntibodyKinetics::igm().fraction_remaining(5.0) - 0.5).abs() < 1e-12);
+        assert!(AntibodyKinetics::new(0.5, 30.0, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_time_to_seronegative_scales_with_peak() {
+        let modest = hepatitis_b(100.0);
+        let strong = hepatitis_b(1000.0);
+        let lost = modest.time_to_seronegative(AntibodyClass::IgG).unwrap();
+        // 10 of 100 mIU/mL is reached late in the plasmablast phase.
+        assert!(lost > 210.0 + 60.0 && lost < 210.0 + 365.0);
+        assert!((modest.titer(AntibodyClass::IgG, lost) - 10.0).abs() < 1e-6);
+        assert!(modest.is_seroprotected(AntibodyClass::IgG, lost - 1.0));
+        assert!(!modest.is_seroprotected(AntibodyClass::IgG, lost + 1.0));
+        // A 1000 mIU/mL peak leaves a 50 mIU/mL tail lasting decades.
+        let strong_lost = strong.time_to_seronegative(AntibodyClass::IgG).unwrap();
+        assert!(strong_lost - 210.0 > 20.0 * 365.25);
+        assert_eq!(strong.protective_classes(400.0), vec![AntibodyClass::IgG]);
+    }
+
+    #[test]
+    fn test_adjuvants_raise_and_prolong_vaccine_titres() {
+        let protected = |adjuvant: Option<&Adjuvant>| {
+            let response = ImmuneResponse::from_vaccine(20.0, adjuvant)
+                .unwrap()
+                .with_seroprotection_threshold(AntibodyClass::IgG, ANTI_HBS_SEROPROTECTION_MIU_ML)
+                .unwrap();
+            let igg = response.titers[&AntibodyClass::IgG];
+            (
+                igg,
+                response.time_to_seronegative(AntibodyClass::IgG).unwrap(),
+            )
+        };
+        let (plain, plain_lost) = protected(None);
+        assert!(plain.peak > 100.0 && plain.peak < 1000.0);
+        for adjuvant in [Adjuvant::alum(), Adjuvant::cpg_1018(), Adjuvant::qs21()] {
+            let (boosted, lost) = protected(Some(&adjuvant));
+            assert!(boosted.peak > plain.peak, "{}", adjuvant.name);
+            assert!(lost > plain_lost, "{}", adjuvant.name);
+        }
+        let (alum, _) = protected(Some(&Adjuvant::alum()));
+        assert!(alum.peak_day > plain.peak_day + 5.0);
+        assert!(ImmuneResponse::from_vaccine(0.0, None).is_err());
+    }
+
+    #[test]
+    fn test_degraded_lot_gives_weaker_response() {
+        use crate::systems::immune::cold_chain::VaccineStability;
+        let fresh = VaccineLot::new(VaccineStability::hepatitis_b());
+        let mut left_out = fresh.clone();
+        left_out.store(20.0 * 24.0, 37.0).unwrap();
+        let peak = |lot: &VaccineLot| {
+            ImmuneResponse::from_vaccine_lot(lot, 20.0, Some(&Adjuvant::alum()))
+                .unwrap()
+                .titers[&AntibodyClass::IgG]
+                .peak
+        };
+        let ratio = peak(&left_out) / peak(&fresh);
+        assert!((ratio - left_out.potency).abs() < 1e-9);
+        assert!(ratio < 0.7);
+    }
+
+    #[test]
+    fn test_no_threshold_or_weak_peak_has_no_crossing() {
+        let response = hepatitis_b(5.0);
+        assert!(response.time_to_seronegative(AntibodyClass::IgG).is_none());
+        assert!(response.time_to_seronegative(AntibodyClass::IgM).is_none());
+        assert!(!response.is_seroprotected(AntibodyClass::IgM, 14.0));
+        assert_eq!(response.titer(AntibodyClass::IgA, 100.0), 0.0);
+        assert_eq!(response.titer(AntibodyClass::IgG, 100.0), 0.0);
+    }
+}
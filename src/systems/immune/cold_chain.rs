@@ -0,0 +1,247 @@
+use crate::biology::physiology::arrhenius_factor;
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// WHO vaccine vial monitor categories: days at 37 °C to the discard
+// point. The paired 25 °C limits (e.g. VVM30: 30 d at 37 °C, 193 d at
+// 25 °C) fix an apparent activation energy of ~119 kJ/mol for all
+// categories.
+// WHO (2006) PQS performance specification E06/IN05.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VialMonitor {
+    Vvm2,
+    Vvm7,
+    Vvm14,
+    Vvm30,
+}
+
+impl VialMonitor {
+    pub fn days_to_endpoint_at_37c(&self) -> f64 {
+        match self {
+            VialMonitor::Vvm2 => 2.0,
+            VialMonitor::Vvm7 => 7.0,
+            VialMonitor::Vvm14 => 14.0,
+            VialMonitor::Vvm30 => 30.0,
+        }
+    }
+}
+
+const VVM_ACTIVATION_ENERGY_J_MOL: f64 = 119.3e3;
+// The discard point is modelled as half potency.
+pub const ENDPOINT_POTENCY: f64 = 0.5;
+// Recommended storage band.
+pub const COLD_CHAIN_MIN_C: f64 = 2.0;
+pub const COLD_CHAIN_MAX_C: f64 = 8.0;
+// Aluminium-adsorbed vaccines freeze from about -0.5 °C; the adjuvant
+// aggregates irreversibly and much of the potency is lost on thawing.
+// Kumru OS et al. (2014) Biologicals 42:237-259
+const FREEZING_POINT_C: f64 = -0.5;
+const POTENCY_RETAINED_AFTER_FREEZE: f64 = 0.3;
+
+// First-order potency loss with Arrhenius temperature dependence,
+// calibrated from the product's vial monitor category.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaccineStability {
+    pub name: String,
+    pub monitor: VialMonitor,
+    pub activation_energy_j_mol: f64,
+    pub freeze_sensitive: bool,
+}
+
+impl VaccineStability {
+    pub fn new(name: &str, monitor: VialMonitor, freeze_sensitive: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            monitor,
+            activation_energy_j_mol: VVM_ACTIVATION_ENERGY_J_MOL,
+            freeze_sensitive,
+        }
+    }
+
+    // Alum-adsorbed, heat-stable and freeze-damaged.
+    pub fn hepatitis_b() -> Self {
+        Self::new("hepatitis_b", VialMonitor::Vvm30, true)
+    }
+
+    // Lyophilised live vaccine.
+    pub fn measles() -> Self {
+        Self::new("measles", VialMonitor::Vvm14, false)
+    }
+
+    // The most heat-labile vaccine in routine use; stored frozen.
+    pub fn oral_polio() -> Self {
+        Self::new("oral_polio", VialMonitor::Vvm2, false)
+    }
+
+    pub fn loss_rate_per_day(&self, celsius: f64) -> f64 {
+        let at_37c = (1.0 / ENDPOINT_POTENCY).ln() / self.monitor.days_to_endpoint_at_37c();
+        at_37c * arrhenius_factor(self.activation_energy_j_mol, celsius, 37.0)
+    }
+
+    // Days from full potency to the discard point at a constant
+    // temperature.
+    pub fn days_to_endpoint(&self, celsius: f64) -> f64 {
+        (1.0 / ENDPOINT_POTENCY).ln() / self.loss_rate_per_day(celsius)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StorageInterval {
+    pub start_hours: f64,
+    pub duration_hours: f64,
+    pub celsius: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExcursionKind {
+    Cold,
+    Heat,
+}
+
+// A run of consecutive storage outside the 2-8 °C band on one side.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Excursion {
+    pub kind: ExcursionKind,
+    pub start_hours: f64,
+    pub duration_hours: f64,
+    // Coldest or hottest reading, by kind.
+    pub extreme_c: f64,
+    pub froze: bool,
+}
+
+// One lot's storage history and the potency it leaves.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaccineLot {
+    pub stability: VaccineStability,
+    pub potency: f64,
+    pub log: Vec<StorageInterval>,
+    pub frozen: bool,
+}
+
+impl VaccineLot {
+    pub fn new(stability: VaccineStability) -> Self {
+        Self {
+            stability,
+            potency: 1.0,
+            log: Vec::new(),
+            frozen: false,
+        }
+    }
+
+    pub fn elapsed_hours(&self) -> f64 {
+        self.log
+            .last()
+            .map_or(0.0, |i| i.start_hours + i.duration_hours)
+    }
+
+    pub fn store(&mut self, duration_hours: f64, celsius: f64) -> BiologyResult<()> {
+        if duration_hours.is_nan() || duration_hours < 0.0 || !celsius.is_finite() {
+            return Err(BiologyError::InvalidValue(
+                "storage needs a non-negative duration and a finite temperature".to_string(),
+            ));
+        }
+        let freezing = celsius <= FREEZING_POINT_C;
+        if freezing && self.stability.freeze_sensitive && !self.frozen {
+            self.potency *= POTENCY_RETAINED_AFTER_FREEZE;
+        }
+        self.frozen = freezing;
+        self.potency *= (-self.stability.loss_rate_per_day(celsius) * duration_hours / 24.0).exp();
+        self.log.push(StorageInterval {
+            start_hours: self.elapsed_hours(),
+            duration_hours,
+            celsius,
+        });
+        Ok(())
+    }
+
+    pub fn excursions(&self) -> Vec<Excursion> {
+        let mut excursions: Vec<Excursion> = Vec::new();
+        let mut previous: Option<ExcursionKind> = None;
+        for interval in &self.log {
+            let kind = if interval.celsius < COLD_CHAIN_MIN_C {
+                Some(ExcursionKind::Cold)
+            } else if interval.celsius > COLD_CHAIN_MAX_C {
+                Some(ExcursionKind::Heat)
+            } else {
+                None
+            };
+            let froze = interval.celsius <= FREEZING_POINT_C;
+            match (kind, excursions.last_mut()) {
+                (Some(kind), Some(current)) if previous == Some(kind) => {
+                    current.duration_hours += interval.duration_hours;
+                    current.froze |= froze;
+                    current.extreme_c = match kind {
+                        ExcursionKind::Cold => current.extreme_c.min(interval.celsius),
+                        ExcursionKind::Heat => current.extreme_c.max(interval.celsius),
+                    };
+                }
+                (Some(kind), _) => excursions.push(Excursion {
+                    kind,
+                    start_hours: interval.start_hours,
+                    duration_hours: interval.duration_hours,
+                    extreme_c: interval.celsius,
+                    froze,
+                }),
+                (None, _) => {}
+            }
+            previous = kind;
+        }
+        excursions
+    }
+
+    // Above the discard point, and never frozen if freezing damages it.
+    pub fn is_usable(&self) -> bool {
+        let froze = self.stability.freeze_sensitive && self.excursions().iter().any(|e| e.froze);
+        self.potency >= ENDPOINT_POTENCY && !froze
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vvm_calibration_reproduces_both_reference_temperatures() {
+        let hep_b = VaccineStability::hepatitis_b();
+        assert!((hep_b.days_to_endpoint(37.0) - 30.0).abs() < 1e-9);
+        assert!((hep_b.days_to_endpoint(25.0) - 193.0).abs() < 2.0);
+        // Refrigerated, the same lot lasts decades.
+        assert!(hep_b.days_to_endpoint(5.0) > 10.0 * 365.0);
+        let opv = VaccineStability::oral_polio();
+        assert!(opv.days_to_endpoint(25.0) < hep_b.days_to_endpoint(25.0) / 10.0);
+    }
+
+    #[test]
+    fn test_excursions_are_logged_and_merged() {
+        let mut lot = VaccineLot::new(VaccineStability::measles());
+        lot.store(72.0, 5.0).unwrap();
+        lot.store(4.0, 20.0).unwrap();
+        lot.store(2.0, 31.0).unwrap();
+        lot.store(24.0, 6.0).unwrap();
+        lot.store(12.0, -2.0).unwrap();
+        let excursions = lot.excursions();
+        assert_eq!(excursions.len(), 2);
+        assert_eq!(excursions[0].kind, ExcursionKind::Heat);
+        assert_eq!(excursions[0].start_hours, 72.0);
+        assert_eq!(excursions[0].duration_hours, 6.0);
+        assert_eq!(excursions[0].extreme_c, 31.0);
+        assert!(excursions[1].froze);
+        assert_eq!(lot.elapsed_hours(), 114.0);
+        // Measles tolerates freezing; six warm hours cost under 1%.
+        assert!(lot.potency > 0.99 && lot.is_usable());
+    }
+
+    #[test]
+    fn test_freezing_ruins_alum_vaccine() {
+        let mut lot = VaccineLot::new(VaccineStability::hepatitis_b());
+        lot.store(6.0, -3.0).unwrap();
+        lot.store(6.0, -3.0).unwrap();
+        assert!((lot.potency - POTENCY_RETAINED_AFTER_FREEZE).abs() < 1e-3);
+        assert!(!lot.is_usable());
+
+        let mut hot = VaccineLot::new(VaccineStability::oral_polio());
+        hot.store(3.0 * 24.0, 37.0).unwrap();
+        assert!(hot.potency < ENDPOINT_POTENCY && !hot.is_usable());
+        assert!(hot.store(-1.0, 5.0).is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::cardiovascular::blood_cells::{
+    Lymphocyte, LymphocyteActivation, LymphocyteLineage, MemoryStatus,
+};
+use serde::{Deserialize, Serialize};
+
+// Persistent antigen drives PD-1, TIM-3 and LAG-3 up on antigen-specific
+// T cells, and function is lost in a fixed order: IL-2 and proliferation
+// first, then TNF, with IFN-γ and killing holding out longest.
+// Wherry EJ (2011) Nat Immunol 12:492-499
+const RECEPTOR_INDUCTION_PER_DAY: f64 = 0.3;
+const RECEPTOR_ANTIGEN_HALF_SATURATION: f64 = 100.0;
+const RECEPTOR_TURNOVER_PER_DAY: f64 = 0.1;
+const IL2_HALF_SIGNAL: f64 = 1.0;
+const TNF_HALF_SIGNAL: f64 = 2.0;
+const IFNG_HALF_SIGNAL: f64 = 4.0;
+const CYTOTOXICITY_HALF_SIGNAL: f64 = 3.0;
+// Weeks of signalling lay down a TOX-driven epigenetic programme that
+// survives antigen clearance, so recovery after cure is only partial.
+// Abdel-Hakeem MS et al. (2021) Nat Immunol 22:1008-1019
+const FIXATION_PER_DAY: f64 = 0.01;
+const FIXATION_RECEPTOR_THRESHOLD: f64 = 1.0;
+const FIXED_SIGNAL: f64 = 2.0;
+// Past this inhibitory signal the cell is classed as exhausted rather
+// than a dampened effector.
+const EXHAUSTION_SIGNAL: f64 = 2.0;
+
+// Exhaustion state of an antigen-specific CD8 T-cell population.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TCellExhaustion {
+    pub days: f64,
+    // Inhibitory receptor expression relative to an acutely activated cell.
+    pub inhibitory_receptors: f64,
+    // Fraction of the population with the exhaustion programme fixed.
+    pub fixation: f64,
+    // Fraction of PD-1 signalling blocked by antibody.
+    pub checkpoint_blockade: f64,
+}
+
+impl Default for TCellExhaustion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TCellExhaustion {
+    pub fn new() -> Self {
+        Self {
+            days: 0.0,
+            inhibitory_receptors: 0.0,
+            fixation: 0.0,
+            checkpoint_blockade: 0.0,
+        }
+    }
+
+    // Anti-PD-1 reinvigorates the unfixed part of the response.
+    // Barber DL et al. (2006) Nature 439:682-687
+    pub fn set_checkpoint_blockade(&mut self, fraction: f64) -> BiologyResult<()> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(BiologyError::InvalidParameter(
+                "checkpoint blockade must lie in [0, 1]".to_string(),
+            ));
+        }
+        self.checkpoint_blockade = fraction;
+        Ok(())
+    }
+
+    // `antigen` is the load the cells see, relative to the initial
+    // infectious dose.
+    pub fn step(&mut self, antigen: f64, dt_days: f64) {
+        let antigen = antigen.max(0.0);
+        let induction =
+            RECEPTOR_INDUCTION_PER_DAY * antigen / (antigen + RECEPTOR_ANTIGEN_HALF_SATURATION);
+        let fixing = FIXATION_PER_DAY
+            * (self.inhibitory_receptors - FIXATION_RECEPTOR_THRESHOLD).max(0.0)
+            * (1.0 - self.fixation);
+        self.inhibitory_receptors +=
+            (induction - RECEPTOR_TURNOVER_PER_DAY * self.inhibitory_receptors) * dt_days;
+        self.fixation = (self.fixation + fixing * dt_days).min(1.0);
+        self.days += dt_days;
+    }
+
+    pub fn inhibitory_signal(&self) -> f64 {
+        self.inhibitory_receptors * (1.0 - self.checkpoint_blockade) + FIXED_SIGNAL * self.fixation
+    }
+
+    fn function(&self, half_signal: f64) -> f64 {
+        1.0 / (1.0 + (self.inhibitory_signal() / half_signal).powi(2))
+    }
+
+    pub fn il2_output(&self) -> f64 {
+        self.function(IL2_HALF_SIGNAL)
+    }
+
+    pub fn tnf_output(&self) -> f64 {
+        self.function(TNF_HALF_SIGNAL)
+    }
+
+    pub fn ifng_output(&self) -> f64 {
+        self.function(IFNG_HALF_SIGNAL)
+    }
+
+    pub fn cytotoxicity(&self) -> f64 {
+        self.function(CYTOTOXICITY_HALF_SIGNAL)
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.inhibitory_signal() >= EXHAUSTION_SIGNAL
+    }
+
+    // Writes the population state onto a representative cell.
+    pub fn apply_to(&self, cell: &mut Lymphocyte) {
+        if !matches!(cell.lineage, LymphocyteLineage::TCell(_)) {
+            return;
+        }
+        cell.activation_state = if self.is_exhausted() {
+            LymphocyteActivation::Exhausted
+        } else {
+            LymphocyteActivation::Effector
+        };
+        if cell.memory_status == MemoryStatus::Naive {
+            cell.memory_status = MemoryStatus::EffectorMemory;
+        }
+        let high = self.inhibitory_receptors > FIXATION_RECEPTOR_THRESHOLD || self.fixation > 0.5;
+        for marker in ["PD-1", "TIM-3", "LAG-3"] {
+            cell.surface_markers.insert(marker.to_string(), high);
+        }
+        for (cytokine, output) in [
+            ("IL-2", self.il2_output()),
+            ("TNF", self.tnf_output()),
+            ("IFN-gamma", self.ifng_output()),
+        ] {
+            cell.cytokine_production
+                .insert(cytokine.to_string(), output);
+        }
+    }
+}
+
+// Relative units: pathogen as multiples of the inoculum, T cells as
+// multiples of the naive precursor pool.
+const PATHOGEN_CARRYING_CAPACITY: f64 = 1e4;
+const KILLING_PER_T_CELL_PER_DAY: f64 = 0.1;
+// Each T cell kills at most so many targets a day, so high loads saturate
+// the response.
+const KILLING_HALF_SATURATION: f64 = 100.0;
+const EXPANSION_PER_DAY: f64 = 1.2;
+const T_CELL_DEATH_PER_DAY: f64 = 0.1;
+const CLEARED_LOAD: f64 = 1e-6;
+
+// Replicating pathogen controlled by one CD8 T-cell response that
+// exhausts if antigen persists.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChronicInfection {
+    pub pathogen_load: f64,
+    pub replication_per_day: f64,
+    pub t_cells: f64,
+    pub exhaustion: TCellExhaustion,
+}
+
+impl ChronicInfection {
+    pub fn new(inoculum: f64, replication_per_day: f64) -> BiologyResult<Self> {
+        if inoculum.is_nan()
+            || inoculum <= 0.0
+            || replication_per_day.is_nan()
+            || replication_per_day <= 0.0
+        {
+            return Err(BiologyError::InvalidParameter(
+                "inoculum and replication rate must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            pathogen_load: inoculum,
+            replication_per_day,
+            t_cells: 1.0,
+            exhaustion: TCellExhaustion::new(),
+        })
+    }
+
+    pub fn is_cleared(&self) -> bool {
+        self.pathogen_load == 0.0
+    }
+
+    pub fn step(&mut self, dt_days: f64) {
+        let p = self.pathogen_load;
+        let growth = self.replication_per_day * p * (1.0 - p / PATHOGEN_CARRYING_CAPACITY);
+        let killing =
+            KILLING_PER_T_CELL_PER_DAY * self.exhaustion.cytotoxicity() * self.t_cells * p
+                / (1.0 + p / KILLING_HALF_SATURATION);
+        // Proliferation needs antigen and autocrine IL-2.
+        let expansion = EXPANSION_PER_DAY * p / (p + 1.0) * self.exhaustion.il2_output();
+        self.exhaustion.step(p, dt_days);
+        self.pathogen_load = (p + (growth - killing) * dt_days).max(0.0);
+        if self.pathogen_load < CLEARED_LOAD {
+            self.pathogen_load = 0.0;
+        }
+        self.t_cells += (expansion - T_CELL_DEATH_PER_DAY) * self.t_cells * dt_days;
+    }
+
+    pub fn run_days(&mut self, days: usize) {
+        for _ in 0..days * 100 {
+            self.step(0.01);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acute_infection_clears_without_exhaustion() {
+        let mut acute = ChronicInfection::new(1.0, 2.0).unwrap();
+        acute.run_days(10);
+        assert!(acute.is_cleared());
+        acute.run_days(30);
+        assert!(!acute.exhaustion.is_exhausted());
+        assert!(acute.exhaustion.il2_output() > 0.95);
+    }
+
+    #[test]
+    fn test_persistent_antigen_exhausts_in_functional_order() {
+        let mut chronic = ChronicInfection::new(100.0, 2.0).unwrap();
+        chronic.run_days(40);
+        assert!(!chronic.is_cleared() && chronic.pathogen_load > 1000.0);
+        let t = &chronic.exhaustion;
+        assert!(t.is_exhausted());
+        assert!(t.il2_output() < t.tnf_output());
+        assert!(t.tnf_output() < t.cytotoxicity());
+        assert!(t.cytotoxicity() < t.ifng_output());
+
+        let mut cell = Lymphocyte::new_cytotoxic_t();
+        t.apply_to(&mut cell);
+        assert_eq!(cell.activation_state, LymphocyteActivation::Exhausted);
+        assert!(cell.surface_markers["PD-1"]);
+        assert!(cell.cytokine_production["IL-2"] < 0.1);
+        let mut b_cell = Lymphocyte::new_b_cell();
+        t.apply_to(&mut b_cell);
+        assert_eq!(b_cell.activation_state, LymphocyteActivation::Naive);
+    }
+
+    #[test]
+    fn test_checkpoint_blockade_reverses_early_but_not_fixed_exhaustion() {
+        let treat_on_day = |day: usize| {
+            let mut infection = ChronicInfection::new(100.0, 2.0).unwrap();
+            infection.run_days(day);
+            infection.exhaustion.set_checkpoint_blockade(0.9).unwrap();
+            infection.run_days(30);
+            infection
+        };
+        let early = treat_on_day(20);
+        assert!(early.is_cleared());
+        // After clearance receptors fall away but the fixed programme
+        // keeps IL-2 below a never-exhausted cell.
+        let mut recovered = early.exhaustion.clone();
+        recovered.set_checkpoint_blockade(0.0).unwrap();
+        for _ in 0..600 {
+            recovered.step(0.0, 0.1);
+        }
+        assert!(recovered.fixation > 0.1 && !recovered.is_exhausted());
+        assert!(recovered.il2_output() < 0.95);
+
+        let untreated = {
+            let mut infection = ChronicInfection::new(100.0, 2.0).unwrap();
+            infection.run_days(150);
+            infection
+        };
+        let late = treat_on_day(120);
+        assert!(late.exhaustion.fixation > early.exhaustion.fixation);
+        assert!(late.pathogen_load < untreated.pathogen_load);
+        assert!(TCellExhaustion::new().set_checkpoint_blockade(1.5).is_err());
+    }
+}
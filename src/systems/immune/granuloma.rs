@@ -0,0 +1,418 @@
+use crate::biology::{BiologyError, BiologyResult};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// Lattice rules follow the agent-based granuloma model of
+// Segovia-Juarez JL, Ganguli S, Kirschner D (2004) J Theor Biol 231:357-376,
+// PMID 15501468: 20 µm micro-compartments, one macrophage per site,
+// chemokine-biased movement and recruitment through the vasculature.
+// Ramakrishnan L (2012) Nat Rev Immunol 12:352-366, PMID 22517424
+pub const SITE_SIZE_UM: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DepotKind {
+    // Protein antigen, cleared by macrophages within days.
+    SolubleAntigen,
+    // Aluminium salts persist at the injection site for months.
+    // Hem SL (2002) Vaccine 20 Suppl 3:S40-S43, PMID 12184363
+    MineralAdjuvant,
+    // Mycobacterial cell wall resists phagolysosomal degradation.
+    Mycobacterial,
+}
+
+impl DepotKind {
+    // Relative degradation per engaged macrophage.
+    pub fn degradability(&self) -> f64 {
+        match self {
+            DepotKind::SolubleAntigen => 1.0,
+            DepotKind::MineralAdjuvant => 0.005,
+            DepotKind::Mycobacterial => 0.05,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AntigenDepot {
+    pub kind: DepotKind,
+    pub radius_sites: usize,
+    pub load_per_site: f64,
+}
+
+impl AntigenDepot {
+    pub fn new(kind: DepotKind, radius_sites: usize, load_per_site: f64) -> BiologyResult<Self> {
+        if load_per_site <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "depot load must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            kind,
+            radius_sites,
+            load_per_site,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GranulomaParameters {
+    pub degradation_per_macrophage_per_hour: f64,
+    pub chemokine_diffusion: f64,
+    pub chemokine_decay_per_hour: f64,
+    pub antigen_chemokine_secretion: f64,
+    pub recruitment_attempts_per_hour: usize,
+    pub recruitment_threshold: f64,
+    pub chemotaxis_probability: f64,
+    pub macrophage_lifespan_hours: f64,
+    // Macrophages engaged with undegraded material beyond this time turn
+    // profibrotic and lay down collagen around themselves.
+    pub chronic_activation_hours: f64,
+    pub collagen_per_hour: f64,
+}
+
+impl GranulomaParameters {
+    pub fn segovia_juarez_2004() -> Self {
+        Self {
+            degradation_per_macrophage_per_hour: 0.05,
+            chemokine_diffusion: 0.2,
+            chemokine_decay_per_hour: 0.05,
+            antigen_chemokine_secretion: 1.0,
+            recruitment_attempts_per_hour: 4,
+            recruitment_threshold: 0.05,
+            chemotaxis_probability: 0.8,
+            macrophage_lifespan_hours: 100.0 * 24.0,
+            chronic_activation_hours: 14.0 * 24.0,
+            collagen_per_hour: 0.01,
+        }
+    }
+}
+
+impl Default for GranulomaParameters {
+    fn default() -> Self {
+        Self::segovia_juarez_2004()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatticeMacrophage {
+    pub x: usize,
+    pub y: usize,
+    pub age_hours: f64,
+    pub engaged_hours: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GranulomaOutcome {
+    Active,
+    Resolved,
+    Fibrotic,
+}
+
+const COLLAGEN_SITE_THRESHOLD: f64 = 1.0;
+const FIBROTIC_SITE_COUNT: usize = 8;
+const RESOLVED_ANTIGEN_FRACTION: f64 = 0.01;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GranulomaModel {
+    pub params: GranulomaParameters,
+    pub width: usize,
+    pub height: usize,
+    pub depot: AntigenDepot,
+    pub antigen: Vec<f64>,
+    pub chemokine: Vec<f64>,
+    pub collagen: Vec<f64>,
+    pub macrophages: Vec<LatticeMacrophage>,
+    pub elapsed_hours: f64,
+    occupied: Vec<bool>,
+    initial_antigen: f64,
+}
+
+impl GranulomaModel {
+    pub fn new<R: Rng>(
+        params: GranulomaParameters,
+        size: usize,
+        depot: AntigenDepot,
+        resident_density: f64,
+        rng: &mut R,
+    ) -> BiologyResult<Self> {
+        if size < 2 * depot.radius_sites + 3 {
+            return Err(BiologyError::InvalidParameter(
+                "lattice too small for depot".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&resident_density) {
+            return Err(BiologyError::InvalidParameter(
+                "resident macrophage density must lie in [0, 1]".to_string(),
+            ));
+        }
+        let n = size * size;
+        let center = (size / 2) as isize;
+        let radius = depot.radius_sites as isize;
+        let mut antigen = vec![0.0; n];
+        for y in 0..size {
+            for x in 0..size {
+                let (dx, dy) = (x as isize - center, y as isize - center);
+                if dx * dx + dy * dy <= radius * radius {
+                    antigen[y * size + x] = depot.load_per_site;
+                }
+            }
+        }
+        let initial_antigen = antigen.iter().sum();
+        let mut model = Self {
+            params,
+            width: size,
+            height: size,
+            depot,
+            antigen,
+            chemokine: vec![0.0; n],
+            collagen: vec![0.0; n],
+            macrophages: Vec::new(),
+            elapsed_hours: 0.0,
+            occupied: vec![false; n],
+            initial_antigen,
+        };
+        for i in 0..n {
+            if model.antigen[i] == 0.0 && rng.gen::<f64>() < resident_density {
+                model.place(i % size, i / size);
+            }
+        }
+        Ok(model)
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    fn place(&mut self, x: usize, y: usize) {
+        let i = self.index(x, y);
+        self.occupied[i] = true;
+        self.macrophages.push(LatticeMacrophage {
+            x,
+            y,
+            age_hours: 0.0,
+            engaged_hours: 0.0,
+        });
+    }
+
+    fn neighbors(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+        let (w, h) = (self.width as isize, self.height as isize);
+        let (x, y) = (x as isize, y as isize);
+        (-1..=1)
+            .flat_map(move |dy| (-1..=1).map(move |dx| (dx, dy)))
+            .filter(|&(dx, dy)| dx != 0 || dy != 0)
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(move |&(nx, ny)| nx >= 0 && ny >= 0 && nx < w && ny < h)
+            .map(|(nx, ny)| (nx as usize, ny as usize))
+    }
+
+    fn diffuse_chemokine(&mut self) {
+        let p = self.params;
+        let mut next = self.chemokine.clone();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = self.index(x, y);
+                let mut laplacian = 0.0;
+                for (nx, ny) in [
+                    (x.wrapping_sub(1), y),
+                    (x + 1, y),
+                    (x, y.wrapping_sub(1)),
+                    (x, y + 1),
+                ] {
+                    // Zero-flux boundary.
+                    let neighbor = if nx < self.width && ny < self.height {
+                        self.chemokine[self.index(nx, ny)]
+                    } else {
+                        self.chemokine[i]
+                    };
+                    laplacian += neighbor - self.chemokine[i];
+                }
+                let source = if self.antigen[i] > 0.0 {
+                    p.antigen_chemokine_secretion
+                } else {
+                    0.0
+                };
+                next[i] = (self.chemokine[i] + p.chemokine_diffusion * laplacian + source
+                    - p.chemokine_decay_per_hour * self.chemokine[i])
+                    .max(0.0);
+            }
+        }
+        self.chemokine = next;
+    }
+
+    fn antigen_nearby(&self, x: usize, y: usize) -> Option<usize> {
+        let here = self.index(x, y);
+        if self.antigen[here] > 0.0 {
+            return Some(here);
+        }
+        self.neighbors(x, y)
+            .map(|(nx, ny)| self.index(nx, ny))
+            .find(|&i| self.antigen[i] > 0.0)
+    }
+
+    // One-hour update: chemokine transport, macrophage engagement, movement,
+    // death and recruitment.
+    pub fn step_hour<R: Rng>(&mut self, rng: &mut R) {
+        let p = self.params;
+        self.diffuse_chemokine();
+
+        let degradation = p.degradation_per_macrophage_per_hour * self.depot.kind.degradability();
+        let mut survivors = Vec::with_capacity(self.macrophages.len());
+        let mut cells = std::mem::take(&mut self.macrophages);
+        for cell in cells.iter_mut() {
+            cell.age_hours += 1.0;
+            let here = self.index(cell.x, cell.y);
+            if rng.gen::<f64>() < 1.0 / p.macrophage_lifespan_hours {
+                self.occupied[here] = false;
+                continue;
+            }
+
+            if let Some(site) = self.antigen_nearby(cell.x, cell.y) {
+                self.antigen[site] = (self.antigen[site] - degradation).max(0.0);
+                cell.engaged_hours += 1.0;
+                if cell.engaged_hours > p.chronic_activation_hours {
+                    self.collagen[here] += p.collagen_per_hour;
+                    for (nx, ny) in self.neighbors(cell.x, cell.y).collect::<Vec<_>>() {
+                        let i = self.index(nx, ny);
+                        if self.antigen[i] == 0.0 {
+                            self.collagen[i] += p.collagen_per_hour;
+                        }
+                    }
+                }
+            } else {
+                let free: Vec<(usize, usize)> = self
+                    .neighbors(cell.x, cell.y)
+                    .filter(|&(nx, ny)| !self.occupied[self.index(nx, ny)])
+                    .collect();
+                if !free.is_empty() {
+                    let target = if rng.gen::<f64>() < p.chemotaxis_probability {
+                        *free
+                            .iter()
+                            .max_by(|a, b| {
+                                let ca = self.chemokine[self.index(a.0, a.1)];
+                                let cb = self.chemokine[self.index(b.0, b.1)];
+                                ca.total_cmp(&cb)
+                            })
+                            .expect("non-empty")
+                    } else {
+                        free[rng.gen_range(0..free.len())]
+                    };
+                    self.occupied[here] = false;
+                    cell.x = target.0;
+                    cell.y = target.1;
+                    let i = self.index(cell.x, cell.y);
+                    self.occupied[i] = true;
+                }
+            }
+            survivors.push(*cell);
+        }
+        self.macrophages = survivors;
+
+        for _ in 0..p.recruitment_attempts_per_hour {
+            let x = rng.gen_range(0..self.width);
+            let y = rng.gen_range(0..self.height);
+            let i = self.index(x, y);
+            if !self.occupied[i] && self.chemokine[i] > p.recruitment_threshold {
+                self.place(x, y);
+            }
+        }
+        self.elapsed_hours += 1.0;
+    }
+
+    pub fn run_days<R: Rng>(&mut self, days: usize, rng: &mut R) {
+        for _ in 0..days * 24 {
+            self.step_hour(rng);
+        }
+    }
+
+    pub fn remaining_antigen_fraction(&self) -> f64 {
+        self.antigen.iter().sum::<f64>() / self.initial_antigen.max(1e-12)
+    }
+
+    pub fn fibrotic_site_count(&self) -> usize {
+        self.collagen
+            .iter()
+            .filter(|c| **c >= COLLAGEN_SITE_THRESHOLD)
+            .count()
+    }
+
+    // Macrophages per site within `radius` sites of the depot centre.
+    pub fn macrophage_density_within(&self, radius: usize) -> f64 {
+        let c = (self.width / 2) as isize;
+        let r = radius as isize;
+        let in_disk = |x: usize, y: usize| {
+            let (dx, dy) = (x as isize - c, y as isize - c);
+            dx * dx + dy * dy <= r * r
+        };
+        let sites = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| in_disk(x, y))
+            .count();
+        let cells = self
+            .macrophages
+            .iter()
+            .filter(|m| in_disk(m.x, m.y))
+            .count();
+        cells as f64 / sites.max(1) as f64
+    }
+
+    pub fn mean_macrophage_density(&self) -> f64 {
+        self.macrophages.len() as f64 / (self.width * self.height) as f64
+    }
+
+    pub fn outcome(&self) -> GranulomaOutcome {
+        if self.fibrotic_site_count() >= FIBROTIC_SITE_COUNT {
+            GranulomaOutcome::Fibrotic
+        } else if self.remaining_antigen_fraction() < RESOLVED_ANTIGEN_FRACTION {
+            GranulomaOutcome::Resolved
+        } else {
+            GranulomaOutcome::Active
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn model(kind: DepotKind, rng: &mut StdRng) -> GranulomaModel {
+        let depot = AntigenDepot::new(kind, 3, 1.0).unwrap();
+        GranulomaModel::new(GranulomaParameters::default(), 41, depot, 0.02, rng).unwrap()
+    }
+
+    #[test]
+    fn test_soluble_antigen_resolves_without_fibrosis() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut granuloma = model(DepotKind::SolubleAntigen, &mut rng);
+        granuloma.run_days(14, &mut rng);
+        assert_eq!(granuloma.outcome(), GranulomaOutcome::Resolved);
+        assert_eq!(granuloma.fibrotic_site_count(), 0);
+    }
+
+    #[test]
+    fn test_macrophages_aggregate_around_depot() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut granuloma = model(DepotKind::Mycobacterial, &mut rng);
+        granuloma.run_days(7, &mut rng);
+        assert!(granuloma.macrophage_density_within(6) > 5.0 * granuloma.mean_macrophage_density());
+    }
+
+    #[test]
+    fn test_persistent_depot_becomes_fibrotic() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut granuloma = model(DepotKind::MineralAdjuvant, &mut rng);
+        granuloma.run_days(42, &mut rng);
+        assert!(granuloma.remaining_antigen_fraction() > 0.3);
+        assert_eq!(granuloma.outcome(), GranulomaOutcome::Fibrotic);
+    }
+
+    #[test]
+    fn test_invalid_configuration_rejected() {
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(AntigenDepot::new(DepotKind::SolubleAntigen, 2, 0.0).is_err());
+        let depot = AntigenDepot::new(DepotKind::SolubleAntigen, 10, 1.0).unwrap();
+        assert!(
+            GranulomaModel::new(GranulomaParameters::default(), 15, depot, 0.02, &mut rng).is_err()
+        );
+    }
+}
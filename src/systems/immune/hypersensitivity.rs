@@ -0,0 +1,356 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Vaccine components with documented hypersensitivity: the first four
+// provoke IgE-mediated reactions, neomycin and thiomersal mostly delayed
+// contact allergy.
+// McNeil MM, DeStefano F (2018) J Allergy Clin Immunol 141:463-472
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Excipient {
+    Gelatin,
+    Ovalbumin,
+    PolyethyleneGlycol,
+    Polysorbate80,
+    Neomycin,
+    Thiomersal,
+}
+
+// Anaphylaxis after any vaccine, 1.31 per million doses.
+// McNeil MM et al. (2016) J Allergy Clin Immunol 137:868-878
+pub const ANAPHYLAXIS_PER_MILLION_DOSES: f64 = 1.31;
+// ImmunoCAP positivity cut-off and the lower bounds of classes 1-6.
+const SIGE_CLASS_BOUNDS_KU_L: [f64; 6] = [0.35, 0.7, 3.5, 17.5, 50.0, 100.0];
+// Heuristic: risk grows tenfold per IgE class of a sensitising component.
+// No cohort reports excipient-specific risk by class; treat as a ranking.
+const RELATIVE_RISK_PER_SIGE_CLASS: f64 = 10.0;
+
+// Allergen-specific IgE in kU/L.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sensitization {
+    pub specific_ige_ku_l: BTreeMap<Excipient, f64>,
+}
+
+impl Sensitization {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_specific_ige(mut self, allergen: Excipient, ku_l: f64) -> BiologyResult<Self> {
+        if ku_l.is_nan() || ku_l < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "specific IgE must be non-negative".to_string(),
+            ));
+        }
+        self.specific_ige_ku_l.insert(allergen, ku_l);
+        Ok(self)
+    }
+
+    // Each exposure under Th2 conditions adds IgE that saturates towards a
+    // ceiling, so repeated exposures sensitise progressively.
+    pub fn expose(&mut self, allergen: Excipient, th2_bias: f64) {
+        const CEILING_KU_L: f64 = 100.0;
+        let ige = self.specific_ige_ku_l.entry(allergen).or_insert(0.0);
+        *ige += th2_bias.clamp(0.0, 1.0) * 0.2 * (CEILING_KU_L - *ige).max(0.0);
+    }
+
+    pub fn specific_ige(&self, allergen: Excipient) -> f64 {
+        self.specific_ige_ku_l
+            .get(&allergen)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    // ImmunoCAP class 0-6.
+    pub fn ige_class(&self, allergen: Excipient) -> usize {
+        let ige = self.specific_ige(allergen);
+        SIGE_CLASS_BOUNDS_KU_L
+            .iter()
+            .filter(|&&bound| ige >= bound)
+            .count()
+    }
+
+    pub fn is_sensitized(&self, allergen: Excipient) -> bool {
+        self.ige_class(allergen) > 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnaphylaxisRisk {
+    pub relative_risk: f64,
+    pub per_million_doses: f64,
+    // Excipients the recipient is sensitised to, with their IgE class.
+    pub drivers: Vec<(Excipient, usize)>,
+}
+
+pub fn anaphylaxis_risk(
+    excipients: &[Excipient],
+    sensitization: &Sensitization,
+) -> AnaphylaxisRisk {
+    let mut relative_risk = 1.0;
+    let mut drivers = Vec::new();
+    for &excipient in excipients {
+        let class = sensitization.ige_class(excipient);
+        if class > 0 {
+            relative_risk *= RELATIVE_RISK_PER_SIGE_CLASS.powi(class as i32);
+            drivers.push((excipient, class));
+        }
+    }
+    AnaphylaxisRisk {
+        relative_risk,
+        per_million_doses: (ANAPHYLAXIS_PER_MILLION_DOSES * relative_risk).min(1e6),
+        drivers,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ReactionGrade {
+    None,
+    Cutaneous,
+    Systemic,
+    Anaphylaxis,
+}
+
+// Mast-cell mediators in plasma after an allergen challenge. Histamine
+// peaks within ~5-10 min and clears within the hour; tryptase peaks at
+// 1-1.5 h and falls with a ~2 h half-life, which is why it is the
+// confirmatory test drawn after the event.
+// Schwartz LB et al. (1989) J Clin Invest 83:1551-1555
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MastCellResponse {
+    pub minutes: f64,
+    // Fraction of stored mediator not yet released.
+    pub granule_stores: f64,
+    pub degranulation_rate_per_min: f64,
+    pub histamine_ng_ml: f64,
+    pub tryptase_ng_ml: f64,
+    pub peak_histamine_ng_ml: f64,
+}
+
+const BASELINE_HISTAMINE_NG_ML: f64 = 0.5;
+const BASELINE_TRYPTASE_NG_ML: f64 = 5.0;
+// Plasma histamine if every mast cell emptied at once.
+const HISTAMINE_CAPACITY_NG_ML: f64 = 60.0;
+const TRYPTASE_CAPACITY_NG_ML: f64 = 100.0;
+const HISTAMINE_CLEARANCE_PER_MIN: f64 = 0.15;
+const TRYPTASE_ENTRY_PER_MIN: f64 = 0.03;
+const TRYPTASE_CLEARANCE_PER_MIN: f64 = std::f64::consts::LN_2 / 120.0;
+// Cross-linking of FcεRI-bound IgE: allergen must bridge two receptors,
+// so release rises steeply with both IgE and allergen dose.
+const CROSSLINK_HALF_SATURATION: f64 = 1.0;
+const MAX_DEGRANULATION_PER_MIN: f64 = 0.5;
+
+impl MastCellResponse {
+    // Challenge with `dose` (relative units) of an allergen against which
+    // the host carries `specific_ige_ku_l`.
+    pub fn challenge(specific_ige_ku_l: f64, dose: f64) -> Self {
+        let crosslinking = (specific_ige_ku_l / 0.35).max(0.0).ln_1p() * dose.max(0.0);
+        let activation =
+            crosslinking.powi(2) / (crosslinking.powi(2) + CROSSLINK_HALF_SATURATION.powi(2));
+        Self {
+            minutes: 0.0,
+            granule_stores: 1.0,
+            degranulation_rate_per_min: MAX_DEGRANULATION_PER_MIN * activation,
+            histamine_ng_ml: BASELINE_HISTAMINE_NG_ML,
+            tryptase_ng_ml: BASELINE_TRYPTASE_NG_ML,
+            peak_histamine_ng_ml: BASELINE_HISTAMINE_NG_ML,
+        }
+    }
+
+    pub fn step(&mut self, dt_minutes: f64) {
+        // Signalling through Syk fades over minutes after challenge.
+        let rate = self.degranulation_rate_per_min * (-self.minutes / 5.0).exp();
+        let released = self.granule_stores * rate * dt_minutes;
+        self.granule_stores -= released;
+        self.histamine_ng_ml += HISTAMINE_CAPACITY_NG_ML * released
+            - HISTAMINE_CLEARANCE_PER_MIN
+                * (self.histamine_ng_ml - BASELINE_HISTAMINE_NG_ML)
+                * dt_minutes;
+        let released_total = 1.0 - self.granule_stores;
+        let tryptase_target = BASELINE_TRYPTASE_NG_ML + TRYPTASE_CAPACITY_NG_ML * released_total;
+        self.tryptase_ng_ml += (TRYPTASE_ENTRY_PER_MIN
+            * (tryptase_target - self.tryptase_ng_ml)
+            * (-self.minutes / 60.0).exp()
+            - TRYPTASE_CLEARANCE_PER_MIN * (self.tryptase_ng_ml - BASELINE_TRYPTASE_NG_ML))
+            * dt_minutes;
+        self.peak_histamine_ng_ml = self.peak_histamine_ng_ml.max(self.histamine_ng_ml);
+        self.minutes += dt_minutes;
+    }
+
+    pub fn run_minutes(&mut self, minutes: usize) {
+        for _ in 0..minutes * 10 {
+            self.step(0.1);
+        }
+    }
+
+    // Graded on peak plasma histamine, which tracks reaction severity;
+    // the cut-offs are this model's, not diagnostic criteria.
+    // Lin RY et al. (2000) J Allergy Clin Immunol 106:65-71
+    pub fn grade(&self) -> ReactionGrade {
+        match self.peak_histamine_ng_ml {
+            h if h >= 10.0 => ReactionGrade::Anaphylaxis,
+            h if h >= 3.0 => ReactionGrade::Systemic,
+            h if h >= 1.0 => ReactionGrade::Cutaneous,
+            _ => ReactionGrade::None,
+        }
+    }
+}
+
+// Delayed-type (type IV) hypersensitivity: memory T cells recognising the
+// antigen are recruited to the site and drive induration that peaks 48-72 h
+// after challenge, as in the tuberculin skin test.
+// Black CA (1999) Dermatol Online J 5(1):7
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DelayedTypeReaction {
+    pub hours: f64,
+    // Antigen-specific memory T cells relative to a typical sensitised host.
+    pub memory_t_cell_frequency: f64,
+    pub antigen: f64,
+    pub cytokine: f64,
+    pub effector_t_cells: f64,
+    pub induration_mm: f64,
+    pub peak_induration_mm: f64,
+}
+
+const DTH_ANTIGEN_CLEARANCE_PER_H: f64 = 0.03;
+const DTH_CYTOKINE_PER_H: f64 = 0.08;
+const DTH_CYTOKINE_DECAY_PER_H: f64 = 0.05;
+const DTH_RECRUITMENT_PER_H: f64 = 0.06;
+const DTH_EFFECTOR_LOSS_PER_H: f64 = 0.03;
+const DTH_MM_PER_EFFECTOR: f64 = 15.0;
+// Tuberculin reading cut-off for most adults.
+pub const TST_POSITIVE_MM: f64 = 10.0;
+
+impl DelayedTypeReaction {
+    pub fn challenge(memory_t_cell_frequency: f64, dose: f64) -> BiologyResult<Self> {
+        if memory_t_cell_frequency.is_nan()
+            || memory_t_cell_frequency < 0.0
+            || dose.is_nan()
+            || dose < 0.0
+        {
+            return Err(BiologyError::InvalidValue(
+                "T-cell frequency and dose must be non-negative".to_string(),
+            ));
+        }
+        Ok(Self {
+            hours: 0.0,
+            memory_t_cell_frequency,
+            antigen: dose,
+            cytokine: 0.0,
+            effector_t_cells: 0.0,
+            induration_mm: 0.0,
+            peak_induration_mm: 0.0,
+        })
+    }
+
+    pub fn step(&mut self, dt_hours: f64) {
+        // Resident memory cells meeting antigen release IFN-γ, which draws
+        // in circulating effectors and macrophages.
+        let recognition = self.memory_t_cell_frequency * self.antigen;
+        let recruitment = DTH_RECRUITMENT_PER_H * self.cytokine;
+        self.cytokine += (DTH_CYTOKINE_PER_H * recognition
+            - DTH_CYTOKINE_DECAY_PER_H * self.cytokine)
+            * dt_hours;
+        self.effector_t_cells +=
+            (recruitment - DTH_EFFECTOR_LOSS_PER_H * self.effector_t_cells) * dt_hours;
+        self.antigen -= DTH_ANTIGEN_CLEARANCE_PER_H * self.antigen * dt_hours;
+        self.induration_mm = DTH_MM_PER_EFFECTOR * self.effector_t_cells;
+        self.peak_induration_mm = self.peak_induration_mm.max(self.induration_mm);
+        self.hours += dt_hours;
+    }
+
+    pub fn run_hours(&mut self, hours: usize) {
+        for _ in 0..hours * 4 {
+            self.step(0.25);
+        }
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.peak_induration_mm >= TST_POSITIVE_MM
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_exposure_sensitizes() {
+        let mut sensitization = Sensitization::new();
+        assert!(!sensitization.is_sensitized(Excipient::Gelatin));
+        sensitization.expose(Excipient::Gelatin, 0.02);
+        assert_eq!(sensitization.ige_class(Excipient::Gelatin), 1);
+        for _ in 0..5 {
+            sensitization.expose(Excipient::Gelatin, 1.0);
+        }
+        assert!(sensitization.ige_class(Excipient::Gelatin) >= 5);
+        assert!(Sensitization::new()
+            .with_specific_ige(Excipient::Ovalbumin, -1.0)
+            .is_err());
+    }
+
+    #[test]
+    fn test_risk_only_rises_for_contained_sensitizing_excipients() {
+        let sensitization = Sensitization::new()
+            .with_specific_ige(Excipient::Gelatin, 5.0)
+            .unwrap()
+            .with_specific_ige(Excipient::PolyethyleneGlycol, 0.1)
+            .unwrap();
+        let mmr = anaphylaxis_risk(&[Excipient::Gelatin, Excipient::Neomycin], &sensitization);
+        assert_eq!(mmr.drivers, vec![(Excipient::Gelatin, 3)]);
+        assert!((mmr.per_million_doses - 1310.0).abs() < 1e-9);
+        let mrna = anaphylaxis_risk(&[Excipient::PolyethyleneGlycol], &sensitization);
+        assert!(mrna.drivers.is_empty());
+        assert_eq!(mrna.per_million_doses, ANAPHYLAXIS_PER_MILLION_DOSES);
+    }
+
+    #[test]
+    fn test_mast_cell_mediator_time_course() {
+        let mut severe = MastCellResponse::challenge(50.0, 1.0);
+        let mut histamine = Vec::new();
+        let mut tryptase = Vec::new();
+        for _ in 0..180 {
+            severe.run_minutes(1);
+            histamine.push(severe.histamine_ng_ml);
+            tryptase.push(severe.tryptase_ng_ml);
+        }
+        let peak_minute = |series: &[f64]| {
+            (0..series.len())
+                .max_by(|&a, &b| series[a].total_cmp(&series[b]))
+                .unwrap()
+                + 1
+        };
+        assert!((3..=10).contains(&peak_minute(&histamine)));
+        assert!((45..=120).contains(&peak_minute(&tryptase)));
+        assert!(histamine[59] < 0.2 * severe.peak_histamine_ng_ml);
+        assert_eq!(severe.grade(), ReactionGrade::Anaphylaxis);
+
+        let mut unsensitized = MastCellResponse::challenge(0.0, 1.0);
+        unsensitized.run_minutes(60);
+        assert_eq!(unsensitized.grade(), ReactionGrade::None);
+        let mut mild = MastCellResponse::challenge(0.7, 0.3);
+        mild.run_minutes(60);
+        assert!(mild.grade() < ReactionGrade::Anaphylaxis);
+    }
+
+    #[test]
+    fn test_delayed_reaction_peaks_at_two_to_three_days() {
+        let mut sensitized = DelayedTypeReaction::challenge(1.0, 1.0).unwrap();
+        let mut induration = Vec::new();
+        for _ in 0..120 {
+            sensitized.run_hours(1);
+            induration.push(sensitized.induration_mm);
+        }
+        let peak_hour = (0..induration.len())
+            .max_by(|&a, &b| induration[a].total_cmp(&induration[b]))
+            .unwrap()
+            + 1;
+        assert!((36..=80).contains(&peak_hour), "peak at {} h", peak_hour);
+        assert!(induration[5] < 0.1 * sensitized.peak_induration_mm);
+        assert!(sensitized.is_positive());
+
+        let mut naive = DelayedTypeReaction::challenge(0.05, 1.0).unwrap();
+        naive.run_hours(120);
+        assert!(!naive.is_positive());
+    }
+}
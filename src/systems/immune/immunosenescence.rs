@@ -0,0 +1,122 @@
+use crate::aging::senescence::{SenescenceParameters, SenescentCellBurden};
+use serde::{Deserialize, Serialize};
+
+// Thymic epithelial space shrinks ~3%/year until middle age and ~1%/year
+// after, and naive T-cell output follows.
+// Steinmann GG, Klaus B, Müller-Hermelink HK (1985) Scand J Immunol 22:563-575
+const THYMIC_INVOLUTION_ONSET_YEARS: f64 = 20.0;
+const MIDDLE_AGE_YEARS: f64 = 45.0;
+const EARLY_INVOLUTION_PER_YEAR: f64 = 0.03;
+const LATE_INVOLUTION_PER_YEAR: f64 = 0.01;
+// Naive CD4 TCR diversity holds near 2e7 clonotypes into the sixties and
+// then collapses ~100-fold within about a decade.
+// Naylor K et al. (2005) J Immunol 174:7446-7452
+const REPERTOIRE_COLLAPSE_AGE_YEARS: f64 = 72.0;
+const REPERTOIRE_COLLAPSE_WIDTH_YEARS: f64 = 3.0;
+const RESIDUAL_REPERTOIRE: f64 = 0.01;
+// Serum IL-6 of ~1 pg/mL in young adults, rising two- to threefold by old
+// age. Senescent-cell burden grows far faster than that, so IL-6 follows
+// its logarithm.
+// Ferrucci L et al. (2005) Blood 105:2294-2299
+const YOUNG_IL6_PG_ML: f64 = 1.0;
+const IL6_PER_LOG_SASP_FOLD: f64 = 0.5;
+const YOUNG_ADULT_REFERENCE_YEARS: f64 = 25.0;
+// Germinal-centre output falls with loss of T follicular helper
+// diversity and with chronic inflammation; the exponents are set so a
+// 75-year-old makes roughly a third of a young adult's vaccine IgG.
+// Goodwin K, Viboud C, Simonsen L (2006) Vaccine 24:1159-1169
+const REPERTOIRE_HELP_EXPONENT: f64 = 0.25;
+const INFLAMMATORY_SUPPRESSION_PER_FOLD: f64 = 0.5;
+
+// Age-related state of adaptive immunity, relative to a young adult.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Immunosenescence {
+    pub age_years: f64,
+    // Naive T-cell export as a fraction of age 20.
+    pub thymic_output: f64,
+    // Naive CD4 TCR clonotype diversity as a fraction of young adults.
+    pub tcr_diversity: f64,
+    pub baseline_il6_pg_ml: f64,
+}
+
+impl Immunosenescence {
+    // Quasi-steady state at an age, with inflammaging from the same
+    // senescent-cell burden the aging engine uses.
+    pub fn for_age(age_years: f64) -> Self {
+        let burden =
+            SenescentCellBurden::new(SenescenceParameters::human_time_rescaled(), age_years);
+        let mut immunity = Self {
+            age_years,
+            thymic_output: 1.0,
+            tcr_diversity: 1.0,
+            baseline_il6_pg_ml: YOUNG_IL6_PG_ML,
+        };
+        immunity.update(age_years, burden.sasp().inflammatory_fold_over_young);
+        immunity
+    }
+
+    // `sasp_fold` is the aging engine's SASP output over its young cohort.
+    pub(crate) fn update(&mut self, age_years: f64, sasp_fold: f64) {
+        self.age_years = age_years;
+        self.thymic_output = thymic_output(age_years);
+        self.tcr_diversity = RESIDUAL_REPERTOIRE
+            + (1.0 - RESIDUAL_REPERTOIRE)
+                / (1.0
+                    + ((age_years - REPERTOIRE_COLLAPSE_AGE_YEARS)
+                        / REPERTOIRE_COLLAPSE_WIDTH_YEARS)
+                        .exp());
+        let young_adult = SenescentCellBurden::new(
+            SenescenceParameters::human_time_rescaled(),
+            YOUNG_ADULT_REFERENCE_YEARS,
+        )
+        .sasp()
+        .inflammatory_fold_over_young;
+        let log_fold = (sasp_fold / young_adult).max(1.0).ln();
+        self.baseline_il6_pg_ml = YOUNG_IL6_PG_ML * (1.0 + IL6_PER_LOG_SASP_FOLD * log_fold);
+    }
+
+    pub fn inflammatory_fold(&self) -> f64 {
+        self.baseline_il6_pg_ml / YOUNG_IL6_PG_ML
+    }
+
+    // Multiplier on vaccine-induced IgG relative to a young adult.
+    pub fn germinal_centre_factor(&self) -> f64 {
+        let help = self.tcr_diversity.powf(REPERTOIRE_HELP_EXPONENT);
+        let suppression =
+            1.0 + INFLAMMATORY_SUPPRESSION_PER_FOLD * (self.inflammatory_fold() - 1.0);
+        help / suppression
+    }
+}
+
+pub fn thymic_output(age_years: f64) -> f64 {
+    let early = (age_years.min(MIDDLE_AGE_YEARS) - THYMIC_INVOLUTION_ONSET_YEARS).max(0.0);
+    let late = (age_years - MIDDLE_AGE_YEARS).max(0.0);
+    (1.0 - EARLY_INVOLUTION_PER_YEAR).powf(early) * (1.0 - LATE_INVOLUTION_PER_YEAR).powf(late)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thymus_and_repertoire_decline_on_different_schedules() {
+        assert_eq!(thymic_output(15.0), 1.0);
+        assert!((thymic_output(45.0) - 0.97f64.powi(25)).abs() < 1e-12);
+        let middle = Immunosenescence::for_age(50.0);
+        let old = Immunosenescence::for_age(80.0);
+        // The thymus has mostly involuted by 50 while the repertoire holds.
+        assert!(middle.thymic_output < 0.5 && middle.tcr_diversity > 0.99);
+        assert!(old.tcr_diversity < 0.1);
+    }
+
+    #[test]
+    fn test_inflammaging_and_germinal_centres_by_age() {
+        let young = Immunosenescence::for_age(25.0);
+        let old = Immunosenescence::for_age(75.0);
+        assert!((young.baseline_il6_pg_ml - YOUNG_IL6_PG_ML).abs() < 1e-9);
+        assert!(old.inflammatory_fold() > 2.0 && old.inflammatory_fold() < 4.0);
+        assert!((young.germinal_centre_factor() - 1.0).abs() < 1e-6);
+        let old_gc = old.germinal_centre_factor();
+        assert!(old_gc > 0.2 && old_gc < 0.5, "old germinal centre {old_gc}");
+    }
+}
@@ -0,0 +1,168 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::immune::antibody::{AntibodyClass, AntibodyKinetics, ImmuneResponse};
+use serde::{Deserialize, Serialize};
+
+// FcRn carries IgG across the placenta from ~17 weeks; the cord:maternal
+// ratio is ~0.1 before 22 weeks, ~0.5 at 28-32 weeks and exceeds 1 at
+// term, so preterm infants start with much less.
+// Palmeira P et al. (2012) Clin Dev Immunol 2012:985646
+const TERM_CORD_TO_MATERNAL_RATIO: f64 = 1.3;
+const TRANSFER_MIDPOINT_WEEKS: f64 = 31.0;
+const TRANSFER_WIDTH_WEEKS: f64 = 2.5;
+// Maternal measles IgG halves every ~40 days in the infant.
+// Leuridan E et al. (2010) BMJ 340:c1626
+const MATERNAL_IGG_HALF_LIFE_DAYS: f64 = 40.0;
+
+pub fn cord_to_maternal_igg_ratio(gestational_weeks: f64) -> f64 {
+    TERM_CORD_TO_MATERNAL_RATIO
+        / (1.0 + (-(gestational_weeks - TRANSFER_MIDPOINT_WEEKS) / TRANSFER_WIDTH_WEEKS).exp())
+}
+
+// Secretory IgA in milk falls from ~12 g/L in colostrum to ~1 g/L in
+// mature milk over the first two weeks. It protects the infant's gut and
+// airway mucosa but is not absorbed, so it adds nothing to serum titres.
+// Hanson LA (1998) Ann Allergy Asthma Immunol 81:523-533
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BreastMilkIga {
+    pub colostrum_g_l: f64,
+    pub mature_g_l: f64,
+    pub transition_days: f64,
+    pub weaning_day: f64,
+}
+
+impl BreastMilkIga {
+    pub fn new(weaning_day: f64) -> BiologyResult<Self> {
+        if weaning_day.is_nan() || weaning_day < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "weaning day must be non-negative".to_string(),
+            ));
+        }
+        Ok(Self {
+            colostrum_g_l: 12.0,
+            mature_g_l: 1.0,
+            transition_days: 4.0,
+            weaning_day,
+        })
+    }
+
+    pub fn concentration_g_l(&self, day: f64) -> f64 {
+        if day < 0.0 || day >= self.weaning_day {
+            return 0.0;
+        }
+        self.mature_g_l
+            + (self.colostrum_g_l - self.mature_g_l) * (-day / self.transition_days).exp()
+    }
+
+    // Mucosal IgA dose, taking intake as ~150 mL/kg/day for a 3.5 kg infant.
+    pub fn daily_intake_g(&self, day: f64) -> f64 {
+        const MILK_L_PER_DAY: f64 = 0.525;
+        self.concentration_g_l(day) * MILK_L_PER_DAY
+    }
+}
+
+// Passive immunity a newborn carries against one antigen. Day 0 is birth.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Neonate {
+    pub gestational_weeks: f64,
+    pub maternal_igg: ImmuneResponse,
+    pub breast_milk: Option<BreastMilkIga>,
+}
+
+impl Neonate {
+    // `delivery_day` is on the mother's clock, so her titre at delivery
+    // sets the infant's starting level.
+    pub fn born_to(
+        mother: &ImmuneResponse,
+        delivery_day: f64,
+        gestational_weeks: f64,
+    ) -> BiologyResult<Self> {
+        if !(22.0..=44.0).contains(&gestational_weeks) {
+            return Err(BiologyError::InvalidValue(
+                "gestational age must lie between 22 and 44 weeks".to_string(),
+            ));
+        }
+        let cord = mother.titer(AntibodyClass::IgG, delivery_day)
+            * cord_to_maternal_igg_ratio(gestational_weeks);
+        let decay = AntibodyKinetics::new(
+            1.0,
+            MATERNAL_IGG_HALF_LIFE_DAYS,
+            MATERNAL_IGG_HALF_LIFE_DAYS,
+        )?;
+        let mut maternal_igg =
+            ImmuneResponse::new().with_titer(AntibodyClass::IgG, cord, 0.0, decay)?;
+        maternal_igg.seroprotection_thresholds = mother.seroprotection_thresholds.clone();
+        Ok(Self {
+            gestational_weeks,
+            maternal_igg,
+            breast_milk: None,
+        })
+    }
+
+    pub fn with_breastfeeding(mut self, weaning_day: f64) -> BiologyResult<Self> {
+        self.breast_milk = Some(BreastMilkIga::new(weaning_day)?);
+        Ok(self)
+    }
+
+    pub fn maternal_titer(&self, day: f64) -> f64 {
+        self.maternal_igg.titer(AntibodyClass::IgG, day)
+    }
+
+    // Day maternal IgG drops below the protective threshold, leaving the
+    // window before the infant's own response.
+    pub fn maternal_protection_until(&self) -> Option<f64> {
+        self.maternal_igg.time_to_seronegative(AntibodyClass::IgG)
+    }
+
+    pub fn mucosal_iga_g(&self, day: f64) -> f64 {
+        self.breast_milk
+            .map_or(0.0, |milk| milk.daily_intake_g(day))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::immune::antibody::MEASLES_SEROPROTECTION_MIU_ML;
+
+    fn mother(igg_miu_ml: f64) -> ImmuneResponse {
+        ImmuneResponse::new()
+            .with_titer(AntibodyClass::IgG, igg_miu_ml, 0.0, AntibodyKinetics::igg())
+            .unwrap()
+            .with_seroprotection_threshold(AntibodyClass::IgG, MEASLES_SEROPROTECTION_MIU_ML)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_transfer_depends_on_gestational_age() {
+        assert!(cord_to_maternal_igg_ratio(20.0) < 0.1);
+        let mid = cord_to_maternal_igg_ratio(30.0);
+        assert!(mid > 0.3 && mid < 0.7);
+        assert!(cord_to_maternal_igg_ratio(40.0) > 1.0);
+        let term = Neonate::born_to(&mother(1000.0), 0.0, 40.0).unwrap();
+        let preterm = Neonate::born_to(&mother(1000.0), 0.0, 28.0).unwrap();
+        assert!(term.maternal_titer(0.0) > 1000.0);
+        assert!(preterm.maternal_titer(0.0) < 0.5 * term.maternal_titer(0.0));
+        assert!(Neonate::born_to(&mother(1000.0), 0.0, 18.0).is_err());
+    }
+
+    #[test]
+    fn test_maternal_protection_wanes_within_months() {
+        let infant = Neonate::born_to(&mother(1000.0), 0.0, 40.0).unwrap();
+        assert!((infant.maternal_titer(40.0) / infant.maternal_titer(0.0) - 0.5).abs() < 1e-9);
+        let until = infant.maternal_protection_until().unwrap();
+        // ~1270 mIU/mL at birth falls below 120 after ~3.4 half-lives.
+        assert!(until > 90.0 && until < 180.0);
+    }
+
+    #[test]
+    fn test_breast_milk_iga_is_mucosal_and_stops_at_weaning() {
+        let infant = Neonate::born_to(&mother(500.0), 0.0, 39.0)
+            .unwrap()
+            .with_breastfeeding(180.0)
+            .unwrap();
+        assert!(infant.mucosal_iga_g(1.0) > 3.0 * infant.mucosal_iga_g(30.0));
+        assert!(infant.mucosal_iga_g(30.0) > 0.0);
+        assert_eq!(infant.mucosal_iga_g(200.0), 0.0);
+        assert_eq!(infant.maternal_igg.titer(AntibodyClass::IgA, 1.0), 0.0);
+    }
+}
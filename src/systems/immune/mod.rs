@@ -0,0 +1,31 @@
+pub mod adjuvant;
+pub mod antibody;
+pub mod cold_chain;
+pub mod exhaustion;
+pub mod granuloma;
+pub mod hypersensitivity;
+pub mod immunosenescence;
+pub mod maternal;
+pub mod trained_immunity;
+pub mod vaccination;
+
+pub use adjuvant::{Adjuvant, AdjuvantMechanism, ApcActivation};
+pub use antibody::{
+    AntibodyClass, AntibodyKinetics, AntibodyTiter, ImmuneResponse, ANTI_HBS_SEROPROTECTION_MIU_ML,
+    MEASLES_SEROPROTECTION_MIU_ML, TETANUS_SEROPROTECTION_IU_ML,
+};
+pub use cold_chain::{
+    Excursion, ExcursionKind, StorageInterval, VaccineLot, VaccineStability, VialMonitor,
+};
+pub use exhaustion::{ChronicInfection, TCellExhaustion};
+pub use granuloma::{
+    AntigenDepot, DepotKind, GranulomaModel, GranulomaOutcome, GranulomaParameters,
+};
+pub use hypersensitivity::{
+    anaphylaxis_risk, AnaphylaxisRisk, DelayedTypeReaction, Excipient, MastCellResponse,
+    ReactionGrade, Sensitization, ANAPHYLAXIS_PER_MILLION_DOSES, TST_POSITIVE_MM,
+};
+pub use immunosenescence::{thymic_output, Immunosenescence};
+pub use maternal::{cord_to_maternal_igg_ratio, BreastMilkIga, Neonate};
+pub use trained_immunity::{TrainedImmunity, TrainingStimulus};
+pub use vaccination::{VaccinationSchedule, VaccineDose};
@@ -0,0 +1,188 @@
+use crate::biology::genetics::epigenetics::{EpigeneticKinetics, PromoterState};
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrainingStimulus {
+    Bcg,
+    BetaGlucan,
+    Lipopolysaccharide,
+}
+
+impl TrainingStimulus {
+    // Live BCG persists for weeks; β-glucan and LPS act as short pulses.
+    pub fn exposure_days(&self) -> f64 {
+        match self {
+            TrainingStimulus::Bcg => 14.0,
+            TrainingStimulus::BetaGlucan => 1.0,
+            TrainingStimulus::Lipopolysaccharide => 1.0,
+        }
+    }
+
+    // High-dose LPS drives tolerance rather than training.
+    // Ifrim DC et al. (2014) Clin Vaccine Immunol 21:534-545, PMID 24521784
+    pub fn induces_tolerance(&self) -> bool {
+        matches!(self, TrainingStimulus::Lipopolysaccharide)
+    }
+}
+
+// Priming marks (H3K4me3 / H3K27ac at TNF, IL6, IL1B promoters) are written
+// in bone-marrow progenitors and inherited by the monocytes they produce, so
+// training outlives the ~1 day monocyte lifespan.
+// Kaufmann E et al. (2018) Cell 172:176-190, PMID 29328912
+// Patel AA et al. (2017) J Exp Med 214:1913-1923, PMID 28606987
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainedImmunity {
+    pub progenitor_mark: PromoterState,
+    pub monocyte_mark: PromoterState,
+    pub tolerance: f64,
+    pub kinetics: EpigeneticKinetics,
+    pub monocyte_turnover_per_day: f64,
+    pub tolerance_decay_per_day: f64,
+    stimulus: Option<(TrainingStimulus, f64, f64)>,
+}
+
+// BCG raises heterologous TNF/IL-1β responses ~2-fold, persisting at 3
+// months and waning by ~1 year.
+// Kleinnijenhuis J et al. (2012) Proc Natl Acad Sci USA 109:17537, PMID 22988082
+// Kleinnijenhuis J et al. (2014) Clin Immunol 155:213-219, PMID 25451159
+const TRAINED_RESPONSE_GAIN: f64 = 1.5;
+const NAIVE_PRIMING: f64 = 0.02;
+
+impl TrainedImmunity {
+    pub fn new_naive() -> Self {
+        let naive = PromoterState {
+            methylation: 0.0,
+            acetylation: NAIVE_PRIMING,
+        };
+        Self {
+            progenitor_mark: naive,
+            monocyte_mark: naive,
+            tolerance: 0.0,
+            kinetics: EpigeneticKinetics {
+                de_novo_methylation_per_day: 0.0,
+                demethylation_per_day: 0.0,
+                acetylation_per_day: 0.4,
+                deacetylation_per_day: 0.008,
+                recruitment_strength: 0.5,
+                recruitment_half_saturation: 0.5,
+                maintenance_fidelity: 1.0,
+            },
+            monocyte_turnover_per_day: 1.0,
+            tolerance_decay_per_day: 0.1,
+            stimulus: None,
+        }
+    }
+
+    pub fn expose(&mut self, stimulus: TrainingStimulus, intensity: f64) -> BiologyResult<()> {
+        if intensity <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "training stimulus intensity must be positive".to_string(),
+            ));
+        }
+        if stimulus.induces_tolerance() {
+            self.tolerance = (self.tolerance + intensity).min(1.0);
+        }
+        self.stimulus = Some((stimulus, intensity, stimulus.exposure_days()));
+        Ok(())
+    }
+
+    pub fn is_exposed(&self) -> bool {
+        self.stimulus.is_some()
+    }
+
+    pub fn step(&mut self, dt_days: f64) {
+        let signal = match self.stimulus.take() {
+            Some((stimulus, intensity, remaining)) => {
+                let left = remaining - dt_days;
+                if left > 0.0 {
+                    self.stimulus = Some((stimulus, intensity, left));
+                }
+                if stimulus.induces_tolerance() {
+                    0.0
+                } else {
+                    intensity
+                }
+            }
+            None => 0.0,
+        };
+
+        self.progenitor_mark
+            .step(&self.kinetics, signal, 0.0, dt_days);
+        // Circulating monocytes are replaced from the primed progenitor pool.
+        let replaced = (1.0 - (-self.monocyte_turnover_per_day * dt_days).exp()).clamp(0.0, 1.0);
+        self.monocyte_mark.acetylation +=
+            (self.progenitor_mark.acetylation - self.monocyte_mark.acetylation) * replaced;
+        self.tolerance *= (-self.tolerance_decay_per_day * dt_days).exp();
+    }
+
+    pub fn run_days(&mut self, days: usize) {
+        for _ in 0..days {
+            self.step(1.0);
+        }
+    }
+
+    // Cytokine output to an unrelated challenge relative to a naive host.
+    pub fn cytokine_response_fold(&self) -> f64 {
+        let primed = (self.monocyte_mark.transcription_gate() - NAIVE_PRIMING).max(0.0);
+        ((1.0 + TRAINED_RESPONSE_GAIN * primed) * (1.0 - self.tolerance)).max(0.0)
+    }
+
+    // Pathogen clearance scales with innate effector output.
+    pub fn nonspecific_protection_factor(&self) -> f64 {
+        self.cytokine_response_fold().sqrt()
+    }
+}
+
+impl Default for TrainedImmunity {
+    fn default() -> Self {
+        Self::new_naive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_naive_host_has_unit_response() {
+        let host = TrainedImmunity::new_naive();
+        assert!((host.cytokine_response_fold() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bcg_training_persists_for_months() {
+        let mut host = TrainedImmunity::new_naive();
+        host.expose(TrainingStimulus::Bcg, 1.0).unwrap();
+        host.run_days(14);
+        let peak = host.cytokine_response_fold();
+        assert!(peak > 1.7 && peak < 3.0, "peak {peak}");
+        host.run_days(76);
+        let three_months = host.cytokine_response_fold();
+        assert!(three_months > 1.5, "3 months {three_months}");
+        host.run_days(275);
+        let one_year = host.cytokine_response_fold();
+        assert!(one_year < three_months && one_year > 1.0);
+    }
+
+    #[test]
+    fn test_training_survives_monocyte_turnover() {
+        let mut host = TrainedImmunity::new_naive();
+        host.expose(TrainingStimulus::BetaGlucan, 2.0).unwrap();
+        host.run_days(30);
+        // Thirty monocyte lifetimes later the response is still elevated.
+        assert!(host.cytokine_response_fold() > 1.3);
+    }
+
+    #[test]
+    fn test_lps_induces_tolerance() {
+        let mut host = TrainedImmunity::new_naive();
+        host.expose(TrainingStimulus::Lipopolysaccharide, 0.7)
+            .unwrap();
+        host.step(1.0);
+        assert!(host.cytokine_response_fold() < 0.5);
+        host.run_days(60);
+        assert!(host.cytokine_response_fold() > 0.9);
+        assert!(host.expose(TrainingStimulus::Bcg, 0.0).is_err());
+    }
+}
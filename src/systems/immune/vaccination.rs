@@ -0,0 +1,198 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::immune::adjuvant::Adjuvant;
+use crate::systems::immune::antibody::{AntibodyClass, ImmuneResponse};
+use crate::systems::immune::immunosenescence::Immunosenescence;
+use crate::systems::immune::maternal::Neonate;
+use serde::{Deserialize, Serialize};
+
+// Secondary IgG responses from primed memory B cells peak about an order
+// of magnitude above the primary.
+const SECONDARY_RESPONSE_GAIN: f64 = 10.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaccineDose {
+    pub day: f64,
+    pub antigen_ug: f64,
+    pub adjuvant: Option<Adjuvant>,
+}
+
+// Doses of one vaccine on the recipient's clock (day 0 is birth for
+// infant schedules).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaccinationSchedule {
+    pub antigen: String,
+    pub doses: Vec<VaccineDose>,
+    pub seroprotection_threshold: f64,
+}
+
+impl VaccinationSchedule {
+    pub fn new(antigen: &str, seroprotection_threshold: f64) -> BiologyResult<Self> {
+        if seroprotection_threshold.is_nan() || seroprotection_threshold <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "seroprotection threshold must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            antigen: antigen.to_string(),
+            doses: Vec::new(),
+            seroprotection_threshold,
+        })
+    }
+
+    pub fn with_dose(
+        mut self,
+        day: f64,
+        antigen_ug: f64,
+        adjuvant: Option<Adjuvant>,
+    ) -> BiologyResult<Self> {
+        if !day.is_finite() || self.doses.last().is_some_and(|d| d.day >= day) {
+            return Err(BiologyError::InvalidValue(
+                "doses must be given on increasing finite days".to_string(),
+            ));
+        }
+        self.doses.push(VaccineDose {
+            day,
+            antigen_ug,
+            adjuvant,
+        });
+        Ok(self)
+    }
+
+    pub fn response(&self) -> BiologyResult<ImmuneResponse> {
+        self.response_with(1.0, |_| 1.0)
+    }
+
+    // Older recipients form weaker germinal centres, scaling every dose's
+    // IgG; priming still takes, so boosters help.
+    pub fn response_aged(&self, immunity: &Immunosenescence) -> BiologyResult<ImmuneResponse> {
+        self.response_with(immunity.germinal_centre_factor(), |_| 1.0)
+    }
+
+    // Maternal IgG binds the vaccine antigen and masks its epitopes from
+    // infant B cells, blunting the IgG each dose induces while it persists.
+    // Modelled as hyperbolic inhibition, half-maximal when the maternal
+    // titre at the dose equals the protective threshold.
+    // Niewiesk S (2014) Front Immunol 5:446
+    pub fn response_in(&self, neonate: &Neonate) -> BiologyResult<ImmuneResponse> {
+        self.response_with(1.0, |day| {
+            1.0 / (1.0 + neonate.maternal_titer(day) / self.seroprotection_threshold)
+        })
+    }
+
+    // Each dose adds a response centred on its own day; later doses boost
+    // from memory only once an earlier dose has primed, and a dose only
+    // replaces the running titre if its peak is higher than what is left.
+    fn response_with(
+        &self,
+        germinal_centre: f64,
+        masking: impl Fn(f64) -> f64,
+    ) -> BiologyResult<ImmuneResponse> {
+        let mut response = ImmuneResponse::new()
+            .with_seroprotection_threshold(AntibodyClass::IgG, self.seroprotection_threshold)?;
+        let mut primed = false;
+        for dose in &self.doses {
+            let induced = ImmuneResponse::from_vaccine(dose.antigen_ug, dose.adjuvant.as_ref())?;
+            let unmasked = masking(dose.day);
+            for (&class, titer) in &induced.titers {
+                let mut peak = titer.peak;
+                if class == AntibodyClass::IgG {
+                    peak *= germinal_centre * unmasked;
+                    if primed {
+                        peak *= SECONDARY_RESPONSE_GAIN;
+                    }
+                }
+                let peak_day = dose.day + titer.peak_day;
+                if peak > response.titer(class, peak_day) {
+                    response = response.with_titer(class, peak, peak_day, titer.kinetics)?;
+                }
+            }
+            // A dose swamped by maternal antibody leaves no memory.
+            primed |= unmasked > 0.5;
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::immune::antibody::{AntibodyKinetics, MEASLES_SEROPROTECTION_MIU_ML};
+
+    fn measles_schedule(first_dose_day: f64) -> VaccinationSchedule {
+        VaccinationSchedule::new("measles", MEASLES_SEROPROTECTION_MIU_ML)
+            .unwrap()
+            .with_dose(first_dose_day, 5.0, None)
+            .unwrap()
+    }
+
+    fn infant() -> Neonate {
+        let mother = ImmuneResponse::new()
+            .with_titer(AntibodyClass::IgG, 1500.0, 0.0, AntibodyKinetics::igg())
+            .unwrap()
+            .with_seroprotection_threshold(AntibodyClass::IgG, MEASLES_SEROPROTECTION_MIU_ML)
+            .unwrap();
+        Neonate::born_to(&mother, 0.0, 40.0).unwrap()
+    }
+
+    #[test]
+    fn test_maternal_antibody_blunts_early_seroconversion() {
+        let infant = infant();
+        let peak = |day: f64| {
+            let schedule = measles_schedule(day);
+            let response = schedule.response_in(&infant).unwrap();
+            response.titers[&AntibodyClass::IgG].peak
+        };
+        let unopposed = measles_schedule(60.0).response().unwrap().titers[&AntibodyClass::IgG].peak;
+        // At two months maternal IgG is still well above threshold.
+        assert!(peak(60.0) < 0.3 * unopposed);
+        // By a year it has gone and the dose takes fully.
+        assert!(peak(365.0) > 0.95 * unopposed);
+        // IgM is not masked.
+        let early = measles_schedule(60.0).response_in(&infant).unwrap();
+        let adult = measles_schedule(60.0).response().unwrap();
+        assert_eq!(
+            early.titers[&AntibodyClass::IgM].peak,
+            adult.titers[&AntibodyClass::IgM].peak
+        );
+    }
+
+    #[test]
+    fn test_booster_after_priming_dose() {
+        let schedule = VaccinationSchedule::new("hepatitis_b", 10.0)
+            .unwrap()
+            .with_dose(0.0, 20.0, Some(Adjuvant::alum()))
+            .unwrap()
+            .with_dose(180.0, 20.0, Some(Adjuvant::alum()))
+            .unwrap();
+        let response = schedule.response().unwrap();
+        let single = ImmuneResponse::from_vaccine(20.0, Some(&Adjuvant::alum())).unwrap();
+        let igg = response.titers[&AntibodyClass::IgG];
+        assert!(igg.peak_day > 180.0);
+        assert!(igg.peak > 5.0 * single.titers[&AntibodyClass::IgG].peak);
+        assert!(response.is_seroprotected(AntibodyClass::IgG, igg.peak_day + 365.0));
+        assert!(schedule.clone().with_dose(100.0, 20.0, None).is_err());
+    }
+
+    #[test]
+    fn test_older_recipients_respond_less_and_lose_protection_sooner() {
+        let schedule = VaccinationSchedule::new("hepatitis_b", 10.0)
+            .unwrap()
+            .with_dose(0.0, 20.0, Some(Adjuvant::alum()))
+            .unwrap()
+            .with_dose(30.0, 20.0, Some(Adjuvant::alum()))
+            .unwrap();
+        let young = schedule
+            .response_aged(&Immunosenescence::for_age(25.0))
+            .unwrap();
+        let old = schedule
+            .response_aged(&Immunosenescence::for_age(75.0))
+            .unwrap();
+        let peak = |r: &ImmuneResponse| r.titers[&AntibodyClass::IgG].peak;
+        assert!((peak(&young) - peak(&schedule.response().unwrap())).abs() < 1e-3 * peak(&young));
+        assert!(peak(&old) < 0.5 * peak(&young));
+        assert!(
+            old.time_to_seronegative(AntibodyClass::IgG).unwrap()
+                < young.time_to_seronegative(AntibodyClass::IgG).unwrap()
+        );
+    }
+}
@@ -1,9 +1,13 @@
 pub mod cardiovascular;
+pub mod immune;
 pub mod nervous;
 pub mod renal;
 pub mod respiratory;
+pub mod skeletal;
 
 pub use cardiovascular::{Blood, BloodVessel, Heart};
+pub use immune::TrainedImmunity;
 pub use nervous::{CentralNervousSystem, PeripheralNervousSystem};
 pub use renal::{Filtration, Kidney};
 pub use respiratory::{BreathingPattern, GasExchange, Lung};
+pub use skeletal::{BoneRemodelingModel, HydroxyapatiteCrystal};
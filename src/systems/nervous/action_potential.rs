@@ -1,3 +1,4 @@
+use crate::biology::{BiologyError, BiologyResult};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -168,6 +169,126 @@ impl ActionPotentialDynamics {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelKind {
+    Sodium,
+    Potassium,
+    Calcium,
+}
+
+// Builds a neuron membrane from the resting defaults, checking the
+// configuration only at `build()`:
+//   NeuronBuilder::new().threshold(-50.0).with_channel(ChannelKind::Calcium, ca)
+#[derive(Debug, Clone)]
+pub struct NeuronBuilder {
+    dynamics: ActionPotentialDynamics,
+}
+
+impl NeuronBuilder {
+    pub fn new() -> Self {
+        Self {
+            dynamics: ActionPotentialDynamics::new_resting(),
+        }
+    }
+
+    // Also starts the membrane at this potential.
+    pub fn resting_potential(mut self, mv: f64) -> Self {
+        self.dynamics.resting_potential_mv = mv;
+        self.dynamics.membrane_potential_mv = mv;
+        self
+    }
+
+    pub fn threshold(mut self, mv: f64) -> Self {
+        self.dynamics.threshold_mv = mv;
+        self
+    }
+
+    pub fn leak_conductance(mut self, ms_cm2: f64) -> Self {
+        self.dynamics.leak_conductance_ms_cm2 = ms_cm2;
+        self
+    }
+
+    pub fn with_channel(mut self, kind: ChannelKind, channels: IonChannelPopulation) -> Self {
+        *self.channel_mut(kind) = channels;
+        self
+    }
+
+    // Blocks a channel type entirely, e.g. tetrodotoxin on sodium.
+    pub fn without_channel(mut self, kind: ChannelKind) -> Self {
+        let channel = self.channel_mut(kind);
+        channel.total_channels = 0;
+        channel.max_conductance_ms_cm2 = 0.0;
+        channel.open_fraction = 0.0;
+        self
+    }
+
+    fn channel_mut(&mut self, kind: ChannelKind) -> &mut IonChannelPopulation {
+        match kind {
+            ChannelKind::Sodium => &mut self.dynamics.sodium_channels,
+            ChannelKind::Potassium => &mut self.dynamics.potassium_channels,
+            ChannelKind::Calcium => &mut self.dynamics.calcium_channels,
+        }
+    }
+
+    pub fn build(self) -> BiologyResult<ActionPotentialDynamics> {
+        let d = self.dynamics;
+        if !(d.resting_potential_mv.is_finite() && d.threshold_mv.is_finite()) {
+            return Err(BiologyError::InvalidParameter(
+                "membrane potentials must be finite".to_string(),
+            ));
+        }
+        if d.threshold_mv <= d.resting_potential_mv {
+            return Err(BiologyError::InvalidParameter(format!(
+                "threshold {} mV must lie above rest {} mV",
+                d.threshold_mv, d.resting_potential_mv
+            )));
+        }
+        if d.leak_conductance_ms_cm2.is_nan() || d.leak_conductance_ms_cm2 < 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "leak conductance must be non-negative".to_string(),
+            ));
+        }
+        let unit = |x: f64| (0.0..=1.0).contains(&x);
+        for (kind, c) in [
+            (ChannelKind::Sodium, &d.sodium_channels),
+            (ChannelKind::Potassium, &d.potassium_channels),
+            (ChannelKind::Calcium, &d.calcium_channels),
+        ] {
+            if c.max_conductance_ms_cm2.is_nan()
+                || c.max_conductance_ms_cm2 < 0.0
+                || !c.reversal_potential_mv.is_finite()
+            {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "{:?} channel needs a non-negative conductance and finite reversal",
+                    kind
+                )));
+            }
+            if !(unit(c.open_fraction) && unit(c.activation_gate_m) && unit(c.inactivation_gate_h))
+            {
+                return Err(BiologyError::InvalidParameter(format!(
+                    "{:?} channel gates must lie in [0, 1]",
+                    kind
+                )));
+            }
+        }
+        // A sodium reversal below threshold could never carry the upstroke.
+        if d.sodium_channels.max_conductance_ms_cm2 > 0.0
+            && d.sodium_channels.reversal_potential_mv <= d.threshold_mv
+        {
+            return Err(BiologyError::InvalidParameter(
+                "sodium reversal potential must exceed threshold".to_string(),
+            ));
+        }
+        Ok(d)
+    }
+}
+
+impl Default for NeuronBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl IonChannelPopulation {
     pub fn new_sodium() -> Self {
         Self {
@@ -436,4 +557,32 @@ mod tests {
         assert!(synapse.is_inhibitory(NeurotransmitterType::GABA));
         assert!(!synapse.is_inhibitory(NeurotransmitterType::Glutamate));
     }
+
+    #[test]
+    fn test_neuron_builder_validates() {
+        let neuron = NeuronBuilder::new()
+            .resting_potential(-65.0)
+            .threshold(-50.0)
+            .without_channel(ChannelKind::Calcium)
+            .build()
+            .unwrap();
+        assert_eq!(neuron.membrane_potential_mv, -65.0);
+        assert_eq!(neuron.calcium_channels.max_conductance_ms_cm2, 0.0);
+        assert!(neuron.is_at_rest());
+
+        assert!(NeuronBuilder::new().threshold(-80.0).build().is_err());
+        assert!(NeuronBuilder::new().leak_conductance(-0.1).build().is_err());
+        let mut leaky_gate = IonChannelPopulation::new_potassium();
+        leaky_gate.activation_gate_m = 1.5;
+        assert!(NeuronBuilder::new()
+            .with_channel(ChannelKind::Potassium, leaky_gate)
+            .build()
+            .is_err());
+        let mut reversed = IonChannelPopulation::new_sodium();
+        reversed.reversal_potential_mv = -80.0;
+        assert!(NeuronBuilder::new()
+            .with_channel(ChannelKind::Sodium, reversed)
+            .build()
+            .is_err());
+    }
 }
@@ -52,9 +52,12 @@ impl BloodBrainBarrierNeuroimmune {
 
     /// Calculate BBB integrity score
     pub fn bbb_integrity_score(&self) -> f64 {
-        let permeability_component = (0.015 - self.bbb_permeability_index_ml_min_g).max(0.0) / 0.015;
-        let occludin_component = (self.tight_junction_occludin_expression_fold_control).min(1.5) / 1.5;
-        let claudin_component = (self.claudin_5_tight_junction_expression_fold_control).min(1.5) / 1.5;
+        let permeability_component =
+            (0.015 - self.bbb_permeability_index_ml_min_g).max(0.0) / 0.015;
+        let occludin_component =
+            (self.tight_junction_occludin_expression_fold_control).min(1.5) / 1.5;
+        let claudin_component =
+            (self.claudin_5_tight_junction_expression_fold_control).min(1.5) / 1.5;
         let pericyte_component = self.pericyte_coverage_brain_capillaries_percent / 100.0;
 
         (permeability_component + occludin_component + claudin_component + pericyte_component) / 4.0
@@ -71,7 +74,10 @@ impl BloodBrainBarrierNeuroimmune {
 
     /// Calculate neuroprotective capacity
     pub fn neuroprotective_capacity(&self) -> f64 {
-        let efflux_capacity = self.p_glycoprotein_bbb_transporter_activity_fold_basal.min(2.0) / 2.0;
+        let efflux_capacity = self
+            .p_glycoprotein_bbb_transporter_activity_fold_basal
+            .min(2.0)
+            / 2.0;
         let glymphatic_function = self.astrocyte_aqp4_polarization_index;
         let structural_integrity = self.bbb_integrity_score();
 
@@ -210,4 +216,4 @@ pub enum CytokineTransport {
     Low,
     Normal,
     Excessive,
-}
\ No newline at end of file
+}
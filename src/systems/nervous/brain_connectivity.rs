@@ -1,3 +1,4 @@
+use crate::geometry::Vec3;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -156,6 +157,11 @@ pub enum PlasticityType {
     HomeostasicPlasticity,
 }
 
+// Hippocampal replay during slow-wave sleep transfers new memories to
+// cortex; per hour of baseline slow-wave activity.
+// Born J, Wilhelm I (2012) Psychol Res 76:192-203
+const REPLAY_RATE_PER_H: f64 = 0.1;
+
 impl BrainNetwork {
     pub fn new() -> Self {
         Self {
@@ -191,6 +197,23 @@ impl BrainNetwork {
             .filter(|r| self.region_connectivity(&r.name) >= threshold)
             .collect()
     }
+
+    // Strengthens hippocampal outputs towards full strength; `signal` is
+    // relative slow-wave activity, as from `SleepWakeRegulation`.
+    pub fn consolidate(&mut self, signal: f64, dt_hours: f64) {
+        let hippocampal: Vec<&str> = self
+            .regions
+            .iter()
+            .filter(|r| r.region_type == BrainRegionType::Hippocampus)
+            .map(|r| r.name.as_str())
+            .collect();
+        let gain = 1.0 - (-REPLAY_RATE_PER_H * signal.max(0.0) * dt_hours).exp();
+        for connection in &mut self.connections {
+            if hippocampal.contains(&connection.from_region.as_str()) {
+                connection.strength += (1.0 - connection.strength) * gain;
+            }
+        }
+    }
 }
 
 impl Default for BrainNetwork {
@@ -199,8 +222,8 @@ impl Default for BrainNetwork {
     }
 }
 
-impl NetworkMetrics {
-    pub fn default() -> Self {
+impl Default for NetworkMetrics {
+    fn default() -> Self {
         Self {
             global_efficiency: 0.5,
             local_efficiency: 0.6,
@@ -210,7 +233,9 @@ impl NetworkMetrics {
             small_worldness: 1.5,
         }
     }
+}
 
+impl NetworkMetrics {
     pub fn is_small_world(&self) -> bool {
         self.small_worldness > 1.0
     }
@@ -241,11 +266,7 @@ impl BrainRegion {
     }
 
     pub fn distance_to(&self, other: &BrainRegion) -> f64 {
-        let dx = self.coordinates_mni[0] - other.coordinates_mni[0];
-        let dy = self.coordinates_mni[1] - other.coordinates_mni[1];
-        let dz = self.coordinates_mni[2] - other.coordinates_mni[2];
-
-        (dx * dx + dy * dy + dz * dz).sqrt()
+        Vec3::from(self.coordinates_mni).distance(Vec3::from(other.coordinates_mni))
     }
 
     pub fn neuron_density(&self) -> f64 {
@@ -435,6 +456,31 @@ mod tests {
         assert!(ltd.is_depression());
     }
 
+    #[test]
+    fn test_sleep_consolidates_hippocampal_outputs() {
+        let mut network = BrainNetwork::new();
+        network.regions.push(BrainRegion::new(
+            "CA1".to_string(),
+            BrainRegionType::Hippocampus,
+        ));
+        network.regions.push(BrainRegion::new(
+            "PFC".to_string(),
+            BrainRegionType::PrefrontalCortex,
+        ));
+        for (from, to) in [("CA1", "PFC"), ("PFC", "CA1")] {
+            network.connections.push(NeuralConnection::new(
+                from.to_string(),
+                to.to_string(),
+                ConnectionType::Excitatory,
+            ));
+        }
+        network.consolidate(0.0, 8.0);
+        assert_eq!(network.connections[0].strength, 0.5);
+        network.consolidate(1.0, 8.0);
+        assert!(network.connections[0].is_strong());
+        assert_eq!(network.connections[1].strength, 0.5);
+    }
+
     #[test]
     fn test_neural_oscillation() {
         let alpha = NeuralOscillation::new(10.0, "Occipital".to_string());
@@ -0,0 +1,378 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::engine::{load, save, substep, unknown_input, Component};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
+
+// Goodwin negative-feedback loop: clock-gene mRNA is translated into a
+// cytoplasmic protein whose nuclear form represses transcription.
+// Concentrations in nM, rates per hour.
+// Gonze D, Bernard S et al. (2005) Biophys J 89:120-129
+const TRANSCRIPTION_NM_H: f64 = 0.7;
+const REPRESSION_NM: f64 = 1.0;
+const REPRESSION_HILL: i32 = 4;
+const RATE_NM_H: f64 = 0.35;
+const LINEAR_RATE_PER_H: f64 = 0.7;
+const SATURATION_NM: f64 = 1.0;
+// Those rates cycle every 23.7 h; slowed to the 24.18 h intrinsic period
+// of the human pacemaker under forced desynchrony.
+// Czeisler CA et al. (1999) Science 284:2177-2181
+const TIME_SCALE: f64 = 23.70 / 24.18;
+// Light induces Per transcription in the SCN, half-maximal near 100 lux
+// for melatonin suppression.
+// Zeitzer JM et al. (2000) J Physiol 526:695-702
+const LIGHT_DRIVE_MAX_NM_H: f64 = 0.006;
+const LIGHT_HALF_LUX: f64 = 100.0;
+// Centre and half-range of protein and repressor on the limit cycle; the
+// angle around it advances near-uniformly with time.
+const PROTEIN_CENTRE_NM: f64 = 0.3429;
+const PROTEIN_HALF_RANGE_NM: f64 = 0.1143;
+const REPRESSOR_CENTRE_NM: f64 = 2.082;
+const REPRESSOR_HALF_RANGE_NM: f64 = 0.299;
+// Angle-phase at local midnight when entrained to light from 07:00 to
+// 23:00, so internal hour reads as the local time the body expects.
+const MIDNIGHT_PHASE_H: f64 = 2.41;
+
+// Day-night swing of one physiological parameter as a multiplier on its
+// 24-h mean: 1 + amplitude * cos(2π (hour - acrophase) / 24).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ParameterModulator {
+    pub amplitude: f64,
+    pub acrophase_hour: f64,
+}
+
+impl ParameterModulator {
+    pub fn new(amplitude: f64, acrophase_hour: f64) -> BiologyResult<Self> {
+        if !(0.0..=1.0).contains(&amplitude) || !acrophase_hour.is_finite() {
+            return Err(BiologyError::InvalidParameter(
+                "modulator amplitude must lie in [0, 1] with a finite acrophase".to_string(),
+            ));
+        }
+        Ok(Self {
+            amplitude,
+            acrophase_hour: acrophase_hour.rem_euclid(24.0),
+        })
+    }
+
+    pub fn factor(&self, internal_hour: f64) -> f64 {
+        1.0 + self.amplitude * (TAU * (internal_hour - self.acrophase_hour) / 24.0).cos()
+    }
+
+    // Plasma cortisol peaks shortly after waking and is lowest around
+    // midnight. Debono M et al. (2009) J Clin Endocrinol Metab 94:1548-1554
+    pub fn cortisol() -> Self {
+        Self {
+            amplitude: 0.7,
+            acrophase_hour: 8.5,
+        }
+    }
+
+    // Pineal melatonin is secreted only at night, peaking around 03:00.
+    // Arendt J (2005) J Biol Rhythms 20:291-303
+    pub fn melatonin() -> Self {
+        Self {
+            amplitude: 1.0,
+            acrophase_hour: 3.0,
+        }
+    }
+
+    // Serum CTX peaks before waking and falls ~60% to an afternoon nadir.
+    // Qvist P et al. (2002) Bone 31:57-61
+    pub fn bone_resorption() -> Self {
+        Self {
+            amplitude: 0.3,
+            acrophase_hour: 5.0,
+        }
+    }
+
+    // Naive T cells leave the blood for lymph nodes by day and return at
+    // night as cortisol falls.
+    // Dimitrov S et al. (2009) Blood 113:5134-5143
+    pub fn circulating_lymphocytes() -> Self {
+        Self {
+            amplitude: 0.3,
+            acrophase_hour: 1.0,
+        }
+    }
+
+    // Estimated hepatic blood flow, and so clearance of high-extraction
+    // drugs, is highest in the morning.
+    // Lemmer B, Nold G (1991) Br J Clin Pharmacol 32:627-629
+    pub fn hepatic_clearance() -> Self {
+        Self {
+            amplitude: 0.2,
+            acrophase_hour: 8.0,
+        }
+    }
+}
+
+// Central pacemaker entrained by light, with the parameter modulators it
+// drives registered by name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CircadianClock {
+    pub mrna_nm: f64,
+    pub protein_nm: f64,
+    pub repressor_nm: f64,
+    pub light_lux: f64,
+    modulators: BTreeMap<String, ParameterModulator>,
+}
+
+impl Default for CircadianClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CircadianClock {
+    // Entrained to a 07:00-23:00 light schedule, at local midnight, with
+    // the standard modulators registered.
+    pub fn new() -> Self {
+        let mut modulators = BTreeMap::new();
+        for (name, modulator) in [
+            ("cortisol", ParameterModulator::cortisol()),
+            ("melatonin", ParameterModulator::melatonin()),
+            ("bone_resorption", ParameterModulator::bone_resorption()),
+            (
+                "circulating_lymphocytes",
+                ParameterModulator::circulating_lymphocytes(),
+            ),
+            ("hepatic_clearance", ParameterModulator::hepatic_clearance()),
+        ] {
+            modulators.insert(name.to_string(), modulator);
+        }
+        Self {
+            mrna_nm: 0.1252,
+            protein_nm: 0.4435,
+            repressor_nm: 2.3456,
+            light_lux: 0.0,
+            modulators,
+        }
+    }
+
+    pub fn set_light(&mut self, lux: f64) -> BiologyResult<()> {
+        check_light(lux)?;
+        self.light_lux = lux;
+        Ok(())
+    }
+
+    // Adds or replaces a named modulator.
+    pub fn register_modulator(&mut self, name: &str, modulator: ParameterModulator) {
+        self.modulators.insert(name.to_string(), modulator);
+    }
+
+    pub fn modulator_names(&self) -> impl Iterator<Item = &str> {
+        self.modulators.keys().map(String::as_str)
+    }
+
+    // Current multiplier for a registered parameter.
+    pub fn modulation(&self, name: &str) -> Option<f64> {
+        self.modulators
+            .get(name)
+            .map(|m| m.factor(self.internal_hour()))
+    }
+
+    // Local time the body expects, from the phase of the oscillator;
+    // within about an hour of true local time once entrained.
+    pub fn internal_hour(&self) -> f64 {
+        let angle = ((self.repressor_nm - REPRESSOR_CENTRE_NM) / REPRESSOR_HALF_RANGE_NM)
+            .atan2((self.protein_nm - PROTEIN_CENTRE_NM) / PROTEIN_HALF_RANGE_NM);
+        (24.0 * angle / TAU - MIDNIGHT_PHASE_H).rem_euclid(24.0)
+    }
+
+    fn light_drive_nm_h(&self) -> f64 {
+        LIGHT_DRIVE_MAX_NM_H * self.light_lux / (self.light_lux + LIGHT_HALF_LUX)
+    }
+
+    // Explicit Euler; stable for steps up to a few tenths of an hour.
+    pub fn step(&mut self, dt_hours: f64) {
+        let saturating = |c: f64| RATE_NM_H * c / (SATURATION_NM + c);
+        let repression = 1.0 / (1.0 + (self.repressor_nm / REPRESSION_NM).powi(REPRESSION_HILL));
+        let d_mrna =
+            TRANSCRIPTION_NM_H * repression - saturating(self.mrna_nm) + self.light_drive_nm_h();
+        let d_protein = LINEAR_RATE_PER_H * self.mrna_nm - saturating(self.protein_nm);
+        let d_repressor = LINEAR_RATE_PER_H * self.protein_nm - saturating(self.repressor_nm);
+        let h = dt_hours * TIME_SCALE;
+        self.mrna_nm = (self.mrna_nm + d_mrna * h).max(0.0);
+        self.protein_nm = (self.protein_nm + d_protein * h).max(0.0);
+        self.repressor_nm = (self.repressor_nm + d_repressor * h).max(0.0);
+    }
+}
+
+fn check_light(lux: f64) -> BiologyResult<()> {
+    if lux.is_nan() || lux < 0.0 {
+        return Err(BiologyError::InvalidValue(
+            "illuminance must be non-negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl Component for CircadianClock {
+    fn kind(&self) -> &'static str {
+        "circadian_clock"
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[("light_lux", "lux")]
+    }
+
+    // The standard modulators, as multipliers on their 24-h means.
+    fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("internal_hour", "h"),
+            ("cortisol", "1"),
+            ("melatonin", "1"),
+            ("bone_resorption", "1"),
+            ("circulating_lymphocytes", "1"),
+            ("hepatic_clearance", "1"),
+        ]
+    }
+
+    fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "light_lux" => self.set_light(value),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn check_input(&self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "light_lux" => check_light(value),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn output(&self, name: &str) -> Option<f64> {
+        match name {
+            "internal_hour" => Some(self.internal_hour()),
+            _ => self.modulation(name),
+        }
+    }
+
+    fn step(&mut self, dt_minutes: f64) {
+        substep(dt_minutes, 3.0, |h| CircadianClock::step(self, h / 60.0));
+    }
+
+    fn save_state(&self) -> Value {
+        save(self)
+    }
+
+    fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+        load(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pharmacology::pharmacokinetics::Pharmacokinetics;
+
+    const DT: f64 = 0.02;
+
+    // One day of 16 h at 500 lux from `lights_on`, hours after midnight
+    // of the body's home time zone.
+    fn live_day(clock: &mut CircadianClock, lights_on: f64) {
+        for i in 0..(24.0 / DT) as usize {
+            let hour = i as f64 * DT;
+            let lux = if (hour - lights_on).rem_euclid(24.0) < 16.0 {
+                500.0
+            } else {
+                0.0
+            };
+            clock.set_light(lux).unwrap();
+            clock.step(DT);
+        }
+    }
+
+    // Circular distance in hours.
+    fn hours_apart(a: f64, b: f64) -> f64 {
+        let d = (a - b).rem_euclid(24.0);
+        d.min(24.0 - d)
+    }
+
+    #[test]
+    fn test_free_runs_with_human_period_in_darkness() {
+        let mut clock = CircadianClock::new();
+        let mut peaks = Vec::new();
+        let mut previous = (clock.repressor_nm, clock.repressor_nm);
+        for i in 0..(240.0 / DT) as usize {
+            clock.step(DT);
+            if previous.1 > previous.0 && previous.1 > clock.repressor_nm {
+                peaks.push(i as f64 * DT);
+            }
+            previous = (previous.1, clock.repressor_nm);
+        }
+        let periods: Vec<f64> = peaks.windows(2).map(|w| w[1] - w[0]).collect();
+        assert!(periods.len() >= 8);
+        assert!(
+            periods.iter().all(|p| (p - 24.18).abs() < 0.3),
+            "{:?}",
+            periods
+        );
+    }
+
+    #[test]
+    fn test_entrains_to_local_time_and_recovers_from_jet_lag() {
+        let mut clock = CircadianClock::new();
+        for _ in 0..10 {
+            live_day(&mut clock, 7.0);
+        }
+        assert!(hours_apart(clock.internal_hour(), 0.0) < 1.0);
+
+        // Fly eight time zones east: local midnight arrives 8 h early.
+        let mut lag = Vec::new();
+        for _ in 0..14 {
+            live_day(&mut clock, 7.0 - 8.0);
+            lag.push(hours_apart(clock.internal_hour(), 8.0));
+        }
+        assert!(lag[1] > 3.0, "{:?}", lag);
+        assert!(lag[13] < 2.0, "{:?}", lag);
+    }
+
+    #[test]
+    fn test_modulators_follow_internal_time() {
+        let clock = CircadianClock::new();
+        let at = |name: &str, hour: f64| {
+            let mut clock = clock.clone();
+            while hours_apart(clock.internal_hour(), hour) > 0.05 {
+                clock.step(DT);
+            }
+            clock.modulation(name).unwrap()
+        };
+        assert!(at("cortisol", 8.5) > 1.6);
+        assert!(at("cortisol", 21.0) < 0.6);
+        assert!(at("melatonin", 14.0) < 0.1);
+        assert!(at("bone_resorption", 5.0) > at("bone_resorption", 15.0) * 1.5);
+        assert!(at("circulating_lymphocytes", 1.0) > at("circulating_lymphocytes", 13.0));
+        assert!(clock.modulation("growth_hormone").is_none());
+
+        // Chronopharmacology: a morning dose is cleared faster.
+        let drug = Pharmacokinetics::new(1.0, 4.0, 50.0);
+        let morning = drug.with_clearance_ratio(at("hepatic_clearance", 8.0));
+        let evening = drug.with_clearance_ratio(at("hepatic_clearance", 20.0));
+        assert!(
+            morning.calculate_concentration(100.0, 6.0)
+                < 0.8 * evening.calculate_concentration(100.0, 6.0)
+        );
+    }
+
+    #[test]
+    fn test_registered_modulator_and_invalid_values() {
+        let mut clock = CircadianClock::new();
+        // Growth hormone pulses with slow-wave sleep early in the night.
+        clock.register_modulator(
+            "growth_hormone",
+            ParameterModulator::new(0.8, 23.0).unwrap(),
+        );
+        assert!(clock.modulator_names().any(|n| n == "growth_hormone"));
+        assert!(clock.modulation("growth_hormone").unwrap() > 1.5);
+        assert!(ParameterModulator::new(1.5, 8.0).is_err());
+        assert!(ParameterModulator::new(0.5, f64::NAN).is_err());
+        assert_eq!(
+            ParameterModulator::new(0.5, 27.0).unwrap().acrophase_hour,
+            3.0
+        );
+        assert!(clock.set_light(-1.0).is_err());
+    }
+}
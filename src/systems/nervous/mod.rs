@@ -3,21 +3,24 @@ pub mod blood_brain_barrier_neuroimmune;
 pub mod brain_connectivity;
 pub mod central;
 pub mod circadian;
+pub mod clock;
 pub mod neurotransmitter_pathways;
 pub mod pain_pathways;
 pub mod peripheral;
+pub mod sleep;
 
 pub use action_potential::{
-    ActionPotentialDynamics, HodgkinHuxleyModel, IonChannelPopulation, NeuronType,
-    NeurotransmitterType, SynapticTransmission,
+    ActionPotentialDynamics, ChannelKind, HodgkinHuxleyModel, IonChannelPopulation, NeuronBuilder,
+    NeuronType, NeurotransmitterType, SynapticTransmission,
 };
 pub use blood_brain_barrier_neuroimmune::{
-    BloodBrainBarrierNeuroimmune, BBBNeuroImmuneStatus, BBBStructuralIntegrity,
-    GlymphaticFunction, NeuroinflammationStatus,
+    BBBNeuroImmuneStatus, BBBStructuralIntegrity, BloodBrainBarrierNeuroimmune, GlymphaticFunction,
+    NeuroinflammationStatus,
 };
 pub use brain_connectivity::*;
 pub use central::{Brain, CentralNervousSystem, SpinalCord};
 pub use circadian::*;
+pub use clock::{CircadianClock, ParameterModulator};
 pub use neurotransmitter_pathways::{
     AcetylcholineSystem, DopaminePathway, DopamineSystem, EndogenousOpioidSystem, GABASystem,
     GlutamateSystem, Neurotransmitter, NeurotransmitterProfile, NorepinephrineSystem,
@@ -27,3 +30,4 @@ pub use pain_pathways::{ChronicPainRisk, PainProcessingSystem};
 pub use peripheral::{
     AutonomicNervousSystem, Parasympathetic, PeripheralNervousSystem, Sympathetic,
 };
+pub use sleep::SleepWakeRegulation;
@@ -223,7 +223,7 @@ impl PainProcessingSystem {
             + (self.pain_memory.pain_catastrophizing_score * 0.3)
             + (self.pain_memory.pain_anxiety_score * 0.2);
 
-        (final_intensity * emotional_modulation).max(0.0).min(10.0)
+        (final_intensity * emotional_modulation).clamp(0.0, 10.0)
     }
 
     pub fn assess_chronic_pain_risk(&self) -> ChronicPainRisk {
@@ -0,0 +1,273 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::engine::{load, save, unknown_input, Component};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::f64::consts::TAU;
+
+// Process S rises towards 1 while awake and decays while asleep; sleep
+// starts when it reaches an upper threshold and ends at a lower one, both
+// shifted by the circadian Process C.
+// Daan S, Beersma DG, Borbély AA (1984) Am J Physiol 246:R161-R183
+const WAKE_TIME_CONSTANT_H: f64 = 18.2;
+const SLEEP_TIME_CONSTANT_H: f64 = 4.2;
+const UPPER_THRESHOLD: f64 = 0.60;
+const LOWER_THRESHOLD: f64 = 0.17;
+const CIRCADIAN_AMPLITUDE: f64 = 0.12;
+// Thresholds peak in the late afternoon, placing sleep at about
+// 23:00-07:30 on the internal clock.
+const CIRCADIAN_ACROPHASE_HOUR: f64 = 17.0;
+// The largest growth-hormone pulse of the day follows sleep onset and
+// tracks slow-wave activity.
+// Van Cauter E, Plat L (1996) J Pediatr 128:S32-S37
+const GH_SLOW_WAVE_GAIN: f64 = 3.0;
+// One night without sleep lowers muscle protein synthesis by 18%, here
+// spread over the ~0.15 of Process S it adds above the bedtime level.
+// Lamon S et al. (2021) Physiol Rep 9:e14660
+const RECOVERY_LOSS_PER_EXCESS_PRESSURE: f64 = 1.2;
+
+// Borbély's two-process model of sleep timing. Slow-wave activity, and so
+// the signals gated on it, follows homeostatic pressure during sleep.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SleepWakeRegulation {
+    pub homeostatic_pressure: f64,
+    pub asleep: bool,
+    // Advances with the wall clock unless set from a `CircadianClock`.
+    pub internal_hour: f64,
+    pub forced_awake: bool,
+}
+
+impl Default for SleepWakeRegulation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SleepWakeRegulation {
+    // Waking at 07:30 after a normal night.
+    pub fn new() -> Self {
+        Self {
+            homeostatic_pressure: Self::lower_threshold(7.5),
+            asleep: false,
+            internal_hour: 7.5,
+            forced_awake: false,
+        }
+    }
+
+    fn process_c(hour: f64) -> f64 {
+        CIRCADIAN_AMPLITUDE * (TAU * (hour - CIRCADIAN_ACROPHASE_HOUR) / 24.0).cos()
+    }
+
+    pub fn upper_threshold(hour: f64) -> f64 {
+        UPPER_THRESHOLD + Self::process_c(hour)
+    }
+
+    pub fn lower_threshold(hour: f64) -> f64 {
+        LOWER_THRESHOLD + Self::process_c(hour)
+    }
+
+    pub fn set_internal_hour(&mut self, hour: f64) -> BiologyResult<()> {
+        check_internal_hour(hour)?;
+        self.internal_hour = hour.rem_euclid(24.0);
+        Ok(())
+    }
+
+    // Sleep deprivation: wakes the sleeper and holds them awake.
+    pub fn keep_awake(&mut self, forced: bool) {
+        self.forced_awake = forced;
+        if forced {
+            self.asleep = false;
+        }
+    }
+
+    // Relative delta power, 0 while awake.
+    pub fn slow_wave_activity(&self) -> f64 {
+        if self.asleep {
+            self.homeostatic_pressure / UPPER_THRESHOLD
+        } else {
+            0.0
+        }
+    }
+
+    // Pituitary GH secretion relative to the waking rate.
+    pub fn growth_hormone_factor(&self) -> f64 {
+        1.0 + GH_SLOW_WAVE_GAIN * self.slow_wave_activity()
+    }
+
+    // Drive for hippocampal replay, fed to `BrainNetwork::consolidate`.
+    pub fn consolidation_signal(&self) -> f64 {
+        self.slow_wave_activity()
+    }
+
+    // Tissue repair capacity; falls once pressure exceeds what a normal
+    // waking day builds up.
+    pub fn recovery_factor(&self) -> f64 {
+        let excess = (self.homeostatic_pressure - UPPER_THRESHOLD).max(0.0);
+        (1.0 - RECOVERY_LOSS_PER_EXCESS_PRESSURE * excess).max(0.0)
+    }
+
+    pub fn step(&mut self, dt_hours: f64) {
+        let hour = self.internal_hour;
+        if self.asleep {
+            self.homeostatic_pressure *= (-dt_hours / SLEEP_TIME_CONSTANT_H).exp();
+            if self.homeostatic_pressure <= Self::lower_threshold(hour) {
+                self.asleep = false;
+            }
+        } else {
+            self.homeostatic_pressure =
+                1.0 - (1.0 - self.homeostatic_pressure) * (-dt_hours / WAKE_TIME_CONSTANT_H).exp();
+            if self.homeostatic_pressure >= Self::upper_threshold(hour) && !self.forced_awake {
+                self.asleep = true;
+            }
+        }
+        self.internal_hour = (hour + dt_hours) % 24.0;
+    }
+}
+
+fn check_internal_hour(hour: f64) -> BiologyResult<()> {
+    if !hour.is_finite() {
+        return Err(BiologyError::InvalidValue(
+            "internal hour must be finite".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl Component for SleepWakeRegulation {
+    fn kind(&self) -> &'static str {
+        "sleep_wake"
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+        // Couple from a clock's `internal_hour` to follow its phase.
+        &[("internal_hour", "h"), ("keep_awake", "1")]
+    }
+
+    fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("asleep", "1"),
+            ("homeostatic_pressure", "1"),
+            ("growth_hormone", "1"),
+            ("consolidation", "1"),
+            ("recovery", "1"),
+        ]
+    }
+
+    fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "internal_hour" => self.set_internal_hour(value),
+            "keep_awake" => {
+                self.keep_awake(value > 0.5);
+                Ok(())
+            }
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn check_input(&self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "internal_hour" => check_internal_hour(value),
+            "keep_awake" => Ok(()),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn output(&self, name: &str) -> Option<f64> {
+        match name {
+            "asleep" => Some(if self.asleep { 1.0 } else { 0.0 }),
+            "homeostatic_pressure" => Some(self.homeostatic_pressure),
+            "growth_hormone" => Some(self.growth_hormone_factor()),
+            "consolidation" => Some(self.consolidation_signal()),
+            "recovery" => Some(self.recovery_factor()),
+            _ => None,
+        }
+    }
+
+    fn step(&mut self, dt_minutes: f64) {
+        SleepWakeRegulation::step(self, dt_minutes / 60.0);
+    }
+
+    fn save_state(&self) -> Value {
+        save(self)
+    }
+
+    fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+        load(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f64 = 0.05;
+
+    // (onset, offset) hours of each sleep episode.
+    fn episodes(sleep: &mut SleepWakeRegulation, hours: f64) -> Vec<(f64, f64)> {
+        let mut episodes = Vec::new();
+        let mut onset = None;
+        for _ in 0..(hours / DT) as usize {
+            let was_asleep = sleep.asleep;
+            sleep.step(DT);
+            match (was_asleep, sleep.asleep) {
+                (false, true) => onset = Some(sleep.internal_hour),
+                (true, false) => {
+                    if let Some(start) = onset.take() {
+                        episodes.push((start, sleep.internal_hour));
+                    }
+                }
+                _ => {}
+            }
+        }
+        episodes
+    }
+
+    #[test]
+    fn test_consolidated_night_sleep() {
+        let mut sleep = SleepWakeRegulation::new();
+        let nights = episodes(&mut sleep, 24.0 * 7.0 + 2.0);
+        assert_eq!(nights.len(), 7);
+        for (onset, offset) in nights {
+            assert!((onset - 23.0).abs() < 0.5, "onset {}", onset);
+            assert!((offset - 7.5).abs() < 0.5, "offset {}", offset);
+        }
+    }
+
+    #[test]
+    fn test_deprivation_raises_pressure_and_impairs_recovery() {
+        let mut sleep = SleepWakeRegulation::new();
+        assert_eq!(sleep.recovery_factor(), 1.0);
+        sleep.keep_awake(true);
+        for _ in 0..(24.0 / DT) as usize {
+            sleep.step(DT);
+        }
+        assert!(!sleep.asleep);
+        assert!(sleep.homeostatic_pressure > 0.7);
+        assert!((sleep.recovery_factor() - 0.82).abs() < 0.03);
+
+        // Recovery sleep starts at once and runs deeper.
+        sleep.keep_awake(false);
+        sleep.step(DT);
+        assert!(sleep.asleep);
+        assert!(sleep.slow_wave_activity() > 1.2);
+        assert!(sleep.growth_hormone_factor() > 4.0);
+        assert!(sleep.set_internal_hour(f64::INFINITY).is_err());
+    }
+
+    #[test]
+    fn test_signals_gated_on_sleep() {
+        let mut sleep = SleepWakeRegulation::new();
+        assert_eq!(sleep.consolidation_signal(), 0.0);
+        assert_eq!(sleep.growth_hormone_factor(), 1.0);
+        sleep.set_internal_hour(23.5).unwrap();
+        sleep.homeostatic_pressure = UPPER_THRESHOLD;
+        sleep.step(DT);
+        assert!(sleep.asleep);
+        let early = sleep.consolidation_signal();
+        for _ in 0..(5.0 / DT) as usize {
+            sleep.step(DT);
+        }
+        // Slow-wave activity dissipates across the night.
+        assert!(sleep.consolidation_signal() < 0.5 * early);
+        assert!(sleep.growth_hormone_factor() > 1.0);
+    }
+}
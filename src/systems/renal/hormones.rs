@@ -1,5 +1,18 @@
+use crate::biology::physiology::properties::{ChemicalProperty, PropertyConsumer};
+use crate::biology::signaling::hypoxia::Hif1a;
 use serde::{Deserialize, Serialize};
 
+// Cortical interstitial PO2 runs at ~40% of arterial, so ~95 mmHg arterial
+// leaves EPO-producing fibroblasts near normoxic tissue tension.
+const RENAL_TISSUE_FRACTION_OF_ARTERIAL_PO2: f64 = 0.42;
+// Anaemia lowers oxygen content rather than tension; serum EPO rises
+// log-linearly as haemoglobin falls, ~10-fold by 10 g/dL, and is
+// suppressed in secondary polycythaemia.
+// Jelkmann W (2011) J Physiol 589:1251-1258
+const REFERENCE_HEMOGLOBIN_G_DL: f64 = 15.0;
+const EPO_LOG_SLOPE_PER_G_DL: f64 = 0.45;
+const EPO_MAX_FOLD: f64 = 1000.0;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RenalHormones {
     pub renin: Renin,
@@ -104,9 +117,10 @@ impl RenalHormones {
     }
 
     pub fn respond_to_hypoxia(&mut self, po2_mmhg: f64) {
-        if po2_mmhg < 60.0 {
-            self.erythropoietin.increase_production();
-        }
+        self.erythropoietin
+            .consume(ChemicalProperty::OxygenTension {
+                mmhg: po2_mmhg * RENAL_TISSUE_FRACTION_OF_ARTERIAL_PO2,
+            });
     }
 
     pub fn respond_to_hypocalcemia(&mut self) {
@@ -162,6 +176,30 @@ impl Erythropoietin {
         self.plasma_concentration_u_l *= 5.0;
     }
 
+    // EPO set by HIF-1α in peritubular fibroblasts at the given renal
+    // tissue tension, scaled from the normoxic output.
+    pub fn respond_to_oxygen(&mut self, renal_tissue_po2_mmhg: f64) {
+        let normal = Self::new_normal();
+        let fold = Hif1a::at_po2(renal_tissue_po2_mmhg).epo_fold();
+        self.production_rate_u_day = normal.production_rate_u_day * fold;
+        self.plasma_concentration_u_l = normal.plasma_concentration_u_l * fold;
+    }
+
+    // Combined hypoxaemic (via renal HIF) and anaemic drive.
+    pub fn respond_to_oxygen_delivery(&mut self, arterial_po2_mmhg: f64, hemoglobin_g_dl: f64) {
+        if !self.oxygen_sensing_active {
+            return;
+        }
+        let normal = Self::new_normal();
+        let hypoxaemia =
+            Hif1a::at_po2(arterial_po2_mmhg * RENAL_TISSUE_FRACTION_OF_ARTERIAL_PO2).epo_fold();
+        let anaemia =
+            (EPO_LOG_SLOPE_PER_G_DL * (REFERENCE_HEMOGLOBIN_G_DL - hemoglobin_g_dl)).exp();
+        let fold = (hypoxaemia * anaemia).min(EPO_MAX_FOLD);
+        self.production_rate_u_day = normal.production_rate_u_day * fold;
+        self.plasma_concentration_u_l = normal.plasma_concentration_u_l * fold;
+    }
+
     pub fn production_stimulus(hemoglobin_g_dl: f64, po2_mmhg: f64) -> f64 {
         let hb_factor = if hemoglobin_g_dl < 12.0 {
             (12.0 - hemoglobin_g_dl) * 2.0
@@ -187,6 +225,16 @@ impl Erythropoietin {
     }
 }
 
+impl PropertyConsumer for Erythropoietin {
+    fn consume(&mut self, property: ChemicalProperty) {
+        if let ChemicalProperty::OxygenTension { mmhg } = property {
+            if self.oxygen_sensing_active {
+                self.respond_to_oxygen(mmhg);
+            }
+        }
+    }
+}
+
 impl Calcitriol {
     pub fn new_normal() -> Self {
         Self {
@@ -478,6 +526,39 @@ mod tests {
         assert!(stimulus > 0.0);
     }
 
+    #[test]
+    fn test_epo_follows_renal_hif() {
+        let mut hormones = RenalHormones::new_normal();
+        hormones.respond_to_hypoxia(95.0);
+        let normoxic = hormones.erythropoietin.plasma_concentration_u_l;
+        assert!((normoxic / 10.0 - 1.0).abs() < 0.1);
+        hormones.respond_to_hypoxia(45.0);
+        let hypoxaemic = hormones.erythropoietin.plasma_concentration_u_l;
+        assert!(hypoxaemic > 3.0 * normoxic);
+        // Repeating the same tension does not compound.
+        hormones.respond_to_hypoxia(45.0);
+        assert_eq!(hormones.erythropoietin.plasma_concentration_u_l, hypoxaemic);
+
+        let mut failing = Erythropoietin::new_normal();
+        failing.oxygen_sensing_active = false;
+        failing.consume(ChemicalProperty::OxygenTension { mmhg: 5.0 });
+        assert_eq!(failing.plasma_concentration_u_l, 10.0);
+    }
+
+    #[test]
+    fn test_epo_rises_with_anaemia() {
+        let mut epo = Erythropoietin::new_normal();
+        epo.respond_to_oxygen_delivery(95.0, 15.0);
+        let normal = epo.plasma_concentration_u_l;
+        epo.respond_to_oxygen_delivery(95.0, 10.0);
+        let anaemic = epo.plasma_concentration_u_l;
+        assert!(anaemic > 8.0 * normal && anaemic < 12.0 * normal);
+        epo.respond_to_oxygen_delivery(95.0, 18.0);
+        assert!(epo.plasma_concentration_u_l < 0.5 * normal);
+        epo.respond_to_oxygen_delivery(45.0, 10.0);
+        assert!(epo.plasma_concentration_u_l > 3.0 * anaemic);
+    }
+
     #[test]
     fn test_calcitriol() {
         let calcitriol = Calcitriol::new_normal();
@@ -0,0 +1,381 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::engine::{load, save, substep, unknown_input, Component};
+use crate::systems::respiratory::gas_exchange::BloodGas;
+use crate::systems::respiratory::oxygen_transport::Hemoglobin;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Alveolar gas equations:
+// PACO2 = PICO2 + 0.863·VCO2/VA
+// PAO2  = FIO2·(PB − PH2O) − PACO2/R
+// West JB, Respiratory Physiology: The Essentials 10e, Ch 5
+pub const WATER_VAPOR_PRESSURE_MMHG: f64 = 47.0;
+pub const SEA_LEVEL_PRESSURE_MMHG: f64 = 760.0;
+const ALVEOLAR_VENTILATION_CONSTANT: f64 = 0.863;
+
+// Henderson-Hasselbalch for the CO2/HCO3⁻ buffer, pK' 6.1, CO2 solubility
+// 0.03 mmol/L/mmHg; acute non-bicarbonate buffering raises HCO3⁻ ~0.1 mEq/L
+// per mmHg PaCO2.
+// Brackett NC et al. (1965) N Engl J Med 272:6-12, PMID 14222250
+const CARBONIC_PK: f64 = 6.1;
+const CO2_SOLUBILITY_MMOL_PER_MMHG: f64 = 0.03;
+const ACUTE_BICARBONATE_SLOPE: f64 = 0.1;
+const REFERENCE_BICARBONATE_MEQ_L: f64 = 24.0;
+const REFERENCE_PACO2_MMHG: f64 = 40.0;
+
+// Hypercapnic response ~2 L/min/mmHg above an apnoeic threshold, scaled
+// hyperbolically by hypoxia: ΔV̇E ∝ 1 + A/(PaO2 − 32).
+// Read DJ (1967) Australas Ann Med 16:20-32, PMID 6032026
+// Weil JV et al. (1970) J Clin Invest 49:1061-1072, PMID 5422012
+const HYPOXIC_ASYMPTOTE_MMHG: f64 = 32.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChemoreflexParameters {
+    pub co2_gain_l_min_per_mmhg: f64,
+    pub co2_threshold_mmhg: f64,
+    pub hypoxic_shape_mmhg: f64,
+    pub central_time_constant_min: f64,
+    // Effective capacitance of the rapidly exchanging CO2 stores.
+    // Farhi LE, Rahn H (1960) Anesthesiology 21:604-614
+    pub co2_store_ml_per_mmhg: f64,
+}
+
+impl ChemoreflexParameters {
+    pub fn healthy_adult() -> Self {
+        Self {
+            co2_gain_l_min_per_mmhg: 2.0,
+            co2_threshold_mmhg: 37.0,
+            hypoxic_shape_mmhg: 40.0,
+            central_time_constant_min: 1.5,
+            co2_store_ml_per_mmhg: 50.0,
+        }
+    }
+}
+
+impl Default for ChemoreflexParameters {
+    fn default() -> Self {
+        Self::healthy_adult()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RespiratoryControl {
+    pub chemoreflex: ChemoreflexParameters,
+    pub hemoglobin: Hemoglobin,
+    pub barometric_pressure_mmhg: f64,
+    pub inspired_o2_fraction: f64,
+    pub inspired_co2_fraction: f64,
+    pub co2_production_ml_min: f64,
+    pub respiratory_quotient: f64,
+    pub dead_space_fraction: f64,
+    pub alveolar_arterial_gradient_mmhg: f64,
+    pub base_excess_meq_l: f64,
+    pub arterial_pco2_mmhg: f64,
+    pub central_pco2_mmhg: f64,
+    pub minute_ventilation_l_min: f64,
+}
+
+impl RespiratoryControl {
+    // VCO2 200 mL/min, RQ 0.8, VD/VT 0.3 and an A-a gradient of ~8 mmHg.
+    pub fn new_healthy() -> Self {
+        let mut control = Self {
+            chemoreflex: ChemoreflexParameters::healthy_adult(),
+            hemoglobin: Hemoglobin::new_normal(),
+            barometric_pressure_mmhg: SEA_LEVEL_PRESSURE_MMHG,
+            inspired_o2_fraction: 0.21,
+            inspired_co2_fraction: 0.0,
+            co2_production_ml_min: 200.0,
+            respiratory_quotient: 0.8,
+            dead_space_fraction: 0.3,
+            alveolar_arterial_gradient_mmhg: 8.0,
+            base_excess_meq_l: 0.0,
+            arterial_pco2_mmhg: REFERENCE_PACO2_MMHG,
+            central_pco2_mmhg: REFERENCE_PACO2_MMHG,
+            minute_ventilation_l_min: 0.0,
+        };
+        control.minute_ventilation_l_min = control.chemoreflex_drive();
+        control
+    }
+
+    pub fn set_barometric_pressure(&mut self, mmhg: f64) -> BiologyResult<()> {
+        check_barometric_pressure(mmhg)?;
+        self.barometric_pressure_mmhg = mmhg;
+        Ok(())
+    }
+
+    pub fn set_inspired_gas(&mut self, o2_fraction: f64, co2_fraction: f64) -> BiologyResult<()> {
+        check_inspired_gas(o2_fraction, co2_fraction)?;
+        self.inspired_o2_fraction = o2_fraction;
+        self.inspired_co2_fraction = co2_fraction;
+        Ok(())
+    }
+
+    fn dry_inspired_pressure(&self) -> f64 {
+        self.barometric_pressure_mmhg - WATER_VAPOR_PRESSURE_MMHG
+    }
+
+    pub fn alveolar_ventilation_l_min(&self) -> f64 {
+        self.minute_ventilation_l_min * (1.0 - self.dead_space_fraction)
+    }
+
+    pub fn alveolar_po2_mmhg(&self) -> f64 {
+        (self.inspired_o2_fraction * self.dry_inspired_pressure()
+            - self.arterial_pco2_mmhg / self.respiratory_quotient)
+            .max(0.0)
+    }
+
+    pub fn arterial_po2_mmhg(&self) -> f64 {
+        (self.alveolar_po2_mmhg() - self.alveolar_arterial_gradient_mmhg).max(1.0)
+    }
+
+    pub fn bicarbonate_meq_l(&self) -> f64 {
+        REFERENCE_BICARBONATE_MEQ_L
+            + self.base_excess_meq_l
+            + ACUTE_BICARBONATE_SLOPE * (self.arterial_pco2_mmhg - REFERENCE_PACO2_MMHG)
+    }
+
+    pub fn arterial_ph(&self) -> f64 {
+        CARBONIC_PK
+            + (self.bicarbonate_meq_l().max(1e-6)
+                / (CO2_SOLUBILITY_MMOL_PER_MMHG * self.arterial_pco2_mmhg.max(1e-6)))
+            .log10()
+    }
+
+    // Hill saturation with the p50 shifted by pH and PCO2 (Bohr effect).
+    pub fn arterial_saturation_percent(&self) -> f64 {
+        let mut hb = self.hemoglobin.clone();
+        hb.p50_mmhg =
+            hb.adjust_p50_for_conditions(37.0, self.arterial_ph(), self.arterial_pco2_mmhg, 5.0);
+        hb.calculate_saturation(self.arterial_po2_mmhg())
+    }
+
+    pub fn arterial_o2_content_ml_dl(&self) -> f64 {
+        self.hemoglobin.oxygen_binding_capacity_ml_dl() * self.arterial_saturation_percent() / 100.0
+            + 0.003 * self.arterial_po2_mmhg()
+    }
+
+    // Total CO2 carried in plasma: bicarbonate plus dissolved CO2.
+    pub fn total_co2_mmol_l(&self) -> f64 {
+        self.bicarbonate_meq_l() + CO2_SOLUBILITY_MMOL_PER_MMHG * self.arterial_pco2_mmhg
+    }
+
+    fn chemoreflex_drive(&self) -> f64 {
+        let c = self.chemoreflex;
+        let hypoxia = (1.0
+            + c.hypoxic_shape_mmhg / (self.arterial_po2_mmhg() - HYPOXIC_ASYMPTOTE_MMHG).max(1.0))
+            / (1.0 + c.hypoxic_shape_mmhg / (100.0 - HYPOXIC_ASYMPTOTE_MMHG));
+        (c.co2_gain_l_min_per_mmhg * (self.central_pco2_mmhg - c.co2_threshold_mmhg) * hypoxia)
+            .max(0.5)
+    }
+
+    pub fn step(&mut self, dt_min: f64) {
+        let c = self.chemoreflex;
+        self.minute_ventilation_l_min = self.chemoreflex_drive();
+
+        let inspired_pco2 = self.inspired_co2_fraction * self.dry_inspired_pressure();
+        let alveolar_ml_min = self.alveolar_ventilation_l_min() * 1000.0;
+        let eliminated = alveolar_ml_min * (self.arterial_pco2_mmhg - inspired_pco2)
+            / (ALVEOLAR_VENTILATION_CONSTANT * 1000.0);
+        self.arterial_pco2_mmhg +=
+            (self.co2_production_ml_min - eliminated) / c.co2_store_ml_per_mmhg * dt_min;
+        self.arterial_pco2_mmhg = self.arterial_pco2_mmhg.max(inspired_pco2).max(1.0);
+
+        let lag = (dt_min / c.central_time_constant_min).min(1.0);
+        self.central_pco2_mmhg += (self.arterial_pco2_mmhg - self.central_pco2_mmhg) * lag;
+    }
+
+    pub fn run_minutes(&mut self, minutes: f64) {
+        let steps = (minutes / 0.05).round() as usize;
+        for _ in 0..steps {
+            self.step(0.05);
+        }
+    }
+
+    pub fn arterial_blood_gas(&self) -> BloodGas {
+        BloodGas {
+            ph: self.arterial_ph(),
+            po2_mmhg: self.arterial_po2_mmhg(),
+            pco2_mmhg: self.arterial_pco2_mmhg,
+            hco3_meq_l: self.bicarbonate_meq_l(),
+            sao2_percent: self.arterial_saturation_percent(),
+        }
+    }
+}
+
+impl Default for RespiratoryControl {
+    fn default() -> Self {
+        Self::new_healthy()
+    }
+}
+
+fn check_barometric_pressure(mmhg: f64) -> BiologyResult<()> {
+    if mmhg <= WATER_VAPOR_PRESSURE_MMHG {
+        return Err(BiologyError::InvalidParameter(
+            "barometric pressure must exceed water vapour pressure".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_inspired_gas(o2_fraction: f64, co2_fraction: f64) -> BiologyResult<()> {
+    if !(0.0..=1.0).contains(&o2_fraction)
+        || !(0.0..=1.0).contains(&co2_fraction)
+        || o2_fraction + co2_fraction > 1.0
+    {
+        return Err(BiologyError::InvalidParameter(
+            "inspired gas fractions must lie in [0, 1] and sum to at most 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_positive(value: f64, what: &str) -> BiologyResult<()> {
+    if value > 0.0 {
+        Ok(())
+    } else {
+        Err(BiologyError::InvalidParameter(format!(
+            "{} must be positive",
+            what
+        )))
+    }
+}
+
+impl Component for RespiratoryControl {
+    fn kind(&self) -> &'static str {
+        "respiratory_control"
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("inspired_o2_fraction", "1"),
+            ("inspired_co2_fraction", "1"),
+            ("barometric_pressure_mmhg", "mmHg"),
+            ("co2_production_ml_min", "mL/min"),
+            ("hemoglobin_g_dl", "g/dL"),
+        ]
+    }
+
+    fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("minute_ventilation_l_min", "L/min"),
+            ("arterial_pco2_mmhg", "mmHg"),
+            ("arterial_po2_mmhg", "mmHg"),
+            ("arterial_saturation_percent", "%"),
+            ("arterial_ph", "1"),
+            ("arterial_o2_content_ml_dl", "mL/dL"),
+        ]
+    }
+
+    fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "inspired_o2_fraction" => self.set_inspired_gas(value, self.inspired_co2_fraction),
+            "inspired_co2_fraction" => self.set_inspired_gas(self.inspired_o2_fraction, value),
+            "barometric_pressure_mmhg" => self.set_barometric_pressure(value),
+            "co2_production_ml_min" => {
+                check_positive(value, "CO2 production")?;
+                self.co2_production_ml_min = value;
+                Ok(())
+            }
+            "hemoglobin_g_dl" => {
+                check_positive(value, "hemoglobin")?;
+                self.hemoglobin.concentration_g_dl = value;
+                Ok(())
+            }
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn check_input(&self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "inspired_o2_fraction" => check_inspired_gas(value, self.inspired_co2_fraction),
+            "inspired_co2_fraction" => check_inspired_gas(self.inspired_o2_fraction, value),
+            "barometric_pressure_mmhg" => check_barometric_pressure(value),
+            "co2_production_ml_min" => check_positive(value, "CO2 production"),
+            "hemoglobin_g_dl" => check_positive(value, "hemoglobin"),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn output(&self, name: &str) -> Option<f64> {
+        match name {
+            "minute_ventilation_l_min" => Some(self.minute_ventilation_l_min),
+            "arterial_pco2_mmhg" => Some(self.arterial_pco2_mmhg),
+            "arterial_po2_mmhg" => Some(self.arterial_po2_mmhg()),
+            "arterial_saturation_percent" => Some(self.arterial_saturation_percent()),
+            "arterial_ph" => Some(self.arterial_ph()),
+            "arterial_o2_content_ml_dl" => Some(self.arterial_o2_content_ml_dl()),
+            _ => None,
+        }
+    }
+
+    fn step(&mut self, dt_minutes: f64) {
+        // The explicit CO2 store update is stable below ~0.1 min.
+        substep(dt_minutes, 0.05, |h| RespiratoryControl::step(self, h));
+    }
+
+    fn save_state(&self) -> Value {
+        save(self)
+    }
+
+    fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+        load(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::validation::ground_truth::GroundTruthDatabase;
+
+    #[test]
+    fn test_room_air_blood_gas_is_normal() {
+        let db = GroundTruthDatabase::new();
+        let resp = db.get_dataset("respiratory").unwrap();
+        let mut control = RespiratoryControl::new_healthy();
+        control.run_minutes(30.0);
+        let abg = control.arterial_blood_gas();
+        assert!(resp.is_within_expected_range("pao2_mmhg", abg.po2_mmhg));
+        assert!(resp.is_within_expected_range("paco2_mmhg", abg.pco2_mmhg));
+        assert!(resp.is_within_expected_range("sao2_percent", abg.sao2_percent));
+        assert!(resp.is_within_expected_range("arterial_ph", abg.ph));
+        assert!(control.minute_ventilation_l_min > 5.0 && control.minute_ventilation_l_min < 8.0);
+    }
+
+    #[test]
+    fn test_inspired_co2_drives_hyperpnoea() {
+        let mut control = RespiratoryControl::new_healthy();
+        let resting = control.minute_ventilation_l_min;
+        control.set_inspired_gas(0.21, 0.05).unwrap();
+        control.run_minutes(30.0);
+        assert!(control.minute_ventilation_l_min > 2.5 * resting);
+        assert!(control.arterial_pco2_mmhg > 44.0);
+        assert!(control.arterial_ph() < 7.37);
+        assert!(control.set_inspired_gas(0.9, 0.2).is_err());
+    }
+
+    #[test]
+    fn test_altitude_hypoxia_triggers_hyperventilation() {
+        let mut control = RespiratoryControl::new_healthy();
+        control.run_minutes(30.0);
+        let sea_level = control.clone();
+        // ~3000 m
+        control.set_barometric_pressure(523.0).unwrap();
+        control.run_minutes(30.0);
+        assert!(control.arterial_po2_mmhg() < 60.0);
+        assert!(control.arterial_saturation_percent() < 90.0);
+        assert!(control.arterial_pco2_mmhg < sea_level.arterial_pco2_mmhg - 1.0);
+        assert!(control.arterial_ph() > sea_level.arterial_ph());
+        assert!(control.set_barometric_pressure(40.0).is_err());
+    }
+
+    #[test]
+    fn test_bohr_shift_lowers_saturation_in_acidosis() {
+        let mut control = RespiratoryControl::new_healthy();
+        let normal = control.arterial_saturation_percent();
+        control.base_excess_meq_l = -10.0;
+        assert!(control.arterial_ph() < 7.25);
+        assert!(control.arterial_saturation_percent() < normal);
+        assert!(control.total_co2_mmol_l() < 20.0);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod breathing;
+pub mod chemoreflex;
 pub mod gas_exchange;
 pub mod lung;
 pub mod oxygen_transport;
@@ -7,6 +8,7 @@ pub mod respiratory_mechanics;
 pub mod ventilation;
 
 pub use breathing::{BreathPhase, BreathingMechanics, BreathingPattern, RespiratoryMuscles};
+pub use chemoreflex::{ChemoreflexParameters, RespiratoryControl};
 pub use gas_exchange::{BloodGas, DiffusionParameters, GasExchange};
 pub use lung::{Alveolus, Lobe, Lung, LungSide};
 pub use oxygen_transport::{
@@ -0,0 +1,630 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::skeletal::hydroxyapatite::{
+    langmuir_occupancy, HydroxyapatiteCrystal, HYDROXYAPATITE_DENSITY_G_PER_CM3,
+};
+use serde::{Deserialize, Serialize};
+
+// Dense sintered HA: E ≈ 110 GPa, modulus of rupture ≈ 115 MPa.
+// Hench LL (1991) J Am Ceram Soc 74:1487-1510
+const DENSE_HA_MODULUS_GPA: f64 = 110.0;
+const DENSE_HA_FLEXURAL_STRENGTH_MPA: f64 = 115.0;
+
+// Open-cell foams: E*/Es = (ρ*/ρs)², brittle crushing
+// σ*/σfs = 0.2·(ρ*/ρs)^1.5.
+// Gibson LJ, Ashby MF (1997) Cellular Solids, 2nd ed., Cambridge UP
+const BRITTLE_CRUSH_COEFFICIENT: f64 = 0.2;
+
+// Pores above ~100 µm admit cells; >300 µm favour vascularised new bone.
+// Karageorgiou V, Kaplan D (2005) Biomaterials 26:5474-5491
+pub const MIN_CELL_INFILTRATION_PORE_UM: f64 = 100.0;
+pub const OSTEOGENIC_PORE_SIZE_UM: f64 = 300.0;
+
+// Tetrakaidecahedral open-cell surface area per unit scaffold volume,
+// SA/V ≈ 10.16/d·(ρ*/ρs)^0.5.
+// O'Brien FJ et al. (2005) Biomaterials 26:433-441
+const CELL_SURFACE_COEFFICIENT: f64 = 10.16;
+
+// Log-mismatch below which a scaffold counts as modulus matched.
+const MODULUS_MATCH_TOLERANCE: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScaffoldRequirements {
+    pub min_porosity: f64,
+    pub max_porosity: f64,
+    pub min_pore_size_um: f64,
+    pub max_pore_size_um: f64,
+    pub target_modulus_gpa: f64,
+    pub min_compressive_strength_mpa: f64,
+}
+
+impl ScaffoldRequirements {
+    pub fn new(
+        porosity: (f64, f64),
+        pore_size_um: (f64, f64),
+        target_modulus_gpa: f64,
+        min_compressive_strength_mpa: f64,
+    ) -> BiologyResult<Self> {
+        let (min_porosity, max_porosity) = porosity;
+        let (min_pore_size_um, max_pore_size_um) = pore_size_um;
+        if min_porosity.is_nan() || min_porosity < 0.0 || max_porosity >= 1.0 {
+            return Err(BiologyError::InvalidValue(
+                "porosity bounds must lie in [0, 1)".to_string(),
+            ));
+        }
+        if max_porosity.is_nan() || max_porosity < min_porosity {
+            return Err(BiologyError::InvalidValue(
+                "max porosity must not be below min porosity".to_string(),
+            ));
+        }
+        if min_pore_size_um.is_nan()
+            || min_pore_size_um <= 0.0
+            || max_pore_size_um.is_nan()
+            || max_pore_size_um < min_pore_size_um
+        {
+            return Err(BiologyError::InvalidValue(
+                "pore size bounds must be positive and ordered".to_string(),
+            ));
+        }
+        if target_modulus_gpa.is_nan() || target_modulus_gpa <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "target modulus must be positive".to_string(),
+            ));
+        }
+        if min_compressive_strength_mpa.is_nan() || min_compressive_strength_mpa < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "minimum compressive strength must be non-negative".to_string(),
+            ));
+        }
+        Ok(Self {
+            min_porosity,
+            max_porosity,
+            min_pore_size_um,
+            max_pore_size_um,
+            target_modulus_gpa,
+            min_compressive_strength_mpa,
+        })
+    }
+
+    // Human cancellous bone: E ≈ 0.1-0.5 GPa, crush strength 2-12 MPa.
+    // Gibson LJ (1985) J Biomech 18:317-328
+    pub fn cancellous_bone() -> Self {
+        Self {
+            min_porosity: 0.5,
+            max_porosity: 0.9,
+            min_pore_size_um: OSTEOGENIC_PORE_SIZE_UM,
+            max_pore_size_um: 800.0,
+            target_modulus_gpa: 0.3,
+            min_compressive_strength_mpa: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScaffoldDesign {
+    pub porosity: f64,
+    pub pore_size_um: f64,
+}
+
+impl ScaffoldDesign {
+    pub fn new(porosity: f64, pore_size_um: f64) -> BiologyResult<Self> {
+        if porosity.is_nan() || !(0.0..1.0).contains(&porosity) {
+            return Err(BiologyError::InvalidValue(
+                "porosity must lie in [0, 1)".to_string(),
+            ));
+        }
+        if pore_size_um.is_nan() || pore_size_um <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "pore size must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            porosity,
+            pore_size_um,
+        })
+    }
+
+    // Stiffness falls faster with porosity than strength, so the most
+    // porous design that still carries the required load is chosen, then
+    // stiffened only as far as the modulus target asks. The smallest
+    // osteogenic pore maximises attachment surface.
+    pub fn optimize(requirements: &ScaffoldRequirements) -> BiologyResult<Self> {
+        let r = requirements;
+        let porosity_for_modulus = 1.0
+            - (r.target_modulus_gpa / DENSE_HA_MODULUS_GPA)
+                .min(1.0)
+                .sqrt();
+        let porosity_for_strength = 1.0
+            - (r.min_compressive_strength_mpa
+                / (BRITTLE_CRUSH_COEFFICIENT * DENSE_HA_FLEXURAL_STRENGTH_MPA))
+                .powf(2.0 / 3.0);
+        let porosity = porosity_for_modulus
+            .min(porosity_for_strength)
+            .min(r.max_porosity);
+        if porosity < r.min_porosity {
+            return Err(BiologyError::InvalidParameter(format!(
+                "an HA scaffold reaching {:.1} MPa needs porosity ≤ {:.2}, below the {:.2} minimum",
+                r.min_compressive_strength_mpa, porosity_for_strength, r.min_porosity
+            )));
+        }
+        let pore_size_um = r
+            .min_pore_size_um
+            .max(OSTEOGENIC_PORE_SIZE_UM)
+            .min(r.max_pore_size_um);
+        Self::new(porosity, pore_size_um)
+    }
+
+    pub fn relative_density(&self) -> f64 {
+        1.0 - self.porosity
+    }
+
+    pub fn elastic_modulus_gpa(&self) -> f64 {
+        DENSE_HA_MODULUS_GPA * self.relative_density().powi(2)
+    }
+
+    pub fn compressive_strength_mpa(&self) -> f64 {
+        BRITTLE_CRUSH_COEFFICIENT
+            * DENSE_HA_FLEXURAL_STRENGTH_MPA
+            * self.relative_density().powf(1.5)
+    }
+
+    pub fn cell_surface_area_per_mm(&self) -> f64 {
+        CELL_SURFACE_COEFFICIENT / (self.pore_size_um * 1.0e-3) * self.relative_density().sqrt()
+    }
+
+    // Capillary-bundle Darcy permeability, k = ε·d²/32.
+    pub fn permeability_m2(&self) -> f64 {
+        let d = self.pore_size_um * 1.0e-6;
+        self.porosity * d * d / 32.0
+    }
+
+    pub fn mineral_mass_g(&self, scaffold_volume_cm3: f64) -> f64 {
+        scaffold_volume_cm3.max(0.0) * self.relative_density() * HYDROXYAPATITE_DENSITY_G_PER_CM3
+    }
+
+    // ln(E/E_target): positive when stiffer than the target.
+    pub fn modulus_mismatch(&self, requirements: &ScaffoldRequirements) -> f64 {
+        (self.elastic_modulus_gpa() / requirements.target_modulus_gpa).ln()
+    }
+
+    pub fn satisfies(&self, requirements: &ScaffoldRequirements) -> bool {
+        let r = requirements;
+        (r.min_porosity..=r.max_porosity).contains(&self.porosity)
+            && (r.min_pore_size_um..=r.max_pore_size_um).contains(&self.pore_size_um)
+            && self.pore_size_um >= MIN_CELL_INFILTRATION_PORE_UM
+            && self.compressive_strength_mpa() >= r.min_compressive_strength_mpa
+    }
+
+    pub fn is_modulus_matched(&self, requirements: &ScaffoldRequirements) -> bool {
+        self.modulus_mismatch(requirements).abs() < MODULUS_MATCH_TOLERANCE
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DrugSorption {
+    pub affinity_per_molar: f64,
+    pub site_density_umol_per_m2: f64,
+    pub desorption_rate_per_hour: f64,
+}
+
+impl DrugSorption {
+    pub fn new(
+        affinity_per_molar: f64,
+        site_density_umol_per_m2: f64,
+        desorption_rate_per_hour: f64,
+    ) -> BiologyResult<Self> {
+        for (name, value) in [
+            ("affinity", affinity_per_molar),
+            ("site density", site_density_umol_per_m2),
+            ("desorption rate", desorption_rate_per_hour),
+        ] {
+            if value.is_nan() || value <= 0.0 {
+                return Err(BiologyError::InvalidValue(format!(
+                    "{} must be positive",
+                    name
+                )));
+            }
+        }
+        Ok(Self {
+            affinity_per_molar,
+            site_density_umol_per_m2,
+            desorption_rate_per_hour,
+        })
+    }
+
+    // Nancollas GH et al. (2006) Bone 38:617-627, PMID 16046206
+    pub fn alendronate() -> Self {
+        Self {
+            affinity_per_molar: 2.94e6,
+            site_density_umol_per_m2: 1.5,
+            desorption_rate_per_hour: 1.0e-3,
+        }
+    }
+
+    // Aminoglycosides adsorb weakly and release mostly as an early burst.
+    // Stigter M et al. (2004) J Control Release 99:127-137
+    pub fn gentamicin() -> Self {
+        Self {
+            affinity_per_molar: 1.0e3,
+            site_density_umol_per_m2: 0.5,
+            desorption_rate_per_hour: 0.5,
+        }
+    }
+
+    fn adsorption_rate_per_molar_hour(&self) -> f64 {
+        self.affinity_per_molar * self.desorption_rate_per_hour
+    }
+}
+
+// Langmuir kinetics dθ/dt = k_on·C·(1 − θ) − k_off·θ between the mineral
+// surface and a well-mixed release medium.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DrugLoadedScaffold {
+    pub sorption: DrugSorption,
+    pub sites_umol: f64,
+    pub bound_umol: f64,
+    pub medium_umol: f64,
+    pub cumulative_released_umol: f64,
+    pub medium_volume_l: f64,
+    pub hours: f64,
+}
+
+impl DrugLoadedScaffold {
+    // Equilibrium loading by soaking in a drug solution.
+    pub fn load(
+        crystal: &HydroxyapatiteCrystal,
+        mineral_mass_g: f64,
+        sorption: DrugSorption,
+        loading_concentration_molar: f64,
+        medium_volume_l: f64,
+    ) -> BiologyResult<Self> {
+        if mineral_mass_g.is_nan() || mineral_mass_g <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "mineral mass must be positive".to_string(),
+            ));
+        }
+        if loading_concentration_molar.is_nan() || loading_concentration_molar < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "loading concentration must be non-negative".to_string(),
+            ));
+        }
+        if medium_volume_l.is_nan() || medium_volume_l <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "release medium volume must be positive".to_string(),
+            ));
+        }
+        let sites_umol =
+            crystal.surface_area_m2(mineral_mass_g) * sorption.site_density_umol_per_m2;
+        let occupancy =
+            langmuir_occupancy(sorption.affinity_per_molar, loading_concentration_molar);
+        Ok(Self {
+            sorption,
+            sites_umol,
+            bound_umol: sites_umol * occupancy,
+            medium_umol: 0.0,
+            cumulative_released_umol: 0.0,
+            medium_volume_l,
+            hours: 0.0,
+        })
+    }
+
+    pub fn loaded_umol(&self) -> f64 {
+        self.bound_umol + self.cumulative_released_umol
+    }
+
+    pub fn surface_occupancy(&self) -> f64 {
+        self.bound_umol / self.sites_umol.max(1e-12)
+    }
+
+    pub fn medium_concentration_molar(&self) -> f64 {
+        self.medium_umol * 1.0e-6 / self.medium_volume_l
+    }
+
+    pub fn released_fraction(&self) -> f64 {
+        let loaded = self.loaded_umol();
+        if loaded <= 0.0 {
+            return 0.0;
+        }
+        self.cumulative_released_umol / loaded
+    }
+
+    // Replacing the medium restores sink conditions.
+    pub fn refresh_medium(&mut self) {
+        self.medium_umol = 0.0;
+    }
+
+    pub fn step(&mut self, dt_hours: f64) {
+        if dt_hours <= 0.0 {
+            return;
+        }
+        let s = &self.sorption;
+        let free_sites = (self.sites_umol - self.bound_umol).max(0.0);
+        let desorption = s.desorption_rate_per_hour * self.bound_umol;
+        let readsorption =
+            s.adsorption_rate_per_molar_hour() * self.medium_concentration_molar() * free_sites;
+        let net =
+            ((desorption - readsorption) * dt_hours).clamp(-self.medium_umol, self.bound_umol);
+        self.bound_umol -= net;
+        self.medium_umol += net;
+        self.cumulative_released_umol += net;
+        self.hours += dt_hours;
+    }
+
+    pub fn run_hours(&mut self, hours: f64) {
+        let dt = 0.05;
+        let steps = (hours / dt).round().max(0.0) as usize;
+        for _ in 0..steps {
+            self.step(dt);
+        }
+    }
+}
+
+// Calcium and phosphate activities in SBF with Davies activity
+// coefficients; Ksp of Ca5(PO4)3OH = 10^-58.5.
+// Kokubo T, Takadama H (2006) Biomaterials 27:2907-2915
+// McDowell H et al. (1977) J Res Natl Bur Stand 81A:273-281
+const LOG_KSP_HYDROXYAPATITE: f64 = -58.5;
+const PHOSPHATE_PKA: [f64; 3] = [2.15, 7.20, 12.35];
+const HA_FORMULA_IONS: f64 = 9.0;
+
+// Classical nucleation: t_ind = t0·exp(B·f / (ln S)²), calibrated so
+// sintered HA nucleates apatite in ~3 days in Kokubo SBF. Bone bonding
+// follows when apatite forms within 28 days.
+const NUCLEATION_PREFACTOR_DAYS: f64 = 1.0 / 24.0;
+const NUCLEATION_BARRIER: f64 = 23.2;
+pub const BIOACTIVITY_WINDOW_DAYS: f64 = 28.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedBodyFluid {
+    pub calcium_mm: f64,
+    pub phosphate_mm: f64,
+    pub ph: f64,
+    pub ionic_strength_m: f64,
+}
+
+impl SimulatedBodyFluid {
+    pub fn kokubo() -> Self {
+        Self {
+            calcium_mm: 2.5,
+            phosphate_mm: 1.0,
+            ph: 7.40,
+            ionic_strength_m: 0.15,
+        }
+    }
+
+    pub fn diluted(&self, factor: f64) -> Self {
+        let f = factor.clamp(0.0, 1.0);
+        Self {
+            calcium_mm: self.calcium_mm * f,
+            phosphate_mm: self.phosphate_mm * f,
+            ..*self
+        }
+    }
+
+    fn log_activity_coefficient(&self, charge: f64) -> f64 {
+        let sqrt_i = self.ionic_strength_m.max(0.0).sqrt();
+        -0.51 * charge * charge * (sqrt_i / (1.0 + sqrt_i) - 0.3 * self.ionic_strength_m)
+    }
+
+    // PO4³⁻ share of total phosphate from the H3PO4 speciation ladder.
+    fn phosphate_trivalent_fraction(&self) -> f64 {
+        let [pk1, pk2, pk3] = PHOSPHATE_PKA;
+        let ph = self.ph;
+        let relative_to_po4 = [
+            pk1 + pk2 + pk3 - 3.0 * ph,
+            pk2 + pk3 - 2.0 * ph,
+            pk3 - ph,
+            0.0,
+        ];
+        1.0 / relative_to_po4
+            .iter()
+            .map(|log| 10f64.powf(*log))
+            .sum::<f64>()
+    }
+
+    // log10(IAP/Ksp) for Ca5(PO4)3OH; > 0 means supersaturated.
+    pub fn hydroxyapatite_saturation_index(&self) -> f64 {
+        if self.calcium_mm <= 0.0 || self.phosphate_mm <= 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let log_ca = (self.calcium_mm * 1e-3).log10() + self.log_activity_coefficient(2.0);
+        let log_po4 = (self.phosphate_mm * 1e-3 * self.phosphate_trivalent_fraction()).log10()
+            + self.log_activity_coefficient(3.0);
+        let log_oh = self.ph - 14.0 + self.log_activity_coefficient(1.0);
+        5.0 * log_ca + 3.0 * log_po4 + log_oh - LOG_KSP_HYDROXYAPATITE
+    }
+}
+
+impl Default for SimulatedBodyFluid {
+    fn default() -> Self {
+        Self::kokubo()
+    }
+}
+
+// Total calcium in a solution saturated with respect to HA that dissolved
+// stoichiometrically (Ca:P 5:3) in water held at `ph`. Ionic strength is
+// carried by Ca²⁺ and monovalent H2PO4⁻, valid below about pH 8.
+pub fn hydroxyapatite_solubility_mm(ph: f64) -> BiologyResult<f64> {
+    if !(3.0..=9.0).contains(&ph) {
+        return Err(BiologyError::InvalidValue(format!(
+            "solubility is modelled for pH 3-9, got {}",
+            ph
+        )));
+    }
+    let saturation = |log_ca_mm: f64| {
+        let calcium_mm = 10f64.powf(log_ca_mm);
+        SimulatedBodyFluid {
+            calcium_mm,
+            phosphate_mm: calcium_mm * 0.6,
+            ph,
+            ionic_strength_m: 2.3e-3 * calcium_mm,
+        }
+        .hydroxyapatite_saturation_index()
+    };
+    let (mut low, mut high) = (-9.0, 3.0);
+    for _ in 0..100 {
+        let mid = 0.5 * (low + high);
+        if saturation(mid) > 0.0 {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    Ok(10f64.powf(0.5 * (low + high)))
+}
+
+// Heterogeneous-nucleation barrier relative to a sintered HA surface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ImplantSurface {
+    Bioglass45S5,
+    AlkaliHeatTreatedTitanium,
+    Hydroxyapatite,
+    BetaTricalciumPhosphate,
+    PolishedTitanium,
+    Alumina,
+}
+
+impl ImplantSurface {
+    pub fn nucleation_barrier_factor(&self) -> f64 {
+        match self {
+            ImplantSurface::Bioglass45S5 => 0.7,
+            ImplantSurface::AlkaliHeatTreatedTitanium => 0.8,
+            ImplantSurface::Hydroxyapatite => 1.0,
+            ImplantSurface::BetaTricalciumPhosphate => 1.1,
+            ImplantSurface::PolishedTitanium => 1.6,
+            ImplantSurface::Alumina => 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BioactivityAssessment {
+    pub saturation_index: f64,
+    pub induction_time_days: f64,
+    pub score: f64,
+}
+
+impl BioactivityAssessment {
+    pub fn assess(surface: ImplantSurface, fluid: &SimulatedBodyFluid) -> Self {
+        let saturation_index = fluid.hydroxyapatite_saturation_index();
+        let ln_s = saturation_index / HA_FORMULA_IONS * std::f64::consts::LN_10;
+        let induction_time_days = if ln_s <= 0.0 {
+            f64::INFINITY
+        } else {
+            NUCLEATION_PREFACTOR_DAYS
+                * (NUCLEATION_BARRIER * surface.nucleation_barrier_factor() / (ln_s * ln_s)).exp()
+        };
+        Self {
+            saturation_index,
+            induction_time_days,
+            score: (1.0 - induction_time_days / BIOACTIVITY_WINDOW_DAYS).max(0.0),
+        }
+    }
+
+    pub fn is_bioactive(&self) -> bool {
+        self.induction_time_days <= BIOACTIVITY_WINDOW_DAYS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancellous_scaffold_is_strength_limited() {
+        let req = ScaffoldRequirements::cancellous_bone();
+        let design = ScaffoldDesign::optimize(&req).unwrap();
+        assert!(design.satisfies(&req));
+        assert!(design.pore_size_um >= OSTEOGENIC_PORE_SIZE_UM);
+        // Brittle HA cannot be both as compliant as trabecular bone and
+        // as strong; strength wins.
+        assert!((design.compressive_strength_mpa() - 2.0).abs() < 0.01);
+        assert!(design.modulus_mismatch(&req) > 0.0);
+
+        let stiff = ScaffoldRequirements::new((0.3, 0.9), (200.0, 600.0), 10.0, 2.0).unwrap();
+        let matched = ScaffoldDesign::optimize(&stiff).unwrap();
+        assert!(matched.is_modulus_matched(&stiff));
+        assert!(matched.satisfies(&stiff));
+    }
+
+    #[test]
+    fn test_infeasible_requirements_are_rejected() {
+        let req = ScaffoldRequirements::new((0.6, 0.9), (300.0, 500.0), 1.0, 50.0).unwrap();
+        assert!(ScaffoldDesign::optimize(&req).is_err());
+        assert!(ScaffoldRequirements::new((0.8, 0.5), (300.0, 500.0), 1.0, 2.0).is_err());
+        // Finer pores expose more surface for attachment.
+        let fine = ScaffoldDesign::new(0.7, 150.0).unwrap();
+        let coarse = ScaffoldDesign::new(0.7, 600.0).unwrap();
+        assert!(fine.cell_surface_area_per_mm() > coarse.cell_surface_area_per_mm());
+    }
+
+    #[test]
+    fn test_drug_loading_saturates_and_release_depends_on_affinity() {
+        let crystal = HydroxyapatiteCrystal::bone_mineral();
+        let low = DrugLoadedScaffold::load(&crystal, 1.0, DrugSorption::alendronate(), 1e-7, 1.0)
+            .unwrap();
+        let high = DrugLoadedScaffold::load(&crystal, 1.0, DrugSorption::alendronate(), 1e-3, 1.0)
+            .unwrap();
+        assert!(high.loaded_umol() > low.loaded_umol());
+        assert!(high.surface_occupancy() > 0.99);
+
+        let mut bp = high;
+        let mut aminoglycoside =
+            DrugLoadedScaffold::load(&crystal, 1.0, DrugSorption::gentamicin(), 1e-2, 1.0).unwrap();
+        for _ in 0..7 {
+            bp.run_hours(24.0);
+            aminoglycoside.run_hours(24.0);
+            bp.refresh_medium();
+            aminoglycoside.refresh_medium();
+        }
+        assert!(aminoglycoside.released_fraction() > 0.9);
+        assert!(bp.released_fraction() < 0.3);
+    }
+
+    #[test]
+    fn test_finite_medium_reaches_sorption_equilibrium() {
+        let crystal = HydroxyapatiteCrystal::bone_mineral();
+        let mut scaffold =
+            DrugLoadedScaffold::load(&crystal, 0.1, DrugSorption::gentamicin(), 1e-2, 0.001)
+                .unwrap();
+        let loaded = scaffold.loaded_umol();
+        scaffold.run_hours(72.0);
+        let theta = langmuir_occupancy(
+            scaffold.sorption.affinity_per_molar,
+            scaffold.medium_concentration_molar(),
+        );
+        assert!((scaffold.surface_occupancy() - theta).abs() < 0.01);
+        assert!((scaffold.bound_umol + scaffold.medium_umol - loaded).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hydroxyapatite_dissolves_in_acid() {
+        let neutral = hydroxyapatite_solubility_mm(7.0).unwrap();
+        let acidic = hydroxyapatite_solubility_mm(5.0).unwrap();
+        assert!(acidic > 50.0 * neutral);
+        let fluid = SimulatedBodyFluid {
+            calcium_mm: neutral,
+            phosphate_mm: 0.6 * neutral,
+            ph: 7.0,
+            ionic_strength_m: 2.3e-3 * neutral,
+        };
+        assert!(fluid.hydroxyapatite_saturation_index().abs() < 1e-6);
+        assert!(hydroxyapatite_solubility_mm(1.0).is_err());
+    }
+
+    #[test]
+    fn test_apatite_forming_ability_in_sbf() {
+        let sbf = SimulatedBodyFluid::kokubo();
+        assert!(sbf.hydroxyapatite_saturation_index() > 0.0);
+        let ha = BioactivityAssessment::assess(ImplantSurface::Hydroxyapatite, &sbf);
+        let glass = BioactivityAssessment::assess(ImplantSurface::Bioglass45S5, &sbf);
+        let alumina = BioactivityAssessment::assess(ImplantSurface::Alumina, &sbf);
+        assert!(ha.is_bioactive() && glass.is_bioactive());
+        assert!(!alumina.is_bioactive());
+        assert!(glass.score > ha.score && alumina.score == 0.0);
+        assert!(ha.induction_time_days > 1.0 && ha.induction_time_days < 7.0);
+
+        let dilute =
+            BioactivityAssessment::assess(ImplantSurface::Hydroxyapatite, &sbf.diluted(0.5));
+        assert!(dilute.induction_time_days > ha.induction_time_days);
+    }
+}
@@ -0,0 +1,438 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::engine::{load, save, substep, unknown_input, Component};
+use crate::systems::skeletal::biomaterials::ScaffoldDesign;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Culture medium at 37 °C.
+const MEDIUM_VISCOSITY_PA_S: f64 = 7.8e-4;
+
+// Air-saturated medium holds ~0.2 mM O2; cells consume ~1e-17 mol/s with
+// a Km near 6 µM, so static constructs are only viable in an outer shell
+// a few hundred µm deep.
+// Malda J, Klein TJ, Upton Z (2007) Tissue Eng 13:2153-2162
+// Ishaug SL et al. (1997) J Biomed Mater Res 36:17-28
+const OXYGEN: Solute = Solute {
+    inlet_mol_m3: 0.2,
+    diffusivity_m2_s: 3.0e-9,
+    uptake_mol_per_cell_s: 1.0e-17,
+    km_mol_m3: 0.006,
+};
+const GLUCOSE: Solute = Solute {
+    inlet_mol_m3: 5.5,
+    diffusivity_m2_s: 9.0e-10,
+    uptake_mol_per_cell_s: 1.0e-17,
+    km_mol_m3: 0.4,
+};
+
+// Logistic proliferation gated by oxygen and glucose, death under anoxia.
+const MAX_PROLIFERATION_PER_DAY: f64 = 0.5;
+const PROLIFERATION_O2_HALF_MOL_M3: f64 = 0.02;
+const PROLIFERATION_GLUCOSE_HALF_MOL_M3: f64 = 0.5;
+const MAX_HYPOXIC_DEATH_PER_DAY: f64 = 0.5;
+const HYPOXIC_DEATH_O2_HALF_MOL_M3: f64 = 0.005;
+const MAX_CELLS_PER_M3: f64 = 1.0e14;
+
+// Matrix secreted per cell per day, ~10% of a 2000 µm³ cell volume.
+// Fluid shear in the 1-30 mPa range raises mineralised matrix output.
+// Bancroft GN et al. (2002) Proc Natl Acad Sci USA 99:12600-12605
+const ECM_M3_PER_CELL_DAY: f64 = 2.0e-16;
+const MAX_PORE_FILL: f64 = 0.9;
+const SHEAR_ECM_GAIN: f64 = 2.0;
+const SHEAR_ECM_HALF_PA: f64 = 0.01;
+
+// Newly deposited matrix behaves as a cellular solid, E ∝ φ².
+const ECM_SOLID_MODULUS_KPA: f64 = 20_000.0;
+
+const NODES: usize = 41;
+const PICARD_ITERATIONS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct Solute {
+    inlet_mol_m3: f64,
+    diffusivity_m2_s: f64,
+    uptake_mol_per_cell_s: f64,
+    km_mol_m3: f64,
+}
+
+// Cell-seeded disc in a flow-through chamber, resolved through its
+// thickness along the flow axis. Transport is quasi-steady on the
+// day-scale of growth.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bioreactor {
+    pub scaffold: ScaffoldDesign,
+    pub scaffold_modulus_kpa: f64,
+    pub thickness_mm: f64,
+    pub diameter_mm: f64,
+    pub flow_ml_min: f64,
+    pub cells_per_m3: Vec<f64>,
+    // Fraction of the pore volume filled with matrix.
+    pub pore_fill: Vec<f64>,
+    pub oxygen_mol_m3: Vec<f64>,
+    pub glucose_mol_m3: Vec<f64>,
+    pub days: f64,
+}
+
+impl Bioreactor {
+    pub fn new(
+        scaffold: ScaffoldDesign,
+        scaffold_modulus_kpa: f64,
+        thickness_mm: f64,
+        diameter_mm: f64,
+        seeding_cells_per_cm3: f64,
+    ) -> BiologyResult<Self> {
+        for (name, value) in [
+            ("scaffold modulus", scaffold_modulus_kpa),
+            ("thickness", thickness_mm),
+            ("diameter", diameter_mm),
+            ("seeding density", seeding_cells_per_cm3),
+        ] {
+            if value.is_nan() || value <= 0.0 {
+                return Err(BiologyError::InvalidValue(format!(
+                    "{} must be positive",
+                    name
+                )));
+            }
+        }
+        let mut reactor = Self {
+            scaffold,
+            scaffold_modulus_kpa,
+            thickness_mm,
+            diameter_mm,
+            flow_ml_min: 0.0,
+            cells_per_m3: vec![seeding_cells_per_cm3 * 1.0e6; NODES],
+            pore_fill: vec![0.0; NODES],
+            oxygen_mol_m3: vec![OXYGEN.inlet_mol_m3; NODES],
+            glucose_mol_m3: vec![GLUCOSE.inlet_mol_m3; NODES],
+            days: 0.0,
+        };
+        reactor.solve_transport();
+        Ok(reactor)
+    }
+
+    // 8 × 3 mm porous polymer disc seeded at 5 million cells per cm³.
+    pub fn static_culture() -> Self {
+        let scaffold = ScaffoldDesign {
+            porosity: 0.8,
+            pore_size_um: 300.0,
+        };
+        Self::new(scaffold, 1000.0, 3.0, 8.0, 5.0e6).expect("preset parameters are valid")
+    }
+
+    // Bancroft et al. perfused 0.3-3 mL/min through 8 mm discs.
+    pub fn perfusion() -> Self {
+        let mut reactor = Self::static_culture();
+        reactor.flow_ml_min = 0.3;
+        reactor.solve_transport();
+        reactor
+    }
+
+    pub fn set_flow(&mut self, flow_ml_min: f64) -> BiologyResult<()> {
+        check_flow(flow_ml_min)?;
+        self.flow_ml_min = flow_ml_min;
+        self.solve_transport();
+        Ok(())
+    }
+
+    fn cross_section_m2(&self) -> f64 {
+        std::f64::consts::PI * (self.diameter_mm * 0.5e-3).powi(2)
+    }
+
+    pub fn superficial_velocity_m_s(&self) -> f64 {
+        self.flow_ml_min * 1.0e-6 / 60.0 / self.cross_section_m2()
+    }
+
+    fn mean(values: &[f64]) -> f64 {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+
+    pub fn mean_pore_fill(&self) -> f64 {
+        Self::mean(&self.pore_fill)
+    }
+
+    // Matrix narrows the open pores and the flow path.
+    pub fn open_pore_design(&self) -> ScaffoldDesign {
+        let fill = self.mean_pore_fill();
+        ScaffoldDesign {
+            porosity: self.scaffold.porosity * (1.0 - fill),
+            pore_size_um: self.scaffold.pore_size_um * (1.0 - fill).sqrt(),
+        }
+    }
+
+    // Poiseuille wall shear in a pore, τ = 8μ·u_pore/d.
+    pub fn wall_shear_stress_pa(&self) -> f64 {
+        let open = self.open_pore_design();
+        let pore_velocity = self.superficial_velocity_m_s() / open.porosity.max(1e-6);
+        8.0 * MEDIUM_VISCOSITY_PA_S * pore_velocity / (open.pore_size_um * 1.0e-6)
+    }
+
+    // Darcy's law across the construct.
+    pub fn pressure_drop_pa(&self) -> f64 {
+        MEDIUM_VISCOSITY_PA_S * self.superficial_velocity_m_s() * self.thickness_mm * 1.0e-3
+            / self.open_pore_design().permeability_m2().max(1e-24)
+    }
+
+    pub fn mean_cells_per_cm3(&self) -> f64 {
+        Self::mean(&self.cells_per_m3) * 1.0e-6
+    }
+
+    pub fn min_oxygen_mol_m3(&self) -> f64 {
+        self.oxygen_mol_m3
+            .iter()
+            .copied()
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    // Share of the thickness with enough oxygen to proliferate.
+    pub fn viable_depth_fraction(&self) -> f64 {
+        self.oxygen_mol_m3
+            .iter()
+            .filter(|&&c| c > PROLIFERATION_O2_HALF_MOL_M3)
+            .count() as f64
+            / NODES as f64
+    }
+
+    pub fn ecm_volume_fraction(&self) -> f64 {
+        self.scaffold.porosity * self.mean_pore_fill()
+    }
+
+    // Scaffold and matrix loaded in parallel.
+    pub fn construct_modulus_kpa(&self) -> f64 {
+        self.scaffold_modulus_kpa + ECM_SOLID_MODULUS_KPA * self.ecm_volume_fraction().powi(2)
+    }
+
+    // Steady advection-diffusion-reaction along the flow axis, upwind
+    // convection and Michaelis-Menten uptake linearised by Picard
+    // iteration. Medium bathes both faces in static culture; under
+    // perfusion the outlet face is an outflow boundary.
+    fn solve_solute(&self, solute: &Solute, field: &mut [f64]) {
+        let n = field.len();
+        let dx = self.thickness_mm * 1.0e-3 / (n - 1) as f64;
+        let open_porosity = self.open_pore_design().porosity.max(0.05);
+        // Bruggeman tortuosity correction.
+        let d_eff = solute.diffusivity_m2_s * open_porosity.powf(1.5);
+        let v = self.superficial_velocity_m_s();
+        let diffusive = d_eff / (dx * dx);
+        let convective = v / dx;
+        let perfused = v > 0.0;
+        let mut lower = vec![0.0; n];
+        let mut diag = vec![1.0; n];
+        let mut upper = vec![0.0; n];
+        let mut rhs = vec![0.0; n];
+        for _ in 0..PICARD_ITERATIONS {
+            rhs[0] = solute.inlet_mol_m3;
+            for i in 1..n {
+                let uptake = solute.uptake_mol_per_cell_s * self.cells_per_m3[i]
+                    / (solute.km_mol_m3 + field[i].max(0.0));
+                if i == n - 1 {
+                    if perfused {
+                        lower[i] = -(diffusive + convective);
+                        diag[i] = diffusive + convective + uptake;
+                        rhs[i] = 0.0;
+                    } else {
+                        lower[i] = 0.0;
+                        diag[i] = 1.0;
+                        rhs[i] = solute.inlet_mol_m3;
+                    }
+                    continue;
+                }
+                lower[i] = -(diffusive + convective);
+                diag[i] = 2.0 * diffusive + convective + uptake;
+                upper[i] = -diffusive;
+                rhs[i] = 0.0;
+            }
+            thomas(&lower, &diag, &upper, &mut rhs);
+            for (c, new) in field.iter_mut().zip(&rhs) {
+                *c = new.max(0.0);
+            }
+        }
+    }
+
+    fn solve_transport(&mut self) {
+        let mut oxygen = std::mem::take(&mut self.oxygen_mol_m3);
+        let mut glucose = std::mem::take(&mut self.glucose_mol_m3);
+        self.solve_solute(&OXYGEN, &mut oxygen);
+        self.solve_solute(&GLUCOSE, &mut glucose);
+        self.oxygen_mol_m3 = oxygen;
+        self.glucose_mol_m3 = glucose;
+    }
+
+    pub fn step(&mut self, dt_days: f64) {
+        if dt_days <= 0.0 {
+            return;
+        }
+        let shear = self.wall_shear_stress_pa();
+        let mechanostimulation = 1.0 + SHEAR_ECM_GAIN * shear / (shear + SHEAR_ECM_HALF_PA);
+        for i in 0..NODES {
+            let o2 = self.oxygen_mol_m3[i];
+            let glucose = self.glucose_mol_m3[i];
+            let n = self.cells_per_m3[i];
+            let nourished = o2 / (o2 + PROLIFERATION_O2_HALF_MOL_M3) * glucose
+                / (glucose + PROLIFERATION_GLUCOSE_HALF_MOL_M3);
+            let growth = MAX_PROLIFERATION_PER_DAY * nourished * (1.0 - n / MAX_CELLS_PER_M3);
+            let death = MAX_HYPOXIC_DEATH_PER_DAY * HYPOXIC_DEATH_O2_HALF_MOL_M3
+                / (HYPOXIC_DEATH_O2_HALF_MOL_M3 + o2);
+            self.cells_per_m3[i] = (n * (1.0 + (growth - death) * dt_days)).max(0.0);
+
+            let fill = self.pore_fill[i];
+            let deposition = ECM_M3_PER_CELL_DAY * n * nourished * mechanostimulation
+                / self.scaffold.porosity
+                * (1.0 - fill / MAX_PORE_FILL).max(0.0);
+            self.pore_fill[i] = (fill + deposition * dt_days).min(MAX_PORE_FILL);
+        }
+        self.days += dt_days;
+        self.solve_transport();
+    }
+
+    pub fn run_days(&mut self, days: f64) {
+        let dt = 0.1;
+        let steps = (days / dt).round().max(0.0) as usize;
+        for _ in 0..steps {
+            self.step(dt);
+        }
+    }
+}
+
+impl Default for Bioreactor {
+    fn default() -> Self {
+        Self::perfusion()
+    }
+}
+
+// Tridiagonal solve in place; `rhs` returns the solution.
+fn thomas(lower: &[f64], diag: &[f64], upper: &[f64], rhs: &mut [f64]) {
+    let n = rhs.len();
+    let mut c = vec![0.0; n];
+    c[0] = upper[0] / diag[0];
+    rhs[0] /= diag[0];
+    for i in 1..n {
+        let m = diag[i] - lower[i] * c[i - 1];
+        c[i] = upper[i] / m;
+        rhs[i] = (rhs[i] - lower[i] * rhs[i - 1]) / m;
+    }
+    for i in (0..n - 1).rev() {
+        rhs[i] -= c[i] * rhs[i + 1];
+    }
+}
+
+// Construct growth runs over weeks; transport is re-solved every
+// substep, so the engine step can be hours.
+fn check_flow(flow_ml_min: f64) -> BiologyResult<()> {
+    if flow_ml_min.is_nan() || flow_ml_min < 0.0 {
+        return Err(BiologyError::InvalidValue(
+            "perfusion flow must be non-negative".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl Component for Bioreactor {
+    fn kind(&self) -> &'static str {
+        "bioreactor"
+    }
+
+    fn inputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[("flow_ml_min", "mL/min")]
+    }
+
+    fn outputs(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("cells_per_cm3", "1/cm3"),
+            ("min_oxygen_mm", "mM"),
+            ("ecm_volume_fraction", "1"),
+            ("construct_modulus_kpa", "kPa"),
+            ("wall_shear_stress_mpa", "mPa"),
+            ("pressure_drop_pa", "Pa"),
+        ]
+    }
+
+    fn set_input(&mut self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "flow_ml_min" => self.set_flow(value),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn check_input(&self, name: &str, value: f64) -> BiologyResult<()> {
+        match name {
+            "flow_ml_min" => check_flow(value),
+            _ => Err(unknown_input(self.kind(), name)),
+        }
+    }
+
+    fn output(&self, name: &str) -> Option<f64> {
+        match name {
+            "cells_per_cm3" => Some(self.mean_cells_per_cm3()),
+            "min_oxygen_mm" => Some(self.min_oxygen_mol_m3()),
+            "ecm_volume_fraction" => Some(self.ecm_volume_fraction()),
+            "construct_modulus_kpa" => Some(self.construct_modulus_kpa()),
+            "wall_shear_stress_mpa" => Some(self.wall_shear_stress_pa() * 1.0e3),
+            "pressure_drop_pa" => Some(self.pressure_drop_pa()),
+            _ => None,
+        }
+    }
+
+    fn step(&mut self, dt_minutes: f64) {
+        substep(dt_minutes, 144.0, |h| Bioreactor::step(self, h / 1440.0));
+    }
+
+    fn natural_dt_minutes(&self) -> Option<f64> {
+        Some(144.0)
+    }
+
+    fn save_state(&self) -> Value {
+        save(self)
+    }
+
+    fn load_state(&mut self, state: Value) -> BiologyResult<()> {
+        load(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_construct_develops_hypoxic_core() {
+        let mut reactor = Bioreactor::static_culture();
+        assert!(reactor.min_oxygen_mol_m3() > 0.1);
+        reactor.run_days(14.0);
+        assert!(reactor.min_oxygen_mol_m3() < 0.01);
+        assert!(reactor.viable_depth_fraction() < 0.8);
+        // The faces still see medium.
+        assert!((reactor.oxygen_mol_m3[0] - 0.2).abs() < 1e-9);
+        assert!((reactor.oxygen_mol_m3[NODES - 1] - 0.2).abs() < 1e-9);
+
+        let mut perfused = Bioreactor::perfusion();
+        perfused.run_days(14.0);
+        assert!(perfused.min_oxygen_mol_m3() > 0.1);
+        assert!(perfused.oxygen_mol_m3[NODES - 1] < perfused.oxygen_mol_m3[0]);
+    }
+
+    #[test]
+    fn test_perfusion_grows_a_stiffer_construct() {
+        let mut still = Bioreactor::static_culture();
+        let mut perfused = Bioreactor::perfusion();
+        still.run_days(21.0);
+        perfused.run_days(21.0);
+        assert!(perfused.mean_cells_per_cm3() > 1.4 * still.mean_cells_per_cm3());
+        assert!(perfused.ecm_volume_fraction() > still.ecm_volume_fraction());
+        assert!(perfused.construct_modulus_kpa() > 1.5 * perfused.scaffold_modulus_kpa);
+        assert!(perfused.construct_modulus_kpa() > still.construct_modulus_kpa());
+    }
+
+    #[test]
+    fn test_higher_flow_raises_shear_and_matrix() {
+        let mut slow = Bioreactor::perfusion();
+        let mut fast = Bioreactor::perfusion();
+        fast.set_flow(3.0).unwrap();
+        assert!(fast.wall_shear_stress_pa() > 5.0 * slow.wall_shear_stress_pa());
+        slow.run_days(14.0);
+        fast.run_days(14.0);
+        assert!(fast.ecm_volume_fraction() > slow.ecm_volume_fraction());
+        // Matrix clogs the pores.
+        assert!(fast.pressure_drop_pa() > Bioreactor::perfusion().pressure_drop_pa() * 10.0);
+        assert!(slow.set_flow(-1.0).is_err());
+    }
+}
@@ -0,0 +1,462 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::skeletal::hydroxyapatite::HydroxyapatiteCrystal;
+use crate::systems::skeletal::synthesis::HydroxyapatiteCharacterization;
+use serde::{Deserialize, Serialize};
+
+// Cu Kα1 laboratory source.
+pub const CU_K_ALPHA_ANGSTROM: f64 = 1.5406;
+const SCHERRER_K: f64 = 0.9;
+const INSTRUMENT_FWHM_DEG: f64 = 0.05;
+
+// Hydroxyapatite P6₃/m, and the fluor- and strontium end-members its
+// substituted solid solutions follow by Vegard's law.
+// Hughes JM et al. (1989) Am Mineral 74:870-876
+// Rokita E et al. (1993) J Cryst Growth 130:543-552
+const HA_A: f64 = 9.418;
+const HA_C: f64 = 6.884;
+const FLUORAPATITE_A: f64 = 9.367;
+const FLUORAPATITE_C: f64 = 6.884;
+const STRONTIUM_APATITE_A: f64 = 9.745;
+const STRONTIUM_APATITE_C: f64 = 7.265;
+// B-type carbonate for phosphate shortens a and lengthens c, Å per wt%.
+// LeGeros RZ (1991) Calcium Phosphates in Oral Biology and Medicine, Karger
+const CARBONATE_DA_PER_WT: f64 = -0.008;
+const CARBONATE_DC_PER_WT: f64 = 0.004;
+const MAX_CARBONATE_WT: f64 = 10.0;
+
+// Reference powder pattern, relative intensities for 2θ 20-55° (Cu Kα).
+// ICDD PDF 09-0432
+const HA_REFLECTIONS: [([i32; 3], f64); 11] = [
+    ([0, 0, 2], 40.0),
+    ([1, 0, 2], 12.0),
+    ([2, 1, 0], 18.0),
+    ([2, 1, 1], 100.0),
+    ([1, 1, 2], 60.0),
+    ([3, 0, 0], 60.0),
+    ([2, 0, 2], 25.0),
+    ([3, 1, 0], 20.0),
+    ([2, 2, 2], 30.0),
+    ([2, 1, 3], 40.0),
+    ([0, 0, 4], 20.0),
+];
+
+// Amorphous calcium phosphate gives one broad hump near 30° 2θ.
+const AMORPHOUS_CENTRE_DEG: f64 = 30.0;
+const AMORPHOUS_FWHM_DEG: f64 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct UnitCell {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub alpha_deg: f64,
+    pub beta_deg: f64,
+    pub gamma_deg: f64,
+}
+
+impl UnitCell {
+    pub fn new(lengths: [f64; 3], angles_deg: [f64; 3]) -> BiologyResult<Self> {
+        if lengths.iter().any(|x| x.is_nan() || *x <= 0.0) {
+            return Err(BiologyError::InvalidValue(
+                "cell edges must be positive".to_string(),
+            ));
+        }
+        if angles_deg
+            .iter()
+            .any(|x| x.is_nan() || *x <= 0.0 || *x >= 180.0)
+        {
+            return Err(BiologyError::InvalidValue(
+                "cell angles must lie in (0, 180) degrees".to_string(),
+            ));
+        }
+        let cell = Self {
+            a: lengths[0],
+            b: lengths[1],
+            c: lengths[2],
+            alpha_deg: angles_deg[0],
+            beta_deg: angles_deg[1],
+            gamma_deg: angles_deg[2],
+        };
+        if cell.volume().is_nan() || cell.volume() <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "cell angles do not close a parallelepiped".to_string(),
+            ));
+        }
+        Ok(cell)
+    }
+
+    fn cosines(&self) -> (f64, f64, f64) {
+        (
+            self.alpha_deg.to_radians().cos(),
+            self.beta_deg.to_radians().cos(),
+            self.gamma_deg.to_radians().cos(),
+        )
+    }
+
+    pub fn volume(&self) -> f64 {
+        let (ca, cb, cg) = self.cosines();
+        self.a * self.b * self.c * (1.0 - ca * ca - cb * cb - cg * cg + 2.0 * ca * cb * cg).sqrt()
+    }
+
+    // Triclinic interplanar spacing from the reciprocal metric.
+    pub fn d_spacing(&self, hkl: [i32; 3]) -> f64 {
+        let [h, k, l] = hkl.map(f64::from);
+        let (a, b, c) = (self.a, self.b, self.c);
+        let (ca, cb, cg) = self.cosines();
+        let (sa2, sb2, sg2) = (1.0 - ca * ca, 1.0 - cb * cb, 1.0 - cg * cg);
+        let v = self.volume();
+        let inverse_d2 = (h * h * b * b * c * c * sa2
+            + k * k * a * a * c * c * sb2
+            + l * l * a * a * b * b * sg2
+            + 2.0 * h * k * a * b * c * c * (ca * cb - cg)
+            + 2.0 * k * l * a * a * b * c * (cb * cg - ca)
+            + 2.0 * h * l * a * b * b * c * (cg * ca - cb))
+            / (v * v);
+        1.0 / inverse_d2.sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HexagonalLattice {
+    pub a: f64,
+    pub c: f64,
+}
+
+impl HexagonalLattice {
+    pub fn new(a: f64, c: f64) -> BiologyResult<Self> {
+        if a.is_nan() || a <= 0.0 || c.is_nan() || c <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "lattice parameters must be positive".to_string(),
+            ));
+        }
+        Ok(Self { a, c })
+    }
+
+    pub fn hydroxyapatite() -> Self {
+        Self { a: HA_A, c: HA_C }
+    }
+
+    pub fn unit_cell(&self) -> UnitCell {
+        UnitCell {
+            a: self.a,
+            b: self.a,
+            c: self.c,
+            alpha_deg: 90.0,
+            beta_deg: 90.0,
+            gamma_deg: 120.0,
+        }
+    }
+
+    // 1/d² = 4/3·(h² + hk + k²)/a² + l²/c²
+    pub fn d_spacing(&self, hkl: [i32; 3]) -> f64 {
+        let [h, k, l] = hkl.map(f64::from);
+        let inverse_d2 =
+            4.0 / 3.0 * (h * h + h * k + k * k) / (self.a * self.a) + l * l / (self.c * self.c);
+        1.0 / inverse_d2.sqrt()
+    }
+
+    // Cosine between the (hkl) normal and the c axis.
+    fn c_axis_cosine(&self, hkl: [i32; 3]) -> f64 {
+        (hkl[2] as f64 / self.c * self.d_spacing(hkl)).abs()
+    }
+
+    pub fn volume(&self) -> f64 {
+        3f64.sqrt() / 2.0 * self.a * self.a * self.c
+    }
+
+    // Fractional change of (a, c) from the reference lattice.
+    pub fn strain_from(&self, reference: &HexagonalLattice) -> (f64, f64) {
+        (self.a / reference.a - 1.0, self.c / reference.c - 1.0)
+    }
+}
+
+impl Default for HexagonalLattice {
+    fn default() -> Self {
+        Self::hydroxyapatite()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IonicSubstitutions {
+    // F⁻ share of the OH⁻ channel sites.
+    pub fluoride_fraction: f64,
+    // Sr²⁺ share of the Ca²⁺ sites.
+    pub strontium_fraction: f64,
+    pub carbonate_wt_percent: f64,
+}
+
+impl IonicSubstitutions {
+    pub fn new(
+        fluoride_fraction: f64,
+        strontium_fraction: f64,
+        carbonate_wt_percent: f64,
+    ) -> BiologyResult<Self> {
+        for (name, value) in [
+            ("fluoride fraction", fluoride_fraction),
+            ("strontium fraction", strontium_fraction),
+        ] {
+            if value.is_nan() || !(0.0..=1.0).contains(&value) {
+                return Err(BiologyError::InvalidValue(format!(
+                    "{} must lie in [0, 1]",
+                    name
+                )));
+            }
+        }
+        if carbonate_wt_percent.is_nan()
+            || !(0.0..=MAX_CARBONATE_WT).contains(&carbonate_wt_percent)
+        {
+            return Err(BiologyError::InvalidValue(format!(
+                "carbonate content must lie in [0, {}] wt%",
+                MAX_CARBONATE_WT
+            )));
+        }
+        Ok(Self {
+            fluoride_fraction,
+            strontium_fraction,
+            carbonate_wt_percent,
+        })
+    }
+
+    pub fn none() -> Self {
+        Self {
+            fluoride_fraction: 0.0,
+            strontium_fraction: 0.0,
+            carbonate_wt_percent: 0.0,
+        }
+    }
+
+    // Adult bone mineral carries 6-7 wt% carbonate.
+    // Boskey AL (2007) Elements 3:385-391
+    pub fn bone_mineral() -> Self {
+        Self {
+            carbonate_wt_percent: 6.5,
+            ..Self::none()
+        }
+    }
+
+    pub fn lattice(&self) -> HexagonalLattice {
+        let a = HA_A
+            + self.fluoride_fraction * (FLUORAPATITE_A - HA_A)
+            + self.strontium_fraction * (STRONTIUM_APATITE_A - HA_A)
+            + self.carbonate_wt_percent * CARBONATE_DA_PER_WT;
+        let c = HA_C
+            + self.fluoride_fraction * (FLUORAPATITE_C - HA_C)
+            + self.strontium_fraction * (STRONTIUM_APATITE_C - HA_C)
+            + self.carbonate_wt_percent * CARBONATE_DC_PER_WT;
+        HexagonalLattice { a, c }
+    }
+
+    pub fn lattice_strain(&self) -> (f64, f64) {
+        self.lattice()
+            .strain_from(&HexagonalLattice::hydroxyapatite())
+    }
+}
+
+impl Default for IonicSubstitutions {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+pub fn bragg_two_theta_deg(d_spacing: f64, wavelength: f64) -> Option<f64> {
+    let s = wavelength / (2.0 * d_spacing);
+    (s > 0.0 && s <= 1.0).then(|| 2.0 * s.asin().to_degrees())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct XrdPeak {
+    pub hkl: [i32; 3],
+    pub d_spacing: f64,
+    pub two_theta_deg: f64,
+    pub relative_intensity: f64,
+    pub fwhm_deg: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct XrdPattern {
+    pub peaks: Vec<XrdPeak>,
+    pub two_theta_deg: Vec<f64>,
+    pub intensity: Vec<f64>,
+}
+
+impl XrdPattern {
+    // Reference intensities at strained peak positions, Scherrer-broadened
+    // by the crystallite extent normal to each plane, over an amorphous
+    // hump carrying the non-crystalline share of the scattering.
+    pub fn simulate(
+        lattice: &HexagonalLattice,
+        crystal: &HydroxyapatiteCrystal,
+        crystallinity: f64,
+    ) -> Self {
+        let lambda = CU_K_ALPHA_ANGSTROM;
+        let crystalline = crystallinity.clamp(0.0, 1.0);
+        let lateral_nm = (crystal.width_nm * crystal.thickness_nm).sqrt();
+        let peaks: Vec<XrdPeak> = HA_REFLECTIONS
+            .iter()
+            .filter_map(|&(hkl, intensity)| {
+                let d = lattice.d_spacing(hkl);
+                let two_theta = bragg_two_theta_deg(d, lambda)?;
+                let cos2 = lattice.c_axis_cosine(hkl).powi(2);
+                let size_nm = 1.0 / (cos2 / crystal.length_nm + (1.0 - cos2) / lateral_nm);
+                let theta = (two_theta / 2.0).to_radians();
+                let beta_rad = SCHERRER_K * lambda / (size_nm * 10.0 * theta.cos());
+                Some(XrdPeak {
+                    hkl,
+                    d_spacing: d,
+                    two_theta_deg: two_theta,
+                    relative_intensity: intensity,
+                    fwhm_deg: beta_rad.to_degrees() + INSTRUMENT_FWHM_DEG,
+                })
+            })
+            .collect();
+
+        let total_area: f64 = peaks.iter().map(|p| p.relative_intensity).sum();
+        let amorphous_area = total_area * (1.0 - crystalline);
+        let gaussian = |x: f64, centre: f64, fwhm: f64, area: f64| {
+            let sigma = fwhm / (8.0 * std::f64::consts::LN_2).sqrt();
+            area / (sigma * (2.0 * std::f64::consts::PI).sqrt())
+                * (-0.5 * ((x - centre) / sigma).powi(2)).exp()
+        };
+        let two_theta_deg: Vec<f64> = (0..=1750).map(|i| 20.0 + 0.02 * i as f64).collect();
+        let intensity = two_theta_deg
+            .iter()
+            .map(|&x| {
+                peaks
+                    .iter()
+                    .map(|p| {
+                        gaussian(
+                            x,
+                            p.two_theta_deg,
+                            p.fwhm_deg,
+                            crystalline * p.relative_intensity,
+                        )
+                    })
+                    .sum::<f64>()
+                    + gaussian(x, AMORPHOUS_CENTRE_DEG, AMORPHOUS_FWHM_DEG, amorphous_area)
+            })
+            .collect();
+        Self {
+            peaks,
+            two_theta_deg,
+            intensity,
+        }
+    }
+
+    pub fn from_characterization(
+        product: &HydroxyapatiteCharacterization,
+        substitutions: &IonicSubstitutions,
+    ) -> Self {
+        Self::simulate(
+            &substitutions.lattice(),
+            &product.crystal,
+            product.crystallinity,
+        )
+    }
+
+    pub fn peak(&self, hkl: [i32; 3]) -> Option<&XrdPeak> {
+        self.peaks.iter().find(|p| p.hkl == hkl)
+    }
+
+    fn intensity_at(&self, two_theta_deg: f64) -> f64 {
+        let i = ((two_theta_deg - self.two_theta_deg[0]) / 0.02).round() as usize;
+        self.intensity[i.min(self.intensity.len() - 1)]
+    }
+
+    // Xc = 1 − V112/300 / I300, with V the trough between the (112) and
+    // (300) reflections.
+    // Landi E et al. (2000) J Eur Ceram Soc 20:2377-2387
+    pub fn crystallinity_index(&self) -> Option<f64> {
+        let p112 = self.peak([1, 1, 2])?.two_theta_deg;
+        let p300 = self.peak([3, 0, 0])?.two_theta_deg;
+        let i300 = self.intensity_at(p300);
+        let trough = self
+            .two_theta_deg
+            .iter()
+            .zip(&self.intensity)
+            .filter(|(x, _)| **x > p112 && **x < p300)
+            .map(|(_, y)| *y)
+            .fold(f64::INFINITY, f64::min);
+        if i300 <= 0.0 || !trough.is_finite() {
+            return None;
+        }
+        Some((1.0 - trough / i300).clamp(0.0, 1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::skeletal::synthesis::{SynthesisRoute, SyntheticHydroxyapatite};
+
+    #[test]
+    fn test_hydroxyapatite_d_spacings_and_peak_positions() {
+        let ha = HexagonalLattice::hydroxyapatite();
+        assert!((ha.d_spacing([0, 0, 2]) - 3.442).abs() < 1e-3);
+        let cell = ha.unit_cell();
+        for hkl in [[2, 1, 1], [1, 1, 2], [3, 0, 0], [2, 1, 3]] {
+            assert!((cell.d_spacing(hkl) - ha.d_spacing(hkl)).abs() < 1e-9);
+        }
+        assert!((cell.volume() - ha.volume()).abs() < 1e-6);
+        let two_theta = |hkl| bragg_two_theta_deg(ha.d_spacing(hkl), CU_K_ALPHA_ANGSTROM).unwrap();
+        assert!((two_theta([2, 1, 1]) - 31.77).abs() < 0.05);
+        assert!((two_theta([3, 0, 0]) - 32.90).abs() < 0.05);
+        assert!((two_theta([0, 0, 2]) - 25.88).abs() < 0.05);
+
+        let cubic = UnitCell::new([5.0; 3], [90.0; 3]).unwrap();
+        assert!((cubic.d_spacing([1, 1, 1]) - 5.0 / 3f64.sqrt()).abs() < 1e-12);
+        assert!(UnitCell::new([1.0, 1.0, 1.0], [10.0, 10.0, 170.0]).is_err());
+    }
+
+    #[test]
+    fn test_substitutions_strain_the_lattice() {
+        let (fa, _) = IonicSubstitutions::new(1.0, 0.0, 0.0)
+            .unwrap()
+            .lattice_strain();
+        let (sr_a, sr_c) = IonicSubstitutions::new(0.0, 0.5, 0.0)
+            .unwrap()
+            .lattice_strain();
+        let (co3_a, co3_c) = IonicSubstitutions::bone_mineral().lattice_strain();
+        assert!(fa < 0.0);
+        assert!(sr_a > 0.0 && sr_c > 0.0);
+        assert!(co3_a < 0.0 && co3_c > 0.0);
+
+        // Contracting a moves (300) to higher angle.
+        let crystal = HydroxyapatiteCrystal::bone_mineral();
+        let pure = XrdPattern::simulate(&HexagonalLattice::hydroxyapatite(), &crystal, 1.0);
+        let fluor = XrdPattern::simulate(
+            &IonicSubstitutions::new(1.0, 0.0, 0.0).unwrap().lattice(),
+            &crystal,
+            1.0,
+        );
+        assert!(
+            fluor.peak([3, 0, 0]).unwrap().two_theta_deg
+                > pure.peak([3, 0, 0]).unwrap().two_theta_deg
+        );
+        assert!(IonicSubstitutions::new(1.5, 0.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_crystallinity_index_tracks_synthesis() {
+        let hydrothermal =
+            SyntheticHydroxyapatite::recipe(SynthesisRoute::Hydrothermal, 200.0, 24.0)
+                .unwrap()
+                .characterize();
+        let precipitate =
+            SyntheticHydroxyapatite::recipe(SynthesisRoute::WetPrecipitation, 25.0, 24.0)
+                .unwrap()
+                .characterize();
+        let none = IonicSubstitutions::none();
+        let sharp = XrdPattern::from_characterization(&hydrothermal, &none);
+        let broad = XrdPattern::from_characterization(&precipitate, &none);
+        assert!(sharp.crystallinity_index().unwrap() > 0.8);
+        assert!(broad.crystallinity_index().unwrap() < sharp.crystallinity_index().unwrap() - 0.3);
+
+        // Needles elongated along c give a sharp (002) and broad (310).
+        let bone = XrdPattern::simulate(
+            &HexagonalLattice::hydroxyapatite(),
+            &HydroxyapatiteCrystal::bone_mineral(),
+            1.0,
+        );
+        assert!(bone.peak([0, 0, 2]).unwrap().fwhm_deg < bone.peak([3, 1, 0]).unwrap().fwhm_deg);
+    }
+}
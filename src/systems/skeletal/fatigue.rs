@@ -0,0 +1,324 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::config::BoneMaterial;
+use serde::{Deserialize, Serialize};
+
+// Tensile cortical bone fatigue follows a steep Basquin law in stress
+// normalised by strength, N = (σa/σu)^-k with k near 15.
+// Carter DR, Caler WE, Spengler DM, Frankel VH (1981) Acta Orthop Scand 52:481-490
+const BONE_BASQUIN_EXPONENT: f64 = 15.0;
+// Tensile strength over compressive strength for cortical bone.
+// Reilly DT, Burstein AH (1975) J Biomech 8:393-405
+const BONE_TENSION_TO_COMPRESSION: f64 = 133.0 / 193.0;
+// Secant modulus falls by roughly 15% before a bone specimen breaks.
+// Pattin CA, Caler WE, Carter DR (1996) J Biomech 29:69-79
+const BONE_STIFFNESS_LOSS_AT_FAILURE: f64 = 0.15;
+
+// Tendon life is log-linear in stress: S/UTS = 1.01 - 0.0725 log10 N.
+// Schechtman H, Bader DL (1997) J Biomech 30:829-835
+const TENDON_SN_INTERCEPT: f64 = 1.01;
+const TENDON_SN_SLOPE: f64 = 0.0725;
+const TENDON_UTS_MPA: f64 = 100.0;
+const TENDON_STIFFNESS_LOSS_AT_FAILURE: f64 = 0.3;
+
+// Strength rises with loading rate as rate^0.06; rate is taken
+// proportional to frequency about a 2 Hz reference.
+// Carter DR, Hayes WC (1977) J Bone Joint Surg Am 59:954-962
+const RATE_SENSITIVITY_EXPONENT: f64 = 0.06;
+const REFERENCE_FREQUENCY_HZ: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SnLaw {
+    // N = (σa/σu)^-exponent
+    Basquin { exponent: f64 },
+    // σa/σu = intercept - slope·log10 N
+    SemiLog { intercept: f64, slope: f64 },
+}
+
+impl SnLaw {
+    // Cycles to failure at a normalised amplitude; at or above ultimate
+    // the specimen breaks within its first cycle.
+    fn cycles_to_failure(&self, normalised: f64) -> f64 {
+        if normalised >= 1.0 {
+            return 1.0;
+        }
+        if normalised <= 0.0 {
+            return f64::INFINITY;
+        }
+        match *self {
+            SnLaw::Basquin { exponent } => normalised.powf(-exponent),
+            SnLaw::SemiLog { intercept, slope } => {
+                10f64.powf(((intercept - normalised) / slope).max(0.0))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FatigueMaterial {
+    pub name: String,
+    pub ultimate_stress_mpa: f64,
+    pub law: SnLaw,
+    pub stiffness_loss_at_failure: f64,
+}
+
+impl FatigueMaterial {
+    pub fn from_bone(material: &BoneMaterial) -> BiologyResult<Self> {
+        material.validate()?;
+        Ok(Self {
+            name: material.name.clone(),
+            ultimate_stress_mpa: material.compressive_strength_mpa * BONE_TENSION_TO_COMPRESSION,
+            law: SnLaw::Basquin {
+                exponent: BONE_BASQUIN_EXPONENT,
+            },
+            stiffness_loss_at_failure: BONE_STIFFNESS_LOSS_AT_FAILURE,
+        })
+    }
+
+    pub fn cortical_bone() -> Self {
+        Self::from_bone(&BoneMaterial::cortical()).expect("preset is valid")
+    }
+
+    // Tendon fascicles are nearly pure type I collagen, so this also
+    // stands in for aligned collagen fibre bundles.
+    pub fn tendon() -> Self {
+        Self {
+            name: "tendon".to_string(),
+            ultimate_stress_mpa: TENDON_UTS_MPA,
+            law: SnLaw::SemiLog {
+                intercept: TENDON_SN_INTERCEPT,
+                slope: TENDON_SN_SLOPE,
+            },
+            stiffness_loss_at_failure: TENDON_STIFFNESS_LOSS_AT_FAILURE,
+        }
+    }
+
+    pub fn strength_at_mpa(&self, frequency_hz: f64) -> f64 {
+        self.ultimate_stress_mpa
+            * (frequency_hz / REFERENCE_FREQUENCY_HZ).powf(RATE_SENSITIVITY_EXPONENT)
+    }
+
+    // Goodman mean-stress correction to an equivalent fully reversed
+    // amplitude; a tensile mean shortens life.
+    pub fn cycles_to_failure(&self, block: &LoadBlock) -> f64 {
+        let strength = self.strength_at_mpa(block.frequency_hz);
+        let mean_ratio = (block.mean_stress_mpa / strength).max(0.0);
+        if mean_ratio >= 1.0 {
+            return 1.0;
+        }
+        let equivalent = block.stress_amplitude_mpa / (1.0 - mean_ratio);
+        self.law.cycles_to_failure(equivalent / strength)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LoadBlock {
+    pub stress_amplitude_mpa: f64,
+    pub mean_stress_mpa: f64,
+    pub frequency_hz: f64,
+    pub cycles: u64,
+}
+
+impl LoadBlock {
+    pub fn new(
+        stress_amplitude_mpa: f64,
+        mean_stress_mpa: f64,
+        frequency_hz: f64,
+        cycles: u64,
+    ) -> BiologyResult<Self> {
+        if stress_amplitude_mpa.is_nan() || stress_amplitude_mpa < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "stress amplitude cannot be negative".to_string(),
+            ));
+        }
+        if mean_stress_mpa.is_nan() {
+            return Err(BiologyError::InvalidValue(
+                "mean stress must be a number".to_string(),
+            ));
+        }
+        if frequency_hz.is_nan() || frequency_hz <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "frequency must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            stress_amplitude_mpa,
+            mean_stress_mpa,
+            frequency_hz,
+            cycles,
+        })
+    }
+
+    pub fn fully_reversed(
+        stress_amplitude_mpa: f64,
+        frequency_hz: f64,
+        cycles: u64,
+    ) -> BiologyResult<Self> {
+        Self::new(stress_amplitude_mpa, 0.0, frequency_hz, cycles)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SnPoint {
+    pub stress_amplitude_mpa: f64,
+    pub cycles_to_failure: f64,
+}
+
+// Linear (Palmgren-Miner) damage accumulation under a block program.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FatigueTest {
+    pub material: FatigueMaterial,
+    pub damage: f64,
+    pub cycles_applied: u64,
+    pub elapsed_seconds: f64,
+    pub failed_at_cycle: Option<u64>,
+}
+
+impl FatigueTest {
+    pub fn new(material: FatigueMaterial) -> Self {
+        Self {
+            material,
+            damage: 0.0,
+            cycles_applied: 0,
+            elapsed_seconds: 0.0,
+            failed_at_cycle: None,
+        }
+    }
+
+    pub fn has_failed(&self) -> bool {
+        self.failed_at_cycle.is_some()
+    }
+
+    // Applies a block, stopping at the cycle where damage reaches one.
+    pub fn apply(&mut self, block: &LoadBlock) -> BiologyResult<()> {
+        if self.has_failed() {
+            return Err(BiologyError::InvalidState(
+                "specimen has already failed".to_string(),
+            ));
+        }
+        let life = self.material.cycles_to_failure(block);
+        let remaining = ((1.0 - self.damage) * life).ceil();
+        let cycles = if (block.cycles as f64) < remaining {
+            self.damage += block.cycles as f64 / life;
+            block.cycles
+        } else {
+            self.damage = 1.0;
+            remaining as u64
+        };
+        self.cycles_applied = self.cycles_applied.saturating_add(cycles);
+        self.elapsed_seconds += cycles as f64 / block.frequency_hz;
+        if self.damage >= 1.0 {
+            self.failed_at_cycle = Some(self.cycles_applied);
+        }
+        Ok(())
+    }
+
+    pub fn run_program(&mut self, program: &[LoadBlock]) -> BiologyResult<()> {
+        for block in program {
+            if self.has_failed() {
+                break;
+            }
+            self.apply(block)?;
+        }
+        Ok(())
+    }
+
+    // Miner's-rule cycles left if loading continues at this block.
+    pub fn residual_life_cycles(&self, block: &LoadBlock) -> f64 {
+        (1.0 - self.damage).max(0.0) * self.material.cycles_to_failure(block)
+    }
+
+    // Phenomenological stiffness loss that reaches the material's
+    // end-of-life value at failure, steepening late in life.
+    pub fn relative_stiffness(&self) -> f64 {
+        1.0 - self.material.stiffness_loss_at_failure * self.damage.clamp(0.0, 1.0).powi(2)
+    }
+}
+
+// Wöhler curve from constant-amplitude tests run to failure.
+pub fn sn_curve(
+    material: &FatigueMaterial,
+    stress_amplitudes_mpa: &[f64],
+    frequency_hz: f64,
+) -> BiologyResult<Vec<SnPoint>> {
+    stress_amplitudes_mpa
+        .iter()
+        .map(|&amplitude| {
+            let block = LoadBlock::fully_reversed(amplitude, frequency_hz, u64::MAX)?;
+            let mut test = FatigueTest::new(material.clone());
+            test.apply(&block)?;
+            Ok(SnPoint {
+                stress_amplitude_mpa: amplitude,
+                // Lives beyond the u64 cycle counter fall back to the S-N law.
+                cycles_to_failure: test
+                    .failed_at_cycle
+                    .map_or_else(|| material.cycles_to_failure(&block), |n| n as f64),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bone_sn_curve_is_steep_and_monotone() {
+        let bone = FatigueMaterial::cortical_bone();
+        let amplitudes = [40.0, 55.0, 70.0, 90.0, 120.0];
+        let curve = sn_curve(&bone, &amplitudes, 2.0).unwrap();
+        for pair in curve.windows(2) {
+            assert!(pair[1].cycles_to_failure < pair[0].cycles_to_failure);
+        }
+        // Half of tensile strength lasts tens of thousands of cycles; habitual
+        // strains (~20 MPa) are effectively unlimited.
+        let half = sn_curve(&bone, &[0.5 * bone.ultimate_stress_mpa], 2.0).unwrap()[0];
+        assert!(half.cycles_to_failure > 1.0e4 && half.cycles_to_failure < 1.0e5);
+        let habitual = sn_curve(&bone, &[20.0], 2.0).unwrap()[0];
+        assert!(habitual.cycles_to_failure > 1.0e10);
+    }
+
+    #[test]
+    fn test_tendon_follows_log_linear_law() {
+        let tendon = FatigueMaterial::tendon();
+        let block = LoadBlock::fully_reversed(0.72 * TENDON_UTS_MPA, 2.0, 1).unwrap();
+        let cycles = tendon.cycles_to_failure(&block);
+        assert!((cycles.log10() - 4.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_miner_rule_accumulates_across_blocks() {
+        let bone = FatigueMaterial::cortical_bone();
+        let high = LoadBlock::fully_reversed(80.0, 2.0, 1).unwrap();
+        let low = LoadBlock::fully_reversed(60.0, 2.0, 1).unwrap();
+        let n_high = bone.cycles_to_failure(&high);
+        let n_low = bone.cycles_to_failure(&low);
+        let mut test = FatigueTest::new(bone);
+        test.apply(&LoadBlock {
+            cycles: (0.4 * n_high) as u64,
+            ..high
+        })
+        .unwrap();
+        assert!((test.damage - 0.4).abs() < 1e-3);
+        assert!((test.residual_life_cycles(&low) / n_low - 0.6).abs() < 1e-3);
+        assert!(test.relative_stiffness() < 1.0);
+        test.apply(&LoadBlock {
+            cycles: u64::MAX,
+            ..low
+        })
+        .unwrap();
+        let failed = test.failed_at_cycle.unwrap() as f64;
+        assert!((failed - (0.4 * n_high + 0.6 * n_low)).abs() / failed < 1e-3);
+        assert!(test.apply(&low).is_err());
+    }
+
+    #[test]
+    fn test_mean_stress_and_frequency_shift_life() {
+        let bone = FatigueMaterial::cortical_bone();
+        let reversed = LoadBlock::fully_reversed(60.0, 2.0, 1).unwrap();
+        let tensile_mean = LoadBlock::new(60.0, 30.0, 2.0, 1).unwrap();
+        assert!(bone.cycles_to_failure(&tensile_mean) < bone.cycles_to_failure(&reversed));
+        let fast = LoadBlock::fully_reversed(60.0, 20.0, 1).unwrap();
+        assert!(bone.cycles_to_failure(&fast) > bone.cycles_to_failure(&reversed));
+        assert!(LoadBlock::fully_reversed(60.0, 0.0, 1).is_err());
+    }
+}
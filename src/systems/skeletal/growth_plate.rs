@@ -0,0 +1,238 @@
+use crate::biology::endocrine::signals::REFERENCE_ESTRADIOL_PG_ML;
+use crate::biology::endocrine::EndocrineSignals;
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Endochondral growth at one physis, per chondrocyte column: resting-zone
+// reserve feeds a proliferative zone whose daughters enlarge in the
+// hypertrophic zone and are replaced by bone at the mineralization front.
+// Length gain is the rate cells leave hypertrophy times their final height.
+// Hunziker EB, Schenk RK (1989) J Physiol 414:55-71, PMID 2607442
+// Human columns hold a few tens of flattened proliferating cells.
+// Kember NF, Sissons HA (1976) J Bone Joint Surg Br 58:426-435, PMID 1018027
+const PROLIFERATIVE_CELLS_AT_FULL_RESERVE: f64 = 20.0;
+const PROLIFERATIVE_CELL_HEIGHT_UM: f64 = 8.0;
+const HYPERTROPHIC_CELL_HEIGHT_UM: f64 = 30.0;
+const HYPERTROPHY_DAYS: f64 = 5.0;
+const DIVISION_RATE_PER_DAY: f64 = 0.065;
+// GH acts on resting and proliferating cells directly and through local and
+// circulating IGF-1; IGF-1 also sets hypertrophic cell size. Split so that
+// growth goes as the square root of the IGF-1 ratio, which lets the low
+// IGF-1 of GH deficiency roughly halve height velocity.
+// Isaksson OG, Lindahl A, Nilsson A, Isgaard J (1987) Endocr Rev 8:426-438, PMID 3319530
+// Wang J, Zhou J, Bondy CA (1999) FASEB J 13:1985-1990, PMID 10544181
+const IGF1_PROLIFERATION_EXPONENT: f64 = 0.35;
+const IGF1_HYPERTROPHY_EXPONENT: f64 = 0.15;
+// Resting-zone progenitors have a finite proliferative capacity spent with
+// each division, so growth slows through childhood; estrogen speeds the
+// exhaustion and the physis fuses once the reserve is gone.
+// Nilsson O, Baron J (2004) Trends Endocrinol Metab 15:370-374, PMID 15380808
+// Weise M et al. (2001) Proc Natl Acad Sci USA 98:6871-6876, PMID 11381135
+const RESERVE_SPENT_PER_DIVISION: f64 = 0.8e-4;
+const ESTROGEN_SENESCENCE_GAIN: f64 = 12.0;
+// Low pubertal estradiol mainly speeds growth; senescence takes hold as
+// levels approach adult values.
+// Cutler GB Jr (1997) J Steroid Biochem Mol Biol 61:141-144, PMID 9365183
+const ESTROGEN_SENESCENCE_EXPONENT: f64 = 2.0;
+const CLOSURE_RESERVE: f64 = 0.1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GrowthPlate {
+    // Remaining proliferative capacity, 1 in early childhood.
+    pub reserve: f64,
+    pub hypertrophic_cells: f64,
+    // Advance of the mineralization front, i.e. longitudinal growth.
+    pub length_gain_mm: f64,
+    pub closed: bool,
+    pub days: f64,
+    // Scales column output to the site's share of limb growth.
+    pub column_density: f64,
+}
+
+impl GrowthPlate {
+    pub fn new(reserve: f64, column_density: f64) -> BiologyResult<Self> {
+        if reserve.is_nan() || reserve <= 0.0 || reserve > 1.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "growth-plate reserve must lie in (0, 1], got {}",
+                reserve
+            )));
+        }
+        if column_density.is_nan() || column_density <= 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "column density must be positive, got {}",
+                column_density
+            )));
+        }
+        let mut plate = Self {
+            reserve,
+            hypertrophic_cells: 0.0,
+            length_gain_mm: 0.0,
+            closed: reserve < CLOSURE_RESERVE,
+            days: 0.0,
+            column_density,
+        };
+        plate.hypertrophic_cells = plate.entering_hypertrophy(1.0) * HYPERTROPHY_DAYS;
+        Ok(plate)
+    }
+
+    // Distal femur and proximal tibia supply ~70% and ~55% of their
+    // bones' growth, about 10 and 6 mm a year in mid-childhood.
+    // Anderson M, Green WT, Messner MB (1963) J Bone Joint Surg Am 45:1-14, PMID 14040773
+    pub fn distal_femur() -> Self {
+        Self::new(1.0, 1.0).expect("valid preset")
+    }
+
+    pub fn proximal_tibia() -> Self {
+        Self::new(1.0, 0.6).expect("valid preset")
+    }
+
+    pub fn proliferative_cells(&self) -> f64 {
+        if self.closed {
+            0.0
+        } else {
+            PROLIFERATIVE_CELLS_AT_FULL_RESERVE * self.reserve
+        }
+    }
+
+    fn entering_hypertrophy(&self, igf1_ratio: f64) -> f64 {
+        self.proliferative_cells()
+            * DIVISION_RATE_PER_DAY
+            * igf1_ratio.max(0.0).powf(IGF1_PROLIFERATION_EXPONENT)
+    }
+
+    fn hypertrophic_cell_height_um(igf1_ratio: f64) -> f64 {
+        HYPERTROPHIC_CELL_HEIGHT_UM * igf1_ratio.max(0.0).powf(IGF1_HYPERTROPHY_EXPONENT)
+    }
+
+    // Height of the proliferative and hypertrophic zones of one column.
+    pub fn column_height_um(&self, signals: &EndocrineSignals) -> f64 {
+        self.proliferative_cells() * PROLIFERATIVE_CELL_HEIGHT_UM
+            + self.hypertrophic_cells * Self::hypertrophic_cell_height_um(signals.igf1_ratio())
+    }
+
+    // Growth rate once the hypertrophic zone has settled at these signals.
+    pub fn growth_velocity_mm_per_year(&self, signals: &EndocrineSignals) -> f64 {
+        let igf1 = signals.igf1_ratio();
+        self.entering_hypertrophy(igf1) * Self::hypertrophic_cell_height_um(igf1) * 365.25 / 1000.0
+            * self.column_density
+    }
+
+    pub fn step_day(&mut self, signals: &EndocrineSignals) {
+        let igf1 = signals.igf1_ratio();
+        let entering = self.entering_hypertrophy(igf1);
+        let completing = self.hypertrophic_cells / HYPERTROPHY_DAYS;
+        self.hypertrophic_cells += entering - completing;
+        self.length_gain_mm +=
+            completing * Self::hypertrophic_cell_height_um(igf1) / 1000.0 * self.column_density;
+
+        let estrogen = signals.estradiol_pg_ml.max(0.0) / REFERENCE_ESTRADIOL_PG_ML;
+        self.reserve -= RESERVE_SPENT_PER_DIVISION
+            * entering
+            * (1.0 + ESTROGEN_SENESCENCE_GAIN * estrogen.powf(ESTROGEN_SENESCENCE_EXPONENT));
+        if !self.closed && self.reserve < CLOSURE_RESERVE {
+            self.closed = true;
+        }
+        self.days += 1.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::endocrine::{FeedbackAxis, SomatotropicAxis};
+
+    // Girl from age 4: IGF-1 rises through childhood and peaks at ~13,
+    // estradiol climbs from 10 to adult levels by 15.
+    fn girl(age: f64) -> EndocrineSignals {
+        let igf1 = if age < 10.0 {
+            100.0 + 12.0 * (age - 4.0)
+        } else if age < 13.0 {
+            172.0 + 76.0 * (age - 10.0)
+        } else {
+            (400.0 - 50.0 * (age - 13.0)).max(250.0)
+        };
+        let estradiol = 5.0 + 18.0 * (age - 10.0).clamp(0.0, 5.0);
+        EndocrineSignals {
+            igf1_ng_ml: igf1,
+            estradiol_pg_ml: estradiol,
+            ..EndocrineSignals::default()
+        }
+    }
+
+    // Yearly length gain from age 4, stopping early once the physis fuses.
+    fn grow(
+        plate: &mut GrowthPlate,
+        years: usize,
+        signals: impl Fn(f64) -> EndocrineSignals,
+    ) -> Vec<f64> {
+        (0..years)
+            .map(|year| {
+                let before = plate.length_gain_mm;
+                for day in 0..365 {
+                    plate.step_day(&signals(4.0 + year as f64 + day as f64 / 365.0));
+                }
+                plate.length_gain_mm - before
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pubertal_spurt_then_closure() {
+        let mut plate = GrowthPlate::distal_femur();
+        let yearly = grow(&mut plate, 16, girl);
+        // ~10 mm a year in mid-childhood, peaking at 11-13.
+        assert!((8.0..12.0).contains(&yearly[3]), "{:?}", yearly);
+        let peak = (0..yearly.len())
+            .max_by(|&a, &b| yearly[a].total_cmp(&yearly[b]))
+            .unwrap();
+        assert!((7..=9).contains(&peak), "peak year {peak}");
+        // Distal femoral physes of girls fuse at about 15-16.
+        assert!(plate.closed);
+        let fused_year = yearly.iter().position(|&v| v == 0.0).unwrap();
+        assert!((11..=13).contains(&fused_year), "{:?}", yearly);
+        assert_eq!(plate.proliferative_cells(), 0.0);
+    }
+
+    #[test]
+    fn test_gh_igf1_and_estrogen_set_rate_and_closure() {
+        let mut deficient = SomatotropicAxis::gh_deficient(0.2);
+        deficient.run_hours(480.0, 0.5);
+        let mut signals = EndocrineSignals::default();
+        deficient.publish(&mut signals);
+        let plate = GrowthPlate::distal_femur();
+        let normal = plate.growth_velocity_mm_per_year(&EndocrineSignals::default());
+        let untreated = plate.growth_velocity_mm_per_year(&signals);
+        assert!(untreated < 0.75 * normal, "{untreated} vs {normal}");
+        deficient.exogenous_gh_ng_ml = 1.0;
+        deficient.run_hours(480.0, 0.5);
+        deficient.publish(&mut signals);
+        assert!(plate.growth_velocity_mm_per_year(&signals) > 0.95 * normal);
+
+        // Without estrogen the physes stay open into adulthood, as in
+        // estrogen-resistant and aromatase-deficient men.
+        // Smith EP et al. (1994) N Engl J Med 331:1056-1061, PMID 8090165
+        let mut unfused = GrowthPlate::distal_femur();
+        let yearly = grow(&mut unfused, 16, |age| EndocrineSignals {
+            estradiol_pg_ml: 0.0,
+            ..girl(age)
+        });
+        assert!(!unfused.closed);
+        assert!(yearly[15] > 1.0);
+    }
+
+    #[test]
+    fn test_columns_and_validation() {
+        let signals = EndocrineSignals::default();
+        let femur = GrowthPlate::distal_femur();
+        let tibia = GrowthPlate::proximal_tibia();
+        assert!(
+            tibia.growth_velocity_mm_per_year(&signals)
+                < femur.growth_velocity_mm_per_year(&signals)
+        );
+        let older = GrowthPlate::new(0.4, 1.0).unwrap();
+        assert!(older.column_height_um(&signals) < femur.column_height_um(&signals));
+        assert!(GrowthPlate::new(0.0, 1.0).is_err());
+        assert!(GrowthPlate::new(1.0, -1.0).is_err());
+        assert!(GrowthPlate::new(0.05, 1.0).unwrap().closed);
+    }
+}
@@ -0,0 +1,47 @@
+pub mod biomaterials;
+pub mod bioreactor;
+pub mod collagen;
+pub mod crystallography;
+pub mod disuse;
+pub mod fatigue;
+pub mod growth_plate;
+pub mod hydroxyapatite;
+pub mod implant;
+pub mod micro_fe;
+pub mod nanoindentation;
+pub mod remodeling;
+pub mod spectroscopy;
+pub mod synthesis;
+pub mod testing;
+pub mod viscoelastic;
+
+pub use biomaterials::{
+    hydroxyapatite_solubility_mm, BioactivityAssessment, DrugLoadedScaffold, DrugSorption,
+    ImplantSurface, ScaffoldDesign, ScaffoldRequirements, SimulatedBodyFluid,
+};
+pub use bioreactor::Bioreactor;
+pub use collagen::CollagenDenaturation;
+pub use crystallography::{HexagonalLattice, IonicSubstitutions, UnitCell, XrdPattern, XrdPeak};
+pub use disuse::{DisuseKind, DisuseScenario, DisuseTrajectory};
+pub use fatigue::{sn_curve, FatigueMaterial, FatigueTest, LoadBlock, SnLaw, SnPoint};
+pub use growth_plate::GrowthPlate;
+pub use hydroxyapatite::{langmuir_occupancy, HydroxyapatiteCrystal};
+pub use implant::Implant;
+pub use micro_fe::{CompressionResult, MicroFeModel};
+pub use nanoindentation::{
+    IndentationMap, IndentationProtocol, LoadDisplacementCurve, MatrixProperties,
+    MineralizationField, OliverPharrResult,
+};
+pub use remodeling::{
+    BoneRemodelingBuilder, BoneRemodelingModel, RemodelingModifiers, RemodelingPhases,
+};
+pub use spectroscopy::{BoneMatrix, FtirMetrics, RamanMetrics, SpectralBand, Technique};
+pub use synthesis::{
+    HydroxyapatiteCharacterization, QualityTargets, SynthesisParameters, SynthesisRoute,
+    SyntheticHydroxyapatite,
+};
+pub use testing::{
+    run_test, BoneTissueLaw, MechanicalTest, MechanicalTestResult, StressStrainBranch,
+    TubularSection,
+};
+pub use viscoelastic::{fit_creep, fit_relaxation, log_spaced_times, PronySeries, PronyTerm};
@@ -0,0 +1,402 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::skeletal::spectroscopy::BoneMatrix;
+use serde::{Deserialize, Serialize};
+
+// Phase densities for mass-to-volume conversion, g/cm³.
+const BONE_MINERAL_DENSITY: f64 = 3.0;
+const COLLAGEN_DENSITY: f64 = 1.41;
+const WATER_DENSITY: f64 = 1.0;
+
+// Lamellar bone indents at E ≈ 22 GPa and H ≈ 0.65 GPa near 44% mineral
+// by volume; both rise steeply with local mineralisation.
+// Rho JY, Tsui TY, Pharr GM (1997) Biomaterials 18:1325-1330
+// Mulder L et al. (2007) Bone 41:256-265
+const REFERENCE_MINERAL_VOLUME: f64 = 0.44;
+const REFERENCE_MODULUS_GPA: f64 = 22.0;
+const REFERENCE_HARDNESS_GPA: f64 = 0.65;
+const MODULUS_MINERAL_EXPONENT: f64 = 2.5;
+const HARDNESS_MINERAL_EXPONENT: f64 = 2.0;
+
+// Berkovich diamond tip and Oliver-Pharr constants.
+// Oliver WC, Pharr GM (1992) J Mater Res 7:1564-1583
+const DIAMOND_MODULUS_GPA: f64 = 1141.0;
+const DIAMOND_POISSON: f64 = 0.07;
+const BERKOVICH_AREA_COEFFICIENT: f64 = 24.5;
+const BERKOVICH_BETA: f64 = 1.034;
+const GEOMETRY_EPSILON: f64 = 0.75;
+const PARABOLOID_UNLOADING_EXPONENT: f64 = 1.5;
+// Oliver-Pharr fit the upper part of the unloading branch.
+const UNLOADING_FIT_FROM: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MatrixProperties {
+    pub elastic_modulus_gpa: f64,
+    pub poisson_ratio: f64,
+    pub hardness_gpa: f64,
+}
+
+impl MatrixProperties {
+    pub fn new(
+        elastic_modulus_gpa: f64,
+        poisson_ratio: f64,
+        hardness_gpa: f64,
+    ) -> BiologyResult<Self> {
+        if elastic_modulus_gpa.is_nan() || elastic_modulus_gpa <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "elastic modulus must be positive".to_string(),
+            ));
+        }
+        if hardness_gpa.is_nan() || hardness_gpa <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "hardness must be positive".to_string(),
+            ));
+        }
+        if poisson_ratio.is_nan() || !(0.0..0.5).contains(&poisson_ratio) {
+            return Err(BiologyError::InvalidValue(
+                "Poisson ratio must lie in [0, 0.5)".to_string(),
+            ));
+        }
+        Ok(Self {
+            elastic_modulus_gpa,
+            poisson_ratio,
+            hardness_gpa,
+        })
+    }
+
+    pub fn from_mineral_volume_fraction(fraction: f64) -> Self {
+        let relative = fraction.max(0.0) / REFERENCE_MINERAL_VOLUME;
+        Self {
+            elastic_modulus_gpa: REFERENCE_MODULUS_GPA * relative.powf(MODULUS_MINERAL_EXPONENT),
+            poisson_ratio: 0.3,
+            hardness_gpa: REFERENCE_HARDNESS_GPA * relative.powf(HARDNESS_MINERAL_EXPONENT),
+        }
+    }
+
+    pub fn from_bone_matrix(matrix: &BoneMatrix) -> Self {
+        Self::from_mineral_volume_fraction(mineral_volume_fraction(matrix))
+    }
+
+    // Tip-sample reduced modulus, 1/Er = (1-ν²)/E + (1-νi²)/Ei.
+    pub fn reduced_modulus_gpa(&self) -> f64 {
+        1.0 / ((1.0 - self.poisson_ratio.powi(2)) / self.elastic_modulus_gpa
+            + (1.0 - DIAMOND_POISSON * DIAMOND_POISSON) / DIAMOND_MODULUS_GPA)
+    }
+}
+
+pub fn mineral_volume_fraction(matrix: &BoneMatrix) -> f64 {
+    let mineral = matrix.mineral_fraction / BONE_MINERAL_DENSITY;
+    let organic = matrix.organic_fraction / COLLAGEN_DENSITY;
+    let water = matrix.water_fraction / WATER_DENSITY;
+    mineral / (mineral + organic + water)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct IndentationProtocol {
+    pub peak_load_mn: f64,
+    pub points_per_segment: usize,
+}
+
+impl IndentationProtocol {
+    pub fn new(peak_load_mn: f64, points_per_segment: usize) -> BiologyResult<Self> {
+        if peak_load_mn.is_nan() || peak_load_mn <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "peak load must be positive".to_string(),
+            ));
+        }
+        if points_per_segment < 10 {
+            return Err(BiologyError::InvalidValue(
+                "need at least 10 points per segment".to_string(),
+            ));
+        }
+        Ok(Self {
+            peak_load_mn,
+            points_per_segment,
+        })
+    }
+}
+
+impl Default for IndentationProtocol {
+    // 2 mN keeps contact depths of a few hundred nm, inside one lamella.
+    fn default() -> Self {
+        Self {
+            peak_load_mn: 2.0,
+            points_per_segment: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoadDisplacementCurve {
+    // (depth nm, load mN)
+    pub loading: Vec<(f64, f64)>,
+    pub unloading: Vec<(f64, f64)>,
+}
+
+impl LoadDisplacementCurve {
+    // Kick's-law loading to the depth set by hardness, then the elastic
+    // paraboloid unloading whose initial slope the reduced modulus fixes.
+    pub fn simulate(material: &MatrixProperties, protocol: &IndentationProtocol) -> Self {
+        let p_max = protocol.peak_load_mn;
+        // mN / GPa = 1e-12 m² = 1e6 nm²
+        let area_nm2 = p_max / material.hardness_gpa * 1.0e6;
+        let contact_depth = (area_nm2 / BERKOVICH_AREA_COEFFICIENT).sqrt();
+        // GPa·nm = 1e-3 mN/nm
+        let stiffness = 2.0 / std::f64::consts::PI.sqrt()
+            * BERKOVICH_BETA
+            * material.reduced_modulus_gpa()
+            * area_nm2.sqrt()
+            * 1.0e-3;
+        let h_max = contact_depth + GEOMETRY_EPSILON * p_max / stiffness;
+        let m = PARABOLOID_UNLOADING_EXPONENT;
+        let h_final = h_max - m * p_max / stiffness;
+        let n = protocol.points_per_segment;
+        let loading = (0..=n)
+            .map(|i| {
+                let h = h_max * i as f64 / n as f64;
+                (h, p_max * (h / h_max).powi(2))
+            })
+            .collect();
+        let unloading = (0..=n)
+            .map(|i| {
+                let h = h_max - (h_max - h_final) * i as f64 / n as f64;
+                (h, p_max * ((h - h_final) / (h_max - h_final)).powf(m))
+            })
+            .collect();
+        Self { loading, unloading }
+    }
+
+    pub fn peak(&self) -> (f64, f64) {
+        self.unloading[0]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OliverPharrResult {
+    pub contact_stiffness_mn_per_nm: f64,
+    pub contact_depth_nm: f64,
+    pub contact_area_nm2: f64,
+    pub reduced_modulus_gpa: f64,
+    pub indentation_modulus_gpa: f64,
+    pub hardness_gpa: f64,
+}
+
+// Fits P = α(h − hf)^m to the upper unloading branch, searching hf and
+// regressing in log space, then applies the Oliver-Pharr relations.
+pub fn oliver_pharr(
+    curve: &LoadDisplacementCurve,
+    sample_poisson_ratio: f64,
+) -> BiologyResult<OliverPharrResult> {
+    let (h_max, p_max) = curve.peak();
+    if p_max <= 0.0 || h_max <= 0.0 {
+        return Err(BiologyError::InvalidState(
+            "unloading curve has no peak".to_string(),
+        ));
+    }
+    let fit: Vec<(f64, f64)> = curve
+        .unloading
+        .iter()
+        .copied()
+        .filter(|&(_, p)| p >= UNLOADING_FIT_FROM * p_max)
+        .collect();
+    let h_floor = curve
+        .unloading
+        .iter()
+        .map(|&(h, _)| h)
+        .fold(f64::INFINITY, f64::min);
+    let h_top = fit.iter().map(|&(h, _)| h).fold(f64::INFINITY, f64::min);
+    if fit.len() < 3 {
+        return Err(BiologyError::InvalidState(
+            "too few unloading points to fit".to_string(),
+        ));
+    }
+
+    let regress = |h_final: f64| -> (f64, f64, f64) {
+        let pts: Vec<(f64, f64)> = fit
+            .iter()
+            .map(|&(h, p)| ((h - h_final).ln(), p.ln()))
+            .collect();
+        let n = pts.len() as f64;
+        let mx = pts.iter().map(|p| p.0).sum::<f64>() / n;
+        let my = pts.iter().map(|p| p.1).sum::<f64>() / n;
+        let sxy: f64 = pts.iter().map(|p| (p.0 - mx) * (p.1 - my)).sum();
+        let sxx: f64 = pts.iter().map(|p| (p.0 - mx).powi(2)).sum();
+        let m = sxy / sxx.max(1e-300);
+        let ln_alpha = my - m * mx;
+        let sse: f64 = pts.iter().map(|p| (p.1 - ln_alpha - m * p.0).powi(2)).sum();
+        (sse, m, ln_alpha.exp())
+    };
+    let lower = h_floor - 0.5 * (h_max - h_floor);
+    let upper = h_top - 1e-6 * h_max;
+    let mut best = (f64::INFINITY, 0.0, 0.0, 0.0);
+    for i in 0..=400 {
+        let h_final = lower + (upper - lower) * i as f64 / 400.0;
+        let (sse, m, alpha) = regress(h_final);
+        if sse < best.0 {
+            best = (sse, m, alpha, h_final);
+        }
+    }
+    let (_, m, alpha, h_final) = best;
+    let stiffness = m * alpha * (h_max - h_final).powf(m - 1.0);
+    let contact_depth = h_max - GEOMETRY_EPSILON * p_max / stiffness;
+    let area = BERKOVICH_AREA_COEFFICIENT * contact_depth * contact_depth;
+    let reduced =
+        std::f64::consts::PI.sqrt() / (2.0 * BERKOVICH_BETA) * stiffness / area.sqrt() * 1.0e3;
+    let tip = (1.0 - DIAMOND_POISSON * DIAMOND_POISSON) / DIAMOND_MODULUS_GPA;
+    let indentation_modulus = (1.0 - sample_poisson_ratio.powi(2)) / (1.0 / reduced - tip);
+    Ok(OliverPharrResult {
+        contact_stiffness_mn_per_nm: stiffness,
+        contact_depth_nm: contact_depth,
+        contact_area_nm2: area,
+        reduced_modulus_gpa: reduced,
+        indentation_modulus_gpa: indentation_modulus,
+        hardness_gpa: p_max / area * 1.0e6,
+    })
+}
+
+// Mineral volume fraction on a regular grid; NaN marks pores.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MineralizationField {
+    pub width: usize,
+    pub height: usize,
+    pub spacing_um: f64,
+    pub mineral_volume_fraction: Vec<f64>,
+}
+
+impl MineralizationField {
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        spacing_um: f64,
+        mut f: impl FnMut(f64, f64) -> f64,
+    ) -> BiologyResult<Self> {
+        if width == 0 || height == 0 || spacing_um.is_nan() || spacing_um <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "field needs positive dimensions and spacing".to_string(),
+            ));
+        }
+        let mineral_volume_fraction = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x as f64 * spacing_um, y as f64 * spacing_um))
+            .collect();
+        Ok(Self {
+            width,
+            height,
+            spacing_um,
+            mineral_volume_fraction,
+        })
+    }
+
+    // One secondary osteon in interstitial bone. Young osteons are less
+    // mineralised than the older interstitial matrix, and mineral rises
+    // outwards from the Haversian canal.
+    // Gourion-Arsiquaud S et al. (2009) J Bone Miner Res 24:1271-1281
+    pub fn osteon(size: usize, spacing_um: f64) -> BiologyResult<Self> {
+        let centre = (size as f64 - 1.0) * spacing_um / 2.0;
+        let canal_radius = 25.0;
+        let osteon_radius = 100.0;
+        Self::from_fn(size, size, spacing_um, |x, y| {
+            let r = ((x - centre).powi(2) + (y - centre).powi(2)).sqrt();
+            if r < canal_radius {
+                f64::NAN
+            } else if r < osteon_radius {
+                0.36 + 0.06 * (r - canal_radius) / (osteon_radius - canal_radius)
+            } else {
+                0.46
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndentationMap {
+    pub width: usize,
+    pub height: usize,
+    pub indentation_modulus_gpa: Vec<Option<f64>>,
+    pub hardness_gpa: Vec<Option<f64>>,
+}
+
+impl IndentationMap {
+    pub fn indent(
+        field: &MineralizationField,
+        protocol: &IndentationProtocol,
+    ) -> BiologyResult<Self> {
+        let mut modulus = Vec::with_capacity(field.mineral_volume_fraction.len());
+        let mut hardness = Vec::with_capacity(field.mineral_volume_fraction.len());
+        for &fraction in &field.mineral_volume_fraction {
+            if fraction.is_nan() || fraction <= 0.0 {
+                modulus.push(None);
+                hardness.push(None);
+                continue;
+            }
+            let material = MatrixProperties::from_mineral_volume_fraction(fraction);
+            let curve = LoadDisplacementCurve::simulate(&material, protocol);
+            let result = oliver_pharr(&curve, material.poisson_ratio)?;
+            modulus.push(Some(result.indentation_modulus_gpa));
+            hardness.push(Some(result.hardness_gpa));
+        }
+        Ok(Self {
+            width: field.width,
+            height: field.height,
+            indentation_modulus_gpa: modulus,
+            hardness_gpa: hardness,
+        })
+    }
+
+    pub fn at(&self, x: usize, y: usize) -> Option<(f64, f64)> {
+        let i = y * self.width + x;
+        Some((self.indentation_modulus_gpa[i]?, self.hardness_gpa[i]?))
+    }
+
+    pub fn mean_modulus_gpa(&self) -> f64 {
+        let values: Vec<f64> = self
+            .indentation_modulus_gpa
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        values.iter().sum::<f64>() / values.len().max(1) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_oliver_pharr_recovers_input_properties() {
+        let material = MatrixProperties::new(20.0, 0.3, 0.6).unwrap();
+        let curve = LoadDisplacementCurve::simulate(&material, &IndentationProtocol::default());
+        let result = oliver_pharr(&curve, 0.3).unwrap();
+        assert!((result.indentation_modulus_gpa - 20.0).abs() < 0.2);
+        assert!((result.hardness_gpa - 0.6).abs() < 0.006);
+        // Contact depths of a few hundred nm at 2 mN.
+        assert!(result.contact_depth_nm > 200.0 && result.contact_depth_nm < 600.0);
+        assert!(MatrixProperties::new(20.0, 0.6, 0.6).is_err());
+    }
+
+    #[test]
+    fn test_cortical_matrix_indents_like_lamellar_bone() {
+        let bone = BoneMatrix::cortical_adult();
+        let fraction = mineral_volume_fraction(&bone);
+        assert!(fraction > 0.4 && fraction < 0.48);
+        let material = MatrixProperties::from_bone_matrix(&bone);
+        assert!(material.elastic_modulus_gpa > 18.0 && material.elastic_modulus_gpa < 28.0);
+        assert!(material.hardness_gpa > 0.5 && material.hardness_gpa < 0.9);
+        let young = MatrixProperties::from_bone_matrix(&BoneMatrix::newly_formed());
+        assert!(young.elastic_modulus_gpa < material.elastic_modulus_gpa / 2.0);
+    }
+
+    #[test]
+    fn test_osteon_map_resolves_mineralisation_gradient() {
+        let field = MineralizationField::osteon(41, 5.0).unwrap();
+        let map = IndentationMap::indent(&field, &IndentationProtocol::default()).unwrap();
+        assert!(map.at(20, 20).is_none());
+        let (osteonal, _) = map.at(26, 20).unwrap();
+        let (outer_osteonal, _) = map.at(38, 20).unwrap();
+        let (interstitial, interstitial_h) = map.at(0, 0).unwrap();
+        assert!(osteonal < outer_osteonal && outer_osteonal < interstitial);
+        assert!(interstitial_h > 0.65);
+        assert!(map.mean_modulus_gpa() > osteonal && map.mean_modulus_gpa() < interstitial);
+        assert!(MineralizationField::osteon(0, 5.0).is_err());
+    }
+}
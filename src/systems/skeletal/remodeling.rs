@@ -0,0 +1,540 @@
+use crate::biology::endocrine::EndocrineSignals;
+use crate::biology::signaling::{TgfBetaSignaling, WntSignaling};
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+// Cancellous BMU phase durations in days.
+// Eriksen EF (1986) Endocr Rev 7:379-408, PMID 3536460
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RemodelingPhases {
+    pub resorption_days: f64,
+    pub reversal_days: f64,
+    pub formation_days: f64,
+}
+
+impl RemodelingPhases {
+    pub fn cancellous_eriksen_1986() -> Self {
+        Self {
+            resorption_days: 43.0,
+            reversal_days: 9.0,
+            formation_days: 145.0,
+        }
+    }
+
+    pub fn sigma_days(&self) -> f64 {
+        self.resorption_days + self.reversal_days + self.formation_days
+    }
+}
+
+impl Default for RemodelingPhases {
+    fn default() -> Self {
+        Self::cancellous_eriksen_1986()
+    }
+}
+
+// Coupling of osteoblast Wnt activity to BMU refill and activation,
+// calibrated against the romosozumab response in FRAME (see bone_agents).
+const WNT_FORMATION_GAIN: f64 = 0.6;
+const WNT_OPG_EXPONENT: f64 = 1.0;
+
+// Pharmacological or disease multipliers applied on top of the baseline site.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RemodelingModifiers {
+    pub activation_frequency_factor: f64,
+    pub resorption_depth_factor: f64,
+    pub formation_factor: f64,
+}
+
+impl RemodelingModifiers {
+    pub fn none() -> Self {
+        Self {
+            activation_frequency_factor: 1.0,
+            resorption_depth_factor: 1.0,
+            formation_factor: 1.0,
+        }
+    }
+
+    // Sex-steroid loss up to doubles activation frequency (Recker 2004, see
+    // postmenopausal_spine); PTH scales activation; glucocorticoid excess
+    // induces osteoblast apoptosis.
+    // Weinstein RS et al. (1998) J Clin Invest 102:274-282, PMID 9664068
+    pub fn from_endocrine(signals: &EndocrineSignals) -> Self {
+        let estrogen_deficit = 1.0 - signals.sex_steroid_bone_protection().clamp(0.0, 1.0);
+        Self {
+            activation_frequency_factor: signals.pth_ratio().max(0.0) * (1.0 + estrogen_deficit),
+            resorption_depth_factor: 1.0,
+            formation_factor: 1.0 / signals.glucocorticoid_ratio().max(1.0),
+        }
+    }
+
+    // β-catenin drives osteoblast differentiation and matrix output, and
+    // raises osteoblast OPG so fewer osteoclasts form.
+    // Glass DA et al. (2005) Dev Cell 8:751-764
+    pub fn from_wnt(wnt: &WntSignaling) -> Self {
+        let activity = wnt.beta_catenin_activity();
+        Self {
+            activation_frequency_factor: activity.powf(-WNT_OPG_EXPONENT),
+            resorption_depth_factor: 1.0,
+            formation_factor: (1.0 + WNT_FORMATION_GAIN * (activity - 1.0)).max(0.0),
+        }
+    }
+
+    pub fn combine(&self, other: &RemodelingModifiers) -> Self {
+        Self {
+            activation_frequency_factor: self.activation_frequency_factor
+                * other.activation_frequency_factor,
+            resorption_depth_factor: self.resorption_depth_factor * other.resorption_depth_factor,
+            formation_factor: self.formation_factor * other.formation_factor,
+        }
+    }
+}
+
+impl Default for RemodelingModifiers {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+// Population BMU model: each day's resorbed volume is refilled over the
+// formation period after the reversal lag, so changes in activation
+// frequency open or close the remodeling space (the remodeling transient).
+// Heaney RP (1994) J Bone Miner Res 9:1515-1523, PMID 7817796
+// The refill is sized by the osteoblasts that matrix TGF-β released during
+// resorption recruits, and paced by their collagen output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoneRemodelingModel {
+    pub phases: RemodelingPhases,
+    pub turnover_per_year: f64,
+    pub activation_frequency_factor: f64,
+    pub bmu_balance_fraction: f64,
+    pub bone_mass_fraction: f64,
+    pub modifiers: RemodelingModifiers,
+    pub coupling: TgfBetaSignaling,
+    pub elapsed_days: f64,
+    formation_schedule: VecDeque<f64>,
+}
+
+impl BoneRemodelingModel {
+    pub fn new(
+        turnover_per_year: f64,
+        activation_frequency_factor: f64,
+        bmu_balance_fraction: f64,
+    ) -> BiologyResult<Self> {
+        if turnover_per_year <= 0.0 || activation_frequency_factor <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "turnover and activation frequency must be positive".to_string(),
+            ));
+        }
+        if bmu_balance_fraction <= -1.0 {
+            return Err(BiologyError::InvalidParameter(
+                "BMU balance cannot remove more than the resorbed volume".to_string(),
+            ));
+        }
+        let mut model = Self {
+            phases: RemodelingPhases::default(),
+            turnover_per_year,
+            activation_frequency_factor,
+            bmu_balance_fraction,
+            bone_mass_fraction: 1.0,
+            modifiers: RemodelingModifiers::none(),
+            coupling: TgfBetaSignaling::new(),
+            elapsed_days: 0.0,
+            formation_schedule: VecDeque::new(),
+        };
+        model.prefill_steady_schedule();
+        Ok(model)
+    }
+
+    // Adult cancellous (spine) turnover ~25%/yr.
+    // Parfitt AM (2002) Bone 30:5-7, PMID 11792557
+    pub fn healthy_adult_spine() -> Self {
+        Self::new(0.25, 1.0, 0.0).expect("valid preset")
+    }
+
+    // Whole skeleton: ~80% cortical at ~3%/yr plus ~20% cancellous at ~25%/yr.
+    // Parfitt AM (2002) Bone 30:5-7, PMID 11792557
+    pub fn whole_adult_skeleton() -> Self {
+        Self::new(0.075, 1.0, 0.0).expect("valid preset")
+    }
+
+    // Activation frequency roughly doubles after menopause with a small
+    // negative balance per BMU.
+    // Recker R et al. (2004) J Bone Miner Res 19:1628-1633, PMID 15355557
+    pub fn postmenopausal_spine() -> Self {
+        Self::new(0.25, 2.0, -0.01).expect("valid preset")
+    }
+
+    fn reversal_lag_days(&self) -> usize {
+        self.phases.reversal_days.round() as usize
+    }
+
+    fn formation_days(&self) -> usize {
+        let collagen = self.coupling.collagen_synthesis_fold().max(1e-3);
+        (self.phases.formation_days / collagen).round().max(1.0) as usize
+    }
+
+    // Refill volume per unit resorbed.
+    fn coupled_refill_fraction(&self) -> f64 {
+        (1.0 + self.bmu_balance_fraction) * self.coupling.osteoblast_recruitment()
+    }
+
+    // Refill owed by BMUs that started before t = 0 at the current rate.
+    fn prefill_steady_schedule(&mut self) {
+        let lag = self.reversal_lag_days();
+        let formation = self.formation_days();
+        let daily = self.baseline_resorption_per_day() * self.coupled_refill_fraction();
+        self.formation_schedule = (0..lag + formation)
+            .map(|day| {
+                let contributing = if day <= lag {
+                    formation
+                } else {
+                    lag + formation - day
+                };
+                daily * contributing as f64 / formation as f64
+            })
+            .collect();
+    }
+
+    fn baseline_resorption_per_day(&self) -> f64 {
+        self.turnover_per_year * self.activation_frequency_factor / 365.25
+    }
+
+    pub fn resorption_rate_per_day(&self) -> f64 {
+        self.baseline_resorption_per_day()
+            * self.modifiers.activation_frequency_factor
+            * self.modifiers.resorption_depth_factor
+            * self.bone_mass_fraction
+    }
+
+    pub fn formation_rate_per_day(&self) -> f64 {
+        self.formation_schedule.front().copied().unwrap_or(0.0) * self.modifiers.formation_factor
+    }
+
+    // Fraction of bone volume currently excavated and awaiting refill.
+    pub fn remodeling_space(&self) -> f64 {
+        self.formation_schedule.iter().sum()
+    }
+
+    pub fn builder() -> BoneRemodelingBuilder {
+        BoneRemodelingBuilder::default()
+    }
+
+    pub fn set_modifiers(&mut self, modifiers: RemodelingModifiers) {
+        self.modifiers = modifiers;
+    }
+
+    // Takes effect for pits resorbed from now on.
+    pub fn set_coupling(&mut self, coupling: TgfBetaSignaling) {
+        self.coupling = coupling;
+    }
+
+    pub fn tgf_beta_release_per_day(&self) -> f64 {
+        self.coupling.released_tgf_beta(self.resorption_rate_per_day())
+    }
+
+    pub fn step_day(&mut self) {
+        let resorbed = self.resorption_rate_per_day();
+        let formed = self.formation_rate_per_day();
+        self.formation_schedule.pop_front();
+
+        let lag = self.reversal_lag_days();
+        let formation = self.formation_days();
+        let needed = lag + formation;
+        while self.formation_schedule.len() < needed {
+            self.formation_schedule.push_back(0.0);
+        }
+        let refill = resorbed * self.coupled_refill_fraction() / formation as f64;
+        for slot in self.formation_schedule.iter_mut().skip(lag).take(formation) {
+            *slot += refill;
+        }
+
+        self.bone_mass_fraction = (self.bone_mass_fraction + formed - resorbed).max(0.0);
+        self.elapsed_days += 1.0;
+    }
+
+    pub fn run_days(&mut self, days: usize) {
+        for _ in 0..days {
+            self.step_day();
+        }
+    }
+
+    // Areal BMD tracks bone volume at a fixed degree of mineralization.
+    pub fn bmd_change_percent(&self) -> f64 {
+        (self.bone_mass_fraction - 1.0) * 100.0
+    }
+}
+
+impl Default for BoneRemodelingModel {
+    fn default() -> Self {
+        Self::healthy_adult_spine()
+    }
+}
+
+// Fluent construction covering phases and modifiers as well as the
+// `new()` arguments; starts from the healthy adult spine.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoneRemodelingBuilder {
+    turnover_per_year: f64,
+    activation_frequency_factor: f64,
+    bmu_balance_fraction: f64,
+    phases: RemodelingPhases,
+    modifiers: RemodelingModifiers,
+    coupling: TgfBetaSignaling,
+    bone_mass_fraction: f64,
+}
+
+impl Default for BoneRemodelingBuilder {
+    fn default() -> Self {
+        Self {
+            turnover_per_year: 0.25,
+            activation_frequency_factor: 1.0,
+            bmu_balance_fraction: 0.0,
+            phases: RemodelingPhases::default(),
+            modifiers: RemodelingModifiers::none(),
+            coupling: TgfBetaSignaling::new(),
+            bone_mass_fraction: 1.0,
+        }
+    }
+}
+
+impl BoneRemodelingBuilder {
+    pub fn turnover_per_year(mut self, turnover: f64) -> Self {
+        self.turnover_per_year = turnover;
+        self
+    }
+
+    pub fn activation_frequency_factor(mut self, factor: f64) -> Self {
+        self.activation_frequency_factor = factor;
+        self
+    }
+
+    pub fn bmu_balance_fraction(mut self, balance: f64) -> Self {
+        self.bmu_balance_fraction = balance;
+        self
+    }
+
+    pub fn phases(mut self, phases: RemodelingPhases) -> Self {
+        self.phases = phases;
+        self
+    }
+
+    pub fn modifiers(mut self, modifiers: RemodelingModifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
+    pub fn coupling(mut self, coupling: TgfBetaSignaling) -> Self {
+        self.coupling = coupling;
+        self
+    }
+
+    pub fn bone_mass_fraction(mut self, fraction: f64) -> Self {
+        self.bone_mass_fraction = fraction;
+        self
+    }
+
+    pub fn build(self) -> BiologyResult<BoneRemodelingModel> {
+        let p = self.phases;
+        if p.resorption_days <= 0.0 || p.reversal_days < 0.0 || p.formation_days < 1.0 {
+            return Err(BiologyError::InvalidParameter(
+                "resorption must be positive, reversal non-negative and formation at least a day"
+                    .to_string(),
+            ));
+        }
+        let m = self.modifiers;
+        let factors = [
+            m.activation_frequency_factor,
+            m.resorption_depth_factor,
+            m.formation_factor,
+        ];
+        if factors.iter().any(|f| !f.is_finite() || *f < 0.0) {
+            return Err(BiologyError::InvalidParameter(
+                "remodeling modifiers must be finite and non-negative".to_string(),
+            ));
+        }
+        if !self.bone_mass_fraction.is_finite() || self.bone_mass_fraction <= 0.0 {
+            return Err(BiologyError::InvalidParameter(
+                "bone mass fraction must be positive".to_string(),
+            ));
+        }
+        let mut model = BoneRemodelingModel::new(
+            self.turnover_per_year,
+            self.activation_frequency_factor,
+            self.bmu_balance_fraction,
+        )?;
+        model.phases = p;
+        model.modifiers = m;
+        model.coupling = self.coupling;
+        model.bone_mass_fraction = self.bone_mass_fraction;
+        // The steady schedule depends on the phase durations.
+        model.prefill_steady_schedule();
+        Ok(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_healthy_site_is_balanced() {
+        let mut site = BoneRemodelingModel::healthy_adult_spine();
+        site.run_days(3 * 365);
+        assert!(site.bmd_change_percent().abs() < 0.1);
+    }
+
+    #[test]
+    fn test_postmenopausal_loss_rate() {
+        // Untreated early postmenopausal spine loses ~0.5-2%/yr.
+        let mut site = BoneRemodelingModel::postmenopausal_spine();
+        site.run_days(365);
+        let loss = -site.bmd_change_percent();
+        assert!(loss > 0.3 && loss < 2.0, "loss {loss}");
+    }
+
+    #[test]
+    fn test_antiresorptive_closes_remodeling_space() {
+        let mut site = BoneRemodelingModel::postmenopausal_spine();
+        let space_before = site.remodeling_space();
+        site.set_modifiers(RemodelingModifiers {
+            activation_frequency_factor: 0.5,
+            ..RemodelingModifiers::none()
+        });
+        site.run_days(365);
+        assert!(site.remodeling_space() < space_before);
+        assert!(site.bmd_change_percent() > 0.0);
+    }
+
+    #[test]
+    fn test_overfilling_builds_bone() {
+        let mut site = BoneRemodelingModel::healthy_adult_spine();
+        site.set_modifiers(RemodelingModifiers {
+            formation_factor: 1.1,
+            ..RemodelingModifiers::none()
+        });
+        site.run_days(365);
+        assert!(site.bmd_change_percent() > 1.0);
+    }
+
+    #[test]
+    fn test_endocrine_signals_drive_turnover() {
+        let reference = RemodelingModifiers::from_endocrine(&EndocrineSignals::default());
+        assert!((reference.activation_frequency_factor - 1.0).abs() < 1e-9);
+        let menopause = RemodelingModifiers::from_endocrine(&EndocrineSignals {
+            estradiol_pg_ml: 5.0,
+            testosterone_ng_dl: 30.0,
+            ..EndocrineSignals::default()
+        });
+        assert!(menopause.activation_frequency_factor > 1.8);
+        let cushing = RemodelingModifiers::from_endocrine(&EndocrineSignals {
+            cortisol_ug_dl: 30.0,
+            ..EndocrineSignals::default()
+        });
+        assert!(cushing.formation_factor < 0.5);
+    }
+
+    #[test]
+    fn test_unloading_raises_sclerostin_and_loses_bone() {
+        let mut wnt = WntSignaling::new();
+        let habitual = RemodelingModifiers::from_wnt(&wnt);
+        assert!((habitual.activation_frequency_factor - 1.0).abs() < 1e-12);
+        assert!((habitual.formation_factor - 1.0).abs() < 1e-12);
+
+        wnt.set_mechanical_strain(0.0).unwrap();
+        let mut site = BoneRemodelingModel::healthy_adult_spine();
+        site.set_modifiers(RemodelingModifiers::from_wnt(&wnt));
+        site.run_days(180);
+        assert!(
+            site.bmd_change_percent() < -1.0,
+            "{}",
+            site.bmd_change_percent()
+        );
+
+        wnt.set_mechanical_strain(3000.0).unwrap();
+        let loaded = RemodelingModifiers::from_wnt(&wnt);
+        assert!(loaded.formation_factor > 1.0 && loaded.activation_frequency_factor < 1.0);
+    }
+
+    #[test]
+    fn test_matrix_tgf_beta_couples_formation_to_resorption() {
+        let mut site = BoneRemodelingModel::healthy_adult_spine();
+        let baseline_release = site.tgf_beta_release_per_day();
+        site.set_modifiers(RemodelingModifiers {
+            activation_frequency_factor: 2.0,
+            ..RemodelingModifiers::none()
+        });
+        assert!((site.tgf_beta_release_per_day() - 2.0 * baseline_release).abs() < 1e-15);
+
+        // Blocking the receptor uncouples: pits are resorbed but underfilled.
+        let mut blocked = BoneRemodelingModel::healthy_adult_spine();
+        let mut coupling = TgfBetaSignaling::new();
+        coupling.set_receptor_blockade(0.5).unwrap();
+        blocked.set_coupling(coupling);
+        blocked.run_days(365);
+        assert!(
+            blocked.bmd_change_percent() < -5.0,
+            "{}",
+            blocked.bmd_change_percent()
+        );
+
+        // Richer matrix recruits more osteoblasts that also lay collagen
+        // faster, so pits overfill.
+        let rich = TgfBetaSignaling::new()
+            .with_matrix_content(1.5, 1.5)
+            .unwrap();
+        let mut site = BoneRemodelingModel::builder()
+            .coupling(rich)
+            .build()
+            .unwrap();
+        let healthy = BoneRemodelingModel::healthy_adult_spine();
+        assert!(site.formation_rate_per_day() > healthy.formation_rate_per_day());
+        site.run_days(365);
+        assert!(site.bmd_change_percent() > 1.0);
+    }
+
+    #[test]
+    fn test_invalid_parameters_rejected() {
+        assert!(BoneRemodelingModel::new(0.0, 1.0, 0.0).is_err());
+        assert!(BoneRemodelingModel::new(0.25, 1.0, -1.5).is_err());
+    }
+
+    #[test]
+    fn test_builder_matches_presets_and_validates() {
+        let built = BoneRemodelingModel::builder()
+            .activation_frequency_factor(2.0)
+            .bmu_balance_fraction(-0.01)
+            .build()
+            .unwrap();
+        let preset = BoneRemodelingModel::postmenopausal_spine();
+        assert_eq!(built.remodeling_space(), preset.remodeling_space());
+
+        let slow = BoneRemodelingModel::builder()
+            .phases(RemodelingPhases {
+                formation_days: 290.0,
+                ..RemodelingPhases::default()
+            })
+            .build()
+            .unwrap();
+        let healthy = BoneRemodelingModel::healthy_adult_spine();
+        assert!(slow.remodeling_space() > 1.5 * healthy.remodeling_space());
+
+        let builder = BoneRemodelingModel::builder();
+        assert!(builder.turnover_per_year(0.0).build().is_err());
+        assert!(builder.bone_mass_fraction(0.0).build().is_err());
+        assert!(builder
+            .phases(RemodelingPhases {
+                resorption_days: 0.0,
+                ..RemodelingPhases::default()
+            })
+            .build()
+            .is_err());
+        assert!(builder
+            .modifiers(RemodelingModifiers {
+                formation_factor: -1.0,
+                ..RemodelingModifiers::none()
+            })
+            .build()
+            .is_err());
+    }
+}
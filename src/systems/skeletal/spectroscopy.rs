@@ -0,0 +1,377 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::skeletal::crystallography::IonicSubstitutions;
+use crate::systems::skeletal::hydroxyapatite::HydroxyapatiteCrystal;
+use serde::{Deserialize, Serialize};
+
+// Band strengths per unit mass, relative to amide I per unit collagen,
+// calibrated so adult cortical bone reproduces the published ratios:
+// Raman ν1 PO4/amide I ~5, ν1 CO3/ν1 PO4 ~0.2, ν1 FWHM ~19 cm⁻¹;
+// FTIR mineral/matrix ~4.5, ν2 CO3/PO4 ~0.008, 1660/1690 ~3.
+// Morris MD, Mandair GS (2011) Clin Orthop Relat Res 469:2160-2169
+// Boskey AL, Mendelsohn R (2005) J Biomed Opt 10:031102
+// Paschalis EP et al. (2001) J Bone Miner Res 16:1821-1828
+const RAMAN_PHOSPHATE_PER_MINERAL: f64 = 1.9;
+const RAMAN_CARBONATE_PER_WT: f64 = 0.031;
+const FTIR_PHOSPHATE_PER_MINERAL: f64 = 1.75;
+const FTIR_CARBONATE_PER_WT: f64 = 0.00123;
+const FTIR_CROSSLINK_BASE: f64 = 1.0;
+const FTIR_CROSSLINK_PER_RATIO: f64 = 0.9;
+
+// ν1 PO4 sits at 962 cm⁻¹ in stoichiometric HA and broadens for thin,
+// carbonated platelets; ~6-8 cm⁻¹ wide in well-crystallised mineral.
+// Awonusi A, Morris MD, Tecklenburg MMJ (2007) Calcif Tissue Int 81:46-52
+const PHOSPHATE_NU1_CM1: f64 = 962.0;
+const PHOSPHATE_NU1_SHIFT_PER_WT: f64 = -0.4;
+const PHOSPHATE_NU1_MIN_FWHM: f64 = 6.0;
+const PHOSPHATE_NU1_FWHM_NM: f64 = 36.0;
+const PHOSPHATE_NU1_FWHM_PER_WT: f64 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Technique {
+    Raman,
+    Ftir,
+}
+
+// Mass fractions of the tissue plus the mineral and collagen descriptors
+// the vibrational bands respond to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoneMatrix {
+    pub mineral_fraction: f64,
+    pub organic_fraction: f64,
+    pub water_fraction: f64,
+    pub crystal: HydroxyapatiteCrystal,
+    pub mineral_crystallinity: f64,
+    pub substitutions: IonicSubstitutions,
+    // Trivalent pyridinoline over divalent deH-DHLNL crosslinks.
+    pub mature_to_immature_crosslinks: f64,
+}
+
+impl BoneMatrix {
+    pub fn new(
+        mineral_fraction: f64,
+        organic_fraction: f64,
+        water_fraction: f64,
+    ) -> BiologyResult<Self> {
+        let fractions = [mineral_fraction, organic_fraction, water_fraction];
+        if fractions.iter().any(|f| f.is_nan() || *f < 0.0) {
+            return Err(BiologyError::InvalidValue(
+                "mass fractions must be non-negative".to_string(),
+            ));
+        }
+        if (fractions.iter().sum::<f64>() - 1.0).abs() > 1e-6 {
+            return Err(BiologyError::InvalidValue(
+                "mineral, organic and water fractions must sum to 1".to_string(),
+            ));
+        }
+        if organic_fraction <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "matrix ratios need an organic phase".to_string(),
+            ));
+        }
+        Ok(Self {
+            mineral_fraction,
+            organic_fraction,
+            water_fraction,
+            ..Self::cortical_adult()
+        })
+    }
+
+    // 65% mineral, 25% organic, 10% water by mass.
+    // Currey JD (2002) Bones: Structure and Mechanics, Princeton UP
+    pub fn cortical_adult() -> Self {
+        Self {
+            mineral_fraction: 0.65,
+            organic_fraction: 0.25,
+            water_fraction: 0.10,
+            crystal: HydroxyapatiteCrystal::bone_mineral(),
+            mineral_crystallinity: 0.5,
+            substitutions: IonicSubstitutions::bone_mineral(),
+            mature_to_immature_crosslinks: 2.5,
+        }
+    }
+
+    // Osteoid a few weeks into primary mineralisation: less, smaller,
+    // less carbonated mineral on collagen still dominated by divalent
+    // crosslinks.
+    pub fn newly_formed() -> Self {
+        Self {
+            mineral_fraction: 0.45,
+            organic_fraction: 0.35,
+            water_fraction: 0.20,
+            crystal: HydroxyapatiteCrystal {
+                length_nm: 30.0,
+                width_nm: 15.0,
+                thickness_nm: 2.0,
+            },
+            mineral_crystallinity: 0.3,
+            substitutions: IonicSubstitutions {
+                carbonate_wt_percent: 4.0,
+                ..IonicSubstitutions::none()
+            },
+            mature_to_immature_crosslinks: 0.8,
+        }
+    }
+
+    pub fn with_crystal(mut self, crystal: HydroxyapatiteCrystal, crystallinity: f64) -> Self {
+        self.crystal = crystal;
+        self.mineral_crystallinity = crystallinity.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_substitutions(mut self, substitutions: IonicSubstitutions) -> Self {
+        self.substitutions = substitutions;
+        self
+    }
+
+    pub fn with_crosslink_ratio(mut self, ratio: f64) -> BiologyResult<Self> {
+        if ratio.is_nan() || ratio < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "crosslink ratio must be non-negative".to_string(),
+            ));
+        }
+        self.mature_to_immature_crosslinks = ratio;
+        Ok(self)
+    }
+
+    fn carbonate_wt(&self) -> f64 {
+        self.substitutions.carbonate_wt_percent
+    }
+
+    pub fn raman_bands(&self) -> Vec<SpectralBand> {
+        let organic = self.organic_fraction;
+        let phosphate = RAMAN_PHOSPHATE_PER_MINERAL * self.mineral_fraction;
+        let nu1_fwhm = PHOSPHATE_NU1_MIN_FWHM
+            + PHOSPHATE_NU1_FWHM_NM / self.crystal.thickness_nm.max(0.1)
+            + PHOSPHATE_NU1_FWHM_PER_WT * self.carbonate_wt();
+        vec![
+            SpectralBand::new("proline", 855.0, 12.0, 0.3 * organic),
+            SpectralBand::new("hydroxyproline", 875.0, 12.0, 0.25 * organic),
+            SpectralBand::new(
+                "phosphate_nu1",
+                PHOSPHATE_NU1_CM1 + PHOSPHATE_NU1_SHIFT_PER_WT * self.carbonate_wt(),
+                nu1_fwhm,
+                phosphate,
+            ),
+            SpectralBand::new("phenylalanine", 1003.0, 6.0, 0.15 * organic),
+            SpectralBand::new(
+                "carbonate_nu1",
+                1070.0,
+                15.0,
+                RAMAN_CARBONATE_PER_WT * self.carbonate_wt() * phosphate,
+            ),
+            SpectralBand::new("ch2_wag", 1450.0, 25.0, 0.8 * organic),
+            SpectralBand::new("amide_i", 1665.0, 40.0, organic),
+        ]
+    }
+
+    pub fn ftir_bands(&self) -> Vec<SpectralBand> {
+        let organic = self.organic_fraction;
+        let phosphate = FTIR_PHOSPHATE_PER_MINERAL * self.mineral_fraction;
+        // Stoichiometric (1030) against non-stoichiometric (1020) apatite.
+        let maturity = 0.75 + 0.75 * self.mineral_crystallinity;
+        let crosslinks =
+            FTIR_CROSSLINK_BASE + FTIR_CROSSLINK_PER_RATIO * self.mature_to_immature_crosslinks;
+        vec![
+            SpectralBand::new(
+                "carbonate_nu2",
+                872.0,
+                10.0,
+                FTIR_CARBONATE_PER_WT * self.carbonate_wt() * phosphate,
+            ),
+            SpectralBand::new("phosphate_1020", 1020.0, 30.0, phosphate / (1.0 + maturity)),
+            SpectralBand::new(
+                "phosphate_1030",
+                1030.0,
+                30.0,
+                phosphate * maturity / (1.0 + maturity),
+            ),
+            SpectralBand::new("amide_ii", 1550.0, 40.0, 0.6 * organic),
+            SpectralBand::new(
+                "amide_i_1660",
+                1660.0,
+                25.0,
+                organic * crosslinks / (1.0 + crosslinks),
+            ),
+            SpectralBand::new("amide_i_1690", 1690.0, 20.0, organic / (1.0 + crosslinks)),
+        ]
+    }
+
+    pub fn bands(&self, technique: Technique) -> Vec<SpectralBand> {
+        match technique {
+            Technique::Raman => self.raman_bands(),
+            Technique::Ftir => self.ftir_bands(),
+        }
+    }
+
+    pub fn raman_metrics(&self) -> RamanMetrics {
+        RamanMetrics::from_bands(&self.raman_bands())
+    }
+
+    pub fn ftir_metrics(&self) -> FtirMetrics {
+        FtirMetrics::from_bands(&self.ftir_bands())
+    }
+}
+
+impl Default for BoneMatrix {
+    fn default() -> Self {
+        Self::cortical_adult()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpectralBand {
+    pub assignment: String,
+    pub position_cm1: f64,
+    pub fwhm_cm1: f64,
+    pub area: f64,
+}
+
+impl SpectralBand {
+    fn new(assignment: &str, position_cm1: f64, fwhm_cm1: f64, area: f64) -> Self {
+        Self {
+            assignment: assignment.to_string(),
+            position_cm1,
+            fwhm_cm1,
+            area,
+        }
+    }
+
+    pub fn lorentzian(&self, wavenumber_cm1: f64) -> f64 {
+        let half = 0.5 * self.fwhm_cm1;
+        self.area / std::f64::consts::PI * half
+            / ((wavenumber_cm1 - self.position_cm1).powi(2) + half * half)
+    }
+}
+
+fn area(bands: &[SpectralBand], assignment: &str) -> f64 {
+    bands
+        .iter()
+        .filter(|b| b.assignment == assignment)
+        .map(|b| b.area)
+        .sum()
+}
+
+fn band<'a>(bands: &'a [SpectralBand], assignment: &str) -> Option<&'a SpectralBand> {
+    bands.iter().find(|b| b.assignment == assignment)
+}
+
+// Sum of Lorentzian bands sampled on an evenly spaced axis.
+pub fn synthesize_spectrum(
+    bands: &[SpectralBand],
+    from_cm1: f64,
+    to_cm1: f64,
+    step_cm1: f64,
+) -> Vec<(f64, f64)> {
+    if step_cm1 <= 0.0 || to_cm1 < from_cm1 {
+        return Vec::new();
+    }
+    let n = ((to_cm1 - from_cm1) / step_cm1).floor() as usize + 1;
+    (0..n)
+        .map(|i| {
+            let x = from_cm1 + step_cm1 * i as f64;
+            (x, bands.iter().map(|b| b.lorentzian(x)).sum())
+        })
+        .collect()
+}
+
+// Band-area ratios as reported in Raman bone-quality studies.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RamanMetrics {
+    pub mineral_to_matrix: f64,
+    pub carbonate_to_phosphate: f64,
+    pub phosphate_nu1_fwhm_cm1: f64,
+    // 1/FWHM of ν1 PO4.
+    pub crystallinity: f64,
+}
+
+impl RamanMetrics {
+    pub fn from_bands(bands: &[SpectralBand]) -> Self {
+        let phosphate = area(bands, "phosphate_nu1");
+        let fwhm = band(bands, "phosphate_nu1").map_or(f64::NAN, |b| b.fwhm_cm1);
+        Self {
+            mineral_to_matrix: phosphate / area(bands, "amide_i"),
+            carbonate_to_phosphate: area(bands, "carbonate_nu1") / phosphate,
+            phosphate_nu1_fwhm_cm1: fwhm,
+            crystallinity: 1.0 / fwhm,
+        }
+    }
+}
+
+// FTIR band-area ratios as reported by Boskey and Paschalis.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FtirMetrics {
+    pub mineral_to_matrix: f64,
+    pub carbonate_to_phosphate: f64,
+    // 1030/1020 sub-band ratio.
+    pub crystallinity: f64,
+    // 1660/1690 sub-band ratio.
+    pub collagen_crosslink_ratio: f64,
+}
+
+impl FtirMetrics {
+    pub fn from_bands(bands: &[SpectralBand]) -> Self {
+        let phosphate = area(bands, "phosphate_1020") + area(bands, "phosphate_1030");
+        let amide_i = area(bands, "amide_i_1660") + area(bands, "amide_i_1690");
+        Self {
+            mineral_to_matrix: phosphate / amide_i,
+            carbonate_to_phosphate: area(bands, "carbonate_nu2") / phosphate,
+            crystallinity: area(bands, "phosphate_1030") / area(bands, "phosphate_1020"),
+            collagen_crosslink_ratio: area(bands, "amide_i_1660") / area(bands, "amide_i_1690"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cortical_bone_matches_published_ratios() {
+        let bone = BoneMatrix::cortical_adult();
+        let raman = bone.raman_metrics();
+        assert!(raman.mineral_to_matrix > 4.0 && raman.mineral_to_matrix < 6.0);
+        assert!(raman.carbonate_to_phosphate > 0.15 && raman.carbonate_to_phosphate < 0.3);
+        assert!(raman.phosphate_nu1_fwhm_cm1 > 17.0 && raman.phosphate_nu1_fwhm_cm1 < 21.0);
+        let ftir = bone.ftir_metrics();
+        assert!(ftir.mineral_to_matrix > 4.0 && ftir.mineral_to_matrix < 5.5);
+        assert!(ftir.carbonate_to_phosphate > 0.006 && ftir.carbonate_to_phosphate < 0.011);
+        assert!((ftir.collagen_crosslink_ratio - 3.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_new_bone_is_less_mineralised_and_mature() {
+        let mature = BoneMatrix::cortical_adult();
+        let young = BoneMatrix::newly_formed();
+        assert!(young.raman_metrics().mineral_to_matrix < mature.raman_metrics().mineral_to_matrix);
+        assert!(young.raman_metrics().crystallinity < mature.raman_metrics().crystallinity);
+        assert!(young.ftir_metrics().crystallinity < mature.ftir_metrics().crystallinity);
+        assert!(
+            young.ftir_metrics().collagen_crosslink_ratio
+                < mature.ftir_metrics().collagen_crosslink_ratio
+        );
+
+        // Well-crystallised synthetic HA gives a narrow ν1 band.
+        let synthetic =
+            mature.with_crystal(HydroxyapatiteCrystal::new(200.0, 25.0, 20.0).unwrap(), 1.0);
+        assert!(synthetic.raman_metrics().phosphate_nu1_fwhm_cm1 < 10.0);
+        assert!(BoneMatrix::new(0.7, 0.3, 0.2).is_err());
+    }
+
+    #[test]
+    fn test_synthetic_raman_spectrum_peaks_at_phosphate_nu1() {
+        let bands = BoneMatrix::cortical_adult().raman_bands();
+        let spectrum = synthesize_spectrum(&bands, 800.0, 1800.0, 1.0);
+        assert_eq!(spectrum.len(), 1001);
+        let (peak, _) = spectrum
+            .iter()
+            .copied()
+            .fold((0.0, f64::NEG_INFINITY), |best, p| {
+                if p.1 > best.1 {
+                    p
+                } else {
+                    best
+                }
+            });
+        assert!((peak - 959.4).abs() < 1.0);
+    }
+}
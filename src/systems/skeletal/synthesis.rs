@@ -0,0 +1,441 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::systems::skeletal::hydroxyapatite::HydroxyapatiteCrystal;
+use serde::{Deserialize, Serialize};
+
+const GAS_CONSTANT_J_MOL_K: f64 = 8.314;
+pub const STOICHIOMETRIC_CA_P: f64 = 10.0 / 6.0;
+const TCP_CA_P: f64 = 1.5;
+
+// Below pH ~9 HPO4²⁻ substitutes into the lattice, giving
+// Ca10-x(HPO4)x(PO4)6-x(OH)2-x down to Ca/P 1.5; below pH ~6.5 brushite
+// precipitates instead. Maturation during aging restores stoichiometry.
+// Elliott JC (1994) Structure and Chemistry of the Apatites, Elsevier
+// Dorozhkin SV (2010) Acta Biomater 6:715-734
+const DEFICIENCY_HALF_PH: f64 = 9.0;
+const BRUSHITE_HALF_PH: f64 = 6.5;
+const MATURATION_REPAIR: f64 = 0.5;
+
+// Crystallinity follows diffusion-limited transformation,
+// Xc = 1 - exp(-(k·t)^0.5); size coarsens by Ostwald ripening,
+// L³ = L0³ + K·t, both Arrhenius in temperature.
+// Lifshitz IM, Slyozov VV (1961) J Phys Chem Solids 19:35-50
+const CRYSTALLINITY_EXPONENT: f64 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SynthesisRoute {
+    WetPrecipitation,
+    SolGel,
+    Hydrothermal,
+}
+
+// Calibrated to room-temperature precipitates of ~50 nm at ~40%
+// crystallinity after a day, whiskers from hydrothermal vessels, and
+// sol-gel powders crystallising on calcination above ~600 °C.
+// Pang YX, Bao X (2003) J Eur Ceram Soc 23:1697-1704
+// Liu DM et al. (2001) Biomaterials 22:1721-1730
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RouteKinetics {
+    temperature_range_c: (f64, f64),
+    reference_temperature_c: f64,
+    crystallisation_per_hour: f64,
+    crystallisation_activation_kj_mol: f64,
+    ripening_nm3_per_hour: f64,
+    ripening_activation_kj_mol: f64,
+    // Length over width and over thickness.
+    aspect: (f64, f64),
+    ph_sensitive: bool,
+}
+
+impl SynthesisRoute {
+    fn kinetics(&self) -> RouteKinetics {
+        match self {
+            SynthesisRoute::WetPrecipitation => RouteKinetics {
+                temperature_range_c: (20.0, 95.0),
+                reference_temperature_c: 25.0,
+                crystallisation_per_hour: 0.02,
+                crystallisation_activation_kj_mol: 30.0,
+                ripening_nm3_per_hour: 4875.0,
+                ripening_activation_kj_mol: 30.0,
+                aspect: (2.0, 8.0),
+                ph_sensitive: true,
+            },
+            SynthesisRoute::Hydrothermal => RouteKinetics {
+                temperature_range_c: (100.0, 250.0),
+                reference_temperature_c: 25.0,
+                crystallisation_per_hour: 0.02,
+                crystallisation_activation_kj_mol: 30.0,
+                ripening_nm3_per_hour: 4875.0,
+                ripening_activation_kj_mol: 30.0,
+                aspect: (8.0, 10.0),
+                ph_sensitive: true,
+            },
+            // Temperature is the calcination temperature of the dried gel.
+            SynthesisRoute::SolGel => RouteKinetics {
+                temperature_range_c: (400.0, 1200.0),
+                reference_temperature_c: 600.0,
+                crystallisation_per_hour: 1.0,
+                crystallisation_activation_kj_mol: 150.0,
+                ripening_nm3_per_hour: 1.5e4,
+                ripening_activation_kj_mol: 200.0,
+                aspect: (1.5, 1.5),
+                ph_sensitive: false,
+            },
+        }
+    }
+}
+
+fn arrhenius(activation_kj_mol: f64, temperature_c: f64, reference_c: f64) -> f64 {
+    let t = temperature_c + 273.15;
+    let t_ref = reference_c + 273.15;
+    (activation_kj_mol * 1.0e3 / GAS_CONSTANT_J_MOL_K * (1.0 / t_ref - 1.0 / t)).exp()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SynthesisParameters {
+    pub route: SynthesisRoute,
+    pub ph: f64,
+    // Reaction temperature; calcination temperature for sol-gel.
+    pub temperature_c: f64,
+    // Aging in the mother liquor; calcination hold for sol-gel.
+    pub aging_hours: f64,
+    pub feed_ca_p_ratio: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HydroxyapatiteCharacterization {
+    pub ca_p_ratio: f64,
+    // XRD crystalline fraction.
+    pub crystallinity: f64,
+    // HA share of the product; the rest is brushite, β-TCP or CaO.
+    pub phase_purity: f64,
+    pub crystal: HydroxyapatiteCrystal,
+}
+
+impl HydroxyapatiteCharacterization {
+    pub fn particle_length_nm(&self) -> f64 {
+        self.crystal.length_nm
+    }
+
+    pub fn specific_surface_area_m2_per_g(&self) -> f64 {
+        self.crystal.specific_surface_area_m2_per_g()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct QualityTargets {
+    pub ca_p_ratio: f64,
+    pub ca_p_tolerance: f64,
+    pub crystallinity_range: (f64, f64),
+    pub particle_length_nm_range: (f64, f64),
+    pub min_phase_purity: f64,
+}
+
+impl QualityTargets {
+    // Bone apatite is calcium deficient, poorly crystalline and ~50 nm.
+    // Boskey AL (2007) Elements 3:385-391
+    pub fn bone_like() -> Self {
+        Self {
+            ca_p_ratio: 1.6,
+            ca_p_tolerance: 0.07,
+            crystallinity_range: (0.2, 0.6),
+            particle_length_nm_range: (20.0, 80.0),
+            min_phase_purity: 0.95,
+        }
+    }
+
+    // Dense sintering grade: stoichiometric, crystalline, sub-micron.
+    // Tighter than the 1.65-1.82 Ca/P window of ISO 13779-3, since excess
+    // calcium sinters to CaO.
+    pub fn sintering_powder() -> Self {
+        Self {
+            ca_p_ratio: STOICHIOMETRIC_CA_P,
+            ca_p_tolerance: 0.02,
+            crystallinity_range: (0.95, 1.0),
+            particle_length_nm_range: (100.0, 1000.0),
+            min_phase_purity: 0.95,
+        }
+    }
+
+    // Sum of normalised shortfalls; zero when every target is met.
+    pub fn violation(&self, product: &HydroxyapatiteCharacterization) -> f64 {
+        fn outside(value: f64, (low, high): (f64, f64)) -> f64 {
+            ((low - value).max(0.0) + (value - high).max(0.0)) / (high - low).abs().max(1e-9)
+        }
+        let ca_p = ((product.ca_p_ratio - self.ca_p_ratio).abs() - self.ca_p_tolerance).max(0.0)
+            / self.ca_p_tolerance.max(1e-9);
+        ca_p + outside(product.crystallinity, self.crystallinity_range)
+            + outside(product.particle_length_nm(), self.particle_length_nm_range)
+            + (self.min_phase_purity - product.phase_purity).max(0.0) * 10.0
+    }
+
+    pub fn is_met_by(&self, product: &HydroxyapatiteCharacterization) -> bool {
+        self.violation(product) <= 0.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SyntheticHydroxyapatite {
+    pub parameters: SynthesisParameters,
+}
+
+impl SyntheticHydroxyapatite {
+    pub fn new(parameters: SynthesisParameters) -> BiologyResult<Self> {
+        let p = parameters;
+        let (t_min, t_max) = p.route.kinetics().temperature_range_c;
+        if p.temperature_c.is_nan() || p.temperature_c < t_min || p.temperature_c > t_max {
+            return Err(BiologyError::InvalidParameter(format!(
+                "{:?} runs between {} and {} °C, not {}",
+                p.route, t_min, t_max, p.temperature_c
+            )));
+        }
+        if p.ph.is_nan() || !(4.0..=14.0).contains(&p.ph) {
+            return Err(BiologyError::InvalidValue(
+                "synthesis pH must lie in [4, 14]".to_string(),
+            ));
+        }
+        if p.aging_hours.is_nan() || p.aging_hours <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "aging time must be positive".to_string(),
+            ));
+        }
+        if p.feed_ca_p_ratio.is_nan() || !(1.0..=2.0).contains(&p.feed_ca_p_ratio) {
+            return Err(BiologyError::InvalidValue(
+                "feed Ca/P ratio must lie in [1, 2]".to_string(),
+            ));
+        }
+        Ok(Self { parameters })
+    }
+
+    // Stoichiometric feed at pH 10, the usual starting recipe.
+    pub fn recipe(
+        route: SynthesisRoute,
+        temperature_c: f64,
+        aging_hours: f64,
+    ) -> BiologyResult<Self> {
+        Self::new(SynthesisParameters {
+            route,
+            ph: 10.0,
+            temperature_c,
+            aging_hours,
+            feed_ca_p_ratio: STOICHIOMETRIC_CA_P,
+        })
+    }
+
+    pub fn characterize(&self) -> HydroxyapatiteCharacterization {
+        let p = self.parameters;
+        let k = p.route.kinetics();
+        let crystallisation = k.crystallisation_per_hour
+            * arrhenius(
+                k.crystallisation_activation_kj_mol,
+                p.temperature_c,
+                k.reference_temperature_c,
+            );
+        let crystallinity =
+            1.0 - (-(crystallisation * p.aging_hours).powf(CRYSTALLINITY_EXPONENT)).exp();
+
+        // Higher supersaturation at high pH nucleates more, smaller nuclei.
+        let nucleus_nm = if k.ph_sensitive {
+            20.0 * 10f64.powf(0.1 * (10.0 - p.ph))
+        } else {
+            20.0
+        };
+        let ripening = k.ripening_nm3_per_hour
+            * arrhenius(
+                k.ripening_activation_kj_mol,
+                p.temperature_c,
+                k.reference_temperature_c,
+            );
+        let length_nm = (nucleus_nm.powi(3) + ripening * p.aging_hours).cbrt();
+        let (width_ratio, thickness_ratio) = k.aspect;
+        let crystal = HydroxyapatiteCrystal {
+            length_nm,
+            width_nm: length_nm / width_ratio,
+            thickness_nm: length_nm / thickness_ratio,
+        };
+
+        let (ca_p_ratio, phase_purity) = if k.ph_sensitive {
+            let deficiency = (1.0 - MATURATION_REPAIR * crystallinity)
+                / (1.0 + 10f64.powf(p.ph - DEFICIENCY_HALF_PH));
+            let lattice_ca_p = (10.0 - deficiency) / 6.0;
+            let brushite = 1.0 / (1.0 + 10f64.powf(p.ph - BRUSHITE_HALF_PH));
+            // Excess calcium stays in solution and is washed out.
+            (p.feed_ca_p_ratio.min(lattice_ca_p), 1.0 - brushite)
+        } else {
+            // Calcination fixes the gel's Ca/P; off-stoichiometric gels
+            // exsolve β-TCP or CaO.
+            let ca_p = p.feed_ca_p_ratio;
+            let second_phase = if ca_p < STOICHIOMETRIC_CA_P {
+                (STOICHIOMETRIC_CA_P - ca_p) / (STOICHIOMETRIC_CA_P - TCP_CA_P)
+            } else {
+                (ca_p - STOICHIOMETRIC_CA_P) / STOICHIOMETRIC_CA_P * 5.0
+            };
+            (ca_p, 1.0 - second_phase.clamp(0.0, 1.0))
+        };
+
+        HydroxyapatiteCharacterization {
+            ca_p_ratio,
+            crystallinity,
+            phase_purity,
+            crystal,
+        }
+    }
+
+    // Grid search over the route's process window; among recipes that meet
+    // every target the coolest, shortest one wins.
+    pub fn optimize_synthesis(
+        route: SynthesisRoute,
+        targets: &QualityTargets,
+    ) -> BiologyResult<Self> {
+        let k = route.kinetics();
+        let (t_min, t_max) = k.temperature_range_c;
+        let ph_grid: Vec<f64> = if k.ph_sensitive {
+            (0..=24).map(|i| 6.0 + 0.25 * i as f64).collect()
+        } else {
+            vec![10.0]
+        };
+        let temperatures: Vec<f64> = (0..=16)
+            .map(|i| t_min + (t_max - t_min) * i as f64 / 16.0)
+            .collect();
+        let aging = [0.5, 1.0, 2.0, 4.0, 8.0, 12.0, 24.0, 48.0, 72.0, 168.0];
+        let feeds = [1.50, 1.55, 1.60, 1.65, STOICHIOMETRIC_CA_P, 1.70];
+
+        let mut best: Option<(f64, f64, SynthesisParameters)> = None;
+        for &ph in &ph_grid {
+            for &temperature_c in &temperatures {
+                for &aging_hours in &aging {
+                    for &feed_ca_p_ratio in &feeds {
+                        let parameters = SynthesisParameters {
+                            route,
+                            ph,
+                            temperature_c,
+                            aging_hours,
+                            feed_ca_p_ratio,
+                        };
+                        let product = Self { parameters }.characterize();
+                        let violation = targets.violation(&product);
+                        let effort = (temperature_c + 273.15) * aging_hours;
+                        let better = match best {
+                            None => true,
+                            Some((v, e, _)) => violation < v || (violation == v && effort < e),
+                        };
+                        if better {
+                            best = Some((violation, effort, parameters));
+                        }
+                    }
+                }
+            }
+        }
+        let (violation, _, parameters) = best.expect("process grid is non-empty");
+        if violation > 0.0 {
+            return Err(BiologyError::InvalidParameter(format!(
+                "no {:?} recipe meets the targets; closest misses by {:.2}",
+                route, violation
+            )));
+        }
+        Self::new(parameters)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn precipitate(ph: f64) -> HydroxyapatiteCharacterization {
+        SyntheticHydroxyapatite::new(SynthesisParameters {
+            route: SynthesisRoute::WetPrecipitation,
+            ph,
+            temperature_c: 25.0,
+            aging_hours: 24.0,
+            feed_ca_p_ratio: STOICHIOMETRIC_CA_P,
+        })
+        .unwrap()
+        .characterize()
+    }
+
+    #[test]
+    fn test_ph_sets_calcium_deficiency_and_phase() {
+        let alkaline = precipitate(11.0);
+        let neutral = precipitate(8.0);
+        let acidic = precipitate(5.5);
+        assert!(alkaline.ca_p_ratio > 1.65);
+        assert!(neutral.ca_p_ratio < 1.58 && neutral.ca_p_ratio >= 1.5);
+        assert!(alkaline.phase_purity > 0.99);
+        assert!(acidic.phase_purity < 0.2);
+        // Room-temperature precipitates are nanocrystalline and poorly ordered.
+        assert!(alkaline.particle_length_nm() > 20.0 && alkaline.particle_length_nm() < 80.0);
+        assert!(alkaline.crystallinity < 0.6);
+    }
+
+    #[test]
+    fn test_heat_and_time_grow_crystals() {
+        let cold = SyntheticHydroxyapatite::recipe(SynthesisRoute::WetPrecipitation, 25.0, 24.0)
+            .unwrap()
+            .characterize();
+        let hot = SyntheticHydroxyapatite::recipe(SynthesisRoute::WetPrecipitation, 90.0, 24.0)
+            .unwrap()
+            .characterize();
+        let hydrothermal =
+            SyntheticHydroxyapatite::recipe(SynthesisRoute::Hydrothermal, 200.0, 24.0)
+                .unwrap()
+                .characterize();
+        assert!(hot.crystallinity > cold.crystallinity);
+        assert!(hydrothermal.crystallinity > 0.99);
+        assert!(hydrothermal.particle_length_nm() > hot.particle_length_nm());
+        assert!(hydrothermal.crystal.width_nm * 5.0 < hydrothermal.particle_length_nm());
+        assert!(
+            cold.specific_surface_area_m2_per_g() > hydrothermal.specific_surface_area_m2_per_g()
+        );
+
+        let gel = |t| {
+            SyntheticHydroxyapatite::recipe(SynthesisRoute::SolGel, t, 2.0)
+                .unwrap()
+                .characterize()
+        };
+        assert!(gel(400.0).crystallinity < 0.1);
+        assert!(gel(700.0).crystallinity > 0.9);
+        assert!(gel(1000.0).particle_length_nm() > 300.0);
+        assert!(
+            SyntheticHydroxyapatite::recipe(SynthesisRoute::WetPrecipitation, 150.0, 1.0).is_err()
+        );
+    }
+
+    #[test]
+    fn test_off_stoichiometric_gel_exsolves_second_phase() {
+        let gel = SyntheticHydroxyapatite::new(SynthesisParameters {
+            route: SynthesisRoute::SolGel,
+            ph: 10.0,
+            temperature_c: 900.0,
+            aging_hours: 2.0,
+            feed_ca_p_ratio: 1.55,
+        })
+        .unwrap()
+        .characterize();
+        assert!(gel.phase_purity < 0.4);
+    }
+
+    #[test]
+    fn test_optimizer_meets_quality_targets() {
+        let bone = QualityTargets::bone_like();
+        let recipe =
+            SyntheticHydroxyapatite::optimize_synthesis(SynthesisRoute::WetPrecipitation, &bone)
+                .unwrap();
+        assert!(bone.is_met_by(&recipe.characterize()));
+
+        let ceramic = QualityTargets::sintering_powder();
+        let recipe =
+            SyntheticHydroxyapatite::optimize_synthesis(SynthesisRoute::SolGel, &ceramic).unwrap();
+        let product = recipe.characterize();
+        assert!(ceramic.is_met_by(&product));
+        assert!(product.phase_purity > 0.95);
+
+        let impossible = QualityTargets {
+            crystallinity_range: (0.0, 0.1),
+            particle_length_nm_range: (500.0, 1000.0),
+            ..ceramic
+        };
+        assert!(
+            SyntheticHydroxyapatite::optimize_synthesis(SynthesisRoute::SolGel, &impossible)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,463 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::config::BoneMaterial;
+use serde::{Deserialize, Serialize};
+
+// Cortical bone is weaker in tension and shear than in compression:
+// ultimate 133 MPa tension and 68 MPa shear against 193 MPa compression,
+// shear modulus 3.3 GPa against 17.9 GPa longitudinal.
+// Reilly DT, Burstein AH (1975) J Biomech 8:393-405
+const TENSION_TO_COMPRESSION_STRENGTH: f64 = 133.0 / 193.0;
+const SHEAR_TO_COMPRESSION_STRENGTH: f64 = 68.0 / 193.0;
+// Yield sits near 85% of ultimate, then bone hardens over a post-yield
+// strain that is shortest in compression and longest in shear.
+const YIELD_TO_ULTIMATE: f64 = 0.85;
+const POST_YIELD_STRAIN_COMPRESSION: f64 = 0.010;
+const POST_YIELD_STRAIN_TENSION: f64 = 0.020;
+const POST_YIELD_STRAIN_SHEAR: f64 = 0.030;
+
+// Yield is read where the secant stiffness has fallen 10% below the
+// initial slope, the usual whole-bone criterion.
+const YIELD_SECANT_DROP: f64 = 0.9;
+const CURVE_POINTS: usize = 200;
+const SECTION_STRIPS: usize = 200;
+
+// Bilinear elastic / linear-hardening branch, brittle at ultimate strain.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StressStrainBranch {
+    pub modulus_mpa: f64,
+    pub yield_stress_mpa: f64,
+    pub ultimate_stress_mpa: f64,
+    pub ultimate_strain: f64,
+}
+
+impl StressStrainBranch {
+    pub fn new(
+        modulus_mpa: f64,
+        ultimate_stress_mpa: f64,
+        post_yield_strain: f64,
+    ) -> BiologyResult<Self> {
+        if modulus_mpa.is_nan() || modulus_mpa <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "modulus must be positive".to_string(),
+            ));
+        }
+        if ultimate_stress_mpa.is_nan() || ultimate_stress_mpa <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "ultimate stress must be positive".to_string(),
+            ));
+        }
+        if post_yield_strain.is_nan() || post_yield_strain < 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "post-yield strain cannot be negative".to_string(),
+            ));
+        }
+        let yield_stress_mpa = YIELD_TO_ULTIMATE * ultimate_stress_mpa;
+        Ok(Self {
+            modulus_mpa,
+            yield_stress_mpa,
+            ultimate_stress_mpa,
+            ultimate_strain: yield_stress_mpa / modulus_mpa + post_yield_strain,
+        })
+    }
+
+    pub fn yield_strain(&self) -> f64 {
+        self.yield_stress_mpa / self.modulus_mpa
+    }
+
+    pub fn stress(&self, strain: f64) -> f64 {
+        let yield_strain = self.yield_strain();
+        if strain <= yield_strain {
+            self.modulus_mpa * strain
+        } else if strain <= self.ultimate_strain {
+            let span = (self.ultimate_strain - yield_strain).max(f64::EPSILON);
+            self.yield_stress_mpa
+                + (self.ultimate_stress_mpa - self.yield_stress_mpa) * (strain - yield_strain)
+                    / span
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BoneTissueLaw {
+    pub compression: StressStrainBranch,
+    pub tension: StressStrainBranch,
+    pub shear: StressStrainBranch,
+}
+
+impl BoneTissueLaw {
+    pub fn from_material(material: &BoneMaterial) -> BiologyResult<Self> {
+        material.validate()?;
+        let modulus = material.youngs_modulus_gpa * 1000.0;
+        let shear_modulus = modulus / (2.0 * (1.0 + material.poisson_ratio));
+        let strength = material.compressive_strength_mpa;
+        Ok(Self {
+            compression: StressStrainBranch::new(modulus, strength, POST_YIELD_STRAIN_COMPRESSION)?,
+            tension: StressStrainBranch::new(
+                modulus,
+                strength * TENSION_TO_COMPRESSION_STRENGTH,
+                POST_YIELD_STRAIN_TENSION,
+            )?,
+            shear: StressStrainBranch::new(
+                shear_modulus,
+                strength * SHEAR_TO_COMPRESSION_STRENGTH,
+                POST_YIELD_STRAIN_SHEAR,
+            )?,
+        })
+    }
+
+    // Signed axial stress, tension positive.
+    fn axial_stress(&self, strain: f64) -> f64 {
+        if strain >= 0.0 {
+            self.tension.stress(strain)
+        } else {
+            -self.compression.stress(-strain)
+        }
+    }
+}
+
+// Hollow circular diaphysis, dimensions in mm.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TubularSection {
+    pub outer_radius_mm: f64,
+    pub inner_radius_mm: f64,
+}
+
+impl TubularSection {
+    pub fn new(outer_radius_mm: f64, inner_radius_mm: f64) -> BiologyResult<Self> {
+        if outer_radius_mm.is_nan() || outer_radius_mm <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "outer radius must be positive".to_string(),
+            ));
+        }
+        if inner_radius_mm.is_nan() || inner_radius_mm < 0.0 || inner_radius_mm >= outer_radius_mm {
+            return Err(BiologyError::InvalidValue(
+                "inner radius must lie in [0, outer radius)".to_string(),
+            ));
+        }
+        Ok(Self {
+            outer_radius_mm,
+            inner_radius_mm,
+        })
+    }
+
+    // Adult femoral midshaft, ~26 mm across with a ~14 mm canal.
+    pub fn femoral_midshaft() -> Self {
+        Self {
+            outer_radius_mm: 13.0,
+            inner_radius_mm: 7.0,
+        }
+    }
+
+    pub fn area_mm2(&self) -> f64 {
+        std::f64::consts::PI * (self.outer_radius_mm.powi(2) - self.inner_radius_mm.powi(2))
+    }
+
+    pub fn second_moment_mm4(&self) -> f64 {
+        std::f64::consts::FRAC_PI_4 * (self.outer_radius_mm.powi(4) - self.inner_radius_mm.powi(4))
+    }
+
+    pub fn polar_moment_mm4(&self) -> f64 {
+        2.0 * self.second_moment_mm4()
+    }
+
+    // Chord width of the wall at height y from the centroid.
+    fn width_at(&self, y: f64) -> f64 {
+        let outer = (self.outer_radius_mm.powi(2) - y * y).max(0.0).sqrt();
+        let inner = (self.inner_radius_mm.powi(2) - y * y).max(0.0).sqrt();
+        2.0 * (outer - inner)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MechanicalTest {
+    ThreePointBending { span_mm: f64 },
+    Compression { length_mm: f64 },
+    Torsion { length_mm: f64 },
+}
+
+// For torsion the curve is rotation (rad) against torque (N·mm) and the
+// derived quantities carry those units; otherwise mm against N.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MechanicalTestResult {
+    pub test: MechanicalTest,
+    pub curve: Vec<(f64, f64)>,
+    pub stiffness: f64,
+    pub yield_load: f64,
+    pub ultimate_load: f64,
+    pub displacement_at_failure: f64,
+    pub work_to_failure: f64,
+}
+
+impl MechanicalTestResult {
+    fn from_curve(test: MechanicalTest, curve: Vec<(f64, f64)>) -> Self {
+        let stiffness = curve[1].1 / curve[1].0;
+        let yield_load = curve
+            .iter()
+            .skip(1)
+            .find(|&&(d, f)| f / d < YIELD_SECANT_DROP * stiffness)
+            .map_or(curve[curve.len() - 1].1, |&(_, f)| f);
+        let ultimate_load = curve.iter().map(|&(_, f)| f).fold(0.0, f64::max);
+        let work_to_failure = curve
+            .windows(2)
+            .map(|w| 0.5 * (w[0].1 + w[1].1) * (w[1].0 - w[0].0))
+            .sum();
+        Self {
+            test,
+            displacement_at_failure: curve[curve.len() - 1].0,
+            curve,
+            stiffness,
+            yield_load,
+            ultimate_load,
+            work_to_failure,
+        }
+    }
+}
+
+pub fn run_test(
+    section: &TubularSection,
+    law: &BoneTissueLaw,
+    test: MechanicalTest,
+) -> BiologyResult<MechanicalTestResult> {
+    let curve = match test {
+        MechanicalTest::ThreePointBending { span_mm } => {
+            require_length(span_mm, "span")?;
+            bending_curve(section, law, span_mm)
+        }
+        MechanicalTest::Compression { length_mm } => {
+            require_length(length_mm, "specimen length")?;
+            compression_curve(section, law, length_mm)
+        }
+        MechanicalTest::Torsion { length_mm } => {
+            require_length(length_mm, "gauge length")?;
+            torsion_curve(section, law, length_mm)
+        }
+    };
+    Ok(MechanicalTestResult::from_curve(test, curve))
+}
+
+fn require_length(value: f64, what: &str) -> BiologyResult<()> {
+    if value.is_nan() || value <= 0.0 {
+        return Err(BiologyError::InvalidValue(format!(
+            "{} must be positive",
+            what
+        )));
+    }
+    Ok(())
+}
+
+fn compression_curve(
+    section: &TubularSection,
+    law: &BoneTissueLaw,
+    length_mm: f64,
+) -> Vec<(f64, f64)> {
+    let failure = law.compression.ultimate_strain * length_mm;
+    (0..=CURVE_POINTS)
+        .map(|i| {
+            let d = failure * i as f64 / CURVE_POINTS as f64;
+            (
+                d,
+                law.compression.stress(d / length_mm) * section.area_mm2(),
+            )
+        })
+        .collect()
+}
+
+fn torsion_curve(section: &TubularSection, law: &BoneTissueLaw, length_mm: f64) -> Vec<(f64, f64)> {
+    let r_out = section.outer_radius_mm;
+    let r_in = section.inner_radius_mm;
+    let failure = law.shear.ultimate_strain * length_mm / r_out;
+    let dr = (r_out - r_in) / SECTION_STRIPS as f64;
+    (0..=CURVE_POINTS)
+        .map(|i| {
+            let theta = failure * i as f64 / CURVE_POINTS as f64;
+            let torque: f64 = (0..SECTION_STRIPS)
+                .map(|k| {
+                    let rho = r_in + (k as f64 + 0.5) * dr;
+                    law.shear.stress(theta * rho / length_mm)
+                        * 2.0
+                        * std::f64::consts::PI
+                        * rho
+                        * rho
+                        * dr
+                })
+                .sum();
+            (theta, torque)
+        })
+        .collect()
+}
+
+// Fibre integration of the section at curvature κ: the neutral axis
+// shifts until net axial force vanishes, which matters once the weaker
+// tensile side yields. Returns (moment, neutral-axis offset).
+fn section_moment(section: &TubularSection, law: &BoneTissueLaw, curvature: f64) -> (f64, f64) {
+    let r = section.outer_radius_mm;
+    let dy = 2.0 * r / SECTION_STRIPS as f64;
+    let fibres: Vec<(f64, f64)> = (0..SECTION_STRIPS)
+        .map(|k| {
+            let y = -r + (k as f64 + 0.5) * dy;
+            (y, section.width_at(y) * dy)
+        })
+        .collect();
+    let axial = |offset: f64| -> f64 {
+        fibres
+            .iter()
+            .map(|&(y, area)| law.axial_stress(curvature * (y - offset)) * area)
+            .sum()
+    };
+    let (mut lo, mut hi) = (-r, r);
+    for _ in 0..60 {
+        let mid = 0.5 * (lo + hi);
+        if axial(mid) > 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let offset = 0.5 * (lo + hi);
+    let moment = fibres
+        .iter()
+        .map(|&(y, area)| law.axial_stress(curvature * (y - offset)) * area * (y - offset))
+        .sum();
+    (moment, offset)
+}
+
+// Builds the moment-curvature relation up to first fibre fracture, then
+// for each midspan curvature integrates curvature along the half-span
+// (moment-area theorem) to get midspan deflection under F = 4M/S.
+fn bending_curve(section: &TubularSection, law: &BoneTissueLaw, span_mm: f64) -> Vec<(f64, f64)> {
+    let r = section.outer_radius_mm;
+    let step = law
+        .tension
+        .ultimate_strain
+        .min(law.compression.ultimate_strain)
+        / r
+        / 400.0;
+    let mut table = vec![(0.0, 0.0)];
+    let mut curvature = 0.0;
+    loop {
+        curvature += step;
+        let (moment, offset) = section_moment(section, law, curvature);
+        let tensile = curvature * (r - offset);
+        let compressive = curvature * (r + offset);
+        if tensile > law.tension.ultimate_strain
+            || compressive > law.compression.ultimate_strain
+            || moment <= table[table.len() - 1].1
+        {
+            break;
+        }
+        table.push((curvature, moment));
+    }
+    let curvature_at = |moment: f64| -> f64 {
+        let i = table
+            .partition_point(|&(_, m)| m < moment)
+            .clamp(1, table.len() - 1);
+        let (k0, m0) = table[i - 1];
+        let (k1, m1) = table[i];
+        k0 + (k1 - k0) * (moment - m0) / (m1 - m0)
+    };
+    let half = span_mm / 2.0;
+    let n = 100;
+    let dx = half / n as f64;
+    let stride = (table.len() / CURVE_POINTS).max(1);
+    let mut curve: Vec<(f64, f64)> = table
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| i % stride == 0 || *i == table.len() - 1)
+        .map(|(_, &(_, m_mid))| {
+            let deflection: f64 = (0..n)
+                .map(|k| {
+                    let x = (k as f64 + 0.5) * dx;
+                    curvature_at(m_mid * x / half) * x * dx
+                })
+                .sum();
+            (deflection, 4.0 * m_mid / span_mm)
+        })
+        .collect();
+    curve.dedup_by(|a, b| a.0 == b.0);
+    curve
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cortical() -> BoneTissueLaw {
+        BoneTissueLaw::from_material(&BoneMaterial::cortical()).unwrap()
+    }
+
+    #[test]
+    fn test_elastic_stiffness_matches_beam_and_shaft_theory() {
+        let section = TubularSection::femoral_midshaft();
+        let law = cortical();
+        let e = law.tension.modulus_mpa;
+        let span = 300.0;
+        let bending = run_test(
+            &section,
+            &law,
+            MechanicalTest::ThreePointBending { span_mm: span },
+        )
+        .unwrap();
+        let expected = 48.0 * e * section.second_moment_mm4() / span.powi(3);
+        assert!((bending.stiffness / expected - 1.0).abs() < 0.02);
+        let torsion =
+            run_test(&section, &law, MechanicalTest::Torsion { length_mm: 200.0 }).unwrap();
+        let expected = law.shear.modulus_mpa * section.polar_moment_mm4() / 200.0;
+        assert!((torsion.stiffness / expected - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_compression_reaches_material_strength() {
+        let section = TubularSection::femoral_midshaft();
+        let result = run_test(
+            &section,
+            &cortical(),
+            MechanicalTest::Compression { length_mm: 50.0 },
+        )
+        .unwrap();
+        assert!((result.ultimate_load / (193.0 * section.area_mm2()) - 1.0).abs() < 1e-6);
+        assert!(result.yield_load < result.ultimate_load);
+        assert!(result.work_to_failure > 0.0);
+        let trabecular = BoneTissueLaw::from_material(&BoneMaterial::trabecular()).unwrap();
+        let weak = run_test(
+            &section,
+            &trabecular,
+            MechanicalTest::Compression { length_mm: 50.0 },
+        )
+        .unwrap();
+        assert!(weak.ultimate_load < result.ultimate_load / 50.0);
+    }
+
+    #[test]
+    fn test_femoral_bending_fails_at_kilonewtons() {
+        let section = TubularSection::femoral_midshaft();
+        let law = cortical();
+        let result = run_test(
+            &section,
+            &law,
+            MechanicalTest::ThreePointBending { span_mm: 300.0 },
+        )
+        .unwrap();
+        // Whole human femora break at a few kN in three-point bending.
+        assert!(result.ultimate_load > 2000.0 && result.ultimate_load < 6000.0);
+        let elastic_limit = 4.0 * law.tension.yield_stress_mpa * section.second_moment_mm4()
+            / section.outer_radius_mm
+            / 300.0;
+        assert!(result.ultimate_load > elastic_limit);
+        assert!(result.yield_load < result.ultimate_load);
+        assert!(result.displacement_at_failure > result.ultimate_load / result.stiffness);
+    }
+
+    #[test]
+    fn test_rejects_bad_geometry() {
+        assert!(TubularSection::new(10.0, 12.0).is_err());
+        let section = TubularSection::femoral_midshaft();
+        assert!(run_test(
+            &section,
+            &cortical(),
+            MechanicalTest::Torsion { length_mm: 0.0 }
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,426 @@
+use crate::biology::{BiologyError, BiologyResult};
+use serde::{Deserialize, Serialize};
+
+// Generalised Maxwell solid, E(t) = E∞ + Σ Ei·exp(-t/τi). Creep is
+// integrated from the branch stresses qi, which obey
+// dqi/dt = Ei·dε/dt - qi/τi while E∞·ε + Σ qi holds the applied stress.
+
+const CREEP_STEPS_PER_TAU: f64 = 20.0;
+const FIT_MAX_ITERATIONS: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PronyTerm {
+    pub modulus_mpa: f64,
+    pub time_constant_s: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PronySeries {
+    pub equilibrium_modulus_mpa: f64,
+    pub terms: Vec<PronyTerm>,
+}
+
+impl PronySeries {
+    pub fn new(equilibrium_modulus_mpa: f64, terms: Vec<PronyTerm>) -> BiologyResult<Self> {
+        if equilibrium_modulus_mpa.is_nan() || equilibrium_modulus_mpa <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "equilibrium modulus must be positive".to_string(),
+            ));
+        }
+        for term in &terms {
+            if term.modulus_mpa.is_nan() || term.modulus_mpa < 0.0 {
+                return Err(BiologyError::InvalidValue(
+                    "Prony moduli cannot be negative".to_string(),
+                ));
+            }
+            if term.time_constant_s.is_nan() || term.time_constant_s <= 0.0 {
+                return Err(BiologyError::InvalidValue(
+                    "Prony time constants must be positive".to_string(),
+                ));
+            }
+        }
+        Ok(Self {
+            equilibrium_modulus_mpa,
+            terms,
+        })
+    }
+
+    // Illustrative fits: tendon relaxes by ~40% over minutes, cortical
+    // bone by ~15% over tens of minutes, instantaneous modulus matching
+    // the `BoneMaterial::cortical` preset.
+    pub fn tendon() -> Self {
+        Self::with_terms(720.0, &[(240.0, 1.0), (180.0, 20.0), (60.0, 300.0)])
+    }
+
+    pub fn cortical_bone() -> Self {
+        Self::with_terms(15200.0, &[(1500.0, 10.0), (1200.0, 1000.0)])
+    }
+
+    fn with_terms(equilibrium: f64, terms: &[(f64, f64)]) -> Self {
+        Self {
+            equilibrium_modulus_mpa: equilibrium,
+            terms: terms
+                .iter()
+                .map(|&(modulus_mpa, time_constant_s)| PronyTerm {
+                    modulus_mpa,
+                    time_constant_s,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn instantaneous_modulus_mpa(&self) -> f64 {
+        self.equilibrium_modulus_mpa + self.terms.iter().map(|t| t.modulus_mpa).sum::<f64>()
+    }
+
+    pub fn relaxation_modulus_mpa(&self, time_s: f64) -> f64 {
+        self.equilibrium_modulus_mpa
+            + self
+                .terms
+                .iter()
+                .map(|t| t.modulus_mpa * (-time_s / t.time_constant_s).exp())
+                .sum::<f64>()
+    }
+
+    // Step strain held constant; returns (time s, stress MPa).
+    pub fn relaxation_test(&self, strain: f64, times_s: &[f64]) -> Vec<(f64, f64)> {
+        times_s
+            .iter()
+            .map(|&t| (t, strain * self.relaxation_modulus_mpa(t)))
+            .collect()
+    }
+
+    // Step stress held constant; returns (time s, strain).
+    pub fn creep_test(&self, stress_mpa: f64, times_s: &[f64]) -> Vec<(f64, f64)> {
+        let e0 = self.instantaneous_modulus_mpa();
+        let strain0 = stress_mpa / e0;
+        let mut q: Vec<f64> = self.terms.iter().map(|t| t.modulus_mpa * strain0).collect();
+        let tau_min = self
+            .terms
+            .iter()
+            .map(|t| t.time_constant_s)
+            .fold(f64::INFINITY, f64::min);
+        let max_step = tau_min / CREEP_STEPS_PER_TAU;
+        let rate = |q: &[f64]| -> Vec<f64> {
+            let strain_rate = q
+                .iter()
+                .zip(&self.terms)
+                .map(|(qi, t)| qi / t.time_constant_s)
+                .sum::<f64>()
+                / e0;
+            q.iter()
+                .zip(&self.terms)
+                .map(|(qi, t)| t.modulus_mpa * strain_rate - qi / t.time_constant_s)
+                .collect()
+        };
+        let mut now = 0.0;
+        times_s
+            .iter()
+            .map(|&target| {
+                while now < target {
+                    let h = (target - now).min(max_step);
+                    let k1 = rate(&q);
+                    let k2 = rate(&axpy(&q, &k1, h / 2.0));
+                    let k3 = rate(&axpy(&q, &k2, h / 2.0));
+                    let k4 = rate(&axpy(&q, &k3, h));
+                    for i in 0..q.len() {
+                        q[i] += h / 6.0 * (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]);
+                    }
+                    now += h;
+                }
+                let branches: f64 = q.iter().sum();
+                (
+                    target,
+                    (stress_mpa - branches) / self.equilibrium_modulus_mpa,
+                )
+            })
+            .collect()
+    }
+}
+
+fn axpy(q: &[f64], k: &[f64], h: f64) -> Vec<f64> {
+    q.iter().zip(k).map(|(a, b)| a + h * b).collect()
+}
+
+// Zero followed by log-spaced samples, so every decade is fitted.
+pub fn log_spaced_times(first_s: f64, last_s: f64, count: usize) -> Vec<f64> {
+    let mut times = vec![0.0];
+    let (a, b) = (first_s.ln(), last_s.ln());
+    let n = count.max(2);
+    times.extend((0..n).map(|i| (a + (b - a) * i as f64 / (n - 1) as f64).exp()));
+    times
+}
+
+fn validate_fit_input(curve: &[(f64, f64)], load: f64, taus: &[f64]) -> BiologyResult<()> {
+    if load.is_nan() || load == 0.0 {
+        return Err(BiologyError::InvalidValue(
+            "applied load must be non-zero".to_string(),
+        ));
+    }
+    if taus.is_empty() || taus.iter().any(|t| t.is_nan() || *t <= 0.0) {
+        return Err(BiologyError::InvalidValue(
+            "time constants must be positive".to_string(),
+        ));
+    }
+    if curve.len() <= taus.len() + 1 {
+        return Err(BiologyError::InvalidValue(
+            "need more samples than Prony parameters".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+// Least squares for E∞ and Ei at fixed τi; terms that come out negative
+// are dropped and the rest refitted, the usual active-set shortcut.
+pub fn fit_relaxation(
+    curve: &[(f64, f64)],
+    strain: f64,
+    taus_s: &[f64],
+) -> BiologyResult<PronySeries> {
+    validate_fit_input(curve, strain, taus_s)?;
+    let mut active: Vec<f64> = taus_s.to_vec();
+    loop {
+        let rows: Vec<Vec<f64>> = curve
+            .iter()
+            .map(|&(t, _)| {
+                std::iter::once(1.0)
+                    .chain(active.iter().map(|tau| (-t / tau).exp()))
+                    .collect()
+            })
+            .collect();
+        let targets: Vec<f64> = curve.iter().map(|&(_, stress)| stress / strain).collect();
+        let coefficients = least_squares(&rows, &targets)?;
+        let worst = coefficients[1..]
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c < 0.0)
+            .min_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, _)| i);
+        match worst {
+            Some(i) => {
+                active.remove(i);
+            }
+            None => {
+                let terms = active
+                    .iter()
+                    .zip(&coefficients[1..])
+                    .map(|(&time_constant_s, &modulus_mpa)| PronyTerm {
+                        modulus_mpa,
+                        time_constant_s,
+                    })
+                    .collect();
+                return PronySeries::new(coefficients[0], terms);
+            }
+        }
+    }
+}
+
+// Starts from the quasi-elastic guess E(t) ≈ σ/ε(t), then refines the
+// log-moduli by Levenberg-Marquardt against the simulated creep curve.
+pub fn fit_creep(
+    curve: &[(f64, f64)],
+    stress_mpa: f64,
+    taus_s: &[f64],
+) -> BiologyResult<PronySeries> {
+    validate_fit_input(curve, stress_mpa, taus_s)?;
+    let apparent: Vec<(f64, f64)> = curve
+        .iter()
+        .map(|&(t, strain)| (t, stress_mpa * stress_mpa / strain))
+        .collect();
+    let guess = fit_relaxation(&apparent, stress_mpa, taus_s)?;
+    let mut params: Vec<f64> = std::iter::once(guess.equilibrium_modulus_mpa)
+        .chain(taus_s.iter().map(|&tau| {
+            guess
+                .terms
+                .iter()
+                .find(|t| t.time_constant_s == tau)
+                .map_or(1e-3, |t| t.modulus_mpa.max(1e-3))
+        }))
+        .map(f64::ln)
+        .collect();
+    let times: Vec<f64> = curve.iter().map(|&(t, _)| t).collect();
+    let scale = curve.iter().map(|&(_, e)| e.abs()).fold(0.0, f64::max);
+    let residuals = |p: &[f64]| -> Vec<f64> {
+        let series = PronySeries::with_terms(
+            p[0].exp(),
+            &p[1..]
+                .iter()
+                .zip(taus_s)
+                .map(|(lp, &tau)| (lp.exp(), tau))
+                .collect::<Vec<_>>(),
+        );
+        series
+            .creep_test(stress_mpa, &times)
+            .iter()
+            .zip(curve)
+            .map(|(model, data)| (model.1 - data.1) / scale)
+            .collect()
+    };
+    let cost = |r: &[f64]| r.iter().map(|x| x * x).sum::<f64>();
+    let mut r = residuals(&params);
+    let mut damping = 1e-3;
+    for _ in 0..FIT_MAX_ITERATIONS {
+        let columns: Vec<Vec<f64>> = (0..params.len())
+            .map(|k| {
+                let mut shifted = params.clone();
+                shifted[k] += 1e-6;
+                residuals(&shifted)
+                    .iter()
+                    .zip(&r)
+                    .map(|(a, b)| (a - b) / 1e-6)
+                    .collect()
+            })
+            .collect();
+        let n = params.len();
+        let mut normal = vec![vec![0.0; n]; n];
+        let mut gradient = vec![0.0; n];
+        for i in 0..n {
+            for j in 0..n {
+                normal[i][j] = columns[i].iter().zip(&columns[j]).map(|(a, b)| a * b).sum();
+            }
+            gradient[i] = -columns[i].iter().zip(&r).map(|(a, b)| a * b).sum::<f64>();
+        }
+        let mut improved = false;
+        while damping < 1e8 {
+            let mut damped = normal.clone();
+            for (i, row) in damped.iter_mut().enumerate() {
+                row[i] += damping * normal[i][i].max(1e-12);
+            }
+            let step = solve(damped, gradient.clone())?;
+            let trial: Vec<f64> = params.iter().zip(&step).map(|(p, s)| p + s).collect();
+            let trial_r = residuals(&trial);
+            if cost(&trial_r) < cost(&r) {
+                params = trial;
+                r = trial_r;
+                damping = (damping / 10.0).max(1e-9);
+                improved = true;
+                break;
+            }
+            damping *= 10.0;
+        }
+        if !improved || cost(&r) < 1e-20 {
+            break;
+        }
+    }
+    let terms = params[1..]
+        .iter()
+        .zip(taus_s)
+        .map(|(lp, &time_constant_s)| PronyTerm {
+            modulus_mpa: lp.exp(),
+            time_constant_s,
+        })
+        .collect();
+    PronySeries::new(params[0].exp(), terms)
+}
+
+fn least_squares(rows: &[Vec<f64>], targets: &[f64]) -> BiologyResult<Vec<f64>> {
+    let n = rows[0].len();
+    let mut normal = vec![vec![0.0; n]; n];
+    let mut rhs = vec![0.0; n];
+    for (row, &y) in rows.iter().zip(targets) {
+        for i in 0..n {
+            rhs[i] += row[i] * y;
+            for j in 0..n {
+                normal[i][j] += row[i] * row[j];
+            }
+        }
+    }
+    solve(normal, rhs)
+}
+
+// Gaussian elimination with partial pivoting.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> BiologyResult<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n)
+            .max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))
+            .unwrap_or(col);
+        if a[pivot][col].abs() < 1e-300 {
+            return Err(BiologyError::InvalidState(
+                "Prony fit is singular; spread the time constants".to_string(),
+            ));
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let factor = a[row][col] / pivot_row[col];
+            for (x, p) in a[row][col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Ok(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64, tolerance: f64) {
+        assert!((a / b - 1.0).abs() < tolerance, "{a} vs {b}");
+    }
+
+    #[test]
+    fn test_creep_runs_between_instantaneous_and_equilibrium_compliance() {
+        let tendon = PronySeries::tendon();
+        let curve = tendon.creep_test(10.0, &log_spaced_times(0.01, 5000.0, 40));
+        assert_close(curve[0].1, 10.0 / tendon.instantaneous_modulus_mpa(), 1e-9);
+        assert_close(
+            curve[curve.len() - 1].1,
+            10.0 / tendon.equilibrium_modulus_mpa,
+            1e-3,
+        );
+        assert!(curve.windows(2).all(|w| w[1].1 >= w[0].1));
+        // Creep is slower than relaxation for the same material.
+        let half_relaxed = tendon.relaxation_modulus_mpa(20.0);
+        let half_crept = 10.0 / tendon.creep_test(10.0, &[20.0])[0].1;
+        assert!(half_crept > half_relaxed);
+    }
+
+    #[test]
+    fn test_relaxation_fit_round_trips() {
+        let bone = PronySeries::cortical_bone();
+        let curve = bone.relaxation_test(0.002, &log_spaced_times(0.1, 10000.0, 50));
+        let fitted = fit_relaxation(&curve, 0.002, &[10.0, 1000.0]).unwrap();
+        assert_close(fitted.equilibrium_modulus_mpa, 15200.0, 1e-6);
+        assert_close(fitted.terms[0].modulus_mpa, 1500.0, 1e-6);
+        assert_close(fitted.terms[1].modulus_mpa, 1200.0, 1e-6);
+        // Redundant time constants are pruned rather than going negative.
+        let padded = fit_relaxation(&curve, 0.002, &[1.0, 10.0, 100.0, 1000.0]).unwrap();
+        assert!(padded.terms.iter().all(|t| t.modulus_mpa >= 0.0));
+        assert_close(
+            padded.instantaneous_modulus_mpa(),
+            bone.instantaneous_modulus_mpa(),
+            1e-3,
+        );
+    }
+
+    #[test]
+    fn test_creep_fit_recovers_relaxation_spectrum() {
+        let tendon = PronySeries::tendon();
+        let curve = tendon.creep_test(5.0, &log_spaced_times(0.01, 3000.0, 40));
+        let fitted = fit_creep(&curve, 5.0, &[1.0, 20.0, 300.0]).unwrap();
+        for t in [0.0, 1.0, 20.0, 300.0, 3000.0] {
+            assert_close(
+                fitted.relaxation_modulus_mpa(t),
+                tendon.relaxation_modulus_mpa(t),
+                0.01,
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_parameters() {
+        assert!(PronySeries::new(0.0, vec![]).is_err());
+        let curve = PronySeries::tendon().relaxation_test(0.01, &[0.0, 1.0]);
+        assert!(fit_relaxation(&curve, 0.01, &[1.0, 10.0]).is_err());
+        assert!(fit_relaxation(&curve, 0.0, &[1.0]).is_err());
+    }
+}
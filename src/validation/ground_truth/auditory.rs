@@ -1,4 +1,6 @@
-use crate::validation::ground_truth::{GroundTruthDatabase, GroundTruthData, GroundTruthDataPoint, ClinicalReference, EvidenceLevel};
+use crate::validation::ground_truth::{
+    ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDataPoint, GroundTruthDatabase,
+};
 
 pub fn initialize_auditory_data(db: &mut GroundTruthDatabase) {
     let mut auditory_data = GroundTruthData::new(
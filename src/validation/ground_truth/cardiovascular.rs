@@ -1,4 +1,6 @@
-use crate::validation::ground_truth::{GroundTruthDatabase, GroundTruthData, GroundTruthDataPoint, ClinicalReference, EvidenceLevel};
+use crate::validation::ground_truth::{
+    ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDataPoint, GroundTruthDatabase,
+};
 
 pub fn initialize_cardiovascular_data(db: &mut GroundTruthDatabase) {
     let mut cv_data = GroundTruthData::new(
@@ -1,4 +1,6 @@
-use crate::validation::ground_truth::{GroundTruthDatabase, GroundTruthData, GroundTruthDataPoint, ClinicalReference, EvidenceLevel};
+use crate::validation::ground_truth::{
+    ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDataPoint, GroundTruthDatabase,
+};
 
 pub fn initialize_endocrine_data(db: &mut GroundTruthDatabase) {
     let mut endo_data = GroundTruthData::new(
@@ -50,8 +52,7 @@ pub fn initialize_endocrine_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("31536142".to_string()),
             doi: Some("10.1530/EJE-19-0567".to_string()),
-            citation: "Deutschbein T et al. (2019) Eur J Endocrinol 181(5):R209-R225"
-                .to_string(),
+            citation: "Deutschbein T et al. (2019) Eur J Endocrinol 181(5):R209-R225".to_string(),
             year: 2019,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: Some(12000),
@@ -68,8 +69,7 @@ pub fn initialize_endocrine_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("31536142".to_string()),
             doi: Some("10.1530/EJE-19-0567".to_string()),
-            citation: "Deutschbein T et al. (2019) Eur J Endocrinol 181(5):R209-R225"
-                .to_string(),
+            citation: "Deutschbein T et al. (2019) Eur J Endocrinol 181(5):R209-R225".to_string(),
             year: 2019,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: Some(12000),
@@ -86,8 +86,7 @@ pub fn initialize_endocrine_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("21310306".to_string()),
             doi: Some("10.1210/jc.2011-0385".to_string()),
-            citation: "Holick MF et al. (2011) J Clin Endocrinol Metab 96(7):1911-1930"
-                .to_string(),
+            citation: "Holick MF et al. (2011) J Clin Endocrinol Metab 96(7):1911-1930".to_string(),
             year: 2011,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: None,
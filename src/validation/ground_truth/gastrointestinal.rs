@@ -1,4 +1,6 @@
-use crate::validation::ground_truth::{GroundTruthDatabase, GroundTruthData, GroundTruthDataPoint, ClinicalReference, EvidenceLevel};
+use crate::validation::ground_truth::{
+    ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDataPoint, GroundTruthDatabase,
+};
 
 pub fn initialize_gastrointestinal_data(db: &mut GroundTruthDatabase) {
     let mut gi_data = GroundTruthData::new(
@@ -33,8 +35,7 @@ pub fn initialize_gastrointestinal_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("21645639".to_string()),
             doi: Some("10.3748/wjg.v17.i21.2584".to_string()),
-            citation: "Rao SSC et al. (2011) World J Gastroenterol 17(21):2584-2596"
-                .to_string(),
+            citation: "Rao SSC et al. (2011) World J Gastroenterol 17(21):2584-2596".to_string(),
             year: 2011,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: Some(2800),
@@ -51,8 +52,7 @@ pub fn initialize_gastrointestinal_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("21645639".to_string()),
             doi: Some("10.3748/wjg.v17.i21.2584".to_string()),
-            citation: "Rao SSC et al. (2011) World J Gastroenterol 17(21):2584-2596"
-                .to_string(),
+            citation: "Rao SSC et al. (2011) World J Gastroenterol 17(21):2584-2596".to_string(),
             year: 2011,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: Some(2800),
@@ -1,4 +1,6 @@
-use crate::validation::ground_truth::{GroundTruthDatabase, GroundTruthData, GroundTruthDataPoint, ClinicalReference, EvidenceLevel};
+use crate::validation::ground_truth::{
+    ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDataPoint, GroundTruthDatabase,
+};
 
 pub fn initialize_hepatic_data(db: &mut GroundTruthDatabase) {
     let mut hepatic_data = GroundTruthData::new(
@@ -15,8 +17,7 @@ pub fn initialize_hepatic_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("29661585".to_string()),
             doi: Some("10.1111/apt.14679".to_string()),
-            citation: "Kwo PY et al. (2018) Aliment Pharmacol Ther 47(11):1447-1454"
-                .to_string(),
+            citation: "Kwo PY et al. (2018) Aliment Pharmacol Ther 47(11):1447-1454".to_string(),
             year: 2018,
             evidence_level: EvidenceLevel::MetaAnalysis,
             sample_size: Some(250000),
@@ -33,8 +34,7 @@ pub fn initialize_hepatic_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("29661585".to_string()),
             doi: Some("10.1111/apt.14679".to_string()),
-            citation: "Kwo PY et al. (2018) Aliment Pharmacol Ther 47(11):1447-1454"
-                .to_string(),
+            citation: "Kwo PY et al. (2018) Aliment Pharmacol Ther 47(11):1447-1454".to_string(),
             year: 2018,
             evidence_level: EvidenceLevel::MetaAnalysis,
             sample_size: Some(250000),
@@ -2,27 +2,27 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // Module declarations
-pub mod cardiovascular;
-pub mod metabolic;
 pub mod aldh2;
-pub mod respiratory;
-pub mod renal;
+pub mod alzheimers;
+pub mod auditory;
+pub mod cardiovascular;
+pub mod dental;
+pub mod dermatology;
 pub mod endocrine;
-pub mod hematology;
-pub mod neurological;
 pub mod gastrointestinal;
-pub mod musculoskeletal;
-pub mod immunology;
+pub mod hematology;
 pub mod hepatic;
-pub mod dermatology;
+pub mod immunology;
+pub mod metabolic;
+pub mod musculoskeletal;
+pub mod neurological;
+pub mod oncology;
 pub mod ophthalmology;
-pub mod auditory;
-pub mod dental;
 pub mod pulmonary;
+pub mod renal;
+pub mod respiratory;
 pub mod rheumatology;
 pub mod urology;
-pub mod oncology;
-pub mod alzheimers;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum EvidenceLevel {
@@ -144,9 +144,18 @@ impl GroundTruthDatabase {
         pulmonary::initialize_pulmonary_data(&mut db);
         rheumatology::initialize_rheumatology_data(&mut db);
         urology::initialize_urology_data(&mut db);
-        db.add_dataset("cancer_biomarkers".to_string(), oncology::get_cancer_biomarkers());
-        db.add_dataset("inflammation_markers".to_string(), oncology::get_inflammation_markers());
-        db.add_dataset("alzheimers_biomarkers".to_string(), alzheimers::get_alzheimers_biomarkers());
+        db.add_dataset(
+            "cancer_biomarkers".to_string(),
+            oncology::get_cancer_biomarkers(),
+        );
+        db.add_dataset(
+            "inflammation_markers".to_string(),
+            oncology::get_inflammation_markers(),
+        );
+        db.add_dataset(
+            "alzheimers_biomarkers".to_string(),
+            alzheimers::get_alzheimers_biomarkers(),
+        );
         db
     }
 
@@ -173,4 +182,10 @@ impl GroundTruthDatabase {
         println!("Categories: {}", categories.len());
         println!("Total parameters: {}", total_params);
     }
-}
\ No newline at end of file
+}
+
+impl Default for GroundTruthDatabase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
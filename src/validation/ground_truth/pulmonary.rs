@@ -1,4 +1,6 @@
-use crate::validation::ground_truth::{GroundTruthDatabase, GroundTruthData, GroundTruthDataPoint, ClinicalReference, EvidenceLevel};
+use crate::validation::ground_truth::{
+    ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDataPoint, GroundTruthDatabase,
+};
 
 pub fn initialize_pulmonary_data(db: &mut GroundTruthDatabase) {
     let mut pulmonary_data = GroundTruthData::new(
@@ -66,8 +68,7 @@ pub fn initialize_pulmonary_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("28245654".to_string()),
             doi: Some("10.1513/AnnalsATS.201607-571FR".to_string()),
-            citation: "Stanojevic S et al. (2017) Ann Am Thorac Soc 14(Suppl 1):S1-S11"
-                .to_string(),
+            citation: "Stanojevic S et al. (2017) Ann Am Thorac Soc 14(Suppl 1):S1-S11".to_string(),
             year: 2017,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: Some(15500),
@@ -84,8 +85,7 @@ pub fn initialize_pulmonary_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("27872413".to_string()),
             doi: Some("10.1513/AnnalsATS.201605-387FR".to_string()),
-            citation: "Stocks J et al. (2016) Ann Am Thorac Soc 13(Suppl 5):S364-S387"
-                .to_string(),
+            citation: "Stocks J et al. (2016) Ann Am Thorac Soc 13(Suppl 5):S364-S387".to_string(),
             year: 2016,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: Some(12800),
@@ -102,8 +102,7 @@ pub fn initialize_pulmonary_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("27872413".to_string()),
             doi: Some("10.1513/AnnalsATS.201605-387FR".to_string()),
-            citation: "Stocks J et al. (2016) Ann Am Thorac Soc 13(Suppl 5):S364-S387"
-                .to_string(),
+            citation: "Stocks J et al. (2016) Ann Am Thorac Soc 13(Suppl 5):S364-S387".to_string(),
             year: 2016,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: Some(12800),
@@ -1,4 +1,6 @@
-use crate::validation::ground_truth::{GroundTruthDatabase, GroundTruthData, GroundTruthDataPoint, ClinicalReference, EvidenceLevel};
+use crate::validation::ground_truth::{
+    ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDataPoint, GroundTruthDatabase,
+};
 
 pub fn initialize_renal_data(db: &mut GroundTruthDatabase) {
     let mut renal_data = GroundTruthData::new(
@@ -83,8 +85,7 @@ pub fn initialize_renal_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("32657974".to_string()),
             doi: Some("10.1093/ndt/gfz282".to_string()),
-            citation: "Delgado C et al. (2020) Nephrol Dial Transplant 35(2):185-191"
-                .to_string(),
+            citation: "Delgado C et al. (2020) Nephrol Dial Transplant 35(2):185-191".to_string(),
             year: 2020,
             evidence_level: EvidenceLevel::CohortStudy,
             sample_size: Some(5504),
@@ -101,8 +102,7 @@ pub fn initialize_renal_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("32657974".to_string()),
             doi: Some("10.1093/ndt/gfz282".to_string()),
-            citation: "Delgado C et al. (2020) Nephrol Dial Transplant 35(2):185-191"
-                .to_string(),
+            citation: "Delgado C et al. (2020) Nephrol Dial Transplant 35(2):185-191".to_string(),
             year: 2020,
             evidence_level: EvidenceLevel::CohortStudy,
             sample_size: Some(5504),
@@ -1,4 +1,6 @@
-use crate::validation::ground_truth::{GroundTruthDatabase, GroundTruthData, GroundTruthDataPoint, ClinicalReference, EvidenceLevel};
+use crate::validation::ground_truth::{
+    ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDataPoint, GroundTruthDatabase,
+};
 
 pub fn initialize_rheumatology_data(db: &mut GroundTruthDatabase) {
     let mut rheum_data = GroundTruthData::new(
@@ -117,8 +119,7 @@ pub fn initialize_rheumatology_data(db: &mut GroundTruthDatabase) {
         reference: ClinicalReference {
             pmid: Some("21646368".to_string()),
             doi: Some("10.1210/jc.2011-0385".to_string()),
-            citation: "Holick MF et al. (2011) J Clin Endocrinol Metab 96(7):1911-1930"
-                .to_string(),
+            citation: "Holick MF et al. (2011) J Clin Endocrinol Metab 96(7):1911-1930".to_string(),
             year: 2011,
             evidence_level: EvidenceLevel::SystematicReview,
             sample_size: None,
@@ -0,0 +1,272 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::io::vtk::DataArray;
+use crate::metabolism::reaction_network::ReactionNetwork;
+use crate::systems::nervous::action_potential::HodgkinHuxleyModel;
+use crate::systems::skeletal::spectroscopy::BoneMatrix;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Domain constraints every model state must satisfy whatever its
+// parameters: amounts are non-negative, compositions are closed, gating
+// variables are probabilities and stress tensors are symmetric. Models
+// report through `Invariants`; the property tests in tests/invariants.rs
+// drive them with generated inputs.
+
+// Beyond the potassium and sodium reversal potentials by tens of
+// millivolts; a membrane outside this range means the integrator has
+// diverged.
+pub const MEMBRANE_VOLTAGE_RANGE_MV: (f64, f64) = (-120.0, 80.0);
+
+// Absolute slack for quantities an adaptive ODE solver may undershoot by
+// roundoff. Integrated networks also get their solver's own error budget.
+pub const CONCENTRATION_TOLERANCE: f64 = 1e-9;
+
+pub const COMPOSITION_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    pub quantity: String,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.quantity, self.message)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InvariantCheck {
+    pub violations: Vec<Violation>,
+}
+
+impl InvariantCheck {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn fail(&mut self, quantity: &str, message: String) -> &mut Self {
+        self.violations.push(Violation {
+            quantity: quantity.to_string(),
+            message,
+        });
+        self
+    }
+
+    pub fn finite(&mut self, quantity: &str, value: f64) -> &mut Self {
+        if !value.is_finite() {
+            return self.fail(quantity, format!("{} is not finite", value));
+        }
+        self
+    }
+
+    pub fn at_least(&mut self, quantity: &str, value: f64, minimum: f64) -> &mut Self {
+        if value.is_nan() || value < minimum {
+            return self.fail(quantity, format!("{} is below {}", value, minimum));
+        }
+        self
+    }
+
+    pub fn non_negative(&mut self, quantity: &str, value: f64) -> &mut Self {
+        self.at_least(quantity, value, 0.0)
+    }
+
+    pub fn bounded(&mut self, quantity: &str, value: f64, low: f64, high: f64) -> &mut Self {
+        if !(low..=high).contains(&value) {
+            return self.fail(
+                quantity,
+                format!("{} is outside [{}, {}]", value, low, high),
+            );
+        }
+        self
+    }
+
+    pub fn fraction(&mut self, quantity: &str, value: f64) -> &mut Self {
+        self.bounded(quantity, value, 0.0, 1.0)
+    }
+
+    // Parts of a closed composition: each non-negative and together equal
+    // to `total`, 1 for fractions or 100 for percentages.
+    pub fn sums_to(&mut self, quantity: &str, parts: &[f64], total: f64) -> &mut Self {
+        for (i, &part) in parts.iter().enumerate() {
+            self.non_negative(&format!("{}[{}]", quantity, i), part);
+        }
+        let sum: f64 = parts.iter().sum();
+        if (sum - total).abs() > COMPOSITION_TOLERANCE * total.abs().max(1.0) || sum.is_nan() {
+            return self.fail(quantity, format!("parts sum to {}, not {}", sum, total));
+        }
+        self
+    }
+
+    // Cauchy stress is symmetric by conservation of angular momentum;
+    // `tolerance` is relative to the largest component.
+    pub fn symmetric(
+        &mut self,
+        quantity: &str,
+        tensor: &[[f64; 3]; 3],
+        tolerance: f64,
+    ) -> &mut Self {
+        let scale = tensor
+            .iter()
+            .flatten()
+            .fold(0.0_f64, |m, v| m.max(v.abs()))
+            .max(f64::MIN_POSITIVE);
+        for (i, j) in [(0, 1), (0, 2), (1, 2)] {
+            let skew = (tensor[i][j] - tensor[j][i]).abs();
+            if skew.is_nan() || skew > tolerance * scale {
+                return self.fail(
+                    quantity,
+                    format!(
+                        "components {}{} and {}{} differ: {} vs {}",
+                        i, j, j, i, tensor[i][j], tensor[j][i]
+                    ),
+                );
+            }
+        }
+        self
+    }
+
+    pub fn is_satisfied(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn into_result(self) -> BiologyResult<()> {
+        if self.violations.is_empty() {
+            return Ok(());
+        }
+        let messages: Vec<String> = self.violations.iter().map(|v| v.to_string()).collect();
+        Err(BiologyError::InvalidState(messages.join("; ")))
+    }
+}
+
+pub trait Invariants {
+    fn check_invariants(&self, check: &mut InvariantCheck);
+
+    fn validate_invariants(&self) -> BiologyResult<()> {
+        let mut check = InvariantCheck::new();
+        self.check_invariants(&mut check);
+        check.into_result()
+    }
+}
+
+impl Invariants for BoneMatrix {
+    fn check_invariants(&self, check: &mut InvariantCheck) {
+        check
+            .sums_to(
+                "bone matrix mass fractions",
+                &[
+                    self.mineral_fraction,
+                    self.organic_fraction,
+                    self.water_fraction,
+                ],
+                1.0,
+            )
+            .fraction("mineral crystallinity", self.mineral_crystallinity)
+            .non_negative("crosslink ratio", self.mature_to_immature_crosslinks)
+            .non_negative("crystal length", self.crystal.length_nm)
+            .non_negative("crystal width", self.crystal.width_nm)
+            .non_negative("crystal thickness", self.crystal.thickness_nm)
+            .non_negative("carbonate", self.substitutions.carbonate_wt_percent);
+    }
+}
+
+// The solver weights each species' error by atol + rtol·|y| over a step,
+// so a species that drains to zero within one step may be left below zero
+// by up to rtol times the level it drained from. The slack is bounded by
+// that species' own peak, never by other, larger pools.
+impl Invariants for ReactionNetwork {
+    fn check_invariants(&self, check: &mut InvariantCheck) {
+        for ((id, molecule), &c) in self.molecules.iter().zip(&self.concentrations) {
+            let slack = CONCENTRATION_TOLERANCE
+                .max(self.solver.atol + self.solver.rtol * self.peak_concentration(id));
+            check
+                .finite(&molecule.name, c)
+                .at_least(&molecule.name, c, -slack);
+        }
+    }
+}
+
+impl Invariants for HodgkinHuxleyModel {
+    fn check_invariants(&self, check: &mut InvariantCheck) {
+        let (low, high) = MEMBRANE_VOLTAGE_RANGE_MV;
+        check
+            .bounded("membrane voltage", self.v_membrane_mv, low, high)
+            .fraction("Na activation m", self.m_activation)
+            .fraction("Na inactivation h", self.h_inactivation)
+            .fraction("K activation n", self.n_potassium);
+    }
+}
+
+// Nine-component arrays are tensor fields and each tuple must be
+// symmetric; other arrays need only finite values.
+impl Invariants for DataArray {
+    fn check_invariants(&self, check: &mut InvariantCheck) {
+        for &value in &self.values {
+            check.finite(&self.name, value);
+        }
+        if self.components != 9 {
+            return;
+        }
+        for (i, tuple) in self.values.chunks_exact(9).enumerate() {
+            let tensor = [
+                [tuple[0], tuple[1], tuple[2]],
+                [tuple[3], tuple[4], tuple[5]],
+                [tuple[6], tuple[7], tuple[8]],
+            ];
+            check.symmetric(&format!("{}[{}]", self.name, i), &tensor, 1e-9);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_collects_every_violation() {
+        let mut check = InvariantCheck::new();
+        check
+            .non_negative("glucose", -1.0)
+            .fraction("open probability", 1.2)
+            .sums_to("composition", &[60.0, 30.0, 10.0], 100.0)
+            .finite("flux", f64::NAN);
+        assert_eq!(check.violations.len(), 3);
+        let error = check.into_result().unwrap_err().to_string();
+        assert!(error.contains("glucose") && error.contains("flux"));
+    }
+
+    #[test]
+    fn test_large_pools_do_not_excuse_a_negative_species() {
+        let mut network = ReactionNetwork::new();
+        network.add_species("substrate", 1000.0);
+        let product = network.add_species("product", 0.0);
+        assert!(network.validate_invariants().is_ok());
+        network.concentrations[product.index()] = -1e-6;
+        assert!(network.validate_invariants().is_err());
+    }
+
+    #[test]
+    fn test_drifted_bone_matrix_is_flagged() {
+        assert!(BoneMatrix::cortical_adult().validate_invariants().is_ok());
+        let mut drifted = BoneMatrix::cortical_adult();
+        drifted.mineral_fraction += 0.1;
+        drifted.organic_fraction -= 0.3;
+        let mut check = InvariantCheck::new();
+        drifted.check_invariants(&mut check);
+        assert_eq!(check.violations.len(), 2);
+    }
+
+    #[test]
+    fn test_asymmetric_tensor_field_is_flagged() {
+        let symmetric = [[1.0, 2.0, 3.0], [2.0, 4.0, 5.0], [3.0, 5.0, 6.0]];
+        let mut skewed = symmetric;
+        skewed[0][1] = 2.5;
+        assert!(DataArray::tensors("stress", &[symmetric])
+            .validate_invariants()
+            .is_ok());
+        let error = DataArray::tensors("stress", &[symmetric, skewed])
+            .validate_invariants()
+            .unwrap_err();
+        assert!(error.to_string().contains("stress[1]"));
+    }
+}
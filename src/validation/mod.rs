@@ -1,3 +1,9 @@
+pub mod benchmarks;
 pub mod ground_truth;
+pub mod invariants;
+pub mod provenance;
 
+pub use benchmarks::{BenchmarkReport, ReferenceDataset, ReferencePoint};
 pub use ground_truth::{ClinicalReference, EvidenceLevel, GroundTruthData, GroundTruthDatabase};
+pub use invariants::{InvariantCheck, Invariants, Violation};
+pub use provenance::{Cited, Provenance, ProvenanceCatalog, Uncertainty};
@@ -0,0 +1,315 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::simulation::runs::ParamValue;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Deref;
+
+// Where a parameter value came from: the publication, the organism and
+// conditions it was measured in, and how precisely. Keys in a
+// `ProvenanceCatalog` are Rust paths below the crate root, naming a preset
+// ("…::AntibodyKinetics::igg") or one of its fields
+// ("…::AntibodyKinetics::igg.long_half_life_days").
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Uncertainty {
+    StandardDeviation(f64),
+    StandardError(f64),
+    // Confidence interval or observed range, in the parameter's units.
+    Interval { low: f64, high: f64 },
+    // Fractional, e.g. 0.1 for ±10%.
+    Relative(f64),
+}
+
+impl fmt::Display for Uncertainty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Uncertainty::StandardDeviation(sd) => write!(f, "SD {}", sd),
+            Uncertainty::StandardError(se) => write!(f, "SE {}", se),
+            Uncertainty::Interval { low, high } => write!(f, "[{}, {}]", low, high),
+            Uncertainty::Relative(r) => write!(f, "±{}%", r * 100.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub citation: String,
+    pub doi: Option<String>,
+    pub pmid: Option<String>,
+    pub species: Option<String>,
+    pub conditions: Option<String>,
+    pub uncertainty: Option<Uncertainty>,
+}
+
+impl Provenance {
+    pub fn new(citation: &str) -> Self {
+        Self {
+            citation: citation.to_string(),
+            doi: None,
+            pmid: None,
+            species: None,
+            conditions: None,
+            uncertainty: None,
+        }
+    }
+
+    pub fn doi(mut self, doi: &str) -> Self {
+        self.doi = Some(doi.to_string());
+        self
+    }
+
+    pub fn pmid(mut self, pmid: &str) -> Self {
+        self.pmid = Some(pmid.to_string());
+        self
+    }
+
+    pub fn species(mut self, species: &str) -> Self {
+        self.species = Some(species.to_string());
+        self
+    }
+
+    pub fn conditions(mut self, conditions: &str) -> Self {
+        self.conditions = Some(conditions.to_string());
+        self
+    }
+
+    pub fn uncertainty(mut self, uncertainty: Uncertainty) -> Self {
+        self.uncertainty = Some(uncertainty);
+        self
+    }
+
+    // Resolvable identifier, DOI preferred.
+    pub fn identifier(&self) -> Option<String> {
+        self.doi
+            .as_ref()
+            .map(|doi| format!("doi:{}", doi))
+            .or_else(|| self.pmid.as_ref().map(|pmid| format!("pmid:{}", pmid)))
+    }
+
+    // Flat attributes for an export group, each name prefixed.
+    pub fn to_attributes(&self, prefix: &str) -> BTreeMap<String, ParamValue> {
+        let mut attributes = BTreeMap::new();
+        attributes.insert(
+            format!("{}.citation", prefix),
+            self.citation.as_str().into(),
+        );
+        let optional = [
+            ("doi", self.doi.clone()),
+            ("pmid", self.pmid.clone()),
+            ("species", self.species.clone()),
+            ("conditions", self.conditions.clone()),
+            ("uncertainty", self.uncertainty.map(|u| u.to_string())),
+        ];
+        for (field, value) in optional {
+            if let Some(value) = value {
+                attributes.insert(format!("{}.{}", prefix, field), ParamValue::Text(value));
+            }
+        }
+        attributes
+    }
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.citation)?;
+        if let Some(id) = self.identifier() {
+            write!(f, " [{}]", id)?;
+        }
+        Ok(())
+    }
+}
+
+// A parameter or preset carried together with its source.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cited<T> {
+    pub value: T,
+    pub provenance: Provenance,
+}
+
+impl<T> Cited<T> {
+    pub fn new(value: T, provenance: Provenance) -> Self {
+        Self { value, provenance }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T> Deref for Cited<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProvenanceCatalog {
+    pub entries: BTreeMap<String, Provenance>,
+}
+
+impl ProvenanceCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Sources of the crate's own presets.
+    pub fn builtin() -> Self {
+        let mut catalog = Self::new();
+        let entries = [
+            (
+                "systems::nervous::action_potential::HodgkinHuxleyModel",
+                Provenance::new("Hodgkin AL, Huxley AF (1952) J Physiol 117:500-544")
+                    .doi("10.1113/jphysiol.1952.sp004764")
+                    .pmid("12991237")
+                    .species("Loligo forbesi")
+                    .conditions("giant axon, voltage clamp at 6.3 °C"),
+            ),
+            (
+                "systems::immune::antibody::AntibodyKinetics::igg.long_half_life_days",
+                Provenance::new(
+                    "Amanna IJ, Carlson NE, Slifka MK (2007) N Engl J Med 357:1903-1915",
+                )
+                .doi("10.1056/NEJMoa066092")
+                .pmid("17989383")
+                .species("Homo sapiens")
+                .conditions("anti-tetanus IgG, longitudinal serology in adults")
+                .uncertainty(Uncertainty::Interval {
+                    low: 10.0 * 365.25,
+                    high: 14.0 * 365.25,
+                }),
+            ),
+            (
+                "systems::skeletal::collagen::CollagenDenaturation::tendon_fibre",
+                Provenance::new("Miles CA, Burjanadze TV, Bailey AJ (1995) J Mol Biol 245:437-446")
+                    .doi("10.1006/jmbi.1994.0035")
+                    .pmid("7837274")
+                    .species("Rattus norvegicus")
+                    .conditions("hydrated tail tendon, differential scanning calorimetry"),
+            ),
+            (
+                "systems::skeletal::collagen::CollagenDenaturation::soluble_monomer",
+                Provenance::new("Leikina E et al. (2002) Proc Natl Acad Sci USA 99:1314-1318")
+                    .doi("10.1073/pnas.032307099")
+                    .pmid("11805290")
+                    .conditions("type I collagen in solution, scanned at 0.25 K/min"),
+            ),
+            (
+                "systems::skeletal::biomaterials::SimulatedBodyFluid::kokubo",
+                Provenance::new("Kokubo T, Takadama H (2006) Biomaterials 27:2907-2915")
+                    .doi("10.1016/j.biomaterials.2006.01.017")
+                    .pmid("16448693")
+                    .conditions("acellular solution buffered to pH 7.40 at 36.5 °C"),
+            ),
+            (
+                "systems::cardiovascular::vascular_tree::VesselTree::murray",
+                Provenance::new("Murray CD (1926) Proc Natl Acad Sci USA 12:207-214")
+                    .doi("10.1073/pnas.12.3.207")
+                    .pmid("16576980")
+                    .conditions("minimum-work optimum for laminar flow"),
+            ),
+            (
+                "metabolism::structure::Element::covalent_radius_angstrom",
+                Provenance::new("Cordero B et al. (2008) Dalton Trans 2832-2838")
+                    .doi("10.1039/b801115j")
+                    .conditions("mean over Cambridge Structural Database bond lengths"),
+            ),
+        ];
+        for (key, provenance) in entries {
+            catalog.entries.insert(key.to_string(), provenance);
+        }
+        catalog
+    }
+
+    pub fn insert(&mut self, key: &str, provenance: Provenance) -> BiologyResult<()> {
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(BiologyError::InvalidParameter(format!(
+                "'{}' is not a valid provenance key",
+                key
+            )));
+        }
+        self.entries.insert(key.to_string(), provenance);
+        Ok(())
+    }
+
+    // Exact entry, else the nearest enclosing one: a field inherits the
+    // source of its preset.
+    pub fn get(&self, key: &str) -> Option<&Provenance> {
+        let mut key = key;
+        loop {
+            if let Some(provenance) = self.entries.get(key) {
+                return Some(provenance);
+            }
+            key = &key[..key.rfind(['.', ':'])?];
+            key = key.trim_end_matches(':');
+        }
+    }
+
+    // Entries under a module or type path.
+    pub fn under<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = (&'a str, &'a Provenance)> {
+        self.entries
+            .iter()
+            .filter(move |(key, _)| key.starts_with(prefix))
+            .map(|(key, provenance)| (key.as_str(), provenance))
+    }
+
+    pub fn cite<T>(&self, key: &str, value: T) -> BiologyResult<Cited<T>> {
+        self.get(key)
+            .map(|provenance| Cited::new(value, provenance.clone()))
+            .ok_or_else(|| BiologyError::InvalidParameter(format!("no provenance for {}", key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::immune::antibody::AntibodyKinetics;
+
+    #[test]
+    fn test_builtin_entries_are_resolvable() {
+        let catalog = ProvenanceCatalog::builtin();
+        for (key, provenance) in catalog.under("") {
+            assert!(
+                provenance.identifier().is_some(),
+                "{} has no DOI or PMID",
+                key
+            );
+        }
+        assert_eq!(catalog.under("systems::skeletal").count(), 3);
+    }
+
+    #[test]
+    fn test_fields_inherit_preset_source() {
+        let mut catalog = ProvenanceCatalog::builtin();
+        let hh = catalog
+            .get("systems::nervous::action_potential::HodgkinHuxleyModel.membrane_capacitance_uf_cm2")
+            .unwrap();
+        assert!(hh.citation.contains("Hodgkin"));
+        assert!(catalog.get("systems::renal").is_none());
+        assert!(catalog.insert("bad key", Provenance::new("x")).is_err());
+    }
+
+    #[test]
+    fn test_cited_preset_exports_attributes() {
+        let catalog = ProvenanceCatalog::builtin();
+        let igg = catalog
+            .cite(
+                "systems::immune::antibody::AntibodyKinetics::igg.long_half_life_days",
+                AntibodyKinetics::igg(),
+            )
+            .unwrap();
+        assert!(igg.long_half_life_days > 3000.0);
+        let attributes = igg.provenance.to_attributes("provenance.igg");
+        assert_eq!(
+            attributes["provenance.igg.doi"],
+            ParamValue::Text("10.1056/NEJMoa066092".to_string())
+        );
+        assert!(attributes.contains_key("provenance.igg.uncertainty"));
+        assert!(igg
+            .provenance
+            .to_string()
+            .ends_with("[doi:10.1056/NEJMoa066092]"));
+    }
+}
@@ -0,0 +1,412 @@
+use crate::biology::{BiologyError, BiologyResult};
+use crate::pharmacology::dose_response::DoseResponseFit;
+use crate::simulation::recorder::TimeSeriesTable;
+use crate::systems::immune::antibody::{AntibodyClass, ImmuneResponse};
+use crate::systems::skeletal::testing::{BoneTissueLaw, MechanicalTest, MechanicalTestResult};
+use plotters::coord::ranged1d::{AsRangedCoord, Ranged, ValueFormatter};
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::io;
+use std::path::Path;
+
+// One-call charts of recorded and fitted results through plotters. A
+// `Plot` is plain data; `save` picks SVG or PNG from the file extension.
+
+const PALETTE: [RGBColor; 6] = [
+    RGBColor(31, 119, 180),
+    RGBColor(214, 39, 40),
+    RGBColor(44, 160, 44),
+    RGBColor(255, 127, 14),
+    RGBColor(148, 103, 189),
+    RGBColor(140, 86, 75),
+];
+
+const CURVE_SAMPLES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesStyle {
+    Line,
+    Points,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub name: String,
+    pub points: Vec<(f64, f64)>,
+    pub style: SeriesStyle,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Plot {
+    pub title: String,
+    pub x_label: String,
+    pub y_label: String,
+    pub x_log: bool,
+    pub y_log: bool,
+    pub size: (u32, u32),
+    pub series: Vec<Series>,
+}
+
+impl Plot {
+    pub fn new(title: &str, x_label: &str, y_label: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            x_label: x_label.to_string(),
+            y_label: y_label.to_string(),
+            x_log: false,
+            y_log: false,
+            size: (800, 500),
+            series: Vec::new(),
+        }
+    }
+
+    pub fn line(mut self, name: &str, points: Vec<(f64, f64)>) -> Self {
+        self.series.push(Series {
+            name: name.to_string(),
+            points,
+            style: SeriesStyle::Line,
+        });
+        self
+    }
+
+    pub fn scatter(mut self, name: &str, points: Vec<(f64, f64)>) -> Self {
+        self.series.push(Series {
+            name: name.to_string(),
+            points,
+            style: SeriesStyle::Points,
+        });
+        self
+    }
+
+    // Selected columns of a recorder table against time; the y axis takes
+    // the shared unit, or is left unitless when the columns disagree.
+    pub fn time_series(
+        table: &TimeSeriesTable,
+        columns: &[&str],
+        time_unit: &str,
+    ) -> BiologyResult<Self> {
+        if columns.is_empty() {
+            return Err(BiologyError::InvalidParameter(
+                "no columns selected for plotting".to_string(),
+            ));
+        }
+        let mut units = Vec::new();
+        let mut plot = Plot::new("", &format!("Time [{}]", time_unit), "");
+        for &name in columns {
+            let column = table
+                .columns
+                .iter()
+                .position(|c| c.name == name)
+                .ok_or_else(|| BiologyError::InvalidParameter(format!("no column {}", name)))?;
+            units.push(table.columns[column].unit.as_str());
+            let points = table
+                .time
+                .iter()
+                .copied()
+                .zip(table.values[column].iter().copied())
+                .collect();
+            plot = plot.line(name, points);
+        }
+        plot.title = columns.join(", ");
+        plot.y_label = if units.iter().all(|&u| u == units[0]) {
+            format!("{} [{}]", columns.join(", "), units[0])
+        } else {
+            "Value".to_string()
+        };
+        Ok(plot)
+    }
+
+    // Titre decay of each class on a log axis, the way serology is read.
+    // Days before a class peaks carry no titre and are left out.
+    pub fn antibody_titers(response: &ImmuneResponse, last_day: f64) -> BiologyResult<Self> {
+        if last_day.is_nan() || last_day <= 0.0 {
+            return Err(BiologyError::InvalidValue(format!(
+                "last day must be positive, got {}",
+                last_day
+            )));
+        }
+        let mut plot = Plot::new("Antibody titres", "Time [days]", "Titre");
+        plot.y_log = true;
+        for class in [AntibodyClass::IgM, AntibodyClass::IgG, AntibodyClass::IgA] {
+            let points: Vec<(f64, f64)> = (0..=CURVE_SAMPLES)
+                .map(|i| {
+                    let day = last_day * i as f64 / CURVE_SAMPLES as f64;
+                    (day, response.titer(class, day))
+                })
+                .filter(|&(_, titer)| titer > 0.0)
+                .collect();
+            if !points.is_empty() {
+                plot = plot.line(&format!("{:?}", class), points);
+            }
+        }
+        Ok(plot)
+    }
+
+    // Compressive, tensile and shear branches of a tissue law, each to its
+    // ultimate strain, with compression plotted as positive.
+    pub fn stress_strain(law: &BoneTissueLaw) -> Self {
+        let branch = |branch: &crate::systems::skeletal::testing::StressStrainBranch| {
+            (0..=CURVE_SAMPLES)
+                .map(|i| {
+                    let strain = branch.ultimate_strain * i as f64 / CURVE_SAMPLES as f64;
+                    (strain * 100.0, branch.stress(strain))
+                })
+                .collect()
+        };
+        Plot::new("Tissue stress-strain", "Strain [%]", "Stress [MPa]")
+            .line("Compression", branch(&law.compression))
+            .line("Tension", branch(&law.tension))
+            .line("Shear", branch(&law.shear))
+    }
+
+    pub fn mechanical_test(result: &MechanicalTestResult) -> Self {
+        let (title, x_label, y_label) = match result.test {
+            MechanicalTest::ThreePointBending { .. } => {
+                ("Three-point bending", "Deflection [mm]", "Load [N]")
+            }
+            MechanicalTest::Compression { .. } => {
+                ("Axial compression", "Displacement [mm]", "Load [N]")
+            }
+            MechanicalTest::Torsion { .. } => ("Torsion", "Rotation [rad]", "Torque [N·mm]"),
+        };
+        Plot::new(title, x_label, y_label).line("Specimen", result.curve.clone())
+    }
+
+    // Observations over the fitted four-parameter logistic on a log dose
+    // axis, the curve spanning the observed doses.
+    pub fn dose_response(
+        points: &[(f64, f64)],
+        fit: &DoseResponseFit,
+        dose_unit: &str,
+        response_unit: &str,
+    ) -> BiologyResult<Self> {
+        let doses = points.iter().map(|p| p.0).filter(|&d| d > 0.0);
+        let low = doses.clone().fold(f64::INFINITY, f64::min);
+        let high = doses.fold(0.0_f64, f64::max);
+        if !low.is_finite() || high <= 0.0 {
+            return Err(BiologyError::InvalidValue(
+                "dose-response plot needs at least one positive dose".to_string(),
+            ));
+        }
+        let curve = (0..=CURVE_SAMPLES)
+            .map(|i| {
+                let dose = low * (high / low).powf(i as f64 / CURVE_SAMPLES as f64);
+                (dose, fit.curve.response(dose))
+            })
+            .collect();
+        let mut plot = Plot::new(
+            &format!("Dose-response (R² = {:.3})", fit.r_squared),
+            &format!("Dose [{}]", dose_unit),
+            &format!("Response [{}]", response_unit),
+        )
+        .scatter("Observed", points.to_vec())
+        .line("4PL fit", curve);
+        plot.x_log = true;
+        Ok(plot)
+    }
+
+    pub fn to_svg(&self) -> io::Result<String> {
+        let mut svg = String::new();
+        {
+            let root = SVGBackend::with_string(&mut svg, self.size).into_drawing_area();
+            self.draw(&root)?;
+        }
+        Ok(svg)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("svg") => {
+                let root = SVGBackend::new(path, self.size).into_drawing_area();
+                self.draw(&root)
+            }
+            Some("png") => {
+                let root = BitMapBackend::new(path, self.size).into_drawing_area();
+                self.draw(&root)
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is neither .svg nor .png", path.display()),
+            )),
+        }
+    }
+
+    // Data bounds padded by 5%, multiplicatively on a log axis where
+    // non-positive values cannot be placed.
+    fn bounds(&self, axis: impl Fn(&(f64, f64)) -> f64, log: bool) -> io::Result<(f64, f64)> {
+        let values = self
+            .series
+            .iter()
+            .flat_map(|s| s.points.iter().map(&axis))
+            .filter(|v| v.is_finite() && (!log || *v > 0.0));
+        let (low, high) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+        if low > high {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("plot {} has no drawable points", self.title),
+            ));
+        }
+        if log {
+            let pad = (high / low).powf(0.05).max(1.1);
+            Ok((low / pad, high * pad))
+        } else {
+            let pad = ((high - low) * 0.05).max(high.abs().max(1.0) * 1e-3);
+            Ok((low - pad, high + pad))
+        }
+    }
+
+    fn draw<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>) -> io::Result<()> {
+        let (x0, x1) = self.bounds(|p| p.0, self.x_log)?;
+        let (y0, y1) = self.bounds(|p| p.1, self.y_log)?;
+        match (self.x_log, self.y_log) {
+            (false, false) => self.draw_chart(root, x0..x1, y0..y1),
+            (true, false) => self.draw_chart(root, (x0..x1).log_scale(), y0..y1),
+            (false, true) => self.draw_chart(root, x0..x1, (y0..y1).log_scale()),
+            (true, true) => self.draw_chart(root, (x0..x1).log_scale(), (y0..y1).log_scale()),
+        }
+    }
+
+    fn draw_chart<DB, X, Y>(&self, root: &DrawingArea<DB, Shift>, x: X, y: Y) -> io::Result<()>
+    where
+        DB: DrawingBackend,
+        X: AsRangedCoord<Value = f64>,
+        Y: AsRangedCoord<Value = f64>,
+        X::CoordDescType: Ranged<ValueType = f64> + ValueFormatter<f64>,
+        Y::CoordDescType: Ranged<ValueType = f64> + ValueFormatter<f64>,
+    {
+        let error = |e: &dyn std::fmt::Display| io::Error::other(e.to_string());
+        root.fill(&WHITE).map_err(|e| error(&e))?;
+        let mut chart = ChartBuilder::on(root)
+            .caption(&self.title, ("sans-serif", 22))
+            .margin(12)
+            .x_label_area_size(45)
+            .y_label_area_size(70)
+            .build_cartesian_2d(x, y)
+            .map_err(|e| error(&e))?;
+        chart
+            .configure_mesh()
+            .x_desc(&self.x_label)
+            .y_desc(&self.y_label)
+            .light_line_style(WHITE)
+            .draw()
+            .map_err(|e| error(&e))?;
+        let finite = |s: &Series| -> Vec<(f64, f64)> {
+            s.points
+                .iter()
+                .copied()
+                .filter(|p| {
+                    p.0.is_finite()
+                        && p.1.is_finite()
+                        && (!self.x_log || p.0 > 0.0)
+                        && (!self.y_log || p.1 > 0.0)
+                })
+                .collect()
+        };
+        for (i, series) in self.series.iter().enumerate() {
+            let color = PALETTE[i % PALETTE.len()];
+            let points = finite(series);
+            let drawn = match series.style {
+                SeriesStyle::Line => {
+                    chart.draw_series(LineSeries::new(points, color.stroke_width(2)))
+                }
+                SeriesStyle::Points => chart.draw_series(
+                    points
+                        .into_iter()
+                        .map(|p| Circle::new(p, 4, color.filled())),
+                ),
+            }
+            .map_err(|e| error(&e))?;
+            drawn.label(series.name.as_str()).legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + 18, y)], color.stroke_width(2))
+            });
+        }
+        if self.series.len() > 1 {
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.85))
+                .border_style(BLACK)
+                .draw()
+                .map_err(|e| error(&e))?;
+        }
+        root.present().map_err(|e| error(&e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::BoneMaterial;
+    use crate::pharmacology::dose_response::{fit_four_parameter_logistic, FourParameterLogistic};
+    use crate::simulation::recorder::Column;
+    use crate::systems::skeletal::testing::{run_test, TubularSection};
+
+    #[test]
+    fn test_time_series_plot_uses_recorded_units() {
+        let mut table = TimeSeriesTable::new(vec![Column {
+            name: "v_m".to_string(),
+            unit: "mV".to_string(),
+        }]);
+        for i in 0..50 {
+            let t = i as f64 * 0.1;
+            table
+                .push_row(t, &[-70.0 + 100.0 * (-(t - 1.0).powi(2) * 20.0).exp()])
+                .unwrap();
+        }
+        let plot = Plot::time_series(&table, &["v_m"], "ms").unwrap();
+        assert_eq!(plot.y_label, "v_m [mV]");
+        let svg = plot.to_svg().unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("Time [ms]"));
+        assert!(Plot::time_series(&table, &["missing"], "ms").is_err());
+    }
+
+    #[test]
+    fn test_dose_response_plot_on_log_axis() {
+        let truth = FourParameterLogistic {
+            bottom: 0.0,
+            top: 100.0,
+            ec50: 10.0,
+            hill_slope: 1.2,
+        };
+        let points: Vec<(f64, f64)> = [0.1, 0.3, 1.0, 3.0, 10.0, 30.0, 100.0, 300.0]
+            .iter()
+            .map(|&d| (d, truth.response(d)))
+            .collect();
+        let fit = fit_four_parameter_logistic(&points).unwrap();
+        let plot = Plot::dose_response(&points, &fit, "nM", "%").unwrap();
+        assert!(plot.x_log);
+        assert_eq!(plot.series[0].style, SeriesStyle::Points);
+        let svg = plot.to_svg().unwrap();
+        assert!(svg.contains("Dose [nM]"));
+        assert!(svg.contains("4PL fit"));
+    }
+
+    #[test]
+    fn test_mechanics_and_titer_plots_write_files() {
+        let law = BoneTissueLaw::from_material(&BoneMaterial::cortical()).unwrap();
+        let result = run_test(
+            &TubularSection::femoral_midshaft(),
+            &law,
+            MechanicalTest::ThreePointBending { span_mm: 200.0 },
+        )
+        .unwrap();
+        let dir = std::env::temp_dir().join(format!("viz_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Plot::mechanical_test(&result)
+            .save(dir.join("bending.svg"))
+            .unwrap();
+        Plot::stress_strain(&law).save(dir.join("law.png")).unwrap();
+        let png = std::fs::read(dir.join("law.png")).unwrap();
+        assert_eq!(&png[..4], b"\x89PNG");
+        let titers =
+            Plot::antibody_titers(&ImmuneResponse::from_vaccine(20.0, None).unwrap(), 365.0)
+                .unwrap();
+        assert!(titers.y_log);
+        assert!(titers.to_svg().unwrap().contains("IgG"));
+        assert!(titers.save(dir.join("titers.pdf")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
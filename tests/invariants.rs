@@ -0,0 +1,118 @@
+use human_biology::io::vtk::DataArray;
+use human_biology::metabolism::reaction_network::{Reaction, ReactionNetwork};
+use human_biology::systems::nervous::action_potential::HodgkinHuxleyModel;
+use human_biology::systems::skeletal::nanoindentation::MatrixProperties;
+use human_biology::systems::skeletal::spectroscopy::BoneMatrix;
+use human_biology::validation::invariants::{InvariantCheck, Invariants};
+use proptest::prelude::*;
+
+fn bone_matrix() -> impl Strategy<Value = BoneMatrix> {
+    (
+        0.0..1.0f64,
+        0.01..1.0f64,
+        0.0..1.0f64,
+        0.0..10.0f64,
+        -1.0..2.0f64,
+    )
+        .prop_map(|(mineral, organic, water, crosslinks, crystallinity)| {
+            let total = mineral + organic + water;
+            let matrix = BoneMatrix::new(mineral / total, organic / total, water / total)
+                .expect("normalised fractions");
+            let crystal = matrix.crystal;
+            matrix
+                .with_crystal(crystal, crystallinity)
+                .with_crosslink_ratio(crosslinks)
+                .expect("non-negative ratio")
+        })
+}
+
+// Linear chain A -> B -> C, with the second step saturable so both rate
+// laws are exercised.
+fn chain_network() -> impl Strategy<Value = ReactionNetwork> {
+    (
+        prop::array::uniform3(0.0..10.0f64),
+        0.01..20.0f64,
+        0.01..20.0f64,
+        0.01..5.0f64,
+    )
+        .prop_map(|(initial, k, vmax, km)| {
+            let mut network = ReactionNetwork::new();
+            let a = network.add_species("A", initial[0]);
+            let b = network.add_species("B", initial[1]);
+            let c = network.add_species("C", initial[2]);
+            let first = Reaction::builder("a_to_b")
+                .reactant(a, 1.0)
+                .product(b, 1.0)
+                .mass_action(k)
+                .build()
+                .unwrap();
+            let second = Reaction::builder("b_to_c")
+                .reactant(b, 1.0)
+                .product(c, 1.0)
+                .michaelis_menten(vmax, km)
+                .build()
+                .unwrap();
+            network.add_reaction(first).unwrap();
+            network.add_reaction(second).unwrap();
+            network
+        })
+}
+
+fn symmetric_tensor() -> impl Strategy<Value = [[f64; 3]; 3]> {
+    prop::array::uniform6(-500.0..500.0f64)
+        .prop_map(|[xx, yy, zz, xy, yz, xz]| [[xx, xy, xz], [xy, yy, yz], [xz, yz, zz]])
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn bone_matrix_composition_is_closed(matrix in bone_matrix()) {
+        prop_assert!(matrix.validate_invariants().is_ok());
+        let percentages = [
+            matrix.mineral_fraction * 100.0,
+            matrix.organic_fraction * 100.0,
+            matrix.water_fraction * 100.0,
+        ];
+        let mut check = InvariantCheck::new();
+        check.sums_to("composition %", &percentages, 100.0);
+        let properties = MatrixProperties::from_bone_matrix(&matrix);
+        check
+            .non_negative("modulus", properties.elastic_modulus_gpa)
+            .non_negative("hardness", properties.hardness_gpa)
+            .finite("modulus", properties.elastic_modulus_gpa);
+        prop_assert!(check.is_satisfied(), "{:?}", check.violations);
+    }
+
+    #[test]
+    fn concentrations_never_go_negative(mut network in chain_network()) {
+        let before: f64 = network.concentrations.iter().sum();
+        network.simulate(5.0).unwrap();
+        prop_assert!(network.validate_invariants().is_ok(), "{:?}", network.concentrations);
+        let after: f64 = network.concentrations.iter().sum();
+        prop_assert!((after - before).abs() <= 1e-4 * before.max(1.0));
+    }
+
+    #[test]
+    fn membrane_voltage_stays_bounded(stimulus in 0.0..40.0f64, onset in 0usize..1000) {
+        let mut neuron = HodgkinHuxleyModel::new();
+        for step in 0..3000 {
+            let current = if step >= onset { stimulus } else { 0.0 };
+            neuron.step(0.01, current);
+            let mut check = InvariantCheck::new();
+            neuron.check_invariants(&mut check);
+            prop_assert!(check.is_satisfied(), "step {}: {:?}", step, check.violations);
+        }
+    }
+
+    #[test]
+    fn stress_tensors_stay_symmetric(
+        tensors in prop::collection::vec(symmetric_tensor(), 1..8),
+        skew in 1.0..50.0f64,
+    ) {
+        prop_assert!(DataArray::tensors("stress", &tensors).validate_invariants().is_ok());
+        let mut skewed = tensors.clone();
+        skewed[0][1][2] += skew;
+        prop_assert!(DataArray::tensors("stress", &skewed).validate_invariants().is_err());
+    }
+}